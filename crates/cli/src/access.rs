@@ -0,0 +1,221 @@
+//! Role based access to the server API.
+//!
+//! Servers started with `--api-tokens` only answer requests which carry one of the
+//! configured tokens as `Authorization: Bearer <token>`. Each token is scoped to a
+//! [`Role`], which limits the endpoints it may call and the columns returned from
+//! stored tables. Without configured tokens every request is treated as [`Role::Ops`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as _, ArrayRef, AsArray as _, RecordBatch, RecordBatchOptions, StructArray,
+};
+use arrow::datatypes::{DataType, Field, FieldRef, Schema};
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Json, Response};
+use caspers_universe::{Error, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Audience an API token is issued to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Role {
+    /// Operators of running simulations, may call every endpoint and see all columns.
+    Ops,
+    /// Analysts of stored simulations, never see payment data.
+    Analyst,
+    /// Public demos, never see personal or payment data and cannot export tables.
+    Demo,
+}
+
+/// Groups of endpoints access is granted for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Endpoint {
+    /// Live stats of simulations running in the server process
+    Stats,
+    /// Aggregated playback of stored runs
+    Playback,
+    /// Listing of stored snapshots
+    Snapshots,
+    /// Rows of stored snapshot tables
+    SnapshotTables,
+    /// Rows of stored snapshot tables as Arrow streams, for bulk exports
+    TableExport,
+    /// Settings of simulations running in the server process, which may be changed,
+    /// and pausing or resuming them
+    Settings,
+}
+
+impl Role {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Role::Ops => "ops",
+            Role::Analyst => "analyst",
+            Role::Demo => "demo",
+        }
+    }
+
+    /// Whether tokens of this role may call `endpoint`.
+    pub(crate) fn allows(&self, endpoint: Endpoint) -> bool {
+        match self {
+            Role::Ops => true,
+            Role::Analyst => endpoint != Endpoint::Settings,
+            Role::Demo => !matches!(endpoint, Endpoint::TableExport | Endpoint::Settings),
+        }
+    }
+
+    /// Names of columns, including fields nested in struct columns, hidden from this role.
+    pub(crate) fn hidden_columns(&self) -> &'static [&'static str] {
+        match self {
+            Role::Ops => &[],
+            Role::Analyst => &["cc_number"],
+            Role::Demo => &["cc_number", "email", "first_name", "last_name"],
+        }
+    }
+
+    /// Fail if tokens of this role may not call `endpoint`.
+    pub(crate) fn require(&self, endpoint: Endpoint) -> Result<(), AccessError> {
+        if self.allows(endpoint) {
+            return Ok(());
+        }
+        Err(AccessError::Forbidden(*self))
+    }
+
+    /// Remove the columns hidden from this role from `batches`.
+    ///
+    /// `batches` must share `schema`; the schema of the redacted batches is returned
+    /// along with them, so empty results keep their columns.
+    pub(crate) fn redact(
+        &self,
+        schema: Arc<Schema>,
+        batches: Vec<RecordBatch>,
+    ) -> Result<(Arc<Schema>, Vec<RecordBatch>)> {
+        let hidden = self.hidden_columns();
+        let schema = redact_batch(&RecordBatch::new_empty(schema), hidden)?.schema();
+        let batches = batches
+            .iter()
+            .map(|batch| redact_batch(batch, hidden))
+            .collect::<Result<_>>()?;
+        Ok((schema, batches))
+    }
+}
+
+fn redact_batch(batch: &RecordBatch, hidden: &[&str]) -> Result<RecordBatch> {
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        if let Some((field, column)) = redact_column(field, column, hidden)? {
+            fields.push(field);
+            columns.push(column);
+        }
+    }
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    Ok(RecordBatch::try_new_with_options(
+        Arc::new(Schema::new(fields)),
+        columns,
+        &options,
+    )?)
+}
+
+fn redact_column(
+    field: &FieldRef,
+    column: &ArrayRef,
+    hidden: &[&str],
+) -> Result<Option<(FieldRef, ArrayRef)>> {
+    if hidden.contains(&field.name().as_str()) {
+        return Ok(None);
+    }
+    let Some(structs) = column.as_struct_opt() else {
+        return Ok(Some((field.clone(), column.clone())));
+    };
+    let mut fields = Vec::new();
+    let mut children = Vec::new();
+    for (child_field, child) in structs.fields().iter().zip(structs.columns()) {
+        if let Some((child_field, child)) = redact_column(child_field, child, hidden)? {
+            fields.push(child_field);
+            children.push(child);
+        }
+    }
+    let redacted = StructArray::try_new_with_length(
+        fields.clone().into(),
+        children,
+        structs.nulls().cloned(),
+        structs.len(),
+    )?;
+    let field = Field::clone(field).with_data_type(DataType::Struct(fields.into()));
+    Ok(Some((Arc::new(field), Arc::new(redacted))))
+}
+
+/// API tokens accepted by the server, and the role each of them is scoped to.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ApiTokens(Option<Arc<HashMap<String, Role>>>);
+
+impl ApiTokens {
+    /// Load tokens from a JSON file mapping each token to its role, e.g. `{"s3cr3t": "demo"}`.
+    pub(crate) fn from_file(path: &str) -> Result<Self> {
+        let tokens: HashMap<String, Role> = serde_json::from_slice(&std::fs::read(path)?)?;
+        if tokens.is_empty() {
+            return Err(Error::invalid_data(format!("no API tokens in '{path}'")));
+        }
+        Ok(Self(Some(Arc::new(tokens))))
+    }
+
+    /// Role of the token presented with a request.
+    fn role(&self, token: Option<&str>) -> Result<Role, AccessError> {
+        let Some(tokens) = &self.0 else {
+            return Ok(Role::Ops);
+        };
+        token
+            .and_then(|token| tokens.get(token))
+            .copied()
+            .ok_or(AccessError::Unauthorized)
+    }
+}
+
+impl<S> FromRequestParts<S> for Role
+where
+    ApiTokens: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AccessError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        ApiTokens::from_ref(state).role(token)
+    }
+}
+
+/// A request was rejected based on its token.
+#[derive(Debug)]
+pub(crate) enum AccessError {
+    /// The request carries no known token.
+    Unauthorized,
+    /// The token's role may not call the endpoint.
+    Forbidden(Role),
+}
+
+impl IntoResponse for AccessError {
+    fn into_response(self) -> Response {
+        let (status, kind, error) = match self {
+            AccessError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "missing or unknown API token".to_string(),
+            ),
+            AccessError::Forbidden(role) => (
+                StatusCode::FORBIDDEN,
+                "forbidden",
+                format!("endpoint not available to '{}' tokens", role.as_str()),
+            ),
+        };
+        (status, Json(json!({ "error": error, "kind": kind }))).into_response()
+    }
+}
@@ -0,0 +1,298 @@
+//! Terminal dashboard with live stats of a running simulation.
+//!
+//! Stats are either taken from the stats channel of a simulation running in this
+//! process (`caspers run --watch`), or polled from the stats endpoint of the server
+//! API of another process (`caspers watch`).
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use caspers_universe::SimulationStats;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize as _};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, clap::Parser)]
+pub(crate) struct WatchArgs {
+    /// Simulation to watch.
+    #[arg(long)]
+    simulation_id: Uuid,
+
+    /// Base URL of the server API the simulation is served by, see `caspers run --serve`.
+    #[arg(long, default_value = "http://127.0.0.1:8000")]
+    api_url: String,
+
+    /// Token sent to servers which require one, see `caspers server --api-tokens`.
+    #[arg(long, env = "CASPERS_API_TOKEN")]
+    api_token: Option<String>,
+
+    /// Milliseconds between two refreshes of the dashboard.
+    #[arg(long, default_value_t = 500)]
+    refresh_ms: u64,
+}
+
+pub(super) async fn handle(args: WatchArgs) -> Result<()> {
+    let source = StatsSource::Remote {
+        client: reqwest::Client::new(),
+        url: format!(
+            "{}/api/simulations/{}/stats",
+            args.api_url.trim_end_matches('/'),
+            args.simulation_id
+        ),
+        token: args.api_token,
+    };
+    let title = format!("simulation {}", args.simulation_id);
+    show(title, source, Duration::from_millis(args.refresh_ms)).await
+}
+
+/// Where the dashboard takes its stats from.
+pub(crate) enum StatsSource {
+    /// Stats channel of a simulation running in this process
+    Local(watch::Receiver<SimulationStats>),
+    /// Stats endpoint of the server API
+    Remote {
+        client: reqwest::Client,
+        url: String,
+        token: Option<String>,
+    },
+}
+
+impl StatsSource {
+    /// Latest stats of the simulation, `None` once a local simulation finished.
+    async fn latest(&mut self) -> Result<Option<SimulationStats>> {
+        match self {
+            StatsSource::Local(receiver) => {
+                if receiver.has_changed().is_err() {
+                    return Ok(None);
+                }
+                Ok(Some(receiver.borrow_and_update().clone()))
+            }
+            StatsSource::Remote { client, url, token } => {
+                let mut request = client.get(url.as_str());
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?;
+                Ok(Some(response.error_for_status()?.json().await?))
+            }
+        }
+    }
+}
+
+/// Show the dashboard until the user quits with `q` or `Esc`.
+pub(crate) async fn show(title: String, mut source: StatsSource, refresh: Duration) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, Dashboard::new(title), &mut source, refresh).await;
+    ratatui::restore();
+    result
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    mut dashboard: Dashboard,
+    source: &mut StatsSource,
+    refresh: Duration,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(refresh);
+    loop {
+        interval.tick().await;
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+            {
+                return Ok(());
+            }
+        }
+        if !dashboard.finished {
+            match source.latest().await {
+                Ok(Some(stats)) => dashboard.update(stats, Instant::now()),
+                Ok(None) => dashboard.finish(),
+                Err(err) => {
+                    dashboard.status = Some(format!("failed to fetch stats: {}", err.report()))
+                }
+            }
+        }
+        terminal.draw(|frame| dashboard.render(frame))?;
+    }
+}
+
+/// Latest stats and the rates derived from consecutive updates.
+struct Dashboard {
+    title: String,
+    stats: Option<SimulationStats>,
+    /// Time the latest stats were received
+    updated_at: Option<Instant>,
+    step_rate: f64,
+    event_rates: BTreeMap<String, f64>,
+    finished: bool,
+    /// Problem fetching stats, shown in the footer
+    status: Option<String>,
+}
+
+impl Dashboard {
+    fn new(title: String) -> Self {
+        Self {
+            title,
+            stats: None,
+            updated_at: None,
+            step_rate: 0.0,
+            event_rates: BTreeMap::new(),
+            finished: false,
+            status: None,
+        }
+    }
+
+    fn update(&mut self, stats: SimulationStats, now: Instant) {
+        if let (Some(previous), Some(updated_at)) = (&self.stats, self.updated_at) {
+            let elapsed = now.duration_since(updated_at).as_secs_f64();
+            if elapsed > 0.0 {
+                let rate = |current: usize, previous: usize| {
+                    current.saturating_sub(previous) as f64 / elapsed
+                };
+                self.step_rate = rate(stats.steps, previous.steps);
+                self.event_rates = stats
+                    .events_by_kind
+                    .iter()
+                    .map(|(kind, &count)| {
+                        let previous = previous.events_by_kind.get(kind).copied();
+                        (kind.clone(), rate(count, previous.unwrap_or_default()))
+                    })
+                    .collect();
+            }
+        }
+        self.stats = Some(stats);
+        self.updated_at = Some(now);
+        self.status = None;
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        self.step_rate = 0.0;
+        self.event_rates.clear();
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [header, utilization, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [sites, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(body);
+        let [events, counts] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side);
+
+        let footer_text = match (&self.status, self.finished) {
+            (Some(status), _) => Line::from(status.as_str()).red(),
+            (None, true) => Line::from("simulation finished - press q to quit"),
+            (None, false) => Line::from("press q to quit").dim(),
+        };
+        frame.render_widget(footer_text, footer);
+
+        let block = |title: &str| Block::bordered().title(title.to_string().bold());
+        let Some(stats) = &self.stats else {
+            frame.render_widget(
+                Paragraph::new("waiting for stats...").block(block(&self.title)),
+                header,
+            );
+            return;
+        };
+
+        let summary = format!(
+            "time {}   steps {} ({:.1}/s)   active journeys {}",
+            stats.simulation_time.format("%Y-%m-%d %H:%M:%S"),
+            stats.steps,
+            self.step_rate,
+            stats.active_journeys,
+        );
+        frame.render_widget(Paragraph::new(summary).block(block(&self.title)), header);
+
+        let couriers = stats.people_by_role.get("courier").copied().unwrap_or(0);
+        let gauge = Gauge::default()
+            .block(block("courier utilization"))
+            .ratio(stats.courier_utilization().clamp(0.0, 1.0))
+            .label(format!(
+                "{} / {couriers} couriers busy",
+                stats.busy_couriers
+            ));
+        frame.render_widget(gauge, utilization);
+
+        render_table(
+            frame,
+            sites,
+            block("sites"),
+            [
+                "site",
+                "submitted",
+                "processing",
+                "packing",
+                "ready",
+                "queued",
+                "delivering",
+            ],
+            stats.sites.iter().map(|(name, load)| {
+                [
+                    name.clone(),
+                    load.submitted.to_string(),
+                    load.processing.to_string(),
+                    load.packing.to_string(),
+                    load.ready.to_string(),
+                    load.queued().to_string(),
+                    load.delivering.to_string(),
+                ]
+            }),
+        );
+
+        render_table(
+            frame,
+            events,
+            block("events"),
+            ["kind", "total", "per second"],
+            stats.events_by_kind.iter().map(|(kind, count)| {
+                let rate = self.event_rates.get(kind).copied().unwrap_or(0.0);
+                [kind.clone(), count.to_string(), format!("{rate:.1}")]
+            }),
+        );
+
+        let orders = stats
+            .orders_by_status
+            .iter()
+            .map(|(status, count)| ["orders".to_string(), status.clone(), count.to_string()]);
+        let people = stats
+            .people_by_status
+            .iter()
+            .map(|(status, count)| ["people".to_string(), status.clone(), count.to_string()]);
+        render_table(
+            frame,
+            counts,
+            block("status"),
+            ["entity", "status", "count"],
+            orders.chain(people),
+        );
+    }
+}
+
+fn render_table<const N: usize>(
+    frame: &mut Frame,
+    area: Rect,
+    block: Block,
+    header: [&str; N],
+    rows: impl Iterator<Item = [String; N]>,
+) {
+    let widths = [Constraint::Fill(1); N];
+    let table = Table::new(rows.map(Row::new), widths)
+        .header(Row::new(header).style(Style::new().bold()))
+        .block(block);
+    frame.render_widget(table, area);
+}
@@ -1,3 +1,5 @@
+use caspers_universe::ErrorKind;
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Debug, thiserror::Error)]
@@ -19,9 +21,41 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[error(transparent)]
+    Http {
+        #[from]
+        source: reqwest::Error,
+    },
+
     #[error(transparent)]
     Url {
         #[from]
         source: url::ParseError,
     },
 }
+
+impl Error {
+    /// Process exit code for the error, following the conventions of `sysexits.h`.
+    pub(crate) fn exit_code(&self) -> u8 {
+        match self {
+            Error::Universe { source } => match source.kind() {
+                ErrorKind::InvalidInput => 65,
+                ErrorKind::NotFound => 66,
+                ErrorKind::Internal => 70,
+                ErrorKind::Storage => 74,
+            },
+            Error::Url { .. } => 64,
+            Error::Io { .. } => 74,
+            Error::Http { .. } => 69,
+            Error::Dialogue { .. } => 1,
+        }
+    }
+
+    /// Render the error together with all its sources.
+    pub(crate) fn report(&self) -> String {
+        match self {
+            Error::Universe { source } => source.report(),
+            err => err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use caspers_universe::{FrameRenderer, SimulationContext, resolve_url};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::output::OutputFormat;
+
+#[derive(Debug, Clone, clap::Parser)]
+pub(crate) struct FramesArgs {
+    #[arg(short, long)]
+    /// Path where the simulation results are stored.
+    working_directory: Option<String>,
+
+    /// Simulation to render.
+    #[arg(long)]
+    simulation_id: Uuid,
+
+    /// Snapshot the simulation was started from.
+    #[arg(long)]
+    snapshot_id: Uuid,
+
+    /// Simulated time of the first frame (RFC 3339).
+    #[arg(long)]
+    start: DateTime<Utc>,
+
+    /// Simulated time of the last frame (RFC 3339).
+    #[arg(long)]
+    end: DateTime<Utc>,
+
+    /// Simulated seconds between two consecutive frames.
+    #[arg(long, default_value_t = 60)]
+    frame_interval: i64,
+
+    /// Directory the GeoJSON frames are written to.
+    #[arg(short, long)]
+    output_dir: PathBuf,
+}
+
+/// Frames written by the `frames` command.
+#[derive(Debug, Serialize)]
+struct FramesReport {
+    frames: usize,
+    output_dir: PathBuf,
+}
+
+impl fmt::Display for FramesReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "wrote {} frames to {}",
+            self.frames,
+            self.output_dir.display()
+        )
+    }
+}
+
+pub(super) async fn handle(args: FramesArgs, output: OutputFormat) -> Result<()> {
+    let ctx = SimulationContext::builder()
+        .with_working_directory(resolve_url(args.working_directory)?)
+        .with_simulation_id(args.simulation_id)
+        .with_snapshot_id(args.snapshot_id)
+        .build()
+        .await?;
+
+    let frames = FrameRenderer::new(args.start, args.end)
+        .with_frame_interval(Duration::seconds(args.frame_interval))
+        .render_results(&ctx)
+        .await?;
+
+    std::fs::create_dir_all(&args.output_dir)?;
+    for frame in &frames {
+        let path = args
+            .output_dir
+            .join(format!("frame_{:06}.geojson", frame.index));
+        std::fs::write(path, frame.to_geojson().to_string())?;
+    }
+    output.print(&FramesReport {
+        frames: frames.len(),
+        output_dir: args.output_dir,
+    })?;
+
+    Ok(())
+}
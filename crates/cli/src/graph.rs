@@ -0,0 +1,74 @@
+use caspers_universe::{
+    GraphFormat, LocalCache, ObjectData, Template, load_cached_simulation_setup,
+    load_simulation_setup, resolve_url,
+};
+use clap::ValueEnum;
+
+use crate::error::Result;
+
+/// Output format for the object graph.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum GraphFormatCli {
+    /// GraphViz DOT
+    Dot,
+    /// D2 diagram
+    D2,
+    /// JSON node and edge lists
+    Json,
+}
+
+impl From<GraphFormatCli> for GraphFormat {
+    fn from(value: GraphFormatCli) -> Self {
+        match value {
+            GraphFormatCli::Dot => GraphFormat::Dot,
+            GraphFormatCli::D2 => GraphFormat::D2,
+            GraphFormatCli::Json => GraphFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, clap::Parser)]
+pub(crate) struct GraphArgs {
+    /// Path to a simulation setup containing `sites` and `brands` folders.
+    ///
+    /// The default template is rendered if no setup is given.
+    #[arg(short, long)]
+    setup: Option<String>,
+
+    #[arg(short, long, value_enum, default_value_t = GraphFormatCli::Dot)]
+    format: GraphFormatCli,
+
+    /// File to write the graph to, printed to stdout if omitted.
+    #[arg(short, long)]
+    output_file: Option<String>,
+
+    /// Directory for local copies of remote setups.
+    #[arg(long)]
+    cache_directory: Option<String>,
+}
+
+pub(super) async fn handle(args: GraphArgs) -> Result<()> {
+    let setup = match args.setup {
+        Some(path) => {
+            let url = resolve_url(Some(path))?;
+            let options = Vec::<(String, String)>::new();
+            match args.cache_directory {
+                Some(directory) => {
+                    load_cached_simulation_setup(&url, options, &LocalCache::new(directory)).await?
+                }
+                None => load_simulation_setup(&url, options).await?,
+            }
+        }
+        None => Template::default().load()?,
+    };
+    let objects = ObjectData::try_new(setup.object_data()?)?;
+    let rendered = objects.graph()?.render(args.format.into())?;
+
+    match args.output_file {
+        Some(path) => std::fs::write(path, rendered)?,
+        None => print!("{rendered}"),
+    }
+
+    Ok(())
+}
@@ -1,7 +1,16 @@
-use caspers_universe::{BrandTemplate, SiteTemplate, Template, initialize_template, resolve_url};
+use std::fmt;
+
+use caspers_universe::Error as UniverseError;
+use caspers_universe::{
+    BrandTemplate, MenuGenerator, RedactionPolicy, SiteTemplate, Template, initialize_template,
+    resolve_url,
+};
 use dialoguer::MultiSelect;
+use serde::Serialize;
+use url::Url;
 
 use crate::error::Result;
+use crate::output::OutputFormat;
 
 #[derive(Debug, Clone, clap::Parser)]
 pub(super) struct InitArgs {
@@ -10,9 +19,38 @@ pub(super) struct InitArgs {
 
     #[arg(short, long)]
     working_directory: Option<String>,
+
+    /// Seed for generating the population, runs of setups with the same seed share their people.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// JSON file with the cuisine mix of brands generated in addition to the selected brands.
+    #[arg(long)]
+    menus: Option<String>,
+
+    /// JSON file mapping column paths, e.g. `properties.email`, to `drop`, `hash` or `mask`.
+    #[arg(long)]
+    redaction: Option<String>,
+}
+
+/// Outcome of the `init` command.
+#[derive(Debug, Serialize)]
+struct InitReport {
+    working_directory: Url,
+    template: bool,
+}
+
+impl fmt::Display for InitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.template {
+            writeln!(f, "Template loaded successfully")
+        } else {
+            writeln!(f, "Initializing without template")
+        }
+    }
 }
 
-pub(super) async fn handle(args: InitArgs) -> Result<()> {
+pub(super) async fn handle(args: InitArgs, output: OutputFormat) -> Result<()> {
     let caspers_directory = resolve_url(args.working_directory)?;
     if args.template {
         let sites = vec![
@@ -54,13 +92,24 @@ pub(super) async fn handle(args: InitArgs) -> Result<()> {
             .map(|idx| brands[idx])
             .collect::<Vec<_>>();
 
-        let template = Template::new(selected_sites, selected_brands);
-
-        initialize_template(&caspers_directory, template).await?;
+        let mut template = Template::new(selected_sites, selected_brands);
+        if let Some(path) = &args.menus {
+            let mut menus: MenuGenerator =
+                serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?;
+            menus.seed = menus.seed.or(args.seed);
+            template = template.with_menus(menus);
+        }
 
-        println!("Template loaded successfully");
-    } else {
-        println!("Initializing without template");
+        let redaction: RedactionPolicy = match &args.redaction {
+            Some(path) => {
+                serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?
+            }
+            None => RedactionPolicy::default(),
+        };
+        initialize_template(&caspers_directory, template, args.seed, redaction).await?;
     }
-    Ok(())
+    output.print(&InitReport {
+        working_directory: caspers_directory,
+        template: args.template,
+    })
 }
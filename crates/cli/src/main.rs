@@ -1,14 +1,28 @@
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory as _, Parser, Subcommand, ValueEnum};
 
-use caspers_universe::{Result, SimulationMode};
+use std::process::ExitCode;
 
-use crate::{init::InitArgs, run::RunArgs};
+use caspers_universe::SimulationMode;
 
+use crate::error::Result;
+use crate::output::OutputFormat;
+
+use crate::{
+    dashboard::WatchArgs, frames::FramesArgs, graph::GraphArgs, init::InitArgs, run::RunArgs,
+    verify::VerifyArgs,
+};
+
+mod access;
+mod dashboard;
 mod error;
+mod frames;
+mod graph;
 mod init;
+mod output;
 mod run;
 mod server;
 mod telemetry;
+mod verify;
 
 #[derive(clap::Parser)]
 #[command(name = "caspers-universe", version, about = "Running Caspers Universe", long_about = None)]
@@ -31,16 +45,37 @@ struct GlobalOpts {
         default_value = "http://localhost:8080"
     )]
     server: String,
+
+    /// Format of the reports printed by commands.
+    #[clap(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Run a simulation
-    Run(RunArgs),
+    Run(Box<RunArgs>),
     /// Initialize a simulation setup
     Init(InitArgs),
     /// Run the servers
     Server(ServerArgs),
+    /// Render the simulation object model as a graph
+    Graph(GraphArgs),
+    /// Render animation frames of a simulation run as GeoJSON
+    Frames(FramesArgs),
+    /// Watch live stats of a running simulation
+    Watch(WatchArgs),
+    /// Replay the latest seeded run of a simulation and compare its events
+    Verify(VerifyArgs),
+    /// Generate shell completions
+    Completions(CompletionsArgs),
+}
+
+#[derive(Debug, Args)]
+struct CompletionsArgs {
+    /// Shell to generate completions for.
+    #[clap(value_enum)]
+    shell: clap_complete::Shell,
 }
 
 #[derive(Debug, Args)]
@@ -53,6 +88,16 @@ struct ServerArgs {
         default_value = "0.0.0.0:8000"
     )]
     server: String,
+
+    /// Path where the simulation results are stored.
+    #[clap(short, long)]
+    working_directory: Option<String>,
+
+    /// JSON file mapping API tokens to their role (`ops`, `analyst` or `demo`).
+    ///
+    /// Without tokens the API is open and every request has the `ops` role.
+    #[clap(long)]
+    api_tokens: Option<String>,
 }
 /// Execution mode for the simulation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -77,16 +122,40 @@ impl From<SimulationModeCli> for SimulationMode {
 }
 
 #[tokio::main(flavor = "multi_thread")]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> ExitCode {
     telemetry::init_tracer_provider();
     let _guard = telemetry::init_tracing_subscriber();
 
     let cli = Cli::parse();
 
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err.report());
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let output = cli.global_opts.output;
     match cli.command {
-        Commands::Run(args) => run::handle(args).await?,
-        Commands::Init(args) => init::handle(args).await?,
+        Commands::Run(args) => run::handle(*args, output).await?,
+        Commands::Init(args) => init::handle(args, output).await?,
         Commands::Server(args) => server::handle(args).await?,
+        Commands::Graph(args) => graph::handle(args).await?,
+        Commands::Frames(args) => frames::handle(args, output).await?,
+        Commands::Watch(args) => dashboard::handle(args).await?,
+        Commands::Verify(args) => verify::handle(args, output).await?,
+        Commands::Completions(args) => {
+            let bin_name = env!("CARGO_BIN_NAME");
+            clap_complete::generate(
+                args.shell,
+                &mut Cli::command(),
+                bin_name,
+                &mut std::io::stdout(),
+            );
+        }
     }
 
     Ok(())
@@ -0,0 +1,31 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::error::Result;
+
+/// Format of reports printed by commands.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Text,
+    /// A single JSON document, e.g. for scripting.
+    Json,
+}
+
+impl OutputFormat {
+    /// Print a report to stdout in this format.
+    pub(crate) fn print<T: Serialize + fmt::Display>(&self, report: &T) -> Result<()> {
+        match self {
+            OutputFormat::Text => print!("{report}"),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(report).map_err(caspers_universe::Error::from)?
+            ),
+        }
+        Ok(())
+    }
+}
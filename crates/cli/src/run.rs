@@ -1,12 +1,29 @@
+use std::collections::HashMap;
+use std::fmt;
+
 use arrow::array::AsArray;
 use arrow::datatypes::TimestampMillisecondType;
 use caspers_universe::Error as UniverseError;
-use caspers_universe::{Simulation, SimulationContext, SimulationMode, resolve_url};
-use chrono::{DateTime, Utc};
+use caspers_universe::{
+    AdaptiveTimeStep, BehaviorHooks, CalendarConfig, Campaign, CarbonConfig, CompensationPolicy,
+    CourierBreaks, CuisinePreferences, DEFAULT_WRITE_CONCURRENCY, DarkStoreConfig, DeliveryRobots,
+    DestinationConfig, EventCoalescing, EventFilter, EventScheduler, FeedbackConfig,
+    GreenDeliveryConfig, InventoryConfig, LocalCache, MembershipConfig, MobilityConfig,
+    NotificationConfig, PriorityConfig, RedactionPolicy, RetryPolicy, RoadClosure, Scenario,
+    SeasonalityConfig, SessionConfig, Simulation, SimulationContext, SimulationContextBuilder,
+    SimulationMode, SiteId, StateStats, StopConditions, resolve_url,
+};
+use chrono::{DateTime, Duration, Utc};
 use clap::ValueEnum;
 use dialoguer::Select;
+use serde::Serialize;
+use uuid::Uuid;
 
+use crate::access::ApiTokens;
+use crate::dashboard::{self, StatsSource};
 use crate::error::Result;
+use crate::output::OutputFormat;
+use crate::server::{ControlRegistry, StatsRegistry};
 
 /// Execution mode for the simulation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -32,8 +49,28 @@ impl From<SimulationModeCli> for SimulationMode {
 
 #[derive(Debug, Clone, clap::Parser)]
 pub(crate) struct RunArgs {
-    #[arg(short, long, default_value_t = 100)]
-    duration: usize,
+    /// Number of steps to run, 100 unless another stop condition is given.
+    #[arg(short, long, conflicts_with = "scenario")]
+    duration: Option<usize>,
+
+    /// End the run once the simulation time reaches this time (RFC 3339).
+    #[arg(long)]
+    until: Option<DateTime<Utc>>,
+
+    /// End the run after the first step completing this many seconds after it started.
+    #[arg(long)]
+    time_budget: Option<u64>,
+
+    /// End the run after the first step by which this many orders were created.
+    #[arg(long)]
+    max_orders: Option<usize>,
+
+    /// TOML file declaring the sites, brands, duration, time step, snapshot interval,
+    /// failures and output locations of the run.
+    ///
+    /// The working directory and run name given on the command line take precedence.
+    #[arg(long)]
+    scenario: Option<String>,
 
     #[arg(short, long)]
     /// Path where basic simulation setup is stored.
@@ -44,13 +81,594 @@ pub(crate) struct RunArgs {
 
     #[arg(long, default_value_t = false)]
     dry_run: bool,
+
+    /// Continue the simulation of this snapshot from the time it was taken.
+    ///
+    /// The simulation and snapshot to start from are asked for if not given.
+    #[arg(long)]
+    resume: Option<Uuid>,
+
+    /// Human readable label stored with the snapshots of this run.
+    #[arg(long)]
+    run_name: Option<String>,
+
+    /// Print state size and cardinality stats every n steps and after the run.
+    #[arg(long)]
+    state_stats: Option<usize>,
+
+    /// Include row and null counts of all tables in the periodic state stats.
+    ///
+    /// Tables are scanned in full for every report, which gets slower as the run progresses.
+    #[arg(long, default_value_t = false, requires = "state_stats")]
+    table_stats: bool,
+
+    /// JSON file with SQL expressions customizing demand and customer behavior.
+    #[arg(long)]
+    hooks: Option<String>,
+
+    /// JSON file with a list of promotional campaigns discounting new orders.
+    #[arg(long)]
+    campaigns: Option<String>,
+
+    /// JSON file with the rules issuing vouchers for late deliveries.
+    #[arg(long)]
+    compensation: Option<String>,
+
+    /// JSON file with the e-bike share, battery range and break durations of couriers.
+    #[arg(long)]
+    courier_breaks: Option<String>,
+
+    /// JSON file with the pickers, SKU inventory and substitutes of sites run as dark stores, keyed by site name.
+    #[arg(long)]
+    dark_stores: Option<String>,
+
+    /// JSON file with the delivery robots or drones of sites, keyed by site name (experimental).
+    #[arg(long)]
+    delivery_robots: Option<String>,
+
+    /// JSON file with streets closed during time windows, e.g. for roadworks.
+    #[arg(long)]
+    road_closures: Option<String>,
+
+    /// JSON file with the rating prompt and response rates of delivered orders.
+    #[arg(long)]
+    feedback: Option<String>,
+
+    /// JSON file with the relative weights with which customers choose each cuisine.
+    #[arg(long)]
+    cuisine_preferences: Option<String>,
+
+    /// JSON file mapping column paths, e.g. `properties.email`, to `drop`, `hash` or `mask`.
+    #[arg(long)]
+    redaction: Option<String>,
+
+    /// Compare row counts and digests of the stored files with their manifests, not only sizes.
+    #[arg(long)]
+    verify_checksums: bool,
+
+    /// JSON file with the channels and engagement rates of customer notifications.
+    #[arg(long, conflicts_with = "no_notifications")]
+    notifications: Option<String>,
+
+    /// Do not send notifications to customers.
+    #[arg(long, default_value_t = false)]
+    no_notifications: bool,
+
+    /// JSON file with the activity schedule of customers moving around between orders.
+    ///
+    /// Use `{}` for the default schedule of commutes and errands.
+    #[arg(long)]
+    mobility: Option<String>,
+
+    /// JSON file with the homes, workplaces and spots customers have their orders delivered to.
+    ///
+    /// Use `{}` for workplaces within commuting distance of the homes.
+    #[arg(long)]
+    destinations: Option<String>,
+
+    /// JSON file with the shares of customers holding a priority subscription or VIP status.
+    ///
+    /// Use `{}` for the default shares, all orders are normal priority if not given.
+    #[arg(long)]
+    priority_tiers: Option<String>,
+
+    /// JSON file with the share, fees and perks of delivery memberships of customers.
+    ///
+    /// Use `{}` for the default memberships, no delivery fees are charged if not given.
+    #[arg(long)]
+    memberships: Option<String>,
+
+    /// JSON file with the conversion and timing of app sessions preceding orders.
+    ///
+    /// The menu items shown in sessions are written to the `impressions` table. Use `{}`
+    /// for the default funnel, no session events are generated if not given.
+    #[arg(long)]
+    sessions: Option<String>,
+
+    /// JSON file with the par levels and restock hour of the ingredient stock of sites.
+    ///
+    /// Use `{}` for the defaults, the daily stock movements are written to the
+    /// `ingredient_inventory` table. No stock is tracked if not given. Set
+    /// `procurement` to order ingredients from suppliers with lead times and delays
+    /// instead of restocking instantly, and `waste` to expire and discard stock,
+    /// written to the `ingredient_waste` table.
+    #[arg(long)]
+    inventory: Option<String>,
+
+    /// JSON file with the longest step of the event-driven scheduler.
+    ///
+    /// Use `{}` for the defaults. Steps advance to the next time an order, courier or
+    /// customer is due to move on instead of by the time increment, so quiet periods
+    /// pass in few steps. The duration still counts time increments.
+    #[arg(long)]
+    scheduler: Option<String>,
+
+    /// JSON file with the bounds of steps lengthened while the simulation is quiet.
+    ///
+    /// Use `{}` for the defaults. Steps grow while no order is open and nobody is on
+    /// their way, and shrink back on activity. Cannot be combined with `--scheduler`.
+    #[arg(long)]
+    adaptive_step: Option<String>,
+
+    /// JSON file with the grams of CO2 emitted per km by each mode of delivery.
+    ///
+    /// Use `{}` for the defaults. The footprint of each delivery is recorded with its
+    /// order and rolled up per site and day in the `delivery_footprint` table. No
+    /// footprint is estimated if not given.
+    #[arg(long)]
+    carbon: Option<String>,
+
+    /// JSON file with the green delivery option offered to customers at checkout.
+    ///
+    /// Use `{}` for the defaults. Green orders are promised later and delivered in
+    /// bundles, and rolled up next to standard orders per site and day in the
+    /// `green_delivery` table. All orders are delivered on their own if not given.
+    #[arg(long)]
+    green_delivery: Option<String>,
+
+    /// JSON file with the public holidays and special events of the run.
+    ///
+    /// Holidays of the configured `country` and events near sites scale demand and
+    /// courier supply while they last, streets closed for events are avoided when
+    /// planning routes.
+    #[arg(long)]
+    calendar: Option<String>,
+
+    /// JSON file with the variation of demand and weather over the year.
+    ///
+    /// Use `{}` for the defaults, a summer dip and a December peak. Demand is the
+    /// same all year if not given.
+    #[arg(long)]
+    seasonality: Option<String>,
+
+    /// Seed of all random choices, runs from the same snapshot with the same seed are reproducible.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Checkpoint the state every n simulated days, e.g. to resume long backfills.
+    ///
+    /// Checkpoints are snapshots taken at midnight UTC, marked with the day they
+    /// complete in their properties.
+    #[arg(long)]
+    checkpoint_days: Option<u32>,
+
+    /// JSON file selecting the event kinds and sample rates of written events.
+    #[arg(long)]
+    event_filter: Option<String>,
+
+    /// JSON file with the target rows and age of events buffered across steps.
+    ///
+    /// Buffered events are written in fewer, larger files, and at the latest with
+    /// each snapshot.
+    #[arg(long)]
+    event_coalescing: Option<String>,
+
+    /// Quarantine sites after this many consecutive failed steps.
+    #[arg(
+        long,
+        default_value_t = caspers_universe::DEFAULT_SITE_FAILURE_THRESHOLD,
+        conflicts_with = "scenario"
+    )]
+    site_failure_threshold: usize,
+
+    /// Simplify journeys in written events to within this many meters.
+    ///
+    /// Journeys keep every vertex of the street network during the run; 0 only
+    /// drops vertices on straight lines.
+    #[arg(long, default_value_t = caspers_universe::DEFAULT_JOURNEY_TOLERANCE_M)]
+    journey_tolerance_m: f64,
+
+    /// H3 resolution of the order heatmap materialized after the run.
+    #[arg(long, default_value_t = caspers_universe::DEFAULT_HEATMAP_RESOLUTION)]
+    heatmap_resolution: u8,
+
+    /// Write daily rollups of orders, revenue and courier load to the `daily_summary` table.
+    #[arg(long, default_value_t = false)]
+    daily_summary: bool,
+
+    /// Mark customers as churned when they did not order for this many days.
+    #[arg(long, default_value_t = caspers_universe::DEFAULT_CHURN_AFTER.num_days())]
+    churn_after_days: i64,
+
+    /// Retry failed storage operations this many times before giving up.
+    #[arg(long, default_value_t = RetryPolicy::default().max_retries())]
+    storage_retries: usize,
+
+    /// Write at most this many result and snapshot tables at the same time.
+    #[arg(long, default_value_t = DEFAULT_WRITE_CONCURRENCY)]
+    write_concurrency: usize,
+
+    /// Serve live simulation stats at this address while running, e.g. `127.0.0.1:8000`.
+    ///
+    /// In realtime and catchup mode, settings like the demand multiplier can be changed,
+    /// and the simulation paused and resumed, through the served API while it runs.
+    #[arg(long)]
+    serve: Option<String>,
+
+    /// JSON file mapping tokens for the served API to their role, see `caspers server`.
+    #[arg(long, requires = "serve")]
+    api_tokens: Option<String>,
+
+    /// Show a dashboard with live stats of the run in the terminal.
+    #[arg(long, default_value_t = false)]
+    watch: bool,
+
+    /// Directory for local copies of remote routing data.
+    #[arg(long)]
+    cache_directory: Option<String>,
+
+    /// WebAssembly module (.wasm or .wat) implementing behavior plugin hooks.
+    #[cfg(feature = "wasm")]
+    #[arg(long)]
+    plugin: Option<String>,
+}
+
+/// Outcome of a simulation run.
+#[derive(Debug, Serialize)]
+struct RunReport {
+    simulation_id: Uuid,
+    /// Snapshot taken at the end of the run
+    snapshot_id: Uuid,
+    quarantined_sites: Vec<SiteId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_stats: Option<StateStats>,
 }
 
-pub(super) async fn handle(args: RunArgs) -> Result<()> {
-    let caspers_directory = resolve_url(args.working_directory)?;
-    let mut builder =
-        SimulationContext::builder().with_working_directory(caspers_directory.clone());
+impl fmt::Display for RunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for site_id in &self.quarantined_sites {
+            writeln!(f, "site {site_id} was quarantined after repeated failures")?;
+        }
+        if let Some(state_stats) = &self.state_stats {
+            writeln!(f, "{state_stats}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) async fn handle(args: RunArgs, output: OutputFormat) -> Result<()> {
+    let hooks: BehaviorHooks = match &args.hooks {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => BehaviorHooks::default(),
+    };
+    let campaigns: Vec<Campaign> = match &args.campaigns {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => Vec::new(),
+    };
+    let event_filter: EventFilter = match &args.event_filter {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => EventFilter::default(),
+    };
+    let event_coalescing: Option<EventCoalescing> = match &args.event_coalescing {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let compensation: CompensationPolicy = match &args.compensation {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => CompensationPolicy::default(),
+    };
+    let courier_breaks: CourierBreaks = match &args.courier_breaks {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => CourierBreaks::default(),
+    };
+    let dark_stores: HashMap<String, DarkStoreConfig> = match &args.dark_stores {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => HashMap::new(),
+    };
+    let delivery_robots: HashMap<String, DeliveryRobots> = match &args.delivery_robots {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => HashMap::new(),
+    };
+    let road_closures: Vec<RoadClosure> = match &args.road_closures {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => Vec::new(),
+    };
+    let feedback: FeedbackConfig = match &args.feedback {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => FeedbackConfig::default(),
+    };
+    let cuisine_preferences: CuisinePreferences = match &args.cuisine_preferences {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => CuisinePreferences::default(),
+    };
+    let notifications: Option<NotificationConfig> = match &args.notifications {
+        _ if args.no_notifications => None,
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => Some(NotificationConfig::default()),
+    };
+    let mobility: Option<MobilityConfig> = match &args.mobility {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let destinations: Option<DestinationConfig> = match &args.destinations {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let priority_tiers: Option<PriorityConfig> = match &args.priority_tiers {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let memberships: Option<MembershipConfig> = match &args.memberships {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let sessions: Option<SessionConfig> = match &args.sessions {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let inventory: Option<InventoryConfig> = match &args.inventory {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let scheduler: Option<EventScheduler> = match &args.scheduler {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let adaptive_step: Option<AdaptiveTimeStep> = match &args.adaptive_step {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let carbon: Option<CarbonConfig> = match &args.carbon {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let green_delivery: Option<GreenDeliveryConfig> = match &args.green_delivery {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let calendar: Option<CalendarConfig> = match &args.calendar {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let seasonality: Option<SeasonalityConfig> = match &args.seasonality {
+        Some(path) => {
+            Some(serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?)
+        }
+        None => None,
+    };
+    let redaction: RedactionPolicy = match &args.redaction {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?).map_err(UniverseError::from)?,
+        None => RedactionPolicy::default(),
+    };
+    let scenario = args
+        .scenario
+        .as_ref()
+        .map(Scenario::from_file)
+        .transpose()?;
+    let caspers_directory = match (&args.working_directory, &scenario) {
+        (None, Some(scenario)) => scenario.working_directory()?,
+        (working_directory, _) => resolve_url(working_directory.as_ref())?,
+    };
+    let run_name = args.run_name.clone().or_else(|| {
+        scenario
+            .as_ref()
+            .and_then(|scenario| scenario.output.run_name.clone())
+    });
+    let builder = SimulationContext::builder()
+        .with_working_directory(caspers_directory.clone())
+        .with_retry_policy(RetryPolicy::default().with_max_retries(args.storage_retries))
+        .with_write_concurrency(args.write_concurrency)
+        .with_cache(args.cache_directory.as_ref().map(LocalCache::new))
+        .with_run_name(run_name)
+        .with_redaction(redaction)
+        .with_verify_checksums(args.verify_checksums);
+
+    let (builder, snapshot_id) = match args.resume {
+        Some(snapshot_id) => (builder, snapshot_id),
+        None => {
+            let Some(selection) = select_snapshot(builder).await? else {
+                return Ok(());
+            };
+            selection
+        }
+    };
+    let ctx = builder
+        .with_resume_snapshot(snapshot_id)
+        .await?
+        .build()
+        .await?;
+    let start_time = *ctx.current_time();
+
+    let builder = Simulation::builder()
+        .with_context(ctx)
+        .with_dry_run(args.dry_run)
+        .with_start_time(start_time)
+        .with_state_stats_interval(args.state_stats)
+        .with_table_stats(args.table_stats)
+        .with_hooks(hooks)
+        .with_campaigns(campaigns)
+        .with_event_filter(event_filter)
+        .with_event_coalescing(event_coalescing)
+        .with_compensation_policy(compensation)
+        .with_courier_breaks(courier_breaks)
+        .with_dark_stores(dark_stores)
+        .with_delivery_robots(delivery_robots)
+        .with_road_closures(road_closures)
+        .with_feedback(feedback)
+        .with_cuisine_preferences(cuisine_preferences)
+        .with_site_failure_threshold(args.site_failure_threshold)
+        .with_journey_tolerance(args.journey_tolerance_m)
+        .with_heatmap_resolution(args.heatmap_resolution)
+        .with_daily_summary(args.daily_summary)
+        .with_churn_after(Duration::days(args.churn_after_days))
+        .with_notifications(notifications)
+        .with_mobility(mobility)
+        .with_destinations(destinations)
+        .with_priority_tiers(priority_tiers)
+        .with_memberships(memberships)
+        .with_sessions(sessions)
+        .with_inventory(inventory)
+        .with_event_scheduler(scheduler)
+        .with_adaptive_step(adaptive_step)
+        .with_carbon(carbon)
+        .with_green_delivery(green_delivery)
+        .with_calendar(calendar)
+        .with_seasonality(seasonality)
+        .with_seed(args.seed)
+        .with_checkpoint_days(args.checkpoint_days);
+
+    // the resumed snapshot determines the start of the run
+    let (builder, steps) = match scenario {
+        Some(scenario) => {
+            let steps = scenario.steps();
+            let builder = builder.with_scenario(scenario).with_start_time(start_time);
+            (builder, Some(steps))
+        }
+        None => (builder, args.duration),
+    };
+    let mut stop = StopConditions::default();
+    if let Some(until) = args.until {
+        stop = stop.with_until(until);
+    }
+    if let Some(seconds) = args.time_budget {
+        stop = stop.with_time_budget(std::time::Duration::from_secs(seconds));
+    }
+    if let Some(orders) = args.max_orders {
+        stop = stop.with_max_orders(orders);
+    }
+    let bounded = args.until.is_some() || args.time_budget.is_some() || args.max_orders.is_some();
+    match steps {
+        Some(steps) => stop = stop.with_max_steps(steps),
+        None if !bounded => stop = stop.with_max_steps(100),
+        None => (),
+    }
+
+    #[cfg(feature = "wasm")]
+    let builder = match &args.plugin {
+        Some(path) => builder.with_plugin(std::sync::Arc::new(
+            caspers_universe::WasmPlugin::from_file(path)?,
+        )),
+        None => builder,
+    };
+
+    let mut simulation = builder.build().await?;
+
+    if let Some(server) = args.serve {
+        let tokens = match &args.api_tokens {
+            Some(path) => ApiTokens::from_file(path)?,
+            None => ApiTokens::default(),
+        };
+        let stats = StatsRegistry::default();
+        stats
+            .write()
+            .map_err(|_| UniverseError::internal("stats registry poisoned"))?
+            .insert(
+                *simulation.ctx().simulation_id(),
+                simulation.subscribe_stats(),
+            );
+        // only live runs may be steered, backfills replay a fixed configuration
+        let controls = ControlRegistry::default();
+        if args.mode != SimulationModeCli::Backfill {
+            controls
+                .write()
+                .map_err(|_| UniverseError::internal("control registry poisoned"))?
+                .insert(*simulation.ctx().simulation_id(), simulation.control());
+        }
+        tokio::spawn(async move {
+            if let Err(err) =
+                crate::server::serve(&server, stats, controls, Some(caspers_directory), tokens)
+                    .await
+            {
+                tracing::error!(target: "caspers::server", "{}", err.report());
+            }
+        });
+    }
+
+    let dashboard = args.watch.then(|| {
+        let title = format!("simulation {}", simulation.ctx().simulation_id());
+        let source = StatsSource::Local(simulation.subscribe_stats());
+        let refresh = std::time::Duration::from_millis(250);
+        tokio::spawn(dashboard::show(title, source, refresh))
+    });
+
+    // an interrupt ends the run after the current step, keeping its results
+    let control = simulation.control();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            tracing::warn!(
+                target: "caspers::simulation",
+                "interrupted, stopping after the current step (interrupt again to abort)"
+            );
+            control.stop();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        }
+    });
+
+    simulation.run_until(stop).await?;
 
+    let state_stats = match args.state_stats {
+        Some(_) => Some(simulation.state_stats().await?),
+        None => None,
+    };
+    let report = RunReport {
+        simulation_id: *simulation.ctx().simulation_id(),
+        snapshot_id: *simulation.ctx().snapshot_id(),
+        quarantined_sites: simulation.quarantined_sites().copied().collect(),
+        state_stats,
+    };
+
+    // the dashboard shows the run as finished once the stats channel is closed
+    drop(simulation);
+    if let Some(dashboard) = dashboard {
+        dashboard
+            .await
+            .map_err(|err| UniverseError::internal(err.to_string()))??;
+    }
+    output.print(&report)
+}
+
+/// Ask which simulation and snapshot to continue, `None` if the selection was aborted.
+async fn select_snapshot(
+    builder: SimulationContextBuilder,
+) -> Result<Option<(SimulationContextBuilder, Uuid)>> {
     let simulations = builder
         .load_simulations()
         .await?
@@ -70,12 +688,11 @@ pub(super) async fn handle(args: RunArgs) -> Result<()> {
         .items(&selections)
         .interact_opt()?
     else {
-        return Ok(());
+        return Ok(None);
     };
 
-    let simulation_id =
-        uuid::Uuid::try_parse(selections[sim_selection]).map_err(UniverseError::from)?;
-    builder = builder.with_simulation_id(simulation_id);
+    let simulation_id = Uuid::try_parse(selections[sim_selection]).map_err(UniverseError::from)?;
+    let builder = builder.with_simulation_id(simulation_id);
 
     let snapshots = builder
         .load_snapshots()
@@ -86,37 +703,36 @@ pub(super) async fn handle(args: RunArgs) -> Result<()> {
         .await
         .map_err(UniverseError::from)?;
 
-    let selections = (0..snapshots.len())
-        .flat_map(|idx| snapshots[idx].column(0).as_string_view().iter())
+    let ids = snapshots
+        .iter()
+        .flat_map(|batch| batch.column(0).as_string_view().iter())
         .flatten()
         .collect::<Vec<_>>();
+    let items = snapshots
+        .iter()
+        .flat_map(|batch| {
+            batch
+                .column(1)
+                .as_primitive::<TimestampMillisecondType>()
+                .iter()
+        })
+        .zip(&ids)
+        .map(
+            |(time, id)| match time.and_then(DateTime::<Utc>::from_timestamp_millis) {
+                Some(time) => format!("{id} ({})", time.to_rfc3339()),
+                None => id.to_string(),
+            },
+        )
+        .collect::<Vec<_>>();
 
     let Some(sn_selection) = Select::new()
         .with_prompt("Which snapshot to start from?")
-        .items(&selections)
+        .items(&items)
         .interact_opt()?
     else {
-        return Ok(());
+        return Ok(None);
     };
 
-    let snapshot_id =
-        uuid::Uuid::try_parse(selections[sn_selection]).map_err(UniverseError::from)?;
-    let ctx = builder.with_snapshot_id(snapshot_id).build().await?;
-
-    let start_time = snapshots[sn_selection]
-        .column(1)
-        .as_primitive::<TimestampMillisecondType>()
-        .value(sn_selection);
-    let start_time = DateTime::<Utc>::from_timestamp_millis(start_time).expect("Invalid timestamp");
-
-    let mut simulation = Simulation::builder()
-        .with_context(ctx)
-        .with_dry_run(args.dry_run)
-        .with_start_time(start_time)
-        .build()
-        .await?;
-
-    simulation.run(args.duration).await?;
-
-    Ok(())
+    let snapshot_id = Uuid::try_parse(ids[sn_selection]).map_err(UniverseError::from)?;
+    Ok(Some((builder, snapshot_id)))
 }
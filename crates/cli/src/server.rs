@@ -1,16 +1,140 @@
-use axum::{Router, response::Json, routing::get};
-use caspers_universe::Result;
+use arrow::array::{AsArray as _, RecordBatch};
+use arrow::ipc::writer::StreamWriter;
+use arrow::json::ArrayWriter;
+use axum::extract::{FromRef, Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::{
+    Router,
+    response::Json,
+    routing::{get, post},
+};
+use caspers_universe::{
+    Error, ErrorKind, FrameRenderer, PlaybackFrame, Result, RuntimeSettings, SettingsUpdate,
+    SimulationContext, SimulationControl, SimulationStats, resolve_url,
+};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
 use std::{net::SocketAddr, path::PathBuf};
+use tokio::sync::watch;
 use tower_http::{
     cors::CorsLayer,
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
+use url::Url;
+use uuid::Uuid;
 
 use crate::ServerArgs;
+use crate::access::{AccessError, ApiTokens, Endpoint, Role};
+
+/// Live stats of the simulations running in this process, by simulation id.
+pub(crate) type StatsRegistry = Arc<RwLock<HashMap<Uuid, watch::Receiver<SimulationStats>>>>;
+
+/// Controls of the simulations running in this process that may be steered, by simulation id.
+pub(crate) type ControlRegistry = Arc<RwLock<HashMap<Uuid, SimulationControl>>>;
+
+/// Simulation id, H3 resolution and frame interval of a playback.
+type PlaybackKey = (Uuid, u8, i64);
+
+/// Most playbacks kept in the [`PlaybackCache`].
+const MAX_CACHED_PLAYBACKS: usize = 32;
+
+/// Playback of completed runs, dropping the least recently used beyond [`MAX_CACHED_PLAYBACKS`].
+#[derive(Debug, Default)]
+struct PlaybackCache {
+    entries: HashMap<PlaybackKey, (u64, Arc<Value>)>,
+    /// Number of lookups and inserts so far, recorded with the entries they used
+    clock: u64,
+}
+
+impl PlaybackCache {
+    fn get(&mut self, key: &PlaybackKey) -> Option<Arc<Value>> {
+        self.clock += 1;
+        let (last_used, playback) = self.entries.get_mut(key)?;
+        *last_used = self.clock;
+        Some(playback.clone())
+    }
+
+    fn insert(&mut self, key: PlaybackKey, playback: Arc<Value>) {
+        self.clock += 1;
+        self.entries.insert(key, (self.clock, playback));
+        if self.entries.len() > MAX_CACHED_PLAYBACKS {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    stats: StatsRegistry,
+    controls: ControlRegistry,
+    working_directory: Option<Url>,
+    playback: Arc<Mutex<PlaybackCache>>,
+    tokens: ApiTokens,
+}
+
+impl FromRef<AppState> for ApiTokens {
+    fn from_ref(state: &AppState) -> Self {
+        state.tokens.clone()
+    }
+}
+
+impl AppState {
+    fn working_directory(&self) -> Result<Url> {
+        self.working_directory
+            .clone()
+            .ok_or_else(|| Error::missing_input("server has no working directory"))
+    }
+}
+
+/// Drive the future created by `task` to completion on a blocking thread.
+///
+/// Futures of the simulation context are not `Send`, so they cannot be awaited in handlers.
+async fn on_blocking_thread<T, F, Fut>(task: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T>>,
+{
+    tokio::task::spawn_blocking(move || tokio::runtime::Handle::current().block_on(task()))
+        .await
+        .map_err(|e| Error::internal(format!("blocking task failed: {e}")))?
+}
 
 pub(super) async fn handle(args: ServerArgs) -> Result<()> {
+    let working_directory = resolve_url(args.working_directory)?;
+    let tokens = match &args.api_tokens {
+        Some(path) => ApiTokens::from_file(path)?,
+        None => ApiTokens::default(),
+    };
+    serve(
+        &args.server,
+        StatsRegistry::default(),
+        ControlRegistry::default(),
+        Some(working_directory),
+        tokens,
+    )
+    .await
+}
+
+pub(crate) async fn serve(
+    server: &str,
+    stats: StatsRegistry,
+    controls: ControlRegistry,
+    working_directory: Option<Url>,
+    tokens: ApiTokens,
+) -> Result<()> {
     // Get the assets directory path relative to the crate root
     let assets_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
     let index_path = assets_dir.join("index.html");
@@ -24,14 +148,33 @@ pub(super) async fn handle(args: ServerArgs) -> Result<()> {
     let app = Router::new()
         .route("/api/health", get(health_check))
         .route("/api/simulation", get(simulation_status))
+        .route("/api/simulations/{id}/stats", get(simulation_stats))
+        .route(
+            "/api/simulations/{id}/settings",
+            get(simulation_settings).patch(update_simulation_settings),
+        )
+        .route("/api/simulations/{id}/pause", post(pause_simulation))
+        .route("/api/simulations/{id}/resume", post(resume_simulation))
+        .route("/api/simulations/{id}/playback", get(simulation_playback))
+        .route("/api/simulations/{id}/snapshots", get(list_snapshots))
+        .route(
+            "/api/simulations/{id}/snapshots/{snapshot_id}/tables/{name}",
+            get(snapshot_table),
+        )
         .layer(TraceLayer::new_for_http())
         .layer(CorsLayer::permissive())
-        .fallback_service(serve_dir);
+        .fallback_service(serve_dir)
+        .with_state(AppState {
+            stats,
+            controls,
+            working_directory,
+            playback: Default::default(),
+            tokens,
+        });
 
-    let addr: SocketAddr = args
-        .server
+    let addr: SocketAddr = server
         .parse()
-        .map_err(|e| caspers_universe::Error::Generic(Box::new(e)))?;
+        .map_err(|e| Error::invalid_data(format!("invalid server address '{server}': {e}")))?;
     tracing::info!(target: "caspers::server", "Starting server on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await?;
     axum::serve(listener, app).await?;
@@ -45,9 +188,415 @@ async fn health_check() -> Json<Value> {
     }))
 }
 
-async fn simulation_status() -> Json<Value> {
-    Json(json!({
+async fn simulation_status() -> Result<Json<Value>, ApiError> {
+    Ok(Json(json!({
         "status": "idle",
         "message": "No simulation currently running"
-    }))
+    })))
+}
+
+async fn simulation_stats(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SimulationStats>, ApiError> {
+    role.require(Endpoint::Stats)?;
+    let stats = state
+        .stats
+        .read()
+        .map_err(|_| Error::internal("stats registry poisoned"))?;
+    let receiver = stats
+        .get(&id)
+        .ok_or_else(|| Error::not_found("simulation", id))?;
+    Ok(Json(receiver.borrow().clone()))
+}
+
+fn simulation_control(state: &AppState, id: Uuid) -> Result<SimulationControl> {
+    state
+        .controls
+        .read()
+        .map_err(|_| Error::internal("control registry poisoned"))?
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| Error::not_found("steerable simulation", id))
+}
+
+/// Settings of a running simulation as of its last step.
+async fn simulation_settings(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RuntimeSettings>, ApiError> {
+    role.require(Endpoint::Settings)?;
+    Ok(Json(simulation_control(&state, id)?.settings()))
+}
+
+/// Change settings of a running simulation, applied at the start of its next step.
+async fn update_simulation_settings(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+    Json(update): Json<SettingsUpdate>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    role.require(Endpoint::Settings)?;
+    simulation_control(&state, id)?.update(update.clone())?;
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({
+            "simulation_id": id,
+            "update": update,
+        })),
+    ))
+}
+
+/// Hold a running simulation once its current step completed.
+async fn pause_simulation(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    role.require(Endpoint::Settings)?;
+    simulation_control(&state, id)?.pause();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "simulation_id": id, "paused": true })),
+    ))
+}
+
+/// Continue a paused simulation with its next step.
+async fn resume_simulation(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    role.require(Endpoint::Settings)?;
+    simulation_control(&state, id)?.resume();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "simulation_id": id, "paused": false })),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaybackParams {
+    /// H3 resolution people are aggregated to.
+    #[serde(default = "PlaybackParams::default_resolution")]
+    resolution: u8,
+
+    /// Simulated seconds between two consecutive frames.
+    #[serde(default = "PlaybackParams::default_interval")]
+    interval: i64,
+}
+
+impl PlaybackParams {
+    /// Finest H3 resolution.
+    const MAX_RESOLUTION: u8 = 15;
+
+    /// Bounds of the frame interval, in simulated seconds.
+    const INTERVALS: std::ops::RangeInclusive<i64> = 10..=86_400;
+
+    fn default_resolution() -> u8 {
+        9
+    }
+
+    fn default_interval() -> i64 {
+        60
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.resolution > Self::MAX_RESOLUTION {
+            return Err(Error::invalid_data(format!(
+                "resolution must be at most {}, got {}",
+                Self::MAX_RESOLUTION,
+                self.resolution
+            )));
+        }
+        if !Self::INTERVALS.contains(&self.interval) {
+            return Err(Error::invalid_data(format!(
+                "interval must be between {} and {} seconds, got {}",
+                Self::INTERVALS.start(),
+                Self::INTERVALS.end(),
+                self.interval
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Playback {
+    simulation_id: Uuid,
+    resolution: u8,
+    interval: i64,
+    frames: Vec<PlaybackFrame>,
+}
+
+/// Frames of a stored simulation run aggregated per H3 cell, for scrubbing in the UI.
+///
+/// Playback of runs which are not live in this process is computed once and cached,
+/// up to [`MAX_CACHED_PLAYBACKS`] of them.
+async fn simulation_playback(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PlaybackParams>,
+) -> Result<Json<Value>, ApiError> {
+    role.require(Endpoint::Playback)?;
+    params.validate()?;
+    let key = (id, params.resolution, params.interval);
+    let cached = state
+        .playback
+        .lock()
+        .map_err(|_| Error::internal("playback cache poisoned"))?
+        .get(&key);
+    if let Some(playback) = cached {
+        return Ok(Json(playback.as_ref().clone()));
+    }
+
+    let working_directory = state.working_directory()?;
+    let playback =
+        on_blocking_thread(
+            move || async move { load_playback(&working_directory, id, &params).await },
+        )
+        .await?;
+    let playback = Arc::new(playback);
+
+    let is_live = state
+        .stats
+        .read()
+        .map_err(|_| Error::internal("stats registry poisoned"))?
+        .contains_key(&id);
+    if !is_live {
+        state
+            .playback
+            .lock()
+            .map_err(|_| Error::internal("playback cache poisoned"))?
+            .insert(key, playback.clone());
+    }
+    Ok(Json(playback.as_ref().clone()))
+}
+
+async fn load_playback(
+    working_directory: &Url,
+    simulation_id: Uuid,
+    params: &PlaybackParams,
+) -> Result<Value> {
+    let builder = SimulationContext::builder()
+        .with_working_directory(working_directory.clone())
+        .with_simulation_id(simulation_id);
+
+    // snapshots are ordered newest first, the run is replayed from the initial one
+    let snapshots = builder
+        .load_snapshots()
+        .await?
+        .select_columns(&["id"])?
+        .collect()
+        .await?;
+    let snapshot_id = snapshots
+        .iter()
+        .rev()
+        .find(|batch| batch.num_rows() > 0)
+        .map(|batch| batch.column(0).as_string_view().value(batch.num_rows() - 1))
+        .ok_or_else(|| Error::not_found("simulation", simulation_id))?;
+    let ctx = builder
+        .with_snapshot_id(Uuid::try_parse(snapshot_id)?)
+        .build()
+        .await?;
+
+    let Some((start, end)) = ctx.results().event_time_range().await? else {
+        return Err(Error::not_found("events of simulation", simulation_id));
+    };
+    let frames = FrameRenderer::new(start, end)
+        .with_frame_interval(Duration::seconds(params.interval))
+        .render_run(&ctx)
+        .await?
+        .iter()
+        .map(|frame| frame.aggregate(params.resolution))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(serde_json::to_value(Playback {
+        simulation_id,
+        resolution: params.resolution,
+        interval: params.interval,
+        frames,
+    })?)
+}
+
+/// Snapshots of a stored simulation, newest first.
+async fn list_snapshots(
+    State(state): State<AppState>,
+    role: Role,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Value>, ApiError> {
+    role.require(Endpoint::Snapshots)?;
+    let working_directory = state.working_directory()?;
+    let snapshots = on_blocking_thread(move || async move {
+        let batches = SimulationContext::builder()
+            .with_working_directory(working_directory)
+            .with_simulation_id(id)
+            .with_read_only(true)
+            .load_snapshots()
+            .await?
+            .collect()
+            .await?;
+        json_rows(&batches)
+    })
+    .await?;
+    Ok(Json(json!({
+        "simulation_id": id,
+        "snapshots": snapshots,
+    })))
+}
+
+/// Largest page of rows returned by [`snapshot_table`].
+const MAX_PAGE_SIZE: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+struct TableParams {
+    /// Maximum number of rows to return, at most [`MAX_PAGE_SIZE`].
+    #[serde(default = "TableParams::default_limit")]
+    limit: usize,
+
+    /// Number of rows to skip, for paging through the table.
+    #[serde(default)]
+    offset: usize,
+
+    /// Encoding of the returned rows.
+    #[serde(default)]
+    format: TableFormat,
+}
+
+impl TableParams {
+    fn default_limit() -> usize {
+        100
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TableFormat {
+    /// A JSON document with the rows as objects.
+    #[default]
+    Json,
+    /// An Arrow IPC stream.
+    Arrow,
+}
+
+/// A page of rows from a table of a stored snapshot.
+///
+/// Columns hidden from the role of the request's token are removed from the page.
+async fn snapshot_table(
+    State(state): State<AppState>,
+    role: Role,
+    Path((id, snapshot_id, name)): Path<(Uuid, Uuid, String)>,
+    Query(params): Query<TableParams>,
+) -> Result<Response, ApiError> {
+    role.require(Endpoint::SnapshotTables)?;
+    if matches!(params.format, TableFormat::Arrow) {
+        role.require(Endpoint::TableExport)?;
+    }
+    if params.limit > MAX_PAGE_SIZE {
+        return Err(Error::invalid_data(format!(
+            "limit must be at most {MAX_PAGE_SIZE}, got {}",
+            params.limit
+        ))
+        .into());
+    }
+    let working_directory = state.working_directory()?;
+    let table = name.clone();
+    let (schema, batches) = on_blocking_thread(move || async move {
+        let ctx = SimulationContext::builder()
+            .with_working_directory(working_directory)
+            .with_simulation_id(id)
+            .with_snapshot_id(snapshot_id)
+            .with_read_only(true)
+            .build()
+            .await?;
+        let df = ctx
+            .snapshots()
+            .table(&table)
+            .await?
+            .limit(params.offset, Some(params.limit))?;
+        let schema = Arc::new(df.schema().as_arrow().clone());
+        Ok((schema, df.collect().await?))
+    })
+    .await?;
+    let (schema, batches) = role.redact(schema, batches)?;
+
+    match params.format {
+        TableFormat::Json => Ok(Json(json!({
+            "simulation_id": id,
+            "snapshot_id": snapshot_id,
+            "table": name,
+            "offset": params.offset,
+            "limit": params.limit,
+            "rows": json_rows(&batches)?,
+        }))
+        .into_response()),
+        TableFormat::Arrow => {
+            let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(Error::from)?;
+            for batch in &batches {
+                writer.write(batch).map_err(Error::from)?;
+            }
+            let body = writer.into_inner().map_err(Error::from)?;
+            Ok(([(header::CONTENT_TYPE, ARROW_STREAM_MEDIA_TYPE)], body).into_response())
+        }
+    }
+}
+
+/// Media type of Arrow IPC streams.
+const ARROW_STREAM_MEDIA_TYPE: &str = "application/vnd.apache.arrow.stream";
+
+/// Rows of `batches` as JSON objects.
+fn json_rows(batches: &[RecordBatch]) -> Result<Value> {
+    let mut writer = ArrayWriter::new(Vec::new());
+    writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+    writer.finish()?;
+    let rows = writer.into_inner();
+    if rows.is_empty() {
+        return Ok(Value::Array(Vec::new()));
+    }
+    Ok(serde_json::from_slice(&rows)?)
+}
+
+/// Error returned from API handlers, rendered as JSON with a status matching its kind.
+enum ApiError {
+    Universe(Error),
+    Access(AccessError),
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        Self::Universe(error)
+    }
+}
+
+impl From<AccessError> for ApiError {
+    fn from(error: AccessError) -> Self {
+        Self::Access(error)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let error = match self {
+            ApiError::Universe(error) => error,
+            ApiError::Access(error) => return error.into_response(),
+        };
+        let kind = error.kind();
+        let status = match kind {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::InvalidInput => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorKind::Storage => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorKind::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        if status.is_server_error() {
+            tracing::error!(target: "caspers::server", "{}", error.report());
+        }
+        let body = Json(json!({
+            "error": error.to_string(),
+            "kind": kind.as_ref(),
+        }));
+        (status, body).into_response()
+    }
 }
@@ -0,0 +1,32 @@
+use caspers_universe::{Error, SimulationContext, resolve_url, verify_simulation};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::output::OutputFormat;
+
+#[derive(Debug, Clone, clap::Parser)]
+pub(crate) struct VerifyArgs {
+    /// Simulation whose latest seeded run is replayed.
+    simulation_id: Uuid,
+
+    #[arg(short, long)]
+    /// Path where the simulation results are stored.
+    working_directory: Option<String>,
+}
+
+/// Replay the latest seeded run of a simulation and fail if its events differ.
+pub(super) async fn handle(args: VerifyArgs, output: OutputFormat) -> Result<()> {
+    let builder =
+        SimulationContext::builder().with_working_directory(resolve_url(args.working_directory)?);
+    let verification = verify_simulation(builder, args.simulation_id).await?;
+    output.print(&verification)?;
+
+    if !verification.is_reproducible() {
+        return Err(Error::internal(format!(
+            "replay of simulation {} produced different events",
+            args.simulation_id
+        ))
+        .into());
+    }
+    Ok(())
+}
@@ -1,41 +1,120 @@
+use std::collections::HashMap;
 use std::hash::Hasher;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{any::Any, sync::LazyLock};
 
 use arrow::array::{
-    AsArray, FixedSizeBinaryBuilder, FixedSizeListBuilder, ListBuilder, RecordBatch,
+    Array as _, AsArray, FixedSizeBinaryBuilder, FixedSizeListBuilder, ListBuilder, RecordBatch,
 };
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, UInt8Type};
 use arrow_schema::{Field, TimeUnit};
 use chrono::{DateTime, Timelike, Utc};
-use datafusion::common::{Result, exec_err, plan_datafusion_err};
+use datafusion::common::{Result, exec_datafusion_err, exec_err, plan_datafusion_err};
 use datafusion::logical_expr::sort_properties::{ExprProperties, SortProperties};
 use datafusion::logical_expr::{
-    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
-    scalar_doc_sections::DOC_SECTION_STRUCT,
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature, TypeSignature,
+    Volatility, scalar_doc_sections::DOC_SECTION_STRUCT,
 };
 use datafusion::scalar::ScalarValue;
-use rand::Rng as _;
+use rand::distr::Distribution as _;
+use rand::distr::weighted::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
+
+use crate::builders::POPULATION_SCHEMA;
+use crate::state::{
+    AgeBracket, DailySchedule, Demographics, FoodPreferences, IncomeBand, MenuItemDiet,
+    hours_between,
+};
+use crate::{BehaviorPlugin, Cuisine};
 
 pub(super) mod fixed;
 
+/// Weight of breakfast relative to lunch and dinner, which are ordered in far more often.
+const BREAKFAST_WEIGHT: f64 = 0.3;
+
+/// Largest number of items in a single order.
+///
+/// Basket sizes passed to the function are clamped to this value so that a
+/// misconfigured hook cannot produce arbitrarily large orders.
+pub const MAX_BASKET_SIZE: usize = 20;
+
+/// Attempts at drawing each item of an order for customers with preferences.
+const MAX_DRAWS_PER_ITEM: usize = 20;
+
 static DOCUMENTATION: LazyLock<Documentation> = LazyLock::new(|| {
     Documentation::builder(
         DOC_SECTION_STRUCT,
         "Randomly generate order by people.",
-        "create_order(timestamp_expr)",
+        "create_order(timestamp_expr, state[, schedule[, age_bracket, household_size, income_band[, preferences]]][, probability_expr, basket_size_expr])",
     )
     .with_argument(
         "timestamp_expr",
         "Datetime expression corresponiding to time of day when decision is made.",
     )
+    .with_argument("state", "Serialized state of the person.")
+    .with_argument(
+        "schedule",
+        "Optional daily schedule of the person; orders follow their meal times while awake if set.",
+    )
+    .with_argument(
+        "age_bracket, household_size, income_band",
+        "Optional demographics of the person, which vary the menu items chosen and the default basket size.",
+    )
+    .with_argument(
+        "preferences",
+        "Optional cuisine and dietary preferences of the person; items ruled out by their diet are never chosen.",
+    )
+    .with_argument(
+        "probability_expr",
+        "Optional probability to place an order, defaults to a schedule or time of day based probability if null.",
+    )
+    .with_argument(
+        "basket_size_expr",
+        "Optional number of items in the order, clamped to between 1 and 20; defaults to a random size between 1 and 5 if null.",
+    )
     .build()
 });
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct CreateOrder {
     signature: Signature,
     menu_items: RecordBatch,
+    /// Relative chance of each menu item to be chosen, uniform if not set
+    weights: Option<WeightedIndex<f64>>,
+    /// Relative chance of each menu item to be chosen by customers of an age bracket and
+    /// income band, replacing `weights` for customers with demographics
+    demographic_weights: HashMap<(AgeBracket, IncomeBand), WeightedIndex<f64>>,
+    /// Cuisine and diets of each menu item, matched against the preferences of customers
+    item_profiles: Vec<(Option<Cuisine>, MenuItemDiet)>,
+    plugin: Option<Arc<dyn BehaviorPlugin>>,
+    /// Random numbers of seeded runs, drawn from the thread rng if not set
+    rng: Option<Arc<Mutex<StdRng>>>,
+    /// Factor applied to the order probabilities
+    demand_multiplier: f64,
+}
+
+impl PartialEq for CreateOrder {
+    fn eq(&self, other: &Self) -> bool {
+        let same_plugin = match (&self.plugin, &other.plugin) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        let same_rng = match (&self.rng, &other.rng) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        self.signature == other.signature
+            && self.menu_items == other.menu_items
+            && self.weights == other.weights
+            && self.demographic_weights == other.demographic_weights
+            && self.item_profiles == other.item_profiles
+            && same_plugin
+            && same_rng
+            && self.demand_multiplier == other.demand_multiplier
+    }
 }
 
 impl std::hash::Hash for CreateOrder {
@@ -48,17 +127,139 @@ impl std::cmp::Eq for CreateOrder {}
 
 impl CreateOrder {
     pub fn new(menu_items: RecordBatch) -> Self {
+        let timestamp = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()));
+        let schedule = POPULATION_SCHEMA
+            .field_with_name("schedule")
+            .expect("population has a schedule")
+            .data_type()
+            .clone();
+        let preferences = POPULATION_SCHEMA
+            .field_with_name("preferences")
+            .expect("population has preferences")
+            .data_type()
+            .clone();
         Self {
-            signature: Signature::exact(
+            signature: Signature::one_of(
                 vec![
-                    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
-                    DataType::Utf8View,
+                    TypeSignature::Exact(vec![timestamp.clone(), DataType::Utf8View]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        schedule.clone(),
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        DataType::Float64,
+                        DataType::Int64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        schedule.clone(),
+                        DataType::Float64,
+                        DataType::Int64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        schedule.clone(),
+                        DataType::Utf8View,
+                        DataType::UInt8,
+                        DataType::Utf8View,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        schedule.clone(),
+                        DataType::Utf8View,
+                        DataType::UInt8,
+                        DataType::Utf8View,
+                        DataType::Float64,
+                        DataType::Int64,
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp.clone(),
+                        DataType::Utf8View,
+                        schedule.clone(),
+                        DataType::Utf8View,
+                        DataType::UInt8,
+                        DataType::Utf8View,
+                        preferences.clone(),
+                    ]),
+                    TypeSignature::Exact(vec![
+                        timestamp,
+                        DataType::Utf8View,
+                        schedule,
+                        DataType::Utf8View,
+                        DataType::UInt8,
+                        DataType::Utf8View,
+                        preferences,
+                        DataType::Float64,
+                        DataType::Int64,
+                    ]),
                 ],
                 Volatility::Volatile,
             ),
             menu_items,
+            weights: None,
+            demographic_weights: HashMap::new(),
+            item_profiles: Vec::new(),
+            plugin: None,
+            rng: None,
+            demand_multiplier: 1.0,
         }
     }
+
+    /// Choose menu items with the given relative weights, one for each menu item.
+    ///
+    /// Items are chosen uniformly if no weight is positive.
+    pub fn with_weights(mut self, weights: Option<Vec<f64>>) -> Self {
+        self.weights = weights.and_then(|weights| WeightedIndex::new(weights).ok());
+        self
+    }
+
+    /// Choose menu items with relative weights per age bracket and income band.
+    ///
+    /// Customers without demographics, or of combinations without weights, choose
+    /// menu items with the weights given to [`with_weights`](Self::with_weights).
+    pub fn with_demographic_weights(
+        mut self,
+        weights: HashMap<(AgeBracket, IncomeBand), Vec<f64>>,
+    ) -> Self {
+        self.demographic_weights = weights
+            .into_iter()
+            .filter_map(|(key, weights)| Some((key, WeightedIndex::new(weights).ok()?)))
+            .collect();
+        self
+    }
+
+    /// Match the cuisine and diets of each menu item against the preferences of customers.
+    ///
+    /// Profiles are given in the order of the menu items; items without a profile suit
+    /// every diet and are of no particular cuisine.
+    pub fn with_item_profiles(mut self, profiles: Vec<(Option<Cuisine>, MenuItemDiet)>) -> Self {
+        self.item_profiles = profiles;
+        self
+    }
+
+    /// Let a plugin score order probabilities and choose menu items.
+    pub fn with_plugin(mut self, plugin: Option<Arc<dyn BehaviorPlugin>>) -> Self {
+        self.plugin = plugin;
+        self
+    }
+
+    /// Draw random numbers from `rng`, shared with the caller for reproducible runs.
+    pub fn with_rng(mut self, rng: Option<Arc<Mutex<StdRng>>>) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Scale the order probabilities by `multiplier`, after hooks and plugin scored them.
+    pub fn with_demand_multiplier(mut self, multiplier: f64) -> Self {
+        self.demand_multiplier = multiplier;
+        self
+    }
 }
 
 fn get_doc() -> &'static Documentation {
@@ -93,10 +294,59 @@ impl ScalarUDFImpl for CreateOrder {
             number_rows,
             ..
         } = args;
-        let mut rng = rand::rng();
+        let mut rng = match &self.rng {
+            Some(rng) => StdRng::from_rng(
+                &mut *rng
+                    .lock()
+                    .map_err(|_| exec_datafusion_err!("create_order rng poisoned"))?,
+            ),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
 
         let sigma_sq = 0.4_f64;
 
+        // custom probabilities and basket sizes are only passed when configured
+        let (probabilities, basket_sizes) = if matches!(args.len(), 4 | 5 | 8 | 9) {
+            let basket_sizes = args.pop().unwrap().into_array(number_rows)?;
+            let probabilities = args.pop().unwrap().into_array(number_rows)?;
+            (Some(probabilities), Some(basket_sizes))
+        } else {
+            (None, None)
+        };
+        let probabilities = probabilities
+            .as_ref()
+            .map(|arr| arr.as_primitive::<Float64Type>());
+        let basket_sizes = basket_sizes
+            .as_ref()
+            .map(|arr| arr.as_primitive::<Int64Type>());
+        let preferences = if args.len() == 7 {
+            Some(args.pop().unwrap().into_array(number_rows)?)
+        } else {
+            None
+        };
+        let preferences = preferences.as_ref().map(|arr| arr.as_struct());
+        let demographics = if args.len() == 6 {
+            let income_bands = args.pop().unwrap().into_array(number_rows)?;
+            let household_sizes = args.pop().unwrap().into_array(number_rows)?;
+            let age_brackets = args.pop().unwrap().into_array(number_rows)?;
+            Some((age_brackets, household_sizes, income_bands))
+        } else {
+            None
+        };
+        let demographics = demographics.as_ref().map(|(ages, households, incomes)| {
+            (
+                ages.as_string_view(),
+                households.as_primitive::<UInt8Type>(),
+                incomes.as_string_view(),
+            )
+        });
+        let schedules = if args.len() == 3 {
+            Some(args.pop().unwrap().into_array(number_rows)?)
+        } else {
+            None
+        };
+        let schedules = schedules.as_ref().map(|arr| arr.as_struct());
+
         let state = args
             .pop()
             .ok_or_else(|| plan_datafusion_err!("create_order expects 2 arguments"))?;
@@ -116,16 +366,60 @@ impl ScalarUDFImpl for CreateOrder {
                     return exec_err!("Invalid timestamp (create_orders)");
                 };
                 let current_minutes = (date_time.hour() * 60 + date_time.minute()) as f64 / 60.0;
-                let prob = 0.01
+                let default_prob = 0.01
                     * (bell(current_minutes, 12.0, sigma_sq)
                         + bell(current_minutes, 18.0, sigma_sq));
 
-                for _ in 0..number_rows {
+                for row in 0..number_rows {
+                    let prob = probabilities
+                        .filter(|arr| arr.is_valid(row))
+                        .map(|arr| sanitize_probability(arr.value(row)))
+                        .or_else(|| {
+                            let schedule = DailySchedule::from_array(schedules?, row)?;
+                            Some(scheduled_probability(&schedule, current_minutes, sigma_sq))
+                        })
+                        .unwrap_or(default_prob);
+                    let prob = match &self.plugin {
+                        Some(plugin) => plugin
+                            .score_order_probability(current_minutes, prob)
+                            .map(sanitize_probability)
+                            .map_err(|e| exec_datafusion_err!("{e}"))?,
+                        None => prob,
+                    };
+                    let prob = sanitize_probability(prob * self.demand_multiplier);
                     if rng.random_bool(prob) {
-                        let count: usize = rng.random_range(1..6);
-                        let random_vec: Vec<usize> = (0..count)
-                            .map(|_| rng.random_range(0..self.menu_items.num_rows()))
-                            .collect();
+                        let person = demographics.and_then(|(ages, households, incomes)| {
+                            Demographics::from_arrays(ages, households, incomes, row)
+                        });
+                        let count = basket_sizes
+                            .filter(|arr| arr.is_valid(row))
+                            .map(|arr| arr.value(row).clamp(1, MAX_BASKET_SIZE as i64) as usize)
+                            .unwrap_or_else(|| {
+                                let extra = person.map_or(0, |person| person.extra_items());
+                                (rng.random_range(1..6) + extra).min(MAX_BASKET_SIZE)
+                            });
+                        let weights = person
+                            .and_then(|person| {
+                                self.demographic_weights
+                                    .get(&(person.age_bracket, person.income_band))
+                            })
+                            .or(self.weights.as_ref());
+                        let preferences =
+                            preferences.and_then(|arr| FoodPreferences::from_array(arr, row));
+                        let chosen = match &self.plugin {
+                            Some(plugin) => plugin
+                                .choose_menu_items(self.menu_items.num_rows(), count)
+                                .map_err(|e| exec_datafusion_err!("{e}"))?,
+                            None => None,
+                        };
+                        let random_vec: Vec<usize> = chosen.unwrap_or_else(|| {
+                            self.draw_items(count, weights, preferences.as_ref(), &mut rng)
+                        });
+                        // customers whose diet rules out the menu do not order
+                        if random_vec.is_empty() {
+                            lb.append_null();
+                            continue;
+                        }
                         for idx in random_vec {
                             lb.values().values().append_value(brand_ids.value(idx))?;
                             lb.values().values().append_value(item_ids.value(idx))?;
@@ -153,6 +447,64 @@ impl ScalarUDFImpl for CreateOrder {
     }
 }
 
+impl CreateOrder {
+    /// Draw `count` menu items with `weights`, skewed by the customer's `preferences`.
+    ///
+    /// Drawn items are kept with a probability proportional to their preference factor,
+    /// see [`FoodPreferences::item_factor`], so fewer items, or none at all, are drawn
+    /// if the diet of the customer rules out most of the menu.
+    fn draw_items(
+        &self,
+        count: usize,
+        weights: Option<&WeightedIndex<f64>>,
+        preferences: Option<&FoodPreferences>,
+        rng: &mut StdRng,
+    ) -> Vec<usize> {
+        let num_items = self.menu_items.num_rows();
+        let draw = |rng: &mut StdRng| match weights {
+            Some(weights) => weights.sample(rng),
+            None => rng.random_range(0..num_items),
+        };
+        let Some(preferences) = preferences else {
+            return (0..count).map(|_| draw(rng)).collect();
+        };
+        let mut items = Vec::with_capacity(count);
+        for _ in 0..count * MAX_DRAWS_PER_ITEM {
+            if items.len() == count {
+                break;
+            }
+            let idx = draw(rng);
+            let factor = match self.item_profiles.get(idx) {
+                Some((cuisine, diet)) => preferences.item_factor(*cuisine, diet),
+                None => preferences.item_factor(None, &MenuItemDiet::default()),
+            };
+            if rng.random_bool((factor / FoodPreferences::MAX_ITEM_FACTOR).clamp(0.0, 1.0)) {
+                items.push(idx);
+            }
+        }
+        items
+    }
+}
+
+/// Clamp a probability to `[0, 1]`, treating NaN as "never".
+fn sanitize_probability(p: f64) -> f64 {
+    if p.is_nan() { 0.0 } else { p.clamp(0.0, 1.0) }
+}
+
+/// Probability of a person ordering at `hour` of the day, peaking around their meals.
+///
+/// People do not order while asleep. The peaks are as high as the ones of the time of
+/// day based probability, so schedules shift demand over the day rather than scaling it.
+fn scheduled_probability(schedule: &DailySchedule, hour: f64, sigma_sq: f64) -> f64 {
+    if !schedule.is_awake(hour) {
+        return 0.0;
+    }
+    let meal = |at: f64| bell(hours_between(at, hour), 0.0, sigma_sq);
+    0.01 * (BREAKFAST_WEIGHT * meal(schedule.breakfast)
+        + meal(schedule.lunch)
+        + meal(schedule.dinner))
+}
+
 fn bell(x: f64, mu: f64, sigma_sq: f64) -> f64 {
     use std::f64::consts::{E, PI};
 
@@ -162,55 +514,284 @@ fn bell(x: f64, mu: f64, sigma_sq: f64) -> f64 {
 
 #[cfg(test)]
 mod tests {
+    use arrow::array::ListArray;
     use arrow_schema::Schema;
     use datafusion::{
         logical_expr::ScalarUDF,
-        prelude::{SessionContext, col, lit},
+        prelude::{Expr, SessionContext, cast, col, lit},
     };
 
     use super::*;
 
-    #[tokio::test]
-    async fn test_create_order() -> Result<(), Box<dyn std::error::Error>> {
+    fn population() -> Result<RecordBatch, Box<dyn std::error::Error>> {
         let mut builder = crate::PopulationData::builder();
         builder.add_site(10, 51.518898098201326, -0.13381370382489707)?;
-        let population_data = builder.finish()?;
+        Ok(builder.finish()?)
+    }
 
+    /// Menu item choices with random brand and item ids.
+    fn menu_items(num_items: usize) -> Result<RecordBatch, Box<dyn std::error::Error>> {
         let mut brand_ids = FixedSizeBinaryBuilder::new(16);
-        brand_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
-        brand_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
-        let brand_ids = Arc::new(brand_ids.finish());
-
         let mut item_ids = FixedSizeBinaryBuilder::new(16);
-        item_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
-        item_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
-        let item_ids = Arc::new(item_ids.finish());
-
+        for _ in 0..num_items {
+            brand_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
+            item_ids.append_value(uuid::Uuid::new_v4().as_bytes())?;
+        }
         let schema = Arc::new(Schema::new(vec![
             Field::new("brand_id", DataType::FixedSizeBinary(16), false),
             Field::new("item_id", DataType::FixedSizeBinary(16), false),
         ]));
-        let batch = RecordBatch::try_new(schema.clone(), vec![brand_ids, item_ids])?;
-        let func = ScalarUDF::new_from_impl(CreateOrder::new(batch));
-
-        let ctx = SessionContext::new();
-
-        let df = ctx.read_batch(population_data)?;
-        let df = df.select(vec![
-            col("id"),
-            func.call(vec![
-                lit(ScalarValue::TimestampMillisecond(
-                    Some(1761675872000),
-                    Some("UTC".into()),
-                )),
-                col("state"),
-            ])
-            .alias("order"),
-        ])?;
-
-        let batches = df.collect().await?;
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![Arc::new(brand_ids.finish()), Arc::new(item_ids.finish())],
+        )?)
+    }
 
+    /// Call `func` for every person in the population and collect the orders.
+    async fn create_orders(
+        func: CreateOrder,
+        extra_args: Vec<Expr>,
+    ) -> Result<ListArray, Box<dyn std::error::Error>> {
+        let func = ScalarUDF::new_from_impl(func);
+        let mut args = vec![
+            lit(ScalarValue::TimestampMillisecond(
+                Some(1761675872000),
+                Some("UTC".into()),
+            )),
+            col("state"),
+        ];
+        args.extend(extra_args);
+
+        let batches = SessionContext::new()
+            .read_batch(population()?)?
+            .select(vec![func.call(args).alias("order")])?
+            .collect()
+            .await?;
         assert!(!batches.is_empty());
+        Ok(batches[0].column(0).as_list::<i32>().clone())
+    }
+
+    #[tokio::test]
+    async fn test_create_order() -> Result<(), Box<dyn std::error::Error>> {
+        let orders = create_orders(CreateOrder::new(menu_items(2)?), vec![]).await?;
+        assert_eq!(orders.len(), population()?.num_rows());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_hooks() -> Result<(), Box<dyn std::error::Error>> {
+        let func = CreateOrder::new(menu_items(1)?);
+        let orders = create_orders(func, vec![lit(1.0_f64), lit(3_i64)]).await?;
+        assert_eq!(orders.null_count(), 0);
+        assert!(orders.iter().flatten().all(|items| items.len() == 3));
+
+        // basket sizes are bounded
+        let func = CreateOrder::new(menu_items(1)?);
+        let orders = create_orders(func, vec![lit(1.0_f64), lit(1_000_000_i64)]).await?;
+        assert!(
+            orders
+                .iter()
+                .flatten()
+                .all(|items| items.len() == MAX_BASKET_SIZE)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_order_demand_multiplier() -> Result<(), Box<dyn std::error::Error>> {
+        let func = CreateOrder::new(menu_items(1)?).with_demand_multiplier(2.0);
+        let orders = create_orders(func, vec![lit(0.5_f64), lit(1_i64)]).await?;
+        assert_eq!(orders.null_count(), 0);
+
+        let func = CreateOrder::new(menu_items(1)?).with_demand_multiplier(0.0);
+        let orders = create_orders(func, vec![lit(1.0_f64), lit(1_i64)]).await?;
+        assert_eq!(orders.null_count(), orders.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_schedule() -> Result<(), Box<dyn std::error::Error>> {
+        let orders = create_orders(CreateOrder::new(menu_items(2)?), vec![col("schedule")]).await?;
+        assert_eq!(orders.len(), population()?.num_rows());
+
+        let func = CreateOrder::new(menu_items(1)?);
+        let orders = create_orders(func, vec![col("schedule"), lit(1.0_f64), lit(2_i64)]).await?;
+        assert_eq!(orders.null_count(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_demographics() -> Result<(), Box<dyn std::error::Error>> {
+        let choices = menu_items(2)?;
+        let cheap_item = choices.column(1).as_fixed_size_binary().value(0).to_vec();
+        let weights = AgeBracket::ALL
+            .into_iter()
+            .flat_map(|age| IncomeBand::ALL.map(|income| ((age, income), vec![1.0, 0.0])))
+            .collect();
+        let func = CreateOrder::new(choices).with_demographic_weights(weights);
+        let demographics = vec![
+            col("schedule"),
+            cast(col("age_bracket"), DataType::Utf8View),
+            col("household_size"),
+            cast(col("income_band"), DataType::Utf8View),
+        ];
+        let orders = create_orders(
+            func,
+            demographics
+                .into_iter()
+                .chain([lit(1.0_f64), lit(1_i64)])
+                .collect(),
+        )
+        .await?;
+        assert_eq!(orders.null_count(), 0);
+
+        // larger households order more items, all of them of the weighted choice
+        for items in orders.iter().flatten() {
+            assert!((1..=4).contains(&items.len()));
+            let items = items.as_fixed_size_list();
+            for item in items.iter().flatten() {
+                assert_eq!(item.as_fixed_size_binary().value(1), cheap_item.as_slice());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_preferences() -> Result<(), Box<dyn std::error::Error>> {
+        let choices = menu_items(2)?;
+        let suitable_item = choices.column(1).as_fixed_size_binary().value(1).to_vec();
+        let meat = MenuItemDiet {
+            vegetarian: false,
+            vegan: false,
+            halal: false,
+        };
+        let func = ScalarUDF::new_from_impl(
+            CreateOrder::new(choices)
+                .with_item_profiles(vec![(None, meat), (None, MenuItemDiet::default())]),
+        );
+        let population = population()?;
+        let args = vec![
+            lit(ScalarValue::TimestampMillisecond(
+                Some(1761675872000),
+                Some("UTC".into()),
+            )),
+            col("state"),
+            col("schedule"),
+            cast(col("age_bracket"), DataType::Utf8View),
+            col("household_size"),
+            cast(col("income_band"), DataType::Utf8View),
+            col("preferences"),
+            lit(1.0_f64),
+            lit(3_i64),
+        ];
+        let batches = SessionContext::new()
+            .read_batch(population.clone())?
+            .select(vec![func.call(args).alias("order")])?
+            .collect()
+            .await?;
+        let orders = batches[0].column(0).as_list::<i32>();
+        let preferences = population
+            .column_by_name("preferences")
+            .unwrap()
+            .as_struct();
+
+        // customers with a diet only order the items suiting it, all others order
+        for (row, items) in orders.iter().enumerate() {
+            match FoodPreferences::from_array(preferences, row) {
+                Some(preferences) if preferences.vegetarian || preferences.halal => {
+                    let items = items.unwrap();
+                    let items = items.as_fixed_size_list();
+                    for item in items.iter().flatten() {
+                        assert_eq!(
+                            item.as_fixed_size_binary().value(1),
+                            suitable_item.as_slice()
+                        );
+                    }
+                }
+                _ => assert!(items.is_some()),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scheduled_probability() {
+        let schedule = DailySchedule {
+            wake: 9.0,
+            work_start: None,
+            work_end: None,
+            breakfast: 9.5,
+            lunch: 14.0,
+            dinner: 21.0,
+            sleep: 2.0,
+        };
+        let probability = |hour| scheduled_probability(&schedule, hour, 0.4);
+        assert_eq!(probability(5.0), 0.0);
+        assert!(probability(14.0) > probability(12.0));
+        assert!(probability(21.0) > probability(18.0));
+        assert!(probability(9.5) < probability(14.0));
+    }
+
+    #[derive(Debug)]
+    struct FirstItemPlugin;
+
+    impl BehaviorPlugin for FirstItemPlugin {
+        fn score_order_probability(&self, _: f64, _: f64) -> crate::Result<f64> {
+            Ok(1.0)
+        }
+
+        fn choose_menu_items(
+            &self,
+            _: usize,
+            basket_size: usize,
+        ) -> crate::Result<Option<Vec<usize>>> {
+            Ok(Some(vec![0; basket_size]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_plugin() -> Result<(), Box<dyn std::error::Error>> {
+        let menu_items = menu_items(2)?;
+        let first_item = menu_items
+            .column(1)
+            .as_fixed_size_binary()
+            .value(0)
+            .to_vec();
+        let func = CreateOrder::new(menu_items).with_plugin(Some(Arc::new(FirstItemPlugin)));
+        let orders = create_orders(func, vec![]).await?;
+
+        assert_eq!(orders.null_count(), 0);
+        assert!(orders.iter().flatten().all(|items| {
+            items
+                .as_fixed_size_list()
+                .iter()
+                .flatten()
+                .all(|item| item.as_fixed_size_binary().value(1) == first_item.as_slice())
+        }));
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct NanPlugin;
+
+    impl BehaviorPlugin for NanPlugin {
+        fn score_order_probability(&self, _: f64, _: f64) -> crate::Result<f64> {
+            Ok(f64::NAN)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_order_with_nan_probability() -> Result<(), Box<dyn std::error::Error>> {
+        let func = CreateOrder::new(menu_items(2)?).with_plugin(Some(Arc::new(NanPlugin)));
+        let orders = create_orders(func, vec![]).await?;
+        assert_eq!(orders.null_count(), orders.len());
 
         Ok(())
     }
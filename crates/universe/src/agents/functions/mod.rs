@@ -1,16 +1,38 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use arrow::array::RecordBatch;
 use datafusion::logical_expr::ScalarUDF;
+use rand::rngs::StdRng;
+
+use crate::{AgeBracket, BehaviorPlugin, Cuisine, IncomeBand, MenuItemDiet};
 
 pub use self::create_order::fixed::OrderSpec;
 
 mod create_order;
 
 pub fn create_order(choices: RecordBatch) -> Arc<ScalarUDF> {
-    Arc::new(ScalarUDF::new_from_impl(create_order::CreateOrder::new(
-        choices,
-    )))
+    create_order_with_plugin(choices, None, HashMap::new(), Vec::new(), None, None, 1.0)
+}
+
+pub fn create_order_with_plugin(
+    choices: RecordBatch,
+    weights: Option<Vec<f64>>,
+    demographic_weights: HashMap<(AgeBracket, IncomeBand), Vec<f64>>,
+    item_profiles: Vec<(Option<Cuisine>, MenuItemDiet)>,
+    plugin: Option<Arc<dyn BehaviorPlugin>>,
+    rng: Option<Arc<Mutex<StdRng>>>,
+    demand_multiplier: f64,
+) -> Arc<ScalarUDF> {
+    Arc::new(ScalarUDF::new_from_impl(
+        create_order::CreateOrder::new(choices)
+            .with_weights(weights)
+            .with_demographic_weights(demographic_weights)
+            .with_item_profiles(item_profiles)
+            .with_plugin(plugin)
+            .with_rng(rng)
+            .with_demand_multiplier(demand_multiplier),
+    ))
 }
 
 pub fn create_order_fixed(choices: RecordBatch, spec: OrderSpec) -> Arc<ScalarUDF> {
@@ -1,12 +1,12 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use itertools::Itertools as _;
 use tracing::{Level, instrument};
 
 use super::OrderLine;
 use crate::EventPayload;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::idents::*;
 use crate::models::{KitchenStation, Station};
 use crate::state::{OrderLineStatus, State};
@@ -55,28 +55,36 @@ impl StationRunner {
 }
 
 #[derive(Clone)]
-enum OrderLineProcessingStatus {
-    // Current instruction index and start time
-    Processing(usize, DateTime<Utc>),
+struct OrderProgress {
+    // The order line item being processed
+    order_line: OrderLine,
 
-    // Blocked at instruction index
-    Blocked(usize),
+    // Index of the instruction currently being performed
+    instruction_idx: usize,
+
+    // Time the current instruction was started
+    started_at: DateTime<Utc>,
 }
 
+/// An order line waiting for a station to perform its next instruction.
 #[derive(Clone)]
-struct OrderProgress {
-    // The order line item being processed
+struct WaitingLine {
     order_line: OrderLine,
-
-    // The processing status of the recipe
-    status: OrderLineProcessingStatus,
+    instruction_idx: usize,
 }
 
+#[derive(Clone)]
 pub struct KitchenRunner {
     id: KitchenId,
     stations: Vec<StationRunner>,
-    queue: VecDeque<OrderLine>,
-    in_progress: HashMap<OrderLineId, OrderProgress>,
+    /// Order lines assigned to the kitchen which have not been queued for a station yet.
+    incoming: VecDeque<OrderLine>,
+    /// Order lines waiting for a station, queued by the type of station they require.
+    ///
+    /// Lines only wait for stations of the type their next instruction requires, so
+    /// a busy oven does not hold up lines that can be processed on a free stove.
+    queues: BTreeMap<KitchenStation, VecDeque<WaitingLine>>,
+    in_progress: BTreeMap<OrderLineId, OrderProgress>,
     completed: Vec<(OrderId, OrderLineId)>,
     accepted_brands: HashSet<BrandId>,
 }
@@ -95,106 +103,134 @@ impl KitchenRunner {
         )
     )]
     pub(crate) fn step(&mut self, ctx: &State) -> Result<Vec<EventPayload>> {
-        let mut events = Vec::new();
-
-        // Try to start new recipes if possible
-        while self.start_order_line(ctx)? {}
+        while let Some(order_line) = self.incoming.pop_front() {
+            self.queue_instruction(ctx, order_line, 0)?;
+        }
 
-        // Process in-progress recipes
-        let mut completed_recipe_ids = Vec::new();
-        let mut to_update = Vec::new();
+        // Start waiting instructions on stations idle since the start of the step
+        let mut events = self.start_waiting(ctx.current_time());
 
+        // Advance lines whose current instruction completes within the step
+        let mut finished = Vec::new();
         for (order_line_id, progress) in self.in_progress.iter() {
             let menu_item = ctx.objects().menu_item(&progress.order_line.item.1)?;
-            match &progress.status {
-                OrderLineProcessingStatus::Processing(instruction_idx, stated_time) => {
-                    let expected_duration =
-                        menu_item.instructions[*instruction_idx].expected_duration;
-
-                    // Check if the recipe will be completed within the current time step
-                    if (ctx.next_time() - stated_time).num_seconds() < expected_duration as i64 {
-                        continue;
-                    }
-
-                    // We finished to current step, so release the current asset
-                    let curr = &menu_item.instructions[*instruction_idx];
-                    release_station(&mut self.stations, &curr.required_station, order_line_id);
-
-                    // Move to next instruction
-                    let next_idx = instruction_idx + 1;
-                    if next_idx >= menu_item.instructions.len() {
-                        // Recipe is complete
-                        completed_recipe_ids.push(*order_line_id);
-                        continue;
-                    }
-
-                    // Move the order to the next station, or block if not available
-                    let next_step = &menu_item.instructions[next_idx];
-                    if let Some(idx) = take_station(&self.stations, &next_step.required_station) {
-                        self.stations[idx].status = StationStatus::Busy(*order_line_id);
-                        to_update.push((
-                            *order_line_id,
-                            OrderLineProcessingStatus::Processing(next_idx, ctx.next_time()),
-                        ));
-                    } else {
-                        to_update
-                            .push((*order_line_id, OrderLineProcessingStatus::Blocked(next_idx)));
-                    }
-                }
-                OrderLineProcessingStatus::Blocked(instruction_idx) => {
-                    // Check if we can now acquire the needed asset
-                    let step = &menu_item.instructions[*instruction_idx];
-                    if let Some(asset_idx) = take_station(&self.stations, &step.required_station) {
-                        // Mark asset as in use
-                        self.stations[asset_idx].status = StationStatus::Busy(*order_line_id);
-                        to_update.push((
-                            *order_line_id,
-                            OrderLineProcessingStatus::Processing(
-                                *instruction_idx,
-                                ctx.next_time(),
-                            ),
-                        ));
-                    }
-                }
+            let expected_duration =
+                menu_item.instructions[progress.instruction_idx].expected_duration;
+            if (ctx.next_time() - progress.started_at).num_seconds() >= expected_duration as i64 {
+                finished.push(*order_line_id);
             }
         }
 
-        // Apply updates
-        for (recipe_id, status) in to_update {
-            if let Some(progress) = self.in_progress.get_mut(&recipe_id) {
-                match status {
-                    OrderLineProcessingStatus::Processing(_, _) => {
-                        events.push(EventPayload::order_line_updated(
-                            recipe_id,
-                            OrderLineStatus::Processing,
-                            Some(self.id),
-                            None,
-                        ));
-                    }
-                    OrderLineProcessingStatus::Blocked(_) => {
-                        events.push(EventPayload::order_line_updated(
-                            recipe_id,
-                            OrderLineStatus::Waiting,
-                            Some(self.id),
-                            None,
-                        ));
-                    }
-                }
+        let mut queued = Vec::new();
+        for order_line_id in finished {
+            let Some(progress) = self.in_progress.remove(&order_line_id) else {
+                continue;
+            };
+            release_station(&mut self.stations, &order_line_id);
 
-                progress.status = status;
-            }
-        }
-
-        // Move completed recipes
-        for recipe_id in completed_recipe_ids {
-            if let Some(progress) = self.in_progress.remove(&recipe_id) {
+            let next_idx = progress.instruction_idx + 1;
+            let menu_item = ctx.objects().menu_item(&progress.order_line.item.1)?;
+            if next_idx >= menu_item.instructions.len() {
+                // Recipe is complete
                 self.completed
-                    .push((progress.order_line.order_id, progress.order_line.id));
+                    .push((progress.order_line.order_id, order_line_id));
+            } else {
+                queued.push(order_line_id);
+                self.queue_instruction(ctx, progress.order_line, next_idx)?;
             }
         }
 
+        // Move lines to the stations freed up during the step
+        events.extend(self.start_waiting(ctx.next_time()));
+
+        // Lines which could not move on to a station of the required type are blocked
+        events.extend(
+            queued
+                .into_iter()
+                .filter(|id| !self.in_progress.contains_key(id))
+                .map(|id| {
+                    EventPayload::order_line_updated(
+                        id,
+                        OrderLineStatus::Waiting,
+                        Some(self.id),
+                        None,
+                    )
+                }),
+        );
+
         Ok(events)
     }
+
+    /// Earliest time an instruction in progress completes, `None` if the kitchen is idle.
+    ///
+    /// Lines waiting for a station start once a station of their type completes an
+    /// instruction, lines not queued yet are due at the current time.
+    pub(crate) fn next_event(&self, ctx: &State) -> Result<Option<DateTime<Utc>>> {
+        if !self.incoming.is_empty() || !self.completed.is_empty() {
+            return Ok(Some(ctx.current_time()));
+        }
+        let mut next: Option<DateTime<Utc>> = None;
+        for progress in self.in_progress.values() {
+            let menu_item = ctx.objects().menu_item(&progress.order_line.item.1)?;
+            let expected_duration =
+                menu_item.instructions[progress.instruction_idx].expected_duration;
+            let done = progress.started_at + TimeDelta::seconds(expected_duration as i64);
+            next = Some(next.map_or(done, |next| next.min(done)));
+        }
+        Ok(next)
+    }
+
+    /// Queue an order line for a station of the type required by the given instruction.
+    fn queue_instruction(
+        &mut self,
+        ctx: &State,
+        order_line: OrderLine,
+        instruction_idx: usize,
+    ) -> Result<()> {
+        let menu_item = ctx.objects().menu_item(&order_line.item.1)?;
+        let required = menu_item.instructions[instruction_idx].required_station;
+        let station_type = KitchenStation::try_from(required)
+            .map_err(|_| Error::invalid_data(format!("unknown kitchen station type {required}")))?;
+        enqueue(
+            self.queues.entry(station_type).or_default(),
+            WaitingLine {
+                order_line,
+                instruction_idx,
+            },
+        );
+        Ok(())
+    }
+
+    /// Start waiting lines on idle stations of the type they require.
+    ///
+    /// Lines are started by priority tier, and in the order they were queued within
+    /// a tier, for each station type.
+    fn start_waiting(&mut self, started_at: DateTime<Utc>) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        for (station_type, queue) in self.queues.iter_mut() {
+            while !queue.is_empty()
+                && let Some(idx) = take_station(&self.stations, station_type)
+                && let Some(waiting) = queue.pop_front()
+            {
+                self.stations[idx].status = StationStatus::Busy(waiting.order_line.id);
+                events.push(EventPayload::order_line_updated(
+                    waiting.order_line.id,
+                    OrderLineStatus::Processing,
+                    Some(self.id),
+                    None,
+                ));
+                self.in_progress.insert(
+                    waiting.order_line.id,
+                    OrderProgress {
+                        order_line: waiting.order_line,
+                        instruction_idx: waiting.instruction_idx,
+                        started_at,
+                    },
+                );
+            }
+        }
+        events
+    }
 }
 
 impl KitchenRunner {
@@ -211,54 +247,50 @@ impl KitchenRunner {
         Ok(KitchenRunner {
             id,
             stations,
-            queue: VecDeque::new(),
-            in_progress: HashMap::new(),
+            incoming: VecDeque::new(),
+            queues: BTreeMap::new(),
+            in_progress: BTreeMap::new(),
             completed: Vec::new(),
             accepted_brands: brands.into_iter().collect(),
         })
     }
 
+    /// Reload the stations of the kitchen from the simulation state.
+    ///
+    /// Stations keep their current status, so work in progress is not interrupted
+    /// when the properties of a station change.
+    pub(crate) fn refresh_stations(&mut self, state: &State) -> Result<()> {
+        let mut current: HashMap<_, _> = self
+            .stations
+            .drain(..)
+            .map(|station| (station.id, station.status))
+            .collect();
+        self.stations = state
+            .objects()
+            .kitchen_stations(&self.id)?
+            .map_ok(|(station_id, station)| {
+                let mut runner = StationRunner::new(station_id, station);
+                if let Some(status) = current.remove(&station_id) {
+                    runner.status = status;
+                }
+                runner
+            })
+            .try_collect()?;
+        Ok(())
+    }
+
     pub fn accepted_brands(&self) -> &HashSet<BrandId> {
         &self.accepted_brands
     }
 
     pub fn queue_order_line(&mut self, item: OrderLine) {
-        self.queue.push_back(item);
-    }
-
-    fn start_order_line(&mut self, ctx: &State) -> Result<bool> {
-        if let Some(order_line) = self.queue.pop_front() {
-            let menu_item = ctx.objects().menu_item(&order_line.item.1)?;
-            // Check if we can start the first step
-            let step = &menu_item.instructions[0];
-            if let Some(asset_idx) = take_station(&self.stations, &step.required_station) {
-                // Mark asset as in use
-                self.stations[asset_idx].status = StationStatus::Busy(order_line.id);
-
-                // Add recipe to in-progress with first instruction
-                self.in_progress.insert(
-                    order_line.id,
-                    OrderProgress {
-                        order_line,
-                        status: OrderLineProcessingStatus::Processing(0, ctx.current_time()),
-                    },
-                );
-
-                Ok(true)
-            } else {
-                // Can't start the recipe yet, put it back in the queue
-                self.queue.push_front(order_line);
-                Ok(false)
-            }
-        } else {
-            Ok(false)
-        }
+        self.incoming.push_back(item);
     }
 
     /// Get statistics about the kitchen's current state.
     pub fn stats(&self) -> KitchenStats {
         KitchenStats {
-            queued: self.queue.len(),
+            queued: self.incoming.len() + self.queues.values().map(VecDeque::len).sum::<usize>(),
             in_progress: self.in_progress.len(),
             completed: self.completed.len(),
             idle_stations: self
@@ -275,21 +307,133 @@ impl KitchenRunner {
     }
 }
 
-fn take_station(assets: &[StationRunner], asset_type: &i32) -> Option<usize> {
-    assets.iter().position(|asset| {
-        matches!(asset.status, StationStatus::Available)
-            && &(asset.station_type as i32) == asset_type
+/// Queue `waiting` behind all lines of the same or a higher priority tier.
+fn enqueue(queue: &mut VecDeque<WaitingLine>, waiting: WaitingLine) {
+    let priority = waiting.order_line.priority;
+    match queue
+        .iter()
+        .position(|queued| queued.order_line.priority < priority)
+    {
+        Some(idx) => queue.insert(idx, waiting),
+        None => queue.push_back(waiting),
+    }
+}
+
+fn take_station(stations: &[StationRunner], station_type: &KitchenStation) -> Option<usize> {
+    stations.iter().position(|station| {
+        matches!(station.status, StationStatus::Available) && &station.station_type == station_type
     })
 }
 
-fn release_station(assets: &mut Vec<StationRunner>, asset_type: &i32, recipe_id: &OrderLineId) {
-    for asset in assets {
-        if &(asset.station_type as i32) == asset_type
-            && let StationStatus::Busy(id) = &asset.status
-            && id == recipe_id
+fn release_station(stations: &mut [StationRunner], order_line_id: &OrderLineId) {
+    for station in stations {
+        if let StationStatus::Busy(id) = &station.status
+            && id == order_line_id
         {
-            asset.status = StationStatus::Available;
+            station.status = StationStatus::Available;
             break;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::PriorityTier;
+
+    use super::*;
+
+    fn waiting_line() -> WaitingLine {
+        waiting_line_with(PriorityTier::Normal)
+    }
+
+    fn waiting_line_with(priority: PriorityTier) -> WaitingLine {
+        WaitingLine {
+            order_line: OrderLine {
+                id: OrderLineId::new(),
+                order_id: OrderId::new(),
+                item: (
+                    BrandId::from_name("brand"),
+                    MenuItemId::from_names("brand", "item"),
+                ),
+                priority,
+            },
+            instruction_idx: 0,
+        }
+    }
+
+    fn station(name: &str, station_type: KitchenStation) -> StationRunner {
+        StationRunner {
+            id: StationId::from_names("london", "kitchen", name),
+            station_type,
+            status: StationStatus::Available,
+        }
+    }
+
+    #[test]
+    fn test_station_type_queues() {
+        let mut kitchen = KitchenRunner {
+            id: KitchenId::from_names("london", "kitchen"),
+            stations: vec![
+                station("oven", KitchenStation::Oven),
+                station("prep-1", KitchenStation::Workstation),
+                station("prep-2", KitchenStation::Workstation),
+            ],
+            incoming: VecDeque::new(),
+            queues: BTreeMap::new(),
+            in_progress: BTreeMap::new(),
+            completed: Vec::new(),
+            accepted_brands: HashSet::new(),
+        };
+        let (first, second, prep) = (waiting_line(), waiting_line(), waiting_line());
+        let (first_id, second_id, prep_id) = (
+            first.order_line.id,
+            second.order_line.id,
+            prep.order_line.id,
+        );
+        kitchen
+            .queues
+            .entry(KitchenStation::Oven)
+            .or_default()
+            .extend([first, second]);
+        kitchen
+            .queues
+            .entry(KitchenStation::Workstation)
+            .or_default()
+            .push_back(prep);
+
+        // the only oven constrains oven steps, but does not block other stations
+        let events = kitchen.start_waiting(Utc::now());
+        assert_eq!(events.len(), 2);
+        assert!(kitchen.in_progress.contains_key(&first_id));
+        assert!(kitchen.in_progress.contains_key(&prep_id));
+        assert!(!kitchen.in_progress.contains_key(&second_id));
+        let stats = kitchen.stats();
+        assert_eq!((stats.queued, stats.idle_stations), (1, 1));
+
+        release_station(&mut kitchen.stations, &first_id);
+        kitchen.in_progress.remove(&first_id);
+        kitchen.start_waiting(Utc::now());
+        assert!(kitchen.in_progress.contains_key(&second_id));
+        assert_eq!(kitchen.stats().queued, 0);
+    }
+
+    #[test]
+    fn test_enqueue_by_priority() {
+        let mut queue = VecDeque::new();
+        let lines = [
+            waiting_line_with(PriorityTier::Normal),
+            waiting_line_with(PriorityTier::Vip),
+            waiting_line_with(PriorityTier::Normal),
+            waiting_line_with(PriorityTier::Priority),
+            waiting_line_with(PriorityTier::Vip),
+        ];
+        let ids: Vec<_> = lines.iter().map(|line| line.order_line.id).collect();
+        for line in lines {
+            enqueue(&mut queue, line);
+        }
+
+        // higher tiers go first, lines of the same tier keep their order
+        let queued: Vec<_> = queue.iter().map(|line| line.order_line.id).collect();
+        assert_eq!(queued, [ids[1], ids[4], ids[3], ids[0], ids[2]]);
+    }
+}
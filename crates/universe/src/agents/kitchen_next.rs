@@ -154,22 +154,17 @@ pub(crate) struct KitchenHandler {
 
 impl KitchenHandler {
     pub(crate) async fn try_new(ctx: &SimulationContext) -> Result<Self> {
-        let objects = ctx
-            .snapshots()
-            .objects()
-            .await?
-            .filter(col("label").in_list(
-                vec![
-                    lit(ObjectLabel::Site.as_ref()),
-                    lit(ObjectLabel::Kitchen.as_ref()),
-                    lit(ObjectLabel::Station.as_ref()),
-                    lit(ObjectLabel::Brand.as_ref()),
-                    lit(ObjectLabel::MenuItem.as_ref()),
-                ],
-                false,
-            ))?
-            .cache()
-            .await?;
+        let labels = col("label").in_list(
+            vec![
+                lit(ObjectLabel::Site.as_ref()),
+                lit(ObjectLabel::Kitchen.as_ref()),
+                lit(ObjectLabel::Station.as_ref()),
+                lit(ObjectLabel::Brand.as_ref()),
+                lit(ObjectLabel::MenuItem.as_ref()),
+            ],
+            false,
+        );
+        let objects = ctx.snapshots().cached("objects", Some(labels)).await?;
         let sites = extract_sites(objects.clone()).await?;
         let (brand_ids, menu_items) = extract_menu_items(objects.clone()).await?;
         let (stations, kitchens) = extract_kitchen_station(ctx, objects, brand_ids).await?;
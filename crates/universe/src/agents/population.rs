@@ -1,4 +1,5 @@
-use std::sync::{Arc, LazyLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
 
 use arrow::{
     array::{
@@ -8,7 +9,7 @@ use arrow::{
     compute::concat_batches,
 };
 use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use datafusion::{
     functions::core::expr_ext::FieldAccessor as _,
     logical_expr::ScalarUDF,
@@ -20,44 +21,297 @@ use geo_traits::to_geo::ToGeoPoint;
 use geoarrow::array::PointArray;
 use geoarrow_array::GeoArrowArrayAccessor;
 use geoarrow_schema::{Dimension, PointType};
-use h3o::{LatLng, Resolution};
+use h3o::{CellIndex, LatLng, Resolution};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng as _};
 use tracing::{Level, instrument};
 use uuid::Uuid;
 
 use crate::{
-    BrandId, EntityView as _, EventPayload, MenuItemId, ObjectLabel, OrderCreatedPayload, PersonId,
-    PersonRole, PersonStatusFlag, Result, SimulationContext, SiteId, State,
-    agents::functions::create_order,
+    BehaviorHooks, BehaviorPlugin, Brand, BrandId, Campaign, Cuisine, CuisinePreferences, Currency,
+    EntityView as _, EventPayload, ExchangeRates, GreenDeliveryConfig, MembershipConfig, MenuItem,
+    MenuItemDiet, MenuItemId, Money, ObjectData, ObjectLabel, OrderChannel, OrderCreatedPayload,
+    OrderId, PackingConfig, PersonId, PersonRole, PersonStatusFlag, PriorityConfig, PriorityTier,
+    Result, SeasonalityConfig, SimulationContext, SiteId, State, TippingModel, Weather,
+    agents::functions::create_order_with_plugin,
+    builders::POPULATION_PREFERENCES_FIELD,
     functions::uuidv7,
-    state::{Journey, Transport},
+    simulation::{Destinations, apply_campaigns},
+    state::{Journey, Transport, demographic_item_weights},
 };
 
 pub struct PopulationRunner {
     create_orders: Arc<ScalarUDF>,
+    /// Brand and menu item ids of the menu items customers can order
+    order_choices: RecordBatch,
+    brand_cuisines: HashMap<BrandId, Cuisine>,
+    /// Prices and diets of the menu items, matched against the customers' demographics
+    /// and preferences
+    item_details: HashMap<MenuItemId, MenuItemDetails>,
+    cuisine_preferences: CuisinePreferences,
+    hooks: BehaviorHooks,
+    campaigns: Vec<Campaign>,
+    tipping: TippingModel,
+    packing: PackingConfig,
+    exchange_rates: ExchangeRates,
+    /// Homes and workplaces orders are delivered to, instead of the current positions
+    destinations: Option<Destinations>,
+    /// Shares of customers in the priority tiers, all orders are normal if not set
+    priority: Option<PriorityConfig>,
+    /// Delivery memberships of customers, no delivery fees are charged if not set
+    memberships: Option<MembershipConfig>,
+    /// Green delivery chosen by customers at checkout, all orders are standard if not set
+    green: Option<GreenDeliveryConfig>,
+    plugin: Option<Arc<dyn BehaviorPlugin>>,
+    /// Random numbers of the order function in seeded runs
+    rng: Option<Arc<Mutex<StdRng>>>,
+    /// Factor applied to the probability of customers placing an order
+    demand_multiplier: f64,
+    /// Factors applied to the probability of customers ordering from each site
+    site_demand: BTreeMap<SiteId, f64>,
+    /// Variation of demand and weather over the year, demand is the same all year if not set
+    seasonality: Option<SeasonalityConfig>,
+    /// Factor applied to the probability of customers placing an order in the current season
+    seasonal_demand: f64,
 }
 
 impl PopulationRunner {
-    pub async fn try_new(ctx: &SimulationContext) -> Result<Self> {
-        let batches = ctx
-            .snapshots()
-            .objects()
-            .await?
-            .filter(col("label").eq(lit(ObjectLabel::MenuItem.as_ref())))?
-            .select([
-                col("parent_id").alias("brand_id"),
-                col("id").alias("menu_item_id"),
-            ])?
-            .collect()
-            .await?;
-        let order_choices = concat_batches(batches[0].schema_ref(), &batches)?;
-        let create_orders = create_order(order_choices);
-        Ok(PopulationRunner { create_orders })
+    pub async fn try_new(
+        ctx: &SimulationContext,
+        hooks: BehaviorHooks,
+        plugin: Option<Arc<dyn BehaviorPlugin>>,
+    ) -> Result<Self> {
+        hooks.validate(ctx, ctx.snapshots().cached("population", None).await?)?;
+
+        let objects = ctx.snapshots().cached("objects", None).await?;
+        let order_choices = menu_choices(objects.clone()).await?;
+        let create_orders = create_order_with_plugin(
+            order_choices.clone(),
+            None,
+            HashMap::new(),
+            Vec::new(),
+            plugin.clone(),
+            None,
+            1.0,
+        );
+        Ok(PopulationRunner {
+            create_orders,
+            order_choices,
+            brand_cuisines: brand_cuisines(objects.clone()).await?,
+            item_details: menu_item_details(objects).await?,
+            cuisine_preferences: CuisinePreferences::default(),
+            hooks,
+            campaigns: Vec::new(),
+            tipping: TippingModel::default(),
+            packing: PackingConfig::default(),
+            exchange_rates: ExchangeRates::default(),
+            destinations: None,
+            priority: None,
+            memberships: None,
+            green: None,
+            plugin,
+            rng: None,
+            demand_multiplier: 1.0,
+            site_demand: BTreeMap::new(),
+            seasonality: None,
+            seasonal_demand: 1.0,
+        })
+    }
+
+    /// Offer the current menu items of the simulation state to customers.
+    ///
+    /// Called when menu items are added or changed during a run.
+    pub(crate) async fn refresh_menu(
+        &mut self,
+        ctx: &SimulationContext,
+        objects: &ObjectData,
+    ) -> Result<()> {
+        let objects = ctx.ctx().read_batch(objects.objects().clone())?;
+        self.order_choices = menu_choices(objects.clone()).await?;
+        self.brand_cuisines = brand_cuisines(objects.clone()).await?;
+        self.item_details = menu_item_details(objects).await?;
+        self.update_create_orders();
+        Ok(())
+    }
+
+    fn update_create_orders(&mut self) {
+        let cuisines: Vec<_> = self
+            .order_choices
+            .column(0)
+            .as_fixed_size_binary()
+            .iter()
+            .map(|brand_id| {
+                let brand_id = BrandId::from(Uuid::from_slice(brand_id?).ok()?);
+                self.brand_cuisines.get(&brand_id).copied()
+            })
+            .collect();
+        let weights = self.cuisine_preferences.item_weights(&cuisines);
+
+        let details: Vec<_> = self
+            .order_choices
+            .column(1)
+            .as_fixed_size_binary()
+            .iter()
+            .map(|item_id| {
+                let item_id = MenuItemId::from(Uuid::from_slice(item_id?).ok()?);
+                self.item_details.get(&item_id)
+            })
+            .collect();
+
+        // prices in the base currency, items of currencies without a rate are unpriced
+        let prices: Vec<_> = details
+            .iter()
+            .map(|details| {
+                let details = details.as_ref()?;
+                let currency = self
+                    .exchange_rates
+                    .resolve(details.currency.as_deref())
+                    .ok()?;
+                Some(details.price * self.exchange_rates.rate(currency).ok()?)
+            })
+            .collect();
+        let demographic_weights = demographic_item_weights(weights.as_deref(), &cuisines, &prices);
+        let profiles = cuisines
+            .iter()
+            .zip(&details)
+            .map(|(cuisine, details)| (*cuisine, details.map(|d| d.diet).unwrap_or_default()))
+            .collect();
+
+        self.create_orders = create_order_with_plugin(
+            self.order_choices.clone(),
+            weights,
+            demographic_weights,
+            profiles,
+            self.plugin.clone(),
+            self.rng.clone(),
+            self.demand_multiplier
+                * self.memberships.as_ref().map_or(1.0, |m| m.demand_boost())
+                * self.peak_site_demand()
+                * self.seasonal_demand,
+        );
+    }
+
+    /// Discount new orders matching any of the campaigns.
+    pub(crate) fn with_campaigns(mut self, campaigns: Vec<Campaign>) -> Self {
+        self.campaigns = campaigns;
+        self
+    }
+
+    /// Sample tips of new orders from `tipping`, unless a tip hook is configured.
+    pub(crate) fn with_tipping(mut self, tipping: TippingModel) -> Self {
+        self.tipping = tipping;
+        self
+    }
+
+    /// Choose menu items according to the `preferences` of customers for cuisines.
+    pub(crate) fn with_cuisine_preferences(mut self, preferences: CuisinePreferences) -> Self {
+        self.cuisine_preferences = preferences;
+        self.update_create_orders();
+        self
+    }
+
+    /// Account for packing orders with `packing` when promising delivery times.
+    pub(crate) fn with_packing(mut self, packing: PackingConfig) -> Self {
+        self.packing = packing;
+        self
+    }
+
+    /// Convert menu prices into the currencies of the ordering sites with `rates`.
+    pub(crate) fn with_exchange_rates(mut self, rates: ExchangeRates) -> Self {
+        self.exchange_rates = rates;
+        self.update_create_orders();
+        self
+    }
+
+    /// Deliver new orders to the homes and workplaces of customers in `destinations`.
+    pub(crate) fn with_destinations(mut self, destinations: Option<Destinations>) -> Self {
+        self.destinations = destinations;
+        self
+    }
+
+    /// Place new orders in the priority tier of their customer per `priority`.
+    pub(crate) fn with_priority_tiers(mut self, priority: Option<PriorityConfig>) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Promise the orders of customers choosing green delivery later per `green`.
+    pub(crate) fn with_green_delivery(mut self, green: Option<GreenDeliveryConfig>) -> Self {
+        self.green = green;
+        self
+    }
+
+    /// Charge delivery fees to customers without a membership per `memberships`.
+    ///
+    /// Members order more often and are placed in at least the tier of the membership.
+    pub(crate) fn with_memberships(mut self, memberships: Option<MembershipConfig>) -> Self {
+        self.memberships = memberships;
+        self.update_create_orders();
+        self
+    }
+
+    /// Vary demand and the weather over the year per `seasonality`.
+    pub(crate) fn with_seasonality(mut self, seasonality: Option<SeasonalityConfig>) -> Self {
+        self.seasonality = seasonality;
+        self
+    }
+
+    /// Draw the choices of customers from random numbers seeded with `seed`.
+    pub(crate) fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.rng = seed.map(|seed| Arc::new(Mutex::new(StdRng::seed_from_u64(seed))));
+        self.update_create_orders();
+        self
+    }
+
+    /// Scale the probability of customers placing an order by `multiplier`.
+    pub(crate) fn set_demand_multiplier(&mut self, multiplier: f64) {
+        if multiplier != self.demand_multiplier {
+            self.demand_multiplier = multiplier;
+            self.update_create_orders();
+        }
+    }
+
+    /// Scale the probability of customers ordering from each site by its factor.
+    pub(crate) fn set_site_demand(&mut self, factors: BTreeMap<SiteId, f64>) {
+        if factors != self.site_demand {
+            self.site_demand = factors;
+            self.update_create_orders();
+        }
+    }
+
+    /// Scale the probability of customers placing an order to the season at `time`.
+    pub(crate) fn advance_season(&mut self, time: DateTime<Utc>) {
+        let Some(seasonality) = &self.seasonality else {
+            return;
+        };
+        let factor = seasonality.demand_factor(time, self.weather(time));
+        if factor != self.seasonal_demand {
+            self.seasonal_demand = factor;
+            self.update_create_orders();
+        }
+    }
+
+    /// Weather during the hour containing `time`, following the season if configured.
+    fn weather(&self, time: DateTime<Utc>) -> Weather {
+        match &self.seasonality {
+            Some(seasonality) => seasonality.weather(&self.tipping, time),
+            None => self.tipping.weather(time),
+        }
+    }
+
+    /// Largest demand factor of the sites, orders are drawn at its rate.
+    fn peak_site_demand(&self) -> f64 {
+        self.site_demand
+            .values()
+            .copied()
+            .reduce(f64::max)
+            .unwrap_or(1.0)
     }
 
     #[instrument(
         name = "step_population",
         level = Level::TRACE,
-        skip(self, ctx, state),
+        skip(self, ctx, state, rng),
         fields(
             caspers.site_id = site_id.to_string()
         )
@@ -67,33 +321,60 @@ impl PopulationRunner {
         ctx: &SimulationContext,
         site_id: &SiteId,
         state: &State,
+        rng: &mut impl Rng,
     ) -> Result<impl Iterator<Item = EventPayload>> {
         let site = state.objects().site(site_id)?;
         let props = site.properties()?;
         let lat_lng = LatLng::new(props.latitude, props.longitude)?;
         let ts = state.current_time().timestamp_millis();
 
+        // customers are served by the site within the cell it is located in
+        let zone = lat_lng.to_cell(Resolution::Six);
+
         let idle_people = state
             .population()
-            .idle_people_in_cell(ctx, lat_lng.to_cell(Resolution::Six), &PersonRole::Customer)
-            .await?
-            .collect()
+            .idle_people_in_cell(ctx, zone, &PersonRole::Customer)
             .await?;
 
-        let idle_people = ctx.ctx().read_batches(idle_people)?;
+        // the order function draws random numbers row by row, so seeded runs
+        // pass it the customers in a fixed order within a single batch
+        let idle_people = if self.rng.is_some() {
+            let idle_people = idle_people.sort(vec![col("id").sort(true, false)])?;
+            let schema = idle_people.schema().inner().clone();
+            let batches = idle_people.collect().await?;
+            ctx.ctx().read_batch(concat_batches(&schema, &batches)?)?
+        } else {
+            ctx.ctx().read_batches(idle_people.collect().await?)?
+        };
+
+        let mut order_args = vec![
+            lit(ScalarValue::TimestampMillisecond(
+                Some(ts),
+                Some("UTC".into()),
+            )),
+            col("state"),
+            col("schedule"),
+            cast(col("age_bracket"), DataType::Utf8View),
+            col("household_size"),
+            cast(col("income_band"), DataType::Utf8View),
+            cast(
+                col("preferences"),
+                POPULATION_PREFERENCES_FIELD.data_type().clone(),
+            ),
+        ];
+        let idle_people = if self.hooks.has_order_hooks() {
+            let df = BehaviorHooks::population_frame(idle_people, state.current_time())?;
+            order_args.push(self.hooks.order_probability_expr(&df)?);
+            order_args.push(self.hooks.basket_size_expr(&df)?);
+            df
+        } else {
+            idle_people
+        };
 
         let orders = idle_people
             .select(vec![
                 col("id"),
-                self.create_orders
-                    .call(vec![
-                        lit(ScalarValue::TimestampMillisecond(
-                            Some(ts),
-                            Some("UTC".into()),
-                        )),
-                        col("state"),
-                    ])
-                    .alias("order"),
+                self.create_orders.call(order_args).alias("order"),
                 col("position"),
             ])?
             .filter(col("order").is_not_null())?
@@ -118,7 +399,7 @@ impl PopulationRunner {
 
             for ((person_id, order), pos) in orders_iter {
                 if let (Some(person_id), Some(order), Some(Ok(pos))) = (person_id, order, pos) {
-                    let items = order
+                    let items: Vec<_> = order
                         .as_fixed_size_list()
                         .iter()
                         .flat_map(|it| {
@@ -131,20 +412,276 @@ impl PopulationRunner {
                             })
                         })
                         .collect();
-                    orders.push(EventPayload::OrderCreated(OrderCreatedPayload {
-                        site_id: *site_id,
-                        person_id: Uuid::from_slice(person_id).unwrap().into(),
+                    orders.push((
+                        PersonId::from(Uuid::from_slice(person_id).unwrap()),
                         items,
-                        destination: pos.to_point(),
-                    }));
+                        pos.to_point(),
+                    ));
                 }
             }
 
             orders
         });
 
-        Ok(orders)
+        // orders are drawn at the rate of members, and thinned to the rate of
+        // each customer
+        let orders: Vec<_> = match &self.memberships {
+            Some(memberships) => orders
+                .filter(|(person_id, _, _)| {
+                    let member = memberships.is_member(person_id);
+                    rng.random_bool(memberships.keep_probability(member))
+                })
+                .collect(),
+            None => orders.collect(),
+        };
+
+        // orders are drawn at the rate of the busiest site, and thinned to the rate
+        // of this one
+        let peak = self.peak_site_demand();
+        let keep = match self.site_demand.get(site_id) {
+            Some(factor) if peak > 0.0 => factor / peak,
+            _ => 1.0,
+        };
+        let orders: Vec<_> = if keep < 1.0 {
+            orders
+                .into_iter()
+                .filter(|_| rng.random_bool(keep))
+                .collect()
+        } else {
+            orders
+        };
+
+        let currency = self.exchange_rates.resolve(
+            state
+                .objects()
+                .site(site_id)?
+                .properties()?
+                .currency
+                .as_deref(),
+        )?;
+        let delivery_fee = self
+            .memberships
+            .as_ref()
+            .map(|memberships| {
+                let fee = Money::new(memberships.delivery_fee, self.exchange_rates.base());
+                self.exchange_rates.convert(fee, currency)
+            })
+            .transpose()?;
+        let mut orders = orders
+            .into_iter()
+            .map(|(person_id, items, position)| {
+                let destination = match &self.destinations {
+                    Some(destinations) => destinations
+                        .spot(position, rng)
+                        .filter(|spot| is_deliverable(state, site_id, zone, spot))
+                        .unwrap_or_else(|| {
+                            destinations.destination(&person_id, position, state.current_time())
+                        }),
+                    None => position,
+                };
+                let (total, prep_time) = order_total_and_prep_time(
+                    state.objects(),
+                    &self.exchange_rates,
+                    currency,
+                    &items,
+                )?;
+                // orders are packed once their slowest line is cooked
+                let prep_time = prep_time + self.packing.duration(items.len());
+                let channel = OrderChannel::sample(rng);
+                let (total, campaigns) = apply_campaigns(
+                    &self.campaigns,
+                    state.current_time(),
+                    channel,
+                    &items,
+                    total.amount(),
+                );
+                let mut priority = self
+                    .priority
+                    .as_ref()
+                    .map_or_else(PriorityTier::default, |p| p.tier(&person_id));
+                let mut total = total;
+                if let Some(memberships) = &self.memberships {
+                    if memberships.is_member(&person_id) {
+                        priority = priority.max(memberships.tier);
+                    } else if let Some(fee) = delivery_fee {
+                        total = ((total + fee.amount()) * 100.0).round() / 100.0;
+                    }
+                }
+                let cuisines = items
+                    .iter()
+                    .map(|(brand_id, _)| self.brand_cuisines.get(brand_id).copied())
+                    .collect();
+                let mut promised_at = promised_at(state.current_time(), prep_time);
+                if let Some(green) = self
+                    .green
+                    .as_ref()
+                    .filter(|green| green.opts_in(&person_id))
+                {
+                    promised_at = green.promised_at(promised_at);
+                }
+                Ok(OrderCreatedPayload {
+                    order_id: OrderId::from_rng(state.current_time(), rng),
+                    site_id: *site_id,
+                    person_id,
+                    items,
+                    cuisines,
+                    destination,
+                    total,
+                    currency,
+                    channel,
+                    priority,
+                    promised_at,
+                    campaigns,
+                    tip: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if self.hooks.tip_amount.is_some() {
+            let tip_inputs = orders
+                .iter()
+                .map(|o| (o.person_id, o.total, o.items.len(), o.channel))
+                .collect::<Vec<_>>();
+            let tips = self
+                .hooks
+                .tip_amounts(ctx, state.current_time(), &tip_inputs)
+                .await?;
+            for (order, tip) in orders.iter_mut().zip(tips) {
+                order.tip = tip;
+            }
+        } else {
+            let weather = self.weather(state.current_time());
+            for order in orders.iter_mut() {
+                let delivery_time = order.promised_at - state.current_time();
+                order.tip = self
+                    .tipping
+                    .sample(rng, order.total, delivery_time, weather);
+            }
+        }
+
+        Ok(orders.into_iter().map(EventPayload::OrderCreated))
+    }
+}
+
+/// Whether couriers of the site can deliver to `point` within the delivery `zone`.
+fn is_deliverable(state: &State, site_id: &SiteId, zone: CellIndex, point: &Point) -> bool {
+    let Ok(lat_lng) = LatLng::new(point.y(), point.x()) else {
+        return false;
+    };
+    lat_lng.to_cell(zone.resolution()) == zone
+        && state
+            .trip_planner(site_id)
+            .is_some_and(|planner| planner.node_near(&lat_lng).is_some())
+}
+
+/// Brand and menu item ids of all menu items customers can order.
+async fn menu_choices(objects: DataFrame) -> Result<RecordBatch> {
+    let batches = objects
+        .filter(col("label").eq(lit(ObjectLabel::MenuItem.as_ref())))?
+        .select([
+            col("parent_id").alias("brand_id"),
+            col("id").alias("menu_item_id"),
+        ])?
+        .collect()
+        .await?;
+    Ok(concat_batches(batches[0].schema_ref(), &batches)?)
+}
+
+/// Cuisines of the categorized brands.
+///
+/// Brands of snapshots taken before brand properties were stored are uncategorized.
+async fn brand_cuisines(objects: DataFrame) -> Result<HashMap<BrandId, Cuisine>> {
+    let batches = objects
+        .filter(col("label").eq(lit(ObjectLabel::Brand.as_ref())))?
+        .select_columns(&["id", "properties"])?
+        .collect()
+        .await?;
+    let mut cuisines = HashMap::new();
+    for batch in batches {
+        let ids = batch.column(0).as_fixed_size_binary();
+        let properties = batch.column(1).as_string::<i64>();
+        for (id, properties) in ids.iter().zip(properties.iter()) {
+            let (Some(id), Some(properties)) = (id, properties) else {
+                continue;
+            };
+            let brand: Brand = serde_json::from_str(properties)?;
+            if !brand.category.is_empty() {
+                cuisines.insert(BrandId::from(Uuid::from_slice(id)?), brand.cuisine()?);
+            }
+        }
+    }
+    Ok(cuisines)
+}
+
+/// Price, currency and diets of a menu item.
+struct MenuItemDetails {
+    price: f64,
+    currency: Option<String>,
+    diet: MenuItemDiet,
+}
+
+/// Prices, currencies and diets of the menu items.
+async fn menu_item_details(objects: DataFrame) -> Result<HashMap<MenuItemId, MenuItemDetails>> {
+    let batches = objects
+        .filter(col("label").eq(lit(ObjectLabel::MenuItem.as_ref())))?
+        .select_columns(&["id", "properties"])?
+        .collect()
+        .await?;
+    let mut details = HashMap::new();
+    for batch in batches {
+        let ids = batch.column(0).as_fixed_size_binary();
+        let properties = batch.column(1).as_string::<i64>();
+        for (id, properties) in ids.iter().zip(properties.iter()) {
+            let (Some(id), Some(properties)) = (id, properties) else {
+                continue;
+            };
+            let item: MenuItem = serde_json::from_str(properties)?;
+            let diet = MenuItemDiet::of(&item);
+            details.insert(
+                MenuItemId::from(Uuid::from_slice(id)?),
+                MenuItemDetails {
+                    price: item.price,
+                    currency: item.currency,
+                    diet,
+                },
+            );
+        }
+    }
+    Ok(details)
+}
+
+/// Time budgeted for delivering an order once it is ready.
+const DELIVERY_ALLOWANCE: Duration = Duration::minutes(30);
+
+/// Time by which an order placed at `ordered_at` is promised to be delivered.
+fn promised_at(ordered_at: DateTime<Utc>, prep_time: Duration) -> DateTime<Utc> {
+    ordered_at + prep_time + DELIVERY_ALLOWANCE
+}
+
+/// Compute the order total and the expected preparation time.
+///
+/// Items are prepared concurrently, so the preparation time is determined
+/// by the item that takes the longest to prepare.
+fn order_total_and_prep_time(
+    objects: &ObjectData,
+    rates: &ExchangeRates,
+    currency: Currency,
+    items: &[(BrandId, MenuItemId)],
+) -> Result<(Money, Duration)> {
+    let mut total = Money::zero(currency);
+    let mut prep_time_s = 0;
+    for (_, item_id) in items {
+        let item = objects.menu_item(item_id)?;
+        let price = Money::new(item.price, rates.resolve(item.currency.as_deref())?);
+        total = total.checked_add(rates.convert(price, currency)?)?;
+        let item_time_s: i64 = item
+            .instructions
+            .iter()
+            .map(|i| i.expected_duration as i64)
+            .sum();
+        prep_time_s = prep_time_s.max(item_time_s);
     }
+    Ok((total.round_cents(), Duration::seconds(prep_time_s)))
 }
 
 // ============================================================================
@@ -479,7 +1016,12 @@ impl PopulationHandler {
         ctx: &SimulationContext,
         create_orders: Arc<ScalarUDF>,
     ) -> Result<Self> {
-        let population = ctx.snapshots().population().await?.collect().await?;
+        let population = ctx
+            .snapshots()
+            .cached("population", None)
+            .await?
+            .collect()
+            .await?;
         Ok(PopulationHandler {
             create_orders,
             population,
@@ -997,3 +1539,58 @@ mod population_tests {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Template;
+
+    #[test]
+    fn test_order_total_and_prep_time() -> Result<()> {
+        let setup = Template::default().load()?;
+        let objects = ObjectData::try_new(setup.object_data()?)?;
+
+        let brand = &setup.brands[0];
+        let brand_id = BrandId::from_name(&brand.name);
+        let items = brand.items[..2]
+            .iter()
+            .map(|item| (brand_id, MenuItemId::from_names(&brand.name, &item.name)))
+            .collect::<Vec<_>>();
+
+        let rates = ExchangeRates::default();
+        let (total, prep_time) =
+            order_total_and_prep_time(&objects, &rates, Currency::USD, &items)?;
+        let expected_total: f64 = brand.items[..2].iter().map(|item| item.price).sum();
+        assert_eq!(total.currency(), Currency::USD);
+        assert_eq!(total.amount(), (expected_total * 100.0).round() / 100.0);
+
+        // prices are converted into the currency of the site
+        let eur = "EUR".parse()?;
+        let rates = rates.with_rate(eur, 2.0);
+        let (converted, _) = order_total_and_prep_time(&objects, &rates, eur, &items)?;
+        assert_eq!(converted.currency(), eur);
+        assert_eq!(converted.amount(), (expected_total * 50.0).round() / 100.0);
+        assert!(order_total_and_prep_time(&objects, &rates, "GBP".parse()?, &items).is_err());
+
+        // items are prepared in parallel, so the slowest item determines the prep time
+        let expected_prep_s = brand.items[..2]
+            .iter()
+            .map(|item| {
+                item.instructions
+                    .iter()
+                    .map(|i| i.expected_duration as i64)
+                    .sum::<i64>()
+            })
+            .max()
+            .unwrap();
+        assert_eq!(prep_time, Duration::seconds(expected_prep_s));
+
+        let ordered_at = Utc::now();
+        assert_eq!(
+            promised_at(ordered_at, prep_time),
+            ordered_at + Duration::seconds(expected_prep_s) + Duration::minutes(30)
+        );
+
+        Ok(())
+    }
+}
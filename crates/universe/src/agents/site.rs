@@ -1,16 +1,27 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
 use arrow::array::AsArray;
+use arrow::datatypes::UInt32Type;
+use chrono::{DateTime, Utc};
 use counter::Counter;
-use h3o::Resolution;
+use h3o::CellIndex;
 use itertools::Itertools as _;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
 use tracing::{Level, Span, field, instrument};
 use uuid::Uuid;
 
 use super::kitchen::{KitchenRunner, KitchenStats};
-use crate::simulation::EventPayload;
-use crate::state::{EntityView, OrderLineStatus, OrderStatus, PersonRole, PersonStatus, State};
-use crate::{Error, OrderUpdatedPayload, Result};
+use crate::simulation::{
+    BehaviorPlugin, BreakTracker, Bundler, CourierAcceptance, CourierActivity, CourierBreaks,
+    DarkStore, DarkStoreConfig, DeliveryRobots, DispatchPolicy, Dispatcher, EventPayload,
+    GreenDeliveryConfig, Packer, PackingConfig, PriorityTier, RobotFleet, hour_of_day, on_shift,
+};
+use crate::state::{
+    EntityView, OrderLineStatus, OrderStatus, PersonRole, PersonStatus, State, Transport,
+};
+use crate::{Error, ExchangeRates, Money, OrderUpdatedPayload, Result, RobotKind};
 use crate::{SimulationContext, idents::*};
 
 #[derive(Clone)]
@@ -18,16 +29,17 @@ pub struct OrderLine {
     pub(crate) id: OrderLineId,
     pub(crate) order_id: OrderId,
     pub(crate) item: (BrandId, MenuItemId),
+    pub(crate) priority: PriorityTier,
 }
 
 struct OrderRouter<'a> {
-    kitchens: &'a mut HashMap<KitchenId, KitchenRunner>,
+    kitchens: &'a mut BTreeMap<KitchenId, KitchenRunner>,
     brand_to_kitchens: HashMap<BrandId, Vec<KitchenId>>,
     submit_counter: Counter<BrandId>,
 }
 
 impl<'a> OrderRouter<'a> {
-    fn new(kitchens: &'a mut HashMap<KitchenId, KitchenRunner>) -> Self {
+    fn new(kitchens: &'a mut BTreeMap<KitchenId, KitchenRunner>) -> Self {
         let brand_to_kitchens = kitchens
             .iter()
             .flat_map(|(id, kitchen)| kitchen.accepted_brands().iter().map(|brand| (*brand, *id)))
@@ -62,6 +74,15 @@ impl<'a> OrderRouter<'a> {
     }
 }
 
+/// How a site prepares the lines of its orders.
+#[derive(Clone)]
+enum Fulfillment {
+    /// Lines are cooked in the kitchens of the site.
+    Kitchens(BTreeMap<KitchenId, KitchenRunner>),
+    /// Lines are picked from the inventory of a grocery dark store.
+    DarkStore(Box<DarkStore>),
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct SiteStats {
     pub queue_length: usize,
@@ -77,11 +98,12 @@ impl std::ops::Add for SiteStats {
     }
 }
 
+#[derive(Clone)]
 pub struct SiteRunner {
     id: SiteId,
 
-    /// Kitchens available at this location.
-    kitchens: HashMap<KitchenId, KitchenRunner>,
+    /// Kitchens or dark store preparing the orders of this location.
+    fulfillment: Fulfillment,
 
     // order_data: OrderData,
     /// Orders waiting to be processed at this location.
@@ -89,6 +111,36 @@ pub struct SiteRunner {
 
     /// Order lines currently being processed at this location.
     order_lines: HashMap<OrderLineId, OrderLine>,
+
+    /// Plugin deciding whether couriers accept deliveries.
+    plugin: Option<Arc<dyn BehaviorPlugin>>,
+
+    /// Model of couriers accepting delivery offers, unless decided by the plugin.
+    acceptance: CourierAcceptance,
+
+    /// Offers of ready orders to couriers.
+    dispatcher: Dispatcher,
+
+    /// Packing of orders once all their lines are cooked.
+    packer: Packer,
+
+    /// Energy and fatigue of the couriers working at this location.
+    breaks: BreakTracker,
+
+    /// Robots or drones delivering orders next to the couriers, if the site operates any.
+    robots: Option<RobotFleet>,
+
+    /// Bundles of green orders, if customers may choose green delivery.
+    bundler: Option<Bundler>,
+
+    /// Random numbers of courier responses and substitutions.
+    rng: StdRng,
+
+    /// Probability that a submitted order fails right away, steered at run time.
+    order_failure_rate: f64,
+
+    /// Share of all couriers on shift, steered at run time.
+    courier_share: f64,
 }
 
 impl SiteRunner {
@@ -126,11 +178,24 @@ impl SiteRunner {
 
         span.record("caspers.orders_created", new_orders.len());
 
-        self.receive_orders(&new_orders, state)?;
-
         // A buffer for all event data generated by this step
         let mut events = Vec::new();
 
+        let new_orders = if self.order_failure_rate > 0.0 {
+            let (failed, accepted): (Vec<_>, Vec<_>) = new_orders
+                .into_iter()
+                .partition(|_| self.rng.random_bool(self.order_failure_rate));
+            events.extend(
+                failed
+                    .into_iter()
+                    .map(|order_id| EventPayload::order_failed(order_id, None)),
+            );
+            accepted
+        } else {
+            new_orders
+        };
+        self.receive_orders(&new_orders, state)?;
+
         // Route orders to kitchens and process completed order lines
         events.extend(self.process_orders(state)?);
 
@@ -142,7 +207,15 @@ impl SiteRunner {
 }
 
 impl SiteRunner {
-    pub(crate) fn try_new(id: SiteId, state: &State) -> Result<Self> {
+    pub(crate) fn try_new(
+        id: SiteId,
+        state: &State,
+        plugin: Option<Arc<dyn BehaviorPlugin>>,
+        acceptance: CourierAcceptance,
+        dispatch: DispatchPolicy,
+        packing: PackingConfig,
+        breaks: CourierBreaks,
+    ) -> Result<Self> {
         let kitchens = state
             .objects()
             .kitchens(&id)?
@@ -150,16 +223,99 @@ impl SiteRunner {
             .flatten()
             .try_collect()?;
 
-        Ok(SiteRunner {
+        let mut runner = SiteRunner {
             id,
-            kitchens,
+            fulfillment: Fulfillment::Kitchens(kitchens),
             order_queue: VecDeque::new(),
             order_lines: HashMap::new(),
-        })
+            plugin,
+            acceptance,
+            dispatcher: Dispatcher::new(dispatch),
+            packer: Packer::new(id, packing),
+            breaks: BreakTracker::new(id, breaks),
+            robots: None,
+            bundler: None,
+            rng: StdRng::from_rng(&mut rand::rng()),
+            order_failure_rate: 0.0,
+            courier_share: 1.0,
+        };
+        runner.requeue_open_orders(state)?;
+        Ok(runner)
     }
 
-    pub(crate) fn id(&self) -> &SiteId {
-        &self.id
+    /// Draw random numbers seeded with `seed` and the id of the site, if set.
+    ///
+    /// Every site draws its own random numbers, so they do not depend on the
+    /// order in which sites are stepped.
+    pub(crate) fn with_seed(mut self, seed: Option<u64>) -> Self {
+        if let Some(seed) = seed {
+            let id: &[u8] = self.id.as_ref();
+            let site = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+            self.rng = StdRng::seed_from_u64(seed ^ site);
+        }
+        self
+    }
+
+    /// Deliver orders with a fleet of robots, if configured for this site.
+    pub(crate) fn with_robots(mut self, robots: Option<DeliveryRobots>) -> Self {
+        self.robots = robots.map(|config| RobotFleet::new(self.id, config));
+        self
+    }
+
+    /// Bundle the orders of customers choosing green delivery, if configured.
+    pub(crate) fn with_green_delivery(mut self, green: Option<GreenDeliveryConfig>) -> Self {
+        self.bundler = green.map(Bundler::new);
+        self
+    }
+
+    /// Run the site as a grocery dark store instead of cooking in its kitchens, if configured.
+    ///
+    /// The prices of all menu items are converted into the currency of the site with `rates`,
+    /// so substitutions change order totals in the currency they were placed in.
+    pub(crate) fn with_dark_store(
+        mut self,
+        dark_store: Option<DarkStoreConfig>,
+        state: &State,
+        rates: &ExchangeRates,
+    ) -> Result<Self> {
+        let Some(config) = dark_store else {
+            return Ok(self);
+        };
+        let objects = state.objects();
+        let currency = rates.resolve(objects.site(&self.id)?.properties()?.currency.as_deref())?;
+        let mut prices = HashMap::new();
+        for item_id in objects.menu_item_ids() {
+            let item = objects.menu_item(item_id)?;
+            let price = Money::new(item.price, rates.resolve(item.currency.as_deref())?);
+            prices
+                .entry(item.name.clone())
+                .or_insert(rates.convert(price, currency)?.amount());
+        }
+        self.fulfillment =
+            Fulfillment::DarkStore(Box::new(DarkStore::new(self.id, config, prices)));
+        Ok(self)
+    }
+
+    /// Fail submitted orders with probability `order_failure_rate` and dispatch only a
+    /// `courier_share` of all couriers, as steered through the controls of the simulation.
+    pub(crate) fn set_controls(&mut self, order_failure_rate: f64, courier_share: f64) {
+        self.order_failure_rate = order_failure_rate;
+        self.courier_share = courier_share;
+    }
+
+    /// Take the number of courier assignments by search ring since the last call.
+    pub(crate) fn take_assignments_by_ring(&mut self) -> BTreeMap<u32, usize> {
+        self.dispatcher.take_assignments_by_ring()
+    }
+
+    /// Reload kitchen stations after they were changed during a run.
+    pub(crate) fn refresh_stations(&mut self, state: &State) -> Result<()> {
+        if let Fulfillment::Kitchens(kitchens) = &mut self.fulfillment {
+            for kitchen in kitchens.values_mut() {
+                kitchen.refresh_stations(state)?;
+            }
+        }
+        Ok(())
     }
 
     /// Receive new orders from the state and queue them for processing.
@@ -168,6 +324,8 @@ impl SiteRunner {
             .iter()
             .flat_map(|order_id| ctx.orders().order(order_id));
         for order in orders {
+            self.packer
+                .expect(*order.id(), order.lines().map(|line| *line.id()).collect());
             for line in order.lines() {
                 self.order_lines.insert(
                     *line.id(),
@@ -175,6 +333,7 @@ impl SiteRunner {
                         id: *line.id(),
                         order_id: *order.id(),
                         item: (line.brand_id().try_into()?, line.menu_item_id().try_into()?),
+                        priority: order.priority().unwrap_or_default(),
                     },
                 );
             }
@@ -183,34 +342,127 @@ impl SiteRunner {
         Ok(())
     }
 
+    /// Queue the orders of the site which are not ready yet, e.g. when the state
+    /// was loaded from a snapshot taken while orders were in flight.
+    ///
+    /// Lines cooked before the snapshot are not cooked again, their orders are
+    /// packed once the remaining lines are done.
+    fn requeue_open_orders(&mut self, state: &State) -> Result<()> {
+        let pending = [
+            OrderStatus::Submitted.as_ref(),
+            OrderStatus::Processing.as_ref(),
+        ];
+        let orders = state
+            .orders()
+            .open_orders(&self.id)
+            .filter(|order| pending.contains(&order.status()))
+            .map(|order| *order.id())
+            .collect_vec();
+        self.receive_orders(&orders, state)?;
+
+        for order in orders.iter().flat_map(|id| state.orders().order(id)) {
+            for line in order.lines() {
+                let status = line.status();
+                if status == OrderLineStatus::Removed.as_ref() {
+                    self.order_lines.remove(line.id());
+                    self.packer.remove_line(order.id(), line.id());
+                }
+            }
+            for line in order.lines() {
+                let status = line.status();
+                if status == OrderLineStatus::Ready.as_ref()
+                    || status == OrderLineStatus::Waiting.as_ref()
+                {
+                    self.order_lines.remove(line.id());
+                    self.packer.cooked(order.id());
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn process_orders(&mut self, ctx: &State) -> Result<Vec<EventPayload>> {
         let mut events = Vec::new();
 
-        // Route order lines to kitchens for processing.
-        // A single order may contain lines from multiple brands,
-        // so we need to route each line separately to a kitchen that can handle it.
-        let mut router = OrderRouter::new(&mut self.kitchens);
-        while let Some(order_id) = self.order_queue.pop_front() {
-            if let Some(order) = ctx.orders().order(&order_id) {
-                for line in order.lines() {
-                    if let Some(line) = self.order_lines.get(line.id()) {
-                        events.extend(router.route_order_line(line.clone()));
+        match &mut self.fulfillment {
+            Fulfillment::Kitchens(kitchens) => {
+                // Route order lines to kitchens for processing.
+                // A single order may contain lines from multiple brands,
+                // so we need to route each line separately to a kitchen that can handle it.
+                let mut router = OrderRouter::new(kitchens);
+                while let Some(order_id) = self.order_queue.pop_front() {
+                    if let Some(order) = ctx.orders().order(&order_id) {
+                        for line in order.lines() {
+                            if let Some(line) = self.order_lines.get(line.id()) {
+                                events.extend(router.route_order_line(line.clone()));
+                            }
+                        }
+                    }
+                }
+
+                // Advance kitchens and collect cooked order lines. Lines of an order are cooked
+                // in parallel and wait for the remaining lines before the order is packed.
+                for kitchen in kitchens.values_mut() {
+                    events.extend(kitchen.step(ctx)?);
+                    events.extend(kitchen.take_completed().into_iter().map(|(order_id, id)| {
+                        let status = if self.packer.cooked(&order_id) {
+                            OrderLineStatus::Waiting
+                        } else {
+                            OrderLineStatus::Ready
+                        };
+                        EventPayload::order_line_updated(id, status, Some(*kitchen.id()), None)
+                    }));
+                }
+            }
+            Fulfillment::DarkStore(store) => {
+                // Every menu item is a SKU, picked by name from the shelves of the store
+                while let Some(order_id) = self.order_queue.pop_front() {
+                    if let Some(order) = ctx.orders().order(&order_id) {
+                        for line in order.lines() {
+                            if let Some(line) = self.order_lines.get(line.id()) {
+                                let sku = ctx.objects().menu_item(&line.item.1)?.name.clone();
+                                events.push(store.queue_order_line(line.clone(), sku));
+                            }
+                        }
+                    }
+                }
+
+                // Orders with a SKU out of stock and no substitute fail and are not packed
+                events.extend(store.step(ctx.current_time(), ctx.next_time(), &mut self.rng));
+                for order_id in store.take_failed() {
+                    self.packer.discard(&order_id);
+                    self.order_lines.retain(|_, line| line.order_id != order_id);
+                }
+                // Lines with a rejected substitute leave the order, which fails once empty
+                for (order_id, id) in store.take_removed() {
+                    self.order_lines.remove(&id);
+                    events.push(EventPayload::order_line_updated(
+                        id,
+                        OrderLineStatus::Removed,
+                        None,
+                        None,
+                    ));
+                    if self.packer.remove_line(&order_id, &id) {
+                        events.push(EventPayload::order_failed(order_id, None));
                     }
                 }
+                events.extend(store.take_completed().into_iter().map(|(order_id, id)| {
+                    let status = if self.packer.cooked(&order_id) {
+                        OrderLineStatus::Waiting
+                    } else {
+                        OrderLineStatus::Ready
+                    };
+                    EventPayload::order_line_updated(id, status, None, None)
+                }));
             }
         }
 
-        // Advance kitchens and collect completed order lines
-        for kitchen in self.kitchens.values_mut() {
-            events.extend(kitchen.step(ctx)?);
-            events.extend(kitchen.take_completed().into_iter().map(|(_, id)| {
-                EventPayload::order_line_updated(
-                    id,
-                    OrderLineStatus::Ready,
-                    Some(*kitchen.id()),
-                    None,
-                )
-            }));
+        // Lines are ready for pickup once their order is packed, which consumes packaging supplies
+        for event in self.packer.step(ctx.next_time()) {
+            if let EventPayload::OrderLineUpdated(payload) = &event {
+                self.order_lines.remove(&payload.order_line_id);
+            }
+            events.push(event);
         }
 
         Ok(events)
@@ -222,8 +474,19 @@ impl SiteRunner {
         }
     }
 
+    /// Number of orders waiting for or occupying a packing station.
+    pub(crate) fn packing_queued(&self) -> usize {
+        self.packer.queued()
+    }
+
     pub fn kitchen_stats(&self) -> impl Iterator<Item = KitchenStats> {
-        self.kitchens.values().map(|kitchen| kitchen.stats())
+        let kitchens = match &self.fulfillment {
+            Fulfillment::Kitchens(kitchens) => Some(kitchens),
+            Fulfillment::DarkStore(_) => None,
+        };
+        kitchens
+            .into_iter()
+            .flat_map(|kitchens| kitchens.values().map(|kitchen| kitchen.stats()))
     }
 
     pub fn total_kitchen_stats(&self) -> KitchenStats {
@@ -231,6 +494,58 @@ impl SiteRunner {
             .fold(KitchenStats::default(), |acc, stats| acc + stats)
     }
 
+    /// Earliest time at which the site moves on without new orders, `None` if it is idle.
+    ///
+    /// Orders waiting to be routed or for a courier are due at the current time, as
+    /// the search for couriers widens with every step an order waits. So are the other
+    /// orders of bundles whose first order was delivered.
+    pub(crate) fn next_event(&self, state: &State) -> Result<Option<DateTime<Utc>>> {
+        let now = state.current_time();
+        if !self.order_queue.is_empty()
+            || state
+                .orders()
+                .orders_with_status(&self.id, &OrderStatus::Ready)
+                .next()
+                .is_some()
+            || self
+                .bundler
+                .as_ref()
+                .is_some_and(|bundler| bundler.has_completed(state))
+        {
+            return Ok(Some(now));
+        }
+        let fulfillment = match &self.fulfillment {
+            Fulfillment::Kitchens(kitchens) => kitchens
+                .values()
+                .map(|kitchen| kitchen.next_event(state))
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .min(),
+            Fulfillment::DarkStore(store) => store.next_event(now),
+        };
+        Ok([
+            fulfillment,
+            self.packer.next_event(now),
+            self.breaks.next_end(),
+            self.robots.as_ref().and_then(RobotFleet::next_event),
+        ]
+        .into_iter()
+        .flatten()
+        .min())
+    }
+
+    /// H3 cells in which couriers for the deliveries of this site are searched.
+    ///
+    /// Sites whose cells do not overlap never offer deliveries to the same couriers.
+    pub(crate) fn courier_cells(&self, state: &State) -> Result<HashSet<CellIndex>> {
+        let policy = self.dispatcher.policy();
+        let location = state.objects().site(&self.id)?.properties()?.lat_lng()?;
+        Ok(location
+            .to_cell(policy.resolution())
+            .grid_disk(policy.search_rings))
+    }
+
     async fn handle_order_pickup(
         &mut self,
         ctx: &SimulationContext,
@@ -248,62 +563,268 @@ impl SiteRunner {
             return Err(Error::invalid_geometry("No node found for site location"));
         };
 
-        let orders = state
+        let mut orders = state
             .orders()
             .orders_with_status(&self.id, &OrderStatus::Ready)
             .collect_vec();
+        // orders of higher priority tiers are offered to the couriers nearby first
+        orders.sort_by_key(|order| std::cmp::Reverse(order.priority()));
+
+        let now = state.current_time();
+        let ready: HashSet<_> = orders.iter().map(|order| *order.id()).collect();
+        self.dispatcher.retain(|order_id| ready.contains(order_id));
+
+        // green orders are dispatched in bundles, led by their first order
+        let mut bundles = HashMap::new();
+        if let Some(bundler) = self.bundler.as_mut() {
+            events.extend(bundler.complete(state)?);
+            let mut green = Vec::new();
+            for order in &orders {
+                if bundler.is_green(&order.customer_person_id().try_into()?) {
+                    green.push((*order.id(), order.destination()?));
+                }
+            }
+            let green_ids: HashSet<_> = green.iter().map(|(order_id, _)| *order_id).collect();
+            for bundle in bundler.bundle(now, green) {
+                bundles.insert(bundle[0], bundle[1..].to_vec());
+            }
+            // orders held back or handed over on the way are not offered on their own
+            orders.retain(|order| {
+                !green_ids.contains(order.id()) || bundles.contains_key(order.id())
+            });
+        }
+        for (courier, order_id, offer) in self.dispatcher.expire(now) {
+            events.push(EventPayload::courier_updated(
+                courier,
+                order_id,
+                CourierActivity::OfferExpired,
+                Some(offer),
+            ));
+        }
+
+        events.extend(self.breaks.end_breaks(now));
+        if let Some(robots) = self.robots.as_mut() {
+            events.extend(robots.advance(now));
+        }
 
+        // couriers holding an unanswered offer or on a break are not available for other orders
+        let reserved = self.dispatcher.reserved();
+        let max_offers = self.dispatcher.policy().max_offers_per_step.max(1);
+        let policy = self.dispatcher.policy();
+        // couriers off shift are skipped, so more are looked up the fewer are on shift
+        let limit = orders.len() * max_offers + reserved.len() + self.breaks.on_break();
+        let limit = (limit as f64 / self.courier_share.max(0.01)).ceil() as usize;
         let couriers = state
             .population()
-            .idle_people_in_cell(
+            .idle_people_near(
                 ctx,
-                site_location.to_cell(Resolution::Eight),
+                site_location.to_cell(policy.resolution()),
+                policy.search_rings,
                 &PersonRole::Courier,
             )
             .await?
-            .limit(0, Some(orders.len()))?
-            .select_columns(&["id"])?
+            .limit(0, Some(limit))?
+            .select_columns(&["id", "ring"])?
             .collect()
             .await?;
-        let couriers_iter = couriers.into_iter().flat_map(|courier| {
-            courier
-                .column(0)
-                .as_fixed_size_binary()
-                .iter()
-                .flat_map(|maybe_id| maybe_id.and_then(|id| Uuid::from_slice(id).ok()))
-                .map(PersonId::from)
-                .collect_vec()
-        });
+        // couriers available for deliveries, with the ring around the site they are in
+        let mut available = couriers
+            .into_iter()
+            .flat_map(|batch| {
+                let rings = batch.column(1).as_primitive::<UInt32Type>().clone();
+                batch
+                    .column(0)
+                    .as_fixed_size_binary()
+                    .iter()
+                    .zip(rings.iter())
+                    .flat_map(|(maybe_id, ring)| {
+                        let id = Uuid::from_slice(maybe_id?).ok()?;
+                        Some((PersonId::from(id), ring?))
+                    })
+                    .collect_vec()
+            })
+            .filter(|(courier, _)| !reserved.contains(courier))
+            .filter(|(courier, _)| on_shift(courier, self.courier_share))
+            .filter(|(courier, _)| self.breaks.check_available(*courier, now, &mut events))
+            .collect_vec();
 
         let mut router = planner.get_router();
+        let rng = &mut self.rng;
+
+        for order in orders {
+            // the search expands the longer the order waits, even without couriers nearby
+            let rings = self.dispatcher.search_rings(*order.id(), now);
+            if self.dispatcher.is_pending(order.id()) {
+                continue;
+            }
+            // orders handed over on the way to the destination of this one
+            let others = bundles.remove(order.id()).unwrap_or_default();
 
-        let order_queue = orders.into_iter().zip(couriers_iter);
-        for (order, courier) in order_queue {
             let destination = order.destination()?;
 
+            // robots take orders within their range before couriers are asked, but no bundles
+            if let Some(robots) = self
+                .robots
+                .as_mut()
+                .filter(|robots| others.is_empty() && robots.is_available())
+            {
+                let distance_m = match robots.kind() {
+                    // drones fly straight to the customer
+                    RobotKind::Drone => Some(site_location.distance_m(destination)),
+                    // sidewalk robots are bound to the streets open to pedestrians
+                    RobotKind::Robot => planner
+                        .nearest_node(&destination)
+                        .and_then(|node| {
+                            planner.plan(
+                                &mut router,
+                                site_location_node,
+                                node,
+                                Transport::Foot,
+                                now,
+                            )
+                        })
+                        .map(|journey| journey.distance_m() as f64),
+                };
+                if let Some(distance_m) = distance_m.filter(|d| robots.reaches(*d))
+                    && let Some(dispatched) = robots.dispatch(
+                        *order.id(),
+                        order.customer_person_id().try_into()?,
+                        distance_m,
+                        now,
+                    )
+                {
+                    events.extend(dispatched);
+                    continue;
+                }
+            }
+
+            if available.is_empty() {
+                continue;
+            }
+
             // Generate the delivery route for the courier
             let Some(destination_node) = planner.nearest_node(&destination) else {
                 tracing::error!(target: "site-agent", "Failed to find a node for order {:?}", order.id());
                 events.push(EventPayload::order_failed(*order.id(), None));
                 continue;
             };
-            let Some(journey) = planner.plan(&mut router, site_location_node, destination_node)
-            else {
+            // couriers ride bicycles
+            let Some(journey) = planner.plan(
+                &mut router,
+                site_location_node,
+                destination_node,
+                Transport::Bicycle,
+                now,
+            ) else {
                 tracing::error!("Failed to find a route for order {:?}", order.id());
                 events.push(EventPayload::order_failed(*order.id(), None));
                 continue;
             };
 
+            let offer = self
+                .acceptance
+                .offer(journey.distance_m() as f64, order.tip().unwrap_or_default());
+            let decision = match &self.plugin {
+                Some(plugin) => {
+                    plugin.accept_assignment(offer.distance_m, hour_of_day(state.current_time()))?
+                }
+                None => None,
+            };
+
+            // offer the order to one courier after another until it is taken
+            let mut assigned = None;
+            for _ in 0..max_offers {
+                let Some(index) = available.iter().position(|(courier, ring)| {
+                    *ring <= rings && self.dispatcher.is_candidate(order.id(), courier)
+                }) else {
+                    break;
+                };
+                let (courier, ring) = available[index];
+                events.push(EventPayload::courier_updated(
+                    courier,
+                    *order.id(),
+                    CourierActivity::OfferReceived,
+                    Some(offer),
+                ));
+
+                if !self.dispatcher.policy().responds(rng) {
+                    // the order waits for the courier until the offer expires
+                    self.dispatcher.hold(*order.id(), courier, offer, now);
+                    available.remove(index);
+                    break;
+                }
+                if decision.unwrap_or_else(|| offer.decide(rng)) {
+                    events.push(EventPayload::courier_updated(
+                        courier,
+                        *order.id(),
+                        CourierActivity::OfferAccepted,
+                        Some(offer),
+                    ));
+                    self.dispatcher.accept(order.id(), ring);
+                    available.remove(index);
+                    assigned = Some(courier);
+                    break;
+                }
+                events.push(EventPayload::courier_updated(
+                    courier,
+                    *order.id(),
+                    CourierActivity::OfferDeclined,
+                    Some(offer),
+                ));
+                self.dispatcher.decline(*order.id(), courier);
+            }
+
+            // orders nobody accepted stay ready and are offered again in the next step
+            let Some(courier) = assigned else {
+                continue;
+            };
+            self.breaks.deliver(&courier, journey.distance_m() as f64);
+
             events.push(EventPayload::order_updated(
                 *order.id(),
                 OrderStatus::PickedUp,
                 Some(courier),
             ));
+            events.push(EventPayload::courier_updated(
+                courier,
+                *order.id(),
+                CourierActivity::PickedUp,
+                None,
+            ));
+
+            for other in &others {
+                events.push(EventPayload::order_updated(
+                    *other,
+                    OrderStatus::PickedUp,
+                    Some(courier),
+                ));
+                events.push(EventPayload::courier_updated(
+                    courier,
+                    *other,
+                    CourierActivity::PickedUp,
+                    None,
+                ));
+            }
 
             events.push(EventPayload::person_updated(
                 courier,
                 PersonStatus::Delivering(*order.id(), journey),
             ));
+
+            // couriers only check out of sites they checked in at
+            let carried = std::iter::once(*order.id())
+                .chain(others.iter().copied())
+                .collect();
+            if let Some(check_out) = state
+                .population()
+                .site_visits()
+                .check_out(&self.id, &courier, carried)
+            {
+                events.push(check_out);
+            }
+            if let Some(bundler) = self.bundler.as_mut() {
+                bundler.dispatch(*order.id(), courier, others);
+            }
         }
 
         Ok(events)
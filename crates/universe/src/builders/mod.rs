@@ -1,16 +1,40 @@
+mod results_daily_summary;
 mod results_events;
+mod results_feedback;
+mod results_footprint;
+mod results_green;
+mod results_heatmap;
+mod results_impressions;
+mod results_inventory;
+mod results_invoices;
+mod results_market_share;
 mod results_metrics;
+mod results_waste;
 mod state_objects;
 mod state_orders;
 mod state_population;
 
+pub(crate) use self::results_daily_summary::{
+    DAILY_SUMMARY_SCHEMA, DailySummaryBuffer, DaySummary,
+};
 pub(crate) use self::results_events::EVENTS_SCHEMA;
 pub use self::results_events::EventDataBuilder;
+pub(crate) use self::results_feedback::{FEEDBACK_SCHEMA, FeedbackBuffer, OrderFeedback};
+pub(crate) use self::results_footprint::{DeliveryFootprint, FOOTPRINT_SCHEMA, FootprintBuffer};
+pub(crate) use self::results_green::{
+    GREEN_DELIVERY_SCHEMA, GreenDeliveryBuffer, GreenDeliveryDay,
+};
+pub(crate) use self::results_heatmap::{HeatmapBuffer, HeatmapCell, ORDER_HEATMAP_SCHEMA};
+pub(crate) use self::results_impressions::{IMPRESSIONS_SCHEMA, Impression, ImpressionBuffer};
+pub(crate) use self::results_inventory::{INVENTORY_SCHEMA, IngredientDay, InventoryBuffer};
+pub(crate) use self::results_invoices::{INVOICES_SCHEMA, Invoice, InvoiceBuffer};
+pub(crate) use self::results_market_share::{CuisineSales, MARKET_SHARE_SCHEMA, MarketShareBuffer};
 pub use self::results_metrics::EventStatsBuffer;
 pub(crate) use self::results_metrics::METRICS_SCHEMA;
+pub(crate) use self::results_waste::{IngredientWaste, WASTE_SCHEMA, WasteBuffer};
 pub(crate) use self::state_objects::OBJECTS_SCHEMA;
 pub use self::state_objects::ObjectDataBuilder;
 pub use self::state_orders::OrderDataBuilder;
 pub(crate) use self::state_orders::{ORDER_LINE_SCHEMA, ORDER_SCHEMA};
-pub(crate) use self::state_population::POPULATION_SCHEMA;
 pub use self::state_population::PopulationDataBuilder;
+pub(crate) use self::state_population::{POPULATION_PREFERENCES_FIELD, POPULATION_SCHEMA};
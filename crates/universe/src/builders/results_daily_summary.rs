@@ -0,0 +1,164 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, Float64Builder, Int64Builder, StringViewBuilder, TimestampMillisecondBuilder,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+
+use crate::{Money, Result};
+
+pub(crate) static DAILY_SUMMARY_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "day",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("steps", DataType::Int64, false),
+        Field::new("orders", DataType::Int64, false),
+        Field::new("delivered_orders", DataType::Int64, false),
+        Field::new("failed_orders", DataType::Int64, false),
+        Field::new("currency", DataType::Utf8View, false),
+        Field::new("revenue", DataType::Float64, false),
+        Field::new("membership_revenue", DataType::Float64, false),
+        Field::new("on_time_rate", DataType::Float64, true),
+        Field::new("avg_delivery_time_s", DataType::Float64, true),
+        Field::new("courier_utilization", DataType::Float64, true),
+    ]))
+});
+
+/// Orders and courier load of one simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DaySummary {
+    /// Midnight starting the day
+    pub(crate) day: DateTime<Utc>,
+    /// Steps of the day covered by the run
+    pub(crate) steps: u64,
+    pub(crate) orders: u64,
+    pub(crate) delivered_orders: u64,
+    /// Orders cancelled or failed during the day
+    pub(crate) failed_orders: u64,
+    /// Order totals converted into the base currency of the simulation
+    pub(crate) revenue: Money,
+    /// Membership fees billed during the day, in the base currency
+    pub(crate) membership_revenue: Money,
+    /// Delivered orders placed during the run, whose promised time is known
+    pub(crate) promised_deliveries: u64,
+    /// Deliveries made no later than the promised time
+    pub(crate) on_time_deliveries: u64,
+    /// Sum of the times from placement to delivery of the promised deliveries
+    pub(crate) delivery_time_s: f64,
+    /// Sum of the courier utilization observed after each step
+    pub(crate) courier_utilization: f64,
+}
+
+impl DaySummary {
+    pub(crate) fn new(day: DateTime<Utc>, revenue: Money) -> Self {
+        Self {
+            day,
+            steps: 0,
+            orders: 0,
+            delivered_orders: 0,
+            failed_orders: 0,
+            revenue,
+            membership_revenue: Money::zero(revenue.currency()),
+            promised_deliveries: 0,
+            on_time_deliveries: 0,
+            delivery_time_s: 0.0,
+            courier_utilization: 0.0,
+        }
+    }
+
+    /// Share of the promised deliveries made on time.
+    pub(crate) fn on_time_rate(&self) -> Option<f64> {
+        (self.promised_deliveries > 0)
+            .then(|| self.on_time_deliveries as f64 / self.promised_deliveries as f64)
+    }
+
+    pub(crate) fn avg_delivery_time_s(&self) -> Option<f64> {
+        (self.promised_deliveries > 0)
+            .then(|| self.delivery_time_s / self.promised_deliveries as f64)
+    }
+
+    /// Mean share of couriers delivering an order over the steps of the day.
+    pub(crate) fn avg_courier_utilization(&self) -> Option<f64> {
+        (self.steps > 0).then(|| self.courier_utilization / self.steps as f64)
+    }
+}
+
+pub(crate) struct DailySummaryBuffer {
+    days: TimestampMillisecondBuilder,
+    steps: Int64Builder,
+    orders: Int64Builder,
+    delivered_orders: Int64Builder,
+    failed_orders: Int64Builder,
+    currency: StringViewBuilder,
+    revenue: Float64Builder,
+    membership_revenue: Float64Builder,
+    on_time_rates: Float64Builder,
+    avg_delivery_times: Float64Builder,
+    courier_utilization: Float64Builder,
+}
+
+impl DailySummaryBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            days: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            steps: Int64Builder::new(),
+            orders: Int64Builder::new(),
+            delivered_orders: Int64Builder::new(),
+            failed_orders: Int64Builder::new(),
+            currency: StringViewBuilder::new(),
+            revenue: Float64Builder::new(),
+            membership_revenue: Float64Builder::new(),
+            on_time_rates: Float64Builder::new(),
+            avg_delivery_times: Float64Builder::new(),
+            courier_utilization: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub(crate) fn push(&mut self, summary: &DaySummary) {
+        self.days.append_value(summary.day.timestamp_millis());
+        self.steps.append_value(summary.steps as i64);
+        self.orders.append_value(summary.orders as i64);
+        self.delivered_orders
+            .append_value(summary.delivered_orders as i64);
+        self.failed_orders
+            .append_value(summary.failed_orders as i64);
+        self.currency.append_value(summary.revenue.currency());
+        self.revenue
+            .append_value(summary.revenue.round_cents().amount());
+        self.membership_revenue
+            .append_value(summary.membership_revenue.round_cents().amount());
+        self.on_time_rates.append_option(summary.on_time_rate());
+        self.avg_delivery_times
+            .append_option(summary.avg_delivery_time_s());
+        self.courier_utilization
+            .append_option(summary.avg_courier_utilization());
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            DAILY_SUMMARY_SCHEMA.clone(),
+            vec![
+                Arc::new(self.days.finish()),
+                Arc::new(self.steps.finish()),
+                Arc::new(self.orders.finish()),
+                Arc::new(self.delivered_orders.finish()),
+                Arc::new(self.failed_orders.finish()),
+                Arc::new(self.currency.finish()),
+                Arc::new(self.revenue.finish()),
+                Arc::new(self.membership_revenue.finish()),
+                Arc::new(self.on_time_rates.finish()),
+                Arc::new(self.avg_delivery_times.finish()),
+                Arc::new(self.courier_utilization.finish()),
+            ],
+        )?)
+    }
+}
@@ -1,13 +1,17 @@
+use std::fmt::{self, Write};
 use std::sync::{Arc, LazyLock};
 
 use arrow::array::{ArrayRef, FixedSizeBinaryBuilder, LargeStringBuilder, RecordBatch};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
-use datafusion::common::Result;
+use chrono::{DateTime, Datelike as _, Timelike as _, Utc};
+use datafusion::common::{DataFusionError, Result};
+use rand::SeedableRng as _;
+use rand::rngs::StdRng;
 use uuid::{ContextV7, Timestamp, Uuid};
 
-use crate::{Event, EventPayload};
+use crate::idents::uuid_v7_from_rng;
+use crate::{Event, EventPayload, ObjectChange};
 
-static EVENT_PREFIX: &str = "io.caspers";
 static DEFAULT_SOURCE: &str = "caspers/universe/default";
 static DEFAULT_SPECVERSION: &str = "1.0";
 static DEFAULT_CONTENT_TYPE: &str = "application/json";
@@ -21,6 +25,8 @@ pub(crate) static EVENTS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         Field::new("datacontenttype", DataType::LargeUtf8, false),
         Field::new("time", DataType::LargeUtf8, false),
         Field::new("data", DataType::LargeUtf8, false),
+        // CloudEvents distributed tracing extension
+        Field::new("traceparent", DataType::LargeUtf8, true),
     ]))
 });
 
@@ -32,8 +38,18 @@ pub struct EventDataBuilder {
     datacontenttype: LargeStringBuilder,
     time: LargeStringBuilder,
     data: LargeStringBuilder,
+    traceparent: LargeStringBuilder,
 
     context: ContextV7,
+
+    /// Random numbers of event ids, drawn from the clock context if not seeded
+    rng: Option<StdRng>,
+
+    /// W3C trace context of the step producing the events
+    current_traceparent: Option<String>,
+
+    /// Scratch buffer reused when serializing event payloads
+    buffer: Vec<u8>,
 }
 
 impl Default for EventDataBuilder {
@@ -52,38 +68,83 @@ impl EventDataBuilder {
             datacontenttype: LargeStringBuilder::new(),
             time: LargeStringBuilder::new(),
             data: LargeStringBuilder::new(),
+            traceparent: LargeStringBuilder::new(),
+            context: ContextV7::new(),
+            rng: None,
+            current_traceparent: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Create a builder with space for `num_events` events.
+    pub fn with_capacity(num_events: usize) -> Self {
+        let strings = |bytes_per_event: usize| {
+            LargeStringBuilder::with_capacity(num_events, num_events * bytes_per_event)
+        };
+        Self {
+            id: FixedSizeBinaryBuilder::with_capacity(num_events, 16),
+            source: strings(DEFAULT_SOURCE.len()),
+            specversion: strings(DEFAULT_SPECVERSION.len()),
+            type_: strings(32),
+            datacontenttype: strings(DEFAULT_CONTENT_TYPE.len()),
+            time: strings(32),
+            data: strings(256),
+            traceparent: strings(55),
             context: ContextV7::new(),
+            rng: None,
+            current_traceparent: None,
+            buffer: Vec::new(),
         }
     }
 
+    /// Derive the ids of events from `seed`, so seeded runs write the same ids.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Attach the W3C `traceparent` of the span producing the events to all subsequently
+    /// added events, so downstream systems can correlate their spans with the simulation.
+    pub fn with_traceparent(mut self, traceparent: impl Into<Option<String>>) -> Self {
+        self.current_traceparent = traceparent.into();
+        self
+    }
+
     pub fn add_event(&mut self, event: &Event) -> Result<()> {
-        let ts = Timestamp::from_unix(
-            &self.context,
-            event.timestamp.timestamp() as u64,
-            event.timestamp.timestamp_subsec_nanos(),
-        );
-        let uuid = Uuid::new_v7(ts);
+        self.add_payload(event.timestamp, &event.payload)
+    }
+
+    /// Add an event without taking ownership of its payload.
+    pub fn add_payload(&mut self, timestamp: DateTime<Utc>, payload: &EventPayload) -> Result<()> {
+        let uuid = match self.rng.as_mut() {
+            Some(rng) => uuid_v7_from_rng(timestamp, rng),
+            None => Uuid::new_v7(Timestamp::from_unix(
+                &self.context,
+                timestamp.timestamp() as u64,
+                timestamp.timestamp_subsec_nanos(),
+            )),
+        };
+
+        self.buffer.clear();
+        serde_json::to_writer(&mut self.buffer, payload)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let data = std::str::from_utf8(&self.buffer)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
 
         self.id.append_value(uuid)?;
         self.source.append_value(DEFAULT_SOURCE);
         self.specversion.append_value(DEFAULT_SPECVERSION);
-        self.type_.append_value(self.event_type(&event.payload));
+        self.type_.append_value(event_type(payload));
         self.datacontenttype.append_value(DEFAULT_CONTENT_TYPE);
-        self.time.append_value(event.timestamp.to_rfc3339());
-        self.data
-            .append_value(serde_json::to_string(&event.payload).unwrap());
+        write_rfc3339(&mut self.time, &timestamp)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        self.time.append_value("");
+        self.data.append_value(data);
+        self.traceparent
+            .append_option(self.current_traceparent.as_deref());
         Ok(())
     }
 
-    fn event_type(&self, event: &EventPayload) -> String {
-        match event {
-            EventPayload::OrderCreated(_) => format!("{}.orders.created", EVENT_PREFIX),
-            EventPayload::OrderUpdated(_) => format!("{}.orders.updated", EVENT_PREFIX),
-            EventPayload::OrderLineUpdated(_) => format!("{}.orders.line_updated", EVENT_PREFIX),
-            EventPayload::PersonUpdated(_) => format!("{}.persons.updated", EVENT_PREFIX),
-        }
-    }
-
     pub fn build(mut self) -> Result<RecordBatch> {
         let arrays: Vec<ArrayRef> = vec![
             Arc::new(self.id.finish()),
@@ -93,7 +154,164 @@ impl EventDataBuilder {
             Arc::new(self.datacontenttype.finish()),
             Arc::new(self.time.finish()),
             Arc::new(self.data.finish()),
+            Arc::new(self.traceparent.finish()),
         ];
         Ok(RecordBatch::try_new(EVENTS_SCHEMA.clone(), arrays)?)
     }
 }
+
+/// Write a timestamp in the format of [`DateTime::to_rfc3339`].
+///
+/// Chrono's formatting allocates intermediate strings, which adds up when writing
+/// every event of a step.
+fn write_rfc3339(w: &mut impl Write, timestamp: &DateTime<Utc>) -> fmt::Result {
+    let naive = timestamp.naive_utc();
+    // leap seconds are represented as nanoseconds beyond one second
+    let (second, nanos) = match naive.nanosecond() {
+        nanos if nanos >= 1_000_000_000 => (naive.second() + 1, nanos - 1_000_000_000),
+        nanos => (naive.second(), nanos),
+    };
+    write!(
+        w,
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        naive.year(),
+        naive.month(),
+        naive.day(),
+        naive.hour(),
+        naive.minute(),
+        second
+    )?;
+    match nanos {
+        0 => Ok(()),
+        nanos if nanos % 1_000_000 == 0 => write!(w, ".{:03}", nanos / 1_000_000),
+        nanos if nanos % 1_000 == 0 => write!(w, ".{:06}", nanos / 1_000),
+        nanos => write!(w, ".{nanos:09}"),
+    }?;
+    w.write_str("+00:00")
+}
+
+/// CloudEvents type of an event payload.
+fn event_type(event: &EventPayload) -> &'static str {
+    match event {
+        EventPayload::OrderCreated(_) => "io.caspers.orders.created",
+        EventPayload::OrderUpdated(_) => "io.caspers.orders.updated",
+        EventPayload::OrderLineUpdated(_) => "io.caspers.orders.line_updated",
+        EventPayload::CompensationIssued(_) => "io.caspers.orders.compensated",
+        EventPayload::PersonUpdated(_) => "io.caspers.persons.updated",
+        EventPayload::PersonLifecycle(_) => "io.caspers.persons.lifecycle",
+        EventPayload::SiteCheckIn(_) => "io.caspers.sites.check_in",
+        EventPayload::SiteCheckOut(_) => "io.caspers.sites.check_out",
+        EventPayload::SupplyUpdated(_) => "io.caspers.sites.supplies",
+        EventPayload::CourierUpdated(_) => "io.caspers.couriers.updated",
+        EventPayload::CourierBreak(_) => "io.caspers.couriers.break",
+        EventPayload::RobotDelivery(_) => "io.caspers.robots.delivery",
+        EventPayload::SubstitutionUpdated(_) => "io.caspers.orders.substitution",
+        EventPayload::NotificationUpdated(_) => "io.caspers.notifications.updated",
+        EventPayload::StepStarted(_) => "io.caspers.simulation.step_started",
+        EventPayload::StepFinished(_) => "io.caspers.simulation.step_finished",
+        EventPayload::ConfigChanged(_) => "io.caspers.simulation.config_changed",
+        EventPayload::MembershipBilled(_) => "io.caspers.persons.membership_billed",
+        EventPayload::SessionUpdated(_) => "io.caspers.sessions.updated",
+        EventPayload::ProcurementUpdated(_) => "io.caspers.procurement.updated",
+        EventPayload::IngredientWasted(_) => "io.caspers.ingredient.wasted",
+        EventPayload::DeliveryFootprint(_) => "io.caspers.orders.delivery_footprint",
+        EventPayload::ObjectChanged(p) => match p.change {
+            ObjectChange::Created => "io.caspers.objects.created",
+            ObjectChange::Updated => "io.caspers.objects.updated",
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array as _, AsArray as _};
+    use chrono::Utc;
+
+    use super::*;
+    use crate::{ObjectChangedPayload, ObjectLabel, StepStartedPayload};
+
+    #[test]
+    fn test_traceparent() -> Result<()> {
+        let event = Event {
+            timestamp: Utc::now(),
+            payload: EventPayload::StepStarted(StepStartedPayload {
+                simulation_time: Utc::now(),
+            }),
+        };
+
+        let mut builder = EventDataBuilder::new();
+        builder.add_event(&event)?;
+        let batch = builder.build()?;
+        assert!(batch.column_by_name("traceparent").unwrap().is_null(0));
+
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut builder = EventDataBuilder::new().with_traceparent(traceparent.to_string());
+        builder.add_event(&event)?;
+        let batch = builder.build()?;
+        let values = batch
+            .column_by_name("traceparent")
+            .unwrap()
+            .as_string::<i64>();
+        assert_eq!(values.value(0), traceparent);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_rfc3339() {
+        let base = DateTime::from_timestamp(1_761_675_872, 0).unwrap();
+        for nanos in [0, 120_000_000, 123_456_000, 123_456_789, 1] {
+            let timestamp = base + chrono::Duration::nanoseconds(nanos);
+            let mut written = String::new();
+            write_rfc3339(&mut written, &timestamp).unwrap();
+            assert_eq!(written, timestamp.to_rfc3339());
+        }
+    }
+
+    #[test]
+    fn test_add_payload() -> Result<()> {
+        let payload = EventPayload::ObjectChanged(ObjectChangedPayload {
+            object_id: Uuid::nil(),
+            label: ObjectLabel::Site,
+            uri_ref: None,
+            change: ObjectChange::Updated,
+        });
+
+        let mut builder = EventDataBuilder::with_capacity(2);
+        builder.add_payload(Utc::now(), &payload)?;
+        builder.add_payload(Utc::now(), &payload)?;
+        let batch = builder.build()?;
+        assert_eq!(batch.num_rows(), 2);
+
+        let types = batch.column_by_name("type").unwrap().as_string::<i64>();
+        assert_eq!(types.value(1), "io.caspers.objects.updated");
+        let data = batch.column_by_name("data").unwrap().as_string::<i64>();
+        assert_eq!(data.value(0), data.value(1));
+        assert_eq!(data.value(0), serde_json::to_string(&payload).unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seeded_ids() -> Result<()> {
+        let timestamp = Utc::now();
+        let payload = EventPayload::step_started(timestamp);
+        let ids = |builder: EventDataBuilder| -> Result<Vec<Vec<u8>>> {
+            let mut builder = builder;
+            builder.add_payload(timestamp, &payload)?;
+            builder.add_payload(timestamp, &payload)?;
+            let batch = builder.build()?;
+            let ids = batch.column(0).as_fixed_size_binary();
+            Ok(ids.iter().flatten().map(|id| id.to_vec()).collect())
+        };
+
+        let seeded = ids(EventDataBuilder::new().with_seed(7))?;
+        assert_eq!(seeded, ids(EventDataBuilder::new().with_seed(7))?);
+        assert_ne!(seeded[0], seeded[1]);
+        assert_ne!(seeded, ids(EventDataBuilder::new().with_seed(8))?);
+
+        let id = Uuid::from_slice(&seeded[0]).unwrap();
+        assert_eq!(id.get_version_num(), 7);
+        Ok(())
+    }
+}
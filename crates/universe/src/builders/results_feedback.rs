@@ -0,0 +1,124 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, FixedSizeBinaryBuilder, Float64Builder, Int64Builder,
+    TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::Result;
+use crate::idents::{OrderId, PersonId, SiteId};
+
+pub(crate) static FEEDBACK_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::FixedSizeBinary(16), false).with_extension_type(UuidExtension),
+        Field::new("order_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("customer_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new(
+            "prompted_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("delivery_time_s", DataType::Float64, false),
+        Field::new("late_s", DataType::Float64, false),
+        Field::new(
+            "responded_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            true,
+        ),
+        Field::new("rating", DataType::Int64, true),
+    ]))
+});
+
+/// A rating prompt for a delivered order and the customer's response, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OrderFeedback {
+    pub(crate) id: Uuid,
+    pub(crate) order_id: OrderId,
+    pub(crate) site_id: SiteId,
+    pub(crate) customer_id: PersonId,
+    /// Time the order was delivered and the customer was prompted
+    pub(crate) prompted_at: DateTime<Utc>,
+    /// Time from placing to delivering the order
+    pub(crate) delivery_time_s: f64,
+    /// Time the order was delivered after the promised time, zero if on time
+    pub(crate) late_s: f64,
+    /// Rating from 1 to 5 and the time it was given, if the customer responded
+    pub(crate) response: Option<(DateTime<Utc>, u8)>,
+}
+
+pub(crate) struct FeedbackBuffer {
+    ids: FixedSizeBinaryBuilder,
+    order_ids: FixedSizeBinaryBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    customer_ids: FixedSizeBinaryBuilder,
+    prompted_at: TimestampMillisecondBuilder,
+    delivery_times: Float64Builder,
+    late: Float64Builder,
+    responded_at: TimestampMillisecondBuilder,
+    ratings: Int64Builder,
+}
+
+impl FeedbackBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            ids: FixedSizeBinaryBuilder::new(16),
+            order_ids: FixedSizeBinaryBuilder::new(16),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            customer_ids: FixedSizeBinaryBuilder::new(16),
+            prompted_at: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            delivery_times: Float64Builder::new(),
+            late: Float64Builder::new(),
+            responded_at: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            ratings: Int64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.ratings.len()
+    }
+
+    pub(crate) fn push(&mut self, feedback: &OrderFeedback) -> Result<()> {
+        self.ids.append_value(feedback.id)?;
+        self.order_ids.append_value(feedback.order_id)?;
+        self.site_ids.append_value(feedback.site_id)?;
+        self.customer_ids.append_value(feedback.customer_id)?;
+        self.prompted_at
+            .append_value(feedback.prompted_at.timestamp_millis());
+        self.delivery_times.append_value(feedback.delivery_time_s);
+        self.late.append_value(feedback.late_s);
+        self.responded_at.append_option(
+            feedback
+                .response
+                .map(|(responded_at, _)| responded_at.timestamp_millis()),
+        );
+        self.ratings
+            .append_option(feedback.response.map(|(_, rating)| rating as i64));
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            FEEDBACK_SCHEMA.clone(),
+            vec![
+                Arc::new(self.ids.finish()),
+                Arc::new(self.order_ids.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.customer_ids.finish()),
+                Arc::new(self.prompted_at.finish()),
+                Arc::new(self.delivery_times.finish()),
+                Arc::new(self.late.finish()),
+                Arc::new(self.responded_at.finish()),
+                Arc::new(self.ratings.finish()),
+            ],
+        )?)
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, FixedSizeBinaryBuilder, Float64Builder, Int64Builder, StringViewBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+
+use crate::idents::SiteId;
+use crate::{DeliveryMode, Result};
+
+pub(crate) static FOOTPRINT_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "day",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("mode", DataType::Utf8View, false),
+        Field::new("deliveries", DataType::Int64, false),
+        Field::new("distance_m", DataType::Float64, false),
+        Field::new("co2_g", DataType::Float64, false),
+    ]))
+});
+
+/// Deliveries of a site by one mode of transport during a simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DeliveryFootprint {
+    /// Midnight starting the day
+    pub(crate) day: DateTime<Utc>,
+    pub(crate) site_id: SiteId,
+    pub(crate) mode: DeliveryMode,
+    /// Number of deliveries started during the day
+    pub(crate) deliveries: i64,
+    /// Distance travelled for the deliveries in meters
+    pub(crate) distance_m: f64,
+    /// Grams of CO2 emitted for the deliveries
+    pub(crate) co2_g: f64,
+}
+
+pub(crate) struct FootprintBuffer {
+    days: TimestampMillisecondBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    modes: StringViewBuilder,
+    deliveries: Int64Builder,
+    distances: Float64Builder,
+    footprints: Float64Builder,
+}
+
+impl FootprintBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            days: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            modes: StringViewBuilder::new(),
+            deliveries: Int64Builder::new(),
+            distances: Float64Builder::new(),
+            footprints: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub(crate) fn push(&mut self, row: &DeliveryFootprint) -> Result<()> {
+        self.days.append_value(row.day.timestamp_millis());
+        self.site_ids.append_value(row.site_id)?;
+        self.modes.append_value(row.mode.as_ref());
+        self.deliveries.append_value(row.deliveries);
+        self.distances.append_value(row.distance_m);
+        self.footprints.append_value(row.co2_g);
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            FOOTPRINT_SCHEMA.clone(),
+            vec![
+                Arc::new(self.days.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.modes.finish()),
+                Arc::new(self.deliveries.finish()),
+                Arc::new(self.distances.finish()),
+                Arc::new(self.footprints.finish()),
+            ],
+        )?)
+    }
+}
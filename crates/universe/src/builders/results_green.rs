@@ -0,0 +1,116 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, BooleanBuilder, FixedSizeBinaryBuilder, Float64Builder, Int64Builder,
+    TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+
+use crate::Result;
+use crate::idents::SiteId;
+
+pub(crate) static GREEN_DELIVERY_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "day",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("green", DataType::Boolean, false),
+        Field::new("orders", DataType::Int64, false),
+        Field::new("delivered_orders", DataType::Int64, false),
+        Field::new("on_time_deliveries", DataType::Int64, false),
+        Field::new("bundled_deliveries", DataType::Int64, false),
+        Field::new("delivery_time_s", DataType::Float64, false),
+        Field::new("co2_g", DataType::Float64, false),
+    ]))
+});
+
+/// Green or standard orders of a site during a simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GreenDeliveryDay {
+    /// Midnight starting the day
+    pub(crate) day: DateTime<Utc>,
+    pub(crate) site_id: SiteId,
+    /// Whether the customers chose green delivery at checkout
+    pub(crate) green: bool,
+    /// Number of orders placed during the day
+    pub(crate) orders: i64,
+    /// Number of orders placed during the run delivered during the day
+    pub(crate) delivered_orders: i64,
+    /// Number of delivered orders which arrived by their promised time
+    pub(crate) on_time_deliveries: i64,
+    /// Number of delivered orders which shared their courier with other orders
+    pub(crate) bundled_deliveries: i64,
+    /// Total seconds from placing to delivering the delivered orders
+    pub(crate) delivery_time_s: f64,
+    /// Grams of CO2 emitted for the delivered orders
+    pub(crate) co2_g: f64,
+}
+
+pub(crate) struct GreenDeliveryBuffer {
+    days: TimestampMillisecondBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    green: BooleanBuilder,
+    orders: Int64Builder,
+    delivered_orders: Int64Builder,
+    on_time_deliveries: Int64Builder,
+    bundled_deliveries: Int64Builder,
+    delivery_times: Float64Builder,
+    footprints: Float64Builder,
+}
+
+impl GreenDeliveryBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            days: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            green: BooleanBuilder::new(),
+            orders: Int64Builder::new(),
+            delivered_orders: Int64Builder::new(),
+            on_time_deliveries: Int64Builder::new(),
+            bundled_deliveries: Int64Builder::new(),
+            delivery_times: Float64Builder::new(),
+            footprints: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub(crate) fn push(&mut self, row: &GreenDeliveryDay) -> Result<()> {
+        self.days.append_value(row.day.timestamp_millis());
+        self.site_ids.append_value(row.site_id)?;
+        self.green.append_value(row.green);
+        self.orders.append_value(row.orders);
+        self.delivered_orders.append_value(row.delivered_orders);
+        self.on_time_deliveries.append_value(row.on_time_deliveries);
+        self.bundled_deliveries.append_value(row.bundled_deliveries);
+        self.delivery_times.append_value(row.delivery_time_s);
+        self.footprints.append_value(row.co2_g);
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            GREEN_DELIVERY_SCHEMA.clone(),
+            vec![
+                Arc::new(self.days.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.green.finish()),
+                Arc::new(self.orders.finish()),
+                Arc::new(self.delivered_orders.finish()),
+                Arc::new(self.on_time_deliveries.finish()),
+                Arc::new(self.bundled_deliveries.finish()),
+                Arc::new(self.delivery_times.finish()),
+                Arc::new(self.footprints.finish()),
+            ],
+        )?)
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    Float64Builder, Int64Builder, StringViewBuilder, TimestampMillisecondBuilder,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use h3o::CellIndex;
+
+use crate::{Money, Result};
+
+pub(crate) static ORDER_HEATMAP_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("cell", DataType::Int64, false),
+        Field::new("resolution", DataType::Int64, false),
+        Field::new(
+            "hour",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("orders", DataType::Int64, false),
+        Field::new("delivered_orders", DataType::Int64, false),
+        Field::new("currency", DataType::Utf8View, false),
+        Field::new("revenue", DataType::Float64, false),
+        Field::new("avg_delivery_time_s", DataType::Float64, true),
+    ]))
+});
+
+/// Orders placed with destinations in one H3 cell during one hour.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HeatmapCell {
+    pub(crate) cell: CellIndex,
+    /// Start of the hour the orders were placed in
+    pub(crate) hour: DateTime<Utc>,
+    pub(crate) orders: u64,
+    pub(crate) delivered_orders: u64,
+    /// Order totals converted into the base currency of the simulation
+    pub(crate) revenue: Money,
+    /// Sum of the times from placement to delivery of the delivered orders
+    pub(crate) delivery_time_s: f64,
+}
+
+impl HeatmapCell {
+    pub(crate) fn avg_delivery_time_s(&self) -> Option<f64> {
+        (self.delivered_orders > 0).then(|| self.delivery_time_s / self.delivered_orders as f64)
+    }
+}
+
+pub(crate) struct HeatmapBuffer {
+    cells: Int64Builder,
+    resolutions: Int64Builder,
+    hours: TimestampMillisecondBuilder,
+    orders: Int64Builder,
+    delivered_orders: Int64Builder,
+    currency: StringViewBuilder,
+    revenue: Float64Builder,
+    avg_delivery_times: Float64Builder,
+}
+
+impl HeatmapBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            cells: Int64Builder::new(),
+            resolutions: Int64Builder::new(),
+            hours: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            orders: Int64Builder::new(),
+            delivered_orders: Int64Builder::new(),
+            currency: StringViewBuilder::new(),
+            revenue: Float64Builder::new(),
+            avg_delivery_times: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, cell: &HeatmapCell) {
+        self.cells.append_value(u64::from(cell.cell) as i64);
+        self.resolutions
+            .append_value(u8::from(cell.cell.resolution()) as i64);
+        self.hours.append_value(cell.hour.timestamp_millis());
+        self.orders.append_value(cell.orders as i64);
+        self.delivered_orders
+            .append_value(cell.delivered_orders as i64);
+        self.currency.append_value(cell.revenue.currency());
+        self.revenue
+            .append_value(cell.revenue.round_cents().amount());
+        self.avg_delivery_times
+            .append_option(cell.avg_delivery_time_s());
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            ORDER_HEATMAP_SCHEMA.clone(),
+            vec![
+                Arc::new(self.cells.finish()),
+                Arc::new(self.resolutions.finish()),
+                Arc::new(self.hours.finish()),
+                Arc::new(self.orders.finish()),
+                Arc::new(self.delivered_orders.finish()),
+                Arc::new(self.currency.finish()),
+                Arc::new(self.revenue.finish()),
+                Arc::new(self.avg_delivery_times.finish()),
+            ],
+        )?)
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, BooleanBuilder, FixedSizeBinaryBuilder, Float64Builder, Int64Builder,
+    StringViewBuilder, TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::Result;
+use crate::idents::{BrandId, MenuItemId, PersonId, SessionId, SiteId};
+
+pub(crate) static IMPRESSIONS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::FixedSizeBinary(16), false).with_extension_type(UuidExtension),
+        Field::new("session_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("person_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("brand_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("menu_item_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("surface", DataType::Utf8View, false),
+        Field::new("position", DataType::Int64, false),
+        Field::new("propensity", DataType::Float64, false),
+        Field::new(
+            "shown_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("clicked", DataType::Boolean, false),
+        Field::new("ordered", DataType::Boolean, false),
+    ]))
+});
+
+/// A menu item shown to a customer during an app session.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Impression {
+    pub(crate) id: Uuid,
+    pub(crate) session_id: SessionId,
+    pub(crate) person_id: PersonId,
+    pub(crate) site_id: SiteId,
+    pub(crate) item: (BrandId, MenuItemId),
+    /// Part of the app the item was shown in, e.g. `recommendation` or `search`
+    pub(crate) surface: String,
+    /// Rank of the item within the surface, starting at 1
+    pub(crate) position: u32,
+    /// Probability with which the logging policy showed the item at the position
+    pub(crate) propensity: f64,
+    pub(crate) shown_at: DateTime<Utc>,
+    pub(crate) clicked: bool,
+    pub(crate) ordered: bool,
+}
+
+pub(crate) struct ImpressionBuffer {
+    ids: FixedSizeBinaryBuilder,
+    session_ids: FixedSizeBinaryBuilder,
+    person_ids: FixedSizeBinaryBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    brand_ids: FixedSizeBinaryBuilder,
+    menu_item_ids: FixedSizeBinaryBuilder,
+    surfaces: StringViewBuilder,
+    positions: Int64Builder,
+    propensities: Float64Builder,
+    shown_at: TimestampMillisecondBuilder,
+    clicked: BooleanBuilder,
+    ordered: BooleanBuilder,
+}
+
+impl ImpressionBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            ids: FixedSizeBinaryBuilder::new(16),
+            session_ids: FixedSizeBinaryBuilder::new(16),
+            person_ids: FixedSizeBinaryBuilder::new(16),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            brand_ids: FixedSizeBinaryBuilder::new(16),
+            menu_item_ids: FixedSizeBinaryBuilder::new(16),
+            surfaces: StringViewBuilder::new(),
+            positions: Int64Builder::new(),
+            propensities: Float64Builder::new(),
+            shown_at: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            clicked: BooleanBuilder::new(),
+            ordered: BooleanBuilder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub(crate) fn push(&mut self, impression: &Impression) -> Result<()> {
+        self.ids.append_value(impression.id)?;
+        self.session_ids.append_value(impression.session_id)?;
+        self.person_ids.append_value(impression.person_id)?;
+        self.site_ids.append_value(impression.site_id)?;
+        self.brand_ids.append_value(impression.item.0)?;
+        self.menu_item_ids.append_value(impression.item.1)?;
+        self.surfaces.append_value(&impression.surface);
+        self.positions.append_value(impression.position as i64);
+        self.propensities.append_value(impression.propensity);
+        self.shown_at
+            .append_value(impression.shown_at.timestamp_millis());
+        self.clicked.append_value(impression.clicked);
+        self.ordered.append_value(impression.ordered);
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            IMPRESSIONS_SCHEMA.clone(),
+            vec![
+                Arc::new(self.ids.finish()),
+                Arc::new(self.session_ids.finish()),
+                Arc::new(self.person_ids.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.brand_ids.finish()),
+                Arc::new(self.menu_item_ids.finish()),
+                Arc::new(self.surfaces.finish()),
+                Arc::new(self.positions.finish()),
+                Arc::new(self.propensities.finish()),
+                Arc::new(self.shown_at.finish()),
+                Arc::new(self.clicked.finish()),
+                Arc::new(self.ordered.finish()),
+            ],
+        )?)
+    }
+}
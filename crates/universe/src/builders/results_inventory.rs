@@ -0,0 +1,143 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, BooleanBuilder, FixedSizeBinaryBuilder, Float64Builder, Int64Builder,
+    StringViewBuilder, TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Datelike as _, Utc, Weekday};
+
+use crate::Result;
+use crate::idents::SiteId;
+
+pub(crate) static INVENTORY_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "day",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("ingredient", DataType::Utf8View, false),
+        Field::new("unit", DataType::Utf8View, false),
+        Field::new("opening_stock", DataType::Float64, false),
+        Field::new("received", DataType::Float64, false),
+        Field::new("consumed", DataType::Float64, false),
+        Field::new("unmet", DataType::Float64, false),
+        Field::new("closing_stock", DataType::Float64, false),
+        Field::new("day_of_week", DataType::Int64, false),
+        Field::new("week_of_year", DataType::Int64, false),
+        Field::new("month", DataType::Int64, false),
+        Field::new("day_of_year", DataType::Int64, false),
+        Field::new("is_weekend", DataType::Boolean, false),
+    ]))
+});
+
+/// Stock movements of one ingredient at a site during a simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IngredientDay {
+    /// Midnight starting the day
+    pub(crate) day: DateTime<Utc>,
+    pub(crate) site_id: SiteId,
+    /// Reference of the ingredient in the ingredient catalog
+    pub(crate) ingredient: String,
+    /// Unit all quantities of the row are measured in
+    pub(crate) unit: String,
+    pub(crate) opening_stock: f64,
+    /// Quantity delivered to the site
+    pub(crate) received: f64,
+    /// Quantity used to prepare order lines
+    pub(crate) consumed: f64,
+    /// Quantity required to prepare order lines beyond the stock on hand
+    pub(crate) unmet: f64,
+    pub(crate) closing_stock: f64,
+}
+
+pub(crate) struct InventoryBuffer {
+    days: TimestampMillisecondBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    ingredients: StringViewBuilder,
+    units: StringViewBuilder,
+    opening_stock: Float64Builder,
+    received: Float64Builder,
+    consumed: Float64Builder,
+    unmet: Float64Builder,
+    closing_stock: Float64Builder,
+    days_of_week: Int64Builder,
+    weeks_of_year: Int64Builder,
+    months: Int64Builder,
+    days_of_year: Int64Builder,
+    weekends: BooleanBuilder,
+}
+
+impl InventoryBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            days: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            ingredients: StringViewBuilder::new(),
+            units: StringViewBuilder::new(),
+            opening_stock: Float64Builder::new(),
+            received: Float64Builder::new(),
+            consumed: Float64Builder::new(),
+            unmet: Float64Builder::new(),
+            closing_stock: Float64Builder::new(),
+            days_of_week: Int64Builder::new(),
+            weeks_of_year: Int64Builder::new(),
+            months: Int64Builder::new(),
+            days_of_year: Int64Builder::new(),
+            weekends: BooleanBuilder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub(crate) fn push(&mut self, row: &IngredientDay) -> Result<()> {
+        self.days.append_value(row.day.timestamp_millis());
+        self.site_ids.append_value(row.site_id)?;
+        self.ingredients.append_value(&row.ingredient);
+        self.units.append_value(&row.unit);
+        self.opening_stock.append_value(row.opening_stock);
+        self.received.append_value(row.received);
+        self.consumed.append_value(row.consumed);
+        self.unmet.append_value(row.unmet);
+        self.closing_stock.append_value(row.closing_stock);
+        let weekday = row.day.weekday();
+        self.days_of_week
+            .append_value(weekday.number_from_monday() as i64);
+        self.weeks_of_year
+            .append_value(row.day.iso_week().week() as i64);
+        self.months.append_value(row.day.month() as i64);
+        self.days_of_year.append_value(row.day.ordinal() as i64);
+        self.weekends
+            .append_value(matches!(weekday, Weekday::Sat | Weekday::Sun));
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            INVENTORY_SCHEMA.clone(),
+            vec![
+                Arc::new(self.days.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.ingredients.finish()),
+                Arc::new(self.units.finish()),
+                Arc::new(self.opening_stock.finish()),
+                Arc::new(self.received.finish()),
+                Arc::new(self.consumed.finish()),
+                Arc::new(self.unmet.finish()),
+                Arc::new(self.closing_stock.finish()),
+                Arc::new(self.days_of_week.finish()),
+                Arc::new(self.weeks_of_year.finish()),
+                Arc::new(self.months.finish()),
+                Arc::new(self.days_of_year.finish()),
+                Arc::new(self.weekends.finish()),
+            ],
+        )?)
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::builder::{
+    ArrayBuilder as _, FixedSizeBinaryBuilder, Float64Builder, Int64Builder,
+    TimestampMillisecondBuilder,
+};
+use arrow::array::{RecordBatch, StringViewBuilder};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::idents::{OrderId, PersonId, SiteId};
+use crate::{Currency, Money, Result};
+
+pub(crate) static INVOICES_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::FixedSizeBinary(16), false).with_extension_type(UuidExtension),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("invoice_number", DataType::Int64, false),
+        Field::new("order_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("customer_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new(
+            "issued_at",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("currency", DataType::Utf8View, false),
+        Field::new("net_amount", DataType::Float64, false),
+        Field::new("tax_rate", DataType::Float64, false),
+        Field::new("tax_amount", DataType::Float64, false),
+        Field::new("gross_amount", DataType::Float64, false),
+        Field::new("tip", DataType::Float64, false),
+        Field::new("total", DataType::Float64, false),
+        Field::new("base_currency", DataType::Utf8View, false),
+        Field::new("base_total", DataType::Float64, false),
+    ]))
+});
+
+/// A single invoice for a delivered order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Invoice {
+    pub(crate) id: Uuid,
+    pub(crate) site_id: SiteId,
+    pub(crate) invoice_number: u64,
+    pub(crate) order_id: OrderId,
+    pub(crate) customer_id: PersonId,
+    pub(crate) issued_at: DateTime<Utc>,
+    pub(crate) currency: Currency,
+    pub(crate) net_amount: f64,
+    pub(crate) tax_rate: f64,
+    pub(crate) tax_amount: f64,
+    pub(crate) gross_amount: f64,
+    pub(crate) tip: f64,
+    /// Total converted into the base currency of the simulation
+    pub(crate) base_total: Money,
+}
+
+impl Invoice {
+    /// Amount charged to the customer, including the tip.
+    pub(crate) fn total(&self) -> f64 {
+        ((self.gross_amount + self.tip) * 100.0).round() / 100.0
+    }
+}
+
+pub(crate) struct InvoiceBuffer {
+    ids: FixedSizeBinaryBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    invoice_numbers: Int64Builder,
+    order_ids: FixedSizeBinaryBuilder,
+    customer_ids: FixedSizeBinaryBuilder,
+    issued_at: TimestampMillisecondBuilder,
+    currency: StringViewBuilder,
+    net_amounts: Float64Builder,
+    tax_rates: Float64Builder,
+    tax_amounts: Float64Builder,
+    gross_amounts: Float64Builder,
+    tips: Float64Builder,
+    totals: Float64Builder,
+    base_currency: StringViewBuilder,
+    base_totals: Float64Builder,
+}
+
+impl InvoiceBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            ids: FixedSizeBinaryBuilder::new(16),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            invoice_numbers: Int64Builder::new(),
+            order_ids: FixedSizeBinaryBuilder::new(16),
+            customer_ids: FixedSizeBinaryBuilder::new(16),
+            issued_at: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            currency: StringViewBuilder::new(),
+            net_amounts: Float64Builder::new(),
+            tax_rates: Float64Builder::new(),
+            tax_amounts: Float64Builder::new(),
+            gross_amounts: Float64Builder::new(),
+            tips: Float64Builder::new(),
+            totals: Float64Builder::new(),
+            base_currency: StringViewBuilder::new(),
+            base_totals: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.invoice_numbers.len()
+    }
+
+    pub(crate) fn push(&mut self, invoice: &Invoice) -> Result<()> {
+        self.ids.append_value(invoice.id)?;
+        self.site_ids.append_value(invoice.site_id)?;
+        self.invoice_numbers
+            .append_value(invoice.invoice_number as i64);
+        self.order_ids.append_value(invoice.order_id)?;
+        self.customer_ids.append_value(invoice.customer_id)?;
+        self.issued_at
+            .append_value(invoice.issued_at.timestamp_millis());
+        self.currency.append_value(invoice.currency);
+        self.net_amounts.append_value(invoice.net_amount);
+        self.tax_rates.append_value(invoice.tax_rate);
+        self.tax_amounts.append_value(invoice.tax_amount);
+        self.gross_amounts.append_value(invoice.gross_amount);
+        self.tips.append_value(invoice.tip);
+        self.totals.append_value(invoice.total());
+        self.base_currency
+            .append_value(invoice.base_total.currency());
+        self.base_totals.append_value(invoice.base_total.amount());
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            INVOICES_SCHEMA.clone(),
+            vec![
+                Arc::new(self.ids.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.invoice_numbers.finish()),
+                Arc::new(self.order_ids.finish()),
+                Arc::new(self.customer_ids.finish()),
+                Arc::new(self.issued_at.finish()),
+                Arc::new(self.currency.finish()),
+                Arc::new(self.net_amounts.finish()),
+                Arc::new(self.tax_rates.finish()),
+                Arc::new(self.tax_amounts.finish()),
+                Arc::new(self.gross_amounts.finish()),
+                Arc::new(self.tips.finish()),
+                Arc::new(self.totals.finish()),
+                Arc::new(self.base_currency.finish()),
+                Arc::new(self.base_totals.finish()),
+            ],
+        )?)
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    FixedSizeBinaryBuilder, Float64Builder, Int64Builder, StringViewBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+
+use crate::idents::SiteId;
+use crate::{Cuisine, Money, Result};
+
+pub(crate) static MARKET_SHARE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("cuisine", DataType::Utf8View, true),
+        Field::new("orders", DataType::Int64, false),
+        Field::new("items", DataType::Int64, false),
+        Field::new("currency", DataType::Utf8View, false),
+        Field::new("revenue", DataType::Float64, false),
+        Field::new("item_share", DataType::Float64, false),
+        Field::new("revenue_share", DataType::Float64, false),
+    ]))
+});
+
+/// Items of one cuisine ordered at a site.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CuisineSales {
+    pub(crate) site_id: SiteId,
+    /// Cuisine of the brands, `None` for uncategorized brands
+    pub(crate) cuisine: Option<Cuisine>,
+    /// Orders with at least one item of the cuisine
+    pub(crate) orders: u64,
+    pub(crate) items: u64,
+    /// Share of the order totals attributed to the items, in the base currency of the simulation
+    pub(crate) revenue: Money,
+}
+
+pub(crate) struct MarketShareBuffer {
+    site_ids: FixedSizeBinaryBuilder,
+    cuisines: StringViewBuilder,
+    orders: Int64Builder,
+    items: Int64Builder,
+    currency: StringViewBuilder,
+    revenue: Float64Builder,
+    item_shares: Float64Builder,
+    revenue_shares: Float64Builder,
+}
+
+impl MarketShareBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            cuisines: StringViewBuilder::new(),
+            orders: Int64Builder::new(),
+            items: Int64Builder::new(),
+            currency: StringViewBuilder::new(),
+            revenue: Float64Builder::new(),
+            item_shares: Float64Builder::new(),
+            revenue_shares: Float64Builder::new(),
+        }
+    }
+
+    /// Add the sales of a cuisine, given the items and revenue of all cuisines at the site.
+    pub(crate) fn push(
+        &mut self,
+        sales: &CuisineSales,
+        site_items: u64,
+        site_revenue: f64,
+    ) -> Result<()> {
+        let share = |part: f64, total: f64| if total > 0.0 { part / total } else { 0.0 };
+        self.site_ids.append_value(sales.site_id)?;
+        self.cuisines
+            .append_option(sales.cuisine.as_ref().map(|c| c.as_ref()));
+        self.orders.append_value(sales.orders as i64);
+        self.items.append_value(sales.items as i64);
+        self.currency.append_value(sales.revenue.currency());
+        self.revenue
+            .append_value(sales.revenue.round_cents().amount());
+        self.item_shares
+            .append_value(share(sales.items as f64, site_items as f64));
+        self.revenue_shares
+            .append_value(share(sales.revenue.amount(), site_revenue));
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            MARKET_SHARE_SCHEMA.clone(),
+            vec![
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.cuisines.finish()),
+                Arc::new(self.orders.finish()),
+                Arc::new(self.items.finish()),
+                Arc::new(self.currency.finish()),
+                Arc::new(self.revenue.finish()),
+                Arc::new(self.item_shares.finish()),
+                Arc::new(self.revenue_shares.finish()),
+            ],
+        )?)
+    }
+}
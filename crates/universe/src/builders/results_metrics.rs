@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::{Arc, LazyLock};
 
 use arrow::array::builder::{Int64Builder, TimestampMillisecondBuilder};
@@ -5,7 +6,7 @@ use arrow::array::{RecordBatch, StringViewBuilder};
 use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
 use chrono::{DateTime, Utc};
 
-use crate::{EventStats, Result};
+use crate::{EventStats, Result, StepTimings};
 
 pub(crate) static METRICS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     Arc::new(Schema::new(vec![
@@ -61,6 +62,48 @@ impl EventStatsBuffer {
         self.value
             .append_value(stats.num_order_lines_updated as i64);
 
+        self.timestamp.append_value(ts);
+        self.source.append_value(source.as_ref());
+        self.label.append_value("site_check_ins");
+        self.value.append_value(stats.num_site_check_ins as i64);
+
+        self.timestamp.append_value(ts);
+        self.source.append_value(source.as_ref());
+        self.label.append_value("site_check_outs");
+        self.value.append_value(stats.num_site_check_outs as i64);
+
+        Ok(())
+    }
+
+    /// Record the duration of each step phase in microseconds.
+    pub(crate) fn push_timings(
+        &mut self,
+        current_time: DateTime<Utc>,
+        timings: &StepTimings,
+    ) -> Result<()> {
+        let ts = current_time.timestamp_millis();
+        for (phase, duration) in timings.iter() {
+            self.timestamp.append_value(ts);
+            self.source.append_value("step_timings");
+            self.label.append_value(format!("{phase}_us"));
+            self.value.append_value(duration.as_micros() as i64);
+        }
+        Ok(())
+    }
+
+    /// Record the number of courier assignments by the ring around the site searched.
+    pub(crate) fn push_assignments_by_ring(
+        &mut self,
+        current_time: DateTime<Utc>,
+        assignments: &BTreeMap<u32, usize>,
+    ) -> Result<()> {
+        let ts = current_time.timestamp_millis();
+        for (ring, count) in assignments {
+            self.timestamp.append_value(ts);
+            self.source.append_value("courier_search");
+            self.label.append_value(format!("assigned_ring_{ring}"));
+            self.value.append_value(*count as i64);
+        }
         Ok(())
     }
 
@@ -0,0 +1,95 @@
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::RecordBatch;
+use arrow::array::builder::{
+    ArrayBuilder as _, FixedSizeBinaryBuilder, Float64Builder, StringViewBuilder,
+    TimestampMillisecondBuilder,
+};
+use arrow_schema::extension::Uuid as UuidExtension;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+
+use crate::Result;
+use crate::idents::SiteId;
+
+pub(crate) static WASTE_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "day",
+            DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("site_id", DataType::FixedSizeBinary(16), false)
+            .with_extension_type(UuidExtension),
+        Field::new("ingredient", DataType::Utf8View, false),
+        Field::new("unit", DataType::Utf8View, false),
+        Field::new("expired", DataType::Float64, false),
+        Field::new("discarded", DataType::Float64, false),
+    ]))
+});
+
+/// Ingredient stock of a site wasted during a simulated day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IngredientWaste {
+    /// Midnight starting the day
+    pub(crate) day: DateTime<Utc>,
+    pub(crate) site_id: SiteId,
+    /// Reference of the ingredient in the ingredient catalog
+    pub(crate) ingredient: String,
+    /// Unit all quantities of the row are measured in
+    pub(crate) unit: String,
+    /// Quantity which passed its shelf life
+    pub(crate) expired: f64,
+    /// Quantity discarded at the end of the day
+    pub(crate) discarded: f64,
+}
+
+pub(crate) struct WasteBuffer {
+    days: TimestampMillisecondBuilder,
+    site_ids: FixedSizeBinaryBuilder,
+    ingredients: StringViewBuilder,
+    units: StringViewBuilder,
+    expired: Float64Builder,
+    discarded: Float64Builder,
+}
+
+impl WasteBuffer {
+    pub(crate) fn new() -> Self {
+        Self {
+            days: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            site_ids: FixedSizeBinaryBuilder::new(16),
+            ingredients: StringViewBuilder::new(),
+            units: StringViewBuilder::new(),
+            expired: Float64Builder::new(),
+            discarded: Float64Builder::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.days.len()
+    }
+
+    pub(crate) fn push(&mut self, row: &IngredientWaste) -> Result<()> {
+        self.days.append_value(row.day.timestamp_millis());
+        self.site_ids.append_value(row.site_id)?;
+        self.ingredients.append_value(&row.ingredient);
+        self.units.append_value(&row.unit);
+        self.expired.append_value(row.expired);
+        self.discarded.append_value(row.discarded);
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            WASTE_SCHEMA.clone(),
+            vec![
+                Arc::new(self.days.finish()),
+                Arc::new(self.site_ids.finish()),
+                Arc::new(self.ingredients.finish()),
+                Arc::new(self.units.finish()),
+                Arc::new(self.expired.finish()),
+                Arc::new(self.discarded.finish()),
+            ],
+        )?)
+    }
+}
@@ -8,7 +8,7 @@ use arrow_schema::extension::{Json as JsonExtension, Uuid as UuidExtension};
 use crate::Error;
 use crate::error::Result;
 use crate::idents::{BrandId, KitchenId, MenuItemId, SiteId, StationId};
-use crate::models::{Brand, SiteSetup};
+use crate::models::{Brand, MenuItem, SiteSetup};
 use crate::state::ObjectLabel;
 
 pub(crate) static OBJECTS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
@@ -24,6 +24,7 @@ pub(crate) static OBJECTS_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         ),
         Field::new("properties", DataType::LargeUtf8, true)
             .with_extension_type(JsonExtension::default()),
+        Field::new("uri", DataType::Utf8, true),
     ]))
 });
 
@@ -33,6 +34,7 @@ pub struct ObjectDataBuilder {
     name: ListBuilder<StringBuilder>,
     label: StringViewBuilder,
     properties: LargeStringBuilder,
+    uri: StringBuilder,
 }
 
 impl Default for ObjectDataBuilder {
@@ -49,31 +51,54 @@ impl ObjectDataBuilder {
             name: ListBuilder::new(StringBuilder::new()),
             label: StringViewBuilder::new(),
             properties: LargeStringBuilder::new(),
+            uri: StringBuilder::new(),
         }
     }
 
-    pub fn append_brand(&mut self, brand_id: &BrandId, brand: &Brand) {
-        self.id.append_value(brand_id).unwrap();
+    pub fn append_brand(&mut self, brand_id: &BrandId, brand: &Brand) -> Result<()> {
+        self.id.append_value(brand_id)?;
         self.parent_id.append_null();
         self.label.append_value(ObjectLabel::Brand);
         self.name.append_value([Some("brands"), Some(&brand.name)]);
-        self.properties.append_null();
+        // menu items are stored as objects of their own
+        let properties = Brand {
+            items: Vec::new(),
+            ..brand.clone()
+        };
+        self.properties
+            .append_value(serde_json::to_string(&properties)?);
+        self.uri.append_value(BrandId::uri_ref(&brand.name));
 
         for item in &brand.items {
-            let item_name = format!("brands/{}/items/{}", brand.name, item.name);
-            let item_id = MenuItemId::from_uri_ref(&item_name);
-            self.id.append_value(item_id).unwrap();
-            self.parent_id.append_value(brand_id).unwrap();
-            self.label.append_value(ObjectLabel::MenuItem);
-            self.name.append_value([
-                Some("brands"),
-                Some(&brand.name),
-                Some("items"),
-                Some(&item.name),
-            ]);
-            self.properties
-                .append_value(serde_json::to_string(&item).unwrap());
+            // items without their own currency are priced in the brand currency
+            if item.currency.is_none() && brand.currency.is_some() {
+                let item = MenuItem {
+                    currency: brand.currency.clone(),
+                    ..item.clone()
+                };
+                self.append_menu_item(brand_id, &brand.name, &item);
+            } else {
+                self.append_menu_item(brand_id, &brand.name, item);
+            }
         }
+        Ok(())
+    }
+
+    pub fn append_menu_item(&mut self, brand_id: &BrandId, brand_name: &str, item: &MenuItem) {
+        let item_id = MenuItemId::from_names(brand_name, &item.name);
+        self.id.append_value(item_id).unwrap();
+        self.parent_id.append_value(brand_id).unwrap();
+        self.label.append_value(ObjectLabel::MenuItem);
+        self.name.append_value([
+            Some("brands"),
+            Some(brand_name),
+            Some("menu_items"),
+            Some(&item.name),
+        ]);
+        self.properties
+            .append_value(serde_json::to_string(&item).unwrap());
+        self.uri
+            .append_value(MenuItemId::uri_ref(brand_name, &item.name));
     }
 
     pub fn append_site_info(&mut self, site: &SiteSetup) -> Result<()> {
@@ -90,6 +115,7 @@ impl ObjectDataBuilder {
             .append_value([Some("sites"), Some(&site_info.name)]);
         self.properties
             .append_value(serde_json::to_string(site_info)?);
+        self.uri.append_value(SiteId::uri_ref(&site_info.name));
 
         for kitchen in &site.kitchens {
             let kitchen_info = kitchen
@@ -108,6 +134,8 @@ impl ObjectDataBuilder {
                 Some(&kitchen_info.name),
             ]);
             self.properties.append_null();
+            self.uri
+                .append_value(KitchenId::uri_ref(&site_info.name, &kitchen_info.name));
 
             for station in &kitchen.stations {
                 let station_id: StationId = uuid::Uuid::parse_str(&station.id)?.into();
@@ -124,6 +152,11 @@ impl ObjectDataBuilder {
                 ]);
                 self.properties
                     .append_value(serde_json::to_string(station).unwrap());
+                self.uri.append_value(StationId::uri_ref(
+                    &site_info.name,
+                    &kitchen_info.name,
+                    &station.name,
+                ));
             }
         }
 
@@ -136,10 +169,11 @@ impl ObjectDataBuilder {
         let label = Arc::new(self.label.finish());
         let name = Arc::new(self.name.finish());
         let properties = Arc::new(self.properties.finish());
+        let uri = Arc::new(self.uri.finish());
 
         Ok(RecordBatch::try_new(
             OBJECTS_SCHEMA.clone(),
-            vec![id, parent_id, label, name, properties],
+            vec![id, parent_id, label, name, properties, uri],
         )?)
     }
 }
@@ -6,11 +6,12 @@ use arrow::array::builder::{
 };
 use arrow_schema::extension::Uuid as UuidExtension;
 use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use geo_traits::PointTrait as _;
 use h3o::LatLng;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::idents::{BrandId, MenuItemId, OrderId, OrderLineId, PersonId, SiteId};
-use crate::{OrderData, OrderLineStatus, OrderStatus};
+use crate::{Money, OrderCreatedPayload, OrderData, OrderLineStatus, OrderStatus};
 
 pub struct OrderDataBuilder {
     orders: OrderBuilder,
@@ -38,9 +39,33 @@ impl OrderDataBuilder {
         destination: LatLng,
         order: &[(BrandId, MenuItemId)],
     ) -> Result<()> {
-        let order_id = self.orders.add_order(site_id, person_id, destination)?;
-        for (brand_id, menu_item_id) in order {
-            self.lines.add_line(order_id, brand_id, menu_item_id)?;
+        let order_id = OrderId::new();
+        self.orders
+            .add_order(order_id, site_id, person_id, destination, None)?;
+        self.add_lines(order_id, order)
+    }
+
+    /// Add the order of an `order_created` event with its id, total, tip and priority.
+    pub fn add_created_order(&mut self, order: &OrderCreatedPayload) -> Result<()> {
+        let destination = order
+            .destination
+            .coord()
+            .ok_or_else(|| Error::invalid_data("no destination coordinates"))?
+            .try_into()?;
+        self.orders.add_order(
+            order.order_id,
+            order.site_id,
+            order.person_id,
+            destination,
+            Some(order),
+        )?;
+        self.add_lines(order.order_id, &order.items)
+    }
+
+    fn add_lines(&mut self, order_id: OrderId, order: &[(BrandId, MenuItemId)]) -> Result<()> {
+        for (index, (brand_id, menu_item_id)) in order.iter().enumerate() {
+            let id = OrderLineId::for_order(&order_id, index);
+            self.lines.add_line(id, order_id, brand_id, menu_item_id)?;
         }
         Ok(())
     }
@@ -84,11 +109,11 @@ impl OrderLineBuilder {
 
     pub fn add_line(
         &mut self,
+        id: OrderLineId,
         order_id: impl AsRef<[u8]>,
         brand_id: impl AsRef<[u8]>,
         menu_item_id: impl AsRef<[u8]>,
     ) -> Result<OrderLineId, ArrowError> {
-        let id = OrderLineId::new();
         self.ids.append_value(id)?;
         self.order_ids.append_value(order_id)?;
         self.brand_ids.append_value(brand_id)?;
@@ -124,6 +149,12 @@ pub(crate) static ORDER_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
             2,
             false,
         ),
+        Field::new("total", DataType::Float64, true),
+        Field::new("tip", DataType::Float64, true),
+        Field::new("currency", DataType::Utf8, true),
+        Field::new("priority", DataType::Utf8, true),
+        Field::new("co2_g", DataType::Float64, true),
+        // status column MUST be the last column - or update the order data update method.
         Field::new("status", DataType::Utf8, false),
     ];
     SchemaRef::new(Schema::new(fields))
@@ -134,6 +165,11 @@ pub(crate) struct OrderBuilder {
     site_ids: FixedSizeBinaryBuilder,
     customer_ids: FixedSizeBinaryBuilder,
     destination: FixedSizeListBuilder<Float64Builder>,
+    totals: Float64Builder,
+    tips: Float64Builder,
+    currencies: StringBuilder,
+    priorities: StringBuilder,
+    footprints: Float64Builder,
     statuses: StringBuilder,
 }
 
@@ -145,25 +181,40 @@ impl OrderBuilder {
             customer_ids: FixedSizeBinaryBuilder::new(16),
             destination: FixedSizeListBuilder::new(Float64Builder::new(), 2)
                 .with_field(Field::new("item", DataType::Float64, false)),
+            totals: Float64Builder::new(),
+            tips: Float64Builder::new(),
+            currencies: StringBuilder::new(),
+            priorities: StringBuilder::new(),
+            footprints: Float64Builder::new(),
             statuses: StringBuilder::new(),
         }
     }
 
     pub fn add_order(
         &mut self,
+        id: OrderId,
         site_id: impl AsRef<[u8]>,
         customer_id: impl AsRef<[u8]>,
         destination: LatLng,
-    ) -> Result<OrderId, ArrowError> {
-        let id = OrderId::new();
+        created: Option<&OrderCreatedPayload>,
+    ) -> Result<(), ArrowError> {
+        let total = created.map(|order| Money::new(order.total, order.currency));
         self.ids.append_value(id)?;
         self.site_ids.append_value(site_id)?;
         self.customer_ids.append_value(customer_id)?;
         self.destination.values().append_value(destination.lat());
         self.destination.values().append_value(destination.lng());
         self.destination.append(true);
+        self.totals.append_option(total.map(|total| total.amount()));
+        self.tips.append_option(created.and_then(|order| order.tip));
+        self.currencies
+            .append_option(total.map(|total| total.currency()));
+        self.priorities
+            .append_option(created.map(|order| order.priority.as_ref()));
+        // the footprint is known once the delivery started
+        self.footprints.append_null();
         self.statuses.append_value(OrderStatus::Submitted.as_ref());
-        Ok(id)
+        Ok(())
     }
 
     pub fn finish(mut self) -> Result<RecordBatch, ArrowError> {
@@ -174,6 +225,11 @@ impl OrderBuilder {
                 Arc::new(self.site_ids.finish()),
                 Arc::new(self.customer_ids.finish()),
                 Arc::new(self.destination.finish()),
+                Arc::new(self.totals.finish()),
+                Arc::new(self.tips.finish()),
+                Arc::new(self.currencies.finish()),
+                Arc::new(self.priorities.finish()),
+                Arc::new(self.footprints.finish()),
                 Arc::new(self.statuses.finish()),
             ],
         )
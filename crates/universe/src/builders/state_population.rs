@@ -1,20 +1,25 @@
 use std::sync::{Arc, LazyLock};
 
-use arrow::array::builder::{FixedSizeBinaryBuilder, StringBuilder};
+use arrow::array::builder::{
+    BooleanBuilder, FixedSizeBinaryBuilder, Float64Builder, ListBuilder, StringBuilder,
+    UInt8Builder,
+};
 use arrow::array::{ArrayRef, DictionaryArray, RecordBatch, StringViewBuilder, StructArray};
+use arrow::buffer::NullBuffer;
 use arrow::datatypes::{DataType, Field, Int8Type, Schema, SchemaRef};
 use arrow_schema::extension::Uuid;
 use fake::Fake;
-use geo::{BoundingRect, Centroid, Contains, Point};
+use geo::{BoundingRect, Contains, Point};
 use geoarrow::array::PointBuilder;
 use geoarrow_array::IntoArrow;
 use geoarrow_schema::{Dimension, PointType};
 use h3o::{LatLng, Resolution, geom::SolventBuilder};
+use rand::SeedableRng as _;
 use rand::distr::{Distribution, Uniform};
-use rand::rngs::ThreadRng;
+use rand::rngs::StdRng;
 
 use crate::idents::PersonId;
-use crate::state::PersonState;
+use crate::state::{DailySchedule, Demographics, FoodPreferences, PersonState};
 use crate::{Error, Result};
 use crate::{PersonRole, PersonStatusFlag};
 
@@ -43,7 +48,7 @@ struct PropertiesBuilder {
     emails: StringViewBuilder,
     cc_numbers: StringViewBuilder,
 
-    rng: ThreadRng,
+    rng: StdRng,
 }
 
 impl Default for PropertiesBuilder {
@@ -59,10 +64,15 @@ impl PropertiesBuilder {
             last_names: StringViewBuilder::new(),
             emails: StringViewBuilder::new(),
             cc_numbers: StringViewBuilder::new(),
-            rng: rand::rng(),
+            rng: StdRng::from_rng(&mut rand::rng()),
         }
     }
 
+    fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
     fn add_entry(&mut self) {
         let gen_first_name = fake::faker::name::en::FirstName();
         let gen_last_name = fake::faker::name::en::LastName();
@@ -97,6 +107,244 @@ impl PropertiesBuilder {
     }
 }
 
+/// Daily routine of customers, see [`DailySchedule`]; couriers have none.
+pub(crate) static POPULATION_SCHEDULE_FIELD: LazyLock<Field> = LazyLock::new(|| {
+    Field::new(
+        "schedule",
+        DataType::Struct(
+            vec![
+                Field::new("wake", DataType::Float64, false),
+                Field::new("work_start", DataType::Float64, true),
+                Field::new("work_end", DataType::Float64, true),
+                Field::new("breakfast", DataType::Float64, false),
+                Field::new("lunch", DataType::Float64, false),
+                Field::new("dinner", DataType::Float64, false),
+                Field::new("sleep", DataType::Float64, false),
+            ]
+            .into(),
+        ),
+        true,
+    )
+});
+
+struct ScheduleBuilder {
+    wake: Float64Builder,
+    work_start: Float64Builder,
+    work_end: Float64Builder,
+    breakfast: Float64Builder,
+    lunch: Float64Builder,
+    dinner: Float64Builder,
+    sleep: Float64Builder,
+    /// Whether each person has a schedule
+    valid: Vec<bool>,
+
+    rng: StdRng,
+}
+
+impl ScheduleBuilder {
+    fn new() -> Self {
+        Self {
+            wake: Float64Builder::new(),
+            work_start: Float64Builder::new(),
+            work_end: Float64Builder::new(),
+            breakfast: Float64Builder::new(),
+            lunch: Float64Builder::new(),
+            dinner: Float64Builder::new(),
+            sleep: Float64Builder::new(),
+            valid: Vec::new(),
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    fn with_seed(mut self, seed: u64) -> Self {
+        // schedules are drawn independently of the properties sampled from the same seed
+        self.rng = StdRng::seed_from_u64(seed.rotate_left(32));
+        self
+    }
+
+    /// Add a freshly sampled schedule.
+    fn add_entry(&mut self) {
+        let schedule = DailySchedule::sample(&mut self.rng);
+        self.wake.append_value(schedule.wake);
+        self.work_start.append_option(schedule.work_start);
+        self.work_end.append_option(schedule.work_end);
+        self.breakfast.append_value(schedule.breakfast);
+        self.lunch.append_value(schedule.lunch);
+        self.dinner.append_value(schedule.dinner);
+        self.sleep.append_value(schedule.sleep);
+        self.valid.push(true);
+    }
+
+    fn add_none(&mut self) {
+        for builder in [
+            &mut self.wake,
+            &mut self.breakfast,
+            &mut self.lunch,
+            &mut self.dinner,
+            &mut self.sleep,
+        ] {
+            builder.append_value(0.0);
+        }
+        self.work_start.append_null();
+        self.work_end.append_null();
+        self.valid.push(false);
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        let fields = match POPULATION_SCHEDULE_FIELD.data_type() {
+            DataType::Struct(fields) => fields.clone(),
+            _ => panic!("Invalid data type for population schedules"),
+        };
+        Arc::new(StructArray::new(
+            fields,
+            vec![
+                Arc::new(self.wake.finish()),
+                Arc::new(self.work_start.finish()),
+                Arc::new(self.work_end.finish()),
+                Arc::new(self.breakfast.finish()),
+                Arc::new(self.lunch.finish()),
+                Arc::new(self.dinner.finish()),
+                Arc::new(self.sleep.finish()),
+            ],
+            Some(NullBuffer::from(std::mem::take(&mut self.valid))),
+        ))
+    }
+}
+
+struct DemographicsBuilder {
+    age_bracket: StringBuilder,
+    household_size: UInt8Builder,
+    income_band: StringBuilder,
+
+    rng: StdRng,
+}
+
+impl DemographicsBuilder {
+    fn new() -> Self {
+        Self {
+            age_bracket: StringBuilder::new(),
+            household_size: UInt8Builder::new(),
+            income_band: StringBuilder::new(),
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed.rotate_left(16));
+        self
+    }
+
+    fn add_entry(&mut self) {
+        let demographics = Demographics::sample(&mut self.rng);
+        self.age_bracket
+            .append_value(demographics.age_bracket.as_ref());
+        self.household_size
+            .append_value(demographics.household_size);
+        self.income_band
+            .append_value(demographics.income_band.as_ref());
+    }
+
+    fn finish(&mut self) -> [ArrayRef; 3] {
+        let age_bracket: DictionaryArray<Int8Type> =
+            self.age_bracket.finish().into_iter().collect();
+        let income_band: DictionaryArray<Int8Type> =
+            self.income_band.finish().into_iter().collect();
+        [
+            Arc::new(age_bracket),
+            Arc::new(self.household_size.finish()),
+            Arc::new(income_band),
+        ]
+    }
+}
+
+/// Cuisine and dietary preferences of customers, see [`FoodPreferences`]; couriers have none.
+pub(crate) static POPULATION_PREFERENCES_FIELD: LazyLock<Field> = LazyLock::new(|| {
+    Field::new(
+        "preferences",
+        DataType::Struct(
+            vec![
+                Field::new_list(
+                    "cuisines",
+                    Field::new_list_field(DataType::Utf8, true),
+                    false,
+                ),
+                Field::new("vegetarian", DataType::Boolean, false),
+                Field::new("vegan", DataType::Boolean, false),
+                Field::new("halal", DataType::Boolean, false),
+            ]
+            .into(),
+        ),
+        true,
+    )
+});
+
+struct PreferencesBuilder {
+    cuisines: ListBuilder<StringBuilder>,
+    vegetarian: BooleanBuilder,
+    vegan: BooleanBuilder,
+    halal: BooleanBuilder,
+    /// Whether each person has preferences
+    valid: Vec<bool>,
+
+    rng: StdRng,
+}
+
+impl PreferencesBuilder {
+    fn new() -> Self {
+        Self {
+            cuisines: ListBuilder::new(StringBuilder::new()),
+            vegetarian: BooleanBuilder::new(),
+            vegan: BooleanBuilder::new(),
+            halal: BooleanBuilder::new(),
+            valid: Vec::new(),
+            rng: StdRng::from_rng(&mut rand::rng()),
+        }
+    }
+
+    fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed.rotate_left(48));
+        self
+    }
+
+    /// Add freshly sampled preferences.
+    fn add_entry(&mut self) {
+        let preferences = FoodPreferences::sample(&mut self.rng);
+        for cuisine in &preferences.cuisines {
+            self.cuisines.values().append_value(cuisine.as_ref());
+        }
+        self.cuisines.append(true);
+        self.vegetarian.append_value(preferences.vegetarian);
+        self.vegan.append_value(preferences.vegan);
+        self.halal.append_value(preferences.halal);
+        self.valid.push(true);
+    }
+
+    fn add_none(&mut self) {
+        self.cuisines.append(true);
+        self.vegetarian.append_value(false);
+        self.vegan.append_value(false);
+        self.halal.append_value(false);
+        self.valid.push(false);
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        let fields = match POPULATION_PREFERENCES_FIELD.data_type() {
+            DataType::Struct(fields) => fields.clone(),
+            _ => panic!("Invalid data type for population preferences"),
+        };
+        Arc::new(StructArray::new(
+            fields,
+            vec![
+                Arc::new(self.cuisines.finish()),
+                Arc::new(self.vegetarian.finish()),
+                Arc::new(self.vegan.finish()),
+                Arc::new(self.halal.finish()),
+            ],
+            Some(NullBuffer::from(std::mem::take(&mut self.valid))),
+        ))
+    }
+}
+
 pub(crate) static POPULATION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
     SchemaRef::new(Schema::new(vec![
         Field::new("id", DataType::FixedSizeBinary(16), false).with_extension_type(Uuid),
@@ -124,6 +372,19 @@ pub(crate) static POPULATION_SCHEMA: LazyLock<SchemaRef> = LazyLock::new(|| {
         )
         .with_extension_type(PointType::new(Dimension::XY, Default::default())),
         Field::new("state", DataType::Utf8View, false),
+        POPULATION_SCHEDULE_FIELD.clone(),
+        Field::new(
+            "age_bracket",
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            true,
+        ),
+        Field::new("household_size", DataType::UInt8, true),
+        Field::new(
+            "income_band",
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            true,
+        ),
+        POPULATION_PREFERENCES_FIELD.clone(),
     ]))
 });
 
@@ -134,6 +395,16 @@ pub struct PopulationDataBuilder {
     properties: PropertiesBuilder,
     position: PointBuilder,
     state: StringViewBuilder,
+    schedule: ScheduleBuilder,
+    demographics: DemographicsBuilder,
+    preferences: PreferencesBuilder,
+
+    /// Seed of deterministic ids, positions, properties, schedules, demographics and
+    /// preferences
+    seed: Option<u64>,
+    rng: StdRng,
+    /// Number of people added so far
+    num_people: usize,
 }
 
 impl Default for PopulationDataBuilder {
@@ -151,17 +422,53 @@ impl PopulationDataBuilder {
             properties: PropertiesBuilder::new(),
             position: PointBuilder::new(PointType::new(Dimension::XY, Default::default())),
             state: StringViewBuilder::new(),
+            schedule: ScheduleBuilder::new(),
+            demographics: DemographicsBuilder::new(),
+            preferences: PreferencesBuilder::new(),
+            seed: None,
+            rng: StdRng::from_rng(&mut rand::rng()),
+            num_people: 0,
         }
     }
 
+    /// Generate the population deterministically from `seed`.
+    ///
+    /// People get ids derived from the seed and the order in which they are added,
+    /// see [`PersonId::from_seed`], so populations generated from the same seed and
+    /// sites can be joined on their people across runs. Positions and properties are
+    /// sampled from the seed as well, and so are the daily schedules and preferences of
+    /// customers and the demographics of everyone.
+    /// Must be set before adding any people.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self.rng = StdRng::seed_from_u64(seed);
+        self.properties = PropertiesBuilder::new().with_seed(seed);
+        self.schedule = ScheduleBuilder::new().with_seed(seed);
+        self.demographics = DemographicsBuilder::new().with_seed(seed);
+        self.preferences = PreferencesBuilder::new().with_seed(seed);
+        self
+    }
+
+    fn next_id(&mut self) -> PersonId {
+        let id = match self.seed {
+            Some(seed) => PersonId::from_seed(seed, self.num_people),
+            None => PersonId::new(),
+        };
+        self.num_people += 1;
+        id
+    }
+
     pub fn add_site(&mut self, n_people: usize, latitude: f64, longitude: f64) -> Result<()> {
         for _ in 0..n_people {
-            let id = PersonId::new();
+            let id = self.next_id();
             self.id.append_value(id)?;
             self.properties.add_entry();
             self.role.append_value(PersonRole::Customer.as_ref());
             self.status.append_value(PersonStatusFlag::Idle.as_ref());
             self.state.append_value(DEFAULT_STATE.as_str());
+            self.schedule.add_entry();
+            self.demographics.add_entry();
+            self.preferences.add_entry();
         }
 
         let latlng = LatLng::new(latitude, longitude)?;
@@ -179,31 +486,31 @@ impl PopulationDataBuilder {
 
         let x_range = Uniform::new(minx, maxx)?;
         let y_range = Uniform::new(miny, maxy)?;
-        x_range
-            .sample_iter(rand::rng())
-            .zip(y_range.sample_iter(rand::rng()))
-            .filter_map(|(x, y)| {
-                let p = Point::new(x, y);
-                geom.contains(&p).then_some(p)
-            })
-            .take(n_people)
-            .for_each(|p| {
+        let mut placed = 0;
+        while placed < n_people {
+            let p = Point::new(x_range.sample(&mut self.rng), y_range.sample(&mut self.rng));
+            if geom.contains(&p) {
                 self.position.push_point(Some(&p));
-            });
+                placed += 1;
+            }
+        }
 
         let n_couriers = n_people / 10;
 
-        let loc = geom
-            .centroid()
-            .ok_or(Error::internal("failed to get centroid"))?;
+        // couriers start at the center of the disk of cells
+        let center = LatLng::from(cell_index);
+        let loc = Point::new(center.lng(), center.lat());
         for _ in 0..n_couriers {
-            let id = PersonId::new();
+            let id = self.next_id();
             self.id.append_value(id)?;
             self.properties.add_entry();
             self.role.append_value(PersonRole::Courier.as_ref());
             self.status.append_value(PersonStatusFlag::Idle.as_ref());
             self.position.push_point(Some(&loc));
             self.state.append_value(DEFAULT_STATE.as_str());
+            self.schedule.add_none();
+            self.demographics.add_entry();
+            self.preferences.add_none();
         }
 
         Ok(())
@@ -212,6 +519,7 @@ impl PopulationDataBuilder {
     pub fn finish(mut self) -> Result<RecordBatch> {
         let role: DictionaryArray<Int8Type> = self.role.finish().into_iter().collect();
         let status: DictionaryArray<Int8Type> = self.status.finish().into_iter().collect();
+        let [age_bracket, household_size, income_band] = self.demographics.finish();
 
         Ok(RecordBatch::try_new(
             POPULATION_SCHEMA.clone(),
@@ -222,7 +530,43 @@ impl PopulationDataBuilder {
                 self.properties.finish(),
                 self.position.finish().into_arrow(),
                 Arc::new(self.state.finish()),
+                self.schedule.finish(),
+                age_bracket,
+                household_size,
+                income_band,
+                self.preferences.finish(),
             ],
         )?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::AsArray as _;
+
+    use super::*;
+
+    fn seeded(seed: u64) -> Result<RecordBatch> {
+        let mut builder = PopulationDataBuilder::new().with_seed(seed);
+        builder.add_site(20, 52.37, 4.89)?;
+        builder.add_site(20, 51.51, -0.13)?;
+        builder.finish()
+    }
+
+    #[test]
+    fn test_seeded_population() -> Result<()> {
+        let population = seeded(42)?;
+        assert_eq!(population, seeded(42)?);
+        assert_ne!(population.column(0), seeded(7)?.column(0));
+
+        // ids follow the order in which people are added
+        let ids = population.column(0).as_fixed_size_binary();
+        assert_eq!(ids.value(0), PersonId::from_seed(42, 0).as_ref() as &[u8]);
+        assert_eq!(ids.value(21), PersonId::from_seed(42, 21).as_ref() as &[u8]);
+
+        let mut unseeded = PopulationDataBuilder::new();
+        unseeded.add_site(20, 52.37, 4.89)?;
+        assert_ne!(unseeded.finish()?.column(0), population.column(0));
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Local cache for remote simulation inputs.
+//!
+//! Routing tables and setups stored in remote object stores are large and rarely
+//! change, so downloading them on every start is wasteful. The cache keeps one copy
+//! of every downloaded file in a content-addressed blob store and links the files of
+//! a remote location into a local mirror directory, which can be read like any other
+//! local table or setup.
+//!
+//! A cached file is reused as long as its remote e-tag and size are unchanged. Files
+//! without an e-tag are downloaded again, but not stored twice if their content hash
+//! matches an existing blob.
+//!
+//! ```text
+//! <cache>/blobs/<sha256>          file contents
+//! <cache>/mirrors/<key>/...       files of a remote location, linked to blobs
+//! <cache>/mirrors/<key>.json      e-tags, sizes and hashes of the mirrored files
+//! ```
+//!
+//! Blobs are never removed; delete the cache directory to reclaim space.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use futures::TryStreamExt;
+use object_store::ObjectStore;
+use object_store::path::Path as ObjectPath;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+use uuid::Uuid;
+
+use super::stores;
+use crate::{Error, Result, RetryPolicy};
+
+/// Content-addressed local copies of remote files.
+#[derive(Debug, Clone)]
+pub struct LocalCache {
+    directory: PathBuf,
+    retry_policy: RetryPolicy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    e_tag: Option<String>,
+    size: u64,
+    digest: String,
+}
+
+impl LocalCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Retry failed downloads according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Mirror all files below `url` and return the local directory containing them.
+    ///
+    /// `options` configure the object store, e.g. with credentials. Local and
+    /// in-memory urls are not cached and returned as is.
+    pub async fn mirror_url<I, K, V>(&self, url: &Url, options: I) -> Result<Url>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        if stores::is_local(url) {
+            return Ok(url.clone());
+        }
+        let (store, prefix) = stores::parse_url_opts(url, options)?;
+        let directory = self.mirror(url.as_str(), store.as_ref(), &prefix).await?;
+        Url::from_directory_path(&directory).map_err(|_| {
+            Error::internal(format!("invalid cache directory '{}'", directory.display()))
+        })
+    }
+
+    /// Mirror all files below `prefix` in `store` into the local directory for `key`.
+    pub(crate) async fn mirror(
+        &self,
+        key: &str,
+        store: &dyn ObjectStore,
+        prefix: &ObjectPath,
+    ) -> Result<PathBuf> {
+        let key = hex_digest(key.as_bytes());
+        let mirror_dir = self.directory.join("mirrors").join(&key);
+        let index_path = self.directory.join("mirrors").join(format!("{key}.json"));
+        tokio::fs::create_dir_all(&mirror_dir).await?;
+        tokio::fs::create_dir_all(self.directory.join("blobs")).await?;
+
+        let mut index: HashMap<String, CacheEntry> = match tokio::fs::read(&index_path).await {
+            // a broken index only costs a download
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        };
+
+        let objects: Vec<_> = self
+            .retry_policy
+            .retry("listing remote files", || async {
+                Ok(store.list(Some(prefix)).try_collect::<Vec<_>>().await?)
+            })
+            .await?;
+
+        let mut mirrored = HashMap::with_capacity(objects.len());
+        for meta in objects {
+            let Some(parts) = meta.location.prefix_match(prefix) else {
+                continue;
+            };
+            let relative = parts
+                .map(|part| part.as_ref().to_string())
+                .collect::<Vec<_>>();
+            let name = relative.join("/");
+            let local_path = relative
+                .iter()
+                .fold(mirror_dir.clone(), |path, part| path.join(part));
+
+            let cached = index.remove(&name).filter(|entry| {
+                entry.e_tag.is_some()
+                    && entry.e_tag == meta.e_tag
+                    && entry.size == meta.size
+                    && file_size(&self.blob_path(&entry.digest)) == Some(entry.size)
+            });
+            let entry = match cached {
+                Some(entry) => {
+                    if file_size(&local_path) != Some(entry.size) {
+                        self.link(&entry, &local_path).await?;
+                    }
+                    entry
+                }
+                None => {
+                    tracing::debug!(target: "caspers::cache", "downloading '{}'", meta.location);
+                    let data = self
+                        .retry_policy
+                        .retry(&format!("downloading '{}'", meta.location), || async {
+                            Ok(store.get(&meta.location).await?.bytes().await?)
+                        })
+                        .await?;
+                    let entry = CacheEntry {
+                        e_tag: meta.e_tag.clone(),
+                        size: data.len() as u64,
+                        digest: hex_digest(&data),
+                    };
+                    self.write_blob(&entry, &data).await?;
+                    self.link(&entry, &local_path).await?;
+                    entry
+                }
+            };
+            mirrored.insert(name, entry);
+        }
+
+        // entries left in the old index no longer exist remotely
+        for name in index.keys() {
+            let path = name
+                .split('/')
+                .fold(mirror_dir.clone(), |path, part| path.join(part));
+            let _ = tokio::fs::remove_file(path).await;
+        }
+
+        write_atomic(&index_path, &serde_json::to_vec(&mirrored)?).await?;
+
+        Ok(mirror_dir)
+    }
+
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.directory.join("blobs").join(digest)
+    }
+
+    async fn write_blob(&self, entry: &CacheEntry, data: &[u8]) -> Result<()> {
+        let blob_path = self.blob_path(&entry.digest);
+        if file_size(&blob_path) == Some(entry.size) {
+            return Ok(());
+        }
+        write_atomic(&blob_path, data).await
+    }
+
+    /// Expose a blob at `path`, sharing its storage where the file system allows.
+    async fn link(&self, entry: &CacheEntry, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let _ = tokio::fs::remove_file(path).await;
+        let blob_path = self.blob_path(&entry.digest);
+        if tokio::fs::hard_link(&blob_path, path).await.is_err() {
+            tokio::fs::copy(&blob_path, path).await?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|meta| meta.len())
+}
+
+/// Write a file so that concurrent readers never observe partial content.
+async fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    tokio::fs::write(&tmp_path, data).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::PutPayload;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mirror() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache = LocalCache::new(cache_dir.path());
+        let store = InMemory::new();
+        let prefix = ObjectPath::from("system/routing_nodes");
+
+        store
+            .put(&prefix.child("a.parquet"), PutPayload::from("first"))
+            .await?;
+        store
+            .put(&prefix.child("b.parquet"), PutPayload::from("second"))
+            .await?;
+
+        let mirror = cache.mirror("memory:///", &store, &prefix).await?;
+        assert_eq!(std::fs::read(mirror.join("a.parquet"))?, b"first");
+        assert_eq!(std::fs::read(mirror.join("b.parquet"))?, b"second");
+
+        // changed files are downloaded again, removed files disappear
+        store
+            .put(&prefix.child("a.parquet"), PutPayload::from("changed"))
+            .await?;
+        store.delete(&prefix.child("b.parquet")).await?;
+
+        let mirror = cache.mirror("memory:///", &store, &prefix).await?;
+        assert_eq!(std::fs::read(mirror.join("a.parquet"))?, b"changed");
+        assert!(!mirror.join("b.parquet").exists());
+
+        // identical content is stored once
+        let blobs = std::fs::read_dir(cache_dir.path().join("blobs"))?.count();
+        store
+            .put(&prefix.child("c.parquet"), PutPayload::from("changed"))
+            .await?;
+        cache.mirror("memory:///", &store, &prefix).await?;
+        assert_eq!(
+            std::fs::read_dir(cache_dir.path().join("blobs"))?.count(),
+            blobs
+        );
+
+        Ok(())
+    }
+}
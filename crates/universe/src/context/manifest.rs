@@ -0,0 +1,492 @@
+//! Manifests of the data files written through a context.
+//!
+//! Object stores may be left with partially uploaded files, and local disks may
+//! truncate files on crashes. Such files often only fail once they are scanned, or
+//! are silently read with fewer rows. Every write through a context therefore
+//! records the files it added in a manifest, together with their size, row count and
+//! SHA-256 digest. Manifests are stored next to the data they describe, in the
+//! `_manifests/` directory of the written partition, e.g.
+//! `results/events/simulation_id=<id>/_manifests/<write id>.manifest`, where they
+//! are not picked up as table data.
+//!
+//! When a context is opened for an existing simulation, the files of its partitions
+//! are checked against their manifests before anything is read.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::catalog::CatalogProvider;
+use datafusion::datasource::listing::ListingTable;
+use datafusion::prelude::SessionContext;
+use futures::{StreamExt as _, TryStreamExt as _};
+use object_store::path::{Path, PathPart};
+use object_store::{ObjectStore, PutPayload};
+use parquet::file::metadata::ParquetMetaDataReader;
+use parquet::file::reader::ChunkReader;
+use serde::{Deserialize, Serialize};
+use url::Url;
+use uuid::Uuid;
+
+use super::cache::hex_digest;
+use super::probe::{ContextReport, TableDiagnostic, TableProblem};
+use crate::{Error, Result};
+
+const MANIFEST_DIR: &str = "_manifests";
+const MANIFEST_EXTENSION: &str = "manifest";
+
+/// Number of files read concurrently when computing or verifying digests.
+const CONCURRENCY: usize = 8;
+
+/// A data file as recorded when it was written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ManifestEntry {
+    /// Path of the file relative to the partition
+    file: String,
+    bytes: u64,
+    rows: usize,
+    sha256: String,
+}
+
+/// A data file which differs from its manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMismatch {
+    /// Path of the file relative to the partition
+    pub file: String,
+    pub problem: FileProblem,
+}
+
+/// How a data file differs from its manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileProblem {
+    /// The file was deleted
+    Missing,
+    /// The file has a different size, e.g. it was truncated
+    Size { expected: u64, found: u64 },
+    /// The file has a different number of rows
+    Rows { expected: usize, found: usize },
+    /// The file has the recorded size and row count, but different content
+    Digest,
+}
+
+impl fmt::Display for FileMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            FileProblem::Missing => write!(f, "file '{}' is missing", self.file),
+            FileProblem::Size { expected, found } => write!(
+                f,
+                "file '{}' has {found} bytes, expected {expected}",
+                self.file
+            ),
+            FileProblem::Rows { expected, found } => write!(
+                f,
+                "file '{}' has {found} rows, expected {expected}",
+                self.file
+            ),
+            FileProblem::Digest => write!(f, "file '{}' has a different digest", self.file),
+        }
+    }
+}
+
+/// Format of the data files of a table.
+#[derive(Debug, Clone, Copy)]
+enum FileFormat {
+    Parquet,
+    Json,
+}
+
+impl FileFormat {
+    fn rows<T: ChunkReader + AsRef<[u8]>>(&self, data: &T) -> Result<usize> {
+        match self {
+            FileFormat::Parquet => {
+                let metadata = ParquetMetaDataReader::new().parse_and_finish(data)?;
+                Ok(metadata.file_metadata().num_rows() as usize)
+            }
+            FileFormat::Json => Ok(data
+                .as_ref()
+                .split(|byte| *byte == b'\n')
+                .filter(|line| !line.trim_ascii().is_empty())
+                .count()),
+        }
+    }
+}
+
+/// The directory of a listing table the data of a simulation is written to.
+pub(super) struct Partition {
+    store: Arc<dyn ObjectStore>,
+    location: Url,
+    prefix: Path,
+    extension: String,
+    format: FileFormat,
+}
+
+impl Partition {
+    /// The partition of `simulation_id` in table `table_name`.
+    ///
+    /// Tables partitioned by simulation are written to the directory of the
+    /// simulation, other tables to their root. Returns `None` for tables which are
+    /// not backed by files, e.g. in-memory tables.
+    pub(super) async fn of_table(
+        ctx: &SessionContext,
+        table_name: &str,
+        simulation_id: &Uuid,
+    ) -> Result<Option<Self>> {
+        let provider = ctx.table_provider(table_name).await?;
+        let Some(table) = provider.as_any().downcast_ref::<ListingTable>() else {
+            return Ok(None);
+        };
+        Self::of_listing_table(ctx, table, simulation_id)
+    }
+
+    fn of_listing_table(
+        ctx: &SessionContext,
+        table: &ListingTable,
+        simulation_id: &Uuid,
+    ) -> Result<Option<Self>> {
+        let Some(table_url) = table.table_paths().first() else {
+            return Ok(None);
+        };
+        let options = table.options();
+        let format = match options.format.get_ext().as_str() {
+            "parquet" => FileFormat::Parquet,
+            "json" => FileFormat::Json,
+            _ => return Ok(None),
+        };
+        let store = ctx.runtime_env().object_store(table_url)?;
+        let table_location: &Url = table_url.as_ref();
+        let (location, prefix) = match options.table_partition_cols.first() {
+            Some((column, _)) => {
+                let directory = format!("{column}={simulation_id}");
+                (
+                    table_location.join(&format!("{directory}/"))?,
+                    table_url.prefix().child(directory),
+                )
+            }
+            None => (table_location.clone(), table_url.prefix().clone()),
+        };
+        Ok(Some(Self {
+            store,
+            location,
+            prefix,
+            extension: options.file_extension.clone(),
+            format,
+        }))
+    }
+
+    /// Sizes of the data files in the partition.
+    pub(super) async fn files(&self) -> Result<HashMap<Path, u64>> {
+        let mut files = HashMap::new();
+        let mut listing = self.store.list(Some(&self.prefix));
+        while let Some(meta) = listing.try_next().await? {
+            if meta.location.as_ref().ends_with(&self.extension)
+                && !self.is_manifest(&meta.location)
+            {
+                files.insert(meta.location, meta.size);
+            }
+        }
+        Ok(files)
+    }
+
+    fn is_manifest(&self, path: &Path) -> bool {
+        path.prefix_match(&self.prefix.child(MANIFEST_DIR))
+            .is_some_and(|mut parts| parts.next().is_some())
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.prefix_match(&self.prefix)
+            .map(|parts| {
+                parts
+                    .map(|part| part.as_ref().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    fn path(&self, relative: &str) -> Path {
+        let parts = relative.split('/').map(PathPart::from);
+        Path::from_iter(self.prefix.parts().chain(parts))
+    }
+
+    async fn entry(&self, path: Path) -> Result<ManifestEntry> {
+        let data = self.store.get(&path).await?.bytes().await?;
+        Ok(ManifestEntry {
+            file: self.relative(&path),
+            bytes: data.len() as u64,
+            sha256: hex_digest(&data),
+            rows: self.format.rows(&data)?,
+        })
+    }
+
+    /// Data files added to the partition since `before` was listed.
+    async fn added(&self, before: &HashMap<Path, u64>) -> Result<Vec<Path>> {
+        Ok(self
+            .files()
+            .await?
+            .into_keys()
+            .filter(|path| !before.contains_key(path))
+            .collect())
+    }
+
+    /// Delete the files added to the partition since `before` was listed, e.g. by a
+    /// write which failed after writing some of its files.
+    pub(super) async fn remove_added(&self, before: &HashMap<Path, u64>) -> Result<()> {
+        for path in self.added(before).await? {
+            self.store.delete(&path).await?;
+        }
+        Ok(())
+    }
+
+    /// Record the files added to the partition since `before` was listed.
+    pub(super) async fn record(&self, before: &HashMap<Path, u64>) -> Result<()> {
+        let added = self.added(before).await?;
+        if added.is_empty() {
+            return Ok(());
+        }
+        let mut entries: Vec<_> = futures::stream::iter(added)
+            .map(|path| self.entry(path))
+            .buffer_unordered(CONCURRENCY)
+            .try_collect()
+            .await?;
+        entries.sort_by(|a: &ManifestEntry, b| a.file.cmp(&b.file));
+
+        let path = self
+            .prefix
+            .child(MANIFEST_DIR)
+            .child(format!("{}.{MANIFEST_EXTENSION}", Uuid::now_v7()));
+        let manifest = serde_json::to_vec(&entries)?;
+        self.store.put(&path, PutPayload::from(manifest)).await?;
+        Ok(())
+    }
+
+    /// Files of the partition which differ from their manifests.
+    ///
+    /// Files are compared by size; with `deep`, they are also read to compare their
+    /// row counts and digests.
+    async fn verify(&self, deep: bool) -> Result<Vec<FileMismatch>> {
+        let files = self.files().await?;
+        let manifests: Vec<_> = self
+            .store
+            .list(Some(&self.prefix.child(MANIFEST_DIR)))
+            .try_filter(|meta| {
+                futures::future::ready(meta.location.extension() == Some(MANIFEST_EXTENSION))
+            })
+            .try_collect()
+            .await?;
+
+        let mut entries = Vec::new();
+        for manifest in manifests {
+            let data = self.store.get(&manifest.location).await?.bytes().await?;
+            entries.extend(serde_json::from_slice::<Vec<ManifestEntry>>(&data)?);
+        }
+
+        let checks = entries.into_iter().map(|entry| {
+            let path = self.path(&entry.file);
+            let size = files.get(&path).copied();
+            async move {
+                let problem = match size {
+                    None => Some(FileProblem::Missing),
+                    Some(found) if found != entry.bytes => Some(FileProblem::Size {
+                        expected: entry.bytes,
+                        found,
+                    }),
+                    Some(_) if deep => {
+                        let data = self.store.get(&path).await?.bytes().await?;
+                        if hex_digest(&data) == entry.sha256 {
+                            None
+                        } else {
+                            // files which cannot be parsed anymore only differ by digest
+                            match self.format.rows(&data) {
+                                Ok(found) if found != entry.rows => Some(FileProblem::Rows {
+                                    expected: entry.rows,
+                                    found,
+                                }),
+                                _ => Some(FileProblem::Digest),
+                            }
+                        }
+                    }
+                    Some(_) => None,
+                };
+                Ok::<_, Error>(problem.map(|problem| FileMismatch {
+                    file: entry.file,
+                    problem,
+                }))
+            }
+        });
+        let mut mismatches: Vec<_> = futures::stream::iter(checks)
+            .buffer_unordered(CONCURRENCY)
+            .try_filter_map(|mismatch| futures::future::ready(Ok(mismatch)))
+            .try_collect()
+            .await?;
+        mismatches.sort_by(|a, b| a.file.cmp(&b.file));
+        Ok(mismatches)
+    }
+}
+
+/// Check the files of `simulation_id` in all tables of `catalog` against their manifests.
+pub(super) async fn verify_catalog(
+    ctx: &SessionContext,
+    catalog: &dyn CatalogProvider,
+    simulation_id: &Uuid,
+    deep: bool,
+) -> Result<ContextReport> {
+    let mut report = ContextReport::default();
+    for schema_name in catalog.schema_names() {
+        let Some(schema) = catalog.schema(&schema_name) else {
+            continue;
+        };
+        let mut table_names = schema.table_names();
+        table_names.sort();
+        for table_name in table_names {
+            let Some(provider) = schema.table(&table_name).await? else {
+                continue;
+            };
+            let Some(table) = provider.as_any().downcast_ref::<ListingTable>() else {
+                continue;
+            };
+            let Some(partition) = Partition::of_listing_table(ctx, table, simulation_id)? else {
+                continue;
+            };
+            let files = partition.verify(deep).await?;
+            if files.is_empty() {
+                continue;
+            }
+            report.diagnostics.push(TableDiagnostic {
+                table: format!("{schema_name}.{table_name}"),
+                location: partition.location.clone(),
+                problem: TableProblem::ManifestMismatch { files },
+                blocking: true,
+                hint: "files were truncated, modified or only partially uploaded, restore them or continue from a snapshot of another simulation".to_string(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{ObjectData, PopulationData, SimulationContext, Template};
+
+    #[tokio::test]
+    async fn test_manifest_verification() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let source = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let partition = dir.path().join(format!(
+            "snapshots/objects/simulation_id={}",
+            source.simulation_id()
+        ));
+        assert!(partition.join(MANIFEST_DIR).is_dir());
+        let file: PathBuf = std::fs::read_dir(&partition)?
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "parquet"))
+            .unwrap();
+        let name = file.file_name().unwrap().to_str().unwrap().to_string();
+        let original = std::fs::read(&file)?;
+
+        let open = async |verify_checksums: bool| {
+            SimulationContext::builder()
+                .with_working_directory(location.clone())
+                .with_simulation_id(*source.simulation_id())
+                .with_snapshot_id(*source.snapshot_id())
+                .with_verify_checksums(verify_checksums)
+                .build()
+                .await
+        };
+        let problems = |result: Result<SimulationContext>| match result {
+            Err(Error::InvalidWorkingDirectory(report)) => report
+                .diagnostics
+                .into_iter()
+                .flat_map(|diagnostic| match diagnostic.problem {
+                    TableProblem::ManifestMismatch { files } => files,
+                    problem => panic!("unexpected problem: {problem:?}"),
+                })
+                .collect::<Vec<_>>(),
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(_) => vec![],
+        };
+
+        assert!(problems(open(true).await).is_empty());
+
+        std::fs::write(&file, &original[..original.len() / 2])?;
+        assert_eq!(
+            problems(open(false).await),
+            vec![FileMismatch {
+                file: name.clone(),
+                problem: FileProblem::Size {
+                    expected: original.len() as u64,
+                    found: (original.len() / 2) as u64,
+                },
+            }]
+        );
+
+        // flipping bytes in the data pages keeps the size and the row count
+        let mut corrupted = original.clone();
+        corrupted[4] ^= 0xff;
+        std::fs::write(&file, &corrupted)?;
+        assert!(problems(open(false).await).is_empty());
+        assert_eq!(
+            problems(open(true).await),
+            vec![FileMismatch {
+                file: name.clone(),
+                problem: FileProblem::Digest,
+            }]
+        );
+
+        std::fs::remove_file(&file)?;
+        assert_eq!(
+            problems(open(false).await),
+            vec![FileMismatch {
+                file: name,
+                problem: FileProblem::Missing,
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_added_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let ctx = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let partition =
+            Partition::of_table(ctx.ctx(), "caspers.snapshots.objects", ctx.simulation_id())
+                .await?
+                .unwrap();
+        let before = partition.files().await?;
+        assert_eq!(before.len(), 1);
+
+        // a stray file left by a failed write is removed, the data written before is kept
+        let stray = dir.path().join(format!(
+            "snapshots/objects/simulation_id={}/stray.parquet",
+            ctx.simulation_id()
+        ));
+        std::fs::write(&stray, b"partial")?;
+        assert_eq!(partition.files().await?.len(), 2);
+        partition.remove_added(&before).await?;
+        assert!(!stray.exists());
+        assert_eq!(partition.files().await?, before);
+        Ok(())
+    }
+}
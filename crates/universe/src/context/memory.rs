@@ -8,17 +8,20 @@ use datafusion::catalog::{
 };
 
 use crate::builders::{
-    EVENTS_SCHEMA, METRICS_SCHEMA, OBJECTS_SCHEMA, ORDER_LINE_SCHEMA, ORDER_SCHEMA,
-    POPULATION_SCHEMA,
+    DAILY_SUMMARY_SCHEMA, EVENTS_SCHEMA, FEEDBACK_SCHEMA, FOOTPRINT_SCHEMA, GREEN_DELIVERY_SCHEMA,
+    IMPRESSIONS_SCHEMA, INVENTORY_SCHEMA, INVOICES_SCHEMA, MARKET_SHARE_SCHEMA, METRICS_SCHEMA,
+    OBJECTS_SCHEMA, ORDER_HEATMAP_SCHEMA, ORDER_LINE_SCHEMA, ORDER_SCHEMA, POPULATION_SCHEMA,
+    WASTE_SCHEMA,
 };
 use crate::context::wrap_schema;
 use crate::{Result, RoutingData};
 
 use super::schemas::{
-    EVENTS_REF, METRICS_REF, OBJECTS_REF, ORDER_LINES_REF, ORDERS_REF, POPULATION_REF,
-    RESULTS_SCHEMA_NAME, ROUTING_EDGES_REF, ROUTING_NODES_REF, SIMULATION_META_REF,
-    SIMULATION_META_SCHEMA, SNAPSHOT_META_REF, SNAPSHOT_META_SCHEMA, SNAPSHOTS_SCHEMA_NAME,
-    SYSTEM_SCHEMA_NAME,
+    DAILY_SUMMARY_REF, EVENTS_REF, FEEDBACK_REF, FOOTPRINT_REF, GREEN_DELIVERY_REF,
+    IMPRESSIONS_REF, INVENTORY_REF, INVOICES_REF, MARKET_SHARE_REF, METRICS_REF, OBJECTS_REF,
+    ORDER_HEATMAP_REF, ORDER_LINES_REF, ORDERS_REF, POPULATION_REF, RESULTS_SCHEMA_NAME,
+    ROUTING_EDGES_REF, ROUTING_NODES_REF, SIMULATION_META_REF, SIMULATION_META_SCHEMA,
+    SNAPSHOT_META_REF, SNAPSHOT_META_SCHEMA, SNAPSHOTS_SCHEMA_NAME, SYSTEM_SCHEMA_NAME, WASTE_REF,
 };
 
 pub fn in_memory_catalog() -> Result<Arc<dyn CatalogProvider>> {
@@ -90,6 +93,46 @@ fn register_results(schema: &dyn SchemaProvider) -> Result<()> {
         EVENTS_REF.table().to_string(),
         mem_table(wrap_schema(&EVENTS_SCHEMA))?,
     )?;
+    schema.register_table(
+        INVOICES_REF.table().to_string(),
+        mem_table(wrap_schema(&INVOICES_SCHEMA))?,
+    )?;
+    schema.register_table(
+        FEEDBACK_REF.table().to_string(),
+        mem_table(wrap_schema(&FEEDBACK_SCHEMA))?,
+    )?;
+    schema.register_table(
+        INVENTORY_REF.table().to_string(),
+        mem_table(wrap_schema(&INVENTORY_SCHEMA))?,
+    )?;
+    schema.register_table(
+        WASTE_REF.table().to_string(),
+        mem_table(wrap_schema(&WASTE_SCHEMA))?,
+    )?;
+    schema.register_table(
+        FOOTPRINT_REF.table().to_string(),
+        mem_table(wrap_schema(&FOOTPRINT_SCHEMA))?,
+    )?;
+    schema.register_table(
+        GREEN_DELIVERY_REF.table().to_string(),
+        mem_table(wrap_schema(&GREEN_DELIVERY_SCHEMA))?,
+    )?;
+    schema.register_table(
+        IMPRESSIONS_REF.table().to_string(),
+        mem_table(wrap_schema(&IMPRESSIONS_SCHEMA))?,
+    )?;
+    schema.register_table(
+        ORDER_HEATMAP_REF.table().to_string(),
+        mem_table(wrap_schema(&ORDER_HEATMAP_SCHEMA))?,
+    )?;
+    schema.register_table(
+        MARKET_SHARE_REF.table().to_string(),
+        mem_table(wrap_schema(&MARKET_SHARE_SCHEMA))?,
+    )?;
+    schema.register_table(
+        DAILY_SUMMARY_REF.table().to_string(),
+        mem_table(wrap_schema(&DAILY_SUMMARY_SCHEMA))?,
+    )?;
 
     Ok(())
 }
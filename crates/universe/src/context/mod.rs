@@ -1,10 +1,10 @@
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
-use arrow::array::RecordBatch;
-use arrow::datatypes::SchemaRef;
+use arrow::array::{AsArray as _, RecordBatch};
+use arrow::datatypes::{SchemaRef, TimestampMillisecondType};
 use arrow_schema::{DataType, Field, FieldRef, Schema, SchemaBuilder};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use datafusion::catalog::CatalogProvider;
 use datafusion::dataframe::DataFrameWriteOptions;
 use datafusion::execution::SessionStateBuilder;
@@ -12,20 +12,46 @@ use datafusion::logical_expr::dml::InsertOp;
 use datafusion::prelude::{DataFrame, Expr, SessionContext, col, lit};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::TableReference;
+use futures::future::BoxFuture;
+use futures::{StreamExt as _, stream};
 use url::Url;
 use uuid::Uuid;
 
+pub use self::cache::LocalCache;
+pub use self::manifest::{FileMismatch, FileProblem};
+pub use self::probe::{ColumnMismatch, ContextReport, TableDiagnostic, TableProblem};
+pub use self::redaction::{Redaction, RedactionPolicy};
+pub use self::retry::RetryPolicy;
 pub(crate) use self::schemas::system::{ROUTING_EDGES_REF, ROUTING_NODES_REF};
 pub(crate) use self::storage::storage_catalog;
+pub use self::stores::{MEMORY_SCHEME, drop_memory_store, memory_store};
 use crate::context::memory::in_memory_catalog;
 use crate::context::schemas::SystemSchema;
-use crate::{Error, ObjectData, OrderData, PopulationData, Result, State, resolve_url};
-
+use crate::simulation::ReplayRecord;
+use crate::{
+    BatchStats, Error, ObjectData, OrderData, PopulationData, Result, ResultExt as _,
+    SimulationConfig, State, resolve_url,
+};
+
+use self::probe::{ProbeRequirements, probe_storage};
+use self::query_cache::QueryCache;
 use self::schemas::{SIMULATION_META_REF, SimulationMetaBuilder, create_snapshot};
 
+mod cache;
+mod manifest;
 mod memory;
+mod probe;
+mod query_cache;
+mod read_only;
+mod redaction;
+mod replay;
+mod retry;
 mod schemas;
 pub(crate) mod storage;
+pub(crate) mod stores;
+
+/// Number of independent tables written at the same time by default.
+pub const DEFAULT_WRITE_CONCURRENCY: usize = 4;
 
 #[derive(Default)]
 pub struct SimulationContextBuilder {
@@ -37,9 +63,22 @@ pub struct SimulationContextBuilder {
 
     object_data: Option<ObjectData>,
     population_data: Option<RecordBatch>,
+    /// Simulation and snapshot the initial population is taken from
+    population_snapshot: Option<(Uuid, Uuid)>,
+    /// Simulation and snapshot a new simulation is forked from
+    fork: Option<(Uuid, Uuid)>,
 
     simulation_start_time: Option<DateTime<Utc>>,
     simulation_time_step: Option<Duration>,
+
+    retry_policy: RetryPolicy,
+    write_concurrency: Option<usize>,
+    cache: Option<LocalCache>,
+
+    run_name: Option<String>,
+    read_only: bool,
+    redaction: RedactionPolicy,
+    verify_checksums: bool,
 }
 
 impl SimulationContextBuilder {
@@ -78,6 +117,59 @@ impl SimulationContextBuilder {
         self
     }
 
+    /// Retry transient storage failures according to `policy`.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Write at most `write_concurrency` independent tables at the same time.
+    ///
+    /// Results and snapshots span several tables, which are written concurrently to
+    /// hide the latency of slow object stores. Defaults to [`DEFAULT_WRITE_CONCURRENCY`].
+    pub fn with_write_concurrency(mut self, write_concurrency: impl Into<Option<usize>>) -> Self {
+        self.write_concurrency = write_concurrency.into();
+        self
+    }
+
+    /// Keep local copies of remote routing data in `cache`.
+    pub fn with_cache(mut self, cache: impl Into<Option<LocalCache>>) -> Self {
+        self.cache = cache.into();
+        self
+    }
+
+    /// Label the run with a human readable `run_name`, stored with its snapshots.
+    pub fn with_run_name(mut self, run_name: impl Into<Option<String>>) -> Self {
+        self.run_name = run_name.into();
+        self
+    }
+
+    /// Open an existing simulation strictly read-only, e.g. for analysis.
+    ///
+    /// The working directory is not modified: no system tables are created, neither
+    /// the simulation nor its snapshots are registered, and all writes to its tables
+    /// fail. Without a snapshot id, the latest snapshot of the simulation is opened.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Read all files of an existing simulation to compare their row counts and
+    /// digests with their manifests.
+    ///
+    /// By default only the presence and size of the files are checked, which detects
+    /// truncated and partially uploaded files without reading them.
+    pub fn with_verify_checksums(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Redact columns of all data written through the context with `policy`.
+    pub fn with_redaction(mut self, policy: RedactionPolicy) -> Self {
+        self.redaction = policy;
+        self
+    }
+
     pub fn with_use_in_memory(mut self, use_in_memory: bool) -> Self {
         self.use_in_memory = use_in_memory;
         self
@@ -93,7 +185,28 @@ impl SimulationContextBuilder {
         self
     }
 
-    fn session(&self) -> (SessionContext, Uuid) {
+    /// Initialize the simulation with the people of an existing population snapshot.
+    ///
+    /// People keep their ids, roles, properties and positions, but start idle and
+    /// without orders. Objects are taken from the same snapshot, unless object data
+    /// is provided as well.
+    pub fn with_population_snapshot(mut self, simulation_id: Uuid, snapshot_id: Uuid) -> Self {
+        self.population_snapshot = Some((simulation_id, snapshot_id));
+        self
+    }
+
+    /// Fork a new simulation from a snapshot of an existing one.
+    ///
+    /// The new simulation starts from the full state of the snapshot, at the time it
+    /// was taken, and records the simulation and snapshot it was forked from with its
+    /// properties. Forking the same snapshot several times creates branches which can
+    /// run divergent scenarios, with their results kept apart by simulation id.
+    pub fn fork_from(mut self, simulation_id: Uuid, snapshot_id: Uuid) -> Self {
+        self.fork = Some((simulation_id, snapshot_id));
+        self
+    }
+
+    fn session(&self) -> Result<(SessionContext, Uuid)> {
         let simulation_id = self.simulation_id.unwrap_or_else(Uuid::now_v7);
         let state = SessionStateBuilder::new()
             .with_default_features()
@@ -101,16 +214,22 @@ impl SimulationContextBuilder {
             .build();
 
         let ctx = SessionContext::new_with_state(state);
+        if let Some(working_directory) = &self.working_directory {
+            stores::register_store(&ctx, working_directory)?;
+        }
 
-        (ctx, simulation_id)
+        Ok((ctx, simulation_id))
     }
 
     pub async fn load_snapshots(&self) -> Result<DataFrame> {
-        let (ctx, _) = self.session();
+        let (ctx, _) = self.session()?;
         let Some(working_directory) = &self.working_directory else {
-            return Err(Error::internal("System location not set"));
+            return Err(Error::missing_input("working directory not set"));
         };
         let catalog = storage_catalog(working_directory)?;
+        if !self.read_only {
+            storage::bootstrap_system(&ctx, working_directory).await?;
+        }
         ctx.register_catalog("caspers", catalog);
         let system = SystemSchema::new(&ctx);
 
@@ -126,22 +245,111 @@ impl SimulationContextBuilder {
         Ok(df)
     }
 
+    /// Continue the simulation a snapshot was taken of, at the time it was taken.
+    ///
+    /// Sets the simulation, the snapshot and the start time of the context, so the
+    /// state of the simulation is loaded from the snapshot once it is built.
+    pub async fn with_resume_snapshot(self, snapshot_id: Uuid) -> Result<Self> {
+        let snapshots = self
+            .load_snapshots()
+            .await?
+            .filter(col("id").eq(lit(ScalarValue::Utf8View(Some(snapshot_id.to_string())))))?
+            .select_columns(&["simulation_id", "simulation_time"])?
+            .collect()
+            .await?;
+        let Some((simulation_id, simulation_time)) = snapshots
+            .iter()
+            .find(|batch| batch.num_rows() > 0)
+            .map(|batch| {
+                let simulation_id = batch.column(0).as_string_view().value(0);
+                let simulation_time = batch
+                    .column(1)
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value(0);
+                (simulation_id.to_string(), simulation_time)
+            })
+        else {
+            return Err(Error::not_found("snapshot", snapshot_id));
+        };
+        let Some(simulation_time) = DateTime::<Utc>::from_timestamp_millis(simulation_time) else {
+            return Err(Error::invalid_data(format!(
+                "snapshot {snapshot_id} has an invalid simulation time"
+            )));
+        };
+        Ok(self
+            .with_simulation_id(Uuid::try_parse(&simulation_id)?)
+            .with_snapshot_id(snapshot_id)
+            .with_simulation_start_time(simulation_time))
+    }
+
     pub async fn load_simulations(&self) -> Result<DataFrame> {
-        let (ctx, _) = self.session();
+        let (ctx, _) = self.session()?;
 
         let Some(working_directory) = &self.working_directory else {
-            return Err(Error::internal("System location not set"));
+            return Err(Error::missing_input("working directory not set"));
         };
         let catalog = storage_catalog(working_directory)?;
+        if !self.read_only {
+            storage::bootstrap_system(&ctx, working_directory).await?;
+        }
         ctx.register_catalog("caspers", catalog);
         let system = SystemSchema::new(&ctx);
         system.simulations().await
     }
 
+    /// Probe the tables of the working directory the context would be built on.
+    ///
+    /// Problems which would prevent the context from being built are marked as
+    /// blocking, others are reported as warnings.
+    pub async fn probe(&self) -> Result<ContextReport> {
+        let Some(working_directory) = &self.working_directory else {
+            return Ok(ContextReport::default());
+        };
+        let (ctx, _) = self.session()?;
+        let catalog_location = resolve_url(working_directory.into())?;
+        let requirements = ProbeRequirements {
+            routing: self.cache.is_some() && !stores::is_local(&catalog_location),
+            simulations: self.simulation_id.is_some()
+                || self.population_snapshot.is_some()
+                || self.fork.is_some(),
+            snapshots: self.snapshot_id.is_some()
+                || self.population_snapshot.is_some()
+                || self.fork.is_some()
+                || self.read_only,
+        };
+        probe_storage(&ctx, &catalog_location, requirements).await
+    }
+
     pub async fn build(self) -> Result<SimulationContext> {
-        let (ctx, simulation_id) = self.session();
+        if self.read_only {
+            return self.build_read_only().await;
+        }
+        if self.fork.is_some()
+            && (self.simulation_id.is_some()
+                || self.snapshot_id.is_some()
+                || self.object_data.is_some()
+                || self.population_data.is_some()
+                || self.population_snapshot.is_some())
+        {
+            return Err(Error::invalid_data(
+                "a forked simulation is initialized from the snapshot it is forked from",
+            ));
+        }
+        let report = self.probe().await?;
+        if !report.is_ok() {
+            return Err(Error::InvalidWorkingDirectory(report));
+        }
+        for diagnostic in &report.diagnostics {
+            tracing::debug!(target: "caspers::simulation::context", "{diagnostic}");
+        }
+
+        let (ctx, simulation_id) = self.session()?;
 
         let catalog = self.build_catalog(&ctx).await?;
+        if self.simulation_id.is_some() {
+            self.verify_manifests(&ctx, catalog.as_ref(), &simulation_id)
+                .await?;
+        }
         ctx.register_catalog("caspers", catalog);
 
         let snapshot_id = if let Some(snapshot_id) = self.snapshot_id {
@@ -158,17 +366,56 @@ impl SimulationContextBuilder {
             time_step: self
                 .simulation_time_step
                 .unwrap_or_else(|| Duration::new(60, 0)),
+            retry_policy: self.retry_policy,
+            write_concurrency: self
+                .write_concurrency
+                .unwrap_or(DEFAULT_WRITE_CONCURRENCY)
+                .max(1),
+            run_name: self.run_name.clone(),
+            read_only: false,
+            query_cache: QueryCache::default(),
+            redaction: self.redaction.clone(),
         };
 
         // TODO: this is a but of a backdoor to allow for initializing a simulation
         // with some data. Idelly this would move to somewhere more separated.
-        match (self.population_data, self.object_data) {
-            (None, None) => (),
+        let (population_data, object_data) = match self.population_snapshot {
+            Some((source_simulation_id, source_snapshot_id)) => {
+                let source = sim_ctx.scoped_to(
+                    source_simulation_id,
+                    source_snapshot_id,
+                    sim_ctx.current_time,
+                );
+                let object_data = match self.object_data {
+                    Some(object_data) => object_data,
+                    None => source.snapshot_objects().await?,
+                };
+                (Some(source.fresh_population().await?), Some(object_data))
+            }
+            None => (self.population_data, self.object_data),
+        };
+        match (population_data, object_data) {
+            (None, None) => {
+                if let Some((parent_simulation_id, parent_snapshot_id)) = self.fork {
+                    let parent = sim_ctx.scoped_to(
+                        parent_simulation_id,
+                        parent_snapshot_id,
+                        sim_ctx.current_time,
+                    );
+                    let sim_state = parent.snapshot_state().await?;
+                    sim_ctx.current_time = sim_state.current_time();
+                    sim_ctx.write_snapshot(&sim_state).await?;
+                }
+            }
             (Some(population_data), Some(object_data)) => {
                 let population = sim_ctx.ctx().read_batch(population_data)?;
                 let population_data = PopulationData::try_new(population).await?;
+                let config = SimulationConfig {
+                    simulation_start: sim_ctx.current_time,
+                    ..Default::default()
+                };
                 let sim_state = State::new(
-                    &Default::default(),
+                    &config,
                     object_data,
                     population_data,
                     OrderData::empty(),
@@ -177,7 +424,7 @@ impl SimulationContextBuilder {
                 sim_ctx.write_snapshot(&sim_state).await?;
             }
             _ => {
-                return Err(Error::internal(
+                return Err(Error::missing_input(
                     "To initialize simulation, both population and object data are required",
                 ));
             }
@@ -186,26 +433,117 @@ impl SimulationContextBuilder {
         // if no id was assigned, we created a new simulation and now need to register it
         if self.simulation_id.is_none() {
             let mut builder = SimulationMetaBuilder::new();
-            builder.add_simulation(&simulation_id, None);
+            builder.add_simulation(&simulation_id, sim_ctx.simulation_properties(self.fork));
             let batch = builder.build()?;
             let df = sim_ctx.ctx().read_batch(batch)?;
-            let write_options =
-                DataFrameWriteOptions::default().with_insert_operation(InsertOp::Append);
-            df.write_table(SIMULATION_META_REF.to_string().as_str(), write_options)
+            sim_ctx
+                .append_table(df, &SIMULATION_META_REF.to_string())
                 .await?;
         }
 
         Ok(sim_ctx)
     }
 
-    async fn build_catalog(&self, _ctx: &SessionContext) -> Result<Arc<dyn CatalogProvider>> {
+    async fn build_read_only(self) -> Result<SimulationContext> {
+        let Some(simulation_id) = self.simulation_id else {
+            return Err(Error::missing_input(
+                "a simulation id is required to open a simulation read-only",
+            ));
+        };
+        if self.working_directory.is_none() {
+            return Err(Error::missing_input(
+                "a working directory is required to open a simulation read-only",
+            ));
+        }
+        if self.object_data.is_some()
+            || self.population_data.is_some()
+            || self.population_snapshot.is_some()
+            || self.fork.is_some()
+        {
+            return Err(Error::read_only("cannot initialize a simulation"));
+        }
+        let report = self.probe().await?;
+        if !report.is_ok() {
+            return Err(Error::InvalidWorkingDirectory(report));
+        }
+
+        let snapshot_id = match self.snapshot_id {
+            Some(snapshot_id) => snapshot_id,
+            None => {
+                let latest = self.load_snapshots().await?.limit(0, Some(1))?;
+                let batches = latest.select_columns(&["id"])?.collect().await?;
+                let Some(id) = batches
+                    .iter()
+                    .find_map(|batch| batch.column(0).as_string_view().iter().flatten().next())
+                else {
+                    return Err(Error::not_found("snapshot of simulation", simulation_id));
+                };
+                Uuid::try_parse(id)?
+            }
+        };
+
+        let (ctx, _) = self.session()?;
+        let catalog = self.build_catalog(&ctx).await?;
+        self.verify_manifests(&ctx, catalog.as_ref(), &simulation_id)
+            .await?;
+        ctx.register_catalog(
+            "caspers",
+            read_only::read_only_catalog(catalog.as_ref()).await?,
+        );
+
+        Ok(SimulationContext {
+            ctx,
+            simulation_id,
+            snapshot_id,
+            current_time: self.simulation_start_time.unwrap_or_else(Utc::now),
+            time_step: self
+                .simulation_time_step
+                .unwrap_or_else(|| Duration::new(60, 0)),
+            retry_policy: self.retry_policy,
+            write_concurrency: self
+                .write_concurrency
+                .unwrap_or(DEFAULT_WRITE_CONCURRENCY)
+                .max(1),
+            run_name: self.run_name.clone(),
+            read_only: true,
+            query_cache: QueryCache::default(),
+            redaction: self.redaction,
+        })
+    }
+
+    /// Fail if files of `simulation_id` differ from the manifests recorded when writing them.
+    async fn verify_manifests(
+        &self,
+        ctx: &SessionContext,
+        catalog: &dyn CatalogProvider,
+        simulation_id: &Uuid,
+    ) -> Result<()> {
+        let report =
+            manifest::verify_catalog(ctx, catalog, simulation_id, self.verify_checksums).await?;
+        if !report.is_ok() {
+            return Err(Error::InvalidWorkingDirectory(report));
+        }
+        Ok(())
+    }
+
+    async fn build_catalog(&self, ctx: &SessionContext) -> Result<Arc<dyn CatalogProvider>> {
         if let Some(working_directory) = &self.working_directory {
             let catalog_location = resolve_url(working_directory.into())?;
-            storage_catalog(&catalog_location)
+            let catalog = storage_catalog(&catalog_location)?;
+            if !self.read_only {
+                storage::bootstrap_system(ctx, &catalog_location).await?;
+            }
+            if let Some(cache) = &self.cache
+                && !stores::is_local(&catalog_location)
+            {
+                let cache = cache.clone().with_retry_policy(self.retry_policy);
+                storage::cache_routing(catalog.as_ref(), &catalog_location, &cache).await?;
+            }
+            Ok(catalog)
         } else if self.use_in_memory {
             in_memory_catalog()
         } else {
-            Err(Error::internal("Results location is not provided"))
+            Err(Error::missing_input("Results location is not provided"))
         }
     }
 }
@@ -216,6 +554,13 @@ pub struct SimulationContext {
     current_time: DateTime<Utc>,
     time_step: Duration,
     ctx: SessionContext,
+    retry_policy: RetryPolicy,
+    write_concurrency: usize,
+    run_name: Option<String>,
+    read_only: bool,
+    redaction: RedactionPolicy,
+    /// Materialized snapshot queries, see [`SnapshotsSchema::cached`](schemas::SnapshotsSchema::cached)
+    query_cache: QueryCache,
 }
 
 impl SimulationContext {
@@ -255,6 +600,139 @@ impl SimulationContext {
         self.current_time += self.time_step;
     }
 
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Number of independent tables written at the same time.
+    pub fn write_concurrency(&self) -> usize {
+        self.write_concurrency
+    }
+
+    /// Whether the context was opened read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Human readable label of the run, if any.
+    pub fn run_name(&self) -> Option<&str> {
+        self.run_name.as_deref()
+    }
+
+    /// Metadata properties identifying the run.
+    pub(in crate::context) fn run_properties(&self) -> Option<String> {
+        self.run_name
+            .as_ref()
+            .map(|run_name| serde_json::json!({ "run_name": run_name }).to_string())
+    }
+
+    /// Metadata properties of a checkpoint completing the simulated `day`.
+    fn checkpoint_properties(&self, day: NaiveDate) -> String {
+        let mut properties = serde_json::Map::new();
+        if let Some(run_name) = &self.run_name {
+            properties.insert("run_name".into(), run_name.as_str().into());
+        }
+        properties.insert(
+            "checkpoint".into(),
+            serde_json::json!({ "day": day.to_string() }),
+        );
+        serde_json::Value::Object(properties).to_string()
+    }
+
+    /// Metadata properties of a new simulation, naming the snapshot it was forked from.
+    fn simulation_properties(&self, forked_from: Option<(Uuid, Uuid)>) -> Option<String> {
+        let mut properties = serde_json::Map::new();
+        if let Some(run_name) = &self.run_name {
+            properties.insert("run_name".into(), run_name.as_str().into());
+        }
+        if let Some((simulation_id, snapshot_id)) = forked_from {
+            properties.insert(
+                "forked_from".into(),
+                serde_json::json!({
+                    "simulation_id": simulation_id.to_string(),
+                    "snapshot_id": snapshot_id.to_string(),
+                }),
+            );
+        }
+        (!properties.is_empty()).then(|| serde_json::Value::Object(properties).to_string())
+    }
+
+    /// Collect a data frame, retrying transient storage failures.
+    pub(crate) async fn collect(&self, df: DataFrame) -> Result<Vec<RecordBatch>> {
+        self.retry_policy
+            .retry("reading table data", || async {
+                Ok(df.clone().collect().await?)
+            })
+            .await
+    }
+
+    /// Append a data frame to a table, retrying transient storage failures.
+    ///
+    /// Appends add new files to the table, so the files an attempt added before it
+    /// failed are deleted before the write is retried, and its rows are not duplicated.
+    pub(crate) async fn append_table(&self, df: DataFrame, table_name: &str) -> Result<()> {
+        if self.read_only {
+            return Err(Error::read_only(format!("cannot write to '{table_name}'")));
+        }
+        let df = self.redaction.apply(df)?;
+        let partition =
+            manifest::Partition::of_table(&self.ctx, table_name, &self.simulation_id).await?;
+        let existing = match &partition {
+            Some(partition) => partition.files().await?,
+            None => Default::default(),
+        };
+        let mut retrying = false;
+        self.retry_policy
+            .retry(&format!("writing to '{table_name}'"), || {
+                let failed_before = std::mem::replace(&mut retrying, true);
+                let (df, partition, existing) = (&df, &partition, &existing);
+                async move {
+                    if let (true, Some(partition)) = (failed_before, partition) {
+                        partition.remove_added(existing).await?;
+                    }
+                    let write_options =
+                        DataFrameWriteOptions::default().with_insert_operation(InsertOp::Append);
+                    df.clone().write_table(table_name, write_options).await?;
+                    Ok(())
+                }
+            })
+            .await?;
+        if let Some(partition) = partition {
+            partition.record(&existing).await?;
+        }
+        Ok(())
+    }
+
+    /// Run writes to independent tables, at most [`write_concurrency`](Self::write_concurrency)
+    /// at a time.
+    ///
+    /// Every write runs to completion even if others fail, so one failing table does not
+    /// leave the remaining tables unwritten. Failures are logged with the name of their
+    /// table, and the first of them is returned.
+    pub(crate) async fn write_tables<'a>(
+        &self,
+        writes: Vec<(String, BoxFuture<'a, Result<()>>)>,
+    ) -> Result<()> {
+        let mut errors = stream::iter(writes)
+            .map(|(table_name, write)| async move {
+                write
+                    .await
+                    .with_context(|| format!("writing to '{table_name}'"))
+            })
+            .buffer_unordered(self.write_concurrency)
+            .filter_map(|result| async move { result.err() })
+            .collect::<Vec<_>>()
+            .await
+            .into_iter();
+        let Some(error) = errors.next() else {
+            return Ok(());
+        };
+        for other in errors {
+            tracing::error!(target: "caspers::simulation::context", "{other}");
+        }
+        Err(error)
+    }
+
     pub fn system(&self) -> schemas::SystemSchema<'_> {
         schemas::SystemSchema::new(&self.ctx)
     }
@@ -272,21 +750,74 @@ impl SimulationContext {
     /// This method creates a new snapshot with the current simulation state
     /// and updates the simulation context to track the new snapshot ID.
     pub async fn write_snapshot(&mut self, state: &State) -> Result<()> {
-        let snapshot_id = create_snapshot(state, self).await?;
+        let snapshot_id = create_snapshot(state, self, self.run_properties()).await?;
+        self.snapshot_id = snapshot_id;
+        self.query_cache.retain_snapshot(snapshot_id);
+        Ok(())
+    }
+
+    /// Write the current simulation state to a snapshot recording how to replay the
+    /// seeded run which reached it, see [`verify_simulation`](crate::verify_simulation).
+    pub(crate) async fn write_replay_snapshot(
+        &mut self,
+        state: &State,
+        replay: &ReplayRecord,
+    ) -> Result<()> {
+        let mut properties = serde_json::Map::new();
+        if let Some(run_name) = &self.run_name {
+            properties.insert("run_name".into(), run_name.as_str().into());
+        }
+        properties.insert("replay".into(), serde_json::to_value(replay)?);
+        let properties = serde_json::Value::Object(properties).to_string();
+        let snapshot_id = create_snapshot(state, self, Some(properties)).await?;
+        self.snapshot_id = snapshot_id;
+        self.query_cache.retain_snapshot(snapshot_id);
+        Ok(())
+    }
+
+    /// Write the current simulation state to a snapshot marked as a checkpoint.
+    ///
+    /// Checkpoints are snapshots like any other, their properties record the last
+    /// simulated `day` they complete, so a crashed run can be resumed from the latest.
+    pub async fn write_checkpoint(&mut self, state: &State, day: NaiveDate) -> Result<()> {
+        let properties = self.checkpoint_properties(day);
+        let snapshot_id = create_snapshot(state, self, Some(properties)).await?;
         self.snapshot_id = snapshot_id;
+        self.query_cache.retain_snapshot(snapshot_id);
         Ok(())
     }
 
+    /// Row counts and null counts for all tables registered in the `caspers` catalog.
+    ///
+    /// Every table is scanned in full, so this should not be called on the hot path of a run.
+    pub async fn table_stats(&self) -> Result<Vec<BatchStats>> {
+        let Some(catalog) = self.ctx().catalog("caspers") else {
+            return Ok(vec![]);
+        };
+        let mut stats = Vec::new();
+        for schema_name in catalog.schema_names() {
+            let Some(schema) = catalog.schema(&schema_name) else {
+                continue;
+            };
+            for table_name in schema.table_names() {
+                let Some(table) = schema.table(&table_name).await? else {
+                    continue;
+                };
+                let df = self.ctx().read_table(table)?;
+                stats
+                    .push(BatchStats::from_frame(format!("{schema_name}.{table_name}"), df).await?);
+            }
+        }
+        Ok(stats)
+    }
+
     async fn scan(&self, table_ref: &TableReference) -> Result<DataFrame> {
         let schema = {
             let state = self.ctx().state_ref();
             state.read().schema_for_ref(table_ref.clone())?
         };
         let Some(table) = schema.table(table_ref.table()).await? else {
-            return Err(Error::internal(format!(
-                "Table '{}' not registered",
-                table_ref
-            )));
+            return Err(Error::TableNotFound(table_ref.to_string()));
         };
         Ok(self.ctx().read_table(table)?)
     }
@@ -312,11 +843,15 @@ impl SimulationContext {
         let sim_id = ScalarValue::Utf8View(Some(self.simulation_id.to_string()));
         let sn_id = ScalarValue::Utf8View(Some(self.snapshot_id.to_string()));
         Ok(df
-            .with_column("simulation_id", lit(sim_id))?
-            .with_column("snapshot_id", lit(sn_id))?)
+            .with_column("snapshot_id", lit(sn_id))?
+            .with_column("simulation_id", lit(sim_id))?)
     }
 }
 
+/// Extend a table schema with the columns identifying the snapshot and simulation of a row.
+///
+/// The simulation id is the last column, so stored tables can partition their
+/// files by simulation.
 fn wrap_schema(schema: &Schema) -> SchemaRef {
     static SIMULATION_ID_FIELD: LazyLock<FieldRef> =
         LazyLock::new(|| Field::new("simulation_id", DataType::Utf8View, false).into());
@@ -326,7 +861,98 @@ fn wrap_schema(schema: &Schema) -> SchemaRef {
     for field in schema.fields() {
         builder.push(field.clone());
     }
-    builder.push(SIMULATION_ID_FIELD.clone());
     builder.push(SNAPSHOT_ID_FIELD.clone());
+    builder.push(SIMULATION_ID_FIELD.clone());
     builder.finish().into()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::FutureExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_tables() -> Result<()> {
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .with_write_concurrency(2)
+            .build()
+            .await?;
+
+        // a failing table does not keep the others from being written
+        let written = AtomicUsize::new(0);
+        let writes = (0..5)
+            .map(|index| {
+                let written = &written;
+                let write = async move {
+                    if index == 1 {
+                        return Err(Error::invalid_data("broken"));
+                    }
+                    written.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                };
+                (format!("table_{index}"), write.boxed())
+            })
+            .collect();
+        let error = ctx.write_tables(writes).await.unwrap_err();
+        assert!(error.to_string().contains("table_1"));
+        assert_eq!(written.load(Ordering::SeqCst), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_snapshot_queries() -> Result<()> {
+        let objects = ObjectData::try_new(
+            crate::templates::Template::default()
+                .load()?
+                .object_data()?,
+        )?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 51.518898098201326, -0.13381370382489707)?;
+        let mut ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .with_population_data(population.finish()?)
+            .with_object_data(objects)
+            .build()
+            .await?;
+
+        let count = |batches: Vec<RecordBatch>| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        let sites = || Some(col("label").eq(lit(crate::ObjectLabel::Site.as_ref())));
+        let first = count(
+            ctx.snapshots()
+                .cached("objects", sites())
+                .await?
+                .collect()
+                .await?,
+        );
+        let second = count(
+            ctx.snapshots()
+                .cached("objects", sites())
+                .await?
+                .collect()
+                .await?,
+        );
+        assert!(first > 0);
+        assert_eq!(first, second);
+        assert_eq!(ctx.query_cache.len(), 1);
+
+        let all = count(
+            ctx.snapshots()
+                .cached("objects", None)
+                .await?
+                .collect()
+                .await?,
+        );
+        assert!(all > first);
+        assert_eq!(ctx.query_cache.len(), 2);
+
+        // entries are kept until a new snapshot is written
+        let state = ctx.snapshot_state().await?;
+        ctx.write_snapshot(&state).await?;
+        assert_eq!(ctx.query_cache.len(), 0);
+        Ok(())
+    }
+}
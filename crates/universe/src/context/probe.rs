@@ -0,0 +1,362 @@
+//! Up-front validation of the tables in a working directory.
+//!
+//! Tables of a storage catalog are listing tables over the files in the working
+//! directory. When files are missing or were written with a different schema,
+//! queries only fail once the tables are scanned, often with errors that do not
+//! mention the table at all. Before a context is built, the expected table locations
+//! are therefore probed, and problems are collected into a [`ContextReport`] with a
+//! hint on how to fix each of them.
+
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::compute::can_cast_types;
+use arrow_schema::{DataType, SchemaRef};
+use datafusion::datasource::file_format::FileFormat;
+use datafusion::datasource::file_format::json::JsonFormat;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::{ListingOptions, ListingTableUrl};
+use datafusion::prelude::SessionContext;
+use futures::TryStreamExt as _;
+use url::Url;
+
+use crate::{Result, RoutingData};
+
+use super::manifest::FileMismatch;
+use super::schemas::{
+    ROUTING_EDGES_REF, ROUTING_NODES_REF, SIMULATION_META_REF, SNAPSHOT_META_REF,
+    SYSTEM_SCHEMA_NAME,
+};
+
+/// What is wrong with a table of the working directory.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableProblem {
+    /// No data files were found at the table location
+    Missing,
+    /// The table has data files, but none with any rows
+    Empty,
+    /// Columns of the data files are missing or cannot be read as the expected type
+    SchemaMismatch { columns: Vec<ColumnMismatch> },
+    /// Data files differ from the manifests recorded when they were written
+    ManifestMismatch { files: Vec<FileMismatch> },
+}
+
+/// A column of the data files which does not match the table schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMismatch {
+    pub column: String,
+    pub expected: DataType,
+    /// Type of the column in the data files, `None` if the column is missing
+    pub found: Option<DataType>,
+}
+
+impl fmt::Display for ColumnMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.found {
+            Some(found) => write!(
+                f,
+                "column '{}' is {found}, expected {}",
+                self.column, self.expected
+            ),
+            None => write!(f, "column '{}' is missing", self.column),
+        }
+    }
+}
+
+/// A problem with a table found when probing the working directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDiagnostic {
+    /// Qualified name of the table, e.g. `system.routing_nodes`
+    pub table: String,
+    pub location: Url,
+    pub problem: TableProblem,
+    /// Whether the context cannot be built with the problem
+    pub blocking: bool,
+    /// How to fix the problem
+    pub hint: String,
+}
+
+impl fmt::Display for TableDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            TableProblem::Missing => write!(f, "{}: no data at {}", self.table, self.location)?,
+            TableProblem::Empty => {
+                write!(f, "{}: table at {} is empty", self.table, self.location)?
+            }
+            TableProblem::SchemaMismatch { columns } => {
+                write!(f, "{}: schema mismatch at {}", self.table, self.location)?;
+                for column in columns {
+                    write!(f, "\n    {column}")?;
+                }
+            }
+            TableProblem::ManifestMismatch { files } => {
+                write!(f, "{}: manifest mismatch at {}", self.table, self.location)?;
+                for file in files {
+                    write!(f, "\n    {file}")?;
+                }
+            }
+        }
+        write!(f, "\n    hint: {}", self.hint)
+    }
+}
+
+/// Problems with the tables of a working directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextReport {
+    pub diagnostics: Vec<TableDiagnostic>,
+}
+
+impl ContextReport {
+    /// Whether the context can be built, possibly with non-blocking problems.
+    pub fn is_ok(&self) -> bool {
+        !self.diagnostics.iter().any(|d| d.blocking)
+    }
+
+    /// Problems preventing the context from being built.
+    pub fn blocking(&self) -> impl Iterator<Item = &TableDiagnostic> {
+        self.diagnostics.iter().filter(|d| d.blocking)
+    }
+}
+
+impl fmt::Display for ContextReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, diagnostic) in self.diagnostics.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            let severity = if diagnostic.blocking {
+                "error"
+            } else {
+                "warning"
+            };
+            write!(f, "  {severity}: {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Tables the context being built depends on.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ProbeRequirements {
+    /// Routing data is read right away, e.g. to mirror it into a local cache
+    pub(super) routing: bool,
+    /// An existing simulation is continued
+    pub(super) simulations: bool,
+    /// An existing snapshot is read
+    pub(super) snapshots: bool,
+}
+
+/// Probe the system tables of the storage catalog at `catalog_location`.
+pub(super) async fn probe_storage(
+    ctx: &SessionContext,
+    catalog_location: &Url,
+    requirements: ProbeRequirements,
+) -> Result<ContextReport> {
+    let system_location = catalog_location.join(&format!("{}/", SYSTEM_SCHEMA_NAME))?;
+    let mut report = ContextReport::default();
+
+    for (table_ref, schema) in [
+        (&*ROUTING_NODES_REF, RoutingData::nodes_schema()),
+        (&*ROUTING_EDGES_REF, RoutingData::edges_schema()),
+    ] {
+        let location = system_location.join(&format!("{}/", table_ref.table()))?;
+        let table = format!("{SYSTEM_SCHEMA_NAME}.{}", table_ref.table());
+        let diagnostic = |problem, hint: &str| TableDiagnostic {
+            table: table.clone(),
+            location: location.clone(),
+            blocking: requirements.routing
+                || matches!(problem, TableProblem::SchemaMismatch { .. }),
+            problem,
+            hint: hint.to_string(),
+        };
+        match probe_table(ctx, &location, Arc::new(ParquetFormat::new())).await? {
+            None => report.diagnostics.push(diagnostic(
+                TableProblem::Missing,
+                "initialize the working directory with `caspers init` to prepare the street networks of the sites",
+            )),
+            Some(found) => {
+                let columns = schema_mismatches(&schema, &found);
+                if !columns.is_empty() {
+                    report.diagnostics.push(diagnostic(
+                        TableProblem::SchemaMismatch { columns },
+                        "the street network was prepared by an incompatible version, initialize a new working directory with `caspers init`",
+                    ));
+                }
+            }
+        }
+    }
+
+    for (table_ref, required, hint) in [
+        (
+            &*SIMULATION_META_REF,
+            requirements.simulations,
+            "no simulation was started in this working directory, start a new simulation instead of continuing one",
+        ),
+        (
+            &*SNAPSHOT_META_REF,
+            requirements.snapshots,
+            "no snapshot was taken in this working directory, run a simulation to take one before reading it",
+        ),
+    ] {
+        if !required {
+            continue;
+        }
+        let location = system_location.join(&format!("{}/", table_ref.table()))?;
+        let format = Arc::new(JsonFormat::default());
+        let problem = match probe_table(ctx, &location, format).await? {
+            None => TableProblem::Missing,
+            Some(found) if found.fields().is_empty() => TableProblem::Empty,
+            Some(_) => continue,
+        };
+        report.diagnostics.push(TableDiagnostic {
+            table: format!("{SYSTEM_SCHEMA_NAME}.{}", table_ref.table()),
+            location,
+            problem,
+            blocking: true,
+            hint: hint.to_string(),
+        });
+    }
+
+    Ok(report)
+}
+
+/// Schema of the data files at `location`, `None` if there are none.
+async fn probe_table(
+    ctx: &SessionContext,
+    location: &Url,
+    format: Arc<dyn FileFormat>,
+) -> Result<Option<SchemaRef>> {
+    let table_url = ListingTableUrl::parse(location)?;
+    let options = ListingOptions::new(format.clone()).with_file_extension(format.get_ext());
+    let state = ctx.state();
+    let store = state.runtime_env().object_store(&table_url)?;
+    let files: Vec<_> = table_url
+        .list_all_files(&state, store.as_ref(), &options.file_extension)
+        .await?
+        .try_collect()
+        .await?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(options.infer_schema(&state, &table_url).await?))
+}
+
+/// Columns of `expected` missing from `found` or not readable as the expected type.
+fn schema_mismatches(expected: &SchemaRef, found: &SchemaRef) -> Vec<ColumnMismatch> {
+    expected
+        .fields()
+        .iter()
+        .filter_map(|field| {
+            let found = found
+                .field_with_name(field.name())
+                .ok()
+                .map(|f| f.data_type().clone());
+            let compatible = found
+                .as_ref()
+                .is_some_and(|found| compatible_types(found, field.data_type()));
+            (!compatible).then(|| ColumnMismatch {
+                column: field.name().clone(),
+                expected: field.data_type().clone(),
+                found,
+            })
+        })
+        .collect()
+}
+
+/// Whether values of type `found` can be read as `expected`.
+///
+/// Nested types are only compared by their kind, since readers adapt the fields of
+/// structs and lists to the table schema.
+fn compatible_types(found: &DataType, expected: &DataType) -> bool {
+    match (found, expected) {
+        (DataType::Struct(_), DataType::Struct(_)) => true,
+        (
+            DataType::List(_) | DataType::LargeList(_) | DataType::ListView(_),
+            DataType::List(_) | DataType::LargeList(_) | DataType::ListView(_),
+        ) => true,
+        (DataType::Struct(_) | DataType::List(_) | DataType::LargeList(_), _)
+        | (_, DataType::Struct(_) | DataType::List(_) | DataType::LargeList(_)) => false,
+        (found, expected) => can_cast_types(found, expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{ArrayRef, RecordBatch, StringArray};
+    use arrow_schema::{Field, Schema};
+    use datafusion::dataframe::DataFrameWriteOptions;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{Error, SimulationContext};
+
+    #[test]
+    fn test_schema_mismatches() {
+        let expected = RoutingData::nodes_schema();
+        assert!(schema_mismatches(&expected, &expected).is_empty());
+
+        let found = Arc::new(Schema::new(vec![
+            Field::new("location", DataType::Utf8View, false),
+            Field::new("id", DataType::FixedSizeBinary(16), false),
+            Field::new("geometry", DataType::Int64, true),
+        ]));
+        let columns = schema_mismatches(&expected, &found);
+        let names: Vec<_> = columns.iter().map(|c| c.column.as_str()).collect();
+        assert_eq!(names, ["properties", "geometry"]);
+        assert!(columns[0].found.is_none());
+        assert_eq!(columns[1].found, Some(DataType::Int64));
+    }
+
+    #[tokio::test]
+    async fn test_probe_storage() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+
+        // continuing a simulation requires recorded simulations
+        let missing = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_simulation_id(Uuid::now_v7())
+            .build()
+            .await;
+        let Err(Error::InvalidWorkingDirectory(report)) = missing else {
+            panic!("expected an invalid working directory");
+        };
+        let blocking: Vec<_> = report
+            .blocking()
+            .map(|d| (d.table.as_str(), &d.problem))
+            .collect();
+        assert_eq!(blocking, [("system.simulations", &TableProblem::Missing)]);
+        assert!(
+            report
+                .diagnostics
+                .iter()
+                .any(|d| d.table == "system.routing_nodes" && d.problem == TableProblem::Missing)
+        );
+
+        // routing data written with an incompatible schema
+        let batch = RecordBatch::try_from_iter([(
+            "location",
+            Arc::new(StringArray::from(vec!["london"])) as ArrayRef,
+        )])?;
+        let nodes = location.join("system/routing_nodes/")?;
+        SessionContext::new()
+            .read_batch(batch)?
+            .write_parquet(nodes.path(), DataFrameWriteOptions::new(), None)
+            .await?;
+        let report = SimulationContext::builder()
+            .with_working_directory(location)
+            .probe()
+            .await?;
+        assert!(!report.is_ok());
+        let [diagnostic] = report.blocking().collect::<Vec<_>>()[..] else {
+            panic!("expected a single blocking problem, got {report}");
+        };
+        let TableProblem::SchemaMismatch { columns } = &diagnostic.problem else {
+            panic!("expected a schema mismatch, got {diagnostic}");
+        };
+        let names: Vec<_> = columns.iter().map(|c| c.column.as_str()).collect();
+        assert_eq!(names, ["id", "properties", "geometry"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+//! In-process cache of snapshot queries.
+//!
+//! Snapshots are immutable once written, yet agents and state loaders scan the same
+//! snapshot tables several times while a simulation is set up, e.g. the objects of the
+//! current snapshot for every runner. The cache keeps the materialized batches of each
+//! query keyed by table, snapshot and filter, so repeated lookups are served from
+//! memory instead of scanning the stored files again. Entries are kept for the whole
+//! run, those of earlier snapshots are dropped whenever the context moves on to a new
+//! snapshot.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
+use datafusion::prelude::Expr;
+use uuid::Uuid;
+
+/// Table, snapshot and filter of a cached query.
+type QueryKey = (String, Uuid, Option<Expr>);
+
+/// Materialized results of snapshot queries.
+#[derive(Debug, Default)]
+pub(crate) struct QueryCache {
+    entries: Mutex<HashMap<QueryKey, (SchemaRef, Vec<RecordBatch>)>>,
+}
+
+impl QueryCache {
+    pub(crate) fn key(table: &str, snapshot_id: Uuid, filter: Option<&Expr>) -> QueryKey {
+        (table.to_string(), snapshot_id, filter.cloned())
+    }
+
+    pub(crate) fn get(&self, key: &QueryKey) -> Option<(SchemaRef, Vec<RecordBatch>)> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    pub(crate) fn insert(&self, key: QueryKey, schema: SchemaRef, batches: Vec<RecordBatch>) {
+        self.entries.lock().unwrap().insert(key, (schema, batches));
+    }
+
+    /// Drop the results of all snapshots other than `snapshot_id`.
+    pub(crate) fn retain_snapshot(&self, snapshot_id: Uuid) {
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|(_, id, _), _| *id == snapshot_id);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_schema::Schema;
+    use datafusion::prelude::{col, lit};
+
+    use super::*;
+
+    #[test]
+    fn test_query_cache() {
+        let cache = QueryCache::default();
+        let (first, second) = (Uuid::now_v7(), Uuid::now_v7());
+        let filter = col("label").eq(lit("menu_item"));
+        let schema = Arc::new(Schema::empty());
+
+        let key = QueryCache::key("objects", first, Some(&filter));
+        assert_eq!(key, QueryCache::key("objects", first, Some(&filter)));
+        assert_ne!(key, QueryCache::key("objects", first, None));
+        assert!(cache.get(&key).is_none());
+
+        cache.insert(key.clone(), schema.clone(), vec![]);
+        cache.insert(QueryCache::key("objects", second, None), schema, vec![]);
+        assert!(cache.get(&key).is_some());
+
+        cache.retain_snapshot(second);
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.len(), 1);
+    }
+}
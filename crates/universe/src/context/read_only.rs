@@ -0,0 +1,208 @@
+//! Read-only access to the tables of a working directory.
+//!
+//! Contexts built with [`with_read_only`](super::SimulationContextBuilder::with_read_only)
+//! open an existing simulation for analysis. No system tables are created, no
+//! simulation or snapshot is registered, and every table of the catalog is wrapped
+//! in a [`ReadOnlyTable`], so neither the simulation nor queries run against the
+//! session, e.g. `INSERT INTO` statements, can write to the working directory.
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use arrow_schema::SchemaRef;
+use async_trait::async_trait;
+use datafusion::catalog::{
+    CatalogProvider, MemoryCatalogProvider, MemorySchemaProvider, SchemaProvider, Session,
+    TableProvider,
+};
+use datafusion::common::{Constraints, Statistics, plan_err};
+use datafusion::datasource::TableType;
+use datafusion::logical_expr::dml::InsertOp;
+use datafusion::logical_expr::{LogicalPlan, TableProviderFilterPushDown};
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::prelude::Expr;
+
+use crate::Result;
+
+/// A table which can be scanned, but rejects all writes.
+#[derive(Debug)]
+pub(super) struct ReadOnlyTable {
+    inner: Arc<dyn TableProvider>,
+}
+
+impl ReadOnlyTable {
+    pub(super) fn new(inner: Arc<dyn TableProvider>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl TableProvider for ReadOnlyTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn constraints(&self) -> Option<&Constraints> {
+        self.inner.constraints()
+    }
+
+    fn table_type(&self) -> TableType {
+        self.inner.table_type()
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        self.inner.get_table_definition()
+    }
+
+    fn get_logical_plan(&'_ self) -> Option<Cow<'_, LogicalPlan>> {
+        self.inner.get_logical_plan()
+    }
+
+    fn get_column_default(&self, column: &str) -> Option<&Expr> {
+        self.inner.get_column_default(column)
+    }
+
+    async fn scan(
+        &self,
+        state: &dyn Session,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        self.inner.scan(state, projection, filters, limit).await
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> datafusion::common::Result<Vec<TableProviderFilterPushDown>> {
+        self.inner.supports_filters_pushdown(filters)
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.inner.statistics()
+    }
+
+    async fn insert_into(
+        &self,
+        _state: &dyn Session,
+        _input: Arc<dyn ExecutionPlan>,
+        _insert_op: InsertOp,
+    ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
+        plan_err!("table is read-only")
+    }
+}
+
+/// A copy of `catalog` with all of its tables wrapped in a [`ReadOnlyTable`].
+pub(super) async fn read_only_catalog(
+    catalog: &dyn CatalogProvider,
+) -> Result<Arc<dyn CatalogProvider>> {
+    let read_only = Arc::new(MemoryCatalogProvider::new());
+    for schema_name in catalog.schema_names() {
+        let Some(schema) = catalog.schema(&schema_name) else {
+            continue;
+        };
+        let read_only_schema = Arc::new(MemorySchemaProvider::new());
+        for table_name in schema.table_names() {
+            let Some(table) = schema.table(&table_name).await? else {
+                continue;
+            };
+            read_only_schema.register_table(table_name, Arc::new(ReadOnlyTable::new(table)))?;
+        }
+        read_only.register_schema(&schema_name, read_only_schema)?;
+    }
+    Ok(read_only)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use datafusion::prelude::SessionContext;
+    use url::Url;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{Error, ObjectData, PopulationData, SimulationContext, Template};
+
+    fn list_files(dir: &Path) -> Vec<std::path::PathBuf> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(list_files(&path));
+            } else {
+                files.push(path);
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_read_only() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+
+        // nothing is bootstrapped in a fresh working directory
+        let fresh = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_simulation_id(Uuid::now_v7())
+            .with_read_only(true)
+            .build()
+            .await;
+        assert!(matches!(fresh, Err(Error::InvalidWorkingDirectory(_))));
+        assert!(list_files(dir.path()).is_empty());
+
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let source = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+        let files = list_files(dir.path());
+
+        // the latest snapshot of the simulation is opened by default
+        let ctx = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_simulation_id(*source.simulation_id())
+            .with_read_only(true)
+            .build()
+            .await?;
+        assert!(ctx.is_read_only());
+        assert_eq!(ctx.snapshot_id(), source.snapshot_id());
+        let objects = ctx.collect(ctx.snapshots().objects().await?).await?;
+        assert!(objects.iter().map(|batch| batch.num_rows()).sum::<usize>() > 0);
+        for name in ctx.snapshots().table_names() {
+            ctx.snapshots().table(name).await?;
+        }
+        let missing = ctx.snapshots().table("invoices").await;
+        assert!(matches!(missing, Err(Error::NotFound { .. })));
+
+        let batch = SessionContext::new()
+            .sql("SELECT arrow_cast('x', 'Utf8View') AS id")
+            .await?;
+        let write = ctx.append_table(batch, "caspers.system.simulations").await;
+        assert!(matches!(write, Err(Error::ReadOnly(_))));
+        let insert = ctx
+            .ctx()
+            .sql("INSERT INTO caspers.system.simulations (id) VALUES ('x')")
+            .await;
+        let insert = match insert {
+            Ok(df) => df.collect().await.map(|_| ()),
+            Err(err) => Err(err),
+        };
+        assert!(insert.is_err());
+
+        assert_eq!(list_files(dir.path()), files);
+        Ok(())
+    }
+}
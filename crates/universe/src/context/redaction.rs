@@ -0,0 +1,247 @@
+//! Redaction of columns before data is persisted.
+//!
+//! Deployments which must not store personal data, not even synthetic, configure a
+//! [`RedactionPolicy`] on the context. Every write through the context, i.e. the
+//! population and order snapshots as well as all result tables, passes through the
+//! policy first, so redacted values never reach the working directory. The in-memory
+//! state of a running simulation is not affected.
+
+use std::collections::BTreeMap;
+
+use arrow_schema::{DataType, Field};
+use datafusion::common::Column;
+use datafusion::functions::core::expr_ext::FieldAccessor as _;
+use datafusion::functions::core::expr_fn::named_struct;
+use datafusion::functions::crypto::expr_fn::sha256;
+use datafusion::functions::encoding::expr_fn::encode;
+use datafusion::functions::unicode::expr_fn::{character_length, lpad, right};
+use datafusion::logical_expr::{cast, when};
+use datafusion::prelude::{DataFrame, Expr, lit};
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// How the values of a column are redacted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Redaction {
+    /// Write nulls instead of the values, only valid for nullable columns.
+    Drop,
+    /// Write the hex encoded SHA-256 digest of the values, so they can still be joined.
+    Hash,
+    /// Replace all but the last four characters of the values with `*`.
+    Mask,
+}
+
+/// Redactions applied to the columns of all tables written through a context.
+///
+/// Columns are named by their path, with fields of struct columns separated by dots,
+/// e.g. `properties.email`. Tables without any of the listed columns are written
+/// unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RedactionPolicy {
+    columns: BTreeMap<String, Redaction>,
+}
+
+impl RedactionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact the column at `path` with `redaction`.
+    pub fn with_column(mut self, path: impl Into<String>, redaction: Redaction) -> Self {
+        self.columns.insert(path.into(), redaction);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Redact the listed columns of `df`.
+    pub(crate) fn apply(&self, df: DataFrame) -> Result<DataFrame> {
+        if self.is_empty() {
+            return Ok(df);
+        }
+        let mut redacted = false;
+        let mut exprs = Vec::with_capacity(df.schema().fields().len());
+        for (qualifier, field) in df.schema().iter() {
+            let column = Expr::Column(Column::from((qualifier, field)));
+            match self.redact(field.name(), column.clone(), field)? {
+                Some(expr) => {
+                    redacted = true;
+                    exprs.push(expr.alias(field.name()));
+                }
+                None => exprs.push(column),
+            }
+        }
+        if !redacted {
+            return Ok(df);
+        }
+        Ok(df.select(exprs)?)
+    }
+
+    /// Redacted values of the column at `path`, `None` if nothing in it is redacted.
+    fn redact(&self, path: &str, expr: Expr, field: &Field) -> Result<Option<Expr>> {
+        if let Some(redaction) = self.columns.get(path) {
+            return redaction.apply(path, expr, field).map(Some);
+        }
+        let DataType::Struct(fields) = field.data_type() else {
+            return Ok(None);
+        };
+        let prefix = format!("{path}.");
+        if !self
+            .columns
+            .keys()
+            .any(|column| column.starts_with(&prefix))
+        {
+            return Ok(None);
+        }
+
+        // structs are rebuilt with the redacted values of their fields
+        let mut args = Vec::with_capacity(2 * fields.len());
+        for child in fields {
+            let child_expr = expr.clone().field(child.name());
+            let child_path = format!("{prefix}{}", child.name());
+            let redacted = self.redact(&child_path, child_expr.clone(), child)?;
+            args.push(lit(child.name().as_str()));
+            args.push(redacted.unwrap_or(child_expr));
+        }
+        // the cast restores the nullability of the fields, which is lost by `named_struct`
+        let rebuilt = cast(named_struct(args), field.data_type().clone());
+        let null = lit(ScalarValue::try_from(field.data_type())?);
+        Ok(Some(when(expr.is_null(), null).otherwise(rebuilt)?))
+    }
+}
+
+impl Redaction {
+    fn apply(&self, path: &str, expr: Expr, field: &Field) -> Result<Expr> {
+        let data_type = field.data_type();
+        let is_string = matches!(
+            data_type,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        );
+        match self {
+            Redaction::Drop if !field.is_nullable() => Err(Error::invalid_data(format!(
+                "column '{path}' is not nullable and cannot be dropped, hash or mask it instead"
+            ))),
+            Redaction::Drop => Ok(lit(ScalarValue::try_from(data_type)?)),
+            Redaction::Hash | Redaction::Mask if !is_string => Err(Error::invalid_data(format!(
+                "column '{path}' of type {data_type} cannot be hashed or masked, only strings can"
+            ))),
+            Redaction::Hash => Ok(cast(encode(sha256(expr), lit("hex")), data_type.clone())),
+            Redaction::Mask => {
+                let masked = lpad(vec![
+                    right(expr.clone(), lit(4)),
+                    character_length(expr),
+                    lit("*"),
+                ]);
+                Ok(cast(masked, data_type.clone()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{AsArray as _, RecordBatch};
+    use url::Url;
+
+    use super::*;
+    use crate::{ObjectData, PopulationData, SimulationContext, Template};
+
+    #[tokio::test]
+    async fn test_redaction() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+        let mut population = PopulationData::builder().with_seed(7);
+        population.add_site(10, 52.37, 4.89)?;
+        let population = population.finish()?;
+
+        let build = async |policy: RedactionPolicy| {
+            let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+            SimulationContext::builder()
+                .with_working_directory(location.clone())
+                .with_object_data(objects)
+                .with_population_data(population.clone())
+                .with_redaction(policy)
+                .build()
+                .await
+        };
+
+        let not_nullable =
+            build(RedactionPolicy::new().with_column("properties.first_name", Redaction::Drop))
+                .await;
+        // tables are written concurrently, which annotates failures with the table name
+        assert!(matches!(
+            not_nullable.as_ref().map_err(Error::root),
+            Err(Error::InvalidData(_))
+        ));
+
+        let ctx = build(
+            RedactionPolicy::new()
+                .with_column("properties.cc_number", Redaction::Drop)
+                .with_column("properties.email", Redaction::Hash)
+                .with_column("properties.last_name", Redaction::Mask),
+        )
+        .await?;
+        let stored = ctx.collect(ctx.snapshots().population().await?).await?;
+        let stored = arrow::compute::concat_batches(&stored[0].schema(), &stored)?;
+
+        let by_id = |batch: &RecordBatch, field: &str| {
+            let ids = batch.column_by_name("id").unwrap().as_fixed_size_binary();
+            let properties = batch.column_by_name("properties").unwrap().as_struct();
+            let values = properties.column_by_name(field).unwrap();
+            let values = arrow::compute::cast(values, &DataType::Utf8).unwrap();
+            ids.iter()
+                .map(|id| id.unwrap().to_vec())
+                .zip(
+                    values
+                        .as_string::<i32>()
+                        .iter()
+                        .map(|v| v.map(str::to_string)),
+                )
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let cc_numbers = by_id(&stored, "cc_number");
+        assert!(cc_numbers.values().all(Option::is_none));
+
+        let emails = by_id(&population, "email");
+        for (id, hashed) in by_id(&stored, "email") {
+            let hashed = hashed.unwrap();
+            assert_eq!(hashed.len(), 64);
+            assert_ne!(Some(hashed), emails[&id]);
+        }
+
+        let last_names = by_id(&population, "last_name");
+        for (id, masked) in by_id(&stored, "last_name") {
+            let (masked, last_name) = (masked.unwrap(), last_names[&id].clone().unwrap());
+            let visible = last_name.chars().count().saturating_sub(4);
+            assert_eq!(masked.chars().count(), last_name.chars().count());
+            assert!(masked.chars().take(visible).all(|c| c == '*'));
+            assert!(masked.ends_with(&last_name.chars().skip(visible).collect::<String>()));
+        }
+
+        assert_eq!(
+            by_id(&stored, "first_name"),
+            by_id(&population, "first_name")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_redaction_policy_json() -> Result<()> {
+        let policy: RedactionPolicy =
+            serde_json::from_str(r#"{"properties.email": "hash", "cc_number": "mask"}"#)?;
+        assert_eq!(
+            policy,
+            RedactionPolicy::new()
+                .with_column("properties.email", Redaction::Hash)
+                .with_column("cc_number", Redaction::Mask)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,525 @@
+//! Reconstruction of the simulation state at arbitrary points in time, and reuse of
+//! snapshot data in new simulations.
+
+use arrow::array::{AsArray as _, RecordBatch};
+use arrow::compute::concat_batches;
+use arrow::datatypes::TimestampMillisecondType;
+use chrono::{DateTime, Utc};
+use datafusion::prelude::{SessionContext, cast, col, lit};
+use datafusion::scalar::ScalarValue;
+use uuid::Uuid;
+
+use crate::builders::POPULATION_SCHEMA;
+use crate::{
+    Error, EventPayload, ObjectData, OrderData, PersonState, PersonStatusFlag, PopulationData,
+    Result, SimulationConfig, State,
+};
+
+use super::SimulationContext;
+use super::query_cache::QueryCache;
+
+impl SimulationContext {
+    /// The simulation state at `timestamp`, registered as tables of a new session.
+    ///
+    /// The state is restored from the latest snapshot of the simulation taken at or
+    /// before `timestamp`, and the events recorded since then up to `timestamp` are
+    /// applied to it. The session provides the `objects`, `population`, `orders` and
+    /// `order_lines` tables.
+    ///
+    /// Replaying requires the events to be written. Positions of moving people and
+    /// object changes are not part of the events and reflect the snapshot.
+    pub async fn state_at(&self, timestamp: DateTime<Utc>) -> Result<SessionContext> {
+        let (snapshot_id, snapshot_time) = self.snapshot_before(timestamp).await?;
+        let snapshot = self.scoped_to(self.simulation_id, snapshot_id, snapshot_time);
+
+        let config = SimulationConfig {
+            simulation_start: snapshot_time,
+            ..Default::default()
+        };
+        let mut state = State::new(
+            &config,
+            snapshot.snapshot_objects().await?,
+            PopulationData::try_new_from_ctx(&snapshot).await?,
+            OrderData::try_new(&snapshot).await?,
+            Default::default(),
+        );
+
+        let recorded = self
+            .results()
+            .events_between(snapshot_time, timestamp)
+            .await?;
+        let mut events = Vec::new();
+        for batch in self.collect(recorded).await? {
+            let data = batch.column(2).as_string::<i64>();
+            for payload in data.iter().flatten() {
+                events.push(serde_json::from_str::<EventPayload>(payload)?);
+            }
+        }
+        state.apply_events(&snapshot, &events).await?;
+
+        let session = SessionContext::new();
+        session.register_batch("objects", state.objects().objects().clone())?;
+        session.register_batch("population", state.population().snapshot().clone())?;
+        session.register_batch("orders", state.orders().batch_orders().clone())?;
+        session.register_batch("order_lines", state.orders().batch_lines().clone())?;
+        Ok(session)
+    }
+
+    /// A context reading the tables of another snapshot within the same session.
+    pub(in crate::context) fn scoped_to(
+        &self,
+        simulation_id: Uuid,
+        snapshot_id: Uuid,
+        current_time: DateTime<Utc>,
+    ) -> SimulationContext {
+        SimulationContext {
+            ctx: self.ctx.clone(),
+            simulation_id,
+            snapshot_id,
+            current_time,
+            time_step: self.time_step,
+            retry_policy: self.retry_policy,
+            write_concurrency: self.write_concurrency,
+            run_name: self.run_name.clone(),
+            read_only: self.read_only,
+            redaction: self.redaction.clone(),
+            query_cache: QueryCache::default(),
+        }
+    }
+
+    pub(in crate::context) async fn snapshot_objects(&self) -> Result<ObjectData> {
+        let objects = self.collect(self.snapshots().objects().await?).await?;
+        match objects.first() {
+            Some(batch) => ObjectData::try_new(concat_batches(batch.schema_ref(), &objects)?),
+            None => Err(Error::not_found("objects of snapshot", self.snapshot_id)),
+        }
+    }
+
+    /// People of the snapshot, idle and without any orders or journeys.
+    pub(in crate::context) async fn fresh_population(&self) -> Result<RecordBatch> {
+        let status_type = POPULATION_SCHEMA
+            .field_with_name("status")?
+            .data_type()
+            .clone();
+        let initial_state = serde_json::to_string(&PersonState::default())?;
+        let population = self.snapshots().population().await?.select(vec![
+            col("id"),
+            col("role"),
+            cast(lit(PersonStatusFlag::Idle.as_ref()), status_type).alias("status"),
+            col("properties"),
+            col("position"),
+            lit(ScalarValue::Utf8View(Some(initial_state))).alias("state"),
+            col("schedule"),
+            col("age_bracket"),
+            col("household_size"),
+            col("income_band"),
+            col("preferences"),
+        ])?;
+        let batches = self.collect(population).await?;
+        match batches.first() {
+            Some(batch) if batches.iter().any(|batch| batch.num_rows() > 0) => {
+                Ok(concat_batches(batch.schema_ref(), &batches)?)
+            }
+            _ => Err(Error::not_found("population of snapshot", self.snapshot_id)),
+        }
+    }
+
+    /// The full simulation state of the snapshot the context is scoped to.
+    ///
+    /// Unlike [`fresh_population`](Self::fresh_population), people keep their
+    /// activities and orders are retained, so a simulation can continue from it.
+    pub(in crate::context) async fn snapshot_state(&self) -> Result<State> {
+        let config = SimulationConfig {
+            simulation_start: self.snapshot_time().await?,
+            ..Default::default()
+        };
+        Ok(State::new(
+            &config,
+            self.snapshot_objects().await?,
+            PopulationData::try_new_from_ctx(self).await?,
+            OrderData::try_new(self).await?,
+            Default::default(),
+        ))
+    }
+
+    /// Simulation time at which the snapshot the context is scoped to was taken.
+    async fn snapshot_time(&self) -> Result<DateTime<Utc>> {
+        let snapshots = self
+            .system()
+            .snapshots()
+            .await?
+            .filter(
+                col("simulation_id")
+                    .eq(lit(ScalarValue::Utf8View(Some(
+                        self.simulation_id.to_string(),
+                    ))))
+                    .and(col("id").eq(lit(ScalarValue::Utf8View(Some(
+                        self.snapshot_id.to_string(),
+                    ))))),
+            )?
+            .select_columns(&["simulation_time"])?;
+        let batches = self.collect(snapshots).await?;
+        let Some(batch) = batches.iter().find(|batch| batch.num_rows() > 0) else {
+            return Err(Error::not_found("snapshot", self.snapshot_id));
+        };
+        let time = batch
+            .column(0)
+            .as_primitive::<TimestampMillisecondType>()
+            .value(0);
+        DateTime::from_timestamp_millis(time)
+            .ok_or_else(|| Error::invalid_data("snapshot time out of range"))
+    }
+
+    /// Id and time of the latest snapshot taken at or before `timestamp`.
+    async fn snapshot_before(&self, timestamp: DateTime<Utc>) -> Result<(Uuid, DateTime<Utc>)> {
+        let snapshots = self
+            .system()
+            .snapshots()
+            .await?
+            .filter(col("simulation_id").eq(lit(ScalarValue::Utf8View(Some(
+                self.simulation_id.to_string(),
+            )))))?
+            .filter(
+                col("simulation_time").lt_eq(lit(ScalarValue::TimestampMillisecond(
+                    Some(timestamp.timestamp_millis()),
+                    Some("UTC".into()),
+                ))),
+            )?
+            .sort(vec![col("simulation_time").sort(false, false)])?
+            .limit(0, Some(1))?
+            .select_columns(&["id", "simulation_time"])?;
+        let batches = self.collect(snapshots).await?;
+        let Some(batch) = batches.iter().find(|batch| batch.num_rows() > 0) else {
+            return Err(Error::not_found(
+                "snapshot",
+                format!("at or before {}", timestamp.to_rfc3339()),
+            ));
+        };
+        let id = Uuid::parse_str(batch.column(0).as_string_view().value(0))?;
+        let time = batch
+            .column(1)
+            .as_primitive::<TimestampMillisecondType>()
+            .value(0);
+        let time = DateTime::from_timestamp_millis(time)
+            .ok_or_else(|| Error::invalid_data("snapshot time out of range"))?;
+        Ok((id, time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use datafusion::prelude::col;
+    use geo::Point;
+
+    use super::*;
+    use crate::test_utils::order_created;
+    use crate::{
+        BrandId, EntityView as _, EventDataBuilder, MenuItemId, OrderCreatedPayload, OrderId,
+        OrderStatus, PriorityTier, Template,
+    };
+
+    async fn order_statuses(session: &SessionContext) -> Result<Vec<String>> {
+        let orders = session.table("orders").await?.select(vec![col("status")])?;
+        Ok(orders
+            .collect()
+            .await?
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_string::<i32>()
+                    .iter()
+                    .flatten()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_state_at() -> Result<()> {
+        let setup = Template::default().load()?;
+        let objects = ObjectData::try_new(setup.object_data()?)?;
+        let site_id = objects.sites()?.next().unwrap().id();
+        let brand = &setup.brands[0];
+        let item = (
+            BrandId::from_name(&brand.name),
+            MenuItemId::from_names(&brand.name, &brand.items[0].name),
+        );
+        let mut population = PopulationData::builder();
+        population.add_site(1, 52.37, 4.89)?;
+
+        let start = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .with_simulation_start_time(start)
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let order_id = OrderId::new();
+        let created = EventPayload::OrderCreated(OrderCreatedPayload {
+            site_id,
+            items: vec![item],
+            cuisines: vec![None],
+            destination: Point::new(4.89, 52.37),
+            total: 12.5,
+            priority: PriorityTier::Vip,
+            promised_at: start + Duration::minutes(45),
+            ..order_created(order_id)
+        });
+        let mut events = EventDataBuilder::new();
+        events.add_payload(start + Duration::seconds(10), &created)?;
+        events.add_payload(
+            start + Duration::seconds(20),
+            &EventPayload::order_updated(order_id, OrderStatus::Processing, None),
+        )?;
+        ctx.results()
+            .write_events(ctx.ctx().read_batch(events.build()?)?)
+            .await?;
+
+        let before = ctx.state_at(start + Duration::seconds(5)).await?;
+        assert!(order_statuses(&before).await?.is_empty());
+
+        // events are applied up to the requested time
+        let created = ctx.state_at(start + Duration::seconds(15)).await?;
+        assert_eq!(order_statuses(&created).await?, vec!["submitted"]);
+        let priorities = created
+            .table("orders")
+            .await?
+            .select(vec![col("priority")])?
+            .collect()
+            .await?;
+        assert_eq!(priorities[0].column(0).as_string::<i32>().value(0), "vip");
+        let processing = ctx.state_at(start + Duration::seconds(30)).await?;
+        assert_eq!(order_statuses(&processing).await?, vec!["processing"]);
+
+        assert!(ctx.state_at(start - Duration::minutes(1)).await.is_err());
+        Ok(())
+    }
+
+    async fn population_ids(ctx: &SimulationContext) -> Result<Vec<Vec<u8>>> {
+        let population = ctx
+            .snapshots()
+            .population()
+            .await?
+            .sort(vec![col("id").sort(true, false)])?;
+        Ok(ctx
+            .collect(population)
+            .await?
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_fixed_size_binary()
+                    .iter()
+                    .flatten()
+                    .map(<[u8]>::to_vec)
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_population_snapshot() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let source = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let reused = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_population_snapshot(*source.simulation_id(), *source.snapshot_id())
+            .build()
+            .await?;
+        assert_ne!(reused.simulation_id(), source.simulation_id());
+        assert_eq!(
+            population_ids(&reused).await?,
+            population_ids(&source).await?
+        );
+
+        let statuses = reused
+            .collect(reused.snapshots().population().await?)
+            .await?;
+        for batch in &statuses {
+            let statuses =
+                arrow::compute::cast(batch.column(2), &arrow::datatypes::DataType::Utf8)?;
+            assert!(
+                statuses
+                    .as_string::<i32>()
+                    .iter()
+                    .all(|s| s == Some("idle"))
+            );
+        }
+        let orders = reused.collect(reused.snapshots().orders().await?).await?;
+        assert_eq!(orders.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+
+        let missing = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_population_snapshot(*source.simulation_id(), Uuid::now_v7())
+            .build()
+            .await;
+        assert!(missing.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fork_from() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+
+        let start = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let parent = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_simulation_start_time(start)
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let fork = || {
+            SimulationContext::builder()
+                .with_working_directory(location.clone())
+                .fork_from(*parent.simulation_id(), *parent.snapshot_id())
+                .build()
+        };
+        let first = fork().await?;
+        let second = fork().await?;
+        assert_ne!(first.simulation_id(), parent.simulation_id());
+        assert_ne!(first.simulation_id(), second.simulation_id());
+        assert_eq!(*first.current_time(), start);
+        assert_eq!(
+            population_ids(&first).await?,
+            population_ids(&parent).await?
+        );
+
+        let simulations = first
+            .system()
+            .simulations()
+            .await?
+            .filter(col("id").eq(lit(ScalarValue::Utf8View(Some(
+                first.simulation_id().to_string(),
+            )))))?
+            .select_columns(&["properties"])?;
+        let batches = first.collect(simulations).await?;
+        let properties: serde_json::Value =
+            serde_json::from_str(batches[0].column(0).as_string_view().value(0))?;
+        assert_eq!(
+            properties["forked_from"]["snapshot_id"],
+            parent.snapshot_id().to_string()
+        );
+
+        let missing = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .fork_from(*parent.simulation_id(), Uuid::now_v7())
+            .build()
+            .await;
+        assert!(missing.is_err());
+        let conflicting = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_simulation_id(*parent.simulation_id())
+            .fork_from(*parent.simulation_id(), *parent.snapshot_id())
+            .build()
+            .await;
+        assert!(conflicting.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+
+        let start = DateTime::parse_from_rfc3339("2025-01-02T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let mut ctx = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_simulation_start_time(start)
+            .with_run_name("backfill".to_string())
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let state = ctx.snapshot_state().await?;
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        ctx.write_checkpoint(&state, day).await?;
+
+        let snapshots = ctx
+            .system()
+            .snapshots()
+            .await?
+            .filter(col("id").eq(lit(ScalarValue::Utf8View(Some(
+                ctx.snapshot_id().to_string(),
+            )))))?
+            .select_columns(&["properties"])?;
+        let batches = ctx.collect(snapshots).await?;
+        let properties: serde_json::Value =
+            serde_json::from_str(batches[0].column(0).as_string_view().value(0))?;
+        assert_eq!(properties["run_name"], "backfill");
+        assert_eq!(properties["checkpoint"]["day"], "2025-01-01");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resume_snapshot() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+
+        let start = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        let source = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_simulation_start_time(start)
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await?;
+
+        let resumed = SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_resume_snapshot(*source.snapshot_id())
+            .await?
+            .build()
+            .await?;
+        assert_eq!(resumed.simulation_id(), source.simulation_id());
+        assert_eq!(resumed.snapshot_id(), source.snapshot_id());
+        assert_eq!(*resumed.current_time(), start);
+        assert_eq!(
+            population_ids(&resumed).await?,
+            population_ids(&source).await?
+        );
+
+        let missing = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_resume_snapshot(Uuid::now_v7())
+            .await;
+        assert!(missing.is_err());
+        Ok(())
+    }
+}
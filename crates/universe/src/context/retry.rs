@@ -0,0 +1,218 @@
+//! Retries of storage operations with exponential backoff.
+//!
+//! Long runs against remote object stores should not fail on a single dropped
+//! connection or throttled request. Operations are retried with exponentially
+//! growing delays and full jitter, as long as the error is considered transient.
+
+use std::future::Future;
+use std::io::ErrorKind as IoErrorKind;
+use std::time::Duration;
+
+use datafusion::common::DataFusionError;
+use rand::Rng as _;
+
+use crate::{Error, Result};
+
+/// Limits for retrying failed storage operations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy running every operation exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Number of retries after the first attempt.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry.
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Upper bound for the delay between two attempts.
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Factor by which the delay grows with every retry, at least 1.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Upper bound of the delay before retry number `retry`, starting at zero.
+    pub(crate) fn max_delay(&self, retry: usize) -> Duration {
+        let factor = self.multiplier.powi(retry.min(i32::MAX as usize) as i32);
+        self.initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff)
+    }
+
+    /// Run `operation` until it succeeds, fails with a fatal error or runs out of retries.
+    ///
+    /// `name` describes the operation in logs and in the context of the returned error.
+    pub async fn retry<T, F, Fut>(&self, name: &str, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retry < self.max_retries && err.is_retryable() => {
+                    // full jitter spreads out retries of concurrent writers
+                    let delay = self
+                        .max_delay(retry)
+                        .mul_f64(rand::rng().random_range(0.0..=1.0));
+                    tracing::warn!(
+                        target: "caspers::storage",
+                        "{name} failed (attempt {} of {}), retrying in {delay:?}: {err}",
+                        retry + 1,
+                        self.max_retries + 1,
+                    );
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                Err(err) if retry > 0 => {
+                    return Err(err.context(format!("{name} failed after {} attempts", retry + 1)));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Whether the error is likely transient, so retrying the operation may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            Error::ObjectStore { source } => object_store_retryable(source),
+            Error::Url { source } => io_retryable(source.kind()),
+            Error::Datafusion { source } => datafusion_retryable(source),
+            _ => false,
+        }
+    }
+}
+
+fn object_store_retryable(err: &object_store::Error) -> bool {
+    // request failures of the HTTP clients, e.g. timeouts or 5xx responses, are
+    // reported as generic errors, all other variants are definite answers
+    matches!(err, object_store::Error::Generic { .. })
+}
+
+fn io_retryable(kind: IoErrorKind) -> bool {
+    matches!(
+        kind,
+        IoErrorKind::TimedOut
+            | IoErrorKind::Interrupted
+            | IoErrorKind::WouldBlock
+            | IoErrorKind::ConnectionReset
+            | IoErrorKind::ConnectionAborted
+            | IoErrorKind::ConnectionRefused
+            | IoErrorKind::BrokenPipe
+            | IoErrorKind::UnexpectedEof
+    )
+}
+
+fn datafusion_retryable(err: &DataFusionError) -> bool {
+    match err.find_root() {
+        DataFusionError::ObjectStore(source) => object_store_retryable(source),
+        DataFusionError::IoError(source) => io_retryable(source.kind()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn transient() -> Error {
+        std::io::Error::new(IoErrorKind::TimedOut, "timed out").into()
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let policy = RetryPolicy::default()
+            .with_max_retries(3)
+            .with_initial_backoff(Duration::from_millis(1));
+
+        // transient errors are retried until the operation succeeds
+        let attempts = AtomicUsize::new(0);
+        let result = policy
+            .retry("test", || async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(transient()),
+                    _ => Ok(42),
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // fatal errors are returned right away
+        let attempts = AtomicUsize::new(0);
+        let result: Result<()> = policy
+            .retry("test", || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(Error::invalid_data("broken"))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // retries are limited
+        let attempts = AtomicUsize::new(0);
+        let result: Result<()> = policy
+            .retry("test", || async {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(transient())
+            })
+            .await;
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_max_delay() {
+        let policy = RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_secs(1));
+        assert_eq!(policy.max_delay(0), Duration::from_millis(100));
+        assert_eq!(policy.max_delay(2), Duration::from_millis(400));
+        assert_eq!(policy.max_delay(10), Duration::from_secs(1));
+        assert_eq!(policy.max_delay(usize::MAX), Duration::from_secs(1));
+    }
+}
@@ -1,9 +1,16 @@
+use std::collections::HashMap;
 use std::sync::LazyLock;
 
-use datafusion::prelude::DataFrame;
+use arrow::array::{Array as _, AsArray as _};
+use arrow::datatypes::{DataType, Int64Type, TimeUnit, TimestampNanosecondType};
+use chrono::{DateTime, Utc};
+use datafusion::common::ScalarValue;
+use datafusion::functions_aggregate::expr_fn::{max, min};
+use datafusion::prelude::{DataFrame, cast, col, lit};
 use datafusion::sql::TableReference;
 
 use crate::Result;
+use crate::idents::SiteId;
 
 use crate::context::SimulationContext;
 
@@ -12,6 +19,26 @@ pub(in crate::context) static METRICS_REF: LazyLock<TableReference> =
     LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "metrics"));
 pub(in crate::context) static EVENTS_REF: LazyLock<TableReference> =
     LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "events"));
+pub(in crate::context) static ORDER_HEATMAP_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "order_heatmap"));
+pub(in crate::context) static INVOICES_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "invoices"));
+pub(in crate::context) static MARKET_SHARE_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "cuisine_market_share"));
+pub(in crate::context) static DAILY_SUMMARY_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "daily_summary"));
+pub(in crate::context) static FEEDBACK_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "order_feedback"));
+pub(in crate::context) static INVENTORY_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "ingredient_inventory"));
+pub(in crate::context) static FOOTPRINT_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "delivery_footprint"));
+pub(in crate::context) static GREEN_DELIVERY_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "green_delivery"));
+pub(in crate::context) static WASTE_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "ingredient_waste"));
+pub(in crate::context) static IMPRESSIONS_REF: LazyLock<TableReference> =
+    LazyLock::new(|| TableReference::full("caspers", RESULTS_SCHEMA_NAME, "impressions"));
 
 pub struct ResultsSchema<'a> {
     ctx: &'a SimulationContext,
@@ -33,14 +60,12 @@ impl<'a> ResultsSchema<'a> {
 
     pub async fn write_metrics(&self, data: DataFrame) -> Result<()> {
         self.ctx
-            .extend_df(data)?
-            .write_table(METRICS_REF.to_string().as_str(), Default::default())
-            .await?;
-        Ok(())
+            .append_table(self.ctx.extend_df(data)?, &METRICS_REF.to_string())
+            .await
     }
 
     pub async fn events(&self) -> Result<DataFrame> {
-        static COLUMNS: &[&str; 7] = &[
+        static COLUMNS: &[&str; 8] = &[
             "id",
             "source",
             "specversion",
@@ -48,6 +73,7 @@ impl<'a> ResultsSchema<'a> {
             "datacontenttype",
             "time",
             "data",
+            "traceparent",
         ];
         Ok(self
             .ctx
@@ -58,9 +84,364 @@ impl<'a> ResultsSchema<'a> {
 
     pub async fn write_events(&self, data: DataFrame) -> Result<()> {
         self.ctx
-            .extend_df(data)?
-            .write_table(EVENTS_REF.to_string().as_str(), Default::default())
-            .await?;
-        Ok(())
+            .append_table(self.ctx.extend_df(data)?, &EVENTS_REF.to_string())
+            .await
+    }
+
+    /// Events of any run of the simulation recorded in `[start, end]`, in the order they were emitted.
+    pub(crate) async fn events_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<DataFrame> {
+        let time = cast(
+            col("time"),
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        );
+        let bound = |time: DateTime<Utc>| {
+            lit(ScalarValue::TimestampNanosecond(
+                time.timestamp_nanos_opt(),
+                Some("UTC".into()),
+            ))
+        };
+        Ok(self
+            .ctx
+            .scan(&EVENTS_REF)
+            .await?
+            .filter(col("simulation_id").eq(lit(ScalarValue::Utf8View(Some(
+                self.ctx.simulation_id().to_string(),
+            )))))?
+            .filter(time.clone().between(bound(start), bound(end)))?
+            .sort(vec![time.sort(true, false), col("id").sort(true, false)])?
+            .select_columns(&["id", "time", "data", "type"])?)
+    }
+
+    /// Simulated time of the first and last event recorded in any run of the simulation.
+    pub async fn event_time_range(&self) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        let time = cast(
+            col("time"),
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        );
+        let df = self
+            .ctx
+            .scan(&EVENTS_REF)
+            .await?
+            .filter(col("simulation_id").eq(lit(ScalarValue::Utf8View(Some(
+                self.ctx.simulation_id().to_string(),
+            )))))?
+            .aggregate(
+                vec![],
+                vec![min(time.clone()).alias("start"), max(time).alias("end")],
+            )?;
+        let Some(batch) = self.ctx.collect(df).await?.into_iter().next() else {
+            return Ok(None);
+        };
+        let start = batch.column(0).as_primitive::<TimestampNanosecondType>();
+        let end = batch.column(1).as_primitive::<TimestampNanosecondType>();
+        if batch.num_rows() == 0 || start.is_null(0) || end.is_null(0) {
+            return Ok(None);
+        }
+        Ok(Some((
+            DateTime::from_timestamp_nanos(start.value(0)),
+            DateTime::from_timestamp_nanos(end.value(0)),
+        )))
+    }
+
+    pub async fn invoices(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 15] = &[
+            "id",
+            "site_id",
+            "invoice_number",
+            "order_id",
+            "customer_id",
+            "issued_at",
+            "currency",
+            "net_amount",
+            "tax_rate",
+            "tax_amount",
+            "gross_amount",
+            "tip",
+            "total",
+            "base_currency",
+            "base_total",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&INVOICES_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub async fn write_invoices(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &INVOICES_REF.to_string())
+            .await
+    }
+
+    /// Rating prompts of delivered orders, with the rating if the customer responded.
+    pub async fn order_feedback(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 9] = &[
+            "id",
+            "order_id",
+            "site_id",
+            "customer_id",
+            "prompted_at",
+            "delivery_time_s",
+            "late_s",
+            "responded_at",
+            "rating",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&FEEDBACK_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub async fn write_order_feedback(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &FEEDBACK_REF.to_string())
+            .await
+    }
+
+    /// Opening and closing stock, deliveries and consumption of each ingredient per
+    /// site and simulated day, with calendar features of the day.
+    pub async fn ingredient_inventory(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 14] = &[
+            "day",
+            "site_id",
+            "ingredient",
+            "unit",
+            "opening_stock",
+            "received",
+            "consumed",
+            "unmet",
+            "closing_stock",
+            "day_of_week",
+            "week_of_year",
+            "month",
+            "day_of_year",
+            "is_weekend",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&INVENTORY_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_ingredient_inventory(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &INVENTORY_REF.to_string())
+            .await
+    }
+
+    /// Expired and discarded stock of each ingredient per site and simulated day.
+    pub async fn ingredient_waste(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 6] = &[
+            "day",
+            "site_id",
+            "ingredient",
+            "unit",
+            "expired",
+            "discarded",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&WASTE_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_ingredient_waste(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &WASTE_REF.to_string())
+            .await
+    }
+
+    /// Deliveries, distance travelled and grams of CO2 emitted per site, mode of
+    /// transport and simulated day.
+    pub async fn delivery_footprint(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 6] = &[
+            "day",
+            "site_id",
+            "mode",
+            "deliveries",
+            "distance_m",
+            "co2_g",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&FOOTPRINT_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_delivery_footprint(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &FOOTPRINT_REF.to_string())
+            .await
+    }
+
+    /// Orders, deliveries, delivery times and grams of CO2 emitted per site, simulated
+    /// day and delivery option chosen at checkout.
+    pub async fn green_delivery(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 9] = &[
+            "day",
+            "site_id",
+            "green",
+            "orders",
+            "delivered_orders",
+            "on_time_deliveries",
+            "bundled_deliveries",
+            "delivery_time_s",
+            "co2_g",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&GREEN_DELIVERY_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_green_delivery(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &GREEN_DELIVERY_REF.to_string())
+            .await
+    }
+
+    /// Menu items shown to customers in app sessions, and whether they were clicked
+    /// and ordered.
+    pub async fn impressions(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 12] = &[
+            "id",
+            "session_id",
+            "person_id",
+            "site_id",
+            "brand_id",
+            "menu_item_id",
+            "surface",
+            "position",
+            "propensity",
+            "shown_at",
+            "clicked",
+            "ordered",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&IMPRESSIONS_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_impressions(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &IMPRESSIONS_REF.to_string())
+            .await
+    }
+
+    /// Orders, revenue and average delivery time per H3 cell and hour.
+    pub async fn order_heatmap(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 8] = &[
+            "cell",
+            "resolution",
+            "hour",
+            "orders",
+            "delivered_orders",
+            "currency",
+            "revenue",
+            "avg_delivery_time_s",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&ORDER_HEATMAP_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_order_heatmap(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &ORDER_HEATMAP_REF.to_string())
+            .await
+    }
+
+    /// Orders, items and revenue per site and cuisine, with the share of each cuisine
+    /// in the items and revenue of the site.
+    pub async fn cuisine_market_share(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 8] = &[
+            "site_id",
+            "cuisine",
+            "orders",
+            "items",
+            "currency",
+            "revenue",
+            "item_share",
+            "revenue_share",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&MARKET_SHARE_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_cuisine_market_share(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &MARKET_SHARE_REF.to_string())
+            .await
+    }
+
+    /// Orders, revenue, on-time rate and courier utilization per simulated day.
+    pub async fn daily_summary(&self) -> Result<DataFrame> {
+        static COLUMNS: &[&str; 10] = &[
+            "day",
+            "steps",
+            "orders",
+            "delivered_orders",
+            "failed_orders",
+            "currency",
+            "revenue",
+            "on_time_rate",
+            "avg_delivery_time_s",
+            "courier_utilization",
+        ];
+        Ok(self
+            .ctx
+            .scan_scoped(&DAILY_SUMMARY_REF)
+            .await?
+            .select_columns(COLUMNS)?)
+    }
+
+    pub(crate) async fn write_daily_summary(&self, data: DataFrame) -> Result<()> {
+        self.ctx
+            .append_table(self.ctx.extend_df(data)?, &DAILY_SUMMARY_REF.to_string())
+            .await
+    }
+
+    /// Number of the last invoice issued by each site in any run of the simulation.
+    pub(crate) async fn last_invoice_numbers(&self) -> Result<HashMap<SiteId, u64>> {
+        let df = self
+            .ctx
+            .scan(&INVOICES_REF)
+            .await?
+            .filter(col("simulation_id").eq(lit(ScalarValue::Utf8View(Some(
+                self.ctx.simulation_id().to_string(),
+            )))))?
+            .aggregate(
+                vec![col("site_id")],
+                vec![max(col("invoice_number")).alias("invoice_number")],
+            )?;
+        let mut numbers = HashMap::new();
+        for batch in self.ctx.collect(df).await? {
+            let site_ids = batch.column(0).as_fixed_size_binary();
+            let invoice_numbers = batch.column(1).as_primitive::<Int64Type>();
+            for row in 0..batch.num_rows() {
+                numbers.insert(
+                    site_ids.value(row).try_into()?,
+                    invoice_numbers.value(row) as u64,
+                );
+            }
+        }
+        Ok(numbers)
     }
 }
@@ -1,15 +1,15 @@
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 
-use datafusion::dataframe::DataFrameWriteOptions;
-use datafusion::logical_expr::dml::InsertOp;
-use datafusion::prelude::{DataFrame, lit};
+use datafusion::catalog::MemTable;
+use datafusion::prelude::{DataFrame, Expr, lit};
 use datafusion::scalar::ScalarValue;
 use datafusion::sql::TableReference;
-use itertools::Itertools;
+use futures::FutureExt as _;
 use uuid::Uuid;
 
 use crate::context::SimulationContext;
-use crate::{Result, State};
+use crate::context::query_cache::QueryCache;
+use crate::{Error, Result, State};
 
 use super::system::{SNAPSHOT_META_REF, SnapshotMetaBuilder};
 
@@ -33,7 +33,7 @@ impl<'a> SnapshotsSchema<'a> {
     }
 
     pub async fn objects(&self) -> Result<DataFrame> {
-        static COLUMNS: &[&str; 5] = &["id", "parent_id", "label", "name", "properties"];
+        static COLUMNS: &[&str; 6] = &["id", "parent_id", "label", "name", "properties", "uri"];
         Ok(self
             .ctx
             .scan_scoped(&OBJECTS_REF)
@@ -42,7 +42,19 @@ impl<'a> SnapshotsSchema<'a> {
     }
 
     pub async fn population(&self) -> Result<DataFrame> {
-        static COLUMNS: &[&str; 6] = &["id", "role", "status", "properties", "position", "state"];
+        static COLUMNS: &[&str; 11] = &[
+            "id",
+            "role",
+            "status",
+            "properties",
+            "position",
+            "state",
+            "schedule",
+            "age_bracket",
+            "household_size",
+            "income_band",
+            "preferences",
+        ];
         Ok(self
             .ctx
             .scan_scoped(&POPULATION_REF)
@@ -51,7 +63,16 @@ impl<'a> SnapshotsSchema<'a> {
     }
 
     pub async fn orders(&self) -> Result<DataFrame> {
-        static COLUMNS: &[&str] = &["id", "site_id", "customer_id", "destination", "status"];
+        static COLUMNS: &[&str] = &[
+            "id",
+            "site_id",
+            "customer_id",
+            "destination",
+            "total",
+            "tip",
+            "currency",
+            "status",
+        ];
         Ok(self
             .ctx
             .scan_scoped(&ORDERS_REF)
@@ -67,17 +88,71 @@ impl<'a> SnapshotsSchema<'a> {
             .await?
             .select_columns(COLUMNS)?)
     }
+
+    /// Names of the tables stored with each snapshot.
+    pub fn table_names(&self) -> [&'static str; 4] {
+        [
+            OBJECTS_REF.table(),
+            POPULATION_REF.table(),
+            ORDERS_REF.table(),
+            ORDER_LINES_REF.table(),
+        ]
+    }
+
+    /// A snapshot table by name, see [`table_names`](Self::table_names).
+    pub async fn table(&self, name: &str) -> Result<DataFrame> {
+        match name {
+            "objects" => self.objects().await,
+            "population" => self.population().await,
+            "orders" => self.orders().await,
+            "order_lines" => self.order_lines().await,
+            _ => Err(Error::not_found("snapshot table", name)),
+        }
+    }
+
+    /// A snapshot table by name, filtered by `filter` and materialized in memory.
+    ///
+    /// Snapshots do not change once written, so the rows are read from storage once
+    /// per snapshot and filter, and served from memory for repeated lookups.
+    pub async fn cached(&self, name: &str, filter: Option<Expr>) -> Result<DataFrame> {
+        let cache = &self.ctx.query_cache;
+        let key = QueryCache::key(name, *self.ctx.snapshot_id(), filter.as_ref());
+        let (schema, batches) = match cache.get(&key) {
+            Some(entry) => entry,
+            None => {
+                let mut df = self.table(name).await?;
+                if let Some(filter) = filter {
+                    df = df.filter(filter)?;
+                }
+                let schema = df.schema().inner().clone();
+                let batches = self.ctx.collect(df).await?;
+                cache.insert(key, schema.clone(), batches.clone());
+                tracing::debug!(
+                    target: "caspers::simulation::context",
+                    "Cached snapshot table '{name}', {} queries cached",
+                    cache.len()
+                );
+                (schema, batches)
+            }
+        };
+        let table = MemTable::try_new(schema, vec![batches])?;
+        Ok(self.ctx.ctx().read_table(Arc::new(table))?)
+    }
 }
 
-pub(crate) async fn create_snapshot(state: &State, ctx: &SimulationContext) -> Result<Uuid> {
+pub(crate) async fn create_snapshot(
+    state: &State,
+    ctx: &SimulationContext,
+    properties: Option<String>,
+) -> Result<Uuid> {
     let snapshot_id = Uuid::now_v7();
     let id_val = ScalarValue::Utf8View(Some(snapshot_id.to_string()));
     let sim_id_val = ScalarValue::Utf8View(Some(ctx.simulation_id.to_string()));
 
     let append_cols = |df: DataFrame| -> Result<DataFrame> {
         Ok(df
-            .with_column("simulation_id", lit(sim_id_val.clone()))?
-            .with_column("snapshot_id", lit(id_val.clone()))?)
+            .with_column("snapshot_id", lit(id_val.clone()))?
+            .with_column("simulation_id", lit(sim_id_val.clone()))?)
     };
 
     let mut tasks_defs = vec![];
@@ -107,26 +182,25 @@ pub(crate) async fn create_snapshot(state: &State, ctx: &SimulationContext) -> R
     }
 
     let mut batch_sn = SnapshotMetaBuilder::new();
-    batch_sn.add_snapshot(&snapshot_id, &ctx.simulation_id, state.current_time(), None);
+    batch_sn.add_snapshot(
+        &snapshot_id,
+        &ctx.simulation_id,
+        state.current_time(),
+        properties,
+    );
     let batch_snapshot = batch_sn.build()?;
     let df_sn = ctx.ctx().read_batch(batch_snapshot)?;
     tasks_defs.push((SNAPSHOT_META_REF.to_string(), df_sn));
 
-    let write_table = async |df: DataFrame, table_name: String| {
-        let write_options =
-            DataFrameWriteOptions::default().with_insert_operation(InsertOp::Append);
-        df.write_table(table_name.as_str(), write_options).await
-    };
-
-    let tasks = tasks_defs
-        .into_iter()
-        .map(|(table_name, df)| write_table(df, table_name))
-        .collect::<Vec<_>>();
-
-    let _results: Vec<_> = futures::future::join_all(tasks)
-        .await
+    let writes = tasks_defs
         .into_iter()
-        .try_collect()?;
+        .map(|(table_name, df)| {
+            let name = table_name.clone();
+            let write = async move { ctx.append_table(df, &name).await };
+            (table_name, write.boxed())
+        })
+        .collect();
+    ctx.write_tables(writes).await?;
 
     Ok(snapshot_id)
 }
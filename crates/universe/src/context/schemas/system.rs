@@ -180,10 +180,7 @@ impl<'a> SystemSchema<'a> {
             state.read().schema_for_ref(table_ref.clone())?
         };
         let Some(table) = schema.table(table_ref.table()).await? else {
-            return Err(Error::internal(format!(
-                "Table '{}' not registered",
-                table_ref
-            )));
+            return Err(Error::TableNotFound(table_ref.to_string()));
         };
         Ok(self.ctx().read_table(table)?.select_columns(columns)?)
     }
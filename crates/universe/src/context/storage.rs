@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 use datafusion::catalog::{
     CatalogProvider, MemoryCatalogProvider, MemorySchemaProvider, SchemaProvider, TableProvider,
 };
@@ -9,22 +9,31 @@ use datafusion::datasource::file_format::parquet::ParquetFormat;
 use datafusion::datasource::listing::{
     ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
 };
+use datafusion::prelude::SessionContext;
+use futures::TryStreamExt as _;
+use object_store::PutPayload;
 use url::Url;
 
 use crate::builders::{
-    EVENTS_SCHEMA, METRICS_SCHEMA, OBJECTS_SCHEMA, ORDER_LINE_SCHEMA, ORDER_SCHEMA,
-    POPULATION_SCHEMA,
+    DAILY_SUMMARY_SCHEMA, EVENTS_SCHEMA, FEEDBACK_SCHEMA, FOOTPRINT_SCHEMA, GREEN_DELIVERY_SCHEMA,
+    IMPRESSIONS_SCHEMA, INVENTORY_SCHEMA, INVOICES_SCHEMA, MARKET_SHARE_SCHEMA, METRICS_SCHEMA,
+    OBJECTS_SCHEMA, ORDER_HEATMAP_SCHEMA, ORDER_LINE_SCHEMA, ORDER_SCHEMA, POPULATION_SCHEMA,
+    WASTE_SCHEMA,
 };
 use crate::context::wrap_schema;
-use crate::{Result, RoutingData};
+use crate::{Error, LocalCache, Result, RoutingData};
 
 use super::schemas::{
-    EVENTS_REF, METRICS_REF, OBJECTS_REF, ORDER_LINES_REF, ORDERS_REF, POPULATION_REF,
-    RESULTS_SCHEMA_NAME, ROUTING_EDGES_REF, ROUTING_NODES_REF, SIMULATION_META_REF,
-    SIMULATION_META_SCHEMA, SNAPSHOT_META_REF, SNAPSHOT_META_SCHEMA, SNAPSHOTS_SCHEMA_NAME,
-    SYSTEM_SCHEMA_NAME,
+    DAILY_SUMMARY_REF, EVENTS_REF, FEEDBACK_REF, FOOTPRINT_REF, GREEN_DELIVERY_REF,
+    IMPRESSIONS_REF, INVENTORY_REF, INVOICES_REF, MARKET_SHARE_REF, METRICS_REF, OBJECTS_REF,
+    ORDER_HEATMAP_REF, ORDER_LINES_REF, ORDERS_REF, POPULATION_REF, RESULTS_SCHEMA_NAME,
+    ROUTING_EDGES_REF, ROUTING_NODES_REF, SIMULATION_META_REF, SIMULATION_META_SCHEMA,
+    SNAPSHOT_META_REF, SNAPSHOT_META_SCHEMA, SNAPSHOTS_SCHEMA_NAME, SYSTEM_SCHEMA_NAME, WASTE_REF,
 };
 
+/// Name of the empty data file of tables created for a fresh working directory.
+static BOOTSTRAP_FILE: &str = "bootstrap.json";
+
 pub fn storage_catalog(catalog_location: &Url) -> Result<Arc<dyn CatalogProvider>> {
     let system_schema = Arc::new(MemorySchemaProvider::new());
     let system_location = catalog_location.join(&format!("{}/", SYSTEM_SCHEMA_NAME))?;
@@ -66,25 +75,81 @@ pub(crate) fn register_system(schema: &dyn SchemaProvider, system_location: &Url
     Ok(())
 }
 
+/// Read the routing tables of a storage catalog from local copies of their files.
+pub(crate) async fn cache_routing(
+    catalog: &dyn CatalogProvider,
+    catalog_location: &Url,
+    cache: &LocalCache,
+) -> Result<()> {
+    let system_schema = catalog
+        .schema(SYSTEM_SCHEMA_NAME)
+        .ok_or_else(|| Error::internal("storage catalog without system schema"))?;
+    let system_location = catalog_location.join(&format!("{}/", SYSTEM_SCHEMA_NAME))?;
+
+    for (table_ref, schema) in [
+        (&*ROUTING_NODES_REF, RoutingData::nodes_schema()),
+        (&*ROUTING_EDGES_REF, RoutingData::edges_schema()),
+    ] {
+        let remote_path = system_location.join(&format!("{}/", table_ref.table()))?;
+        let local_path = cache
+            .mirror_url(&remote_path, Vec::<(String, String)>::new())
+            .await?;
+        tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", table_ref, local_path);
+        system_schema.deregister_table(table_ref.table())?;
+        system_schema.register_table(
+            table_ref.table().into(),
+            parquet_provider(&local_path, schema)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Create the system tables of a fresh working directory.
+///
+/// The simulations and snapshots tables are created as empty tables, so listing them
+/// works before the first simulation is recorded. Tables which already have data
+/// files are left untouched.
+pub(crate) async fn bootstrap_system(ctx: &SessionContext, catalog_location: &Url) -> Result<()> {
+    let system_location = catalog_location.join(&format!("{}/", SYSTEM_SCHEMA_NAME))?;
+    let state = ctx.state();
+    for table_ref in [&*SIMULATION_META_REF, &*SNAPSHOT_META_REF] {
+        let table_url =
+            ListingTableUrl::parse(system_location.join(&format!("{}/", table_ref.table()))?)?;
+        let store = state.runtime_env().object_store(&table_url)?;
+        let files: Vec<_> = table_url
+            .list_all_files(&state, store.as_ref(), ".json")
+            .await?
+            .try_collect()
+            .await?;
+        if files.is_empty() {
+            tracing::debug!(target: "caspers::simulation::context", "creating empty table '{}' @ {}", table_ref, table_url);
+            let path = table_url.prefix().child(BOOTSTRAP_FILE);
+            store.put(&path, PutPayload::new()).await?;
+        }
+    }
+    Ok(())
+}
+
 fn register_snapshots(schema: &dyn SchemaProvider, snapshots_path: &Url) -> Result<()> {
     let population_path = snapshots_path.join(&format!("{}/", POPULATION_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *POPULATION_REF, population_path);
-    let population_snapshot = parquet_provider(&population_path, wrap_schema(&POPULATION_SCHEMA))?;
+    let population_snapshot = simulation_provider(&population_path, &POPULATION_SCHEMA)?;
     schema.register_table(POPULATION_REF.table().to_string(), population_snapshot)?;
 
     let objects_path = snapshots_path.join(&format!("{}/", OBJECTS_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *OBJECTS_REF, objects_path);
-    let objects_snapshot = parquet_provider(&objects_path, wrap_schema(&OBJECTS_SCHEMA))?;
+    let objects_snapshot = simulation_provider(&objects_path, &OBJECTS_SCHEMA)?;
     schema.register_table(OBJECTS_REF.table().to_string(), objects_snapshot)?;
 
     let orders_path = snapshots_path.join(&format!("{}/", ORDERS_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *ORDERS_REF, orders_path);
-    let orders_snapshot = parquet_provider(&orders_path, ORDER_SCHEMA.clone())?;
+    let orders_snapshot = simulation_provider(&orders_path, &ORDER_SCHEMA)?;
     schema.register_table(ORDERS_REF.table().to_string(), orders_snapshot)?;
 
     let order_lines_path = snapshots_path.join(&format!("{}/", ORDER_LINES_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *ORDER_LINES_REF, order_lines_path);
-    let order_lines_snapshot = parquet_provider(&order_lines_path, ORDER_LINE_SCHEMA.clone())?;
+    let order_lines_snapshot = simulation_provider(&order_lines_path, &ORDER_LINE_SCHEMA)?;
     schema.register_table(ORDER_LINES_REF.table().to_string(), order_lines_snapshot)?;
 
     Ok(())
@@ -93,23 +158,114 @@ fn register_snapshots(schema: &dyn SchemaProvider, snapshots_path: &Url) -> Resu
 fn register_results(schema: &dyn SchemaProvider, results_path: &Url) -> Result<()> {
     let metrics_path = results_path.join(&format!("{}/", METRICS_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *METRICS_REF, metrics_path);
-    let metrics_snapshot = parquet_provider(&metrics_path, wrap_schema(&METRICS_SCHEMA))?;
+    let metrics_snapshot = simulation_provider(&metrics_path, &METRICS_SCHEMA)?;
     schema.register_table(METRICS_REF.table().to_string(), metrics_snapshot)?;
 
     let events_path = results_path.join(&format!("{}/", EVENTS_REF.table()))?;
     tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *EVENTS_REF, events_path);
-    let events_snapshot = parquet_provider(&events_path, wrap_schema(&EVENTS_SCHEMA))?;
+    let events_snapshot = simulation_provider(&events_path, &EVENTS_SCHEMA)?;
     schema.register_table(EVENTS_REF.table().to_string(), events_snapshot)?;
 
+    let invoices_path = results_path.join(&format!("{}/", INVOICES_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *INVOICES_REF, invoices_path);
+    let invoices_table = simulation_provider(&invoices_path, &INVOICES_SCHEMA)?;
+    schema.register_table(INVOICES_REF.table().to_string(), invoices_table)?;
+
+    let feedback_path = results_path.join(&format!("{}/", FEEDBACK_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *FEEDBACK_REF, feedback_path);
+    let feedback_table = simulation_provider(&feedback_path, &FEEDBACK_SCHEMA)?;
+    schema.register_table(FEEDBACK_REF.table().to_string(), feedback_table)?;
+
+    let inventory_path = results_path.join(&format!("{}/", INVENTORY_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *INVENTORY_REF, inventory_path);
+    let inventory_table = simulation_provider(&inventory_path, &INVENTORY_SCHEMA)?;
+    schema.register_table(INVENTORY_REF.table().to_string(), inventory_table)?;
+
+    let waste_path = results_path.join(&format!("{}/", WASTE_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *WASTE_REF, waste_path);
+    let waste_table = simulation_provider(&waste_path, &WASTE_SCHEMA)?;
+    schema.register_table(WASTE_REF.table().to_string(), waste_table)?;
+
+    let footprint_path = results_path.join(&format!("{}/", FOOTPRINT_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *FOOTPRINT_REF, footprint_path);
+    let footprint_table = simulation_provider(&footprint_path, &FOOTPRINT_SCHEMA)?;
+    schema.register_table(FOOTPRINT_REF.table().to_string(), footprint_table)?;
+
+    let green_path = results_path.join(&format!("{}/", GREEN_DELIVERY_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *GREEN_DELIVERY_REF, green_path);
+    let green_table = simulation_provider(&green_path, &GREEN_DELIVERY_SCHEMA)?;
+    schema.register_table(GREEN_DELIVERY_REF.table().to_string(), green_table)?;
+
+    let impressions_path = results_path.join(&format!("{}/", IMPRESSIONS_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *IMPRESSIONS_REF, impressions_path);
+    let impressions_table = simulation_provider(&impressions_path, &IMPRESSIONS_SCHEMA)?;
+    schema.register_table(IMPRESSIONS_REF.table().to_string(), impressions_table)?;
+
+    let heatmap_path = results_path.join(&format!("{}/", ORDER_HEATMAP_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *ORDER_HEATMAP_REF, heatmap_path);
+    let heatmap_table = simulation_provider(&heatmap_path, &ORDER_HEATMAP_SCHEMA)?;
+    schema.register_table(ORDER_HEATMAP_REF.table().to_string(), heatmap_table)?;
+
+    let market_share_path = results_path.join(&format!("{}/", MARKET_SHARE_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *MARKET_SHARE_REF, market_share_path);
+    let market_share_table = simulation_provider(&market_share_path, &MARKET_SHARE_SCHEMA)?;
+    schema.register_table(MARKET_SHARE_REF.table().to_string(), market_share_table)?;
+
+    let daily_summary_path = results_path.join(&format!("{}/", DAILY_SUMMARY_REF.table()))?;
+    tracing::debug!(target: "caspers::simulation::context", "registering '{}' @ {}", *DAILY_SUMMARY_REF, daily_summary_path);
+    let daily_summary_table = simulation_provider(&daily_summary_path, &DAILY_SUMMARY_SCHEMA)?;
+    schema.register_table(DAILY_SUMMARY_REF.table().to_string(), daily_summary_table)?;
+
     Ok(())
 }
 
+/// Parquet table of simulation data with the files of each simulation in its own directory.
+///
+/// Rows are partitioned by `simulation_id` into `simulation_id=<id>/` directories, so
+/// runs sharing a storage location never write into the same directory.
+///
+/// Files written by earlier versions may lack columns added to the schema since.
+/// All data columns are therefore nullable, and columns missing from a file are
+/// read as nulls, so directories with files of mixed versions remain queryable.
+fn simulation_provider(table_path: &Url, schema: &Schema) -> Result<Arc<dyn TableProvider>> {
+    let schema = wrap_schema(&evolvable_schema(schema));
+    let (partition_field, file_fields) = schema
+        .fields()
+        .split_last()
+        .ok_or_else(|| Error::internal("simulation table without columns"))?;
+    let file_schema = Arc::new(Schema::new(file_fields.to_vec()));
+    let partition_cols = vec![(
+        partition_field.name().clone(),
+        partition_field.data_type().clone(),
+    )];
+    listing_provider(table_path, file_schema, partition_cols)
+}
+
+/// `schema` with all top-level columns nullable.
+fn evolvable_schema(schema: &Schema) -> Schema {
+    let fields: Vec<_> = schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone().with_nullable(true))
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
 fn parquet_provider(table_path: &Url, schema: SchemaRef) -> Result<Arc<dyn TableProvider>> {
+    listing_provider(table_path, schema, Vec::new())
+}
+
+fn listing_provider(
+    table_path: &Url,
+    schema: SchemaRef,
+    partition_cols: Vec<(String, DataType)>,
+) -> Result<Arc<dyn TableProvider>> {
     let table_path = ListingTableUrl::parse(table_path)?;
 
     let file_format = ParquetFormat::new();
-    let listing_options =
-        ListingOptions::new(Arc::new(file_format)).with_file_extension(".parquet");
+    let listing_options = ListingOptions::new(Arc::new(file_format))
+        .with_file_extension(".parquet")
+        .with_table_partition_cols(partition_cols);
 
     let config = ListingTableConfig::new(table_path)
         .with_listing_options(listing_options)
@@ -130,3 +286,126 @@ fn json_provider(table_path: &Url, schema: SchemaRef) -> Result<Arc<dyn TablePro
 
     Ok(Arc::new(ListingTable::try_new(config)?))
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::AsArray as _;
+    use datafusion::prelude::{col, lit};
+    use datafusion::scalar::ScalarValue;
+
+    use super::*;
+    use crate::{ObjectData, PopulationData, SimulationContext, Template};
+
+    async fn create_simulation(location: &Url, run_name: &str) -> Result<SimulationContext> {
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut population = PopulationData::builder();
+        population.add_site(10, 52.37, 4.89)?;
+        SimulationContext::builder()
+            .with_working_directory(location.clone())
+            .with_run_name(run_name.to_string())
+            .with_object_data(objects)
+            .with_population_data(population.finish()?)
+            .build()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_simulation_partitions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+
+        let first = create_simulation(&location, "first").await?;
+        let second = create_simulation(&location, "second").await?;
+
+        // every simulation writes to its own directory
+        for ctx in [&first, &second] {
+            let partition = dir.path().join(format!(
+                "snapshots/objects/simulation_id={}",
+                ctx.simulation_id()
+            ));
+            assert!(partition.is_dir());
+
+            let objects = ctx.collect(ctx.snapshots().objects().await?).await?;
+            let rows: usize = objects.iter().map(|batch| batch.num_rows()).sum();
+            let expected = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+            assert_eq!(rows, expected.objects().num_rows());
+        }
+
+        // run names are recorded with the simulation and its snapshots
+        let simulations = first
+            .system()
+            .simulations()
+            .await?
+            .filter(col("id").eq(lit(ScalarValue::Utf8View(Some(
+                first.simulation_id().to_string(),
+            )))))?;
+        let simulations = first.collect(simulations).await?;
+        let properties = simulations[0].column(1).as_string_view().value(0);
+        assert_eq!(properties, r#"{"run_name":"first"}"#);
+        assert_eq!(second.run_name(), Some("second"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_evolution() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+        let ctx = create_simulation(&location, "current").await?;
+        let current = ctx.collect(ctx.snapshots().population().await?).await?;
+        let current_rows: usize = current.iter().map(|batch| batch.num_rows()).sum();
+
+        // a file written before the `state` column was added
+        let legacy = ctx
+            .ctx()
+            .table(POPULATION_REF.to_string())
+            .await?
+            .drop_columns(&["state", "simulation_id"])?;
+        let partition = dir.path().join(format!(
+            "snapshots/population/simulation_id={}/legacy.parquet",
+            ctx.simulation_id()
+        ));
+        legacy
+            .write_parquet(partition.to_str().unwrap(), Default::default(), None)
+            .await?;
+
+        let population = ctx.snapshots().population().await?;
+        let missing = population
+            .clone()
+            .filter(col("state").is_null())?
+            .count()
+            .await?;
+        assert_eq!(missing, current_rows);
+        assert_eq!(population.count().await?, 2 * current_rows);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_system() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = Url::from_directory_path(dir.path()).unwrap();
+
+        // system tables of a fresh working directory can be listed right away
+        let builder = SimulationContext::builder().with_working_directory(location.clone());
+        let simulations = builder.load_simulations().await?.collect().await?;
+        assert_eq!(simulations.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+        let snapshots = builder.load_snapshots().await?.collect().await?;
+        assert_eq!(snapshots.iter().map(|b| b.num_rows()).sum::<usize>(), 0);
+        for table in ["simulations", "snapshots"] {
+            assert!(
+                dir.path()
+                    .join("system")
+                    .join(table)
+                    .join(BOOTSTRAP_FILE)
+                    .is_file()
+            );
+        }
+
+        let ctx = create_simulation(&location, "first").await?;
+        let simulations = ctx.collect(ctx.system().simulations().await?).await?;
+        assert_eq!(simulations.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Object stores backing working directories.
+//!
+//! Working directories are usually local folders or remote buckets, accessed through
+//! the object store for the scheme of their url. Working directories with a
+//! `memory://` url are kept in process memory instead. Each host, e.g.
+//! `memory://demo/`, names a separate [`InMemory`] store which is shared by all
+//! contexts of the process, so a simulation can be initialized, run and inspected
+//! without touching disk.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use datafusion::prelude::SessionContext;
+use object_store::ObjectStore;
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use url::Url;
+
+use crate::{Error, Result};
+
+/// Scheme of working directories kept in process memory.
+pub const MEMORY_SCHEME: &str = "memory";
+
+static MEMORY_STORES: LazyLock<Mutex<HashMap<String, Arc<InMemory>>>> =
+    LazyLock::new(Default::default);
+
+/// The in-memory store of a `memory://` url, created on first use.
+pub fn memory_store(url: &Url) -> Result<Arc<InMemory>> {
+    if url.scheme() != MEMORY_SCHEME {
+        return Err(Error::invalid_data(format!(
+            "expected a '{MEMORY_SCHEME}://' url, got '{url}'"
+        )));
+    }
+    let mut stores = MEMORY_STORES
+        .lock()
+        .map_err(|_| Error::internal("memory store registry poisoned"))?;
+    let store = stores
+        .entry(url.host_str().unwrap_or_default().to_string())
+        .or_default();
+    Ok(store.clone())
+}
+
+/// Drop the in-memory store of a `memory://` url and all data written to it.
+///
+/// Returns whether a store existed.
+pub fn drop_memory_store(url: &Url) -> Result<bool> {
+    let mut stores = MEMORY_STORES
+        .lock()
+        .map_err(|_| Error::internal("memory store registry poisoned"))?;
+    Ok(stores.remove(url.host_str().unwrap_or_default()).is_some())
+}
+
+/// Whether data at `url` is kept on this machine and does not need a local cache.
+pub(crate) fn is_local(url: &Url) -> bool {
+    matches!(url.scheme(), "file" | MEMORY_SCHEME)
+}
+
+/// Make the object store of `url` available to queries in `ctx`.
+///
+/// Stores of other schemes are resolved by the session itself.
+pub(crate) fn register_store(ctx: &SessionContext, url: &Url) -> Result<()> {
+    if url.scheme() == MEMORY_SCHEME {
+        ctx.register_object_store(url, memory_store(url)?);
+    }
+    Ok(())
+}
+
+/// The object store and path of `url`, configured with `options`.
+pub(crate) fn parse_url_opts<I, K, V>(url: &Url, options: I) -> Result<(Arc<dyn ObjectStore>, Path)>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    if url.scheme() == MEMORY_SCHEME {
+        let path = Path::from_url_path(url.path()).map_err(object_store::Error::from)?;
+        return Ok((memory_store(url)?, path));
+    }
+    let (store, path) = object_store::parse_url_opts(url, options)?;
+    Ok((store.into(), path))
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::PutPayload;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store() -> Result<()> {
+        let url = Url::parse("memory://test-memory-store/")?;
+        let (store, path) = parse_url_opts(&url, Vec::<(String, String)>::new())?;
+        store
+            .put(&path.child("data.json"), PutPayload::new())
+            .await?;
+
+        // stores are shared by host
+        let (shared, _) = parse_url_opts(&url.join("nested/")?, Vec::<(String, String)>::new())?;
+        assert!(shared.head(&Path::from("data.json")).await.is_ok());
+        let other = memory_store(&Url::parse("memory://other-memory-store/")?)?;
+        assert!(other.head(&Path::from("data.json")).await.is_err());
+
+        assert!(drop_memory_store(&url)?);
+        assert!(
+            memory_store(&url)?
+                .head(&Path::from("data.json"))
+                .await
+                .is_err()
+        );
+        assert!(memory_store(&Url::parse("file:///tmp/")?).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_memory_working_directory() -> Result<()> {
+        use arrow::array::AsArray as _;
+
+        use crate::{ObjectData, SimulationContext, Template};
+
+        let url = Url::parse("memory://test-memory-working-directory/")?;
+        crate::initialize_template(&url, Template::default(), Some(42), Default::default()).await?;
+
+        // contexts on the same url see the data written by earlier ones
+        let builder = SimulationContext::builder().with_working_directory(url.clone());
+        let simulations = builder.load_simulations().await?.collect().await?;
+        let simulation_id = simulations[0]
+            .column(0)
+            .as_string_view()
+            .value(0)
+            .parse::<uuid::Uuid>()?;
+        let builder = builder.with_simulation_id(simulation_id);
+        let snapshots = builder.load_snapshots().await?.collect().await?;
+        let snapshot_id = snapshots[0]
+            .column(0)
+            .as_string_view()
+            .value(0)
+            .parse::<uuid::Uuid>()?;
+
+        let ctx = builder.with_snapshot_id(snapshot_id).build().await?;
+        let objects = ctx.collect(ctx.snapshots().objects().await?).await?;
+        let rows: usize = objects.iter().map(|batch| batch.num_rows()).sum();
+        let expected = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        assert_eq!(rows, expected.objects().num_rows());
+
+        assert!(drop_memory_store(&url)?);
+        Ok(())
+    }
+}
@@ -1,16 +1,51 @@
+use strum::{AsRefStr, Display};
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Broad category of an [`Error`], e.g. to choose exit codes or HTTP statuses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ErrorKind {
+    /// A referenced entity, table or file does not exist.
+    NotFound,
+    /// Setup data, configuration or arguments are invalid or missing.
+    InvalidInput,
+    /// Reading from or writing to storage failed.
+    Storage,
+    /// A bug or an unexpected condition in the simulation.
+    Internal,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("Not found")]
-    NotFound,
+    #[error("{entity} not found: {id}")]
+    NotFound { entity: &'static str, id: String },
+
+    #[error("Table '{0}' not registered")]
+    TableNotFound(String),
+
+    /// An error annotated with what was being done when it occurred.
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<Error>,
+    },
 
     #[error("Missing input: {0}")]
     MissingInput(String),
 
+    /// Tables of the working directory are missing or cannot be read.
+    #[error("Invalid working directory:\n{0}")]
+    InvalidWorkingDirectory(crate::context::ContextReport),
+
     #[error("Invalid data: {0}")]
     InvalidData(String),
 
+    /// A write was attempted on a context opened read-only.
+    #[error("Read-only context: {0}")]
+    ReadOnly(String),
+
     #[error("Missing geometry")]
     MissingGeometry,
 
@@ -21,7 +56,7 @@ pub enum Error {
     InternalError(String),
 
     #[error("Generic error: {0}")]
-    Generic(Box<dyn std::error::Error>),
+    Generic(Box<dyn std::error::Error + Send + Sync>),
 
     #[error("Invalid uuid")]
     InvalidUuid {
@@ -66,7 +101,9 @@ pub enum Error {
     },
 
     #[error("H3 error: {source}")]
-    H3 { source: Box<dyn std::error::Error> },
+    H3 {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 
     #[error("Rand error: {source}")]
     Rand {
@@ -85,6 +122,10 @@ pub enum Error {
         #[from]
         source: std::io::Error,
     },
+
+    #[cfg(feature = "wasm")]
+    #[error("Wasm error: {0}")]
+    Wasm(String),
 }
 
 impl From<h3o::error::DissolutionError> for Error {
@@ -103,20 +144,154 @@ impl From<h3o::error::InvalidLatLng> for Error {
     }
 }
 
+#[cfg(feature = "wasm")]
+impl From<wasmtime::Error> for Error {
+    fn from(error: wasmtime::Error) -> Self {
+        Error::Wasm(format!("{error:#}"))
+    }
+}
+
 impl Error {
+    pub fn not_found(entity: &'static str, id: impl ToString) -> Self {
+        Error::NotFound {
+            entity,
+            id: id.to_string(),
+        }
+    }
+
+    pub fn missing_input(message: impl ToString) -> Self {
+        Error::MissingInput(message.to_string())
+    }
+
     pub fn invalid_data(message: impl ToString) -> Self {
         Error::InvalidData(message.to_string())
     }
 
+    pub fn read_only(message: impl ToString) -> Self {
+        Error::ReadOnly(message.to_string())
+    }
+
     pub fn invalid_geometry(message: impl ToString) -> Self {
         Error::InvalidGeometry(message.to_string())
     }
 
-    pub fn generic(error: impl std::error::Error + 'static) -> Self {
+    pub fn generic(error: impl std::error::Error + Send + Sync + 'static) -> Self {
         Error::Generic(Box::new(error))
     }
 
     pub fn internal(message: impl ToString) -> Self {
         Error::InternalError(message.to_string())
     }
+
+    /// Annotate the error with what was being done when it occurred.
+    pub fn context(self, context: impl Into<String>) -> Self {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error without any context annotations.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        use datafusion::common::DataFusionError;
+
+        match self.root() {
+            Error::NotFound { .. } | Error::TableNotFound(_) => ErrorKind::NotFound,
+            Error::MissingInput(_)
+            | Error::InvalidWorkingDirectory(_)
+            | Error::InvalidData(_)
+            | Error::ReadOnly(_)
+            | Error::MissingGeometry
+            | Error::InvalidGeometry(_)
+            | Error::InvalidUuid { .. }
+            | Error::InvalidUrl { .. }
+            | Error::Serde { .. }
+            | Error::H3 { .. }
+            | Error::Rand { .. } => ErrorKind::InvalidInput,
+            Error::ObjectStore {
+                source: object_store::Error::NotFound { .. },
+            } => ErrorKind::NotFound,
+            Error::ObjectStore { .. } | Error::Url { .. } | Error::Parquet { .. } => {
+                ErrorKind::Storage
+            }
+            Error::Datafusion { source } => match source.find_root() {
+                DataFusionError::ObjectStore(_) | DataFusionError::IoError(_) => ErrorKind::Storage,
+                DataFusionError::Plan(_) | DataFusionError::SchemaError(..) => {
+                    ErrorKind::InvalidInput
+                }
+                _ => ErrorKind::Internal,
+            },
+            #[cfg(feature = "wasm")]
+            Error::Wasm(_) => ErrorKind::InvalidInput,
+            Error::Context { .. }
+            | Error::InternalError(_)
+            | Error::Generic(_)
+            | Error::Arrow { .. }
+            | Error::GeoArrow { .. } => ErrorKind::Internal,
+        }
+    }
+
+    /// Render the error together with all its sources, one per line.
+    pub fn report(&self) -> String {
+        let mut report = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            report.push_str(&format!("\n  caused by: {err}"));
+            source = err.source();
+        }
+        report
+    }
+}
+
+/// Attach context to errors of fallible operations.
+pub trait ResultExt<T> {
+    /// Annotate an error with what was being done when it occurred.
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// Like [`ResultExt::context`], but only builds the context on error.
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|err| err.into().context(context))
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|err| err.into().context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_context() {
+        let result: Result<()> = Err(Error::not_found("order", "42"));
+        let err = result
+            .context("updating order status")
+            .with_context(|| "processing site 'london'")
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(matches!(
+            err.root(),
+            Error::NotFound {
+                entity: "order",
+                ..
+            }
+        ));
+        assert_eq!(
+            err.report(),
+            "processing site 'london'\n  caused by: updating order status\n  caused by: order not found: 42"
+        );
+    }
 }
@@ -12,12 +12,29 @@
 //! Event-like data profits from UUIDs that can be ordered based on time as such we can use
 //! UUID v7 for these cases.
 //!
+//! Every entity also has a stable URI reference that is easier to read than its UUID.
+//! Objects defined in the simulation setup derive their UUID from a hierarchical URI
+//! (e.g. `sites/<site>/kitchens/<kitchen>`), while event-like entities are addressed
+//! by their collection and UUID (e.g. `orders/<uuid>`).
+//!
 //! [`Uuid`]: uuid::Uuid
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng as _};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::Error;
 
+/// UUID v7 for `time` with its random bits drawn from `rng`.
+///
+/// Unlike [`Uuid::now_v7`], the id is reproducible for a seeded `rng` and follows the
+/// simulation clock rather than the wall clock.
+pub(crate) fn uuid_v7_from_rng(time: DateTime<Utc>, rng: &mut impl Rng) -> Uuid {
+    uuid::Builder::from_unix_timestamp_millis(time.timestamp_millis() as u64, &rng.random())
+        .into_uuid()
+}
+
 pub trait TypedId:
     Clone + PartialEq + Eq + AsRef<Uuid> + AsRef<[u8]> + ToString + From<Uuid>
 {
@@ -62,7 +79,8 @@ macro_rules! impl_id_type {
     };
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct SiteId(Uuid);
 
@@ -73,11 +91,22 @@ impl SiteId {
     pub fn from_uri_ref(name: impl AsRef<str>) -> Self {
         SiteId(Uuid::new_v5(&Uuid::NAMESPACE_URL, name.as_ref().as_bytes()))
     }
+
+    /// Creates a new [`SiteId`] from the name of the site.
+    pub fn from_name(site: &str) -> Self {
+        Self::from_uri_ref(Self::uri_ref(site))
+    }
+
+    /// URI reference for a site in the form of `sites/<site_name>`
+    pub fn uri_ref(site: &str) -> String {
+        format!("sites/{site}")
+    }
 }
 
 impl_id_type!(SiteId);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct KitchenId(Uuid);
 
@@ -85,11 +114,22 @@ impl KitchenId {
     pub fn from_uri_ref(name: impl AsRef<str>) -> Self {
         KitchenId(Uuid::new_v5(&Uuid::NAMESPACE_URL, name.as_ref().as_bytes()))
     }
+
+    /// Creates a new [`KitchenId`] from the names of the site and kitchen.
+    pub fn from_names(site: &str, kitchen: &str) -> Self {
+        Self::from_uri_ref(Self::uri_ref(site, kitchen))
+    }
+
+    /// URI reference for a kitchen in the form of `sites/<site_name>/kitchens/<kitchen_name>`
+    pub fn uri_ref(site: &str, kitchen: &str) -> String {
+        format!("{}/kitchens/{kitchen}", SiteId::uri_ref(site))
+    }
 }
 
 impl_id_type!(KitchenId);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct StationId(Uuid);
 
@@ -97,11 +137,23 @@ impl StationId {
     pub fn from_uri_ref(name: impl AsRef<str>) -> Self {
         StationId(Uuid::new_v5(&Uuid::NAMESPACE_URL, name.as_ref().as_bytes()))
     }
+
+    /// Creates a new [`StationId`] from the names of the site, kitchen and station.
+    pub fn from_names(site: &str, kitchen: &str, station: &str) -> Self {
+        Self::from_uri_ref(Self::uri_ref(site, kitchen, station))
+    }
+
+    /// URI reference for a station in the form of
+    /// `sites/<site_name>/kitchens/<kitchen_name>/stations/<station_name>`
+    pub fn uri_ref(site: &str, kitchen: &str, station: &str) -> String {
+        format!("{}/stations/{station}", KitchenId::uri_ref(site, kitchen))
+    }
 }
 
 impl_id_type!(StationId);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct OrderId(Uuid);
 
@@ -115,11 +167,22 @@ impl OrderId {
     pub fn new() -> Self {
         OrderId(Uuid::now_v7())
     }
+
+    /// Creates an [`OrderId`] for an order placed at `time`, drawing from `rng`.
+    pub(crate) fn from_rng(time: DateTime<Utc>, rng: &mut impl Rng) -> Self {
+        OrderId(uuid_v7_from_rng(time, rng))
+    }
+
+    /// URI reference for the order in the form of `orders/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("orders/{}", self.0)
+    }
 }
 
 impl_id_type!(OrderId);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct OrderLineId(Uuid);
 
@@ -133,11 +196,33 @@ impl OrderLineId {
     pub fn new() -> Self {
         OrderLineId(Uuid::now_v7())
     }
+
+    /// Creates the [`OrderLineId`] of the `index`-th line of an order.
+    ///
+    /// The id shares the timestamp of the order id and derives its random bits
+    /// from it, so the lines of an order are identified the same way in every run.
+    pub fn for_order(order_id: &OrderId, index: usize) -> Self {
+        let bytes = order_id.0.as_bytes();
+        let mut millis = [0; 8];
+        millis[2..].copy_from_slice(&bytes[..6]);
+        let seed = u64::from_le_bytes(bytes[8..].try_into().expect("uuids have 16 bytes"));
+        let mut rng = StdRng::seed_from_u64(seed ^ index as u64);
+        OrderLineId(
+            uuid::Builder::from_unix_timestamp_millis(u64::from_be_bytes(millis), &rng.random())
+                .into_uuid(),
+        )
+    }
+
+    /// URI reference for the order line in the form of `order_lines/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("order_lines/{}", self.0)
+    }
 }
 
 impl_id_type!(OrderLineId);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct BrandId(Uuid);
 
@@ -145,11 +230,22 @@ impl BrandId {
     pub fn from_uri_ref(name: impl AsRef<str>) -> Self {
         BrandId(Uuid::new_v5(&Uuid::NAMESPACE_URL, name.as_ref().as_bytes()))
     }
+
+    /// Creates a new [`BrandId`] from the name of the brand.
+    pub fn from_name(brand: &str) -> Self {
+        Self::from_uri_ref(Self::uri_ref(brand))
+    }
+
+    /// URI reference for a brand in the form of `brands/<brand_name>`
+    pub fn uri_ref(brand: &str) -> String {
+        format!("brands/{brand}")
+    }
 }
 
 impl_id_type!(BrandId);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct MenuItemId(Uuid);
 
@@ -157,11 +253,22 @@ impl MenuItemId {
     pub fn from_uri_ref(name: impl AsRef<str>) -> Self {
         MenuItemId(Uuid::new_v5(&Uuid::NAMESPACE_URL, name.as_ref().as_bytes()))
     }
+
+    /// Creates a new [`MenuItemId`] from the names of the brand and menu item.
+    pub fn from_names(brand: &str, item: &str) -> Self {
+        Self::from_uri_ref(Self::uri_ref(brand, item))
+    }
+
+    /// URI reference for a menu item in the form of `brands/<brand_name>/menu_items/<item_name>`
+    pub fn uri_ref(brand: &str, item: &str) -> String {
+        format!("{}/menu_items/{item}", BrandId::uri_ref(brand))
+    }
 }
 
 impl_id_type!(MenuItemId);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
 #[serde(transparent)]
 pub struct PersonId(pub(crate) Uuid);
 
@@ -175,6 +282,108 @@ impl PersonId {
     pub fn new() -> Self {
         PersonId(Uuid::now_v7())
     }
+
+    /// Creates a stable [`PersonId`] for the `index`-th person generated from `seed`.
+    ///
+    /// The id is a UUID v5 of the URI reference `people/<seed>/<index>`.
+    pub fn from_seed(seed: u64, index: usize) -> Self {
+        PersonId(Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            format!("people/{seed}/{index}").as_bytes(),
+        ))
+    }
+
+    /// URI reference for the person in the form of `people/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("people/{}", self.0)
+    }
 }
 
 impl_id_type!(PersonId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
+#[serde(transparent)]
+pub struct NotificationId(Uuid);
+
+impl Default for NotificationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NotificationId {
+    pub fn new() -> Self {
+        NotificationId(Uuid::now_v7())
+    }
+
+    /// Creates a [`NotificationId`] for a notification sent at `time`, drawing from `rng`.
+    pub(crate) fn from_rng(time: DateTime<Utc>, rng: &mut impl Rng) -> Self {
+        NotificationId(uuid_v7_from_rng(time, rng))
+    }
+
+    /// URI reference for the notification in the form of `notifications/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("notifications/{}", self.0)
+    }
+}
+
+impl_id_type!(NotificationId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
+#[serde(transparent)]
+pub struct SessionId(Uuid);
+
+impl Default for SessionId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionId {
+    pub fn new() -> Self {
+        SessionId(Uuid::now_v7())
+    }
+
+    /// Creates a [`SessionId`] for an app session started at `time`, drawing from `rng`.
+    pub(crate) fn from_rng(time: DateTime<Utc>, rng: &mut impl Rng) -> Self {
+        SessionId(uuid_v7_from_rng(time, rng))
+    }
+
+    /// URI reference for the session in the form of `sessions/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("sessions/{}", self.0)
+    }
+}
+
+impl_id_type!(SessionId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::pyclass(frozen, eq, hash))]
+#[serde(transparent)]
+pub struct SupplierOrderId(Uuid);
+
+impl Default for SupplierOrderId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SupplierOrderId {
+    pub fn new() -> Self {
+        SupplierOrderId(Uuid::now_v7())
+    }
+
+    /// Creates a [`SupplierOrderId`] for an order placed at `time`, drawing from `rng`.
+    pub(crate) fn from_rng(time: DateTime<Utc>, rng: &mut impl Rng) -> Self {
+        SupplierOrderId(uuid_v7_from_rng(time, rng))
+    }
+
+    /// URI reference for the supplier order in the form of `supplier_orders/<uuid>`
+    pub fn uri_ref(&self) -> String {
+        format!("supplier_orders/{}", self.0)
+    }
+}
+
+impl_id_type!(SupplierOrderId);
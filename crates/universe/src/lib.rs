@@ -12,6 +12,7 @@ pub use self::builders::*;
 pub use self::error::*;
 pub use self::idents::*;
 pub use self::models::*;
+pub use self::money::*;
 pub use self::simulation::*;
 pub use self::state::*;
 #[cfg(any(test, feature = "templates"))]
@@ -28,6 +29,7 @@ mod error;
 mod functions;
 mod idents;
 mod models;
+mod money;
 #[cfg(feature = "python")]
 mod python;
 mod simulation;
@@ -63,27 +65,32 @@ impl SimulationSetup {
             .into_iter()
             .filter(|file| file.location.extension() == Some("json"))
         {
-            let site_bytes = store.get(&file.location).await?.bytes().await?;
-            let mut site_setup: SiteSetup = serde_json::from_slice(&site_bytes)?;
+            let context = || format!("loading site setup '{}'", file.location);
+            let site_bytes = RetryPolicy::default()
+                .retry(&context(), || async {
+                    Ok(store.get(&file.location).await?.bytes().await?)
+                })
+                .await?;
+            let site_value: serde_json::Value =
+                serde_json::from_slice(&site_bytes).with_context(context)?;
+            PropertySchemas::default_schemas()
+                .validate_site_setup(&site_value, file.location.as_ref())?;
+            let mut site_setup: SiteSetup =
+                serde_json::from_value(site_value).with_context(context)?;
             if let Some(ref mut site) = site_setup.info {
-                site.id = SiteId::from_uri_ref(format!("sites/{}", site.name)).to_string();
+                site.id = SiteId::from_name(&site.name).to_string();
                 site_setup.kitchens = site_setup
                     .kitchens
                     .into_iter()
                     .map(|mut kitchen_setup| {
                         if let Some(ref mut kitchen) = kitchen_setup.info {
-                            kitchen.id = KitchenId::from_uri_ref(format!(
-                                "sites/{}/kitchens/{}",
-                                site.name, kitchen.name
-                            ))
-                            .to_string();
+                            kitchen.id =
+                                KitchenId::from_names(&site.name, &kitchen.name).to_string();
 
                             for station in &mut kitchen_setup.stations {
-                                station.id = StationId::from_uri_ref(format!(
-                                    "sites/{}/kitchens/{}/stations/{}",
-                                    site.name, kitchen.name, station.name
-                                ))
-                                .to_string();
+                                station.id =
+                                    StationId::from_names(&site.name, &kitchen.name, &station.name)
+                                        .to_string();
                             }
                         }
 
@@ -93,7 +100,10 @@ impl SimulationSetup {
 
                 sites.push(site_setup);
             } else {
-                return Err(Error::invalid_data("missing site information"));
+                return Err(Error::invalid_data(format!(
+                    "missing site information in '{}'",
+                    file.location
+                )));
             };
         }
 
@@ -105,16 +115,21 @@ impl SimulationSetup {
         let mut brands = Vec::new();
 
         for file in brand_files {
-            let brand_data = store.get(&file.location).await?.bytes().await?;
-            let mut brand: Brand = serde_json::from_slice(&brand_data)?;
-            brand.id = BrandId::from_uri_ref(format!("brands/{}", brand.name)).to_string();
+            let context = || format!("loading brand '{}'", file.location);
+            let brand_data = RetryPolicy::default()
+                .retry(&context(), || async {
+                    Ok(store.get(&file.location).await?.bytes().await?)
+                })
+                .await?;
+            let brand_value: serde_json::Value =
+                serde_json::from_slice(&brand_data).with_context(context)?;
+            PropertySchemas::default_schemas()
+                .validate_brand(&brand_value, file.location.as_ref())?;
+            let mut brand: Brand = serde_json::from_value(brand_value).with_context(context)?;
+            brand.id = BrandId::from_name(&brand.name).to_string();
 
             for menu_item in brand.items.iter_mut() {
-                menu_item.id = MenuItemId::from_uri_ref(format!(
-                    "brands/{}/menu_items/{}",
-                    brand.id, menu_item.name
-                ))
-                .to_string();
+                menu_item.id = MenuItemId::from_names(&brand.name, &menu_item.name).to_string();
             }
 
             brands.push(brand);
@@ -137,7 +152,7 @@ fn generate_objects(brands: &HashMap<BrandId, &Brand>, sites: &[SiteSetup]) -> R
     let mut builder = ObjectDataBuilder::new();
 
     for (brand_id, brand) in brands.iter() {
-        builder.append_brand(brand_id, brand);
+        builder.append_brand(brand_id, brand)?;
     }
 
     for site in sites {
@@ -153,8 +168,23 @@ where
     K: AsRef<str>,
     V: Into<String>,
 {
-    let (store, path) = object_store::parse_url_opts(url, options)?;
-    SimulationSetup::load(&store, &path).await
+    let (store, path) = context::stores::parse_url_opts(url, options)?;
+    SimulationSetup::load(store.as_ref(), &path).await
+}
+
+/// Load a simulation setup through a local cache of its files.
+pub async fn load_cached_simulation_setup<I, K, V>(
+    url: &Url,
+    options: I,
+    cache: &LocalCache,
+) -> Result<SimulationSetup>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    let local_url = cache.mirror_url(url, options).await?;
+    load_simulation_setup(&local_url, Vec::<(String, String)>::new()).await
 }
 
 #[instrument(name = "run_simulation", skip_all)]
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+use crate::error::{Error, Result};
+use crate::models::Brand;
+
+/// Cuisines brands are categorized by.
+///
+/// The `category` of a [`Brand`] is one of the snake case names of the cuisines, so
+/// generated brands, customer preferences and order analytics all share the same
+/// taxonomy.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    EnumString,
+    Display,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Cuisine {
+    Asian,
+    FastFood,
+    Mexican,
+}
+
+impl Cuisine {
+    pub const ALL: [Cuisine; 3] = [Cuisine::Asian, Cuisine::FastFood, Cuisine::Mexican];
+}
+
+impl Brand {
+    /// Cuisine the brand is categorized by.
+    pub fn cuisine(&self) -> Result<Cuisine> {
+        self.category.parse().map_err(|_| {
+            Error::invalid_data(format!(
+                "brand '{}' has unknown category '{}'",
+                self.name, self.category
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brand_cuisine() {
+        let mut brand = Brand {
+            name: "tacos".into(),
+            category: "mexican".into(),
+            ..Default::default()
+        };
+        assert_eq!(brand.cuisine().unwrap(), Cuisine::Mexican);
+        assert_eq!(Cuisine::FastFood.to_string(), "fast_food");
+
+        brand.category = "Mexican".into();
+        assert!(brand.cuisine().is_err());
+    }
+}
@@ -0,0 +1,884 @@
+//! Conversions from simulation events into their protobuf representation.
+//!
+//! The messages in `caspers.messages.v1` are the stable contract for consumers
+//! reading events from external systems (e.g. gRPC or Kafka).
+
+use geo::Point;
+
+use super::caspers::messages::v1 as pb;
+use crate::state::{Journey, OrderLineStatus, OrderStatus, PersonStatus};
+use crate::{
+    BreakActivity, BreakReason, CompensationIssuedPayload, ConfigChangedPayload, CourierActivity,
+    CourierBreakPayload, CourierOffer, CourierUpdatedPayload, Cuisine, DeliveryFootprintPayload,
+    DeliveryMode, Event, EventPayload, FunnelStage, IngredientWastedPayload, LifecycleStage,
+    MembershipBilledPayload, NotificationChannel, NotificationStatus, NotificationTrigger,
+    NotificationUpdatedPayload, ObjectChange, ObjectChangedPayload, OrderChannel,
+    OrderCreatedPayload, OrderLineUpdatedPayload, OrderUpdatedPayload, PersonLifecyclePayload,
+    PersonUpdatedPayload, PriorityTier, ProcurementActivity, ProcurementUpdatedPayload,
+    RobotActivity, RobotDeliveryPayload, RobotKind, SessionUpdatedPayload, SiteCheckInPayload,
+    SiteCheckOutPayload, StepFinishedPayload, StepStartedPayload, SubstitutionStatus,
+    SubstitutionUpdatedPayload, SupplyActivity, SupplyUpdatedPayload, WasteReason,
+};
+
+impl From<&Event> for pb::SimulationEvent {
+    fn from(event: &Event) -> Self {
+        Self {
+            time: Some(event.timestamp.into()),
+            payload: Some((&event.payload).into()),
+        }
+    }
+}
+
+impl From<&EventPayload> for pb::simulation_event::Payload {
+    fn from(payload: &EventPayload) -> Self {
+        use pb::simulation_event::Payload;
+
+        match payload {
+            EventPayload::PersonUpdated(p) => Payload::PersonUpdated(p.into()),
+            EventPayload::OrderCreated(p) => Payload::OrderCreated(p.into()),
+            EventPayload::OrderUpdated(p) => Payload::OrderUpdated(p.into()),
+            EventPayload::OrderLineUpdated(p) => Payload::OrderLineUpdated(p.into()),
+            EventPayload::SiteCheckIn(p) => Payload::SiteCheckIn(p.into()),
+            EventPayload::SiteCheckOut(p) => Payload::SiteCheckOut(p.into()),
+            EventPayload::StepStarted(p) => Payload::StepStarted(p.into()),
+            EventPayload::StepFinished(p) => Payload::StepFinished(p.into()),
+            EventPayload::ObjectChanged(p) => Payload::ObjectChanged(p.into()),
+            EventPayload::CourierUpdated(p) => Payload::CourierUpdated(p.into()),
+            EventPayload::PersonLifecycle(p) => Payload::PersonLifecycle(p.into()),
+            EventPayload::NotificationUpdated(p) => Payload::NotificationUpdated(p.into()),
+            EventPayload::CompensationIssued(p) => Payload::CompensationIssued(p.into()),
+            EventPayload::SupplyUpdated(p) => Payload::SupplyUpdated(p.into()),
+            EventPayload::CourierBreak(p) => Payload::CourierBreak(p.into()),
+            EventPayload::RobotDelivery(p) => Payload::RobotDelivery(p.into()),
+            EventPayload::SubstitutionUpdated(p) => Payload::SubstitutionUpdated(p.into()),
+            EventPayload::ConfigChanged(p) => Payload::ConfigChanged(p.into()),
+            EventPayload::MembershipBilled(p) => Payload::MembershipBilled(p.into()),
+            EventPayload::SessionUpdated(p) => Payload::SessionUpdated(p.into()),
+            EventPayload::ProcurementUpdated(p) => Payload::ProcurementUpdated(p.into()),
+            EventPayload::IngredientWasted(p) => Payload::IngredientWasted(p.into()),
+            EventPayload::DeliveryFootprint(p) => Payload::DeliveryFootprint(p.into()),
+        }
+    }
+}
+
+impl From<&PersonUpdatedPayload> for pb::PersonUpdated {
+    fn from(payload: &PersonUpdatedPayload) -> Self {
+        let mut message = pb::PersonUpdated {
+            person_id: payload.person_id.to_string(),
+            status: pb::PersonStatus::from(&payload.status).into(),
+            ..Default::default()
+        };
+        match &payload.status {
+            PersonStatus::Idle => (),
+            PersonStatus::AwaitingOrder(order_id) => {
+                message.order_id = Some(order_id.to_string());
+            }
+            PersonStatus::Eating(until) => message.busy_until = Some((*until).into()),
+            PersonStatus::Moving(journey) => message.journey = Some(journey.into()),
+            PersonStatus::Delivering(order_id, journey)
+            | PersonStatus::WaitingForCustomer(order_id, journey) => {
+                message.order_id = Some(order_id.to_string());
+                message.journey = Some(journey.into());
+            }
+        }
+        message
+    }
+}
+
+impl From<&Journey> for pb::JourneyProgress {
+    fn from(journey: &Journey) -> Self {
+        Self {
+            distance_m: journey.distance_m() as u64,
+            progress_percentage: journey.progress_percentage(),
+        }
+    }
+}
+
+impl From<&PersonStatus> for pb::PersonStatus {
+    fn from(status: &PersonStatus) -> Self {
+        match status {
+            PersonStatus::Idle => pb::PersonStatus::Idle,
+            PersonStatus::AwaitingOrder(_) => pb::PersonStatus::AwaitingOrder,
+            PersonStatus::Eating(_) => pb::PersonStatus::Eating,
+            PersonStatus::Moving(_) => pb::PersonStatus::Moving,
+            PersonStatus::Delivering(_, _) => pb::PersonStatus::Delivering,
+            PersonStatus::WaitingForCustomer(_, _) => pb::PersonStatus::WaitingForCustomer,
+        }
+    }
+}
+
+impl From<&OrderCreatedPayload> for pb::OrderCreated {
+    fn from(payload: &OrderCreatedPayload) -> Self {
+        Self {
+            site_id: payload.site_id.to_string(),
+            person_id: payload.person_id.to_string(),
+            items: payload
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, (brand_id, menu_item_id))| pb::OrderItem {
+                    brand_id: brand_id.to_string(),
+                    menu_item_id: menu_item_id.to_string(),
+                    cuisine: payload
+                        .cuisines
+                        .get(idx)
+                        .copied()
+                        .flatten()
+                        .map_or(pb::Cuisine::Unspecified, pb::Cuisine::from)
+                        .into(),
+                })
+                .collect(),
+            destination: Some(location(&payload.destination)),
+            total: payload.total,
+            channel: pb::OrderChannel::from(payload.channel).into(),
+            promised_at: Some(payload.promised_at.into()),
+            campaigns: payload.campaigns.clone(),
+            tip: payload.tip,
+            currency: payload.currency.to_string(),
+            order_id: payload.order_id.to_string(),
+            priority: pb::PriorityTier::from(payload.priority).into(),
+        }
+    }
+}
+
+impl From<Cuisine> for pb::Cuisine {
+    fn from(cuisine: Cuisine) -> Self {
+        match cuisine {
+            Cuisine::Asian => pb::Cuisine::Asian,
+            Cuisine::FastFood => pb::Cuisine::FastFood,
+            Cuisine::Mexican => pb::Cuisine::Mexican,
+        }
+    }
+}
+
+impl From<OrderChannel> for pb::OrderChannel {
+    fn from(channel: OrderChannel) -> Self {
+        match channel {
+            OrderChannel::App => pb::OrderChannel::App,
+            OrderChannel::Web => pb::OrderChannel::Web,
+            OrderChannel::Phone => pb::OrderChannel::Phone,
+        }
+    }
+}
+
+impl From<PriorityTier> for pb::PriorityTier {
+    fn from(tier: PriorityTier) -> Self {
+        match tier {
+            PriorityTier::Normal => pb::PriorityTier::Normal,
+            PriorityTier::Priority => pb::PriorityTier::Priority,
+            PriorityTier::Vip => pb::PriorityTier::Vip,
+        }
+    }
+}
+
+impl From<&OrderUpdatedPayload> for pb::OrderUpdated {
+    fn from(payload: &OrderUpdatedPayload) -> Self {
+        Self {
+            order_id: payload.order_id.to_string(),
+            status: pb::Status::from(&payload.status).into(),
+            actor_id: payload.actor_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+impl From<&OrderStatus> for pb::Status {
+    fn from(status: &OrderStatus) -> Self {
+        match status {
+            OrderStatus::Submitted => pb::Status::Received,
+            OrderStatus::Processing => pb::Status::Processing,
+            OrderStatus::Ready => pb::Status::Ready,
+            OrderStatus::PickedUp => pb::Status::PickedUp,
+            OrderStatus::Delivered => pb::Status::Delivered,
+            OrderStatus::Cancelled => pb::Status::Cancelled,
+            OrderStatus::Failed => pb::Status::Failed,
+            OrderStatus::Unknown(_) => pb::Status::Unspecified,
+        }
+    }
+}
+
+impl From<&OrderLineUpdatedPayload> for pb::OrderLineUpdated {
+    fn from(payload: &OrderLineUpdatedPayload) -> Self {
+        Self {
+            order_line_id: payload.order_line_id.to_string(),
+            status: pb::OrderLineStatus::from(payload.status).into(),
+            kitchen_id: payload.kitchen_id.map(|id| id.to_string()),
+            actor_id: payload.actor_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+impl From<OrderLineStatus> for pb::OrderLineStatus {
+    fn from(status: OrderLineStatus) -> Self {
+        match status {
+            OrderLineStatus::Submitted => pb::OrderLineStatus::Submitted,
+            OrderLineStatus::Assigned => pb::OrderLineStatus::Assigned,
+            OrderLineStatus::Processing => pb::OrderLineStatus::Processing,
+            OrderLineStatus::Ready => pb::OrderLineStatus::Ready,
+            OrderLineStatus::Delivered => pb::OrderLineStatus::Delivered,
+            OrderLineStatus::Waiting => pb::OrderLineStatus::Waiting,
+            OrderLineStatus::Removed => pb::OrderLineStatus::Removed,
+        }
+    }
+}
+
+impl From<&SiteCheckInPayload> for pb::SiteCheckIn {
+    fn from(payload: &SiteCheckInPayload) -> Self {
+        Self {
+            site_id: payload.site_id.to_string(),
+            person_id: payload.person_id.to_string(),
+        }
+    }
+}
+
+impl From<&SiteCheckOutPayload> for pb::SiteCheckOut {
+    fn from(payload: &SiteCheckOutPayload) -> Self {
+        Self {
+            site_id: payload.site_id.to_string(),
+            person_id: payload.person_id.to_string(),
+            order_ids: payload.order_ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+}
+
+impl From<&StepStartedPayload> for pb::StepStarted {
+    fn from(payload: &StepStartedPayload) -> Self {
+        Self {
+            simulation_time: Some(payload.simulation_time.into()),
+        }
+    }
+}
+
+impl From<&StepFinishedPayload> for pb::StepFinished {
+    fn from(payload: &StepFinishedPayload) -> Self {
+        Self {
+            simulation_time: Some(payload.simulation_time.into()),
+            num_events: payload.num_events as u64,
+        }
+    }
+}
+
+impl From<&ObjectChangedPayload> for pb::ObjectChanged {
+    fn from(payload: &ObjectChangedPayload) -> Self {
+        Self {
+            object_id: payload.object_id.to_string(),
+            label: payload.label.as_ref().to_string(),
+            uri_ref: payload.uri_ref.clone(),
+            change: pb::ObjectChange::from(payload.change).into(),
+        }
+    }
+}
+
+impl From<ObjectChange> for pb::ObjectChange {
+    fn from(change: ObjectChange) -> Self {
+        match change {
+            ObjectChange::Created => pb::ObjectChange::Created,
+            ObjectChange::Updated => pb::ObjectChange::Updated,
+        }
+    }
+}
+
+impl From<&CourierUpdatedPayload> for pb::CourierUpdated {
+    fn from(payload: &CourierUpdatedPayload) -> Self {
+        Self {
+            courier_id: payload.courier_id.to_string(),
+            order_id: payload.order_id.to_string(),
+            activity: pb::CourierActivity::from(payload.activity).into(),
+            offer: payload.offer.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl From<CourierActivity> for pb::CourierActivity {
+    fn from(activity: CourierActivity) -> Self {
+        match activity {
+            CourierActivity::OfferReceived => pb::CourierActivity::OfferReceived,
+            CourierActivity::OfferAccepted => pb::CourierActivity::OfferAccepted,
+            CourierActivity::OfferDeclined => pb::CourierActivity::OfferDeclined,
+            CourierActivity::PickedUp => pb::CourierActivity::PickedUp,
+            CourierActivity::ArrivedAtCustomer => pb::CourierActivity::ArrivedAtCustomer,
+            CourierActivity::Delivered => pb::CourierActivity::Delivered,
+            CourierActivity::OfferExpired => pb::CourierActivity::OfferExpired,
+        }
+    }
+}
+
+impl From<&CourierBreakPayload> for pb::CourierBreak {
+    fn from(payload: &CourierBreakPayload) -> Self {
+        Self {
+            courier_id: payload.courier_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            reason: pb::BreakReason::from(payload.reason).into(),
+            activity: pb::BreakActivity::from(payload.activity).into(),
+        }
+    }
+}
+
+impl From<BreakReason> for pb::BreakReason {
+    fn from(reason: BreakReason) -> Self {
+        match reason {
+            BreakReason::Charging => pb::BreakReason::Charging,
+            BreakReason::Rest => pb::BreakReason::Rest,
+        }
+    }
+}
+
+impl From<BreakActivity> for pb::BreakActivity {
+    fn from(activity: BreakActivity) -> Self {
+        match activity {
+            BreakActivity::Started => pb::BreakActivity::Started,
+            BreakActivity::Ended => pb::BreakActivity::Ended,
+        }
+    }
+}
+
+impl From<&RobotDeliveryPayload> for pb::RobotDelivery {
+    fn from(payload: &RobotDeliveryPayload) -> Self {
+        Self {
+            robot_id: payload.robot_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            order_id: payload.order_id.to_string(),
+            kind: pb::RobotKind::from(payload.kind).into(),
+            activity: pb::RobotActivity::from(payload.activity).into(),
+            distance_m: payload.distance_m,
+        }
+    }
+}
+
+impl From<RobotKind> for pb::RobotKind {
+    fn from(kind: RobotKind) -> Self {
+        match kind {
+            RobotKind::Robot => pb::RobotKind::Robot,
+            RobotKind::Drone => pb::RobotKind::Drone,
+        }
+    }
+}
+
+impl From<RobotActivity> for pb::RobotActivity {
+    fn from(activity: RobotActivity) -> Self {
+        match activity {
+            RobotActivity::Dispatched => pb::RobotActivity::Dispatched,
+            RobotActivity::Delivered => pb::RobotActivity::Delivered,
+            RobotActivity::Returned => pb::RobotActivity::Returned,
+        }
+    }
+}
+
+impl From<&SubstitutionUpdatedPayload> for pb::SubstitutionUpdated {
+    fn from(payload: &SubstitutionUpdatedPayload) -> Self {
+        Self {
+            order_id: payload.order_id.to_string(),
+            order_line_id: payload.order_line_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            sku: payload.sku.clone(),
+            substitute: payload.substitute.clone(),
+            status: pb::SubstitutionStatus::from(payload.status).into(),
+            price_delta: payload.price_delta,
+        }
+    }
+}
+
+impl From<&ConfigChangedPayload> for pb::ConfigChanged {
+    fn from(payload: &ConfigChangedPayload) -> Self {
+        Self {
+            setting: payload.setting.clone(),
+            previous: payload.previous,
+            value: payload.value,
+        }
+    }
+}
+
+impl From<&MembershipBilledPayload> for pb::MembershipBilled {
+    fn from(payload: &MembershipBilledPayload) -> Self {
+        Self {
+            person_id: payload.person_id.to_string(),
+            amount: payload.amount,
+            currency: payload.currency.to_string(),
+        }
+    }
+}
+
+impl From<&SessionUpdatedPayload> for pb::SessionUpdated {
+    fn from(payload: &SessionUpdatedPayload) -> Self {
+        Self {
+            session_id: payload.session_id.to_string(),
+            person_id: payload.person_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            stage: pb::FunnelStage::from(payload.stage).into(),
+            menu_item_ids: payload
+                .menu_item_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            order_id: payload.order_id.map(|id| id.to_string()),
+            occurred_at: Some(payload.occurred_at.into()),
+        }
+    }
+}
+
+impl From<FunnelStage> for pb::FunnelStage {
+    fn from(stage: FunnelStage) -> Self {
+        match stage {
+            FunnelStage::Browse => pb::FunnelStage::Browse,
+            FunnelStage::Cart => pb::FunnelStage::Cart,
+            FunnelStage::Checkout => pb::FunnelStage::Checkout,
+            FunnelStage::Abandoned => pb::FunnelStage::Abandoned,
+        }
+    }
+}
+
+impl From<&ProcurementUpdatedPayload> for pb::ProcurementUpdated {
+    fn from(payload: &ProcurementUpdatedPayload) -> Self {
+        Self {
+            supplier_order_id: payload.supplier_order_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            ingredient: payload.ingredient.clone(),
+            unit: payload.unit.clone(),
+            activity: pb::ProcurementActivity::from(payload.activity).into(),
+            quantity: payload.quantity,
+            expected_at: Some(payload.expected_at.into()),
+        }
+    }
+}
+
+impl From<ProcurementActivity> for pb::ProcurementActivity {
+    fn from(activity: ProcurementActivity) -> Self {
+        match activity {
+            ProcurementActivity::Ordered => pb::ProcurementActivity::Ordered,
+            ProcurementActivity::Delayed => pb::ProcurementActivity::Delayed,
+            ProcurementActivity::Delivered => pb::ProcurementActivity::Delivered,
+        }
+    }
+}
+
+impl From<&IngredientWastedPayload> for pb::IngredientWasted {
+    fn from(payload: &IngredientWastedPayload) -> Self {
+        Self {
+            site_id: payload.site_id.to_string(),
+            ingredient: payload.ingredient.clone(),
+            unit: payload.unit.clone(),
+            reason: pb::WasteReason::from(payload.reason).into(),
+            quantity: payload.quantity,
+        }
+    }
+}
+
+impl From<WasteReason> for pb::WasteReason {
+    fn from(reason: WasteReason) -> Self {
+        match reason {
+            WasteReason::Expired => pb::WasteReason::Expired,
+            WasteReason::Discarded => pb::WasteReason::Discarded,
+        }
+    }
+}
+
+impl From<&DeliveryFootprintPayload> for pb::DeliveryFootprint {
+    fn from(payload: &DeliveryFootprintPayload) -> Self {
+        Self {
+            order_id: payload.order_id.to_string(),
+            site_id: payload.site_id.to_string(),
+            mode: pb::DeliveryMode::from(payload.mode).into(),
+            distance_m: payload.distance_m,
+            co2_g: payload.co2_g,
+        }
+    }
+}
+
+impl From<DeliveryMode> for pb::DeliveryMode {
+    fn from(mode: DeliveryMode) -> Self {
+        match mode {
+            DeliveryMode::Foot => pb::DeliveryMode::Foot,
+            DeliveryMode::Bicycle => pb::DeliveryMode::Bicycle,
+            DeliveryMode::Car => pb::DeliveryMode::Car,
+            DeliveryMode::Robot => pb::DeliveryMode::Robot,
+            DeliveryMode::Drone => pb::DeliveryMode::Drone,
+        }
+    }
+}
+
+impl From<SubstitutionStatus> for pb::SubstitutionStatus {
+    fn from(status: SubstitutionStatus) -> Self {
+        match status {
+            SubstitutionStatus::Suggested => pb::SubstitutionStatus::Suggested,
+            SubstitutionStatus::Accepted => pb::SubstitutionStatus::Accepted,
+            SubstitutionStatus::Rejected => pb::SubstitutionStatus::Rejected,
+        }
+    }
+}
+
+impl From<&PersonLifecyclePayload> for pb::PersonLifecycle {
+    fn from(payload: &PersonLifecyclePayload) -> Self {
+        Self {
+            person_id: payload.person_id.to_string(),
+            stage: pb::LifecycleStage::from(payload.stage).into(),
+            order_id: payload.order_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+impl From<LifecycleStage> for pb::LifecycleStage {
+    fn from(stage: LifecycleStage) -> Self {
+        match stage {
+            LifecycleStage::SignedUp => pb::LifecycleStage::SignedUp,
+            LifecycleStage::FirstOrder => pb::LifecycleStage::FirstOrder,
+            LifecycleStage::Churned => pb::LifecycleStage::Churned,
+        }
+    }
+}
+
+impl From<&NotificationUpdatedPayload> for pb::NotificationUpdated {
+    fn from(payload: &NotificationUpdatedPayload) -> Self {
+        Self {
+            notification_id: payload.notification_id.to_string(),
+            person_id: payload.person_id.to_string(),
+            order_id: payload.order_id.to_string(),
+            channel: pb::NotificationChannel::from(payload.channel).into(),
+            trigger: pb::NotificationTrigger::from(payload.trigger).into(),
+            status: pb::NotificationStatus::from(payload.status).into(),
+        }
+    }
+}
+
+impl From<NotificationChannel> for pb::NotificationChannel {
+    fn from(channel: NotificationChannel) -> Self {
+        match channel {
+            NotificationChannel::Push => pb::NotificationChannel::Push,
+            NotificationChannel::Sms => pb::NotificationChannel::Sms,
+            NotificationChannel::Email => pb::NotificationChannel::Email,
+        }
+    }
+}
+
+impl From<NotificationTrigger> for pb::NotificationTrigger {
+    fn from(trigger: NotificationTrigger) -> Self {
+        match trigger {
+            NotificationTrigger::OrderConfirmed => pb::NotificationTrigger::OrderConfirmed,
+            NotificationTrigger::CourierNearby => pb::NotificationTrigger::CourierNearby,
+            NotificationTrigger::OrderDelivered => pb::NotificationTrigger::OrderDelivered,
+        }
+    }
+}
+
+impl From<NotificationStatus> for pb::NotificationStatus {
+    fn from(status: NotificationStatus) -> Self {
+        match status {
+            NotificationStatus::Sent => pb::NotificationStatus::Sent,
+            NotificationStatus::Delivered => pb::NotificationStatus::Delivered,
+            NotificationStatus::Failed => pb::NotificationStatus::Failed,
+            NotificationStatus::Opened => pb::NotificationStatus::Opened,
+            NotificationStatus::Clicked => pb::NotificationStatus::Clicked,
+        }
+    }
+}
+
+impl From<&CompensationIssuedPayload> for pb::CompensationIssued {
+    fn from(payload: &CompensationIssuedPayload) -> Self {
+        Self {
+            order_id: payload.order_id.to_string(),
+            person_id: payload.person_id.to_string(),
+            rule: payload.rule.clone(),
+            late_seconds: payload.late_s,
+            amount: payload.amount,
+            currency: payload.currency.to_string(),
+        }
+    }
+}
+
+impl From<&SupplyUpdatedPayload> for pb::SupplyUpdated {
+    fn from(payload: &SupplyUpdatedPayload) -> Self {
+        Self {
+            site_id: payload.site_id.to_string(),
+            supply: payload.supply.clone(),
+            activity: pb::SupplyActivity::from(payload.activity).into(),
+            stock: payload.stock,
+        }
+    }
+}
+
+impl From<SupplyActivity> for pb::SupplyActivity {
+    fn from(activity: SupplyActivity) -> Self {
+        match activity {
+            SupplyActivity::Reordered => pb::SupplyActivity::Reordered,
+            SupplyActivity::Restocked => pb::SupplyActivity::Restocked,
+            SupplyActivity::Depleted => pb::SupplyActivity::Depleted,
+        }
+    }
+}
+
+impl From<&CourierOffer> for pb::CourierOffer {
+    fn from(offer: &CourierOffer) -> Self {
+        Self {
+            distance_m: offer.distance_m,
+            earnings: offer.earnings,
+            tip: offer.tip,
+            acceptance_probability: offer.acceptance_probability,
+        }
+    }
+}
+
+fn location(point: &Point) -> pb::Location {
+    pb::Location {
+        latitude: point.y(),
+        longitude: point.x(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use prost::Message as _;
+
+    use pb::simulation_event::Payload;
+
+    use super::*;
+    use uuid::Uuid;
+
+    use crate::idents::{
+        MenuItemId, NotificationId, OrderId, OrderLineId, PersonId, SessionId, SiteId,
+        SupplierOrderId,
+    };
+    use crate::{CourierAcceptance, Currency};
+
+    #[test]
+    fn test_event_roundtrip() {
+        let site_id = SiteId::from_uri_ref("sites/test");
+        let person_id = PersonId::new();
+        let order_id = OrderId::new();
+        let event = Event {
+            timestamp: Utc::now(),
+            payload: EventPayload::site_check_out(site_id, person_id, vec![order_id]),
+        };
+
+        let message = pb::SimulationEvent::from(&event);
+        let decoded = pb::SimulationEvent::decode(message.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(message, decoded);
+
+        let Some(pb::simulation_event::Payload::SiteCheckOut(check_out)) = decoded.payload else {
+            panic!("expected check out payload");
+        };
+        assert_eq!(check_out.site_id, site_id.to_string());
+        assert_eq!(check_out.order_ids, vec![order_id.to_string()]);
+    }
+
+    #[test]
+    fn test_courier_updated() {
+        let offer = CourierAcceptance::default().offer(2_500.0, 1.5);
+        let payload = EventPayload::courier_updated(
+            PersonId::new(),
+            OrderId::new(),
+            CourierActivity::OfferDeclined,
+            Some(offer),
+        );
+        let Payload::CourierUpdated(message) = Payload::from(&payload) else {
+            panic!("expected courier payload");
+        };
+        assert_eq!(message.activity(), pb::CourierActivity::OfferDeclined);
+        let message_offer = message.offer.unwrap();
+        assert_eq!(message_offer.earnings, offer.earnings);
+        assert_eq!(message_offer.tip, 1.5);
+    }
+
+    #[test]
+    fn test_person_lifecycle() {
+        let order_id = OrderId::new();
+        let payload = EventPayload::person_lifecycle(
+            PersonId::new(),
+            LifecycleStage::FirstOrder,
+            Some(order_id),
+        );
+        let Payload::PersonLifecycle(message) = Payload::from(&payload) else {
+            panic!("expected lifecycle payload");
+        };
+        assert_eq!(message.stage(), pb::LifecycleStage::FirstOrder);
+        assert_eq!(message.order_id, Some(order_id.to_string()));
+    }
+
+    #[test]
+    fn test_notification_updated() {
+        let payload = EventPayload::notification_updated(
+            NotificationId::new(),
+            PersonId::new(),
+            OrderId::new(),
+            NotificationChannel::Sms,
+            NotificationTrigger::CourierNearby,
+            NotificationStatus::Clicked,
+        );
+        let Payload::NotificationUpdated(message) = Payload::from(&payload) else {
+            panic!("expected notification payload");
+        };
+        assert_eq!(message.channel(), pb::NotificationChannel::Sms);
+        assert_eq!(message.trigger(), pb::NotificationTrigger::CourierNearby);
+        assert_eq!(message.status(), pb::NotificationStatus::Clicked);
+    }
+
+    #[test]
+    fn test_supply_updated() {
+        let payload = EventPayload::supply_updated(
+            SiteId::from_name("london"),
+            "bags".into(),
+            SupplyActivity::Depleted,
+            0.0,
+        );
+        let Payload::SupplyUpdated(message) = Payload::from(&payload) else {
+            panic!("expected supply payload");
+        };
+        assert_eq!(message.activity(), pb::SupplyActivity::Depleted);
+        assert_eq!(message.supply, "bags");
+    }
+
+    #[test]
+    fn test_procurement_updated() {
+        let payload = EventPayload::procurement_updated(
+            SupplierOrderId::new(),
+            SiteId::from_name("london"),
+            ("ingredients/rice".into(), "g".into()),
+            ProcurementActivity::Delayed,
+            1200.0,
+            Utc::now(),
+        );
+        let Payload::ProcurementUpdated(message) = Payload::from(&payload) else {
+            panic!("expected procurement payload");
+        };
+        assert_eq!(message.activity(), pb::ProcurementActivity::Delayed);
+        assert_eq!(message.quantity, 1200.0);
+        assert!(message.expected_at.is_some());
+    }
+
+    #[test]
+    fn test_ingredient_wasted() {
+        let payload = EventPayload::ingredient_wasted(
+            SiteId::from_name("london"),
+            ("ingredients/rice".into(), "g".into()),
+            WasteReason::Expired,
+            350.0,
+        );
+        let Payload::IngredientWasted(message) = Payload::from(&payload) else {
+            panic!("expected ingredient wasted payload");
+        };
+        assert_eq!(message.reason(), pb::WasteReason::Expired);
+        assert_eq!(message.quantity, 350.0);
+    }
+
+    #[test]
+    fn test_delivery_footprint() {
+        let payload = EventPayload::delivery_footprint(
+            OrderId::new(),
+            SiteId::from_name("london"),
+            DeliveryMode::Drone,
+            2_400.0,
+            96.0,
+        );
+        let Payload::DeliveryFootprint(message) = Payload::from(&payload) else {
+            panic!("expected delivery footprint payload");
+        };
+        assert_eq!(message.mode(), pb::DeliveryMode::Drone);
+        assert_eq!(message.co2_g, 96.0);
+    }
+
+    #[test]
+    fn test_courier_break() {
+        let payload = EventPayload::courier_break(
+            PersonId::new(),
+            SiteId::from_name("london"),
+            BreakReason::Charging,
+            BreakActivity::Started,
+        );
+        let Payload::CourierBreak(message) = Payload::from(&payload) else {
+            panic!("expected courier break payload");
+        };
+        assert_eq!(message.reason(), pb::BreakReason::Charging);
+        assert_eq!(message.activity(), pb::BreakActivity::Started);
+    }
+
+    #[test]
+    fn test_robot_delivery() {
+        let payload = EventPayload::robot_delivery(
+            Uuid::new_v4(),
+            SiteId::from_name("london"),
+            OrderId::new(),
+            RobotKind::Drone,
+            RobotActivity::Delivered,
+            1_200.0,
+        );
+        let Payload::RobotDelivery(message) = Payload::from(&payload) else {
+            panic!("expected robot delivery payload");
+        };
+        assert_eq!(message.kind(), pb::RobotKind::Drone);
+        assert_eq!(message.activity(), pb::RobotActivity::Delivered);
+        assert_eq!(message.distance_m, 1_200.0);
+    }
+
+    #[test]
+    fn test_substitution_updated() {
+        let payload = EventPayload::substitution_updated(
+            OrderId::new(),
+            OrderLineId::new(),
+            SiteId::from_name("london"),
+            "oat milk".into(),
+            "soy milk".into(),
+            SubstitutionStatus::Accepted,
+            -0.5,
+        );
+        let Payload::SubstitutionUpdated(message) = Payload::from(&payload) else {
+            panic!("expected substitution payload");
+        };
+        assert_eq!(message.status(), pb::SubstitutionStatus::Accepted);
+        assert_eq!(message.substitute, "soy milk");
+        assert_eq!(message.price_delta, -0.5);
+    }
+
+    #[test]
+    fn test_config_changed() {
+        let payload = EventPayload::config_changed("active_couriers", None, Some(12.0));
+        let Payload::ConfigChanged(message) = Payload::from(&payload) else {
+            panic!("expected config changed payload");
+        };
+        assert_eq!(message.setting, "active_couriers");
+        assert_eq!(message.previous, None);
+        assert_eq!(message.value, Some(12.0));
+    }
+
+    #[test]
+    fn test_membership_billed() {
+        let person_id = PersonId::new();
+        let payload = EventPayload::membership_billed(person_id, 9.99, Currency::USD);
+        let Payload::MembershipBilled(message) = Payload::from(&payload) else {
+            panic!("expected membership billed payload");
+        };
+        assert_eq!(message.person_id, person_id.to_string());
+        assert_eq!(message.amount, 9.99);
+        assert_eq!(message.currency, "USD");
+    }
+
+    #[test]
+    fn test_session_updated() {
+        let (order_id, menu_item_id) = (OrderId::new(), MenuItemId::from_names("brand", "item"));
+        let payload = EventPayload::session_updated(
+            SessionId::new(),
+            PersonId::new(),
+            SiteId::from_name("london"),
+            FunnelStage::Checkout,
+            vec![menu_item_id],
+            Some(order_id),
+            chrono::Utc::now(),
+        );
+        let Payload::SessionUpdated(message) = Payload::from(&payload) else {
+            panic!("expected session updated payload");
+        };
+        assert_eq!(message.stage(), pb::FunnelStage::Checkout);
+        assert_eq!(message.menu_item_ids, [menu_item_id.to_string()]);
+        assert_eq!(message.order_id, Some(order_id.to_string()));
+        assert!(message.occurred_at.is_some());
+    }
+
+    #[test]
+    fn test_order_status() {
+        let payload = OrderUpdatedPayload {
+            order_id: OrderId::new(),
+            status: OrderStatus::Cancelled,
+            actor_id: None,
+        };
+        let message = pb::OrderUpdated::from(&payload);
+        assert_eq!(message.status(), pb::Status::Cancelled);
+        assert_eq!(message.actor_id, None);
+    }
+}
@@ -17,6 +17,9 @@ pub struct Site {
     pub latitude: f64,
     #[prost(double, tag = "4")]
     pub longitude: f64,
+    /// ISO 4217 code of the currency orders at the site are charged in
+    #[prost(string, optional, tag = "5")]
+    pub currency: ::core::option::Option<::prost::alloc::string::String>,
 }
 impl ::prost::Name for Site {
     const NAME: &'static str = "Site";
@@ -176,6 +179,9 @@ pub struct Brand {
     pub category: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "5")]
     pub items: ::prost::alloc::vec::Vec<MenuItem>,
+    /// ISO 4217 code of the currency menu items without their own currency are priced in
+    #[prost(string, optional, tag = "6")]
+    pub currency: ::core::option::Option<::prost::alloc::string::String>,
 }
 impl ::prost::Name for Brand {
     const NAME: &'static str = "Brand";
@@ -203,7 +209,7 @@ pub struct MenuItem {
     /// description of the menu item
     #[prost(string, tag = "3")]
     pub description: ::prost::alloc::string::String,
-    /// Price of the menu item in USD
+    /// Price of the menu item in its currency
     #[prost(double, tag = "4")]
     pub price: f64,
     /// URL to an image representing the menu item
@@ -215,6 +221,9 @@ pub struct MenuItem {
     /// Instructions required to prepare the menu item
     #[prost(message, repeated, tag = "7")]
     pub instructions: ::prost::alloc::vec::Vec<Instruction>,
+    /// ISO 4217 code of the currency the price is given in, defaults to the brand currency
+    #[prost(string, optional, tag = "8")]
+    pub currency: ::core::option::Option<::prost::alloc::string::String>,
 }
 impl ::prost::Name for MenuItem {
     const NAME: &'static str = "MenuItem";
@@ -22,6 +22,9 @@ impl serde::Serialize for Brand {
         if !self.items.is_empty() {
             len += 1;
         }
+        if self.currency.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("caspers.core.v1.Brand", len)?;
         if !self.id.is_empty() {
             struct_ser.serialize_field("id", &self.id)?;
@@ -38,6 +41,9 @@ impl serde::Serialize for Brand {
         if !self.items.is_empty() {
             struct_ser.serialize_field("items", &self.items)?;
         }
+        if let Some(v) = self.currency.as_ref() {
+            struct_ser.serialize_field("currency", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -53,6 +59,7 @@ impl<'de> serde::Deserialize<'de> for Brand {
             "description",
             "category",
             "items",
+            "currency",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -62,6 +69,7 @@ impl<'de> serde::Deserialize<'de> for Brand {
             Description,
             Category,
             Items,
+            Currency,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -89,6 +97,7 @@ impl<'de> serde::Deserialize<'de> for Brand {
                             "description" => Ok(GeneratedField::Description),
                             "category" => Ok(GeneratedField::Category),
                             "items" => Ok(GeneratedField::Items),
+                            "currency" => Ok(GeneratedField::Currency),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -113,6 +122,7 @@ impl<'de> serde::Deserialize<'de> for Brand {
                 let mut description__ = None;
                 let mut category__ = None;
                 let mut items__ = None;
+                let mut currency__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Id => {
@@ -145,6 +155,12 @@ impl<'de> serde::Deserialize<'de> for Brand {
                             }
                             items__ = Some(map_.next_value()?);
                         }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = map_.next_value()?;
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -156,6 +172,7 @@ impl<'de> serde::Deserialize<'de> for Brand {
                     description: description__.unwrap_or_default(),
                     category: category__.unwrap_or_default(),
                     items: items__.unwrap_or_default(),
+                    currency: currency__,
                 })
             }
         }
@@ -1475,6 +1492,9 @@ impl serde::Serialize for MenuItem {
         if !self.instructions.is_empty() {
             len += 1;
         }
+        if self.currency.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("caspers.core.v1.MenuItem", len)?;
         if !self.id.is_empty() {
             struct_ser.serialize_field("id", &self.id)?;
@@ -1497,6 +1517,9 @@ impl serde::Serialize for MenuItem {
         if !self.instructions.is_empty() {
             struct_ser.serialize_field("instructions", &self.instructions)?;
         }
+        if let Some(v) = self.currency.as_ref() {
+            struct_ser.serialize_field("currency", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -1515,6 +1538,7 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
             "imageUrl",
             "ingredients",
             "instructions",
+            "currency",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1526,6 +1550,7 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
             ImageUrl,
             Ingredients,
             Instructions,
+            Currency,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -1555,6 +1580,7 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
                             "imageUrl" | "image_url" => Ok(GeneratedField::ImageUrl),
                             "ingredients" => Ok(GeneratedField::Ingredients),
                             "instructions" => Ok(GeneratedField::Instructions),
+                            "currency" => Ok(GeneratedField::Currency),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -1581,6 +1607,7 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
                 let mut image_url__ = None;
                 let mut ingredients__ = None;
                 let mut instructions__ = None;
+                let mut currency__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Id => {
@@ -1627,6 +1654,12 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
                             }
                             instructions__ = Some(map_.next_value()?);
                         }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = map_.next_value()?;
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -1640,6 +1673,7 @@ impl<'de> serde::Deserialize<'de> for MenuItem {
                     image_url: image_url__,
                     ingredients: ingredients__.unwrap_or_default(),
                     instructions: instructions__.unwrap_or_default(),
+                    currency: currency__,
                 })
             }
         }
@@ -1666,6 +1700,9 @@ impl serde::Serialize for Site {
         if self.longitude != 0. {
             len += 1;
         }
+        if self.currency.is_some() {
+            len += 1;
+        }
         let mut struct_ser = serializer.serialize_struct("caspers.core.v1.Site", len)?;
         if !self.id.is_empty() {
             struct_ser.serialize_field("id", &self.id)?;
@@ -1679,6 +1716,9 @@ impl serde::Serialize for Site {
         if self.longitude != 0. {
             struct_ser.serialize_field("longitude", &self.longitude)?;
         }
+        if let Some(v) = self.currency.as_ref() {
+            struct_ser.serialize_field("currency", v)?;
+        }
         struct_ser.end()
     }
 }
@@ -1693,6 +1733,7 @@ impl<'de> serde::Deserialize<'de> for Site {
             "name",
             "latitude",
             "longitude",
+            "currency",
         ];
 
         #[allow(clippy::enum_variant_names)]
@@ -1701,6 +1742,7 @@ impl<'de> serde::Deserialize<'de> for Site {
             Name,
             Latitude,
             Longitude,
+            Currency,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -1727,6 +1769,7 @@ impl<'de> serde::Deserialize<'de> for Site {
                             "name" => Ok(GeneratedField::Name),
                             "latitude" => Ok(GeneratedField::Latitude),
                             "longitude" => Ok(GeneratedField::Longitude),
+                            "currency" => Ok(GeneratedField::Currency),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -1750,6 +1793,7 @@ impl<'de> serde::Deserialize<'de> for Site {
                 let mut name__ = None;
                 let mut latitude__ = None;
                 let mut longitude__ = None;
+                let mut currency__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
                         GeneratedField::Id => {
@@ -1780,6 +1824,12 @@ impl<'de> serde::Deserialize<'de> for Site {
                                 Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
                             ;
                         }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = map_.next_value()?;
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
@@ -1790,6 +1840,7 @@ impl<'de> serde::Deserialize<'de> for Site {
                     name: name__.unwrap_or_default(),
                     latitude: latitude__.unwrap_or_default(),
                     longitude: longitude__.unwrap_or_default(),
+                    currency: currency__,
                 })
             }
         }
@@ -197,6 +197,8 @@ pub enum Status {
     Delivered = 6,
     /// status cancelled
     Cancelled = 7,
+    /// status failed
+    Failed = 8,
 }
 impl Status {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -213,6 +215,7 @@ impl Status {
             Status::PickedUp => "STATUS_PICKED_UP",
             Status::Delivered => "STATUS_DELIVERED",
             Status::Cancelled => "STATUS_CANCELLED",
+            Status::Failed => "STATUS_FAILED",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -226,9 +229,1494 @@ impl Status {
             "STATUS_PICKED_UP" => Some(Self::PickedUp),
             "STATUS_DELIVERED" => Some(Self::Delivered),
             "STATUS_CANCELLED" => Some(Self::Cancelled),
+            "STATUS_FAILED" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+/// A geographic location.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Location {
+    #[prost(double, tag="1")]
+    pub latitude: f64,
+    #[prost(double, tag="2")]
+    pub longitude: f64,
+}
+impl ::prost::Name for Location {
+const NAME: &'static str = "Location";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.Location".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.Location".into() }}
+/// Progress of a person along a journey.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JourneyProgress {
+    /// Total distance of the journey in meters
+    #[prost(uint64, tag="1")]
+    pub distance_m: u64,
+    /// Completed share of the journey in percent
+    #[prost(double, tag="2")]
+    pub progress_percentage: f64,
+}
+impl ::prost::Name for JourneyProgress {
+const NAME: &'static str = "JourneyProgress";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.JourneyProgress".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.JourneyProgress".into() }}
+/// A person changed their status.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PersonUpdated {
+    /// The unique identifier for the person.
+    #[prost(string, tag="1")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The new status of the person.
+    #[prost(enumeration="PersonStatus", tag="2")]
+    pub status: i32,
+    /// The order the person is handling, if any.
+    #[prost(string, optional, tag="3")]
+    pub order_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The journey the person is travelling, if any.
+    #[prost(message, optional, tag="4")]
+    pub journey: ::core::option::Option<JourneyProgress>,
+    /// The time until which the person is busy, if any.
+    #[prost(message, optional, tag="5")]
+    pub busy_until: ::core::option::Option<::pbjson_types::Timestamp>,
+}
+impl ::prost::Name for PersonUpdated {
+const NAME: &'static str = "PersonUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.PersonUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.PersonUpdated".into() }}
+/// A single menu item within a created order.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderItem {
+    /// The unique identifier for the brand.
+    #[prost(string, tag="1")]
+    pub brand_id: ::prost::alloc::string::String,
+    /// The unique identifier for the menu item.
+    #[prost(string, tag="2")]
+    pub menu_item_id: ::prost::alloc::string::String,
+    /// Cuisine of the brand, unspecified for uncategorized brands.
+    #[prost(enumeration="Cuisine", tag="3")]
+    pub cuisine: i32,
+}
+impl ::prost::Name for OrderItem {
+const NAME: &'static str = "OrderItem";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.OrderItem".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.OrderItem".into() }}
+/// A customer created a new order.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderCreated {
+    /// The unique identifier for the site fulfilling the order.
+    #[prost(string, tag="1")]
+    pub site_id: ::prost::alloc::string::String,
+    /// The unique identifier for the customer.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The items in the order.
+    #[prost(message, repeated, tag="3")]
+    pub items: ::prost::alloc::vec::Vec<OrderItem>,
+    /// Where the order should be delivered.
+    #[prost(message, optional, tag="4")]
+    pub destination: ::core::option::Option<Location>,
+    /// Order total in the order currency.
+    #[prost(double, tag="5")]
+    pub total: f64,
+    /// The channel through which the order was placed.
+    #[prost(enumeration="OrderChannel", tag="6")]
+    pub channel: i32,
+    /// Time by which the order is promised to be delivered.
+    #[prost(message, optional, tag="7")]
+    pub promised_at: ::core::option::Option<::pbjson_types::Timestamp>,
+    /// Names of campaigns applied to the order.
+    #[prost(string, repeated, tag="8")]
+    pub campaigns: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Tip in the order currency, if any.
+    #[prost(double, optional, tag="9")]
+    pub tip: ::core::option::Option<f64>,
+    /// ISO 4217 code of the currency of the site fulfilling the order.
+    #[prost(string, tag="10")]
+    pub currency: ::prost::alloc::string::String,
+    /// The unique identifier assigned to the new order.
+    #[prost(string, tag="11")]
+    pub order_id: ::prost::alloc::string::String,
+    /// Priority tier of the customer placing the order.
+    #[prost(enumeration="PriorityTier", tag="12")]
+    pub priority: i32,
+}
+impl ::prost::Name for OrderCreated {
+const NAME: &'static str = "OrderCreated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.OrderCreated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.OrderCreated".into() }}
+/// An order changed its status.
+///
+/// Cancelled and failed orders are reported through this message.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderUpdated {
+    /// The unique identifier for the order.
+    #[prost(string, tag="1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The new status of the order.
+    #[prost(enumeration="Status", tag="2")]
+    pub status: i32,
+    /// The person who caused the update, if any.
+    #[prost(string, optional, tag="3")]
+    pub actor_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+impl ::prost::Name for OrderUpdated {
+const NAME: &'static str = "OrderUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.OrderUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.OrderUpdated".into() }}
+/// An order line changed its status.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderLineUpdated {
+    /// The unique identifier for the order line.
+    #[prost(string, tag="1")]
+    pub order_line_id: ::prost::alloc::string::String,
+    /// The new status of the order line.
+    #[prost(enumeration="OrderLineStatus", tag="2")]
+    pub status: i32,
+    /// The kitchen handling the order line, if any.
+    #[prost(string, optional, tag="3")]
+    pub kitchen_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The person who caused the update, if any.
+    #[prost(string, optional, tag="4")]
+    pub actor_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+impl ::prost::Name for OrderLineUpdated {
+const NAME: &'static str = "OrderLineUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.OrderLineUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.OrderLineUpdated".into() }}
+/// A person arrived at a site.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SiteCheckIn {
+    /// The unique identifier for the site.
+    #[prost(string, tag="1")]
+    pub site_id: ::prost::alloc::string::String,
+    /// The unique identifier for the person.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+}
+impl ::prost::Name for SiteCheckIn {
+const NAME: &'static str = "SiteCheckIn";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SiteCheckIn".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SiteCheckIn".into() }}
+/// A person left a site.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SiteCheckOut {
+    /// The unique identifier for the site.
+    #[prost(string, tag="1")]
+    pub site_id: ::prost::alloc::string::String,
+    /// The unique identifier for the person.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The orders collected at the site.
+    #[prost(string, repeated, tag="3")]
+    pub order_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+impl ::prost::Name for SiteCheckOut {
+const NAME: &'static str = "SiteCheckOut";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SiteCheckOut".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SiteCheckOut".into() }}
+/// The simulation started advancing by one time step.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StepStarted {
+    /// Simulation time at the start of the step.
+    #[prost(message, optional, tag="1")]
+    pub simulation_time: ::core::option::Option<::pbjson_types::Timestamp>,
+}
+impl ::prost::Name for StepStarted {
+const NAME: &'static str = "StepStarted";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.StepStarted".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.StepStarted".into() }}
+/// The simulation finished advancing by one time step.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StepFinished {
+    /// Simulation time at the start of the step.
+    #[prost(message, optional, tag="1")]
+    pub simulation_time: ::core::option::Option<::pbjson_types::Timestamp>,
+    /// Number of events generated during the step.
+    #[prost(uint64, tag="2")]
+    pub num_events: u64,
+}
+impl ::prost::Name for StepFinished {
+const NAME: &'static str = "StepFinished";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.StepFinished".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.StepFinished".into() }}
+/// An object (site, kitchen, station, brand or menu item) was added or changed.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectChanged {
+    /// The unique identifier for the object.
+    #[prost(string, tag="1")]
+    pub object_id: ::prost::alloc::string::String,
+    /// The label of the object (e.g. site or menu_item).
+    #[prost(string, tag="2")]
+    pub label: ::prost::alloc::string::String,
+    /// The URI reference of the object, if known.
+    #[prost(string, optional, tag="3")]
+    pub uri_ref: ::core::option::Option<::prost::alloc::string::String>,
+    /// The kind of change.
+    #[prost(enumeration="ObjectChange", tag="4")]
+    pub change: i32,
+}
+impl ::prost::Name for ObjectChanged {
+const NAME: &'static str = "ObjectChanged";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.ObjectChanged".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.ObjectChanged".into() }}
+/// Terms of a delivery offered to a courier.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CourierOffer {
+    /// Distance of the delivery route in meters.
+    #[prost(double, tag="1")]
+    pub distance_m: f64,
+    /// Earnings for the delivery in the site currency, including the tip.
+    #[prost(double, tag="2")]
+    pub earnings: f64,
+    /// Modelled probability that the courier accepts the offer.
+    #[prost(double, tag="3")]
+    pub acceptance_probability: f64,
+    /// Tip in the site currency the customer added to the order.
+    #[prost(double, tag="4")]
+    pub tip: f64,
+}
+impl ::prost::Name for CourierOffer {
+const NAME: &'static str = "CourierOffer";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.CourierOffer".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.CourierOffer".into() }}
+/// A courier made progress on a delivery.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CourierUpdated {
+    /// The unique identifier for the courier.
+    #[prost(string, tag="1")]
+    pub courier_id: ::prost::alloc::string::String,
+    /// The unique identifier for the order.
+    #[prost(string, tag="2")]
+    pub order_id: ::prost::alloc::string::String,
+    /// What the courier did.
+    #[prost(enumeration="CourierActivity", tag="3")]
+    pub activity: i32,
+    /// The offer the courier responded to, for offer activities.
+    #[prost(message, optional, tag="4")]
+    pub offer: ::core::option::Option<CourierOffer>,
+}
+impl ::prost::Name for CourierUpdated {
+const NAME: &'static str = "CourierUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.CourierUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.CourierUpdated".into() }}
+/// A customer reached a stage of their lifecycle.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PersonLifecycle {
+    /// The unique identifier for the customer.
+    #[prost(string, tag="1")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The stage the customer reached.
+    #[prost(enumeration="LifecycleStage", tag="2")]
+    pub stage: i32,
+    /// The order placed, for first orders.
+    #[prost(string, optional, tag="3")]
+    pub order_id: ::core::option::Option<::prost::alloc::string::String>,
+}
+impl ::prost::Name for PersonLifecycle {
+const NAME: &'static str = "PersonLifecycle";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.PersonLifecycle".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.PersonLifecycle".into() }}
+/// A notification sent to a customer made progress.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NotificationUpdated {
+    /// The unique identifier for the notification.
+    #[prost(string, tag="1")]
+    pub notification_id: ::prost::alloc::string::String,
+    /// The unique identifier for the customer.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The unique identifier for the order.
+    #[prost(string, tag="3")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The channel the notification is sent through.
+    #[prost(enumeration="NotificationChannel", tag="4")]
+    pub channel: i32,
+    /// The order milestone the notification is sent for.
+    #[prost(enumeration="NotificationTrigger", tag="5")]
+    pub trigger: i32,
+    /// The step the notification reached.
+    #[prost(enumeration="NotificationStatus", tag="6")]
+    pub status: i32,
+}
+impl ::prost::Name for NotificationUpdated {
+const NAME: &'static str = "NotificationUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.NotificationUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.NotificationUpdated".into() }}
+/// A customer received a voucher for a late delivery.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompensationIssued {
+    /// The unique identifier for the order.
+    #[prost(string, tag="1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The unique identifier for the customer.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+    /// Name of the compensation rule that applied.
+    #[prost(string, tag="3")]
+    pub rule: ::prost::alloc::string::String,
+    /// Seconds the order was delivered after the promised time.
+    #[prost(double, tag="4")]
+    pub late_seconds: f64,
+    /// Voucher amount in the order currency.
+    #[prost(double, tag="5")]
+    pub amount: f64,
+    /// ISO 4217 code of the order currency.
+    #[prost(string, tag="6")]
+    pub currency: ::prost::alloc::string::String,
+}
+impl ::prost::Name for CompensationIssued {
+const NAME: &'static str = "CompensationIssued";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.CompensationIssued".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.CompensationIssued".into() }}
+/// Packaging supplies of a site were reordered, restocked or ran out.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SupplyUpdated {
+    /// The unique identifier for the site.
+    #[prost(string, tag="1")]
+    pub site_id: ::prost::alloc::string::String,
+    /// Name of the packaging supply.
+    #[prost(string, tag="2")]
+    pub supply: ::prost::alloc::string::String,
+    /// What happened to the supply.
+    #[prost(enumeration="SupplyActivity", tag="3")]
+    pub activity: i32,
+    /// Units of the supply in stock after the activity.
+    #[prost(double, tag="4")]
+    pub stock: f64,
+}
+impl ::prost::Name for SupplyUpdated {
+const NAME: &'static str = "SupplyUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SupplyUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SupplyUpdated".into() }}
+/// A courier working at a site started or ended a break.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CourierBreak {
+    /// The unique identifier for the courier.
+    #[prost(string, tag="1")]
+    pub courier_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site.
+    #[prost(string, tag="2")]
+    pub site_id: ::prost::alloc::string::String,
+    /// Why the courier takes the break.
+    #[prost(enumeration="BreakReason", tag="3")]
+    pub reason: i32,
+    /// Whether the break started or ended.
+    #[prost(enumeration="BreakActivity", tag="4")]
+    pub activity: i32,
+}
+impl ::prost::Name for CourierBreak {
+const NAME: &'static str = "CourierBreak";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.CourierBreak".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.CourierBreak".into() }}
+/// A robot of a site made progress on a delivery.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RobotDelivery {
+    /// The unique identifier for the robot.
+    #[prost(string, tag="1")]
+    pub robot_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site.
+    #[prost(string, tag="2")]
+    pub site_id: ::prost::alloc::string::String,
+    /// The unique identifier for the order.
+    #[prost(string, tag="3")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The kind of the robot.
+    #[prost(enumeration="RobotKind", tag="4")]
+    pub kind: i32,
+    /// The progress of the delivery.
+    #[prost(enumeration="RobotActivity", tag="5")]
+    pub activity: i32,
+    /// Distance from the site to the customer in meters.
+    #[prost(double, tag="6")]
+    pub distance_m: f64,
+}
+impl ::prost::Name for RobotDelivery {
+const NAME: &'static str = "RobotDelivery";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.RobotDelivery".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.RobotDelivery".into() }}
+/// An item of an order was out of stock and substituted.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubstitutionUpdated {
+    /// The unique identifier for the order.
+    #[prost(string, tag="1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The unique identifier for the order line.
+    #[prost(string, tag="2")]
+    pub order_line_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site.
+    #[prost(string, tag="3")]
+    pub site_id: ::prost::alloc::string::String,
+    /// Name of the SKU that was out of stock.
+    #[prost(string, tag="4")]
+    pub sku: ::prost::alloc::string::String,
+    /// Name of the SKU suggested in its place.
+    #[prost(string, tag="5")]
+    pub substitute: ::prost::alloc::string::String,
+    /// The progress of the substitution.
+    #[prost(enumeration="SubstitutionStatus", tag="6")]
+    pub status: i32,
+    /// Change of the order total in the order currency.
+    #[prost(double, tag="7")]
+    pub price_delta: f64,
+}
+impl ::prost::Name for SubstitutionUpdated {
+const NAME: &'static str = "SubstitutionUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SubstitutionUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SubstitutionUpdated".into() }}
+/// A setting of a running simulation was changed.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfigChanged {
+    /// Name of the setting, e.g. demand_multiplier.
+    #[prost(string, tag="1")]
+    pub setting: ::prost::alloc::string::String,
+    /// Value before the change, unset if the setting was not set.
+    #[prost(double, optional, tag="2")]
+    pub previous: ::core::option::Option<f64>,
+    /// Value from this step on, unset if the setting was cleared.
+    #[prost(double, optional, tag="3")]
+    pub value: ::core::option::Option<f64>,
+}
+impl ::prost::Name for ConfigChanged {
+const NAME: &'static str = "ConfigChanged";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.ConfigChanged".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.ConfigChanged".into() }}
+/// A member was billed the monthly fee of their delivery subscription.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MembershipBilled {
+    /// The unique identifier for the member.
+    #[prost(string, tag="1")]
+    pub person_id: ::prost::alloc::string::String,
+    /// Membership fee in the billing currency.
+    #[prost(double, tag="2")]
+    pub amount: f64,
+    /// ISO 4217 code of the billing currency.
+    #[prost(string, tag="3")]
+    pub currency: ::prost::alloc::string::String,
+}
+impl ::prost::Name for MembershipBilled {
+const NAME: &'static str = "MembershipBilled";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.MembershipBilled".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.MembershipBilled".into() }}
+/// An app session of a customer reached a stage of the ordering funnel.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionUpdated {
+    /// The unique identifier for the session.
+    #[prost(string, tag="1")]
+    pub session_id: ::prost::alloc::string::String,
+    /// The unique identifier for the customer.
+    #[prost(string, tag="2")]
+    pub person_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site the customer browsed.
+    #[prost(string, tag="3")]
+    pub site_id: ::prost::alloc::string::String,
+    /// The stage the session reached.
+    #[prost(enumeration="FunnelStage", tag="4")]
+    pub stage: i32,
+    /// Menu items viewed while browsing or in the cart at the later stages.
+    #[prost(string, repeated, tag="5")]
+    pub menu_item_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// The order placed at checkout, if any.
+    #[prost(string, optional, tag="6")]
+    pub order_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Time at which the session reached the stage.
+    #[prost(message, optional, tag="7")]
+    pub occurred_at: ::core::option::Option<::pbjson_types::Timestamp>,
+}
+impl ::prost::Name for SessionUpdated {
+const NAME: &'static str = "SessionUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SessionUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SessionUpdated".into() }}
+/// An ingredient of a supplier order was ordered, delayed or delivered.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProcurementUpdated {
+    /// The unique identifier for the supplier order.
+    #[prost(string, tag="1")]
+    pub supplier_order_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site receiving the order.
+    #[prost(string, tag="2")]
+    pub site_id: ::prost::alloc::string::String,
+    /// Reference of the ingredient in the ingredient catalog.
+    #[prost(string, tag="3")]
+    pub ingredient: ::prost::alloc::string::String,
+    /// Unit the quantity is measured in.
+    #[prost(string, tag="4")]
+    pub unit: ::prost::alloc::string::String,
+    /// What happened to the ingredient of the order.
+    #[prost(enumeration="ProcurementActivity", tag="5")]
+    pub activity: i32,
+    /// Quantity ordered, or delivered once the order arrived.
+    #[prost(double, tag="6")]
+    pub quantity: f64,
+    /// Time at which the delivery is expected to arrive.
+    #[prost(message, optional, tag="7")]
+    pub expected_at: ::core::option::Option<::pbjson_types::Timestamp>,
+}
+impl ::prost::Name for ProcurementUpdated {
+const NAME: &'static str = "ProcurementUpdated";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.ProcurementUpdated".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.ProcurementUpdated".into() }}
+/// Stock of an ingredient was thrown away at a site.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IngredientWasted {
+    /// The unique identifier for the site the stock was kept at.
+    #[prost(string, tag="1")]
+    pub site_id: ::prost::alloc::string::String,
+    /// Reference of the ingredient in the ingredient catalog.
+    #[prost(string, tag="2")]
+    pub ingredient: ::prost::alloc::string::String,
+    /// Unit the quantity is measured in.
+    #[prost(string, tag="3")]
+    pub unit: ::prost::alloc::string::String,
+    /// Why the stock was thrown away.
+    #[prost(enumeration="WasteReason", tag="4")]
+    pub reason: i32,
+    /// Quantity thrown away.
+    #[prost(double, tag="5")]
+    pub quantity: f64,
+}
+impl ::prost::Name for IngredientWasted {
+const NAME: &'static str = "IngredientWasted";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.IngredientWasted".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.IngredientWasted".into() }}
+/// Carbon footprint of delivering an order.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeliveryFootprint {
+    /// The unique identifier for the order delivered.
+    #[prost(string, tag="1")]
+    pub order_id: ::prost::alloc::string::String,
+    /// The unique identifier for the site delivering the order.
+    #[prost(string, tag="2")]
+    pub site_id: ::prost::alloc::string::String,
+    /// How the order is delivered.
+    #[prost(enumeration="DeliveryMode", tag="3")]
+    pub mode: i32,
+    /// Distance travelled for the delivery in meters.
+    #[prost(double, tag="4")]
+    pub distance_m: f64,
+    /// Grams of CO2 emitted for the delivery.
+    #[prost(double, tag="5")]
+    pub co2_g: f64,
+}
+impl ::prost::Name for DeliveryFootprint {
+const NAME: &'static str = "DeliveryFootprint";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.DeliveryFootprint".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.DeliveryFootprint".into() }}
+/// An event emitted by the simulation.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulationEvent {
+    /// Time at which the event occurred.
+    #[prost(message, optional, tag="1")]
+    pub time: ::core::option::Option<::pbjson_types::Timestamp>,
+    /// The event payload.
+    #[prost(oneof="simulation_event::Payload", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24")]
+    pub payload: ::core::option::Option<simulation_event::Payload>,
+}
+/// Nested message and enum types in `SimulationEvent`.
+pub mod simulation_event {
+    /// The event payload.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Payload {
+        #[prost(message, tag="2")]
+        PersonUpdated(super::PersonUpdated),
+        #[prost(message, tag="3")]
+        OrderCreated(super::OrderCreated),
+        #[prost(message, tag="4")]
+        OrderUpdated(super::OrderUpdated),
+        #[prost(message, tag="5")]
+        OrderLineUpdated(super::OrderLineUpdated),
+        #[prost(message, tag="6")]
+        SiteCheckIn(super::SiteCheckIn),
+        #[prost(message, tag="7")]
+        SiteCheckOut(super::SiteCheckOut),
+        #[prost(message, tag="8")]
+        StepStarted(super::StepStarted),
+        #[prost(message, tag="9")]
+        StepFinished(super::StepFinished),
+        #[prost(message, tag="10")]
+        ObjectChanged(super::ObjectChanged),
+        #[prost(message, tag="11")]
+        CourierUpdated(super::CourierUpdated),
+        #[prost(message, tag="12")]
+        PersonLifecycle(super::PersonLifecycle),
+        #[prost(message, tag="13")]
+        NotificationUpdated(super::NotificationUpdated),
+        #[prost(message, tag="14")]
+        CompensationIssued(super::CompensationIssued),
+        #[prost(message, tag="15")]
+        SupplyUpdated(super::SupplyUpdated),
+        #[prost(message, tag="16")]
+        CourierBreak(super::CourierBreak),
+        #[prost(message, tag="17")]
+        RobotDelivery(super::RobotDelivery),
+        #[prost(message, tag="18")]
+        SubstitutionUpdated(super::SubstitutionUpdated),
+        #[prost(message, tag="19")]
+        ConfigChanged(super::ConfigChanged),
+        #[prost(message, tag="20")]
+        MembershipBilled(super::MembershipBilled),
+        #[prost(message, tag="21")]
+        SessionUpdated(super::SessionUpdated),
+        #[prost(message, tag="22")]
+        ProcurementUpdated(super::ProcurementUpdated),
+        #[prost(message, tag="23")]
+        IngredientWasted(super::IngredientWasted),
+        #[prost(message, tag="24")]
+        DeliveryFootprint(super::DeliveryFootprint),
+    }
+}
+impl ::prost::Name for SimulationEvent {
+const NAME: &'static str = "SimulationEvent";
+const PACKAGE: &'static str = "caspers.messages.v1";
+fn full_name() -> ::prost::alloc::string::String { "caspers.messages.v1.SimulationEvent".into() }fn type_url() -> ::prost::alloc::string::String { "/caspers.messages.v1.SimulationEvent".into() }}
+/// The status of a person in the simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PersonStatus {
+    /// default status
+    Unspecified = 0,
+    /// person is not doing anything
+    Idle = 1,
+    /// person is waiting for an order to be delivered
+    AwaitingOrder = 2,
+    /// person is eating
+    Eating = 3,
+    /// person is moving to a destination
+    Moving = 4,
+    /// person is delivering an order
+    Delivering = 5,
+    /// person is waiting for the customer to take an order
+    WaitingForCustomer = 6,
+}
+impl PersonStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PersonStatus::Unspecified => "PERSON_STATUS_UNSPECIFIED",
+            PersonStatus::Idle => "PERSON_STATUS_IDLE",
+            PersonStatus::AwaitingOrder => "PERSON_STATUS_AWAITING_ORDER",
+            PersonStatus::Eating => "PERSON_STATUS_EATING",
+            PersonStatus::Moving => "PERSON_STATUS_MOVING",
+            PersonStatus::Delivering => "PERSON_STATUS_DELIVERING",
+            PersonStatus::WaitingForCustomer => "PERSON_STATUS_WAITING_FOR_CUSTOMER",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PERSON_STATUS_UNSPECIFIED" => Some(Self::Unspecified),
+            "PERSON_STATUS_IDLE" => Some(Self::Idle),
+            "PERSON_STATUS_AWAITING_ORDER" => Some(Self::AwaitingOrder),
+            "PERSON_STATUS_EATING" => Some(Self::Eating),
+            "PERSON_STATUS_MOVING" => Some(Self::Moving),
+            "PERSON_STATUS_DELIVERING" => Some(Self::Delivering),
+            "PERSON_STATUS_WAITING_FOR_CUSTOMER" => Some(Self::WaitingForCustomer),
+            _ => None,
+        }
+    }
+}
+/// The status of an order line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderLineStatus {
+    /// default status
+    Unspecified = 0,
+    /// order line is submitted
+    Submitted = 1,
+    /// order line is assigned to a kitchen
+    Assigned = 2,
+    /// order line is currently processing
+    Processing = 3,
+    /// order line is ready for pick up
+    Ready = 4,
+    /// order line is delivered
+    Delivered = 5,
+    /// order line is waiting
+    Waiting = 6,
+    /// order line was removed from the order
+    Removed = 7,
+}
+impl OrderLineStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            OrderLineStatus::Unspecified => "ORDER_LINE_STATUS_UNSPECIFIED",
+            OrderLineStatus::Submitted => "ORDER_LINE_STATUS_SUBMITTED",
+            OrderLineStatus::Assigned => "ORDER_LINE_STATUS_ASSIGNED",
+            OrderLineStatus::Processing => "ORDER_LINE_STATUS_PROCESSING",
+            OrderLineStatus::Ready => "ORDER_LINE_STATUS_READY",
+            OrderLineStatus::Delivered => "ORDER_LINE_STATUS_DELIVERED",
+            OrderLineStatus::Waiting => "ORDER_LINE_STATUS_WAITING",
+            OrderLineStatus::Removed => "ORDER_LINE_STATUS_REMOVED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ORDER_LINE_STATUS_UNSPECIFIED" => Some(Self::Unspecified),
+            "ORDER_LINE_STATUS_SUBMITTED" => Some(Self::Submitted),
+            "ORDER_LINE_STATUS_ASSIGNED" => Some(Self::Assigned),
+            "ORDER_LINE_STATUS_PROCESSING" => Some(Self::Processing),
+            "ORDER_LINE_STATUS_READY" => Some(Self::Ready),
+            "ORDER_LINE_STATUS_DELIVERED" => Some(Self::Delivered),
+            "ORDER_LINE_STATUS_WAITING" => Some(Self::Waiting),
+            "ORDER_LINE_STATUS_REMOVED" => Some(Self::Removed),
+            _ => None,
+        }
+    }
+}
+/// The channel through which an order was placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OrderChannel {
+    /// default channel
+    Unspecified = 0,
+    /// order placed in the app
+    App = 1,
+    /// order placed on the web
+    Web = 2,
+    /// order placed by phone
+    Phone = 3,
+}
+impl OrderChannel {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            OrderChannel::Unspecified => "ORDER_CHANNEL_UNSPECIFIED",
+            OrderChannel::App => "ORDER_CHANNEL_APP",
+            OrderChannel::Web => "ORDER_CHANNEL_WEB",
+            OrderChannel::Phone => "ORDER_CHANNEL_PHONE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ORDER_CHANNEL_UNSPECIFIED" => Some(Self::Unspecified),
+            "ORDER_CHANNEL_APP" => Some(Self::App),
+            "ORDER_CHANNEL_WEB" => Some(Self::Web),
+            "ORDER_CHANNEL_PHONE" => Some(Self::Phone),
+            _ => None,
+        }
+    }
+}
+/// Priority tier of the customer placing an order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PriorityTier {
+    /// default tier
+    Unspecified = 0,
+    /// customer without a subscription
+    Normal = 1,
+    /// customer with a priority subscription
+    Priority = 2,
+    /// very important customer
+    Vip = 3,
+}
+impl PriorityTier {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PriorityTier::Unspecified => "PRIORITY_TIER_UNSPECIFIED",
+            PriorityTier::Normal => "PRIORITY_TIER_NORMAL",
+            PriorityTier::Priority => "PRIORITY_TIER_PRIORITY",
+            PriorityTier::Vip => "PRIORITY_TIER_VIP",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PRIORITY_TIER_UNSPECIFIED" => Some(Self::Unspecified),
+            "PRIORITY_TIER_NORMAL" => Some(Self::Normal),
+            "PRIORITY_TIER_PRIORITY" => Some(Self::Priority),
+            "PRIORITY_TIER_VIP" => Some(Self::Vip),
+            _ => None,
+        }
+    }
+}
+/// Cuisines brands are categorized by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Cuisine {
+    /// uncategorized brand
+    Unspecified = 0,
+    /// asian cuisine
+    Asian = 1,
+    /// fast food
+    FastFood = 2,
+    /// mexican cuisine
+    Mexican = 3,
+}
+impl Cuisine {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Cuisine::Unspecified => "CUISINE_UNSPECIFIED",
+            Cuisine::Asian => "CUISINE_ASIAN",
+            Cuisine::FastFood => "CUISINE_FAST_FOOD",
+            Cuisine::Mexican => "CUISINE_MEXICAN",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CUISINE_UNSPECIFIED" => Some(Self::Unspecified),
+            "CUISINE_ASIAN" => Some(Self::Asian),
+            "CUISINE_FAST_FOOD" => Some(Self::FastFood),
+            "CUISINE_MEXICAN" => Some(Self::Mexican),
+            _ => None,
+        }
+    }
+}
+/// The kind of change applied to an object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ObjectChange {
+    /// default change
+    Unspecified = 0,
+    /// object was created
+    Created = 1,
+    /// object properties were updated
+    Updated = 2,
+}
+impl ObjectChange {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ObjectChange::Unspecified => "OBJECT_CHANGE_UNSPECIFIED",
+            ObjectChange::Created => "OBJECT_CHANGE_CREATED",
+            ObjectChange::Updated => "OBJECT_CHANGE_UPDATED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OBJECT_CHANGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "OBJECT_CHANGE_CREATED" => Some(Self::Created),
+            "OBJECT_CHANGE_UPDATED" => Some(Self::Updated),
+            _ => None,
+        }
+    }
+}
+/// A step in a courier's handling of a delivery.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CourierActivity {
+    /// default activity
+    Unspecified = 0,
+    /// courier was offered a delivery
+    OfferReceived = 1,
+    /// courier accepted the offered delivery
+    OfferAccepted = 2,
+    /// courier declined the offered delivery
+    OfferDeclined = 3,
+    /// courier picked up the order at the site
+    PickedUp = 4,
+    /// courier arrived at the delivery destination
+    ArrivedAtCustomer = 5,
+    /// courier handed the order to the customer
+    Delivered = 6,
+    /// courier did not respond to the offered delivery in time
+    OfferExpired = 7,
+}
+impl CourierActivity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CourierActivity::Unspecified => "COURIER_ACTIVITY_UNSPECIFIED",
+            CourierActivity::OfferReceived => "COURIER_ACTIVITY_OFFER_RECEIVED",
+            CourierActivity::OfferAccepted => "COURIER_ACTIVITY_OFFER_ACCEPTED",
+            CourierActivity::OfferDeclined => "COURIER_ACTIVITY_OFFER_DECLINED",
+            CourierActivity::PickedUp => "COURIER_ACTIVITY_PICKED_UP",
+            CourierActivity::ArrivedAtCustomer => "COURIER_ACTIVITY_ARRIVED_AT_CUSTOMER",
+            CourierActivity::Delivered => "COURIER_ACTIVITY_DELIVERED",
+            CourierActivity::OfferExpired => "COURIER_ACTIVITY_OFFER_EXPIRED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "COURIER_ACTIVITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "COURIER_ACTIVITY_OFFER_RECEIVED" => Some(Self::OfferReceived),
+            "COURIER_ACTIVITY_OFFER_ACCEPTED" => Some(Self::OfferAccepted),
+            "COURIER_ACTIVITY_OFFER_DECLINED" => Some(Self::OfferDeclined),
+            "COURIER_ACTIVITY_PICKED_UP" => Some(Self::PickedUp),
+            "COURIER_ACTIVITY_ARRIVED_AT_CUSTOMER" => Some(Self::ArrivedAtCustomer),
+            "COURIER_ACTIVITY_DELIVERED" => Some(Self::Delivered),
+            "COURIER_ACTIVITY_OFFER_EXPIRED" => Some(Self::OfferExpired),
+            _ => None,
+        }
+    }
+}
+/// Stage of a customer's lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum LifecycleStage {
+    /// default stage
+    Unspecified = 0,
+    /// customer joined the simulation
+    SignedUp = 1,
+    /// customer placed their first order
+    FirstOrder = 2,
+    /// customer did not order for longer than the churn period
+    Churned = 3,
+}
+impl LifecycleStage {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            LifecycleStage::Unspecified => "LIFECYCLE_STAGE_UNSPECIFIED",
+            LifecycleStage::SignedUp => "LIFECYCLE_STAGE_SIGNED_UP",
+            LifecycleStage::FirstOrder => "LIFECYCLE_STAGE_FIRST_ORDER",
+            LifecycleStage::Churned => "LIFECYCLE_STAGE_CHURNED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "LIFECYCLE_STAGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "LIFECYCLE_STAGE_SIGNED_UP" => Some(Self::SignedUp),
+            "LIFECYCLE_STAGE_FIRST_ORDER" => Some(Self::FirstOrder),
+            "LIFECYCLE_STAGE_CHURNED" => Some(Self::Churned),
+            _ => None,
+        }
+    }
+}
+/// Channel a notification is sent through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NotificationChannel {
+    /// default channel
+    Unspecified = 0,
+    /// push notification to the app
+    Push = 1,
+    /// text message
+    Sms = 2,
+    /// email
+    Email = 3,
+}
+impl NotificationChannel {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NotificationChannel::Unspecified => "NOTIFICATION_CHANNEL_UNSPECIFIED",
+            NotificationChannel::Push => "NOTIFICATION_CHANNEL_PUSH",
+            NotificationChannel::Sms => "NOTIFICATION_CHANNEL_SMS",
+            NotificationChannel::Email => "NOTIFICATION_CHANNEL_EMAIL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NOTIFICATION_CHANNEL_UNSPECIFIED" => Some(Self::Unspecified),
+            "NOTIFICATION_CHANNEL_PUSH" => Some(Self::Push),
+            "NOTIFICATION_CHANNEL_SMS" => Some(Self::Sms),
+            "NOTIFICATION_CHANNEL_EMAIL" => Some(Self::Email),
+            _ => None,
+        }
+    }
+}
+/// Order milestone a notification is sent for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NotificationTrigger {
+    /// default trigger
+    Unspecified = 0,
+    /// the order was submitted to the site
+    OrderConfirmed = 1,
+    /// the courier arrived at the delivery destination
+    CourierNearby = 2,
+    /// the order was handed to the customer
+    OrderDelivered = 3,
+}
+impl NotificationTrigger {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NotificationTrigger::Unspecified => "NOTIFICATION_TRIGGER_UNSPECIFIED",
+            NotificationTrigger::OrderConfirmed => "NOTIFICATION_TRIGGER_ORDER_CONFIRMED",
+            NotificationTrigger::CourierNearby => "NOTIFICATION_TRIGGER_COURIER_NEARBY",
+            NotificationTrigger::OrderDelivered => "NOTIFICATION_TRIGGER_ORDER_DELIVERED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NOTIFICATION_TRIGGER_UNSPECIFIED" => Some(Self::Unspecified),
+            "NOTIFICATION_TRIGGER_ORDER_CONFIRMED" => Some(Self::OrderConfirmed),
+            "NOTIFICATION_TRIGGER_COURIER_NEARBY" => Some(Self::CourierNearby),
+            "NOTIFICATION_TRIGGER_ORDER_DELIVERED" => Some(Self::OrderDelivered),
+            _ => None,
+        }
+    }
+}
+/// Step in the delivery of a notification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NotificationStatus {
+    /// default status
+    Unspecified = 0,
+    /// notification was handed to the provider of the channel
+    Sent = 1,
+    /// notification reached the customer
+    Delivered = 2,
+    /// notification could not be delivered
+    Failed = 3,
+    /// customer opened the notification
+    Opened = 4,
+    /// customer followed the link in the notification
+    Clicked = 5,
+}
+impl NotificationStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NotificationStatus::Unspecified => "NOTIFICATION_STATUS_UNSPECIFIED",
+            NotificationStatus::Sent => "NOTIFICATION_STATUS_SENT",
+            NotificationStatus::Delivered => "NOTIFICATION_STATUS_DELIVERED",
+            NotificationStatus::Failed => "NOTIFICATION_STATUS_FAILED",
+            NotificationStatus::Opened => "NOTIFICATION_STATUS_OPENED",
+            NotificationStatus::Clicked => "NOTIFICATION_STATUS_CLICKED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NOTIFICATION_STATUS_UNSPECIFIED" => Some(Self::Unspecified),
+            "NOTIFICATION_STATUS_SENT" => Some(Self::Sent),
+            "NOTIFICATION_STATUS_DELIVERED" => Some(Self::Delivered),
+            "NOTIFICATION_STATUS_FAILED" => Some(Self::Failed),
+            "NOTIFICATION_STATUS_OPENED" => Some(Self::Opened),
+            "NOTIFICATION_STATUS_CLICKED" => Some(Self::Clicked),
+            _ => None,
+        }
+    }
+}
+/// Activity of the packaging supplies of a site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SupplyActivity {
+    /// default activity
+    Unspecified = 0,
+    /// supplies fell to the reorder point and were reordered
+    Reordered = 1,
+    /// reordered supplies arrived at the site
+    Restocked = 2,
+    /// supplies ran out, holding up packing until they are restocked
+    Depleted = 3,
+}
+impl SupplyActivity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SupplyActivity::Unspecified => "SUPPLY_ACTIVITY_UNSPECIFIED",
+            SupplyActivity::Reordered => "SUPPLY_ACTIVITY_REORDERED",
+            SupplyActivity::Restocked => "SUPPLY_ACTIVITY_RESTOCKED",
+            SupplyActivity::Depleted => "SUPPLY_ACTIVITY_DEPLETED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SUPPLY_ACTIVITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "SUPPLY_ACTIVITY_REORDERED" => Some(Self::Reordered),
+            "SUPPLY_ACTIVITY_RESTOCKED" => Some(Self::Restocked),
+            "SUPPLY_ACTIVITY_DEPLETED" => Some(Self::Depleted),
+            _ => None,
+        }
+    }
+}
+/// Why a courier takes a break.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BreakReason {
+    /// default reason
+    Unspecified = 0,
+    /// courier charges the battery of their e-bike
+    Charging = 1,
+    /// courier rests after working for a long time
+    Rest = 2,
+}
+impl BreakReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            BreakReason::Unspecified => "BREAK_REASON_UNSPECIFIED",
+            BreakReason::Charging => "BREAK_REASON_CHARGING",
+            BreakReason::Rest => "BREAK_REASON_REST",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BREAK_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "BREAK_REASON_CHARGING" => Some(Self::Charging),
+            "BREAK_REASON_REST" => Some(Self::Rest),
+            _ => None,
+        }
+    }
+}
+/// Whether a break started or ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BreakActivity {
+    /// default activity
+    Unspecified = 0,
+    /// courier became unavailable for deliveries
+    Started = 1,
+    /// courier is available for deliveries again
+    Ended = 2,
+}
+impl BreakActivity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            BreakActivity::Unspecified => "BREAK_ACTIVITY_UNSPECIFIED",
+            BreakActivity::Started => "BREAK_ACTIVITY_STARTED",
+            BreakActivity::Ended => "BREAK_ACTIVITY_ENDED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "BREAK_ACTIVITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "BREAK_ACTIVITY_STARTED" => Some(Self::Started),
+            "BREAK_ACTIVITY_ENDED" => Some(Self::Ended),
+            _ => None,
+        }
+    }
+}
+/// Kind of an autonomous delivery vehicle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RobotKind {
+    /// default kind
+    Unspecified = 0,
+    /// sidewalk robot following the streets open to pedestrians
+    Robot = 1,
+    /// drone flying straight to the customer
+    Drone = 2,
+}
+impl RobotKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RobotKind::Unspecified => "ROBOT_KIND_UNSPECIFIED",
+            RobotKind::Robot => "ROBOT_KIND_ROBOT",
+            RobotKind::Drone => "ROBOT_KIND_DRONE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ROBOT_KIND_UNSPECIFIED" => Some(Self::Unspecified),
+            "ROBOT_KIND_ROBOT" => Some(Self::Robot),
+            "ROBOT_KIND_DRONE" => Some(Self::Drone),
+            _ => None,
+        }
+    }
+}
+/// Progress of a delivery by a robot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RobotActivity {
+    /// default activity
+    Unspecified = 0,
+    /// robot left the site with the order
+    Dispatched = 1,
+    /// robot handed the order to the customer
+    Delivered = 2,
+    /// robot is back at the site and available for deliveries
+    Returned = 3,
+}
+impl RobotActivity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RobotActivity::Unspecified => "ROBOT_ACTIVITY_UNSPECIFIED",
+            RobotActivity::Dispatched => "ROBOT_ACTIVITY_DISPATCHED",
+            RobotActivity::Delivered => "ROBOT_ACTIVITY_DELIVERED",
+            RobotActivity::Returned => "ROBOT_ACTIVITY_RETURNED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ROBOT_ACTIVITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "ROBOT_ACTIVITY_DISPATCHED" => Some(Self::Dispatched),
+            "ROBOT_ACTIVITY_DELIVERED" => Some(Self::Delivered),
+            "ROBOT_ACTIVITY_RETURNED" => Some(Self::Returned),
+            _ => None,
+        }
+    }
+}
+/// Progress of substituting an item that is out of stock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SubstitutionStatus {
+    /// default status
+    Unspecified = 0,
+    /// substitute was suggested to the customer
+    Suggested = 1,
+    /// customer accepted the substitute
+    Accepted = 2,
+    /// customer rejected the substitute, the item is removed from the order
+    Rejected = 3,
+}
+impl SubstitutionStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SubstitutionStatus::Unspecified => "SUBSTITUTION_STATUS_UNSPECIFIED",
+            SubstitutionStatus::Suggested => "SUBSTITUTION_STATUS_SUGGESTED",
+            SubstitutionStatus::Accepted => "SUBSTITUTION_STATUS_ACCEPTED",
+            SubstitutionStatus::Rejected => "SUBSTITUTION_STATUS_REJECTED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SUBSTITUTION_STATUS_UNSPECIFIED" => Some(Self::Unspecified),
+            "SUBSTITUTION_STATUS_SUGGESTED" => Some(Self::Suggested),
+            "SUBSTITUTION_STATUS_ACCEPTED" => Some(Self::Accepted),
+            "SUBSTITUTION_STATUS_REJECTED" => Some(Self::Rejected),
             _ => None,
         }
     }
 }
 include!("caspers.messages.v1.serde.rs");
-// @@protoc_insertion_point(module)
\ No newline at end of file
+/// Stage of the funnel an app session reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum FunnelStage {
+    /// default stage
+    Unspecified = 0,
+    /// the customer viewed menu items
+    Browse = 1,
+    /// the customer added menu items to the cart
+    Cart = 2,
+    /// the customer placed the order
+    Checkout = 3,
+    /// the customer left the app without ordering
+    Abandoned = 4,
+}
+impl FunnelStage {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            FunnelStage::Unspecified => "FUNNEL_STAGE_UNSPECIFIED",
+            FunnelStage::Browse => "FUNNEL_STAGE_BROWSE",
+            FunnelStage::Cart => "FUNNEL_STAGE_CART",
+            FunnelStage::Checkout => "FUNNEL_STAGE_CHECKOUT",
+            FunnelStage::Abandoned => "FUNNEL_STAGE_ABANDONED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "FUNNEL_STAGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "FUNNEL_STAGE_BROWSE" => Some(Self::Browse),
+            "FUNNEL_STAGE_CART" => Some(Self::Cart),
+            "FUNNEL_STAGE_CHECKOUT" => Some(Self::Checkout),
+            "FUNNEL_STAGE_ABANDONED" => Some(Self::Abandoned),
+            _ => None,
+        }
+    }
+}
+/// Activity of a supplier order replenishing the ingredient stock of a site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProcurementActivity {
+    /// default activity
+    Unspecified = 0,
+    /// the site ordered the ingredient from its supplier
+    Ordered = 1,
+    /// the delivery of the ingredient is running late
+    Delayed = 2,
+    /// the ingredient was delivered to the site, possibly short of the ordered quantity
+    Delivered = 3,
+}
+impl ProcurementActivity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ProcurementActivity::Unspecified => "PROCUREMENT_ACTIVITY_UNSPECIFIED",
+            ProcurementActivity::Ordered => "PROCUREMENT_ACTIVITY_ORDERED",
+            ProcurementActivity::Delayed => "PROCUREMENT_ACTIVITY_DELAYED",
+            ProcurementActivity::Delivered => "PROCUREMENT_ACTIVITY_DELIVERED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PROCUREMENT_ACTIVITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "PROCUREMENT_ACTIVITY_ORDERED" => Some(Self::Ordered),
+            "PROCUREMENT_ACTIVITY_DELAYED" => Some(Self::Delayed),
+            "PROCUREMENT_ACTIVITY_DELIVERED" => Some(Self::Delivered),
+            _ => None,
+        }
+    }
+}
+/// Reason ingredient stock was thrown away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WasteReason {
+    /// default reason
+    Unspecified = 0,
+    /// the stock passed its shelf life
+    Expired = 1,
+    /// the stock was discarded at the end of the day, e.g. prepped but unused
+    Discarded = 2,
+}
+impl WasteReason {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WasteReason::Unspecified => "WASTE_REASON_UNSPECIFIED",
+            WasteReason::Expired => "WASTE_REASON_EXPIRED",
+            WasteReason::Discarded => "WASTE_REASON_DISCARDED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WASTE_REASON_UNSPECIFIED" => Some(Self::Unspecified),
+            "WASTE_REASON_EXPIRED" => Some(Self::Expired),
+            "WASTE_REASON_DISCARDED" => Some(Self::Discarded),
+            _ => None,
+        }
+    }
+}
+/// Means of transport delivering an order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DeliveryMode {
+    /// default mode
+    Unspecified = 0,
+    /// courier on foot
+    Foot = 1,
+    /// courier riding a bicycle
+    Bicycle = 2,
+    /// courier driving a car or scooter
+    Car = 3,
+    /// sidewalk robot
+    Robot = 4,
+    /// drone flying straight to the customer
+    Drone = 5,
+}
+impl DeliveryMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DeliveryMode::Unspecified => "DELIVERY_MODE_UNSPECIFIED",
+            DeliveryMode::Foot => "DELIVERY_MODE_FOOT",
+            DeliveryMode::Bicycle => "DELIVERY_MODE_BICYCLE",
+            DeliveryMode::Car => "DELIVERY_MODE_CAR",
+            DeliveryMode::Robot => "DELIVERY_MODE_ROBOT",
+            DeliveryMode::Drone => "DELIVERY_MODE_DRONE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "DELIVERY_MODE_UNSPECIFIED" => Some(Self::Unspecified),
+            "DELIVERY_MODE_FOOT" => Some(Self::Foot),
+            "DELIVERY_MODE_BICYCLE" => Some(Self::Bicycle),
+            "DELIVERY_MODE_CAR" => Some(Self::Car),
+            "DELIVERY_MODE_ROBOT" => Some(Self::Robot),
+            "DELIVERY_MODE_DRONE" => Some(Self::Drone),
+            _ => None,
+        }
+    }
+}
+// @@protoc_insertion_point(module)
@@ -1,4 +1,152 @@
 // @generated
+impl serde::Serialize for BreakActivity {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "BREAK_ACTIVITY_UNSPECIFIED",
+            Self::Started => "BREAK_ACTIVITY_STARTED",
+            Self::Ended => "BREAK_ACTIVITY_ENDED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for BreakActivity {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "BREAK_ACTIVITY_UNSPECIFIED",
+            "BREAK_ACTIVITY_STARTED",
+            "BREAK_ACTIVITY_ENDED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BreakActivity;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "BREAK_ACTIVITY_UNSPECIFIED" => Ok(BreakActivity::Unspecified),
+                    "BREAK_ACTIVITY_STARTED" => Ok(BreakActivity::Started),
+                    "BREAK_ACTIVITY_ENDED" => Ok(BreakActivity::Ended),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BreakReason {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "BREAK_REASON_UNSPECIFIED",
+            Self::Charging => "BREAK_REASON_CHARGING",
+            Self::Rest => "BREAK_REASON_REST",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for BreakReason {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "BREAK_REASON_UNSPECIFIED",
+            "BREAK_REASON_CHARGING",
+            "BREAK_REASON_REST",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BreakReason;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "BREAK_REASON_UNSPECIFIED" => Ok(BreakReason::Unspecified),
+                    "BREAK_REASON_CHARGING" => Ok(BreakReason::Charging),
+                    "BREAK_REASON_REST" => Ok(BreakReason::Rest),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
 impl serde::Serialize for CloudEvent {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -509,7 +657,7 @@ impl<'de> serde::Deserialize<'de> for CloudEventBatch {
         deserializer.deserialize_struct("caspers.messages.v1.CloudEventBatch", FIELDS, GeneratedVisitor)
     }
 }
-impl serde::Serialize for LineItem {
+impl serde::Serialize for CompensationIssued {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
@@ -517,63 +665,72 @@ impl serde::Serialize for LineItem {
     {
         use serde::ser::SerializeStruct;
         let mut len = 0;
-        if !self.id.is_empty() {
+        if !self.order_id.is_empty() {
             len += 1;
         }
-        if !self.product_id.is_empty() {
+        if !self.person_id.is_empty() {
             len += 1;
         }
-        if self.quantity != 0 {
+        if !self.rule.is_empty() {
             len += 1;
         }
-        if self.price != 0. {
+        if self.late_seconds != 0. {
             len += 1;
         }
-        if self.total_price != 0. {
+        if self.amount != 0. {
             len += 1;
         }
-        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.LineItem", len)?;
-        if !self.id.is_empty() {
-            struct_ser.serialize_field("id", &self.id)?;
+        if !self.currency.is_empty() {
+            len += 1;
         }
-        if !self.product_id.is_empty() {
-            struct_ser.serialize_field("product_id", &self.product_id)?;
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.CompensationIssued", len)?;
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
         }
-        if self.quantity != 0 {
-            struct_ser.serialize_field("quantity", &self.quantity)?;
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
         }
-        if self.price != 0. {
-            struct_ser.serialize_field("price", &self.price)?;
+        if !self.rule.is_empty() {
+            struct_ser.serialize_field("rule", &self.rule)?;
         }
-        if self.total_price != 0. {
-            struct_ser.serialize_field("total_price", &self.total_price)?;
+        if self.late_seconds != 0. {
+            struct_ser.serialize_field("late_seconds", &self.late_seconds)?;
+        }
+        if self.amount != 0. {
+            struct_ser.serialize_field("amount", &self.amount)?;
+        }
+        if !self.currency.is_empty() {
+            struct_ser.serialize_field("currency", &self.currency)?;
         }
         struct_ser.end()
     }
 }
-impl<'de> serde::Deserialize<'de> for LineItem {
+impl<'de> serde::Deserialize<'de> for CompensationIssued {
     #[allow(deprecated)]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         const FIELDS: &[&str] = &[
-            "id",
-            "product_id",
-            "productId",
-            "quantity",
-            "price",
-            "total_price",
-            "totalPrice",
+            "order_id",
+            "orderId",
+            "person_id",
+            "personId",
+            "rule",
+            "late_seconds",
+            "lateSeconds",
+            "amount",
+            "currency",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
-            Id,
-            ProductId,
-            Quantity,
-            Price,
-            TotalPrice,
+            OrderId,
+            PersonId,
+            Rule,
+            LateSeconds,
+            Amount,
+            Currency,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -596,11 +753,12 @@ impl<'de> serde::Deserialize<'de> for LineItem {
                         E: serde::de::Error,
                     {
                         match value {
-                            "id" => Ok(GeneratedField::Id),
-                            "productId" | "product_id" => Ok(GeneratedField::ProductId),
-                            "quantity" => Ok(GeneratedField::Quantity),
-                            "price" => Ok(GeneratedField::Price),
-                            "totalPrice" | "total_price" => Ok(GeneratedField::TotalPrice),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "rule" => Ok(GeneratedField::Rule),
+                            "lateSeconds" | "late_seconds" => Ok(GeneratedField::LateSeconds),
+                            "amount" => Ok(GeneratedField::Amount),
+                            "currency" => Ok(GeneratedField::Currency),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -610,77 +768,83 @@ impl<'de> serde::Deserialize<'de> for LineItem {
         }
         struct GeneratedVisitor;
         impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
-            type Value = LineItem;
+            type Value = CompensationIssued;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("struct caspers.messages.v1.LineItem")
+                formatter.write_str("struct caspers.messages.v1.CompensationIssued")
             }
 
-            fn visit_map<V>(self, mut map_: V) -> std::result::Result<LineItem, V::Error>
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CompensationIssued, V::Error>
                 where
                     V: serde::de::MapAccess<'de>,
             {
-                let mut id__ = None;
-                let mut product_id__ = None;
-                let mut quantity__ = None;
-                let mut price__ = None;
-                let mut total_price__ = None;
+                let mut order_id__ = None;
+                let mut person_id__ = None;
+                let mut rule__ = None;
+                let mut late_seconds__ = None;
+                let mut amount__ = None;
+                let mut currency__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
-                        GeneratedField::Id => {
-                            if id__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("id"));
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
                             }
-                            id__ = Some(map_.next_value()?);
+                            order_id__ = Some(map_.next_value()?);
                         }
-                        GeneratedField::ProductId => {
-                            if product_id__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("productId"));
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
                             }
-                            product_id__ = Some(map_.next_value()?);
+                            person_id__ = Some(map_.next_value()?);
                         }
-                        GeneratedField::Quantity => {
-                            if quantity__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("quantity"));
+                        GeneratedField::Rule => {
+                            if rule__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("rule"));
                             }
-                            quantity__ = 
-                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
-                            ;
+                            rule__ = Some(map_.next_value()?);
                         }
-                        GeneratedField::Price => {
-                            if price__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("price"));
+                        GeneratedField::LateSeconds => {
+                            if late_seconds__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("lateSeconds"));
                             }
-                            price__ = 
+                            late_seconds__ = 
                                 Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
                             ;
                         }
-                        GeneratedField::TotalPrice => {
-                            if total_price__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("totalPrice"));
+                        GeneratedField::Amount => {
+                            if amount__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("amount"));
                             }
-                            total_price__ = 
+                            amount__ = 
                                 Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
                             ;
                         }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = Some(map_.next_value()?);
+                        }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
                 }
-                Ok(LineItem {
-                    id: id__.unwrap_or_default(),
-                    product_id: product_id__.unwrap_or_default(),
-                    quantity: quantity__.unwrap_or_default(),
-                    price: price__.unwrap_or_default(),
-                    total_price: total_price__.unwrap_or_default(),
+                Ok(CompensationIssued {
+                    order_id: order_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                    rule: rule__.unwrap_or_default(),
+                    late_seconds: late_seconds__.unwrap_or_default(),
+                    amount: amount__.unwrap_or_default(),
+                    currency: currency__.unwrap_or_default(),
                 })
             }
         }
-        deserializer.deserialize_struct("caspers.messages.v1.LineItem", FIELDS, GeneratedVisitor)
+        deserializer.deserialize_struct("caspers.messages.v1.CompensationIssued", FIELDS, GeneratedVisitor)
     }
 }
-impl serde::Serialize for Order {
+impl serde::Serialize for ConfigChanged {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
@@ -688,47 +852,45 @@ impl serde::Serialize for Order {
     {
         use serde::ser::SerializeStruct;
         let mut len = 0;
-        if !self.id.is_empty() {
+        if !self.setting.is_empty() {
             len += 1;
         }
-        if !self.customer_id.is_empty() {
+        if self.previous.is_some() {
             len += 1;
         }
-        if !self.line_items.is_empty() {
+        if self.value.is_some() {
             len += 1;
         }
-        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.Order", len)?;
-        if !self.id.is_empty() {
-            struct_ser.serialize_field("id", &self.id)?;
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.ConfigChanged", len)?;
+        if !self.setting.is_empty() {
+            struct_ser.serialize_field("setting", &self.setting)?;
         }
-        if !self.customer_id.is_empty() {
-            struct_ser.serialize_field("customer_id", &self.customer_id)?;
+        if let Some(v) = self.previous.as_ref() {
+            struct_ser.serialize_field("previous", v)?;
         }
-        if !self.line_items.is_empty() {
-            struct_ser.serialize_field("line_items", &self.line_items)?;
+        if let Some(v) = self.value.as_ref() {
+            struct_ser.serialize_field("value", v)?;
         }
         struct_ser.end()
     }
 }
-impl<'de> serde::Deserialize<'de> for Order {
+impl<'de> serde::Deserialize<'de> for ConfigChanged {
     #[allow(deprecated)]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         const FIELDS: &[&str] = &[
-            "id",
-            "customer_id",
-            "customerId",
-            "line_items",
-            "lineItems",
+            "setting",
+            "previous",
+            "value",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
-            Id,
-            CustomerId,
-            LineItems,
+            Setting,
+            Previous,
+            Value,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -751,9 +913,9 @@ impl<'de> serde::Deserialize<'de> for Order {
                         E: serde::de::Error,
                     {
                         match value {
-                            "id" => Ok(GeneratedField::Id),
-                            "customerId" | "customer_id" => Ok(GeneratedField::CustomerId),
-                            "lineItems" | "line_items" => Ok(GeneratedField::LineItems),
+                            "setting" => Ok(GeneratedField::Setting),
+                            "previous" => Ok(GeneratedField::Previous),
+                            "value" => Ok(GeneratedField::Value),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -763,95 +925,6005 @@ impl<'de> serde::Deserialize<'de> for Order {
         }
         struct GeneratedVisitor;
         impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
-            type Value = Order;
+            type Value = ConfigChanged;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("struct caspers.messages.v1.Order")
+                formatter.write_str("struct caspers.messages.v1.ConfigChanged")
             }
 
-            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Order, V::Error>
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<ConfigChanged, V::Error>
                 where
                     V: serde::de::MapAccess<'de>,
             {
-                let mut id__ = None;
-                let mut customer_id__ = None;
-                let mut line_items__ = None;
+                let mut setting__ = None;
+                let mut previous__ = None;
+                let mut value__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
-                        GeneratedField::Id => {
-                            if id__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("id"));
+                        GeneratedField::Setting => {
+                            if setting__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("setting"));
                             }
-                            id__ = Some(map_.next_value()?);
+                            setting__ = Some(map_.next_value()?);
                         }
-                        GeneratedField::CustomerId => {
-                            if customer_id__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("customerId"));
+                        GeneratedField::Previous => {
+                            if previous__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("previous"));
                             }
-                            customer_id__ = Some(map_.next_value()?);
+                            previous__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
                         }
-                        GeneratedField::LineItems => {
-                            if line_items__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("lineItems"));
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
                             }
-                            line_items__ = Some(map_.next_value()?);
+                            value__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
                         }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
                 }
-                Ok(Order {
-                    id: id__.unwrap_or_default(),
-                    customer_id: customer_id__.unwrap_or_default(),
-                    line_items: line_items__.unwrap_or_default(),
+                Ok(ConfigChanged {
+                    setting: setting__.unwrap_or_default(),
+                    previous: previous__,
+                    value: value__,
                 })
             }
         }
-        deserializer.deserialize_struct("caspers.messages.v1.Order", FIELDS, GeneratedVisitor)
+        deserializer.deserialize_struct("caspers.messages.v1.ConfigChanged", FIELDS, GeneratedVisitor)
     }
 }
-impl serde::Serialize for OrderStatus {
+impl serde::Serialize for CourierActivity {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        use serde::ser::SerializeStruct;
-        let mut len = 0;
-        if !self.id.is_empty() {
-            len += 1;
-        }
-        if self.status != 0 {
+        let variant = match self {
+            Self::Unspecified => "COURIER_ACTIVITY_UNSPECIFIED",
+            Self::OfferReceived => "COURIER_ACTIVITY_OFFER_RECEIVED",
+            Self::OfferAccepted => "COURIER_ACTIVITY_OFFER_ACCEPTED",
+            Self::OfferDeclined => "COURIER_ACTIVITY_OFFER_DECLINED",
+            Self::PickedUp => "COURIER_ACTIVITY_PICKED_UP",
+            Self::ArrivedAtCustomer => "COURIER_ACTIVITY_ARRIVED_AT_CUSTOMER",
+            Self::Delivered => "COURIER_ACTIVITY_DELIVERED",
+            Self::OfferExpired => "COURIER_ACTIVITY_OFFER_EXPIRED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for CourierActivity {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "COURIER_ACTIVITY_UNSPECIFIED",
+            "COURIER_ACTIVITY_OFFER_RECEIVED",
+            "COURIER_ACTIVITY_OFFER_ACCEPTED",
+            "COURIER_ACTIVITY_OFFER_DECLINED",
+            "COURIER_ACTIVITY_PICKED_UP",
+            "COURIER_ACTIVITY_ARRIVED_AT_CUSTOMER",
+            "COURIER_ACTIVITY_DELIVERED",
+            "COURIER_ACTIVITY_OFFER_EXPIRED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CourierActivity;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "COURIER_ACTIVITY_UNSPECIFIED" => Ok(CourierActivity::Unspecified),
+                    "COURIER_ACTIVITY_OFFER_RECEIVED" => Ok(CourierActivity::OfferReceived),
+                    "COURIER_ACTIVITY_OFFER_ACCEPTED" => Ok(CourierActivity::OfferAccepted),
+                    "COURIER_ACTIVITY_OFFER_DECLINED" => Ok(CourierActivity::OfferDeclined),
+                    "COURIER_ACTIVITY_PICKED_UP" => Ok(CourierActivity::PickedUp),
+                    "COURIER_ACTIVITY_ARRIVED_AT_CUSTOMER" => Ok(CourierActivity::ArrivedAtCustomer),
+                    "COURIER_ACTIVITY_DELIVERED" => Ok(CourierActivity::Delivered),
+                    "COURIER_ACTIVITY_OFFER_EXPIRED" => Ok(CourierActivity::OfferExpired),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CourierBreak {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.courier_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if self.reason != 0 {
+            len += 1;
+        }
+        if self.activity != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.CourierBreak", len)?;
+        if !self.courier_id.is_empty() {
+            struct_ser.serialize_field("courier_id", &self.courier_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if self.reason != 0 {
+            let v = BreakReason::try_from(self.reason)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.reason)))?;
+            struct_ser.serialize_field("reason", &v)?;
+        }
+        if self.activity != 0 {
+            let v = BreakActivity::try_from(self.activity)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.activity)))?;
+            struct_ser.serialize_field("activity", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CourierBreak {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "courier_id",
+            "courierId",
+            "site_id",
+            "siteId",
+            "reason",
+            "activity",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            CourierId,
+            SiteId,
+            Reason,
+            Activity,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "courierId" | "courier_id" => Ok(GeneratedField::CourierId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "reason" => Ok(GeneratedField::Reason),
+                            "activity" => Ok(GeneratedField::Activity),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CourierBreak;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.CourierBreak")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CourierBreak, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut courier_id__ = None;
+                let mut site_id__ = None;
+                let mut reason__ = None;
+                let mut activity__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::CourierId => {
+                            if courier_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("courierId"));
+                            }
+                            courier_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Reason => {
+                            if reason__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("reason"));
+                            }
+                            reason__ = Some(map_.next_value::<BreakReason>()? as i32);
+                        }
+                        GeneratedField::Activity => {
+                            if activity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("activity"));
+                            }
+                            activity__ = Some(map_.next_value::<BreakActivity>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(CourierBreak {
+                    courier_id: courier_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    reason: reason__.unwrap_or_default(),
+                    activity: activity__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.CourierBreak", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CourierOffer {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.distance_m != 0. {
+            len += 1;
+        }
+        if self.earnings != 0. {
+            len += 1;
+        }
+        if self.acceptance_probability != 0. {
+            len += 1;
+        }
+        if self.tip != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.CourierOffer", len)?;
+        if self.distance_m != 0. {
+            struct_ser.serialize_field("distance_m", &self.distance_m)?;
+        }
+        if self.earnings != 0. {
+            struct_ser.serialize_field("earnings", &self.earnings)?;
+        }
+        if self.acceptance_probability != 0. {
+            struct_ser.serialize_field("acceptance_probability", &self.acceptance_probability)?;
+        }
+        if self.tip != 0. {
+            struct_ser.serialize_field("tip", &self.tip)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CourierOffer {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "distance_m",
+            "distanceM",
+            "earnings",
+            "acceptance_probability",
+            "acceptanceProbability",
+            "tip",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            DistanceM,
+            Earnings,
+            AcceptanceProbability,
+            Tip,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "distanceM" | "distance_m" => Ok(GeneratedField::DistanceM),
+                            "earnings" => Ok(GeneratedField::Earnings),
+                            "acceptanceProbability" | "acceptance_probability" => Ok(GeneratedField::AcceptanceProbability),
+                            "tip" => Ok(GeneratedField::Tip),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CourierOffer;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.CourierOffer")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CourierOffer, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut distance_m__ = None;
+                let mut earnings__ = None;
+                let mut acceptance_probability__ = None;
+                let mut tip__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::DistanceM => {
+                            if distance_m__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("distanceM"));
+                            }
+                            distance_m__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Earnings => {
+                            if earnings__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("earnings"));
+                            }
+                            earnings__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AcceptanceProbability => {
+                            if acceptance_probability__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("acceptanceProbability"));
+                            }
+                            acceptance_probability__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Tip => {
+                            if tip__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tip"));
+                            }
+                            tip__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(CourierOffer {
+                    distance_m: distance_m__.unwrap_or_default(),
+                    earnings: earnings__.unwrap_or_default(),
+                    acceptance_probability: acceptance_probability__.unwrap_or_default(),
+                    tip: tip__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.CourierOffer", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CourierUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.courier_id.is_empty() {
+            len += 1;
+        }
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if self.activity != 0 {
+            len += 1;
+        }
+        if self.offer.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.CourierUpdated", len)?;
+        if !self.courier_id.is_empty() {
+            struct_ser.serialize_field("courier_id", &self.courier_id)?;
+        }
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if self.activity != 0 {
+            let v = CourierActivity::try_from(self.activity)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.activity)))?;
+            struct_ser.serialize_field("activity", &v)?;
+        }
+        if let Some(v) = self.offer.as_ref() {
+            struct_ser.serialize_field("offer", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CourierUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "courier_id",
+            "courierId",
+            "order_id",
+            "orderId",
+            "activity",
+            "offer",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            CourierId,
+            OrderId,
+            Activity,
+            Offer,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "courierId" | "courier_id" => Ok(GeneratedField::CourierId),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "activity" => Ok(GeneratedField::Activity),
+                            "offer" => Ok(GeneratedField::Offer),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CourierUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.CourierUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CourierUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut courier_id__ = None;
+                let mut order_id__ = None;
+                let mut activity__ = None;
+                let mut offer__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::CourierId => {
+                            if courier_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("courierId"));
+                            }
+                            courier_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Activity => {
+                            if activity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("activity"));
+                            }
+                            activity__ = Some(map_.next_value::<CourierActivity>()? as i32);
+                        }
+                        GeneratedField::Offer => {
+                            if offer__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("offer"));
+                            }
+                            offer__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(CourierUpdated {
+                    courier_id: courier_id__.unwrap_or_default(),
+                    order_id: order_id__.unwrap_or_default(),
+                    activity: activity__.unwrap_or_default(),
+                    offer: offer__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.CourierUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Cuisine {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "CUISINE_UNSPECIFIED",
+            Self::Asian => "CUISINE_ASIAN",
+            Self::FastFood => "CUISINE_FAST_FOOD",
+            Self::Mexican => "CUISINE_MEXICAN",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for Cuisine {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "CUISINE_UNSPECIFIED",
+            "CUISINE_ASIAN",
+            "CUISINE_FAST_FOOD",
+            "CUISINE_MEXICAN",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Cuisine;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "CUISINE_UNSPECIFIED" => Ok(Cuisine::Unspecified),
+                    "CUISINE_ASIAN" => Ok(Cuisine::Asian),
+                    "CUISINE_FAST_FOOD" => Ok(Cuisine::FastFood),
+                    "CUISINE_MEXICAN" => Ok(Cuisine::Mexican),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for DeliveryFootprint {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if self.mode != 0 {
+            len += 1;
+        }
+        if self.distance_m != 0. {
+            len += 1;
+        }
+        if self.co2_g != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.DeliveryFootprint", len)?;
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if self.mode != 0 {
+            let v = DeliveryMode::try_from(self.mode)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.mode)))?;
+            struct_ser.serialize_field("mode", &v)?;
+        }
+        if self.distance_m != 0. {
+            struct_ser.serialize_field("distance_m", &self.distance_m)?;
+        }
+        if self.co2_g != 0. {
+            struct_ser.serialize_field("co2_g", &self.co2_g)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for DeliveryFootprint {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "order_id",
+            "orderId",
+            "site_id",
+            "siteId",
+            "mode",
+            "distance_m",
+            "distanceM",
+            "co2_g",
+            "co2G",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            OrderId,
+            SiteId,
+            Mode,
+            DistanceM,
+            Co2G,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "mode" => Ok(GeneratedField::Mode),
+                            "distanceM" | "distance_m" => Ok(GeneratedField::DistanceM),
+                            "co2G" | "co2_g" => Ok(GeneratedField::Co2G),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = DeliveryFootprint;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.DeliveryFootprint")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<DeliveryFootprint, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut order_id__ = None;
+                let mut site_id__ = None;
+                let mut mode__ = None;
+                let mut distance_m__ = None;
+                let mut co2_g__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Mode => {
+                            if mode__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("mode"));
+                            }
+                            mode__ = Some(map_.next_value::<DeliveryMode>()? as i32);
+                        }
+                        GeneratedField::DistanceM => {
+                            if distance_m__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("distanceM"));
+                            }
+                            distance_m__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Co2G => {
+                            if co2_g__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("co2G"));
+                            }
+                            co2_g__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(DeliveryFootprint {
+                    order_id: order_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    mode: mode__.unwrap_or_default(),
+                    distance_m: distance_m__.unwrap_or_default(),
+                    co2_g: co2_g__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.DeliveryFootprint", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for DeliveryMode {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "DELIVERY_MODE_UNSPECIFIED",
+            Self::Foot => "DELIVERY_MODE_FOOT",
+            Self::Bicycle => "DELIVERY_MODE_BICYCLE",
+            Self::Car => "DELIVERY_MODE_CAR",
+            Self::Robot => "DELIVERY_MODE_ROBOT",
+            Self::Drone => "DELIVERY_MODE_DRONE",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for DeliveryMode {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "DELIVERY_MODE_UNSPECIFIED",
+            "DELIVERY_MODE_FOOT",
+            "DELIVERY_MODE_BICYCLE",
+            "DELIVERY_MODE_CAR",
+            "DELIVERY_MODE_ROBOT",
+            "DELIVERY_MODE_DRONE",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = DeliveryMode;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "DELIVERY_MODE_UNSPECIFIED" => Ok(DeliveryMode::Unspecified),
+                    "DELIVERY_MODE_FOOT" => Ok(DeliveryMode::Foot),
+                    "DELIVERY_MODE_BICYCLE" => Ok(DeliveryMode::Bicycle),
+                    "DELIVERY_MODE_CAR" => Ok(DeliveryMode::Car),
+                    "DELIVERY_MODE_ROBOT" => Ok(DeliveryMode::Robot),
+                    "DELIVERY_MODE_DRONE" => Ok(DeliveryMode::Drone),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for FunnelStage {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "FUNNEL_STAGE_UNSPECIFIED",
+            Self::Browse => "FUNNEL_STAGE_BROWSE",
+            Self::Cart => "FUNNEL_STAGE_CART",
+            Self::Checkout => "FUNNEL_STAGE_CHECKOUT",
+            Self::Abandoned => "FUNNEL_STAGE_ABANDONED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for FunnelStage {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "FUNNEL_STAGE_UNSPECIFIED",
+            "FUNNEL_STAGE_BROWSE",
+            "FUNNEL_STAGE_CART",
+            "FUNNEL_STAGE_CHECKOUT",
+            "FUNNEL_STAGE_ABANDONED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = FunnelStage;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "FUNNEL_STAGE_UNSPECIFIED" => Ok(FunnelStage::Unspecified),
+                    "FUNNEL_STAGE_BROWSE" => Ok(FunnelStage::Browse),
+                    "FUNNEL_STAGE_CART" => Ok(FunnelStage::Cart),
+                    "FUNNEL_STAGE_CHECKOUT" => Ok(FunnelStage::Checkout),
+                    "FUNNEL_STAGE_ABANDONED" => Ok(FunnelStage::Abandoned),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for IngredientWasted {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.ingredient.is_empty() {
+            len += 1;
+        }
+        if !self.unit.is_empty() {
+            len += 1;
+        }
+        if self.reason != 0 {
+            len += 1;
+        }
+        if self.quantity != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.IngredientWasted", len)?;
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.ingredient.is_empty() {
+            struct_ser.serialize_field("ingredient", &self.ingredient)?;
+        }
+        if !self.unit.is_empty() {
+            struct_ser.serialize_field("unit", &self.unit)?;
+        }
+        if self.reason != 0 {
+            let v = WasteReason::try_from(self.reason)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.reason)))?;
+            struct_ser.serialize_field("reason", &v)?;
+        }
+        if self.quantity != 0. {
+            struct_ser.serialize_field("quantity", &self.quantity)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for IngredientWasted {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "site_id",
+            "siteId",
+            "ingredient",
+            "unit",
+            "reason",
+            "quantity",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SiteId,
+            Ingredient,
+            Unit,
+            Reason,
+            Quantity,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "ingredient" => Ok(GeneratedField::Ingredient),
+                            "unit" => Ok(GeneratedField::Unit),
+                            "reason" => Ok(GeneratedField::Reason),
+                            "quantity" => Ok(GeneratedField::Quantity),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = IngredientWasted;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.IngredientWasted")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<IngredientWasted, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut site_id__ = None;
+                let mut ingredient__ = None;
+                let mut unit__ = None;
+                let mut reason__ = None;
+                let mut quantity__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Ingredient => {
+                            if ingredient__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("ingredient"));
+                            }
+                            ingredient__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Unit => {
+                            if unit__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("unit"));
+                            }
+                            unit__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Reason => {
+                            if reason__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("reason"));
+                            }
+                            reason__ = Some(map_.next_value::<WasteReason>()? as i32);
+                        }
+                        GeneratedField::Quantity => {
+                            if quantity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("quantity"));
+                            }
+                            quantity__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(IngredientWasted {
+                    site_id: site_id__.unwrap_or_default(),
+                    ingredient: ingredient__.unwrap_or_default(),
+                    unit: unit__.unwrap_or_default(),
+                    reason: reason__.unwrap_or_default(),
+                    quantity: quantity__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.IngredientWasted", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for JourneyProgress {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.distance_m != 0 {
+            len += 1;
+        }
+        if self.progress_percentage != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.JourneyProgress", len)?;
+        if self.distance_m != 0 {
+            #[allow(clippy::needless_borrow)]
+            #[allow(clippy::needless_borrows_for_generic_args)]
+            struct_ser.serialize_field("distance_m", ToString::to_string(&self.distance_m).as_str())?;
+        }
+        if self.progress_percentage != 0. {
+            struct_ser.serialize_field("progress_percentage", &self.progress_percentage)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for JourneyProgress {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "distance_m",
+            "distanceM",
+            "progress_percentage",
+            "progressPercentage",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            DistanceM,
+            ProgressPercentage,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "distanceM" | "distance_m" => Ok(GeneratedField::DistanceM),
+                            "progressPercentage" | "progress_percentage" => Ok(GeneratedField::ProgressPercentage),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = JourneyProgress;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.JourneyProgress")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<JourneyProgress, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut distance_m__ = None;
+                let mut progress_percentage__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::DistanceM => {
+                            if distance_m__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("distanceM"));
+                            }
+                            distance_m__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ProgressPercentage => {
+                            if progress_percentage__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("progressPercentage"));
+                            }
+                            progress_percentage__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(JourneyProgress {
+                    distance_m: distance_m__.unwrap_or_default(),
+                    progress_percentage: progress_percentage__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.JourneyProgress", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for LifecycleStage {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "LIFECYCLE_STAGE_UNSPECIFIED",
+            Self::SignedUp => "LIFECYCLE_STAGE_SIGNED_UP",
+            Self::FirstOrder => "LIFECYCLE_STAGE_FIRST_ORDER",
+            Self::Churned => "LIFECYCLE_STAGE_CHURNED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for LifecycleStage {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "LIFECYCLE_STAGE_UNSPECIFIED",
+            "LIFECYCLE_STAGE_SIGNED_UP",
+            "LIFECYCLE_STAGE_FIRST_ORDER",
+            "LIFECYCLE_STAGE_CHURNED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = LifecycleStage;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "LIFECYCLE_STAGE_UNSPECIFIED" => Ok(LifecycleStage::Unspecified),
+                    "LIFECYCLE_STAGE_SIGNED_UP" => Ok(LifecycleStage::SignedUp),
+                    "LIFECYCLE_STAGE_FIRST_ORDER" => Ok(LifecycleStage::FirstOrder),
+                    "LIFECYCLE_STAGE_CHURNED" => Ok(LifecycleStage::Churned),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for LineItem {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.id.is_empty() {
+            len += 1;
+        }
+        if !self.product_id.is_empty() {
+            len += 1;
+        }
+        if self.quantity != 0 {
+            len += 1;
+        }
+        if self.price != 0. {
+            len += 1;
+        }
+        if self.total_price != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.LineItem", len)?;
+        if !self.id.is_empty() {
+            struct_ser.serialize_field("id", &self.id)?;
+        }
+        if !self.product_id.is_empty() {
+            struct_ser.serialize_field("product_id", &self.product_id)?;
+        }
+        if self.quantity != 0 {
+            struct_ser.serialize_field("quantity", &self.quantity)?;
+        }
+        if self.price != 0. {
+            struct_ser.serialize_field("price", &self.price)?;
+        }
+        if self.total_price != 0. {
+            struct_ser.serialize_field("total_price", &self.total_price)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for LineItem {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "product_id",
+            "productId",
+            "quantity",
+            "price",
+            "total_price",
+            "totalPrice",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            ProductId,
+            Quantity,
+            Price,
+            TotalPrice,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "productId" | "product_id" => Ok(GeneratedField::ProductId),
+                            "quantity" => Ok(GeneratedField::Quantity),
+                            "price" => Ok(GeneratedField::Price),
+                            "totalPrice" | "total_price" => Ok(GeneratedField::TotalPrice),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = LineItem;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.LineItem")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<LineItem, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut product_id__ = None;
+                let mut quantity__ = None;
+                let mut price__ = None;
+                let mut total_price__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::ProductId => {
+                            if product_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("productId"));
+                            }
+                            product_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Quantity => {
+                            if quantity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("quantity"));
+                            }
+                            quantity__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Price => {
+                            if price__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("price"));
+                            }
+                            price__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::TotalPrice => {
+                            if total_price__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("totalPrice"));
+                            }
+                            total_price__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(LineItem {
+                    id: id__.unwrap_or_default(),
+                    product_id: product_id__.unwrap_or_default(),
+                    quantity: quantity__.unwrap_or_default(),
+                    price: price__.unwrap_or_default(),
+                    total_price: total_price__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.LineItem", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Location {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.latitude != 0. {
+            len += 1;
+        }
+        if self.longitude != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.Location", len)?;
+        if self.latitude != 0. {
+            struct_ser.serialize_field("latitude", &self.latitude)?;
+        }
+        if self.longitude != 0. {
+            struct_ser.serialize_field("longitude", &self.longitude)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Location {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "latitude",
+            "longitude",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Latitude,
+            Longitude,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "latitude" => Ok(GeneratedField::Latitude),
+                            "longitude" => Ok(GeneratedField::Longitude),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Location;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.Location")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Location, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut latitude__ = None;
+                let mut longitude__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Latitude => {
+                            if latitude__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("latitude"));
+                            }
+                            latitude__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Longitude => {
+                            if longitude__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("longitude"));
+                            }
+                            longitude__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(Location {
+                    latitude: latitude__.unwrap_or_default(),
+                    longitude: longitude__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.Location", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for MembershipBilled {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if self.amount != 0. {
+            len += 1;
+        }
+        if !self.currency.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.MembershipBilled", len)?;
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if self.amount != 0. {
+            struct_ser.serialize_field("amount", &self.amount)?;
+        }
+        if !self.currency.is_empty() {
+            struct_ser.serialize_field("currency", &self.currency)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for MembershipBilled {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "person_id",
+            "personId",
+            "amount",
+            "currency",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            PersonId,
+            Amount,
+            Currency,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "amount" => Ok(GeneratedField::Amount),
+                            "currency" => Ok(GeneratedField::Currency),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = MembershipBilled;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.MembershipBilled")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<MembershipBilled, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut person_id__ = None;
+                let mut amount__ = None;
+                let mut currency__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Amount => {
+                            if amount__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("amount"));
+                            }
+                            amount__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(MembershipBilled {
+                    person_id: person_id__.unwrap_or_default(),
+                    amount: amount__.unwrap_or_default(),
+                    currency: currency__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.MembershipBilled", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NotificationChannel {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "NOTIFICATION_CHANNEL_UNSPECIFIED",
+            Self::Push => "NOTIFICATION_CHANNEL_PUSH",
+            Self::Sms => "NOTIFICATION_CHANNEL_SMS",
+            Self::Email => "NOTIFICATION_CHANNEL_EMAIL",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for NotificationChannel {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "NOTIFICATION_CHANNEL_UNSPECIFIED",
+            "NOTIFICATION_CHANNEL_PUSH",
+            "NOTIFICATION_CHANNEL_SMS",
+            "NOTIFICATION_CHANNEL_EMAIL",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NotificationChannel;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "NOTIFICATION_CHANNEL_UNSPECIFIED" => Ok(NotificationChannel::Unspecified),
+                    "NOTIFICATION_CHANNEL_PUSH" => Ok(NotificationChannel::Push),
+                    "NOTIFICATION_CHANNEL_SMS" => Ok(NotificationChannel::Sms),
+                    "NOTIFICATION_CHANNEL_EMAIL" => Ok(NotificationChannel::Email),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NotificationStatus {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "NOTIFICATION_STATUS_UNSPECIFIED",
+            Self::Sent => "NOTIFICATION_STATUS_SENT",
+            Self::Delivered => "NOTIFICATION_STATUS_DELIVERED",
+            Self::Failed => "NOTIFICATION_STATUS_FAILED",
+            Self::Opened => "NOTIFICATION_STATUS_OPENED",
+            Self::Clicked => "NOTIFICATION_STATUS_CLICKED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for NotificationStatus {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "NOTIFICATION_STATUS_UNSPECIFIED",
+            "NOTIFICATION_STATUS_SENT",
+            "NOTIFICATION_STATUS_DELIVERED",
+            "NOTIFICATION_STATUS_FAILED",
+            "NOTIFICATION_STATUS_OPENED",
+            "NOTIFICATION_STATUS_CLICKED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NotificationStatus;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "NOTIFICATION_STATUS_UNSPECIFIED" => Ok(NotificationStatus::Unspecified),
+                    "NOTIFICATION_STATUS_SENT" => Ok(NotificationStatus::Sent),
+                    "NOTIFICATION_STATUS_DELIVERED" => Ok(NotificationStatus::Delivered),
+                    "NOTIFICATION_STATUS_FAILED" => Ok(NotificationStatus::Failed),
+                    "NOTIFICATION_STATUS_OPENED" => Ok(NotificationStatus::Opened),
+                    "NOTIFICATION_STATUS_CLICKED" => Ok(NotificationStatus::Clicked),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NotificationTrigger {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "NOTIFICATION_TRIGGER_UNSPECIFIED",
+            Self::OrderConfirmed => "NOTIFICATION_TRIGGER_ORDER_CONFIRMED",
+            Self::CourierNearby => "NOTIFICATION_TRIGGER_COURIER_NEARBY",
+            Self::OrderDelivered => "NOTIFICATION_TRIGGER_ORDER_DELIVERED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for NotificationTrigger {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "NOTIFICATION_TRIGGER_UNSPECIFIED",
+            "NOTIFICATION_TRIGGER_ORDER_CONFIRMED",
+            "NOTIFICATION_TRIGGER_COURIER_NEARBY",
+            "NOTIFICATION_TRIGGER_ORDER_DELIVERED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NotificationTrigger;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "NOTIFICATION_TRIGGER_UNSPECIFIED" => Ok(NotificationTrigger::Unspecified),
+                    "NOTIFICATION_TRIGGER_ORDER_CONFIRMED" => Ok(NotificationTrigger::OrderConfirmed),
+                    "NOTIFICATION_TRIGGER_COURIER_NEARBY" => Ok(NotificationTrigger::CourierNearby),
+                    "NOTIFICATION_TRIGGER_ORDER_DELIVERED" => Ok(NotificationTrigger::OrderDelivered),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NotificationUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.notification_id.is_empty() {
+            len += 1;
+        }
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if self.channel != 0 {
+            len += 1;
+        }
+        if self.trigger != 0 {
+            len += 1;
+        }
+        if self.status != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.NotificationUpdated", len)?;
+        if !self.notification_id.is_empty() {
+            struct_ser.serialize_field("notification_id", &self.notification_id)?;
+        }
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if self.channel != 0 {
+            let v = NotificationChannel::try_from(self.channel)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.channel)))?;
+            struct_ser.serialize_field("channel", &v)?;
+        }
+        if self.trigger != 0 {
+            let v = NotificationTrigger::try_from(self.trigger)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.trigger)))?;
+            struct_ser.serialize_field("trigger", &v)?;
+        }
+        if self.status != 0 {
+            let v = NotificationStatus::try_from(self.status)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
+            struct_ser.serialize_field("status", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for NotificationUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "notification_id",
+            "notificationId",
+            "person_id",
+            "personId",
+            "order_id",
+            "orderId",
+            "channel",
+            "trigger",
+            "status",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            NotificationId,
+            PersonId,
+            OrderId,
+            Channel,
+            Trigger,
+            Status,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "notificationId" | "notification_id" => Ok(GeneratedField::NotificationId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "channel" => Ok(GeneratedField::Channel),
+                            "trigger" => Ok(GeneratedField::Trigger),
+                            "status" => Ok(GeneratedField::Status),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NotificationUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.NotificationUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<NotificationUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut notification_id__ = None;
+                let mut person_id__ = None;
+                let mut order_id__ = None;
+                let mut channel__ = None;
+                let mut trigger__ = None;
+                let mut status__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::NotificationId => {
+                            if notification_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("notificationId"));
+                            }
+                            notification_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Channel => {
+                            if channel__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("channel"));
+                            }
+                            channel__ = Some(map_.next_value::<NotificationChannel>()? as i32);
+                        }
+                        GeneratedField::Trigger => {
+                            if trigger__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("trigger"));
+                            }
+                            trigger__ = Some(map_.next_value::<NotificationTrigger>()? as i32);
+                        }
+                        GeneratedField::Status => {
+                            if status__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("status"));
+                            }
+                            status__ = Some(map_.next_value::<NotificationStatus>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(NotificationUpdated {
+                    notification_id: notification_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                    order_id: order_id__.unwrap_or_default(),
+                    channel: channel__.unwrap_or_default(),
+                    trigger: trigger__.unwrap_or_default(),
+                    status: status__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.NotificationUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ObjectChange {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "OBJECT_CHANGE_UNSPECIFIED",
+            Self::Created => "OBJECT_CHANGE_CREATED",
+            Self::Updated => "OBJECT_CHANGE_UPDATED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for ObjectChange {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "OBJECT_CHANGE_UNSPECIFIED",
+            "OBJECT_CHANGE_CREATED",
+            "OBJECT_CHANGE_UPDATED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ObjectChange;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "OBJECT_CHANGE_UNSPECIFIED" => Ok(ObjectChange::Unspecified),
+                    "OBJECT_CHANGE_CREATED" => Ok(ObjectChange::Created),
+                    "OBJECT_CHANGE_UPDATED" => Ok(ObjectChange::Updated),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ObjectChanged {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.object_id.is_empty() {
+            len += 1;
+        }
+        if !self.label.is_empty() {
+            len += 1;
+        }
+        if self.uri_ref.is_some() {
+            len += 1;
+        }
+        if self.change != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.ObjectChanged", len)?;
+        if !self.object_id.is_empty() {
+            struct_ser.serialize_field("object_id", &self.object_id)?;
+        }
+        if !self.label.is_empty() {
+            struct_ser.serialize_field("label", &self.label)?;
+        }
+        if let Some(v) = self.uri_ref.as_ref() {
+            struct_ser.serialize_field("uri_ref", v)?;
+        }
+        if self.change != 0 {
+            let v = ObjectChange::try_from(self.change)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.change)))?;
+            struct_ser.serialize_field("change", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ObjectChanged {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "object_id",
+            "objectId",
+            "label",
+            "uri_ref",
+            "uriRef",
+            "change",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ObjectId,
+            Label,
+            UriRef,
+            Change,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "objectId" | "object_id" => Ok(GeneratedField::ObjectId),
+                            "label" => Ok(GeneratedField::Label),
+                            "uriRef" | "uri_ref" => Ok(GeneratedField::UriRef),
+                            "change" => Ok(GeneratedField::Change),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ObjectChanged;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.ObjectChanged")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<ObjectChanged, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut object_id__ = None;
+                let mut label__ = None;
+                let mut uri_ref__ = None;
+                let mut change__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::ObjectId => {
+                            if object_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("objectId"));
+                            }
+                            object_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Label => {
+                            if label__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("label"));
+                            }
+                            label__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::UriRef => {
+                            if uri_ref__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("uriRef"));
+                            }
+                            uri_ref__ = map_.next_value()?;
+                        }
+                        GeneratedField::Change => {
+                            if change__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("change"));
+                            }
+                            change__ = Some(map_.next_value::<ObjectChange>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(ObjectChanged {
+                    object_id: object_id__.unwrap_or_default(),
+                    label: label__.unwrap_or_default(),
+                    uri_ref: uri_ref__,
+                    change: change__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.ObjectChanged", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Order {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.id.is_empty() {
+            len += 1;
+        }
+        if !self.customer_id.is_empty() {
+            len += 1;
+        }
+        if !self.line_items.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.Order", len)?;
+        if !self.id.is_empty() {
+            struct_ser.serialize_field("id", &self.id)?;
+        }
+        if !self.customer_id.is_empty() {
+            struct_ser.serialize_field("customer_id", &self.customer_id)?;
+        }
+        if !self.line_items.is_empty() {
+            struct_ser.serialize_field("line_items", &self.line_items)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Order {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "customer_id",
+            "customerId",
+            "line_items",
+            "lineItems",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            CustomerId,
+            LineItems,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "customerId" | "customer_id" => Ok(GeneratedField::CustomerId),
+                            "lineItems" | "line_items" => Ok(GeneratedField::LineItems),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Order;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.Order")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Order, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut customer_id__ = None;
+                let mut line_items__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::CustomerId => {
+                            if customer_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("customerId"));
+                            }
+                            customer_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::LineItems => {
+                            if line_items__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("lineItems"));
+                            }
+                            line_items__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(Order {
+                    id: id__.unwrap_or_default(),
+                    customer_id: customer_id__.unwrap_or_default(),
+                    line_items: line_items__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.Order", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderChannel {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "ORDER_CHANNEL_UNSPECIFIED",
+            Self::App => "ORDER_CHANNEL_APP",
+            Self::Web => "ORDER_CHANNEL_WEB",
+            Self::Phone => "ORDER_CHANNEL_PHONE",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderChannel {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "ORDER_CHANNEL_UNSPECIFIED",
+            "ORDER_CHANNEL_APP",
+            "ORDER_CHANNEL_WEB",
+            "ORDER_CHANNEL_PHONE",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderChannel;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "ORDER_CHANNEL_UNSPECIFIED" => Ok(OrderChannel::Unspecified),
+                    "ORDER_CHANNEL_APP" => Ok(OrderChannel::App),
+                    "ORDER_CHANNEL_WEB" => Ok(OrderChannel::Web),
+                    "ORDER_CHANNEL_PHONE" => Ok(OrderChannel::Phone),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderCreated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if !self.items.is_empty() {
+            len += 1;
+        }
+        if self.destination.is_some() {
+            len += 1;
+        }
+        if self.total != 0. {
+            len += 1;
+        }
+        if self.channel != 0 {
+            len += 1;
+        }
+        if self.promised_at.is_some() {
+            len += 1;
+        }
+        if !self.campaigns.is_empty() {
+            len += 1;
+        }
+        if self.tip.is_some() {
+            len += 1;
+        }
+        if !self.currency.is_empty() {
+            len += 1;
+        }
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if self.priority != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderCreated", len)?;
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if !self.items.is_empty() {
+            struct_ser.serialize_field("items", &self.items)?;
+        }
+        if let Some(v) = self.destination.as_ref() {
+            struct_ser.serialize_field("destination", v)?;
+        }
+        if self.total != 0. {
+            struct_ser.serialize_field("total", &self.total)?;
+        }
+        if self.channel != 0 {
+            let v = OrderChannel::try_from(self.channel)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.channel)))?;
+            struct_ser.serialize_field("channel", &v)?;
+        }
+        if let Some(v) = self.promised_at.as_ref() {
+            struct_ser.serialize_field("promised_at", v)?;
+        }
+        if !self.campaigns.is_empty() {
+            struct_ser.serialize_field("campaigns", &self.campaigns)?;
+        }
+        if let Some(v) = self.tip.as_ref() {
+            struct_ser.serialize_field("tip", v)?;
+        }
+        if !self.currency.is_empty() {
+            struct_ser.serialize_field("currency", &self.currency)?;
+        }
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if self.priority != 0 {
+            let v = PriorityTier::try_from(self.priority)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.priority)))?;
+            struct_ser.serialize_field("priority", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderCreated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "site_id",
+            "siteId",
+            "person_id",
+            "personId",
+            "items",
+            "destination",
+            "total",
+            "channel",
+            "promised_at",
+            "promisedAt",
+            "campaigns",
+            "tip",
+            "currency",
+            "order_id",
+            "orderId",
+            "priority",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SiteId,
+            PersonId,
+            Items,
+            Destination,
+            Total,
+            Channel,
+            PromisedAt,
+            Campaigns,
+            Tip,
+            Currency,
+            OrderId,
+            Priority,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "items" => Ok(GeneratedField::Items),
+                            "destination" => Ok(GeneratedField::Destination),
+                            "total" => Ok(GeneratedField::Total),
+                            "channel" => Ok(GeneratedField::Channel),
+                            "promisedAt" | "promised_at" => Ok(GeneratedField::PromisedAt),
+                            "campaigns" => Ok(GeneratedField::Campaigns),
+                            "tip" => Ok(GeneratedField::Tip),
+                            "currency" => Ok(GeneratedField::Currency),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "priority" => Ok(GeneratedField::Priority),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderCreated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.OrderCreated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderCreated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut site_id__ = None;
+                let mut person_id__ = None;
+                let mut items__ = None;
+                let mut destination__ = None;
+                let mut total__ = None;
+                let mut channel__ = None;
+                let mut promised_at__ = None;
+                let mut campaigns__ = None;
+                let mut tip__ = None;
+                let mut currency__ = None;
+                let mut order_id__ = None;
+                let mut priority__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Items => {
+                            if items__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("items"));
+                            }
+                            items__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Destination => {
+                            if destination__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("destination"));
+                            }
+                            destination__ = map_.next_value()?;
+                        }
+                        GeneratedField::Total => {
+                            if total__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("total"));
+                            }
+                            total__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Channel => {
+                            if channel__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("channel"));
+                            }
+                            channel__ = Some(map_.next_value::<OrderChannel>()? as i32);
+                        }
+                        GeneratedField::PromisedAt => {
+                            if promised_at__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("promisedAt"));
+                            }
+                            promised_at__ = map_.next_value()?;
+                        }
+                        GeneratedField::Campaigns => {
+                            if campaigns__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("campaigns"));
+                            }
+                            campaigns__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Tip => {
+                            if tip__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tip"));
+                            }
+                            tip__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::Currency => {
+                            if currency__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currency"));
+                            }
+                            currency__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Priority => {
+                            if priority__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("priority"));
+                            }
+                            priority__ = Some(map_.next_value::<PriorityTier>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(OrderCreated {
+                    site_id: site_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                    items: items__.unwrap_or_default(),
+                    destination: destination__,
+                    total: total__.unwrap_or_default(),
+                    channel: channel__.unwrap_or_default(),
+                    promised_at: promised_at__,
+                    campaigns: campaigns__.unwrap_or_default(),
+                    tip: tip__,
+                    currency: currency__.unwrap_or_default(),
+                    order_id: order_id__.unwrap_or_default(),
+                    priority: priority__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.OrderCreated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderItem {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.brand_id.is_empty() {
+            len += 1;
+        }
+        if !self.menu_item_id.is_empty() {
+            len += 1;
+        }
+        if self.cuisine != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderItem", len)?;
+        if !self.brand_id.is_empty() {
+            struct_ser.serialize_field("brand_id", &self.brand_id)?;
+        }
+        if !self.menu_item_id.is_empty() {
+            struct_ser.serialize_field("menu_item_id", &self.menu_item_id)?;
+        }
+        if self.cuisine != 0 {
+            let v = Cuisine::try_from(self.cuisine)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.cuisine)))?;
+            struct_ser.serialize_field("cuisine", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderItem {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "brand_id",
+            "brandId",
+            "menu_item_id",
+            "menuItemId",
+            "cuisine",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            BrandId,
+            MenuItemId,
+            Cuisine,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "brandId" | "brand_id" => Ok(GeneratedField::BrandId),
+                            "menuItemId" | "menu_item_id" => Ok(GeneratedField::MenuItemId),
+                            "cuisine" => Ok(GeneratedField::Cuisine),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderItem;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.OrderItem")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderItem, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut brand_id__ = None;
+                let mut menu_item_id__ = None;
+                let mut cuisine__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::BrandId => {
+                            if brand_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("brandId"));
+                            }
+                            brand_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::MenuItemId => {
+                            if menu_item_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("menuItemId"));
+                            }
+                            menu_item_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Cuisine => {
+                            if cuisine__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("cuisine"));
+                            }
+                            cuisine__ = Some(map_.next_value::<Cuisine>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(OrderItem {
+                    brand_id: brand_id__.unwrap_or_default(),
+                    menu_item_id: menu_item_id__.unwrap_or_default(),
+                    cuisine: cuisine__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.OrderItem", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderLineStatus {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "ORDER_LINE_STATUS_UNSPECIFIED",
+            Self::Submitted => "ORDER_LINE_STATUS_SUBMITTED",
+            Self::Assigned => "ORDER_LINE_STATUS_ASSIGNED",
+            Self::Processing => "ORDER_LINE_STATUS_PROCESSING",
+            Self::Ready => "ORDER_LINE_STATUS_READY",
+            Self::Delivered => "ORDER_LINE_STATUS_DELIVERED",
+            Self::Waiting => "ORDER_LINE_STATUS_WAITING",
+            Self::Removed => "ORDER_LINE_STATUS_REMOVED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderLineStatus {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "ORDER_LINE_STATUS_UNSPECIFIED",
+            "ORDER_LINE_STATUS_SUBMITTED",
+            "ORDER_LINE_STATUS_ASSIGNED",
+            "ORDER_LINE_STATUS_PROCESSING",
+            "ORDER_LINE_STATUS_READY",
+            "ORDER_LINE_STATUS_DELIVERED",
+            "ORDER_LINE_STATUS_WAITING",
+            "ORDER_LINE_STATUS_REMOVED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderLineStatus;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "ORDER_LINE_STATUS_UNSPECIFIED" => Ok(OrderLineStatus::Unspecified),
+                    "ORDER_LINE_STATUS_SUBMITTED" => Ok(OrderLineStatus::Submitted),
+                    "ORDER_LINE_STATUS_ASSIGNED" => Ok(OrderLineStatus::Assigned),
+                    "ORDER_LINE_STATUS_PROCESSING" => Ok(OrderLineStatus::Processing),
+                    "ORDER_LINE_STATUS_READY" => Ok(OrderLineStatus::Ready),
+                    "ORDER_LINE_STATUS_DELIVERED" => Ok(OrderLineStatus::Delivered),
+                    "ORDER_LINE_STATUS_WAITING" => Ok(OrderLineStatus::Waiting),
+                    "ORDER_LINE_STATUS_REMOVED" => Ok(OrderLineStatus::Removed),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderLineUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.order_line_id.is_empty() {
+            len += 1;
+        }
+        if self.status != 0 {
+            len += 1;
+        }
+        if self.kitchen_id.is_some() {
+            len += 1;
+        }
+        if self.actor_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderLineUpdated", len)?;
+        if !self.order_line_id.is_empty() {
+            struct_ser.serialize_field("order_line_id", &self.order_line_id)?;
+        }
+        if self.status != 0 {
+            let v = OrderLineStatus::try_from(self.status)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
+            struct_ser.serialize_field("status", &v)?;
+        }
+        if let Some(v) = self.kitchen_id.as_ref() {
+            struct_ser.serialize_field("kitchen_id", v)?;
+        }
+        if let Some(v) = self.actor_id.as_ref() {
+            struct_ser.serialize_field("actor_id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderLineUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "order_line_id",
+            "orderLineId",
+            "status",
+            "kitchen_id",
+            "kitchenId",
+            "actor_id",
+            "actorId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            OrderLineId,
+            Status,
+            KitchenId,
+            ActorId,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "orderLineId" | "order_line_id" => Ok(GeneratedField::OrderLineId),
+                            "status" => Ok(GeneratedField::Status),
+                            "kitchenId" | "kitchen_id" => Ok(GeneratedField::KitchenId),
+                            "actorId" | "actor_id" => Ok(GeneratedField::ActorId),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderLineUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.OrderLineUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderLineUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut order_line_id__ = None;
+                let mut status__ = None;
+                let mut kitchen_id__ = None;
+                let mut actor_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::OrderLineId => {
+                            if order_line_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderLineId"));
+                            }
+                            order_line_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Status => {
+                            if status__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("status"));
+                            }
+                            status__ = Some(map_.next_value::<OrderLineStatus>()? as i32);
+                        }
+                        GeneratedField::KitchenId => {
+                            if kitchen_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("kitchenId"));
+                            }
+                            kitchen_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::ActorId => {
+                            if actor_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("actorId"));
+                            }
+                            actor_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(OrderLineUpdated {
+                    order_line_id: order_line_id__.unwrap_or_default(),
+                    status: status__.unwrap_or_default(),
+                    kitchen_id: kitchen_id__,
+                    actor_id: actor_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.OrderLineUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderStatus {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.id.is_empty() {
+            len += 1;
+        }
+        if self.status != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderStatus", len)?;
+        if !self.id.is_empty() {
+            struct_ser.serialize_field("id", &self.id)?;
+        }
+        if self.status != 0 {
+            let v = Status::try_from(self.status)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
+            struct_ser.serialize_field("status", &v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderStatus {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "status",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            Status,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "status" => Ok(GeneratedField::Status),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderStatus;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.OrderStatus")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderStatus, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut status__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Status => {
+                            if status__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("status"));
+                            }
+                            status__ = Some(map_.next_value::<Status>()? as i32);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(OrderStatus {
+                    id: id__.unwrap_or_default(),
+                    status: status__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.OrderStatus", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for OrderUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if self.status != 0 {
+            len += 1;
+        }
+        if self.actor_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderUpdated", len)?;
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if self.status != 0 {
+            let v = Status::try_from(self.status)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
+            struct_ser.serialize_field("status", &v)?;
+        }
+        if let Some(v) = self.actor_id.as_ref() {
+            struct_ser.serialize_field("actor_id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for OrderUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "order_id",
+            "orderId",
+            "status",
+            "actor_id",
+            "actorId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            OrderId,
+            Status,
+            ActorId,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "status" => Ok(GeneratedField::Status),
+                            "actorId" | "actor_id" => Ok(GeneratedField::ActorId),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = OrderUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.OrderUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut order_id__ = None;
+                let mut status__ = None;
+                let mut actor_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Status => {
+                            if status__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("status"));
+                            }
+                            status__ = Some(map_.next_value::<Status>()? as i32);
+                        }
+                        GeneratedField::ActorId => {
+                            if actor_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("actorId"));
+                            }
+                            actor_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(OrderUpdated {
+                    order_id: order_id__.unwrap_or_default(),
+                    status: status__.unwrap_or_default(),
+                    actor_id: actor_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.OrderUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PersonLifecycle {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if self.stage != 0 {
+            len += 1;
+        }
+        if self.order_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.PersonLifecycle", len)?;
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if self.stage != 0 {
+            let v = LifecycleStage::try_from(self.stage)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.stage)))?;
+            struct_ser.serialize_field("stage", &v)?;
+        }
+        if let Some(v) = self.order_id.as_ref() {
+            struct_ser.serialize_field("order_id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for PersonLifecycle {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "person_id",
+            "personId",
+            "stage",
+            "order_id",
+            "orderId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            PersonId,
+            Stage,
+            OrderId,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "stage" => Ok(GeneratedField::Stage),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PersonLifecycle;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.PersonLifecycle")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<PersonLifecycle, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut person_id__ = None;
+                let mut stage__ = None;
+                let mut order_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Stage => {
+                            if stage__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("stage"));
+                            }
+                            stage__ = Some(map_.next_value::<LifecycleStage>()? as i32);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(PersonLifecycle {
+                    person_id: person_id__.unwrap_or_default(),
+                    stage: stage__.unwrap_or_default(),
+                    order_id: order_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.PersonLifecycle", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PersonStatus {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "PERSON_STATUS_UNSPECIFIED",
+            Self::Idle => "PERSON_STATUS_IDLE",
+            Self::AwaitingOrder => "PERSON_STATUS_AWAITING_ORDER",
+            Self::Eating => "PERSON_STATUS_EATING",
+            Self::Moving => "PERSON_STATUS_MOVING",
+            Self::Delivering => "PERSON_STATUS_DELIVERING",
+            Self::WaitingForCustomer => "PERSON_STATUS_WAITING_FOR_CUSTOMER",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for PersonStatus {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "PERSON_STATUS_UNSPECIFIED",
+            "PERSON_STATUS_IDLE",
+            "PERSON_STATUS_AWAITING_ORDER",
+            "PERSON_STATUS_EATING",
+            "PERSON_STATUS_MOVING",
+            "PERSON_STATUS_DELIVERING",
+            "PERSON_STATUS_WAITING_FOR_CUSTOMER",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PersonStatus;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "PERSON_STATUS_UNSPECIFIED" => Ok(PersonStatus::Unspecified),
+                    "PERSON_STATUS_IDLE" => Ok(PersonStatus::Idle),
+                    "PERSON_STATUS_AWAITING_ORDER" => Ok(PersonStatus::AwaitingOrder),
+                    "PERSON_STATUS_EATING" => Ok(PersonStatus::Eating),
+                    "PERSON_STATUS_MOVING" => Ok(PersonStatus::Moving),
+                    "PERSON_STATUS_DELIVERING" => Ok(PersonStatus::Delivering),
+                    "PERSON_STATUS_WAITING_FOR_CUSTOMER" => Ok(PersonStatus::WaitingForCustomer),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PersonUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if self.status != 0 {
+            len += 1;
+        }
+        if self.order_id.is_some() {
+            len += 1;
+        }
+        if self.journey.is_some() {
+            len += 1;
+        }
+        if self.busy_until.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.PersonUpdated", len)?;
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if self.status != 0 {
+            let v = PersonStatus::try_from(self.status)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
+            struct_ser.serialize_field("status", &v)?;
+        }
+        if let Some(v) = self.order_id.as_ref() {
+            struct_ser.serialize_field("order_id", v)?;
+        }
+        if let Some(v) = self.journey.as_ref() {
+            struct_ser.serialize_field("journey", v)?;
+        }
+        if let Some(v) = self.busy_until.as_ref() {
+            struct_ser.serialize_field("busy_until", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for PersonUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "person_id",
+            "personId",
+            "status",
+            "order_id",
+            "orderId",
+            "journey",
+            "busy_until",
+            "busyUntil",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            PersonId,
+            Status,
+            OrderId,
+            Journey,
+            BusyUntil,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "status" => Ok(GeneratedField::Status),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "journey" => Ok(GeneratedField::Journey),
+                            "busyUntil" | "busy_until" => Ok(GeneratedField::BusyUntil),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PersonUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.PersonUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<PersonUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut person_id__ = None;
+                let mut status__ = None;
+                let mut order_id__ = None;
+                let mut journey__ = None;
+                let mut busy_until__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Status => {
+                            if status__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("status"));
+                            }
+                            status__ = Some(map_.next_value::<PersonStatus>()? as i32);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Journey => {
+                            if journey__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("journey"));
+                            }
+                            journey__ = map_.next_value()?;
+                        }
+                        GeneratedField::BusyUntil => {
+                            if busy_until__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("busyUntil"));
+                            }
+                            busy_until__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(PersonUpdated {
+                    person_id: person_id__.unwrap_or_default(),
+                    status: status__.unwrap_or_default(),
+                    order_id: order_id__,
+                    journey: journey__,
+                    busy_until: busy_until__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.PersonUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PriorityTier {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "PRIORITY_TIER_UNSPECIFIED",
+            Self::Normal => "PRIORITY_TIER_NORMAL",
+            Self::Priority => "PRIORITY_TIER_PRIORITY",
+            Self::Vip => "PRIORITY_TIER_VIP",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for PriorityTier {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "PRIORITY_TIER_UNSPECIFIED",
+            "PRIORITY_TIER_NORMAL",
+            "PRIORITY_TIER_PRIORITY",
+            "PRIORITY_TIER_VIP",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PriorityTier;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "PRIORITY_TIER_UNSPECIFIED" => Ok(PriorityTier::Unspecified),
+                    "PRIORITY_TIER_NORMAL" => Ok(PriorityTier::Normal),
+                    "PRIORITY_TIER_PRIORITY" => Ok(PriorityTier::Priority),
+                    "PRIORITY_TIER_VIP" => Ok(PriorityTier::Vip),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ProcurementActivity {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "PROCUREMENT_ACTIVITY_UNSPECIFIED",
+            Self::Ordered => "PROCUREMENT_ACTIVITY_ORDERED",
+            Self::Delayed => "PROCUREMENT_ACTIVITY_DELAYED",
+            Self::Delivered => "PROCUREMENT_ACTIVITY_DELIVERED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for ProcurementActivity {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "PROCUREMENT_ACTIVITY_UNSPECIFIED",
+            "PROCUREMENT_ACTIVITY_ORDERED",
+            "PROCUREMENT_ACTIVITY_DELAYED",
+            "PROCUREMENT_ACTIVITY_DELIVERED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ProcurementActivity;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "PROCUREMENT_ACTIVITY_UNSPECIFIED" => Ok(ProcurementActivity::Unspecified),
+                    "PROCUREMENT_ACTIVITY_ORDERED" => Ok(ProcurementActivity::Ordered),
+                    "PROCUREMENT_ACTIVITY_DELAYED" => Ok(ProcurementActivity::Delayed),
+                    "PROCUREMENT_ACTIVITY_DELIVERED" => Ok(ProcurementActivity::Delivered),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ProcurementUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.supplier_order_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.ingredient.is_empty() {
+            len += 1;
+        }
+        if !self.unit.is_empty() {
+            len += 1;
+        }
+        if self.activity != 0 {
+            len += 1;
+        }
+        if self.quantity != 0. {
+            len += 1;
+        }
+        if self.expected_at.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.ProcurementUpdated", len)?;
+        if !self.supplier_order_id.is_empty() {
+            struct_ser.serialize_field("supplier_order_id", &self.supplier_order_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.ingredient.is_empty() {
+            struct_ser.serialize_field("ingredient", &self.ingredient)?;
+        }
+        if !self.unit.is_empty() {
+            struct_ser.serialize_field("unit", &self.unit)?;
+        }
+        if self.activity != 0 {
+            let v = ProcurementActivity::try_from(self.activity)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.activity)))?;
+            struct_ser.serialize_field("activity", &v)?;
+        }
+        if self.quantity != 0. {
+            struct_ser.serialize_field("quantity", &self.quantity)?;
+        }
+        if let Some(v) = self.expected_at.as_ref() {
+            struct_ser.serialize_field("expected_at", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ProcurementUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "supplier_order_id",
+            "supplierOrderId",
+            "site_id",
+            "siteId",
+            "ingredient",
+            "unit",
+            "activity",
+            "quantity",
+            "expected_at",
+            "expectedAt",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SupplierOrderId,
+            SiteId,
+            Ingredient,
+            Unit,
+            Activity,
+            Quantity,
+            ExpectedAt,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "supplierOrderId" | "supplier_order_id" => Ok(GeneratedField::SupplierOrderId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "ingredient" => Ok(GeneratedField::Ingredient),
+                            "unit" => Ok(GeneratedField::Unit),
+                            "activity" => Ok(GeneratedField::Activity),
+                            "quantity" => Ok(GeneratedField::Quantity),
+                            "expectedAt" | "expected_at" => Ok(GeneratedField::ExpectedAt),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ProcurementUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.ProcurementUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<ProcurementUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut supplier_order_id__ = None;
+                let mut site_id__ = None;
+                let mut ingredient__ = None;
+                let mut unit__ = None;
+                let mut activity__ = None;
+                let mut quantity__ = None;
+                let mut expected_at__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SupplierOrderId => {
+                            if supplier_order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("supplierOrderId"));
+                            }
+                            supplier_order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Ingredient => {
+                            if ingredient__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("ingredient"));
+                            }
+                            ingredient__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Unit => {
+                            if unit__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("unit"));
+                            }
+                            unit__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Activity => {
+                            if activity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("activity"));
+                            }
+                            activity__ = Some(map_.next_value::<ProcurementActivity>()? as i32);
+                        }
+                        GeneratedField::Quantity => {
+                            if quantity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("quantity"));
+                            }
+                            quantity__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ExpectedAt => {
+                            if expected_at__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expectedAt"));
+                            }
+                            expected_at__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(ProcurementUpdated {
+                    supplier_order_id: supplier_order_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    ingredient: ingredient__.unwrap_or_default(),
+                    unit: unit__.unwrap_or_default(),
+                    activity: activity__.unwrap_or_default(),
+                    quantity: quantity__.unwrap_or_default(),
+                    expected_at: expected_at__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.ProcurementUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for RobotActivity {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "ROBOT_ACTIVITY_UNSPECIFIED",
+            Self::Dispatched => "ROBOT_ACTIVITY_DISPATCHED",
+            Self::Delivered => "ROBOT_ACTIVITY_DELIVERED",
+            Self::Returned => "ROBOT_ACTIVITY_RETURNED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for RobotActivity {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "ROBOT_ACTIVITY_UNSPECIFIED",
+            "ROBOT_ACTIVITY_DISPATCHED",
+            "ROBOT_ACTIVITY_DELIVERED",
+            "ROBOT_ACTIVITY_RETURNED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = RobotActivity;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "ROBOT_ACTIVITY_UNSPECIFIED" => Ok(RobotActivity::Unspecified),
+                    "ROBOT_ACTIVITY_DISPATCHED" => Ok(RobotActivity::Dispatched),
+                    "ROBOT_ACTIVITY_DELIVERED" => Ok(RobotActivity::Delivered),
+                    "ROBOT_ACTIVITY_RETURNED" => Ok(RobotActivity::Returned),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for RobotDelivery {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.robot_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.order_id.is_empty() {
+            len += 1;
+        }
+        if self.kind != 0 {
+            len += 1;
+        }
+        if self.activity != 0 {
+            len += 1;
+        }
+        if self.distance_m != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.RobotDelivery", len)?;
+        if !self.robot_id.is_empty() {
+            struct_ser.serialize_field("robot_id", &self.robot_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if self.kind != 0 {
+            let v = RobotKind::try_from(self.kind)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.kind)))?;
+            struct_ser.serialize_field("kind", &v)?;
+        }
+        if self.activity != 0 {
+            let v = RobotActivity::try_from(self.activity)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.activity)))?;
+            struct_ser.serialize_field("activity", &v)?;
+        }
+        if self.distance_m != 0. {
+            struct_ser.serialize_field("distance_m", &self.distance_m)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for RobotDelivery {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "robot_id",
+            "robotId",
+            "site_id",
+            "siteId",
+            "order_id",
+            "orderId",
+            "kind",
+            "activity",
+            "distance_m",
+            "distanceM",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            RobotId,
+            SiteId,
+            OrderId,
+            Kind,
+            Activity,
+            DistanceM,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "robotId" | "robot_id" => Ok(GeneratedField::RobotId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "kind" => Ok(GeneratedField::Kind),
+                            "activity" => Ok(GeneratedField::Activity),
+                            "distanceM" | "distance_m" => Ok(GeneratedField::DistanceM),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = RobotDelivery;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.RobotDelivery")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<RobotDelivery, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut robot_id__ = None;
+                let mut site_id__ = None;
+                let mut order_id__ = None;
+                let mut kind__ = None;
+                let mut activity__ = None;
+                let mut distance_m__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::RobotId => {
+                            if robot_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("robotId"));
+                            }
+                            robot_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Kind => {
+                            if kind__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("kind"));
+                            }
+                            kind__ = Some(map_.next_value::<RobotKind>()? as i32);
+                        }
+                        GeneratedField::Activity => {
+                            if activity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("activity"));
+                            }
+                            activity__ = Some(map_.next_value::<RobotActivity>()? as i32);
+                        }
+                        GeneratedField::DistanceM => {
+                            if distance_m__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("distanceM"));
+                            }
+                            distance_m__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(RobotDelivery {
+                    robot_id: robot_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    order_id: order_id__.unwrap_or_default(),
+                    kind: kind__.unwrap_or_default(),
+                    activity: activity__.unwrap_or_default(),
+                    distance_m: distance_m__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.RobotDelivery", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for RobotKind {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "ROBOT_KIND_UNSPECIFIED",
+            Self::Robot => "ROBOT_KIND_ROBOT",
+            Self::Drone => "ROBOT_KIND_DRONE",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for RobotKind {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "ROBOT_KIND_UNSPECIFIED",
+            "ROBOT_KIND_ROBOT",
+            "ROBOT_KIND_DRONE",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = RobotKind;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "ROBOT_KIND_UNSPECIFIED" => Ok(RobotKind::Unspecified),
+                    "ROBOT_KIND_ROBOT" => Ok(RobotKind::Robot),
+                    "ROBOT_KIND_DRONE" => Ok(RobotKind::Drone),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SessionUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.session_id.is_empty() {
+            len += 1;
+        }
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if self.stage != 0 {
+            len += 1;
+        }
+        if !self.menu_item_ids.is_empty() {
+            len += 1;
+        }
+        if self.order_id.is_some() {
+            len += 1;
+        }
+        if self.occurred_at.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SessionUpdated", len)?;
+        if !self.session_id.is_empty() {
+            struct_ser.serialize_field("session_id", &self.session_id)?;
+        }
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if self.stage != 0 {
+            let v = FunnelStage::try_from(self.stage)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.stage)))?;
+            struct_ser.serialize_field("stage", &v)?;
+        }
+        if !self.menu_item_ids.is_empty() {
+            struct_ser.serialize_field("menu_item_ids", &self.menu_item_ids)?;
+        }
+        if let Some(v) = self.order_id.as_ref() {
+            struct_ser.serialize_field("order_id", v)?;
+        }
+        if let Some(v) = self.occurred_at.as_ref() {
+            struct_ser.serialize_field("occurred_at", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SessionUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "session_id",
+            "sessionId",
+            "person_id",
+            "personId",
+            "site_id",
+            "siteId",
+            "stage",
+            "menu_item_ids",
+            "menuItemIds",
+            "order_id",
+            "orderId",
+            "occurred_at",
+            "occurredAt",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SessionId,
+            PersonId,
+            SiteId,
+            Stage,
+            MenuItemIds,
+            OrderId,
+            OccurredAt,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "sessionId" | "session_id" => Ok(GeneratedField::SessionId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "stage" => Ok(GeneratedField::Stage),
+                            "menuItemIds" | "menu_item_ids" => Ok(GeneratedField::MenuItemIds),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "occurredAt" | "occurred_at" => Ok(GeneratedField::OccurredAt),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SessionUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.SessionUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SessionUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut session_id__ = None;
+                let mut person_id__ = None;
+                let mut site_id__ = None;
+                let mut stage__ = None;
+                let mut menu_item_ids__ = None;
+                let mut order_id__ = None;
+                let mut occurred_at__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SessionId => {
+                            if session_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("sessionId"));
+                            }
+                            session_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Stage => {
+                            if stage__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("stage"));
+                            }
+                            stage__ = Some(map_.next_value::<FunnelStage>()? as i32);
+                        }
+                        GeneratedField::MenuItemIds => {
+                            if menu_item_ids__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("menuItemIds"));
+                            }
+                            menu_item_ids__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
+                            }
+                            order_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::OccurredAt => {
+                            if occurred_at__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("occurredAt"));
+                            }
+                            occurred_at__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(SessionUpdated {
+                    session_id: session_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    stage: stage__.unwrap_or_default(),
+                    menu_item_ids: menu_item_ids__.unwrap_or_default(),
+                    order_id: order_id__,
+                    occurred_at: occurred_at__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.SessionUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SimulationEvent {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.time.is_some() {
+            len += 1;
+        }
+        if self.payload.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SimulationEvent", len)?;
+        if let Some(v) = self.time.as_ref() {
+            struct_ser.serialize_field("time", v)?;
+        }
+        if let Some(v) = self.payload.as_ref() {
+            match v {
+                simulation_event::Payload::PersonUpdated(v) => {
+                    struct_ser.serialize_field("person_updated", v)?;
+                }
+                simulation_event::Payload::OrderCreated(v) => {
+                    struct_ser.serialize_field("order_created", v)?;
+                }
+                simulation_event::Payload::OrderUpdated(v) => {
+                    struct_ser.serialize_field("order_updated", v)?;
+                }
+                simulation_event::Payload::OrderLineUpdated(v) => {
+                    struct_ser.serialize_field("order_line_updated", v)?;
+                }
+                simulation_event::Payload::SiteCheckIn(v) => {
+                    struct_ser.serialize_field("site_check_in", v)?;
+                }
+                simulation_event::Payload::SiteCheckOut(v) => {
+                    struct_ser.serialize_field("site_check_out", v)?;
+                }
+                simulation_event::Payload::StepStarted(v) => {
+                    struct_ser.serialize_field("step_started", v)?;
+                }
+                simulation_event::Payload::StepFinished(v) => {
+                    struct_ser.serialize_field("step_finished", v)?;
+                }
+                simulation_event::Payload::ObjectChanged(v) => {
+                    struct_ser.serialize_field("object_changed", v)?;
+                }
+                simulation_event::Payload::CourierUpdated(v) => {
+                    struct_ser.serialize_field("courier_updated", v)?;
+                }
+                simulation_event::Payload::PersonLifecycle(v) => {
+                    struct_ser.serialize_field("person_lifecycle", v)?;
+                }
+                simulation_event::Payload::NotificationUpdated(v) => {
+                    struct_ser.serialize_field("notification_updated", v)?;
+                }
+                simulation_event::Payload::CompensationIssued(v) => {
+                    struct_ser.serialize_field("compensation_issued", v)?;
+                }
+                simulation_event::Payload::SupplyUpdated(v) => {
+                    struct_ser.serialize_field("supply_updated", v)?;
+                }
+                simulation_event::Payload::CourierBreak(v) => {
+                    struct_ser.serialize_field("courier_break", v)?;
+                }
+                simulation_event::Payload::RobotDelivery(v) => {
+                    struct_ser.serialize_field("robot_delivery", v)?;
+                }
+                simulation_event::Payload::SubstitutionUpdated(v) => {
+                    struct_ser.serialize_field("substitution_updated", v)?;
+                }
+                simulation_event::Payload::ConfigChanged(v) => {
+                    struct_ser.serialize_field("config_changed", v)?;
+                }
+                simulation_event::Payload::MembershipBilled(v) => {
+                    struct_ser.serialize_field("membership_billed", v)?;
+                }
+                simulation_event::Payload::SessionUpdated(v) => {
+                    struct_ser.serialize_field("session_updated", v)?;
+                }
+                simulation_event::Payload::ProcurementUpdated(v) => {
+                    struct_ser.serialize_field("procurement_updated", v)?;
+                }
+                simulation_event::Payload::IngredientWasted(v) => {
+                    struct_ser.serialize_field("ingredient_wasted", v)?;
+                }
+                simulation_event::Payload::DeliveryFootprint(v) => {
+                    struct_ser.serialize_field("delivery_footprint", v)?;
+                }
+            }
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SimulationEvent {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "time",
+            "person_updated",
+            "personUpdated",
+            "order_created",
+            "orderCreated",
+            "order_updated",
+            "orderUpdated",
+            "order_line_updated",
+            "orderLineUpdated",
+            "site_check_in",
+            "siteCheckIn",
+            "site_check_out",
+            "siteCheckOut",
+            "step_started",
+            "stepStarted",
+            "step_finished",
+            "stepFinished",
+            "object_changed",
+            "objectChanged",
+            "courier_updated",
+            "courierUpdated",
+            "person_lifecycle",
+            "personLifecycle",
+            "notification_updated",
+            "notificationUpdated",
+            "compensation_issued",
+            "compensationIssued",
+            "supply_updated",
+            "supplyUpdated",
+            "courier_break",
+            "courierBreak",
+            "robot_delivery",
+            "robotDelivery",
+            "substitution_updated",
+            "substitutionUpdated",
+            "config_changed",
+            "configChanged",
+            "membership_billed",
+            "membershipBilled",
+            "session_updated",
+            "sessionUpdated",
+            "procurement_updated",
+            "procurementUpdated",
+            "ingredient_wasted",
+            "ingredientWasted",
+            "delivery_footprint",
+            "deliveryFootprint",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Time,
+            PersonUpdated,
+            OrderCreated,
+            OrderUpdated,
+            OrderLineUpdated,
+            SiteCheckIn,
+            SiteCheckOut,
+            StepStarted,
+            StepFinished,
+            ObjectChanged,
+            CourierUpdated,
+            PersonLifecycle,
+            NotificationUpdated,
+            CompensationIssued,
+            SupplyUpdated,
+            CourierBreak,
+            RobotDelivery,
+            SubstitutionUpdated,
+            ConfigChanged,
+            MembershipBilled,
+            SessionUpdated,
+            ProcurementUpdated,
+            IngredientWasted,
+            DeliveryFootprint,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "time" => Ok(GeneratedField::Time),
+                            "personUpdated" | "person_updated" => Ok(GeneratedField::PersonUpdated),
+                            "orderCreated" | "order_created" => Ok(GeneratedField::OrderCreated),
+                            "orderUpdated" | "order_updated" => Ok(GeneratedField::OrderUpdated),
+                            "orderLineUpdated" | "order_line_updated" => Ok(GeneratedField::OrderLineUpdated),
+                            "siteCheckIn" | "site_check_in" => Ok(GeneratedField::SiteCheckIn),
+                            "siteCheckOut" | "site_check_out" => Ok(GeneratedField::SiteCheckOut),
+                            "stepStarted" | "step_started" => Ok(GeneratedField::StepStarted),
+                            "stepFinished" | "step_finished" => Ok(GeneratedField::StepFinished),
+                            "objectChanged" | "object_changed" => Ok(GeneratedField::ObjectChanged),
+                            "courierUpdated" | "courier_updated" => Ok(GeneratedField::CourierUpdated),
+                            "personLifecycle" | "person_lifecycle" => Ok(GeneratedField::PersonLifecycle),
+                            "notificationUpdated" | "notification_updated" => Ok(GeneratedField::NotificationUpdated),
+                            "compensationIssued" | "compensation_issued" => Ok(GeneratedField::CompensationIssued),
+                            "supplyUpdated" | "supply_updated" => Ok(GeneratedField::SupplyUpdated),
+                            "courierBreak" | "courier_break" => Ok(GeneratedField::CourierBreak),
+                            "robotDelivery" | "robot_delivery" => Ok(GeneratedField::RobotDelivery),
+                            "substitutionUpdated" | "substitution_updated" => Ok(GeneratedField::SubstitutionUpdated),
+                            "configChanged" | "config_changed" => Ok(GeneratedField::ConfigChanged),
+                            "membershipBilled" | "membership_billed" => Ok(GeneratedField::MembershipBilled),
+                            "sessionUpdated" | "session_updated" => Ok(GeneratedField::SessionUpdated),
+                            "procurementUpdated" | "procurement_updated" => Ok(GeneratedField::ProcurementUpdated),
+                            "ingredientWasted" | "ingredient_wasted" => Ok(GeneratedField::IngredientWasted),
+                            "deliveryFootprint" | "delivery_footprint" => Ok(GeneratedField::DeliveryFootprint),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SimulationEvent;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.SimulationEvent")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SimulationEvent, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut time__ = None;
+                let mut payload__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Time => {
+                            if time__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("time"));
+                            }
+                            time__ = map_.next_value()?;
+                        }
+                        GeneratedField::PersonUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::PersonUpdated)
+;
+                        }
+                        GeneratedField::OrderCreated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderCreated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::OrderCreated)
+;
+                        }
+                        GeneratedField::OrderUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::OrderUpdated)
+;
+                        }
+                        GeneratedField::OrderLineUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderLineUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::OrderLineUpdated)
+;
+                        }
+                        GeneratedField::SiteCheckIn => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteCheckIn"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::SiteCheckIn)
+;
+                        }
+                        GeneratedField::SiteCheckOut => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteCheckOut"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::SiteCheckOut)
+;
+                        }
+                        GeneratedField::StepStarted => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("stepStarted"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::StepStarted)
+;
+                        }
+                        GeneratedField::StepFinished => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("stepFinished"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::StepFinished)
+;
+                        }
+                        GeneratedField::ObjectChanged => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("objectChanged"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::ObjectChanged)
+;
+                        }
+                        GeneratedField::CourierUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("courierUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::CourierUpdated)
+;
+                        }
+                        GeneratedField::PersonLifecycle => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personLifecycle"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::PersonLifecycle)
+;
+                        }
+                        GeneratedField::NotificationUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("notificationUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::NotificationUpdated)
+;
+                        }
+                        GeneratedField::CompensationIssued => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("compensationIssued"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::CompensationIssued)
+;
+                        }
+                        GeneratedField::SupplyUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("supplyUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::SupplyUpdated)
+;
+                        }
+                        GeneratedField::CourierBreak => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("courierBreak"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::CourierBreak)
+;
+                        }
+                        GeneratedField::RobotDelivery => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("robotDelivery"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::RobotDelivery)
+;
+                        }
+                        GeneratedField::SubstitutionUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("substitutionUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::SubstitutionUpdated)
+;
+                        }
+                        GeneratedField::ConfigChanged => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("configChanged"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::ConfigChanged)
+;
+                        }
+                        GeneratedField::MembershipBilled => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("membershipBilled"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::MembershipBilled)
+;
+                        }
+                        GeneratedField::SessionUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("sessionUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::SessionUpdated)
+;
+                        }
+                        GeneratedField::ProcurementUpdated => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("procurementUpdated"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::ProcurementUpdated)
+;
+                        }
+                        GeneratedField::IngredientWasted => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("ingredientWasted"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::IngredientWasted)
+;
+                        }
+                        GeneratedField::DeliveryFootprint => {
+                            if payload__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("deliveryFootprint"));
+                            }
+                            payload__ = map_.next_value::<::std::option::Option<_>>()?.map(simulation_event::Payload::DeliveryFootprint)
+;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(SimulationEvent {
+                    time: time__,
+                    payload: payload__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.SimulationEvent", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SiteCheckIn {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SiteCheckIn", len)?;
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SiteCheckIn {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "site_id",
+            "siteId",
+            "person_id",
+            "personId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SiteId,
+            PersonId,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SiteCheckIn;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.SiteCheckIn")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SiteCheckIn, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut site_id__ = None;
+                let mut person_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(SiteCheckIn {
+                    site_id: site_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.SiteCheckIn", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SiteCheckOut {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.person_id.is_empty() {
+            len += 1;
+        }
+        if !self.order_ids.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SiteCheckOut", len)?;
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.person_id.is_empty() {
+            struct_ser.serialize_field("person_id", &self.person_id)?;
+        }
+        if !self.order_ids.is_empty() {
+            struct_ser.serialize_field("order_ids", &self.order_ids)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SiteCheckOut {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "site_id",
+            "siteId",
+            "person_id",
+            "personId",
+            "order_ids",
+            "orderIds",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SiteId,
+            PersonId,
+            OrderIds,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "personId" | "person_id" => Ok(GeneratedField::PersonId),
+                            "orderIds" | "order_ids" => Ok(GeneratedField::OrderIds),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SiteCheckOut;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.SiteCheckOut")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SiteCheckOut, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut site_id__ = None;
+                let mut person_id__ = None;
+                let mut order_ids__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::PersonId => {
+                            if person_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("personId"));
+                            }
+                            person_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderIds => {
+                            if order_ids__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderIds"));
+                            }
+                            order_ids__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(SiteCheckOut {
+                    site_id: site_id__.unwrap_or_default(),
+                    person_id: person_id__.unwrap_or_default(),
+                    order_ids: order_ids__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.SiteCheckOut", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Status {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "STATUS_UNSPECIFIED",
+            Self::Received => "STATUS_RECEIVED",
+            Self::Accepted => "STATUS_ACCEPTED",
+            Self::Processing => "STATUS_PROCESSING",
+            Self::Ready => "STATUS_READY",
+            Self::PickedUp => "STATUS_PICKED_UP",
+            Self::Delivered => "STATUS_DELIVERED",
+            Self::Cancelled => "STATUS_CANCELLED",
+            Self::Failed => "STATUS_FAILED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for Status {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "STATUS_UNSPECIFIED",
+            "STATUS_RECEIVED",
+            "STATUS_ACCEPTED",
+            "STATUS_PROCESSING",
+            "STATUS_READY",
+            "STATUS_PICKED_UP",
+            "STATUS_DELIVERED",
+            "STATUS_CANCELLED",
+            "STATUS_FAILED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Status;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "STATUS_UNSPECIFIED" => Ok(Status::Unspecified),
+                    "STATUS_RECEIVED" => Ok(Status::Received),
+                    "STATUS_ACCEPTED" => Ok(Status::Accepted),
+                    "STATUS_PROCESSING" => Ok(Status::Processing),
+                    "STATUS_READY" => Ok(Status::Ready),
+                    "STATUS_PICKED_UP" => Ok(Status::PickedUp),
+                    "STATUS_DELIVERED" => Ok(Status::Delivered),
+                    "STATUS_CANCELLED" => Ok(Status::Cancelled),
+                    "STATUS_FAILED" => Ok(Status::Failed),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StepFinished {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.simulation_time.is_some() {
+            len += 1;
+        }
+        if self.num_events != 0 {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.StepFinished", len)?;
+        if let Some(v) = self.simulation_time.as_ref() {
+            struct_ser.serialize_field("simulation_time", v)?;
+        }
+        if self.num_events != 0 {
+            #[allow(clippy::needless_borrow)]
+            #[allow(clippy::needless_borrows_for_generic_args)]
+            struct_ser.serialize_field("num_events", ToString::to_string(&self.num_events).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StepFinished {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "simulation_time",
+            "simulationTime",
+            "num_events",
+            "numEvents",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SimulationTime,
+            NumEvents,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "simulationTime" | "simulation_time" => Ok(GeneratedField::SimulationTime),
+                            "numEvents" | "num_events" => Ok(GeneratedField::NumEvents),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StepFinished;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.StepFinished")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<StepFinished, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut simulation_time__ = None;
+                let mut num_events__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SimulationTime => {
+                            if simulation_time__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("simulationTime"));
+                            }
+                            simulation_time__ = map_.next_value()?;
+                        }
+                        GeneratedField::NumEvents => {
+                            if num_events__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("numEvents"));
+                            }
+                            num_events__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(StepFinished {
+                    simulation_time: simulation_time__,
+                    num_events: num_events__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.StepFinished", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for StepStarted {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if self.simulation_time.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.StepStarted", len)?;
+        if let Some(v) = self.simulation_time.as_ref() {
+            struct_ser.serialize_field("simulation_time", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for StepStarted {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "simulation_time",
+            "simulationTime",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SimulationTime,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "simulationTime" | "simulation_time" => Ok(GeneratedField::SimulationTime),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = StepStarted;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.StepStarted")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<StepStarted, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut simulation_time__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SimulationTime => {
+                            if simulation_time__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("simulationTime"));
+                            }
+                            simulation_time__ = map_.next_value()?;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(StepStarted {
+                    simulation_time: simulation_time__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.StepStarted", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SubstitutionStatus {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "SUBSTITUTION_STATUS_UNSPECIFIED",
+            Self::Suggested => "SUBSTITUTION_STATUS_SUGGESTED",
+            Self::Accepted => "SUBSTITUTION_STATUS_ACCEPTED",
+            Self::Rejected => "SUBSTITUTION_STATUS_REJECTED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for SubstitutionStatus {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "SUBSTITUTION_STATUS_UNSPECIFIED",
+            "SUBSTITUTION_STATUS_SUGGESTED",
+            "SUBSTITUTION_STATUS_ACCEPTED",
+            "SUBSTITUTION_STATUS_REJECTED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SubstitutionStatus;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "SUBSTITUTION_STATUS_UNSPECIFIED" => Ok(SubstitutionStatus::Unspecified),
+                    "SUBSTITUTION_STATUS_SUGGESTED" => Ok(SubstitutionStatus::Suggested),
+                    "SUBSTITUTION_STATUS_ACCEPTED" => Ok(SubstitutionStatus::Accepted),
+                    "SUBSTITUTION_STATUS_REJECTED" => Ok(SubstitutionStatus::Rejected),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SubstitutionUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.order_id.is_empty() {
             len += 1;
         }
-        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.OrderStatus", len)?;
-        if !self.id.is_empty() {
-            struct_ser.serialize_field("id", &self.id)?;
+        if !self.order_line_id.is_empty() {
+            len += 1;
+        }
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.sku.is_empty() {
+            len += 1;
+        }
+        if !self.substitute.is_empty() {
+            len += 1;
         }
         if self.status != 0 {
-            let v = Status::try_from(self.status)
+            len += 1;
+        }
+        if self.price_delta != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SubstitutionUpdated", len)?;
+        if !self.order_id.is_empty() {
+            struct_ser.serialize_field("order_id", &self.order_id)?;
+        }
+        if !self.order_line_id.is_empty() {
+            struct_ser.serialize_field("order_line_id", &self.order_line_id)?;
+        }
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.sku.is_empty() {
+            struct_ser.serialize_field("sku", &self.sku)?;
+        }
+        if !self.substitute.is_empty() {
+            struct_ser.serialize_field("substitute", &self.substitute)?;
+        }
+        if self.status != 0 {
+            let v = SubstitutionStatus::try_from(self.status)
                 .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.status)))?;
             struct_ser.serialize_field("status", &v)?;
         }
+        if self.price_delta != 0. {
+            struct_ser.serialize_field("price_delta", &self.price_delta)?;
+        }
         struct_ser.end()
     }
 }
-impl<'de> serde::Deserialize<'de> for OrderStatus {
+impl<'de> serde::Deserialize<'de> for SubstitutionUpdated {
     #[allow(deprecated)]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         const FIELDS: &[&str] = &[
-            "id",
+            "order_id",
+            "orderId",
+            "order_line_id",
+            "orderLineId",
+            "site_id",
+            "siteId",
+            "sku",
+            "substitute",
             "status",
+            "price_delta",
+            "priceDelta",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
-            Id,
+            OrderId,
+            OrderLineId,
+            SiteId,
+            Sku,
+            Substitute,
             Status,
+            PriceDelta,
             __SkipField__,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
@@ -874,8 +6946,13 @@ impl<'de> serde::Deserialize<'de> for OrderStatus {
                         E: serde::de::Error,
                     {
                         match value {
-                            "id" => Ok(GeneratedField::Id),
+                            "orderId" | "order_id" => Ok(GeneratedField::OrderId),
+                            "orderLineId" | "order_line_id" => Ok(GeneratedField::OrderLineId),
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "sku" => Ok(GeneratedField::Sku),
+                            "substitute" => Ok(GeneratedField::Substitute),
                             "status" => Ok(GeneratedField::Status),
+                            "priceDelta" | "price_delta" => Ok(GeneratedField::PriceDelta),
                             _ => Ok(GeneratedField::__SkipField__),
                         }
                     }
@@ -885,86 +6962,120 @@ impl<'de> serde::Deserialize<'de> for OrderStatus {
         }
         struct GeneratedVisitor;
         impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
-            type Value = OrderStatus;
+            type Value = SubstitutionUpdated;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("struct caspers.messages.v1.OrderStatus")
+                formatter.write_str("struct caspers.messages.v1.SubstitutionUpdated")
             }
 
-            fn visit_map<V>(self, mut map_: V) -> std::result::Result<OrderStatus, V::Error>
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SubstitutionUpdated, V::Error>
                 where
                     V: serde::de::MapAccess<'de>,
             {
-                let mut id__ = None;
+                let mut order_id__ = None;
+                let mut order_line_id__ = None;
+                let mut site_id__ = None;
+                let mut sku__ = None;
+                let mut substitute__ = None;
                 let mut status__ = None;
+                let mut price_delta__ = None;
                 while let Some(k) = map_.next_key()? {
                     match k {
-                        GeneratedField::Id => {
-                            if id__.is_some() {
-                                return Err(serde::de::Error::duplicate_field("id"));
+                        GeneratedField::OrderId => {
+                            if order_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderId"));
                             }
-                            id__ = Some(map_.next_value()?);
+                            order_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::OrderLineId => {
+                            if order_line_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("orderLineId"));
+                            }
+                            order_line_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Sku => {
+                            if sku__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("sku"));
+                            }
+                            sku__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Substitute => {
+                            if substitute__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("substitute"));
+                            }
+                            substitute__ = Some(map_.next_value()?);
                         }
                         GeneratedField::Status => {
                             if status__.is_some() {
                                 return Err(serde::de::Error::duplicate_field("status"));
                             }
-                            status__ = Some(map_.next_value::<Status>()? as i32);
+                            status__ = Some(map_.next_value::<SubstitutionStatus>()? as i32);
+                        }
+                        GeneratedField::PriceDelta => {
+                            if price_delta__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("priceDelta"));
+                            }
+                            price_delta__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
                         }
                         GeneratedField::__SkipField__ => {
                             let _ = map_.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
                 }
-                Ok(OrderStatus {
-                    id: id__.unwrap_or_default(),
+                Ok(SubstitutionUpdated {
+                    order_id: order_id__.unwrap_or_default(),
+                    order_line_id: order_line_id__.unwrap_or_default(),
+                    site_id: site_id__.unwrap_or_default(),
+                    sku: sku__.unwrap_or_default(),
+                    substitute: substitute__.unwrap_or_default(),
                     status: status__.unwrap_or_default(),
+                    price_delta: price_delta__.unwrap_or_default(),
                 })
             }
         }
-        deserializer.deserialize_struct("caspers.messages.v1.OrderStatus", FIELDS, GeneratedVisitor)
+        deserializer.deserialize_struct("caspers.messages.v1.SubstitutionUpdated", FIELDS, GeneratedVisitor)
     }
 }
-impl serde::Serialize for Status {
+impl serde::Serialize for SupplyActivity {
     #[allow(deprecated)]
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         let variant = match self {
-            Self::Unspecified => "STATUS_UNSPECIFIED",
-            Self::Received => "STATUS_RECEIVED",
-            Self::Accepted => "STATUS_ACCEPTED",
-            Self::Processing => "STATUS_PROCESSING",
-            Self::Ready => "STATUS_READY",
-            Self::PickedUp => "STATUS_PICKED_UP",
-            Self::Delivered => "STATUS_DELIVERED",
-            Self::Cancelled => "STATUS_CANCELLED",
+            Self::Unspecified => "SUPPLY_ACTIVITY_UNSPECIFIED",
+            Self::Reordered => "SUPPLY_ACTIVITY_REORDERED",
+            Self::Restocked => "SUPPLY_ACTIVITY_RESTOCKED",
+            Self::Depleted => "SUPPLY_ACTIVITY_DEPLETED",
         };
         serializer.serialize_str(variant)
     }
 }
-impl<'de> serde::Deserialize<'de> for Status {
+impl<'de> serde::Deserialize<'de> for SupplyActivity {
     #[allow(deprecated)]
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
         const FIELDS: &[&str] = &[
-            "STATUS_UNSPECIFIED",
-            "STATUS_RECEIVED",
-            "STATUS_ACCEPTED",
-            "STATUS_PROCESSING",
-            "STATUS_READY",
-            "STATUS_PICKED_UP",
-            "STATUS_DELIVERED",
-            "STATUS_CANCELLED",
+            "SUPPLY_ACTIVITY_UNSPECIFIED",
+            "SUPPLY_ACTIVITY_REORDERED",
+            "SUPPLY_ACTIVITY_RESTOCKED",
+            "SUPPLY_ACTIVITY_DEPLETED",
         ];
 
         struct GeneratedVisitor;
 
         impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
-            type Value = Status;
+            type Value = SupplyActivity;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(formatter, "expected one of: {:?}", &FIELDS)
@@ -999,14 +7110,235 @@ impl<'de> serde::Deserialize<'de> for Status {
                 E: serde::de::Error,
             {
                 match value {
-                    "STATUS_UNSPECIFIED" => Ok(Status::Unspecified),
-                    "STATUS_RECEIVED" => Ok(Status::Received),
-                    "STATUS_ACCEPTED" => Ok(Status::Accepted),
-                    "STATUS_PROCESSING" => Ok(Status::Processing),
-                    "STATUS_READY" => Ok(Status::Ready),
-                    "STATUS_PICKED_UP" => Ok(Status::PickedUp),
-                    "STATUS_DELIVERED" => Ok(Status::Delivered),
-                    "STATUS_CANCELLED" => Ok(Status::Cancelled),
+                    "SUPPLY_ACTIVITY_UNSPECIFIED" => Ok(SupplyActivity::Unspecified),
+                    "SUPPLY_ACTIVITY_REORDERED" => Ok(SupplyActivity::Reordered),
+                    "SUPPLY_ACTIVITY_RESTOCKED" => Ok(SupplyActivity::Restocked),
+                    "SUPPLY_ACTIVITY_DEPLETED" => Ok(SupplyActivity::Depleted),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SupplyUpdated {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.site_id.is_empty() {
+            len += 1;
+        }
+        if !self.supply.is_empty() {
+            len += 1;
+        }
+        if self.activity != 0 {
+            len += 1;
+        }
+        if self.stock != 0. {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("caspers.messages.v1.SupplyUpdated", len)?;
+        if !self.site_id.is_empty() {
+            struct_ser.serialize_field("site_id", &self.site_id)?;
+        }
+        if !self.supply.is_empty() {
+            struct_ser.serialize_field("supply", &self.supply)?;
+        }
+        if self.activity != 0 {
+            let v = SupplyActivity::try_from(self.activity)
+                .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.activity)))?;
+            struct_ser.serialize_field("activity", &v)?;
+        }
+        if self.stock != 0. {
+            struct_ser.serialize_field("stock", &self.stock)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SupplyUpdated {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "site_id",
+            "siteId",
+            "supply",
+            "activity",
+            "stock",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            SiteId,
+            Supply,
+            Activity,
+            Stock,
+            __SkipField__,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "siteId" | "site_id" => Ok(GeneratedField::SiteId),
+                            "supply" => Ok(GeneratedField::Supply),
+                            "activity" => Ok(GeneratedField::Activity),
+                            "stock" => Ok(GeneratedField::Stock),
+                            _ => Ok(GeneratedField::__SkipField__),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SupplyUpdated;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct caspers.messages.v1.SupplyUpdated")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SupplyUpdated, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut site_id__ = None;
+                let mut supply__ = None;
+                let mut activity__ = None;
+                let mut stock__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::SiteId => {
+                            if site_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("siteId"));
+                            }
+                            site_id__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Supply => {
+                            if supply__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("supply"));
+                            }
+                            supply__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Activity => {
+                            if activity__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("activity"));
+                            }
+                            activity__ = Some(map_.next_value::<SupplyActivity>()? as i32);
+                        }
+                        GeneratedField::Stock => {
+                            if stock__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("stock"));
+                            }
+                            stock__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::__SkipField__ => {
+                            let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                Ok(SupplyUpdated {
+                    site_id: site_id__.unwrap_or_default(),
+                    supply: supply__.unwrap_or_default(),
+                    activity: activity__.unwrap_or_default(),
+                    stock: stock__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("caspers.messages.v1.SupplyUpdated", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for WasteReason {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::Unspecified => "WASTE_REASON_UNSPECIFIED",
+            Self::Expired => "WASTE_REASON_EXPIRED",
+            Self::Discarded => "WASTE_REASON_DISCARDED",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for WasteReason {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "WASTE_REASON_UNSPECIFIED",
+            "WASTE_REASON_EXPIRED",
+            "WASTE_REASON_DISCARDED",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = WasteReason;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "WASTE_REASON_UNSPECIFIED" => Ok(WasteReason::Unspecified),
+                    "WASTE_REASON_EXPIRED" => Ok(WasteReason::Expired),
+                    "WASTE_REASON_DISCARDED" => Ok(WasteReason::Discarded),
                     _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
                 }
             }
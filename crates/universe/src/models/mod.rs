@@ -3,9 +3,13 @@ use std::sync::Arc;
 use crate::error::Result;
 
 pub use caspers::models::v1::*;
+pub use cuisine::Cuisine;
 
 pub type MenuItemRef = Arc<MenuItem>;
 
+mod cuisine;
+mod events;
+
 pub mod caspers {
     pub mod models {
         pub mod v1 {
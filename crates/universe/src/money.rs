@@ -0,0 +1,252 @@
+//! Monetary amounts and currency conversion.
+//!
+//! Sites charge customers in their own currency, while brands may price their menus
+//! in another one. Amounts therefore always carry their currency, and are only added
+//! up after converting them into a common currency with the configured
+//! [`ExchangeRates`]. Objects without a currency use the base currency of the rates.
+//!
+//! ```
+//! use caspers_universe::{Currency, ExchangeRates, Money};
+//!
+//! let rates = ExchangeRates::new(Currency::USD).with_rate("EUR".parse()?, 1.1);
+//! let price = Money::new(10.0, "EUR".parse()?);
+//! assert_eq!(rates.convert(price, Currency::USD)?.amount(), 11.0);
+//! # Ok::<(), caspers_universe::Error>(())
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// ISO 4217 currency code, e.g. `USD`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    pub const USD: Currency = Currency(*b"USD");
+
+    pub fn as_str(&self) -> &str {
+        // only constructed from ASCII letters
+        std::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::USD
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Error;
+
+    fn from_str(code: &str) -> Result<Self> {
+        match code.as_bytes() {
+            &[a, b, c] if code.bytes().all(|byte| byte.is_ascii_uppercase()) => {
+                Ok(Currency([a, b, c]))
+            }
+            _ => Err(Error::invalid_data(format!(
+                "invalid currency code '{code}', expected three uppercase letters"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = Error;
+
+    fn try_from(code: String) -> Result<Self> {
+        code.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(currency: Currency) -> Self {
+        currency.as_str().to_string()
+    }
+}
+
+impl AsRef<str> for Currency {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Currency({})", self.as_str())
+    }
+}
+
+/// An amount of money in a specific currency.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Money {
+    amount: f64,
+    currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: f64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0.0, currency)
+    }
+
+    pub fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Add two amounts, failing if they are in different currencies.
+    pub fn checked_add(self, other: Money) -> Result<Money> {
+        if self.currency != other.currency {
+            return Err(Error::invalid_data(format!(
+                "cannot add {other} to {self} without conversion"
+            )));
+        }
+        Ok(Money::new(self.amount + other.amount, self.currency))
+    }
+
+    /// The amount rounded to cents.
+    pub fn round_cents(self) -> Money {
+        Money::new((self.amount * 100.0).round() / 100.0, self.currency)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.amount, self.currency)
+    }
+}
+
+/// Conversion rates between currencies.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExchangeRates {
+    /// Currency of objects which do not specify one
+    base: Currency,
+
+    /// Value of one unit of each currency in the base currency
+    rates: BTreeMap<Currency, f64>,
+}
+
+impl Default for ExchangeRates {
+    fn default() -> Self {
+        Self::new(Currency::USD)
+    }
+}
+
+impl ExchangeRates {
+    pub fn new(base: Currency) -> Self {
+        Self {
+            base,
+            rates: BTreeMap::new(),
+        }
+    }
+
+    /// One unit of `currency` is worth `rate` units of the base currency.
+    pub fn with_rate(mut self, currency: Currency, rate: f64) -> Self {
+        self.rates.insert(currency, rate);
+        self
+    }
+
+    pub fn base(&self) -> Currency {
+        self.base
+    }
+
+    /// The currency with the given code, or the base currency if there is none.
+    pub fn resolve(&self, code: Option<&str>) -> Result<Currency> {
+        code.map_or(Ok(self.base), str::parse)
+    }
+
+    /// Value of one unit of `currency` in the base currency.
+    pub fn rate(&self, currency: Currency) -> Result<f64> {
+        if currency == self.base {
+            return Ok(1.0);
+        }
+        self.rates.get(&currency).copied().ok_or_else(|| {
+            Error::invalid_data(format!(
+                "no exchange rate from {currency} to {} configured",
+                self.base
+            ))
+        })
+    }
+
+    /// Convert `money` into `currency`.
+    pub fn convert(&self, money: Money, currency: Currency) -> Result<Money> {
+        if money.currency == currency {
+            return Ok(money);
+        }
+        let amount = money.amount * self.rate(money.currency)? / self.rate(currency)?;
+        Ok(Money::new(amount, currency))
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (currency, rate) in &self.rates {
+            if !rate.is_finite() || *rate <= 0.0 {
+                return Err(Error::invalid_data(format!(
+                    "exchange rate {rate} for {currency} must be positive"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency() -> Result<()> {
+        let eur: Currency = "EUR".parse()?;
+        assert_eq!(eur.to_string(), "EUR");
+        assert!("eur".parse::<Currency>().is_err());
+        assert!("EURO".parse::<Currency>().is_err());
+        assert_eq!(serde_json::to_string(&eur)?, "\"EUR\"");
+        assert_eq!(serde_json::from_str::<Currency>("\"EUR\"")?, eur);
+        assert!(serde_json::from_str::<Currency>("\"E\"").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_exchange_rates() -> Result<()> {
+        let eur: Currency = "EUR".parse()?;
+        let gbp: Currency = "GBP".parse()?;
+        let rates = ExchangeRates::new(Currency::USD)
+            .with_rate(eur, 1.1)
+            .with_rate(gbp, 1.25);
+
+        let price = Money::new(10.0, eur);
+        assert_eq!(rates.convert(price, eur)?, price);
+        assert_eq!(rates.convert(price, Currency::USD)?.amount(), 11.0);
+        assert_eq!(rates.convert(price, gbp)?.round_cents().amount(), 8.8);
+        assert!(rates.convert(price, "JPY".parse()?).is_err());
+
+        assert_eq!(rates.resolve(None)?, Currency::USD);
+        assert_eq!(rates.resolve(Some("GBP"))?, gbp);
+
+        // amounts in different currencies are never mixed silently
+        assert!(price.checked_add(Money::new(1.0, gbp)).is_err());
+        assert_eq!(price.checked_add(Money::new(1.0, eur))?.amount(), 11.0);
+
+        assert!(rates.with_rate(eur, 0.0).validate().is_err());
+        Ok(())
+    }
+}
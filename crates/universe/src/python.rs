@@ -1,9 +1,12 @@
 use itertools::Itertools as _;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use uuid::Uuid;
 
 use crate::{
-    Brand, Ingredient, IngredientQuantity, Instruction, Kitchen, KitchenSetup, MenuItem,
-    SimulationSetup, Site, SiteSetup, Station,
+    Brand, BrandId, Ingredient, IngredientQuantity, Instruction, Kitchen, KitchenId, KitchenSetup,
+    MenuItem, MenuItemId, OrderId, OrderLineId, PersonId, SimulationSetup, Site, SiteId, SiteSetup,
+    Station, StationId,
 };
 
 #[pymethods]
@@ -45,13 +48,20 @@ impl SiteSetup {
 #[pymethods]
 impl Site {
     #[new]
-    #[pyo3(signature = (id, name, latitude, longitude))]
-    fn new(id: String, name: String, latitude: f64, longitude: f64) -> Self {
+    #[pyo3(signature = (id, name, latitude, longitude, currency=None))]
+    fn new(
+        id: String,
+        name: String,
+        latitude: f64,
+        longitude: f64,
+        currency: Option<String>,
+    ) -> Self {
         Site {
             id,
             name,
             latitude,
             longitude,
+            currency,
         }
     }
 
@@ -172,3 +182,89 @@ impl Instruction {
         )
     }
 }
+
+fn parse_uuid(value: &str) -> PyResult<Uuid> {
+    Uuid::try_parse(value)
+        .map_err(|e| PyValueError::new_err(format!("invalid uuid '{value}': {e}")))
+}
+
+/// Expose a typed identifier to Python.
+///
+/// Additional methods specific to an id type can be passed as trailing tokens.
+macro_rules! impl_py_id {
+    ($type:ident $(, $($extra:tt)*)?) => {
+        #[pymethods]
+        impl $type {
+            #[new]
+            fn py_new(value: &str) -> PyResult<Self> {
+                Ok(parse_uuid(value)?.into())
+            }
+
+            /// Create an id from its 16 byte representation.
+            #[staticmethod]
+            fn from_bytes(value: &[u8]) -> PyResult<Self> {
+                Self::try_from(value).map_err(|e| PyValueError::new_err(e.to_string()))
+            }
+
+            /// The 16 byte representation as stored in Arrow data.
+            #[getter]
+            fn bytes(&self) -> Vec<u8> {
+                AsRef::<[u8]>::as_ref(self).to_vec()
+            }
+
+            /// The id as a 32 character hex string.
+            #[getter]
+            fn hex(&self) -> String {
+                AsRef::<Uuid>::as_ref(self).simple().to_string()
+            }
+
+            /// The id as a `urn:uuid:` URN.
+            #[getter]
+            fn urn(&self) -> String {
+                AsRef::<Uuid>::as_ref(self).urn().to_string()
+            }
+
+            fn __str__(&self) -> String {
+                self.to_string()
+            }
+
+            fn __repr__(&self) -> String {
+                format!("{}('{}')", stringify!($type), self)
+            }
+
+            $($($extra)*)?
+        }
+    };
+}
+
+/// Methods for ids derived from a URI reference (e.g. `sites/<name>`).
+macro_rules! impl_py_uri_id {
+    ($type:ident) => {
+        impl_py_id! {
+            $type,
+            /// Create an id from a URI reference.
+            #[staticmethod]
+            #[pyo3(name = "from_uri_ref")]
+            fn py_from_uri_ref(uri_ref: &str) -> Self {
+                Self::from_uri_ref(uri_ref)
+            }
+
+            /// Parse an id from either its UUID or URI reference form.
+            #[staticmethod]
+            fn parse(value: &str) -> Self {
+                Uuid::try_parse(value)
+                    .map(Self::from)
+                    .unwrap_or_else(|_| Self::from_uri_ref(value))
+            }
+        }
+    };
+}
+
+impl_py_uri_id!(SiteId);
+impl_py_uri_id!(KitchenId);
+impl_py_uri_id!(StationId);
+impl_py_uri_id!(BrandId);
+impl_py_uri_id!(MenuItemId);
+impl_py_id!(OrderId);
+impl_py_id!(OrderLineId);
+impl_py_id!(PersonId);
@@ -0,0 +1,76 @@
+//! User-defined agents stepped alongside the built-in ones.
+//!
+//! Sites and the population are advanced by built-in runners. Downstream crates may
+//! add agents of their own via [`SimulationBuilder::with_agent`](super::SimulationBuilder::with_agent),
+//! e.g. a marketing agent raising demand while a campaign runs. Custom agents are
+//! stepped in the order they were added, after the state was updated with the events
+//! of the step, and before the events are written. They observe the step through an
+//! [`AgentStep`] and act on the simulation through its [`SimulationControl`], so their
+//! changes apply from the next step on and are reported as `ConfigChanged` events.
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use caspers_universe::{Agent, AgentStep, Result, SettingsUpdate};
+//!
+//! /// Doubles demand during the first hour of every day.
+//! #[derive(Debug)]
+//! struct Breakfast;
+//!
+//! #[async_trait]
+//! impl Agent for Breakfast {
+//!     fn name(&self) -> &str {
+//!         "breakfast"
+//!     }
+//!
+//!     async fn step(&mut self, step: AgentStep<'_>) -> Result<()> {
+//!         use chrono::Timelike as _;
+//!         let multiplier = if step.time.hour() == 0 { 2.0 } else { 1.0 };
+//!         step.control.update(SettingsUpdate {
+//!             demand_multiplier: Some(multiplier),
+//!             ..Default::default()
+//!         })
+//!     }
+//! }
+//! ```
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::context::SimulationContext;
+use crate::state::State;
+use crate::{EventPayload, Result};
+
+use super::SimulationControl;
+
+/// The simulation as seen by a custom agent during a step.
+#[derive(Clone, Copy)]
+pub struct AgentStep<'a> {
+    /// Time at which the step started
+    pub time: DateTime<Utc>,
+
+    /// Context to query the state and results tables with
+    pub ctx: &'a SimulationContext,
+
+    /// State of the simulation, updated with the events of the step
+    pub state: &'a State,
+
+    /// Events of the step, in the order they were emitted
+    pub events: &'a [EventPayload],
+
+    /// Handle to change the settings of the simulation from the next step on
+    pub control: &'a SimulationControl,
+}
+
+/// An agent stepped by the simulation alongside its sites and population.
+#[async_trait]
+pub trait Agent: Debug + Send + Sync {
+    /// Name of the agent, used to attribute failed steps.
+    fn name(&self) -> &str;
+
+    /// Advance the agent by one step.
+    ///
+    /// An error fails the step of the simulation.
+    async fn step(&mut self, step: AgentStep<'_>) -> Result<()>;
+}
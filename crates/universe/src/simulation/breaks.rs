@@ -0,0 +1,308 @@
+//! Energy and fatigue of couriers.
+//!
+//! Couriers cannot deliver around the clock. Every courier takes a rest break after
+//! working for [`CourierBreaks::rest_after_mins`] without one, and couriers riding
+//! e-bikes stop to charge once their battery runs low. Each delivery drains the
+//! battery by the round trip to the customer and back to the site, so couriers on
+//! long trips need to charge more often. Couriers on a break are not offered
+//! deliveries, which lowers the effective fleet capacity as the day goes on.
+//!
+//! Whether a courier rides an e-bike is derived from their id, so it does not change
+//! between steps or sites. Breaks are reported as
+//! [`CourierBreakPayload`](crate::CourierBreakPayload) events when they start and end.
+//! Energy and fatigue are tracked from the moment a courier is first available at a
+//! site and start from a full battery and a fresh courier in every run.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{PersonId, SiteId};
+use crate::{BreakActivity, BreakReason, Error, EventPayload, Result};
+
+/// Breaks couriers take to recharge their e-bikes and to rest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CourierBreaks {
+    /// Fraction of couriers riding e-bikes, which need charging breaks
+    pub ebike_share: f64,
+
+    /// Kilometers an e-bike covers on a full battery
+    pub battery_range_km: f64,
+
+    /// Battery level, as a fraction of a full charge, below which couriers stop to charge
+    pub charge_below: f64,
+
+    /// Minutes it takes to charge an empty battery
+    pub charge_mins: i64,
+
+    /// Minutes couriers work before they take a rest break
+    pub rest_after_mins: i64,
+
+    /// Minutes of a rest break
+    pub rest_mins: i64,
+}
+
+impl Default for CourierBreaks {
+    fn default() -> Self {
+        Self {
+            ebike_share: 0.5,
+            battery_range_km: 40.0,
+            charge_below: 0.2,
+            charge_mins: 60,
+            rest_after_mins: 240,
+            rest_mins: 30,
+        }
+    }
+}
+
+impl CourierBreaks {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.ebike_share) || !(0.0..=1.0).contains(&self.charge_below) {
+            return Err(Error::invalid_data(
+                "e-bike share and charge level must be between 0 and 1",
+            ));
+        }
+        if self.battery_range_km.is_nan() || self.battery_range_km <= 0.0 {
+            return Err(Error::invalid_data("battery range must be positive"));
+        }
+        if self.rest_after_mins <= 0 {
+            return Err(Error::invalid_data("couriers must work before resting"));
+        }
+        if self.charge_mins < 0 || self.rest_mins < 0 {
+            return Err(Error::invalid_data("break durations must not be negative"));
+        }
+        Ok(())
+    }
+
+    /// Whether the courier rides an e-bike.
+    fn rides_ebike(&self, courier: &PersonId) -> bool {
+        let bytes: &[u8] = courier.as_ref();
+        let draw = u16::from_le_bytes([bytes[14], bytes[15]]) as f64 / (u16::MAX as f64 + 1.0);
+        draw < self.ebike_share
+    }
+}
+
+/// Energy and fatigue of a single courier.
+#[derive(Debug, Clone)]
+struct CourierEnergy {
+    /// Charge of the e-bike as a fraction of a full battery, `None` for other vehicles
+    battery: Option<f64>,
+    /// Start of work since the last break
+    working_since: DateTime<Utc>,
+    /// The break the courier is on, and when it ends
+    on_break: Option<(BreakReason, DateTime<Utc>)>,
+}
+
+/// Energy and fatigue of the couriers working at a site.
+#[derive(Debug, Clone)]
+pub(crate) struct BreakTracker {
+    site_id: SiteId,
+    config: CourierBreaks,
+    couriers: BTreeMap<PersonId, CourierEnergy>,
+}
+
+impl BreakTracker {
+    pub(crate) fn new(site_id: SiteId, config: CourierBreaks) -> Self {
+        Self {
+            site_id,
+            config,
+            couriers: BTreeMap::new(),
+        }
+    }
+
+    /// Number of couriers currently on a break.
+    pub(crate) fn on_break(&self) -> usize {
+        self.couriers
+            .values()
+            .filter(|energy| energy.on_break.is_some())
+            .count()
+    }
+
+    /// Earliest time a break ends.
+    pub(crate) fn next_end(&self) -> Option<DateTime<Utc>> {
+        self.couriers
+            .values()
+            .filter_map(|energy| energy.on_break.as_ref().map(|(_, until)| *until))
+            .min()
+    }
+
+    /// End breaks which are over at `now`.
+    pub(crate) fn end_breaks(&mut self, now: DateTime<Utc>) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        for (courier, energy) in self.couriers.iter_mut() {
+            let Some((reason, _)) = energy.on_break.take_if(|(_, until)| *until <= now) else {
+                continue;
+            };
+            if reason == BreakReason::Charging {
+                energy.battery = Some(1.0);
+            }
+            // charging also gives couriers a rest
+            energy.working_since = now;
+            events.push(EventPayload::courier_break(
+                *courier,
+                self.site_id,
+                reason,
+                BreakActivity::Ended,
+            ));
+        }
+        events
+    }
+
+    /// Whether the courier can be offered deliveries, starting a break if they need one.
+    ///
+    /// Couriers not seen before start working at `now`.
+    pub(crate) fn check_available(
+        &mut self,
+        courier: PersonId,
+        now: DateTime<Utc>,
+        events: &mut Vec<EventPayload>,
+    ) -> bool {
+        let config = &self.config;
+        let energy = self
+            .couriers
+            .entry(courier)
+            .or_insert_with(|| CourierEnergy {
+                battery: config.rides_ebike(&courier).then_some(1.0),
+                working_since: now,
+                on_break: None,
+            });
+        if energy.on_break.is_some() {
+            return false;
+        }
+
+        let (reason, duration) = match energy.battery {
+            Some(battery) if battery < config.charge_below => {
+                let mins = config.charge_mins as f64 * (1.0 - battery.max(0.0));
+                (
+                    BreakReason::Charging,
+                    Duration::seconds((mins * 60.0) as i64),
+                )
+            }
+            _ if now - energy.working_since >= Duration::minutes(config.rest_after_mins) => {
+                (BreakReason::Rest, Duration::minutes(config.rest_mins))
+            }
+            _ => return true,
+        };
+        energy.on_break = Some((reason, now + duration));
+        events.push(EventPayload::courier_break(
+            courier,
+            self.site_id,
+            reason,
+            BreakActivity::Started,
+        ));
+        false
+    }
+
+    /// Drain the courier's battery for a delivery route of `distance_m` meters.
+    ///
+    /// Couriers return to the site after each delivery, so the route is ridden twice.
+    pub(crate) fn deliver(&mut self, courier: &PersonId, distance_m: f64) {
+        let range_m = self.config.battery_range_km * 1000.0;
+        if let Some(battery) = self
+            .couriers
+            .get_mut(courier)
+            .and_then(|energy| energy.battery.as_mut())
+        {
+            *battery -= 2.0 * distance_m.max(0.0) / range_m;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(events: &[EventPayload]) -> Vec<(BreakReason, BreakActivity)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::CourierBreak(payload) => Some((payload.reason, payload.activity)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_breaks() {
+        let config = CourierBreaks {
+            ebike_share: 1.0,
+            battery_range_km: 10.0,
+            charge_below: 0.2,
+            charge_mins: 60,
+            rest_after_mins: 120,
+            rest_mins: 30,
+        };
+        let mut tracker = BreakTracker::new(SiteId::from_name("london"), config.clone());
+        let courier = PersonId::new();
+        let start = Utc::now();
+        let mut events = Vec::new();
+
+        assert!(tracker.check_available(courier, start, &mut events));
+        assert!(events.is_empty());
+
+        // each round trip of 2 x 1.5km drains 30% of the battery
+        tracker.deliver(&courier, 1_500.0);
+        tracker.deliver(&courier, 1_500.0);
+        assert!(tracker.check_available(courier, start, &mut events));
+        tracker.deliver(&courier, 1_500.0);
+        assert!(!tracker.check_available(courier, start, &mut events));
+        assert_eq!(
+            kinds(&events),
+            vec![(BreakReason::Charging, BreakActivity::Started)]
+        );
+        assert_eq!(tracker.on_break(), 1);
+
+        // charging from 10% takes 54 minutes
+        assert!(tracker.end_breaks(start + Duration::minutes(50)).is_empty());
+        let ended = tracker.end_breaks(start + Duration::minutes(54));
+        assert_eq!(
+            kinds(&ended),
+            vec![(BreakReason::Charging, BreakActivity::Ended)]
+        );
+        assert_eq!(tracker.on_break(), 0);
+
+        // couriers rest after working for two hours since their last break
+        let charged = start + Duration::minutes(54);
+        events.clear();
+        assert!(tracker.check_available(courier, charged + Duration::minutes(119), &mut events));
+        assert!(!tracker.check_available(courier, charged + Duration::minutes(120), &mut events));
+        assert_eq!(
+            kinds(&events),
+            vec![(BreakReason::Rest, BreakActivity::Started)]
+        );
+        let ended = tracker.end_breaks(charged + Duration::minutes(150));
+        assert_eq!(
+            kinds(&ended),
+            vec![(BreakReason::Rest, BreakActivity::Ended)]
+        );
+
+        // couriers without e-bikes only rest
+        let mut tracker = BreakTracker::new(
+            SiteId::from_name("london"),
+            CourierBreaks {
+                ebike_share: 0.0,
+                ..config
+            },
+        );
+        assert!(tracker.check_available(courier, start, &mut events));
+        tracker.deliver(&courier, 100_000.0);
+        assert!(tracker.check_available(courier, start, &mut events));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(CourierBreaks::default().validate().is_ok());
+        let invalid = CourierBreaks {
+            ebike_share: 1.5,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+        let invalid = CourierBreaks {
+            battery_range_km: 0.0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
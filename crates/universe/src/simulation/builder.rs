@@ -1,18 +1,55 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use arrow::compute::concat_batches;
 use chrono::{DateTime, Duration, Utc};
 use datafusion::prelude::{col, lit};
 use itertools::Itertools as _;
+use rand::SeedableRng as _;
+use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
 use url::Url;
 
 use crate::agents::{PopulationRunner, SiteRunner};
 use crate::context::SimulationContext;
-use crate::state::{EntityView, RoutingData, State};
-use crate::{Error, EventTracker, ObjectData, OrderData, PopulationData, Result};
+use crate::state::{
+    DEFAULT_JOURNEY_TOLERANCE_M, EntityView, PersonRole, RoadClosure, RoutingData, State,
+};
+use crate::{
+    Error, EventPayload, EventTracker, ExchangeRates, ObjectData, OrderData, PopulationData,
+    Result, ResultExt as _, resolve_url,
+};
 
-use super::{EventStatsBuffer, Simulation};
+use super::bus::EventBus;
+use super::carbon::FootprintTracker;
+use super::compensation::Compensator;
+use super::controls::Controls;
+use super::daily_summary::DailySummary;
+use super::event_writer::EventTableWriter;
+use super::feedback::FeedbackCollector;
+use super::green::GreenDeliveryReport;
+use super::heatmap::heatmap_resolution;
+use super::inventory::IngredientInventory;
+use super::invoices::Invoicer;
+use super::kpis::KpiRecorder;
+use super::lifecycle::CustomerLifecycle;
+use super::memberships::MembershipBilling;
+use super::mobility::Mobility;
+use super::notifications::Notifier;
+use super::quarantine::SiteQuarantine;
+use super::sessions::AppSessions;
+use super::verify::EventDigest;
+use super::{
+    AdaptiveTimeStep, Agent, BehaviorHooks, BehaviorPlugin, Calendar, CalendarConfig, Campaign,
+    CarbonConfig, CompensationPolicy, CourierAcceptance, CourierBreaks, CuisinePreferences,
+    DEFAULT_CHURN_AFTER, DEFAULT_HEATMAP_RESOLUTION, DEFAULT_SITE_FAILURE_THRESHOLD,
+    DarkStoreConfig, DeliveryRobots, DestinationConfig, Destinations, DispatchPolicy,
+    EventCallback, EventCoalescing, EventFilter, EventKind, EventScheduler, EventStatsBuffer,
+    FeedbackConfig, GreenDeliveryConfig, InventoryConfig, InvoiceConfig, MembershipConfig,
+    MobilityConfig, NotificationConfig, PackingConfig, PriorityConfig, RuntimeSettings, Scenario,
+    SeasonalityConfig, SessionConfig, Simulation, TippingModel,
+};
 
 /// Execution mode for the simulation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -36,7 +73,193 @@ pub struct SimulationConfig {
 
     pub(crate) dry_run: bool,
 
+    /// Whether to write the events of the run, never written in dry runs
     pub(crate) write_events: bool,
+
+    /// Report state size and cardinality every n steps
+    pub(crate) state_stats_interval: Option<usize>,
+
+    /// Include registered tables in periodic state stats reports
+    #[serde(default)]
+    pub(crate) table_stats: bool,
+
+    /// SQL expressions customizing demand and customer behavior
+    #[serde(default)]
+    pub(crate) hooks: BehaviorHooks,
+
+    /// Promotional campaigns discounting new orders
+    #[serde(default)]
+    pub(crate) campaigns: Vec<Campaign>,
+
+    /// Consecutive failed steps after which a site is quarantined
+    #[serde(default = "default_site_failure_threshold")]
+    pub(crate) site_failure_threshold: usize,
+
+    /// Model of couriers accepting delivery offers
+    #[serde(default)]
+    pub(crate) courier_acceptance: CourierAcceptance,
+
+    /// Rules for offering deliveries to couriers
+    #[serde(default)]
+    pub(crate) dispatch: DispatchPolicy,
+
+    /// Packing stations of sites and the time it takes to pack orders
+    #[serde(default)]
+    pub(crate) packing: PackingConfig,
+
+    /// Charging and rest breaks of couriers
+    #[serde(default)]
+    pub(crate) courier_breaks: CourierBreaks,
+
+    /// Robots and drones delivering orders, keyed by the name of the site operating them
+    #[serde(default)]
+    pub(crate) delivery_robots: HashMap<String, DeliveryRobots>,
+
+    /// Sites run as grocery dark stores, keyed by site name
+    #[serde(default)]
+    pub(crate) dark_stores: HashMap<String, DarkStoreConfig>,
+
+    /// Streets closed during time windows, avoided when planning routes
+    #[serde(default)]
+    pub(crate) road_closures: Vec<RoadClosure>,
+
+    /// Model of customers tipping on their orders
+    #[serde(default)]
+    pub(crate) tipping: TippingModel,
+
+    /// Tax settings of invoices for delivered orders
+    #[serde(default)]
+    pub(crate) invoicing: InvoiceConfig,
+
+    /// Rules issuing vouchers for late deliveries
+    #[serde(default)]
+    pub(crate) compensation: CompensationPolicy,
+
+    /// Rating prompts and response behavior of customers
+    #[serde(default)]
+    pub(crate) feedback: FeedbackConfig,
+
+    /// Relative weights with which customers choose menu items of each cuisine
+    #[serde(default)]
+    pub(crate) cuisine_preferences: CuisinePreferences,
+
+    /// Conversion rates between the currencies of sites and menus
+    #[serde(default)]
+    pub(crate) exchange_rates: ExchangeRates,
+
+    /// Events written to the results
+    #[serde(default)]
+    pub(crate) event_filter: EventFilter,
+
+    /// Tolerance in meters within which journeys in written events are simplified
+    #[serde(default = "default_journey_tolerance")]
+    pub(crate) journey_tolerance_m: Option<f64>,
+
+    /// Buffer events across steps to write them in fewer, larger files
+    #[serde(default)]
+    pub(crate) event_coalescing: Option<EventCoalescing>,
+
+    /// H3 resolution of the order heatmap materialized after each run
+    #[serde(default = "default_heatmap_resolution")]
+    pub(crate) heatmap_resolution: Option<u8>,
+
+    /// Roll up orders, revenue and courier load per simulated day while running
+    #[serde(default)]
+    pub(crate) daily_summary: bool,
+
+    /// Time without orders after which customers are marked as churned
+    #[serde(default = "default_churn_after")]
+    pub(crate) churn_after: Option<Duration>,
+
+    /// Notifications sent to customers at order milestones
+    #[serde(default = "default_notifications")]
+    pub(crate) notifications: Option<NotificationConfig>,
+
+    /// Activity schedule of customers moving around between orders
+    #[serde(default)]
+    pub(crate) mobility: Option<MobilityConfig>,
+
+    /// Homes and workplaces of customers orders are delivered to
+    #[serde(default)]
+    pub(crate) destinations: Option<DestinationConfig>,
+
+    /// Shares of customers in the priority tiers, all customers are normal if not set
+    #[serde(default)]
+    pub(crate) priority: Option<PriorityConfig>,
+
+    /// Delivery memberships held by a share of customers, none if not set
+    #[serde(default)]
+    pub(crate) memberships: Option<MembershipConfig>,
+
+    /// App sessions preceding orders, no sessions are generated if not set
+    #[serde(default)]
+    pub(crate) sessions: Option<SessionConfig>,
+
+    /// Ingredient stock of the sites, not tracked if not set
+    #[serde(default)]
+    pub(crate) inventory: Option<InventoryConfig>,
+
+    /// Advance steps from event to event, every step covers the time increment if not set
+    #[serde(default)]
+    pub(crate) scheduler: Option<EventScheduler>,
+
+    /// Lengthen steps while the simulation is quiet, every step covers the time
+    /// increment if not set
+    #[serde(default)]
+    pub(crate) adaptive_step: Option<AdaptiveTimeStep>,
+
+    /// Emission factors of deliveries, no footprint is estimated if not set
+    #[serde(default)]
+    pub(crate) carbon: Option<CarbonConfig>,
+
+    /// Green delivery offered at checkout, all orders are delivered on their own if not set
+    #[serde(default)]
+    pub(crate) green_delivery: Option<GreenDeliveryConfig>,
+
+    /// Holidays and special events shifting demand, courier supply and roads
+    #[serde(default)]
+    pub(crate) calendar: Option<CalendarConfig>,
+
+    /// Variation of demand and weather over the year, demand is the same all year if not set
+    #[serde(default)]
+    pub(crate) seasonality: Option<SeasonalityConfig>,
+
+    /// Seed of all random choices, runs from the same state and seed are reproducible
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+
+    /// Take a snapshot every n steps of a run, only at the end of runs if not set
+    #[serde(default)]
+    pub(crate) snapshot_interval: Option<usize>,
+
+    /// Take a checkpoint snapshot every n simulated days of a run, independent of the
+    /// snapshot interval
+    #[serde(default)]
+    pub(crate) checkpoint_days: Option<u32>,
+
+    /// Settings the simulation starts with, which may be changed through its controls
+    #[serde(default)]
+    pub(crate) settings: RuntimeSettings,
+}
+
+fn default_site_failure_threshold() -> usize {
+    DEFAULT_SITE_FAILURE_THRESHOLD
+}
+
+fn default_heatmap_resolution() -> Option<u8> {
+    Some(DEFAULT_HEATMAP_RESOLUTION)
+}
+
+fn default_journey_tolerance() -> Option<f64> {
+    Some(DEFAULT_JOURNEY_TOLERANCE_M)
+}
+
+fn default_churn_after() -> Option<Duration> {
+    Some(DEFAULT_CHURN_AFTER)
+}
+
+fn default_notifications() -> Option<NotificationConfig> {
+    Some(NotificationConfig::default())
 }
 
 impl Default for SimulationConfig {
@@ -45,7 +268,48 @@ impl Default for SimulationConfig {
             simulation_start: Utc::now(),
             time_increment: Duration::seconds(60),
             dry_run: false,
-            write_events: false,
+            write_events: true,
+            state_stats_interval: None,
+            table_stats: false,
+            hooks: BehaviorHooks::default(),
+            campaigns: Vec::new(),
+            site_failure_threshold: DEFAULT_SITE_FAILURE_THRESHOLD,
+            courier_acceptance: CourierAcceptance::default(),
+            dispatch: DispatchPolicy::default(),
+            packing: PackingConfig::default(),
+            courier_breaks: CourierBreaks::default(),
+            delivery_robots: HashMap::new(),
+            dark_stores: HashMap::new(),
+            road_closures: Vec::new(),
+            tipping: TippingModel::default(),
+            invoicing: InvoiceConfig::default(),
+            compensation: CompensationPolicy::default(),
+            feedback: FeedbackConfig::default(),
+            cuisine_preferences: CuisinePreferences::default(),
+            exchange_rates: ExchangeRates::default(),
+            event_filter: EventFilter::default(),
+            journey_tolerance_m: default_journey_tolerance(),
+            event_coalescing: None,
+            heatmap_resolution: default_heatmap_resolution(),
+            daily_summary: false,
+            churn_after: default_churn_after(),
+            notifications: default_notifications(),
+            mobility: None,
+            destinations: None,
+            priority: None,
+            memberships: None,
+            sessions: None,
+            inventory: None,
+            scheduler: None,
+            adaptive_step: None,
+            carbon: None,
+            green_delivery: None,
+            calendar: None,
+            seasonality: None,
+            seed: None,
+            snapshot_interval: None,
+            checkpoint_days: None,
+            settings: RuntimeSettings::default(),
         }
     }
 }
@@ -68,6 +332,142 @@ pub struct SimulationBuilder {
 
     /// Whether to write events to the event tracker
     write_events: bool,
+
+    /// Report state size and cardinality every n steps
+    state_stats_interval: Option<usize>,
+
+    /// Whether periodic state stats reports scan all registered tables
+    table_stats: bool,
+
+    /// SQL expressions customizing demand and customer behavior
+    hooks: BehaviorHooks,
+
+    /// Promotional campaigns discounting new orders
+    campaigns: Vec<Campaign>,
+
+    /// Consecutive failed steps after which a site is quarantined
+    site_failure_threshold: usize,
+
+    /// Model of couriers accepting delivery offers
+    courier_acceptance: CourierAcceptance,
+
+    /// Rules for offering deliveries to couriers
+    dispatch: DispatchPolicy,
+
+    /// Packing stations of sites and the time it takes to pack orders
+    packing: PackingConfig,
+
+    /// Charging and rest breaks of couriers
+    courier_breaks: CourierBreaks,
+
+    /// Robots and drones delivering orders, keyed by the name of the site operating them
+    delivery_robots: HashMap<String, DeliveryRobots>,
+
+    /// Sites run as grocery dark stores, keyed by site name
+    dark_stores: HashMap<String, DarkStoreConfig>,
+
+    /// Streets closed during time windows, avoided when planning routes
+    road_closures: Vec<RoadClosure>,
+
+    /// Model of customers tipping on their orders
+    tipping: TippingModel,
+
+    /// Tax settings of invoices for delivered orders
+    invoicing: InvoiceConfig,
+
+    /// Rules issuing vouchers for late deliveries
+    compensation: CompensationPolicy,
+
+    /// Rating prompts and response behavior of customers
+    feedback: FeedbackConfig,
+
+    /// Relative weights with which customers choose menu items of each cuisine
+    cuisine_preferences: CuisinePreferences,
+
+    /// Conversion rates between the currencies of sites and menus
+    exchange_rates: ExchangeRates,
+
+    /// Events written to the results
+    event_filter: EventFilter,
+
+    /// Tolerance in meters within which journeys in written events are simplified
+    journey_tolerance_m: Option<f64>,
+    event_coalescing: Option<EventCoalescing>,
+
+    /// H3 resolution of the order heatmap materialized after each run
+    heatmap_resolution: Option<u8>,
+
+    /// Roll up orders, revenue and courier load per simulated day while running
+    daily_summary: bool,
+
+    /// Time without orders after which customers are marked as churned
+    churn_after: Option<Duration>,
+
+    /// Notifications sent to customers at order milestones
+    notifications: Option<NotificationConfig>,
+
+    /// Activity schedule of customers moving around between orders
+    mobility: Option<MobilityConfig>,
+
+    /// Homes and workplaces of customers orders are delivered to
+    destinations: Option<DestinationConfig>,
+
+    /// Shares of customers in the priority tiers
+    priority: Option<PriorityConfig>,
+
+    /// Delivery memberships held by a share of customers
+    memberships: Option<MembershipConfig>,
+
+    /// App sessions preceding orders
+    sessions: Option<SessionConfig>,
+
+    /// Ingredient stock of the sites
+    inventory: Option<InventoryConfig>,
+
+    /// Event-driven stepping
+    scheduler: Option<EventScheduler>,
+
+    /// Steps growing in quiet periods
+    adaptive_step: Option<AdaptiveTimeStep>,
+
+    /// Carbon footprint of deliveries
+    carbon: Option<CarbonConfig>,
+
+    /// Green delivery offered at checkout
+    green_delivery: Option<GreenDeliveryConfig>,
+
+    /// Holidays and special events
+    calendar: Option<CalendarConfig>,
+
+    /// Variation of demand and weather over the year
+    seasonality: Option<SeasonalityConfig>,
+
+    /// Seed of all random choices
+    seed: Option<u64>,
+
+    /// Take a snapshot every n steps of a run
+    snapshot_interval: Option<usize>,
+
+    /// Take a checkpoint every n simulated days of a run
+    checkpoint_days: Option<u32>,
+
+    /// Settings the simulation starts with
+    settings: RuntimeSettings,
+
+    /// Sites and brands the setup must contain
+    scenario: Option<Scenario>,
+
+    /// Configuration replacing all of the above, e.g. as recorded by a seeded run
+    config: Option<SimulationConfig>,
+
+    /// Plugin customizing behavior models
+    plugin: Option<Arc<dyn BehaviorPlugin>>,
+
+    /// User-defined agents stepped alongside the built-in ones
+    agents: Vec<Box<dyn Agent>>,
+
+    /// Callbacks called for the events of their kind
+    event_callbacks: Vec<(EventKind, EventCallback)>,
 }
 
 impl Default for SimulationBuilder {
@@ -78,7 +478,53 @@ impl Default for SimulationBuilder {
             start_time: Utc::now(),
             working_directory: None,
             dry_run: false,
-            write_events: false,
+            write_events: true,
+            state_stats_interval: None,
+            table_stats: false,
+            hooks: BehaviorHooks::default(),
+            campaigns: Vec::new(),
+            site_failure_threshold: DEFAULT_SITE_FAILURE_THRESHOLD,
+            courier_acceptance: CourierAcceptance::default(),
+            dispatch: DispatchPolicy::default(),
+            packing: PackingConfig::default(),
+            courier_breaks: CourierBreaks::default(),
+            delivery_robots: HashMap::new(),
+            dark_stores: HashMap::new(),
+            road_closures: Vec::new(),
+            tipping: TippingModel::default(),
+            invoicing: InvoiceConfig::default(),
+            compensation: CompensationPolicy::default(),
+            feedback: FeedbackConfig::default(),
+            cuisine_preferences: CuisinePreferences::default(),
+            exchange_rates: ExchangeRates::default(),
+            event_filter: EventFilter::default(),
+            journey_tolerance_m: default_journey_tolerance(),
+            event_coalescing: None,
+            heatmap_resolution: default_heatmap_resolution(),
+            daily_summary: false,
+            churn_after: default_churn_after(),
+            notifications: default_notifications(),
+            mobility: None,
+            destinations: None,
+            priority: None,
+            memberships: None,
+            sessions: None,
+            inventory: None,
+            scheduler: None,
+            adaptive_step: None,
+            carbon: None,
+            green_delivery: None,
+            calendar: None,
+            seasonality: None,
+            seed: None,
+            snapshot_interval: None,
+            checkpoint_days: None,
+            settings: RuntimeSettings::default(),
+            scenario: None,
+            config: None,
+            plugin: None,
+            agents: Vec::new(),
+            event_callbacks: Vec::new(),
         }
     }
 }
@@ -127,12 +573,409 @@ impl SimulationBuilder {
         self
     }
 
+    /// Log a state size and cardinality report every `interval` steps
+    pub fn with_state_stats_interval(mut self, interval: impl Into<Option<usize>>) -> Self {
+        self.state_stats_interval = interval.into().filter(|i| *i > 0);
+        self
+    }
+
+    /// Include row and null counts of all registered tables in periodic state stats reports.
+    ///
+    /// Computing these scans every results and snapshot table, so the cost of each report
+    /// grows with the length of the run.
+    pub fn with_table_stats(mut self, table_stats: bool) -> Self {
+        self.table_stats = table_stats;
+        self
+    }
+
+    /// Customize demand and customer behavior via SQL expressions
+    pub fn with_hooks(mut self, hooks: BehaviorHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Discount new orders matching any of the campaigns
+    pub fn with_campaigns(mut self, campaigns: impl IntoIterator<Item = Campaign>) -> Self {
+        self.campaigns = campaigns.into_iter().collect();
+        self
+    }
+
+    /// Quarantine sites whose steps fail `threshold` times in a row.
+    ///
+    /// Quarantined sites stop receiving orders for the rest of the run, while all
+    /// other sites continue.
+    pub fn with_site_failure_threshold(mut self, threshold: usize) -> Self {
+        self.site_failure_threshold = threshold;
+        self
+    }
+
+    /// Model couriers accepting delivery offers with `acceptance`
+    pub fn with_courier_acceptance(mut self, acceptance: CourierAcceptance) -> Self {
+        self.courier_acceptance = acceptance;
+        self
+    }
+
+    /// Offer deliveries to couriers according to `policy`
+    pub fn with_dispatch_policy(mut self, policy: DispatchPolicy) -> Self {
+        self.dispatch = policy;
+        self
+    }
+
+    /// Send couriers on charging and rest breaks according to `breaks`
+    pub fn with_courier_breaks(mut self, breaks: CourierBreaks) -> Self {
+        self.courier_breaks = breaks;
+        self
+    }
+
+    /// Deliver orders with the robots or drones configured for each site (experimental)
+    ///
+    /// Fleets are keyed by the name of the site operating them. Sites without a fleet
+    /// deliver all orders with couriers.
+    pub fn with_delivery_robots(mut self, robots: HashMap<String, DeliveryRobots>) -> Self {
+        self.delivery_robots = robots;
+        self
+    }
+
+    /// Run the sites named in `dark_stores` as grocery dark stores
+    ///
+    /// Dark stores pick the menu items of their brands from inventory instead of
+    /// cooking them in kitchens, suggesting substitutes for items out of stock.
+    /// All other sites keep cooking.
+    pub fn with_dark_stores(mut self, dark_stores: HashMap<String, DarkStoreConfig>) -> Self {
+        self.dark_stores = dark_stores;
+        self
+    }
+
+    /// Avoid the streets closed by `closures` when planning routes within their windows
+    pub fn with_road_closures(mut self, closures: Vec<RoadClosure>) -> Self {
+        self.road_closures = closures;
+        self
+    }
+
+    /// Pack orders at sites according to `packing`
+    pub fn with_packing(mut self, packing: PackingConfig) -> Self {
+        self.packing = packing;
+        self
+    }
+
+    /// Sample tips of new orders from `tipping`
+    ///
+    /// A tip hook configured via [`with_hooks`](Self::with_hooks)
+    /// takes precedence over the model.
+    pub fn with_tipping_model(mut self, tipping: TippingModel) -> Self {
+        self.tipping = tipping;
+        self
+    }
+
+    /// Issue invoices for delivered orders according to `invoicing`
+    pub fn with_invoice_config(mut self, invoicing: InvoiceConfig) -> Self {
+        self.invoicing = invoicing;
+        self
+    }
+
+    /// Issue vouchers for late deliveries according to the rules of `policy`
+    pub fn with_compensation_policy(mut self, policy: CompensationPolicy) -> Self {
+        self.compensation = policy;
+        self
+    }
+
+    /// Prompt customers for ratings of delivered orders according to `feedback`
+    pub fn with_feedback(mut self, feedback: FeedbackConfig) -> Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Let customers choose menu items according to their `preferences` for cuisines
+    pub fn with_cuisine_preferences(mut self, preferences: CuisinePreferences) -> Self {
+        self.cuisine_preferences = preferences;
+        self
+    }
+
+    /// Convert prices between currencies with `rates`
+    ///
+    /// Menu prices are converted into the currency of the ordering site, and
+    /// invoices report their totals in the base currency of the rates as well.
+    pub fn with_exchange_rates(mut self, rates: ExchangeRates) -> Self {
+        self.exchange_rates = rates;
+        self
+    }
+
+    /// Only write the events selected by `filter`
+    ///
+    /// Metrics and the simulation state still account for every event.
+    pub fn with_event_filter(mut self, filter: EventFilter) -> Self {
+        self.event_filter = filter;
+        self
+    }
+
+    /// Simplify the journeys of written events to within `tolerance_m` meters
+    ///
+    /// Journeys are kept at full resolution while the simulation runs. Pass `None`
+    /// to store every vertex of the street network.
+    pub fn with_journey_tolerance(mut self, tolerance_m: impl Into<Option<f64>>) -> Self {
+        self.journey_tolerance_m = tolerance_m.into();
+        self
+    }
+
+    /// Buffer the events of consecutive steps and write them in fewer, larger files
+    ///
+    /// Buffered events are written once they reach the target number of rows or age,
+    /// and with the other results, e.g. before snapshots and at the end of a run.
+    /// Until then, they are missing from the `events` table.
+    pub fn with_event_coalescing(mut self, coalescing: impl Into<Option<EventCoalescing>>) -> Self {
+        self.event_coalescing = coalescing.into();
+        self
+    }
+
+    /// Materialize the order heatmap at the given H3 resolution after each run
+    ///
+    /// Pass `None` to skip the materialization.
+    pub fn with_heatmap_resolution(mut self, resolution: impl Into<Option<u8>>) -> Self {
+        self.heatmap_resolution = resolution.into();
+        self
+    }
+
+    /// Write orders, revenue, on-time rate and courier utilization of each simulated
+    /// day to the `daily_summary` table as the run passes midnight
+    pub fn with_daily_summary(mut self, daily_summary: bool) -> Self {
+        self.daily_summary = daily_summary;
+        self
+    }
+
+    /// Mark customers as churned when they did not order for `churn_after`
+    ///
+    /// Pass `None` to never mark customers as churned.
+    pub fn with_churn_after(mut self, churn_after: impl Into<Option<Duration>>) -> Self {
+        self.churn_after = churn_after.into();
+        self
+    }
+
+    /// Notify customers at order milestones according to `notifications`
+    ///
+    /// Pass `None` to not send any notifications.
+    pub fn with_notifications(
+        mut self,
+        notifications: impl Into<Option<NotificationConfig>>,
+    ) -> Self {
+        self.notifications = notifications.into();
+        self
+    }
+
+    /// Send idle customers on trips according to the activity schedule of `mobility`
+    ///
+    /// Pass `None` to keep customers in place between orders.
+    pub fn with_mobility(mut self, mobility: impl Into<Option<MobilityConfig>>) -> Self {
+        self.mobility = mobility.into();
+        self
+    }
+
+    /// Deliver orders to the homes, workplaces or visited spots of customers per `destinations`
+    ///
+    /// Pass `None` to deliver orders to wherever customers are when ordering.
+    pub fn with_destinations(mut self, destinations: impl Into<Option<DestinationConfig>>) -> Self {
+        self.destinations = destinations.into();
+        self
+    }
+
+    /// Assign customers to the priority tiers per `priority`
+    ///
+    /// Kitchens and dispatchers serve orders of higher tiers first. Pass `None` to
+    /// place all orders in the normal tier.
+    pub fn with_priority_tiers(mut self, priority: impl Into<Option<PriorityConfig>>) -> Self {
+        self.priority = priority.into();
+        self
+    }
+
+    /// Sell delivery memberships to a share of customers per `memberships`
+    ///
+    /// Members are billed monthly, have delivery fees waived and order more often.
+    /// Pass `None` to not charge any delivery or membership fees.
+    pub fn with_memberships(mut self, memberships: impl Into<Option<MembershipConfig>>) -> Self {
+        self.memberships = memberships.into();
+        self
+    }
+
+    /// Report the app sessions of customers per `sessions`
+    ///
+    /// Every order is preceded by a session passing through the browse, cart and
+    /// checkout stages, and idle customers abandon sessions without ordering. Pass
+    /// `None` to not report any sessions.
+    pub fn with_sessions(mut self, sessions: impl Into<Option<SessionConfig>>) -> Self {
+        self.sessions = sessions.into();
+        self
+    }
+
+    /// Track the ingredient stock of the sites per `inventory`
+    ///
+    /// Preparing order lines consumes ingredients, which are replenished daily, and
+    /// the stock movements of each simulated day are written to the
+    /// `ingredient_inventory` table. Pass `None` to not track any stock.
+    pub fn with_inventory(mut self, inventory: impl Into<Option<InventoryConfig>>) -> Self {
+        self.inventory = inventory.into();
+        self
+    }
+
+    /// Advance each step to the next scheduled event per `scheduler`
+    ///
+    /// Instead of covering a single time increment, steps end once an agent is due
+    /// to change on its own, so quiet periods pass in few steps. Pass `None` to
+    /// advance every step by the time increment.
+    pub fn with_event_scheduler(mut self, scheduler: impl Into<Option<EventScheduler>>) -> Self {
+        self.scheduler = scheduler.into();
+        self
+    }
+
+    /// Lengthen steps while the simulation is quiet per `adaptive`
+    ///
+    /// While no order is open and nobody is on their way, e.g. at night, every step
+    /// grows up to the longest step, and steps return to the shortest step as soon
+    /// as there is activity. Cannot be combined with the event scheduler. Pass `None`
+    /// to advance every step by the time increment.
+    pub fn with_adaptive_step(mut self, adaptive: impl Into<Option<AdaptiveTimeStep>>) -> Self {
+        self.adaptive_step = adaptive.into();
+        self
+    }
+
+    /// Estimate the carbon footprint of deliveries per `carbon`
+    ///
+    /// The footprint of each delivery is recorded with its order, and rolled up per
+    /// site, mode of transport and day in the `delivery_footprint` table. Pass `None`
+    /// to not estimate any footprint.
+    pub fn with_carbon(mut self, carbon: impl Into<Option<CarbonConfig>>) -> Self {
+        self.carbon = carbon.into();
+        self
+    }
+
+    /// Offer green delivery at checkout per `green`
+    ///
+    /// Customers choosing it accept a longer delivery window, and their orders are
+    /// delivered in bundles. Deliveries and emissions of green and standard orders
+    /// are rolled up per site and day in the `green_delivery` table. Pass `None` to
+    /// deliver all orders on their own.
+    pub fn with_green_delivery(mut self, green: impl Into<Option<GreenDeliveryConfig>>) -> Self {
+        self.green_delivery = green.into();
+        self
+    }
+
+    /// Follow the holidays and special events of `calendar`
+    ///
+    /// Holidays and events scale the demand and courier supply of the sites while
+    /// they last, and streets closed for events are avoided like road closures.
+    /// Pass `None` to run without a calendar.
+    pub fn with_calendar(mut self, calendar: impl Into<Option<CalendarConfig>>) -> Self {
+        self.calendar = calendar.into();
+        self
+    }
+
+    /// Vary demand and the weather over the year per `seasonality`
+    ///
+    /// Demand follows monthly factors and school holidays, and rises in rainy hours,
+    /// which are more frequent in the wet months. Pass `None` to keep demand the same
+    /// all year.
+    pub fn with_seasonality(mut self, seasonality: impl Into<Option<SeasonalityConfig>>) -> Self {
+        self.seasonality = seasonality.into();
+        self
+    }
+
+    /// Draw all random choices of the simulation from `seed`
+    ///
+    /// Runs starting from the same snapshot at the same time with the same configuration
+    /// and seed produce the same events and snapshots. Pass `None` to seed from entropy.
+    pub fn with_seed(mut self, seed: impl Into<Option<u64>>) -> Self {
+        self.seed = seed.into();
+        self
+    }
+
+    /// Build the simulation with `config` instead of the configuration set on the builder
+    pub(crate) fn with_config(mut self, config: SimulationConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Take a snapshot every `interval` steps of a run in addition to the one at its end
+    pub fn with_snapshot_interval(mut self, interval: impl Into<Option<usize>>) -> Self {
+        self.snapshot_interval = interval.into().filter(|i| *i > 0);
+        self
+    }
+
+    /// Checkpoint the state whenever a run completes `days` simulated days
+    ///
+    /// Checkpoints are taken at midnight UTC, independent of the snapshot interval, and
+    /// are marked as such in the snapshot metadata, so a crashed backfill loses at most
+    /// `days` of simulated time when resumed from its latest checkpoint.
+    pub fn with_checkpoint_days(mut self, days: impl Into<Option<u32>>) -> Self {
+        self.checkpoint_days = days.into().filter(|days| *days > 0);
+        self
+    }
+
+    /// Start the simulation with `settings`, e.g. to fail orders from the first step
+    ///
+    /// The settings may still be changed through the [`Simulation::control`] handle.
+    pub fn with_runtime_settings(mut self, settings: RuntimeSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Apply the time resolution, snapshot interval, failure profile and output
+    /// locations of `scenario`
+    ///
+    /// Building the simulation fails if the sites and brands of the scenario are not
+    /// part of the setup. A context passed via [`with_context`](Self::with_context)
+    /// takes precedence over the output locations of the scenario.
+    pub fn with_scenario(mut self, scenario: Scenario) -> Self {
+        if let Some(start) = scenario.start {
+            self.start_time = start;
+        }
+        self.time_increment = scenario.time_step();
+        self.snapshot_interval = scenario.snapshot_interval();
+        self.site_failure_threshold = scenario.failures.site_failure_threshold;
+        self.settings = scenario.runtime_settings();
+        self.write_events = scenario.output.write_events;
+        self.scenario = Some(scenario);
+        self
+    }
+
+    /// Customize behavior models via a plugin, e.g. a `WasmPlugin`
+    pub fn with_plugin(mut self, plugin: Arc<dyn BehaviorPlugin>) -> Self {
+        self.plugin = Some(plugin);
+        self
+    }
+
+    /// Step a user-defined agent alongside the sites and the population
+    ///
+    /// Agents are stepped in the order they are added, after the built-in agents.
+    pub fn with_agent(mut self, agent: impl Agent + 'static) -> Self {
+        self.agents.push(Box::new(agent));
+        self
+    }
+
+    /// Call `callback` with the step time for every event of `kind`
+    ///
+    /// Callbacks are called synchronously while the events of a step are published,
+    /// after they were applied to the state, e.g. to push new orders to a queue.
+    pub fn on_event(
+        mut self,
+        kind: EventKind,
+        callback: impl Fn(DateTime<Utc>, &EventPayload) + Send + Sync + 'static,
+    ) -> Self {
+        self.event_callbacks.push((kind, Arc::new(callback)));
+        self
+    }
+
     async fn build_context(&mut self) -> Result<SimulationContext> {
         if let Some(ctx) = self.ctx.take() {
             Ok(ctx)
         } else {
-            SimulationContext::builder()
-                .with_working_directory(self.working_directory.clone())
+            let mut builder = SimulationContext::builder();
+            let mut working_directory = self.working_directory.clone();
+            if let Some(output) = self.scenario.as_ref().map(|scenario| &scenario.output) {
+                if working_directory.is_none() && output.working_directory.is_some() {
+                    working_directory = Some(resolve_url(output.working_directory.as_ref())?);
+                }
+                builder = builder.with_run_name(output.run_name.clone());
+            }
+            builder
+                .with_working_directory(working_directory)
                 .build()
                 .await
         }
@@ -146,7 +989,12 @@ impl SimulationBuilder {
     ) -> Result<State> {
         tracing::debug!(target: "caspers::simulation::builder", "building simulation state");
 
-        let objects = ctx.snapshots().objects().await?.collect().await?;
+        let objects = ctx
+            .snapshots()
+            .cached("objects", None)
+            .await?
+            .collect()
+            .await?;
         let objects = ObjectData::try_new(concat_batches(objects[0].schema_ref(), &objects)?)?;
 
         tracing::debug!(target: "caspers::simulation::builder", "generating routers");
@@ -154,23 +1002,33 @@ impl SimulationBuilder {
         for site in objects.sites()? {
             let info = site.properties()?;
 
+            let context = || format!("loading routing data for site '{}'", info.name);
             let site_nodes = ctx
-                .system()
-                .routing_nodes()
-                .await?
-                .filter(col("location").eq(lit(&info.name)))?
-                .collect()
-                .await?;
-            let site_nodes = concat_batches(site_nodes[0].schema_ref(), &site_nodes)?;
-
+                .collect(
+                    ctx.system()
+                        .routing_nodes()
+                        .await?
+                        .filter(col("location").eq(lit(&info.name)))?,
+                )
+                .await
+                .with_context(context)?;
             let site_edges = ctx
-                .system()
-                .routing_edges()
-                .await?
-                .filter(col("location").eq(lit(&info.name)))?
-                .collect()
-                .await?;
-            let site_edges = concat_batches(site_edges[0].schema_ref(), &site_edges)?;
+                .collect(
+                    ctx.system()
+                        .routing_edges()
+                        .await?
+                        .filter(col("location").eq(lit(&info.name)))?,
+                )
+                .await
+                .with_context(context)?;
+            let (Some(nodes), Some(edges)) = (site_nodes.first(), site_edges.first()) else {
+                return Err(Error::missing_input(format!(
+                    "no street network for site '{}', initialize the working directory with `caspers init`",
+                    info.name
+                )));
+            };
+            let site_nodes = concat_batches(nodes.schema_ref(), &site_nodes)?;
+            let site_edges = concat_batches(edges.schema_ref(), &site_edges)?;
 
             routers.insert(site.id(), RoutingData::try_new(site_nodes, site_edges)?);
         }
@@ -184,35 +1042,331 @@ impl SimulationBuilder {
 
     /// Build the simulation with the given initial conditions
     pub async fn build(mut self) -> Result<Simulation> {
-        let config = SimulationConfig {
+        let config = self.config.take().unwrap_or_else(|| SimulationConfig {
             simulation_start: self.start_time,
             time_increment: self.time_increment,
             dry_run: self.dry_run,
             write_events: self.write_events,
-        };
+            state_stats_interval: self.state_stats_interval,
+            table_stats: self.table_stats,
+            hooks: self.hooks.clone(),
+            campaigns: self.campaigns.clone(),
+            site_failure_threshold: self.site_failure_threshold,
+            courier_acceptance: self.courier_acceptance.clone(),
+            dispatch: self.dispatch.clone(),
+            packing: self.packing.clone(),
+            courier_breaks: self.courier_breaks.clone(),
+            delivery_robots: self.delivery_robots.clone(),
+            dark_stores: self.dark_stores.clone(),
+            road_closures: self.road_closures.clone(),
+            tipping: self.tipping.clone(),
+            invoicing: self.invoicing.clone(),
+            compensation: self.compensation.clone(),
+            feedback: self.feedback.clone(),
+            cuisine_preferences: self.cuisine_preferences.clone(),
+            exchange_rates: self.exchange_rates.clone(),
+            event_filter: self.event_filter.clone(),
+            journey_tolerance_m: self.journey_tolerance_m,
+            event_coalescing: self.event_coalescing.clone(),
+            heatmap_resolution: self.heatmap_resolution,
+            daily_summary: self.daily_summary,
+            churn_after: self.churn_after,
+            notifications: self.notifications.clone(),
+            mobility: self.mobility.clone(),
+            destinations: self.destinations.clone(),
+            priority: self.priority.clone(),
+            memberships: self.memberships.clone(),
+            sessions: self.sessions.clone(),
+            inventory: self.inventory.clone(),
+            scheduler: self.scheduler.clone(),
+            adaptive_step: self.adaptive_step.clone(),
+            carbon: self.carbon.clone(),
+            green_delivery: self.green_delivery.clone(),
+            calendar: self.calendar.clone(),
+            seasonality: self.seasonality.clone(),
+            seed: self.seed,
+            snapshot_interval: self.snapshot_interval,
+            checkpoint_days: self.checkpoint_days,
+            settings: self.settings.clone(),
+        });
+        for campaign in &config.campaigns {
+            campaign.validate()?;
+        }
+        config.dispatch.validate()?;
+        config.packing.validate()?;
+        config.courier_breaks.validate()?;
+        for robots in config.delivery_robots.values() {
+            robots.validate()?;
+        }
+        for dark_store in config.dark_stores.values() {
+            dark_store.validate()?;
+        }
+        for closure in &config.road_closures {
+            closure.validate()?;
+        }
+        config.invoicing.validate()?;
+        config.compensation.validate()?;
+        config.feedback.validate()?;
+        config.cuisine_preferences.validate()?;
+        config.exchange_rates.validate()?;
+        config.event_filter.validate()?;
+        if let Some(coalescing) = &config.event_coalescing {
+            coalescing.validate()?;
+        }
+        if config
+            .journey_tolerance_m
+            .is_some_and(|tolerance| tolerance.is_nan() || tolerance < 0.0)
+        {
+            return Err(Error::invalid_data(
+                "journey tolerance must not be negative",
+            ));
+        }
+        if let Some(resolution) = config.heatmap_resolution {
+            heatmap_resolution(resolution)?;
+        }
+        if config
+            .churn_after
+            .is_some_and(|churn_after| churn_after <= Duration::zero())
+        {
+            return Err(Error::invalid_data("churn period must be positive"));
+        }
+        if let Some(notifications) = &config.notifications {
+            notifications.validate()?;
+        }
+        if let Some(mobility) = &config.mobility {
+            mobility.validate()?;
+        }
+        if let Some(destinations) = &config.destinations {
+            destinations.validate()?;
+        }
+        if let Some(priority) = &config.priority {
+            priority.validate()?;
+        }
+        if let Some(memberships) = &config.memberships {
+            memberships.validate()?;
+        }
+        if let Some(sessions) = &config.sessions {
+            sessions.validate()?;
+        }
+        if let Some(inventory) = &config.inventory {
+            inventory.validate()?;
+        }
+        if let Some(scheduler) = &config.scheduler {
+            scheduler.validate()?;
+        }
+        if let Some(adaptive) = &config.adaptive_step {
+            if config.scheduler.is_some() {
+                return Err(Error::invalid_data(
+                    "adaptive steps cannot be combined with the event scheduler",
+                ));
+            }
+            adaptive.validate()?;
+        }
+        if let Some(carbon) = &config.carbon {
+            carbon.validate()?;
+        }
+        if let Some(green) = &config.green_delivery {
+            green.validate()?;
+        }
+        if let Some(calendar) = &config.calendar {
+            calendar.validate()?;
+        }
+        if let Some(seasonality) = &config.seasonality {
+            seasonality.validate()?;
+        }
+        config.settings.validate()?;
+        if let Some(scenario) = &self.scenario {
+            scenario.validate()?;
+        }
 
         let ctx = if let Some(ctx) = self.ctx.take() {
             ctx
         } else {
             self.build_context().await?
         };
+        if ctx.is_read_only() {
+            return Err(Error::read_only("cannot run a simulation"));
+        }
 
         let state = self.build_state(&ctx, &config).await?;
+        validate_station_compatibility(state.objects())?;
+        if let Some(scenario) = &self.scenario {
+            scenario.check_setup(&state)?;
+        }
+        let site_names: Vec<_> = state
+            .objects()
+            .sites()?
+            .map(|site| Ok::<_, Error>(site.properties()?.name))
+            .try_collect()?;
+        if let Some(name) = config
+            .delivery_robots
+            .keys()
+            .find(|name| !site_names.contains(name))
+        {
+            return Err(Error::invalid_data(format!(
+                "delivery robots configured for unknown site '{name}'"
+            )));
+        }
+        if let Some(name) = config
+            .dark_stores
+            .keys()
+            .find(|name| !site_names.contains(name))
+        {
+            return Err(Error::invalid_data(format!(
+                "dark store configured for unknown site '{name}'"
+            )));
+        }
 
         let sites = state
             .objects()
             .sites()?
-            .map(|site| Ok::<_, Error>((site.id(), SiteRunner::try_new(site.id(), &state)?)))
+            .map(|site| {
+                let name = site.properties()?.name;
+                Ok::<_, Error>((
+                    site.id(),
+                    SiteRunner::try_new(
+                        site.id(),
+                        &state,
+                        self.plugin.clone(),
+                        config.courier_acceptance.clone(),
+                        config.dispatch.clone(),
+                        config.packing.clone(),
+                        config.courier_breaks.clone(),
+                    )?
+                    .with_dark_store(
+                        config.dark_stores.get(&name).cloned(),
+                        &state,
+                        &config.exchange_rates,
+                    )?
+                    .with_robots(config.delivery_robots.get(&name).cloned())
+                    .with_green_delivery(config.green_delivery.clone())
+                    .with_seed(config.seed),
+                ))
+            })
             .try_collect()?;
 
-        Ok(Simulation {
-            population: PopulationRunner::try_new(&ctx).await?,
-            ctx,
+        let kpis = KpiRecorder::new(ctx.simulation_id());
+        let quarantine = SiteQuarantine::new(config.site_failure_threshold);
+        let (stats, _) = watch::channel(state.simulation_stats()?);
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        let invoicer = Invoicer::new(
+            config.invoicing.clone(),
+            config.exchange_rates.clone(),
+            ctx.results().last_invoice_numbers().await?,
+            StdRng::from_rng(&mut rng),
+        );
+        let mut lifecycle = CustomerLifecycle::new(
+            config.churn_after,
+            state.population().people_with_role(&PersonRole::Customer)?,
+        );
+        lifecycle.record_results(&ctx, state.current_time()).await?;
+        let compensator = Compensator::new(config.compensation.clone());
+        let feedback = FeedbackCollector::new(config.feedback.clone());
+        let notifier = config.notifications.clone().map(Notifier::new);
+        let mobility = config.mobility.clone().map(Mobility::new);
+        let sessions = config.sessions.clone().map(AppSessions::new);
+        let memberships = config
+            .memberships
+            .clone()
+            .map(|memberships| MembershipBilling::new(memberships, config.exchange_rates.base()));
+        let destinations = config
+            .destinations
+            .clone()
+            .map(|destinations| Destinations::try_new(destinations, &state))
+            .transpose()?;
+        let daily_summary = config
+            .daily_summary
+            .then(|| DailySummary::new(config.exchange_rates.clone()));
+        let inventory = config
+            .inventory
+            .clone()
+            .map(|inventory| IngredientInventory::try_new(inventory, &state))
+            .transpose()?;
+        let footprints = config.carbon.clone().map(FootprintTracker::new);
+        let green_deliveries = config.green_delivery.clone().map(GreenDeliveryReport::new);
+        let calendar = config
+            .calendar
+            .clone()
+            .map(|calendar| Calendar::try_new(calendar, &state))
+            .transpose()?;
+        let controls = Controls::new(config.settings.clone());
+        let mut bus = EventBus::default();
+        // drawn even without events written, so dry runs replay the same random numbers
+        let writer_rng = StdRng::from_rng(&mut rng);
+        if config.write_events && !config.dry_run {
+            bus.add_sink(EventTableWriter::new(&config, writer_rng));
+        }
+        for (kind, callback) in self.event_callbacks.drain(..) {
+            bus.on_event(kind, callback);
+        }
+        // seeded runs record the hash of their events to verify replays from this snapshot
+        let replay = config.seed.map(|_| EventDigest::new(*ctx.snapshot_id()));
+        let mut simulation = Simulation {
+            population: PopulationRunner::try_new(&ctx, config.hooks.clone(), self.plugin.clone())
+                .await?
+                .with_campaigns(config.campaigns.clone())
+                .with_tipping(config.tipping.clone())
+                .with_packing(config.packing.clone())
+                .with_cuisine_preferences(config.cuisine_preferences.clone())
+                .with_exchange_rates(config.exchange_rates.clone())
+                .with_destinations(destinations)
+                .with_priority_tiers(config.priority.clone())
+                .with_memberships(config.memberships.clone())
+                .with_green_delivery(config.green_delivery.clone())
+                .with_seasonality(config.seasonality.clone())
+                .with_seed(config.seed),
+            agents: std::mem::take(&mut self.agents),
+            ctx: Arc::new(ctx),
             config,
-            state,
+            state: Arc::new(state),
             sites,
             event_tracker: EventTracker::new(),
             stats_buffer: EventStatsBuffer::new(),
-        })
+            invoicer,
+            feedback,
+            compensator,
+            lifecycle,
+            notifier,
+            mobility,
+            memberships,
+            sessions,
+            daily_summary,
+            inventory,
+            footprints,
+            green_deliveries,
+            calendar,
+            kpis,
+            quarantine,
+            pending_site_events: HashMap::new(),
+            bus,
+            controls,
+            rng,
+            stats,
+            replay,
+        };
+        // the agents start out with the settings the simulation was built with
+        simulation.apply_settings();
+        Ok(simulation)
+    }
+}
+
+/// Ensure all menu items can be prepared in every kitchen they may be routed to.
+///
+/// Without this check, order lines for such items block a kitchen queue mid-run.
+fn validate_station_compatibility(objects: &ObjectData) -> Result<()> {
+    let incompatible = objects.incompatible_menu_items()?;
+    if incompatible.is_empty() {
+        return Ok(());
     }
+    Err(Error::invalid_data(format!(
+        "{} menu items cannot be prepared at their sites:\n{}",
+        incompatible.len(),
+        incompatible
+            .iter()
+            .map(|item| format!("  - {item}"))
+            .join("\n")
+    )))
 }
@@ -0,0 +1,322 @@
+//! Fan-out of the events of each step to subscribers.
+//!
+//! The agents of the simulation produce the events of a step, which are then handed
+//! to the [`EventBus`]. Sinks, e.g. a stream to an external broker or a live view of
+//! the simulation, subscribe to the topics they are interested in, identified by the
+//! [`EventKind`] of the events, and receive one [`EventBatch`] per step over a bounded
+//! channel. Adding a sink thus does not require any change to the step loop.
+//!
+//! Channels are bounded, so a sink falling behind slows down the simulation rather
+//! than buffering events without limit. Subscriptions end once they are dropped.
+//!
+//! Sinks which write the results of the simulation, e.g. the `events` table, are added
+//! as an [`EventSink`]. They are awaited while the step is published, before any other
+//! subscriber, so a failed write fails the step, and are flushed before snapshots.
+//!
+//! Integrations which react to single events, e.g. pushing new orders to a queue,
+//! may instead register an [`EventCallback`] for an event kind. Callbacks are called
+//! synchronously for every event of their kind while the step is published, before
+//! the batches are sent to subscribers, so they should return quickly.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+
+use crate::context::SimulationContext;
+use crate::{EventKind, EventPayload, Result};
+
+/// Callback called with the step time and an event of the kind it is registered for.
+pub type EventCallback = Arc<dyn Fn(DateTime<Utc>, &EventPayload) + Send + Sync>;
+
+/// Default number of step batches buffered for a subscriber.
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 64;
+
+/// Events of a single step on the topics of a subscription, in the order emitted.
+#[derive(Debug, Clone)]
+pub struct EventBatch {
+    pub step_time: DateTime<Utc>,
+    pub events: Arc<[EventPayload]>,
+}
+
+/// Receiving end of a subscription to the [`EventBus`].
+#[derive(Debug)]
+pub struct EventSubscription {
+    receiver: mpsc::Receiver<EventBatch>,
+}
+
+impl EventSubscription {
+    /// Receive the events of the next step, `None` once the simulation is dropped.
+    pub async fn recv(&mut self) -> Option<EventBatch> {
+        self.receiver.recv().await
+    }
+
+    /// Receive the events of the next step if they have already been published.
+    pub fn try_recv(&mut self) -> Option<EventBatch> {
+        self.receiver.try_recv().ok()
+    }
+
+    pub fn into_inner(self) -> mpsc::Receiver<EventBatch> {
+        self.receiver
+    }
+}
+
+/// The events of a step as published to the [`EventBus`].
+pub(crate) struct PublishedStep<'a> {
+    pub(crate) step_time: DateTime<Utc>,
+    /// Time of the state after the step
+    pub(crate) state_time: DateTime<Utc>,
+    /// Simulated time covered by the step
+    pub(crate) time_step: Duration,
+    /// Trace context of the step, recorded with the written events
+    pub(crate) traceparent: Option<String>,
+    pub(crate) events: &'a [EventPayload],
+}
+
+/// Sink writing the events of every step, e.g. to a results table.
+#[async_trait]
+pub(crate) trait EventSink: Debug + Send + Sync {
+    /// Write the events of a step, an error fails the step.
+    async fn write(&mut self, ctx: &SimulationContext, step: &PublishedStep<'_>) -> Result<()>;
+
+    /// Write the events buffered across steps, if any.
+    async fn flush(&mut self, ctx: &SimulationContext) -> Result<()>;
+}
+
+struct Subscriber {
+    /// Kinds of events delivered, all events if `None`
+    topics: Option<HashSet<EventKind>>,
+    sender: mpsc::Sender<EventBatch>,
+}
+
+/// Publishes the events of each step to the sinks subscribed to their topics.
+#[derive(Default)]
+pub(crate) struct EventBus {
+    sinks: Vec<Box<dyn EventSink>>,
+    subscribers: Vec<Subscriber>,
+    callbacks: Vec<(EventKind, EventCallback)>,
+}
+
+impl EventBus {
+    /// Subscribe to the events of the given kinds, or all events if `topics` is empty.
+    ///
+    /// Up to `capacity` steps are buffered before publishing waits for the subscriber.
+    pub(crate) fn subscribe(
+        &mut self,
+        topics: impl IntoIterator<Item = EventKind>,
+        capacity: usize,
+    ) -> EventSubscription {
+        let topics: HashSet<_> = topics.into_iter().collect();
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        self.subscribers.push(Subscriber {
+            topics: (!topics.is_empty()).then_some(topics),
+            sender,
+        });
+        EventSubscription { receiver }
+    }
+
+    /// Write the events of every step to `sink`.
+    pub(crate) fn add_sink(&mut self, sink: impl EventSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Call `callback` for every published event of the given kind.
+    pub(crate) fn on_event(&mut self, kind: EventKind, callback: EventCallback) {
+        self.callbacks.push((kind, callback));
+    }
+
+    /// Deliver the events of a step to all sinks, callbacks and subscribers.
+    ///
+    /// Subscribers that went away are dropped from the bus.
+    pub(crate) async fn publish(
+        &mut self,
+        ctx: &SimulationContext,
+        step: &PublishedStep<'_>,
+    ) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.write(ctx, step).await?;
+        }
+        let (step_time, events) = (step.step_time, step.events);
+        if !self.callbacks.is_empty() {
+            for event in events {
+                let kind = event.kind();
+                for (_, callback) in self.callbacks.iter().filter(|(k, _)| *k == kind) {
+                    callback(step_time, event);
+                }
+            }
+        }
+        if self.subscribers.is_empty() {
+            return Ok(());
+        }
+        // subscribers to all topics share a single copy of the events
+        let mut all: Option<Arc<[EventPayload]>> = None;
+        let mut closed = Vec::new();
+        for (idx, subscriber) in self.subscribers.iter().enumerate() {
+            let events = match &subscriber.topics {
+                None => all.get_or_insert_with(|| events.into()).clone(),
+                Some(topics) => events
+                    .iter()
+                    .filter(|event| topics.contains(&event.kind()))
+                    .cloned()
+                    .collect(),
+            };
+            let batch = EventBatch { step_time, events };
+            if subscriber.sender.send(batch).await.is_err() {
+                closed.push(idx);
+            }
+        }
+        for idx in closed.into_iter().rev() {
+            self.subscribers.swap_remove(idx);
+            tracing::debug!(
+                target: "caspers::simulation::bus",
+                "dropped closed event subscription, {} remaining",
+                self.subscribers.len()
+            );
+        }
+        Ok(())
+    }
+
+    /// Write the events all sinks buffered across steps.
+    pub(crate) async fn flush(&mut self, ctx: &SimulationContext) -> Result<()> {
+        for sink in &mut self.sinks {
+            sink.flush(ctx).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::idents::OrderId;
+    use crate::{Error, OrderStatus};
+
+    use super::*;
+
+    fn published(time: DateTime<Utc>, events: &[EventPayload]) -> PublishedStep<'_> {
+        PublishedStep {
+            step_time: time,
+            state_time: time,
+            time_step: Duration::from_secs(60),
+            traceparent: None,
+            events,
+        }
+    }
+
+    async fn context() -> Result<SimulationContext> {
+        SimulationContext::builder()
+            .with_use_in_memory(true)
+            .build()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_publish_by_topic() -> Result<()> {
+        let ctx = context().await?;
+        let mut bus = EventBus::default();
+        let mut all = bus.subscribe([], 4);
+        let mut orders = bus.subscribe([EventKind::OrderUpdated], 4);
+        let dropped = bus.subscribe([EventKind::StepStarted], 4);
+        drop(dropped);
+
+        let now = Utc::now();
+        let events = vec![
+            EventPayload::step_started(now),
+            EventPayload::order_updated(OrderId::new(), OrderStatus::Delivered, None),
+            EventPayload::step_finished(now, 2),
+        ];
+        bus.publish(&ctx, &published(now, &events)).await?;
+        assert_eq!(bus.subscribers.len(), 2);
+
+        let batch = all.recv().await.unwrap();
+        assert_eq!(batch.step_time, now);
+        assert_eq!(batch.events.len(), 3);
+        let batch = orders.recv().await.unwrap();
+        assert_eq!(batch.events.len(), 1);
+        assert_eq!(batch.events[0].kind(), EventKind::OrderUpdated);
+        assert!(orders.try_recv().is_none());
+
+        // steps without events on the topics still deliver a batch
+        bus.publish(&ctx, &published(now, &events[..1])).await?;
+        assert!(orders.try_recv().unwrap().events.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_callbacks() -> Result<()> {
+        let ctx = context().await?;
+        let mut bus = EventBus::default();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let orders = seen.clone();
+        bus.on_event(
+            EventKind::OrderUpdated,
+            Arc::new(move |_, event| orders.lock().unwrap().push(event.kind())),
+        );
+
+        let now = Utc::now();
+        let events = vec![
+            EventPayload::step_started(now),
+            EventPayload::order_updated(OrderId::new(), OrderStatus::Delivered, None),
+            EventPayload::order_updated(OrderId::new(), OrderStatus::Cancelled, None),
+            EventPayload::step_finished(now, 3),
+        ];
+        // callbacks are called without any subscribers
+        bus.publish(&ctx, &published(now, &events)).await?;
+        assert_eq!(*seen.lock().unwrap(), vec![EventKind::OrderUpdated; 2]);
+        Ok(())
+    }
+
+    /// Sink counting the events written, failing once it reaches `limit`.
+    #[derive(Debug, Default)]
+    struct CountingSink {
+        written: Arc<Mutex<usize>>,
+        limit: usize,
+    }
+
+    #[async_trait]
+    impl EventSink for CountingSink {
+        async fn write(&mut self, _: &SimulationContext, step: &PublishedStep<'_>) -> Result<()> {
+            let mut written = self.written.lock().unwrap();
+            if *written + step.events.len() > self.limit {
+                return Err(Error::internal("sink is full"));
+            }
+            *written += step.events.len();
+            Ok(())
+        }
+
+        async fn flush(&mut self, _: &SimulationContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sinks() -> Result<()> {
+        let ctx = context().await?;
+        let written = Arc::new(Mutex::new(0));
+        let mut bus = EventBus::default();
+        bus.add_sink(CountingSink {
+            written: written.clone(),
+            limit: 3,
+        });
+        let mut all = bus.subscribe([], 4);
+
+        let now = Utc::now();
+        let events = vec![
+            EventPayload::step_started(now),
+            EventPayload::step_finished(now, 1),
+        ];
+        bus.publish(&ctx, &published(now, &events)).await?;
+        assert_eq!(*written.lock().unwrap(), 2);
+        assert_eq!(all.recv().await.unwrap().events.len(), 2);
+
+        // a failed write fails the step before it reaches the subscribers
+        assert!(bus.publish(&ctx, &published(now, &events)).await.is_err());
+        assert!(all.try_recv().is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+//! Public holidays and special events shifting demand, courier supply and roads.
+//!
+//! A [`CalendarConfig`] lists public holidays, e.g. loaded from a per-country holiday
+//! file, and special events such as concerts or marathons. Holidays apply to all sites
+//! for the whole simulated day, which is a UTC date like all times of a run. Only the
+//! holidays of the configured [`CalendarConfig::country`] apply, holidays without a
+//! country apply everywhere.
+//!
+//! Special events apply during their time window to the sites within
+//! [`SpecialEvent::reach_m`] of the venue. Streets closed for an event become
+//! [`RoadClosure`]s of the same window, i.e. routes planned during the event avoid them.
+//!
+//! Factors of all holidays and events active at a time multiply, and apply on top of
+//! the [`RuntimeSettings`](super::RuntimeSettings): demand factors scale the probability
+//! of customers ordering from a site, courier factors the share of its couriers on
+//! shift, which never exceeds all couriers.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use h3o::LatLng;
+use serde::{Deserialize, Serialize};
+
+use crate::idents::SiteId;
+use crate::state::{ClosureArea, EntityView as _, RoadClosure, State};
+use crate::{Error, Result};
+
+/// Holidays and special events of a run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// Country code of the sites, e.g. `DE`, selecting the holidays which apply
+    pub country: Option<String>,
+
+    /// Public holidays, of all countries or only the configured one
+    pub holidays: Vec<Holiday>,
+
+    /// Events near sites, e.g. concerts or marathons
+    pub events: Vec<SpecialEvent>,
+}
+
+/// A public holiday.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holiday {
+    pub name: String,
+
+    /// Simulated day of the holiday
+    pub date: NaiveDate,
+
+    /// Country code of the country observing the holiday, all countries if not set
+    #[serde(default)]
+    pub country: Option<String>,
+
+    /// Factor applied to the probability of customers placing an order
+    #[serde(default = "default_holiday_demand_factor")]
+    pub demand_factor: f64,
+
+    /// Factor applied to the share of couriers on shift
+    #[serde(default = "default_holiday_courier_factor")]
+    pub courier_factor: f64,
+}
+
+fn default_holiday_demand_factor() -> f64 {
+    1.25
+}
+
+fn default_holiday_courier_factor() -> f64 {
+    0.8
+}
+
+/// An event drawing crowds to a venue for a while.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpecialEvent {
+    pub name: String,
+
+    pub latitude: f64,
+    pub longitude: f64,
+
+    /// Time at which the event starts
+    pub start: DateTime<Utc>,
+
+    /// Time at which the event ends
+    pub end: DateTime<Utc>,
+
+    /// Distance in meters from the venue within which sites are affected
+    #[serde(default = "default_event_reach_m")]
+    pub reach_m: f64,
+
+    /// Factor applied to the probability of customers ordering from affected sites
+    #[serde(default = "default_factor")]
+    pub demand_factor: f64,
+
+    /// Factor applied to the share of couriers of affected sites on shift
+    #[serde(default = "default_factor")]
+    pub courier_factor: f64,
+
+    /// Names of the streets closed during the event
+    #[serde(default)]
+    pub closed_streets: Vec<String>,
+
+    /// Radius in meters around the venue in which all streets are closed during the event
+    #[serde(default)]
+    pub closure_radius_m: Option<f64>,
+}
+
+fn default_event_reach_m() -> f64 {
+    3_000.0
+}
+
+fn default_factor() -> f64 {
+    1.0
+}
+
+impl CalendarConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        let valid_factor = |factor: f64| factor.is_finite() && factor >= 0.0;
+        for holiday in &self.holidays {
+            if !valid_factor(holiday.demand_factor) || !valid_factor(holiday.courier_factor) {
+                return Err(Error::invalid_data(format!(
+                    "factors of holiday '{}' must be non-negative numbers",
+                    holiday.name
+                )));
+            }
+        }
+        for event in &self.events {
+            if !valid_factor(event.demand_factor) || !valid_factor(event.courier_factor) {
+                return Err(Error::invalid_data(format!(
+                    "factors of event '{}' must be non-negative numbers",
+                    event.name
+                )));
+            }
+            LatLng::new(event.latitude, event.longitude)?;
+            if !(event.reach_m.is_finite() && event.reach_m >= 0.0) {
+                return Err(Error::invalid_data(format!(
+                    "reach of event '{}' must be a non-negative number",
+                    event.name
+                )));
+            }
+            if event.end <= event.start {
+                return Err(Error::invalid_data(format!(
+                    "event '{}' must end after it starts",
+                    event.name
+                )));
+            }
+        }
+        self.road_closures()
+            .iter()
+            .try_for_each(RoadClosure::validate)
+    }
+
+    /// Whether `holiday` is observed in the configured country.
+    fn observes(&self, holiday: &Holiday) -> bool {
+        match (&self.country, &holiday.country) {
+            (Some(country), Some(observed)) => country.eq_ignore_ascii_case(observed),
+            _ => true,
+        }
+    }
+
+    /// Streets closed for special events.
+    pub(crate) fn road_closures(&self) -> Vec<RoadClosure> {
+        self.events
+            .iter()
+            .filter(|event| !event.closed_streets.is_empty() || event.closure_radius_m.is_some())
+            .map(|event| RoadClosure {
+                name: event.name.clone(),
+                streets: event.closed_streets.clone(),
+                area: event.closure_radius_m.map(|radius_m| ClosureArea {
+                    latitude: event.latitude,
+                    longitude: event.longitude,
+                    radius_m,
+                }),
+                start: event.start,
+                end: event.end,
+            })
+            .collect()
+    }
+}
+
+/// Demand and courier factors of the sites, following the calendar as the run advances.
+#[derive(Debug, Clone)]
+pub(crate) struct Calendar {
+    config: CalendarConfig,
+
+    /// Sites within reach of each special event, in the order of the events
+    reached: Vec<HashSet<SiteId>>,
+
+    sites: Vec<SiteId>,
+
+    /// Names of the holidays and events active at the last update
+    active: Vec<String>,
+
+    demand_factors: BTreeMap<SiteId, f64>,
+    courier_factors: BTreeMap<SiteId, f64>,
+}
+
+impl Calendar {
+    pub(crate) fn try_new(config: CalendarConfig, state: &State) -> Result<Self> {
+        let mut sites = Vec::new();
+        for site in state.objects().sites()? {
+            let props = site.properties()?;
+            sites.push((site.id(), LatLng::new(props.latitude, props.longitude)?));
+        }
+        let reached = config
+            .events
+            .iter()
+            .map(|event| {
+                let venue = LatLng::new(event.latitude, event.longitude)?;
+                Ok::<_, Error>(
+                    sites
+                        .iter()
+                        .filter(|(_, location)| location.distance_m(venue) <= event.reach_m)
+                        .map(|(site_id, _)| *site_id)
+                        .collect(),
+                )
+            })
+            .collect::<Result<_>>()?;
+        let sites: Vec<_> = sites.into_iter().map(|(site_id, _)| site_id).collect();
+        Ok(Self {
+            demand_factors: sites.iter().map(|site_id| (*site_id, 1.0)).collect(),
+            courier_factors: sites.iter().map(|site_id| (*site_id, 1.0)).collect(),
+            config,
+            reached,
+            sites,
+            active: Vec::new(),
+        })
+    }
+
+    /// Update the factors of the sites to the holidays and events active at `time`.
+    ///
+    /// Returns whether the active holidays and events changed.
+    pub(crate) fn update(&mut self, time: DateTime<Utc>) -> bool {
+        let holidays: Vec<_> = self
+            .config
+            .holidays
+            .iter()
+            .filter(|holiday| holiday.date == time.date_naive() && self.config.observes(holiday))
+            .collect();
+        let events: Vec<_> = self
+            .config
+            .events
+            .iter()
+            .zip(&self.reached)
+            .filter(|(event, _)| event.start <= time && time < event.end)
+            .collect();
+        let active: Vec<_> = holidays
+            .iter()
+            .map(|holiday| holiday.name.clone())
+            .chain(events.iter().map(|(event, _)| event.name.clone()))
+            .collect();
+        if active == self.active {
+            return false;
+        }
+        if !active.is_empty() {
+            tracing::info!(
+                target: "caspers::simulation::calendar",
+                "calendar at {}: {}",
+                time.to_rfc3339(),
+                active.join(", ")
+            );
+        }
+        self.active = active;
+
+        let holiday_demand: f64 = holidays.iter().map(|h| h.demand_factor).product();
+        let holiday_couriers: f64 = holidays.iter().map(|h| h.courier_factor).product();
+        for site_id in &self.sites {
+            let (demand, couriers) = events
+                .iter()
+                .filter(|(_, reached)| reached.contains(site_id))
+                .fold((holiday_demand, holiday_couriers), |(d, c), (event, _)| {
+                    (d * event.demand_factor, c * event.courier_factor)
+                });
+            self.demand_factors.insert(*site_id, demand);
+            self.courier_factors.insert(*site_id, couriers);
+        }
+        true
+    }
+
+    /// Earliest time after `time` at which a holiday or event starts or ends.
+    pub(crate) fn next_change(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let holidays = self.config.holidays.iter().flat_map(|holiday| {
+            let start = holiday.date.and_time(Default::default()).and_utc();
+            let end = holiday
+                .date
+                .checked_add_days(Days::new(1))
+                .map(|end| end.and_time(Default::default()).and_utc());
+            std::iter::once(start).chain(end)
+        });
+        let events = self
+            .config
+            .events
+            .iter()
+            .flat_map(|event| [event.start, event.end]);
+        holidays.chain(events).filter(|change| *change > time).min()
+    }
+
+    /// Factors applied to the probability of customers ordering from each site.
+    pub(crate) fn demand_factors(&self) -> &BTreeMap<SiteId, f64> {
+        &self.demand_factors
+    }
+
+    /// Factor applied to the share of couriers of the site on shift.
+    pub(crate) fn courier_factor(&self, site_id: &SiteId) -> f64 {
+        self.courier_factors.get(site_id).copied().unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, start: DateTime<Utc>, hours: i64) -> SpecialEvent {
+        SpecialEvent {
+            name: name.to_string(),
+            latitude: 52.52,
+            longitude: 13.405,
+            start,
+            end: start + chrono::Duration::hours(hours),
+            reach_m: default_event_reach_m(),
+            demand_factor: 1.5,
+            courier_factor: 0.5,
+            closed_streets: Vec::new(),
+            closure_radius_m: None,
+        }
+    }
+
+    #[test]
+    fn test_holiday_countries() {
+        let holiday: Holiday = serde_json::from_value(serde_json::json!({
+            "name": "Tag der Deutschen Einheit",
+            "date": "2025-10-03",
+            "country": "DE"
+        }))
+        .unwrap();
+        assert_eq!(holiday.demand_factor, default_holiday_demand_factor());
+
+        let config = CalendarConfig {
+            country: Some("de".to_string()),
+            ..Default::default()
+        };
+        assert!(config.observes(&holiday));
+        let config = CalendarConfig {
+            country: Some("FR".to_string()),
+            ..Default::default()
+        };
+        assert!(!config.observes(&holiday));
+        assert!(CalendarConfig::default().observes(&holiday));
+    }
+
+    #[test]
+    fn test_road_closures() {
+        let start = DateTime::parse_from_rfc3339("2025-09-21T08:00:00Z")
+            .unwrap()
+            .to_utc();
+        let mut marathon = event("marathon", start, 6);
+        marathon.closed_streets = vec!["Unter den Linden".to_string()];
+        let mut concert = event("concert", start, 3);
+        concert.closure_radius_m = Some(500.0);
+        let config = CalendarConfig {
+            events: vec![marathon, concert, event("market", start, 2)],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        let closures = config.road_closures();
+        assert_eq!(closures.len(), 2);
+        assert_eq!(closures[0].streets, vec!["Unter den Linden".to_string()]);
+        assert_eq!(closures[1].area.as_ref().unwrap().radius_m, 500.0);
+        assert_eq!(closures[1].end, start + chrono::Duration::hours(3));
+    }
+
+    #[test]
+    fn test_validate() {
+        let start = Utc::now();
+        let mut invalid = event("backwards", start, -1);
+        let config = CalendarConfig {
+            events: vec![invalid.clone()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        invalid.end = start + chrono::Duration::hours(1);
+        invalid.demand_factor = -1.0;
+        let config = CalendarConfig {
+            events: vec![invalid],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
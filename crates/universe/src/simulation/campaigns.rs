@@ -0,0 +1,151 @@
+//! Promotional campaigns discounting orders.
+//!
+//! Campaigns are matched against every new order. All matching campaigns are applied
+//! in the order they are configured and recorded on the `order_created` event, so
+//! stream consumers can attribute discounts without joining against configuration.
+//!
+//! ```
+//! use caspers_universe::{Campaign, OrderChannel};
+//!
+//! let campaign = Campaign::new("app-launch", 0.15).with_channel(OrderChannel::App);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{BrandId, MenuItemId};
+use crate::{Error, OrderChannel, Result};
+
+/// A discount applied to orders matching all of its conditions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Campaign {
+    pub name: String,
+
+    /// Fraction of the order total taken off, between 0 and 1.
+    pub discount: f64,
+
+    /// Only apply to orders placed through this channel.
+    #[serde(default)]
+    pub channel: Option<OrderChannel>,
+
+    /// Only apply to orders containing an item of this brand.
+    #[serde(default)]
+    pub brand_id: Option<BrandId>,
+
+    /// Start of the time window in which the campaign is active.
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+
+    /// End of the time window in which the campaign is active.
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+impl Campaign {
+    pub fn new(name: impl Into<String>, discount: f64) -> Self {
+        Self {
+            name: name.into(),
+            discount,
+            channel: None,
+            brand_id: None,
+            starts_at: None,
+            ends_at: None,
+        }
+    }
+
+    pub fn with_channel(mut self, channel: OrderChannel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    pub fn with_brand(mut self, brand_id: BrandId) -> Self {
+        self.brand_id = Some(brand_id);
+        self
+    }
+
+    pub fn with_time_window(
+        mut self,
+        starts_at: impl Into<Option<DateTime<Utc>>>,
+        ends_at: impl Into<Option<DateTime<Utc>>>,
+    ) -> Self {
+        self.starts_at = starts_at.into();
+        self.ends_at = ends_at.into();
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.discount) {
+            return Err(Error::invalid_data(format!(
+                "campaign '{}' has discount {} outside of [0, 1]",
+                self.name, self.discount
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the campaign applies to an order.
+    pub fn applies_to(
+        &self,
+        time: DateTime<Utc>,
+        channel: OrderChannel,
+        items: &[(BrandId, MenuItemId)],
+    ) -> bool {
+        self.channel.is_none_or(|c| c == channel)
+            && self
+                .brand_id
+                .is_none_or(|brand| items.iter().any(|(b, _)| *b == brand))
+            && self.starts_at.is_none_or(|start| time >= start)
+            && self.ends_at.is_none_or(|end| time < end)
+    }
+}
+
+/// Apply all matching campaigns to an order total.
+///
+/// Returns the discounted total rounded to cents and the names of the applied campaigns.
+pub(crate) fn apply_campaigns(
+    campaigns: &[Campaign],
+    time: DateTime<Utc>,
+    channel: OrderChannel,
+    items: &[(BrandId, MenuItemId)],
+    total: f64,
+) -> (f64, Vec<String>) {
+    let mut total = total;
+    let mut applied = Vec::new();
+    for campaign in campaigns {
+        if campaign.applies_to(time, channel, items) {
+            total *= 1.0 - campaign.discount;
+            applied.push(campaign.name.clone());
+        }
+    }
+    ((total * 100.0).round() / 100.0, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_apply_campaigns() {
+        let now = Utc::now();
+        let brand = BrandId::from_name("brand");
+        let items = vec![(brand, MenuItemId::from_names("brand", "item"))];
+        let campaigns = vec![
+            Campaign::new("app", 0.5).with_channel(OrderChannel::App),
+            Campaign::new("brand", 0.1).with_brand(brand),
+            Campaign::new("other-brand", 0.1).with_brand(BrandId::from_name("other")),
+            Campaign::new("expired", 0.1).with_time_window(None, now - Duration::hours(1)),
+        ];
+
+        let (total, applied) = apply_campaigns(&campaigns, now, OrderChannel::App, &items, 20.0);
+        assert_eq!(total, 9.0);
+        assert_eq!(applied, vec!["app".to_string(), "brand".to_string()]);
+
+        let (total, applied) = apply_campaigns(&campaigns, now, OrderChannel::Web, &[], 20.0);
+        assert_eq!(total, 20.0);
+        assert!(applied.is_empty());
+
+        assert!(Campaign::new("invalid", 1.5).validate().is_err());
+    }
+}
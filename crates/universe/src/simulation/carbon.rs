@@ -0,0 +1,326 @@
+//! Carbon footprint of deliveries.
+//!
+//! Once a delivery starts, i.e. a courier picked up the order or a robot was
+//! dispatched with it, its footprint is estimated from the mode of transport and the
+//! distance to the customer, using the emission factors of [`CarbonConfig`]. Couriers
+//! are assigned a mode by the transport of their route, robots and drones by their
+//! kind. The footprint is reported as a `DeliveryFootprint` event, recorded in the
+//! `co2_g` column of the order, and rolled up per site, mode and day in the
+//! `delivery_footprint` results table.
+//!
+//! Couriers and robots return to their site after each delivery, so the trip back
+//! counts towards the footprint unless [`CarbonConfig::round_trip`] is disabled.
+//! Couriers picking up several orders at once, i.e. bundled green deliveries, hand
+//! them over one after another. The detours between the destinations count towards
+//! the trip, whose footprint is shared evenly among its orders.
+
+use std::collections::BTreeMap;
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, DurationRound as _, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::builders::{DeliveryFootprint, FootprintBuffer};
+use crate::idents::{OrderId, PersonId, SiteId};
+use crate::state::{OrderStatus, PersonStatus, State, Transport};
+use crate::{DeliveryMode, Error, EventPayload, Result, RobotActivity, RobotKind};
+
+/// Emission factors of the modes of transport delivering orders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarbonConfig {
+    /// Grams of CO2 emitted per km travelled by each mode, modes not listed emit none
+    pub grams_per_km: BTreeMap<DeliveryMode, f64>,
+
+    /// Whether the trip back to the site counts towards the footprint of a delivery
+    pub round_trip: bool,
+}
+
+impl Default for CarbonConfig {
+    fn default() -> Self {
+        Self {
+            grams_per_km: BTreeMap::from([
+                (DeliveryMode::Foot, 0.0),
+                (DeliveryMode::Bicycle, 5.0),
+                (DeliveryMode::Car, 120.0),
+                (DeliveryMode::Robot, 10.0),
+                (DeliveryMode::Drone, 30.0),
+            ]),
+            round_trip: true,
+        }
+    }
+}
+
+impl CarbonConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (mode, grams) in &self.grams_per_km {
+            if !(grams.is_finite() && *grams >= 0.0) {
+                return Err(Error::invalid_data(format!(
+                    "emission factor of {mode} must be a non-negative number"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Distance travelled and grams of CO2 emitted for a delivery over `distance_m`.
+    fn footprint(&self, mode: DeliveryMode, distance_m: f64) -> (f64, f64) {
+        let distance_m = if self.round_trip {
+            distance_m * 2.0
+        } else {
+            distance_m
+        };
+        let grams_per_km = self.grams_per_km.get(&mode).copied().unwrap_or_default();
+        (distance_m, grams_per_km * distance_m / 1000.0)
+    }
+}
+
+/// Mode of a courier delivering an order along a route for `transport`.
+fn courier_mode(transport: Transport) -> DeliveryMode {
+    match transport {
+        Transport::Foot => DeliveryMode::Foot,
+        Transport::Bicycle => DeliveryMode::Bicycle,
+        // any other transport is motorized
+        _ => DeliveryMode::Car,
+    }
+}
+
+/// Orders picked up by `courier` in the same step as `order_id`, in the order of the events.
+fn bundled_with(
+    events: &[EventPayload],
+    courier: PersonId,
+    order_id: OrderId,
+) -> impl Iterator<Item = OrderId> + '_ {
+    events.iter().filter_map(move |event| match event {
+        EventPayload::OrderUpdated(payload)
+            if payload.status == OrderStatus::PickedUp
+                && payload.actor_id == Some(courier)
+                && payload.order_id != order_id =>
+        {
+            Some(payload.order_id)
+        }
+        _ => None,
+    })
+}
+
+/// Deliveries of a site by one mode during the current day.
+#[derive(Debug, Clone, Default)]
+struct DeliveryTotals {
+    deliveries: i64,
+    distance_m: f64,
+    co2_g: f64,
+}
+
+/// Estimates the footprint of deliveries and rolls it up per day.
+pub(crate) struct FootprintTracker {
+    config: CarbonConfig,
+    /// The day currently rolled up
+    day: Option<DateTime<Utc>>,
+    totals: BTreeMap<(SiteId, DeliveryMode), DeliveryTotals>,
+    /// Completed days waiting to be written
+    buffer: FootprintBuffer,
+}
+
+impl FootprintTracker {
+    pub(crate) fn new(config: CarbonConfig) -> Self {
+        Self {
+            config,
+            day: None,
+            totals: BTreeMap::new(),
+            buffer: FootprintBuffer::new(),
+        }
+    }
+
+    /// Account for the deliveries started in the step at `now`, returning an event
+    /// with the footprint of each.
+    ///
+    /// The previous day is completed once a step starts on a new day.
+    pub(crate) fn record(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+        state: &State,
+    ) -> Result<Vec<EventPayload>> {
+        let day = now
+            .duration_trunc(TimeDelta::days(1))
+            .map_err(|e| Error::invalid_data(format!("invalid step time: {e}")))?;
+        if self.day.is_some_and(|current| current != day) {
+            self.finish_day()?;
+        }
+        self.day = Some(day);
+
+        let mut footprints = Vec::new();
+        for event in events {
+            let (order_ids, site_id, mode, distance_m) = match event {
+                EventPayload::PersonUpdated(payload) => {
+                    let PersonStatus::Delivering(order_id, journey) = &payload.status else {
+                        continue;
+                    };
+                    let Some(order) = state.orders().order(order_id) else {
+                        continue;
+                    };
+                    let mut order_ids = vec![*order_id];
+                    let mut distance_m = journey.distance_m() as f64;
+                    let mut stop = order.destination()?;
+                    for other in bundled_with(events, payload.person_id, *order_id) {
+                        let Some(other_order) = state.orders().order(&other) else {
+                            continue;
+                        };
+                        let destination = other_order.destination()?;
+                        distance_m += stop.distance_m(destination);
+                        stop = destination;
+                        order_ids.push(other);
+                    }
+                    (
+                        order_ids,
+                        SiteId::try_from(order.site_id())?,
+                        courier_mode(journey.transport()),
+                        distance_m,
+                    )
+                }
+                EventPayload::RobotDelivery(payload)
+                    if payload.activity == RobotActivity::Dispatched =>
+                {
+                    let mode = match payload.kind {
+                        RobotKind::Robot => DeliveryMode::Robot,
+                        RobotKind::Drone => DeliveryMode::Drone,
+                    };
+                    (
+                        vec![payload.order_id],
+                        payload.site_id,
+                        mode,
+                        payload.distance_m,
+                    )
+                }
+                _ => continue,
+            };
+            footprints.extend(self.account(&order_ids, site_id, mode, distance_m));
+        }
+        Ok(footprints)
+    }
+
+    /// Add the footprint of a trip delivering `order_ids` to the totals of the day,
+    /// returning an event with the share of each order.
+    fn account(
+        &mut self,
+        order_ids: &[OrderId],
+        site_id: SiteId,
+        mode: DeliveryMode,
+        distance_m: f64,
+    ) -> Vec<EventPayload> {
+        let (distance_m, co2_g) = self.config.footprint(mode, distance_m);
+        let totals = self.totals.entry((site_id, mode)).or_default();
+        totals.deliveries += order_ids.len() as i64;
+        totals.distance_m += distance_m;
+        totals.co2_g += co2_g;
+        let share = order_ids.len().max(1) as f64;
+        order_ids
+            .iter()
+            .map(|order_id| {
+                EventPayload::delivery_footprint(
+                    *order_id,
+                    site_id,
+                    mode,
+                    distance_m / share,
+                    co2_g / share,
+                )
+            })
+            .collect()
+    }
+
+    /// Complete the day currently rolled up, e.g. at the end of a run.
+    pub(crate) fn finish_day(&mut self) -> Result<()> {
+        let Some(day) = self.day.take() else {
+            return Ok(());
+        };
+        for ((site_id, mode), totals) in std::mem::take(&mut self.totals) {
+            self.buffer.push(&DeliveryFootprint {
+                day,
+                site_id,
+                mode,
+                deliveries: totals.deliveries,
+                distance_m: totals.distance_m,
+                co2_g: totals.co2_g,
+            })?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footprint() {
+        let config = CarbonConfig::default();
+        assert_eq!(
+            config.footprint(DeliveryMode::Car, 1_500.0),
+            (3_000.0, 360.0)
+        );
+        assert_eq!(
+            config.footprint(DeliveryMode::Foot, 1_500.0),
+            (3_000.0, 0.0)
+        );
+
+        let config = CarbonConfig {
+            grams_per_km: BTreeMap::from([(DeliveryMode::Drone, 40.0)]),
+            round_trip: false,
+        };
+        assert_eq!(config.footprint(DeliveryMode::Drone, 500.0), (500.0, 20.0));
+        // modes without an emission factor emit nothing
+        assert_eq!(config.footprint(DeliveryMode::Car, 500.0), (500.0, 0.0));
+    }
+
+    #[test]
+    fn test_daily_totals() -> Result<()> {
+        let mut tracker = FootprintTracker::new(CarbonConfig::default());
+        let london = SiteId::from_name("london");
+        let paris = SiteId::from_name("paris");
+        tracker.day = Some(DateTime::UNIX_EPOCH);
+
+        let events = tracker.account(&[OrderId::new()], london, DeliveryMode::Drone, 1_000.0);
+        let EventPayload::DeliveryFootprint(payload) = &events[0] else {
+            panic!("expected delivery footprint event");
+        };
+        assert_eq!(payload.distance_m, 2_000.0);
+        assert_eq!(payload.co2_g, 60.0);
+        tracker.account(&[OrderId::new()], london, DeliveryMode::Drone, 500.0);
+
+        // orders delivered on one trip share its footprint
+        let bundle = [OrderId::new(), OrderId::new()];
+        let events = tracker.account(&bundle, paris, DeliveryMode::Car, 1_000.0);
+        assert_eq!(events.len(), 2);
+        let EventPayload::DeliveryFootprint(payload) = &events[1] else {
+            panic!("expected delivery footprint event");
+        };
+        assert_eq!(payload.order_id, bundle[1]);
+        assert_eq!(payload.co2_g, 120.0);
+        assert_eq!(tracker.totals[&(paris, DeliveryMode::Car)].deliveries, 2);
+
+        // one row per site and mode
+        assert!(!tracker.has_pending());
+        tracker.finish_day()?;
+        assert_eq!(tracker.flush()?.num_rows(), 2);
+        assert!(tracker.totals.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(CarbonConfig::default().validate().is_ok());
+        let config = CarbonConfig {
+            grams_per_km: BTreeMap::from([(DeliveryMode::Car, -1.0)]),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
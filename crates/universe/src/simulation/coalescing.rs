@@ -0,0 +1,144 @@
+//! Coalescing of event batches across steps before they are written.
+//!
+//! Every step writes its events as a new file to the `events` table, so quiet steps
+//! produce many tiny files which are slow to list and scan. With [`EventCoalescing`],
+//! the events of consecutive steps are buffered until they reach a target number of
+//! rows or the oldest of them reaches a maximum age in simulated time, and are then
+//! written as a single file. Buffered events are also written whenever the other
+//! results are, e.g. before a snapshot, while paused and at the end of a run, so the
+//! `events` table is consistent with every snapshot.
+
+use arrow::array::RecordBatch;
+use arrow::compute::concat_batches;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::builders::EVENTS_SCHEMA;
+use crate::{Error, Result};
+
+/// When buffered events are written to the `events` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventCoalescing {
+    /// Write buffered events once they span at least this many rows
+    pub target_rows: usize,
+
+    /// Write buffered events once the oldest of them is this many simulated minutes old
+    pub max_age_minutes: u32,
+}
+
+impl Default for EventCoalescing {
+    fn default() -> Self {
+        Self {
+            target_rows: 100_000,
+            max_age_minutes: 60,
+        }
+    }
+}
+
+impl EventCoalescing {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.target_rows == 0 {
+            return Err(Error::invalid_data(
+                "target rows of event coalescing must be positive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Event batches of recent steps which have not been written yet.
+#[derive(Debug, Default)]
+pub(crate) struct EventBatches {
+    batches: Vec<RecordBatch>,
+    rows: usize,
+
+    /// Start of the step of the oldest buffered batch
+    since: Option<DateTime<Utc>>,
+}
+
+impl EventBatches {
+    /// Buffer the events of the step starting at `time`.
+    pub(crate) fn push(&mut self, time: DateTime<Utc>, batch: RecordBatch) {
+        if batch.num_rows() == 0 {
+            return;
+        }
+        self.since.get_or_insert(time);
+        self.rows += batch.num_rows();
+        self.batches.push(batch);
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.batches.is_empty()
+    }
+
+    /// Whether the buffered events should be written at `now`.
+    pub(crate) fn is_due(&self, coalescing: &EventCoalescing, now: DateTime<Utc>) -> bool {
+        let max_age = TimeDelta::minutes(coalescing.max_age_minutes.into());
+        self.rows >= coalescing.target_rows
+            || self.since.is_some_and(|since| now - since >= max_age)
+    }
+
+    /// Take the buffered events as a single batch.
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        let batch = concat_batches(&EVENTS_SCHEMA, &self.batches)?;
+        self.batches.clear();
+        self.rows = 0;
+        self.since = None;
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::EventDataBuilder;
+    use crate::{EventPayload, StepStartedPayload};
+
+    use super::*;
+
+    fn batch(time: DateTime<Utc>, events: usize) -> RecordBatch {
+        let mut builder = EventDataBuilder::with_capacity(events);
+        for _ in 0..events {
+            builder
+                .add_payload(
+                    time,
+                    &EventPayload::StepStarted(StepStartedPayload {
+                        simulation_time: time,
+                    }),
+                )
+                .unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_event_batches() {
+        let coalescing = EventCoalescing {
+            target_rows: 10,
+            max_age_minutes: 30,
+        };
+        let start = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        let mut batches = EventBatches::default();
+        batches.push(start, batch(start, 0));
+        assert!(!batches.has_pending());
+
+        batches.push(start, batch(start, 4));
+        let later = start + TimeDelta::minutes(10);
+        batches.push(later, batch(later, 4));
+        assert!(!batches.is_due(&coalescing, later));
+
+        // due once the oldest events are old enough
+        assert!(batches.is_due(&coalescing, start + TimeDelta::minutes(30)));
+
+        // or once enough rows are buffered
+        batches.push(later, batch(later, 2));
+        assert!(batches.is_due(&coalescing, later));
+
+        let flushed = batches.flush().unwrap();
+        assert_eq!(flushed.num_rows(), 10);
+        assert!(!batches.has_pending());
+        assert!(!batches.is_due(&coalescing, start + TimeDelta::days(1)));
+    }
+}
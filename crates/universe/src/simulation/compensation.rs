@@ -0,0 +1,261 @@
+//! Automatic compensation of late deliveries.
+//!
+//! A [`CompensationPolicy`] is a list of rules of the form "if an order is delivered
+//! more than X minutes after the promised time, issue a voucher of Y". When an order
+//! is delivered late, the rule with the highest threshold the delay exceeds is
+//! applied, and a [`CompensationIssuedPayload`](crate::CompensationIssuedPayload)
+//! event is emitted. At most one voucher is issued per order.
+//!
+//! The policy is deterministic, so it serves as a baseline to compare other
+//! compensation strategies against. Orders placed before the start of the run are
+//! not compensated, since the time they were promised at is not known.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{OrderId, PersonId};
+use crate::state::OrderStatus;
+use crate::{Currency, Error, EventPayload, Result};
+
+/// Value of a voucher issued by a compensation rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Voucher {
+    /// Fixed amount in the order currency
+    Fixed { amount: f64 },
+    /// Share of the order total, optionally capped at an amount in the order currency
+    Share {
+        share: f64,
+        #[serde(default)]
+        max_amount: Option<f64>,
+    },
+}
+
+impl Voucher {
+    /// Voucher amount for an order with the given total, rounded to cents.
+    fn amount(&self, total: f64) -> f64 {
+        let amount = match self {
+            Voucher::Fixed { amount } => *amount,
+            Voucher::Share { share, max_amount } => {
+                let amount = total * share;
+                max_amount.map_or(amount, |max| amount.min(max))
+            }
+        };
+        (amount * 100.0).round() / 100.0
+    }
+}
+
+/// Issue `voucher` for orders delivered more than `late_minutes` after the promised time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompensationRule {
+    /// Name of the rule, reported on the issued compensations
+    pub name: String,
+    pub late_minutes: f64,
+    pub voucher: Voucher,
+}
+
+impl CompensationRule {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(Error::invalid_data("compensation rules need a name"));
+        }
+        if !(self.late_minutes >= 0.0 && self.late_minutes.is_finite()) {
+            return Err(Error::invalid_data(format!(
+                "compensation rule '{}' needs a non-negative delay",
+                self.name
+            )));
+        }
+        let valid = match &self.voucher {
+            Voucher::Fixed { amount } => *amount >= 0.0,
+            Voucher::Share { share, max_amount } => {
+                (0.0..=1.0).contains(share) && max_amount.is_none_or(|max| max >= 0.0)
+            }
+        };
+        if !valid {
+            return Err(Error::invalid_data(format!(
+                "compensation rule '{}' has an invalid voucher",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Rules compensating customers for late deliveries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompensationPolicy {
+    pub rules: Vec<CompensationRule>,
+}
+
+impl CompensationPolicy {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for rule in &self.rules {
+            rule.validate()?;
+        }
+        Ok(())
+    }
+
+    /// The rule applying to an order delivered `late` after the promised time, if any.
+    pub fn rule(&self, late: Duration) -> Option<&CompensationRule> {
+        let late_minutes = late.num_milliseconds() as f64 / 60_000.0;
+        self.rules
+            .iter()
+            .filter(|rule| late_minutes > rule.late_minutes)
+            .max_by(|a, b| a.late_minutes.total_cmp(&b.late_minutes))
+    }
+}
+
+struct PlacedOrder {
+    person_id: PersonId,
+    promised_at: DateTime<Utc>,
+    total: f64,
+    currency: Currency,
+}
+
+/// Applies a [`CompensationPolicy`] to delivered orders.
+pub(crate) struct Compensator {
+    policy: CompensationPolicy,
+    orders: HashMap<OrderId, PlacedOrder>,
+}
+
+impl Compensator {
+    pub(crate) fn new(policy: CompensationPolicy) -> Self {
+        Self {
+            policy,
+            orders: HashMap::new(),
+        }
+    }
+
+    /// Compensation events for the orders delivered at `now` in `events`.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+    ) -> Vec<EventPayload> {
+        if self.policy.rules.is_empty() {
+            return Vec::new();
+        }
+        let mut compensations = Vec::new();
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(payload) => {
+                    self.orders.insert(
+                        payload.order_id,
+                        PlacedOrder {
+                            person_id: payload.person_id,
+                            promised_at: payload.promised_at,
+                            total: payload.total,
+                            currency: payload.currency,
+                        },
+                    );
+                }
+                EventPayload::OrderUpdated(payload) if !payload.status.is_open() => {
+                    let Some(order) = self.orders.remove(&payload.order_id) else {
+                        continue;
+                    };
+                    if payload.status != OrderStatus::Delivered {
+                        continue;
+                    }
+                    let late = now - order.promised_at;
+                    if let Some(rule) = self.policy.rule(late) {
+                        compensations.push(EventPayload::compensation_issued(
+                            payload.order_id,
+                            order.person_id,
+                            rule.name.clone(),
+                            late.num_milliseconds() as f64 / 1000.0,
+                            rule.voucher.amount(order.total),
+                            order.currency,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        compensations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderCreatedPayload;
+    use crate::test_utils::order_created;
+
+    fn policy() -> CompensationPolicy {
+        serde_json::from_str(
+            r#"{"rules": [
+                {"name": "late", "late_minutes": 10, "voucher": {"type": "fixed", "amount": 5}},
+                {"name": "very_late", "late_minutes": 30, "voucher": {"type": "share", "share": 0.5, "max_amount": 12}}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compensation_rules() {
+        let policy = policy();
+        policy.validate().unwrap();
+        assert!(policy.rule(Duration::minutes(10)).is_none());
+        assert_eq!(policy.rule(Duration::minutes(11)).unwrap().name, "late");
+        assert_eq!(
+            policy.rule(Duration::minutes(45)).unwrap().name,
+            "very_late"
+        );
+        assert_eq!(policy.rules[1].voucher.amount(30.0), 12.0);
+        assert_eq!(policy.rules[1].voucher.amount(10.0), 5.0);
+
+        let invalid = CompensationPolicy {
+            rules: vec![CompensationRule {
+                name: "refund".into(),
+                late_minutes: 5.0,
+                voucher: Voucher::Share {
+                    share: 1.5,
+                    max_amount: None,
+                },
+            }],
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_compensate_late_deliveries() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut compensator = Compensator::new(policy());
+        let eur: Currency = "EUR".parse().unwrap();
+
+        let created = |order_id| {
+            EventPayload::OrderCreated(OrderCreatedPayload {
+                total: 20.0,
+                currency: eur,
+                promised_at: start + Duration::minutes(30),
+                ..order_created(order_id)
+            })
+        };
+        let (on_time, late, unknown) = (OrderId::new(), OrderId::new(), OrderId::new());
+        assert!(
+            compensator
+                .step(start, &[created(on_time), created(late)])
+                .is_empty()
+        );
+
+        let delivered =
+            |order_id| EventPayload::order_updated(order_id, OrderStatus::Delivered, None);
+        let events = compensator.step(
+            start + Duration::minutes(25),
+            &[delivered(on_time), delivered(unknown)],
+        );
+        assert!(events.is_empty());
+        let events = compensator.step(start + Duration::minutes(45), &[delivered(late)]);
+        let [EventPayload::CompensationIssued(payload)] = events.as_slice() else {
+            panic!("expected a single compensation, got {events:?}");
+        };
+        assert_eq!(payload.order_id, late);
+        assert_eq!(payload.rule, "late");
+        assert_eq!(payload.amount, 5.0);
+        assert_eq!(payload.currency, eur);
+        assert_eq!(payload.late_s, 900.0);
+    }
+}
@@ -0,0 +1,425 @@
+//! Steering of running simulations.
+//!
+//! Some settings may be changed while a simulation runs, e.g. to raise demand or
+//! send couriers home during a live demo. A [`SimulationControl`] handle, obtained
+//! from [`Simulation::control`](super::Simulation::control), queues updates to the
+//! [`RuntimeSettings`], which are applied at the start of the next step. Every change
+//! is reported as a `ConfigChanged` event of that step, so the events of a run
+//! explain shifts in its figures.
+//!
+//! All settings start out neutral unless the run was built with other settings, e.g.
+//! from a scenario, i.e. a run that is never steered behaves as it would without
+//! controls.
+//!
+//! Handles may also pause a simulation. A paused simulation finishes the step in
+//! progress and does not start the next one until it is resumed, which leaves the
+//! state consistent, e.g. to write a snapshot.
+//!
+//! Handles may also stop a simulation, e.g. when the process is interrupted. A stopped
+//! run finishes the step in progress and then ends as if all its steps were covered,
+//! i.e. the pending results are written and a final snapshot is taken.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+
+use crate::idents::PersonId;
+use crate::{Error, EventPayload, Result};
+
+/// Settings of a running simulation that may be changed between steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeSettings {
+    /// Factor applied to the probability of customers placing an order
+    pub demand_multiplier: f64,
+
+    /// Probability that a site fails an order right after it was submitted
+    pub order_failure_rate: f64,
+
+    /// Couriers on shift across all sites, all couriers if not set
+    pub active_couriers: Option<usize>,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            demand_multiplier: 1.0,
+            order_failure_rate: 0.0,
+            active_couriers: None,
+        }
+    }
+}
+
+impl RuntimeSettings {
+    pub(crate) fn validate(&self) -> Result<()> {
+        SettingsUpdate {
+            demand_multiplier: Some(self.demand_multiplier),
+            order_failure_rate: Some(self.order_failure_rate),
+            active_couriers: self.active_couriers,
+            all_couriers: false,
+        }
+        .validate()
+    }
+
+    /// Share of the `couriers` of the population on shift.
+    pub(crate) fn courier_share(&self, couriers: usize) -> f64 {
+        match self.active_couriers {
+            Some(active) if couriers > 0 => (active as f64 / couriers as f64).min(1.0),
+            Some(_) => 0.0,
+            None => 1.0,
+        }
+    }
+}
+
+/// Changes to the [`RuntimeSettings`], settings which are not given are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SettingsUpdate {
+    pub demand_multiplier: Option<f64>,
+    pub order_failure_rate: Option<f64>,
+    pub active_couriers: Option<usize>,
+
+    /// Put all couriers back on shift
+    pub all_couriers: bool,
+}
+
+impl SettingsUpdate {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(multiplier) = self.demand_multiplier
+            && !(multiplier.is_finite() && multiplier >= 0.0)
+        {
+            return Err(Error::invalid_data(
+                "demand multiplier must be a non-negative number",
+            ));
+        }
+        if let Some(rate) = self.order_failure_rate
+            && !(0.0..=1.0).contains(&rate)
+        {
+            return Err(Error::invalid_data(format!(
+                "order failure rate {rate} outside of [0, 1]"
+            )));
+        }
+        if self.all_couriers && self.active_couriers.is_some() {
+            return Err(Error::invalid_data(
+                "active couriers cannot be set when putting all couriers on shift",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Apply the update to `settings`, returning the events of the changed settings.
+    fn apply(&self, settings: &mut RuntimeSettings) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        if let Some(multiplier) = self.demand_multiplier
+            && multiplier != settings.demand_multiplier
+        {
+            events.push(EventPayload::config_changed(
+                "demand_multiplier",
+                Some(settings.demand_multiplier),
+                Some(multiplier),
+            ));
+            settings.demand_multiplier = multiplier;
+        }
+        if let Some(rate) = self.order_failure_rate
+            && rate != settings.order_failure_rate
+        {
+            events.push(EventPayload::config_changed(
+                "order_failure_rate",
+                Some(settings.order_failure_rate),
+                Some(rate),
+            ));
+            settings.order_failure_rate = rate;
+        }
+        let active_couriers = match self.active_couriers {
+            Some(active) => Some(Some(active)),
+            None => self.all_couriers.then_some(None),
+        };
+        if let Some(active) = active_couriers
+            && active != settings.active_couriers
+        {
+            events.push(EventPayload::config_changed(
+                "active_couriers",
+                settings.active_couriers.map(|active| active as f64),
+                active.map(|active| active as f64),
+            ));
+            settings.active_couriers = active;
+        }
+        events
+    }
+}
+
+/// Handle to steer a running simulation, e.g. from the handlers of a server.
+#[derive(Debug, Clone)]
+pub struct SimulationControl {
+    updates: mpsc::UnboundedSender<SettingsUpdate>,
+    settings: watch::Receiver<RuntimeSettings>,
+    paused: Arc<watch::Sender<bool>>,
+    stopped: Arc<watch::Sender<bool>>,
+}
+
+impl SimulationControl {
+    /// Queue `update` to be applied at the start of the next step.
+    pub fn update(&self, update: SettingsUpdate) -> Result<()> {
+        update.validate()?;
+        self.updates
+            .send(update)
+            .map_err(|_| Error::invalid_data("simulation is no longer running"))
+    }
+
+    /// Settings as of the last step that started.
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings.borrow().clone()
+    }
+
+    /// Hold the simulation once the step in progress completed.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    /// Continue a paused simulation with its next step.
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// End the run once the step in progress completed, also if it is paused.
+    pub fn stop(&self) {
+        self.stopped.send_replace(true);
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.stopped.borrow()
+    }
+}
+
+/// Receives the updates queued by [`SimulationControl`] handles.
+pub(crate) struct Controls {
+    sender: mpsc::UnboundedSender<SettingsUpdate>,
+    receiver: mpsc::UnboundedReceiver<SettingsUpdate>,
+    settings: watch::Sender<RuntimeSettings>,
+    paused: Arc<watch::Sender<bool>>,
+    stopped: Arc<watch::Sender<bool>>,
+}
+
+impl Controls {
+    pub(crate) fn new(settings: RuntimeSettings) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (settings, _) = watch::channel(settings);
+        let (paused, _) = watch::channel(false);
+        let (stopped, _) = watch::channel(false);
+        Self {
+            sender,
+            receiver,
+            settings,
+            paused: Arc::new(paused),
+            stopped: Arc::new(stopped),
+        }
+    }
+
+    pub(crate) fn handle(&self) -> SimulationControl {
+        SimulationControl {
+            updates: self.sender.clone(),
+            settings: self.settings.subscribe(),
+            paused: self.paused.clone(),
+            stopped: self.stopped.clone(),
+        }
+    }
+
+    pub(crate) fn settings(&self) -> RuntimeSettings {
+        self.settings.borrow().clone()
+    }
+
+    pub(crate) fn set_paused(&self, paused: bool) {
+        self.paused.send_replace(paused);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    pub(crate) fn is_stopped(&self) -> bool {
+        *self.stopped.borrow()
+    }
+
+    /// Wait until the simulation is no longer paused.
+    pub(crate) async fn resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        // the sender lives as long as the controls, so waiting cannot fail
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+
+    /// Apply the updates queued since the last step, in the order they were queued.
+    ///
+    /// Returns the events of the settings that changed.
+    pub(crate) fn take_changes(&mut self) -> Vec<EventPayload> {
+        let mut settings = self.settings();
+        let mut events = Vec::new();
+        while let Ok(update) = self.receiver.try_recv() {
+            events.extend(update.apply(&mut settings));
+        }
+        if !events.is_empty() {
+            tracing::info!(
+                target: "caspers::simulation::controls",
+                "applying {} setting changes: {settings:?}",
+                events.len()
+            );
+            self.settings.send_replace(settings);
+        }
+        events
+    }
+}
+
+/// Whether `courier` is on shift while a `share` of all couriers is.
+///
+/// Couriers are picked by their id, so the same couriers stay on shift across
+/// steps and sites, and raising the share only adds couriers.
+pub(crate) fn on_shift(courier: &PersonId, share: f64) -> bool {
+    if share >= 1.0 {
+        return true;
+    }
+    let id: &[u8] = courier.as_ref();
+    let key = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+    (key as f64 / u64::MAX as f64) < share
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_changes() {
+        let mut controls = Controls::new(RuntimeSettings::default());
+        let control = controls.handle();
+        assert!(controls.take_changes().is_empty());
+
+        control
+            .update(SettingsUpdate {
+                demand_multiplier: Some(2.0),
+                active_couriers: Some(10),
+                ..Default::default()
+            })
+            .unwrap();
+        // settings which do not change are not reported
+        control
+            .update(SettingsUpdate {
+                demand_multiplier: Some(1.5),
+                order_failure_rate: Some(0.0),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(control.settings(), RuntimeSettings::default());
+
+        let events = controls.take_changes();
+        assert_eq!(events.len(), 3);
+        let EventPayload::ConfigChanged(payload) = &events[2] else {
+            panic!("expected config changed event");
+        };
+        assert_eq!(payload.setting, "demand_multiplier");
+        assert_eq!(payload.previous, Some(2.0));
+        assert_eq!(payload.value, Some(1.5));
+        assert_eq!(control.settings().demand_multiplier, 1.5);
+        assert_eq!(control.settings().active_couriers, Some(10));
+
+        control
+            .update(SettingsUpdate {
+                all_couriers: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let events = controls.take_changes();
+        let EventPayload::ConfigChanged(payload) = &events[0] else {
+            panic!("expected config changed event");
+        };
+        assert_eq!(payload.previous, Some(10.0));
+        assert_eq!(payload.value, None);
+        assert_eq!(controls.settings().active_couriers, None);
+    }
+
+    #[tokio::test]
+    async fn test_pause() {
+        let controls = Controls::new(RuntimeSettings::default());
+        let control = controls.handle();
+        assert!(!control.is_paused());
+        controls.resumed().await;
+
+        control.pause();
+        assert!(controls.is_paused());
+        let resumed = controls.resumed();
+        tokio::pin!(resumed);
+        assert!(futures::poll!(resumed.as_mut()).is_pending());
+
+        controls.set_paused(false);
+        assert!(!control.is_paused());
+        resumed.await;
+    }
+
+    #[tokio::test]
+    async fn test_stop() {
+        let controls = Controls::new(RuntimeSettings::default());
+        let control = controls.handle();
+        assert!(!control.is_stopped());
+
+        // stopping wakes a paused simulation, so it can end its run
+        control.pause();
+        let resumed = controls.resumed();
+        tokio::pin!(resumed);
+        assert!(futures::poll!(resumed.as_mut()).is_pending());
+        control.stop();
+        assert!(controls.is_stopped());
+        assert!(!controls.is_paused());
+        resumed.await;
+    }
+
+    #[test]
+    fn test_validate() {
+        let control = Controls::new(RuntimeSettings::default()).handle();
+        for update in [
+            SettingsUpdate {
+                demand_multiplier: Some(-1.0),
+                ..Default::default()
+            },
+            SettingsUpdate {
+                order_failure_rate: Some(1.5),
+                ..Default::default()
+            },
+            SettingsUpdate {
+                active_couriers: Some(3),
+                all_couriers: true,
+                ..Default::default()
+            },
+        ] {
+            assert!(update.validate().is_err());
+        }
+        // updates cannot be queued once the simulation is gone
+        assert!(control.update(SettingsUpdate::default()).is_err());
+    }
+
+    #[test]
+    fn test_on_shift() {
+        let couriers: Vec<_> = (0..1000).map(|_| PersonId::new()).collect();
+        let count = |share: f64| couriers.iter().filter(|c| on_shift(c, share)).count();
+        assert_eq!(count(1.0), 1000);
+        assert_eq!(count(0.0), 0);
+        assert!((400..600).contains(&count(0.5)));
+        // couriers on shift at a lower share stay on shift at a higher one
+        assert!(
+            couriers
+                .iter()
+                .filter(|c| on_shift(c, 0.3))
+                .all(|c| on_shift(c, 0.6))
+        );
+
+        let settings = RuntimeSettings {
+            active_couriers: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(settings.courier_share(20), 0.25);
+        assert_eq!(settings.courier_share(2), 1.0);
+        assert_eq!(RuntimeSettings::default().courier_share(20), 1.0);
+    }
+}
@@ -0,0 +1,421 @@
+//! Courier decisions on delivery offers.
+//!
+//! Couriers are offered deliveries rather than simply assigned to them. Whether a
+//! courier accepts follows a logistic model of the offered earnings and the route
+//! distance: well paid short trips are almost always taken, long trips for little
+//! money are mostly declined. Offers, decisions and the progress of accepted
+//! deliveries are reported as [`CourierUpdatedPayload`](crate::CourierUpdatedPayload)
+//! events, which form a per-courier feed similar to what a courier app shows.
+//!
+//! A [`BehaviorPlugin`](crate::BehaviorPlugin) deciding in `accept_assignment`
+//! replaces the sampled decision, while offers still report the modelled terms.
+//!
+//! Ready orders are dispatched like on a delivery marketplace: the order is offered
+//! to one idle courier at a time. A decline cascades the offer to the next
+//! candidate, while a courier who does not respond holds the offer until it expires
+//! after the [`DispatchPolicy::offer_timeout_secs`]. Couriers are never offered the
+//! same order twice.
+//!
+//! Couriers are searched in the H3 cell of the site at the
+//! [`DispatchPolicy::search_resolution`] first. Orders nobody in the cell takes widen
+//! the search by one ring of neighboring cells every
+//! [`DispatchPolicy::ring_timeout_secs`], up to [`DispatchPolicy::search_rings`]. How
+//! far the search had to expand for each assignment is reported in the metrics.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+use h3o::Resolution;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{OrderId, PersonId};
+use crate::{Error, Result};
+
+/// Logistic model of couriers accepting delivery offers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CourierAcceptance {
+    /// Fixed pay per delivery in the site currency
+    pub base_pay: f64,
+
+    /// Pay per kilometer of the delivery route in the site currency
+    pub pay_per_km: f64,
+
+    /// Log-odds of accepting an offer without earnings or distance
+    pub intercept: f64,
+
+    /// Change in log-odds per unit of earnings
+    pub earnings_weight: f64,
+
+    /// Change in log-odds per kilometer of distance
+    pub distance_weight: f64,
+}
+
+impl Default for CourierAcceptance {
+    fn default() -> Self {
+        Self {
+            base_pay: 3.0,
+            pay_per_km: 1.2,
+            intercept: 0.5,
+            earnings_weight: 0.3,
+            distance_weight: -0.6,
+        }
+    }
+}
+
+/// Terms of a delivery offered to a courier.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CourierOffer {
+    /// Distance of the delivery route in meters
+    pub distance_m: f64,
+
+    /// Earnings for the delivery in the site currency, including the tip
+    pub earnings: f64,
+
+    /// Tip in the site currency the customer added to the order
+    #[serde(default)]
+    pub tip: f64,
+
+    /// Modelled probability that the courier accepts the offer
+    pub acceptance_probability: f64,
+}
+
+impl CourierAcceptance {
+    /// Offer for a delivery route of `distance_m` meters on an order tipped with `tip`.
+    pub fn offer(&self, distance_m: f64, tip: f64) -> CourierOffer {
+        let distance_m = distance_m.max(0.0);
+        let tip = tip.max(0.0);
+        let earnings = self.base_pay + self.pay_per_km * distance_m / 1000.0 + tip;
+        CourierOffer {
+            distance_m,
+            earnings,
+            tip,
+            acceptance_probability: self.probability(distance_m, earnings),
+        }
+    }
+
+    /// Probability that a courier accepts a delivery with the given terms.
+    pub fn probability(&self, distance_m: f64, earnings: f64) -> f64 {
+        let log_odds = self.intercept
+            + self.earnings_weight * earnings
+            + self.distance_weight * distance_m / 1000.0;
+        let probability = 1.0 / (1.0 + (-log_odds).exp());
+        // degenerate weights must not break sampling
+        if probability.is_nan() {
+            0.0
+        } else {
+            probability
+        }
+    }
+}
+
+impl CourierOffer {
+    /// Sample the courier's decision on the offer.
+    pub(crate) fn decide(&self, rng: &mut impl Rng) -> bool {
+        rng.random_bool(self.acceptance_probability.clamp(0.0, 1.0))
+    }
+}
+
+/// Rules for offering deliveries to couriers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DispatchPolicy {
+    /// Seconds a courier has to respond before an offer expires
+    pub offer_timeout_secs: i64,
+
+    /// Maximum number of offers made for a single order within one step
+    pub max_offers_per_step: usize,
+
+    /// Probability that a courier responds to an offer before it expires
+    pub response_rate: f64,
+
+    /// H3 resolution of the cells in which couriers are searched around the site
+    pub search_resolution: u8,
+
+    /// Rings of neighboring cells up to which the search for couriers expands
+    pub search_rings: u32,
+
+    /// Seconds an order waits for a courier before the search expands by another ring
+    pub ring_timeout_secs: i64,
+}
+
+impl Default for DispatchPolicy {
+    fn default() -> Self {
+        Self {
+            offer_timeout_secs: 60,
+            max_offers_per_step: 3,
+            response_rate: 0.9,
+            search_resolution: 8,
+            search_rings: 2,
+            ring_timeout_secs: 120,
+        }
+    }
+}
+
+impl DispatchPolicy {
+    pub(crate) fn validate(&self) -> Result<()> {
+        Resolution::try_from(self.search_resolution)
+            .map_err(|e| Error::invalid_data(format!("invalid courier search resolution: {e}")))?;
+        Ok(())
+    }
+
+    /// Resolution of the cells in which couriers are searched.
+    pub(crate) fn resolution(&self) -> Resolution {
+        Resolution::try_from(self.search_resolution).unwrap_or(Resolution::Eight)
+    }
+
+    /// Sample whether a courier responds to an offer in time.
+    pub(crate) fn responds(&self, rng: &mut impl Rng) -> bool {
+        rng.random_bool(self.response_rate.clamp(0.0, 1.0))
+    }
+}
+
+/// An offer waiting for the courier's response.
+#[derive(Debug, Clone)]
+struct PendingOffer {
+    courier: PersonId,
+    offer: CourierOffer,
+    expires_at: DateTime<Utc>,
+}
+
+/// Progress of offering a single order.
+#[derive(Debug, Clone, Default)]
+struct OrderDispatch {
+    /// Couriers who declined or let an offer for the order expire
+    passed: HashSet<PersonId>,
+    pending: Option<PendingOffer>,
+    /// Time the search for a courier started
+    searching_since: Option<DateTime<Utc>>,
+}
+
+/// Offers of ready orders at a site.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Dispatcher {
+    policy: DispatchPolicy,
+    orders: BTreeMap<OrderId, OrderDispatch>,
+    /// Number of assignments by the ring in which the courier was found
+    assignments_by_ring: BTreeMap<u32, usize>,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(policy: DispatchPolicy) -> Self {
+        Self {
+            policy,
+            orders: BTreeMap::new(),
+            assignments_by_ring: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> &DispatchPolicy {
+        &self.policy
+    }
+
+    /// Forget orders which are no longer waiting for a courier.
+    pub(crate) fn retain(&mut self, mut is_ready: impl FnMut(&OrderId) -> bool) {
+        self.orders.retain(|order_id, _| is_ready(order_id));
+    }
+
+    /// Expire offers without response, returning the courier, order and terms of each.
+    pub(crate) fn expire(&mut self, now: DateTime<Utc>) -> Vec<(PersonId, OrderId, CourierOffer)> {
+        let mut expired = Vec::new();
+        for (order_id, dispatch) in self.orders.iter_mut() {
+            if let Some(pending) = dispatch
+                .pending
+                .take_if(|pending| pending.expires_at <= now)
+            {
+                dispatch.passed.insert(pending.courier);
+                expired.push((pending.courier, *order_id, pending.offer));
+            }
+        }
+        expired
+    }
+
+    /// Couriers holding an offer they have not responded to.
+    pub(crate) fn reserved(&self) -> HashSet<PersonId> {
+        self.orders
+            .values()
+            .filter_map(|dispatch| dispatch.pending.as_ref().map(|pending| pending.courier))
+            .collect()
+    }
+
+    /// Whether the order is currently offered to a courier.
+    pub(crate) fn is_pending(&self, order_id: &OrderId) -> bool {
+        self.orders
+            .get(order_id)
+            .is_some_and(|dispatch| dispatch.pending.is_some())
+    }
+
+    /// Whether the courier may still be offered the order.
+    pub(crate) fn is_candidate(&self, order_id: &OrderId, courier: &PersonId) -> bool {
+        self.orders
+            .get(order_id)
+            .is_none_or(|dispatch| !dispatch.passed.contains(courier))
+    }
+
+    /// Record that the courier declined the order.
+    pub(crate) fn decline(&mut self, order_id: OrderId, courier: PersonId) {
+        self.orders
+            .entry(order_id)
+            .or_default()
+            .passed
+            .insert(courier);
+    }
+
+    /// Record an offer the courier did not respond to yet.
+    pub(crate) fn hold(
+        &mut self,
+        order_id: OrderId,
+        courier: PersonId,
+        offer: CourierOffer,
+        now: DateTime<Utc>,
+    ) {
+        self.orders.entry(order_id).or_default().pending = Some(PendingOffer {
+            courier,
+            offer,
+            expires_at: now + Duration::seconds(self.policy.offer_timeout_secs.max(0)),
+        });
+    }
+
+    /// Rings around the site searched for couriers for the order at `now`.
+    ///
+    /// The search of orders not seen before starts at `now`.
+    pub(crate) fn search_rings(&mut self, order_id: OrderId, now: DateTime<Utc>) -> u32 {
+        let dispatch = self.orders.entry(order_id).or_default();
+        let since = *dispatch.searching_since.get_or_insert(now);
+        if self.policy.ring_timeout_secs <= 0 {
+            return self.policy.search_rings;
+        }
+        let expansions = (now - since).num_seconds() / self.policy.ring_timeout_secs;
+        expansions.clamp(0, self.policy.search_rings as i64) as u32
+    }
+
+    /// Record that the order was accepted by a courier found in `ring`.
+    pub(crate) fn accept(&mut self, order_id: &OrderId, ring: u32) {
+        self.orders.remove(order_id);
+        *self.assignments_by_ring.entry(ring).or_default() += 1;
+    }
+
+    /// Take the number of assignments by ring recorded since the last call.
+    pub(crate) fn take_assignments_by_ring(&mut self) -> BTreeMap<u32, usize> {
+        std::mem::take(&mut self.assignments_by_ring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_courier_acceptance() {
+        let model = CourierAcceptance::default();
+
+        let short = model.offer(1_000.0, 0.0);
+        let long = model.offer(10_000.0, 0.0);
+        assert!(long.earnings > short.earnings);
+        assert!(short.acceptance_probability > long.acceptance_probability);
+        assert!((0.0..=1.0).contains(&long.acceptance_probability));
+
+        // better pay for the same distance is more attractive
+        assert!(model.probability(5_000.0, 20.0) > model.probability(5_000.0, 5.0));
+
+        // tips are paid out to the courier
+        let tipped = model.offer(1_000.0, 4.0);
+        assert_eq!(tipped.earnings, short.earnings + 4.0);
+        assert!(tipped.acceptance_probability > short.acceptance_probability);
+
+        let never = CourierAcceptance {
+            intercept: f64::NEG_INFINITY,
+            ..Default::default()
+        };
+        let offer = never.offer(1_000.0, 0.0);
+        assert_eq!(offer.acceptance_probability, 0.0);
+        assert!(!offer.decide(&mut rand::rng()));
+
+        let broken = CourierAcceptance {
+            intercept: f64::NAN,
+            ..Default::default()
+        };
+        assert_eq!(broken.offer(1_000.0, 0.0).acceptance_probability, 0.0);
+    }
+
+    #[test]
+    fn test_dispatcher() {
+        let mut dispatcher = Dispatcher::new(DispatchPolicy {
+            offer_timeout_secs: 60,
+            ..Default::default()
+        });
+        let order = OrderId::new();
+        let first = PersonId::new();
+        let second = PersonId::new();
+        let offer = CourierAcceptance::default().offer(1_000.0, 0.0);
+        let now = Utc::now();
+
+        // declined orders are not offered to the same courier again
+        dispatcher.decline(order, first);
+        assert!(!dispatcher.is_candidate(&order, &first));
+        assert!(dispatcher.is_candidate(&order, &second));
+
+        // unanswered offers reserve the courier until they expire
+        dispatcher.hold(order, second, offer, now);
+        assert!(dispatcher.is_pending(&order));
+        assert!(dispatcher.reserved().contains(&second));
+        assert!(dispatcher.expire(now + Duration::seconds(30)).is_empty());
+
+        let expired = dispatcher.expire(now + Duration::seconds(60));
+        assert_eq!(expired, vec![(second, order, offer)]);
+        assert!(!dispatcher.is_pending(&order));
+        assert!(!dispatcher.is_candidate(&order, &second));
+        assert!(dispatcher.reserved().is_empty());
+
+        // accepted and vanished orders are forgotten
+        dispatcher.accept(&order, 0);
+        assert!(dispatcher.is_candidate(&order, &first));
+        dispatcher.decline(order, first);
+        dispatcher.retain(|_| false);
+        assert!(dispatcher.is_candidate(&order, &first));
+    }
+
+    #[test]
+    fn test_search_rings() {
+        let mut dispatcher = Dispatcher::new(DispatchPolicy {
+            search_rings: 2,
+            ring_timeout_secs: 120,
+            ..Default::default()
+        });
+        let order = OrderId::new();
+        let now = Utc::now();
+
+        // the search expands by a ring every two minutes, up to two rings
+        assert_eq!(dispatcher.search_rings(order, now), 0);
+        assert_eq!(
+            dispatcher.search_rings(order, now + Duration::seconds(119)),
+            0
+        );
+        assert_eq!(
+            dispatcher.search_rings(order, now + Duration::seconds(120)),
+            1
+        );
+        assert_eq!(
+            dispatcher.search_rings(order, now + Duration::minutes(30)),
+            2
+        );
+
+        dispatcher.accept(&order, 2);
+        dispatcher.accept(&OrderId::new(), 0);
+        dispatcher.accept(&OrderId::new(), 2);
+        let stats = dispatcher.take_assignments_by_ring();
+        assert_eq!(stats, BTreeMap::from([(0, 1), (2, 2)]));
+        assert!(dispatcher.take_assignments_by_ring().is_empty());
+
+        // accepted orders start a new search when they are ready again
+        let later = now + Duration::hours(1);
+        assert_eq!(dispatcher.search_rings(order, later), 0);
+
+        let invalid = DispatchPolicy {
+            search_resolution: 16,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
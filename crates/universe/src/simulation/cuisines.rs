@@ -0,0 +1,242 @@
+//! Customer preferences for cuisines and the cuisine market share of the sites.
+//!
+//! Brands are categorized by their [`Cuisine`]. Customers choose menu items with a
+//! relative weight per cuisine given by the [`CuisinePreferences`] of the simulation,
+//! and the cuisine of each ordered item is recorded on the
+//! [`OrderCreatedPayload`](crate::OrderCreatedPayload).
+//!
+//! After a run, the `order_created` events of all runs of the simulation are
+//! aggregated per site and cuisine, and written to the `cuisine_market_share` results
+//! table with the snapshot taken at the end of the run. Order totals are attributed
+//! to the cuisines of an order by their share of its items, and reported in the base
+//! currency of the configured [`ExchangeRates`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use arrow::array::RecordBatch;
+use arrow::array::cast::AsArray as _;
+use chrono::{DateTime, Utc};
+use datafusion::prelude::{col, lit};
+use serde::{Deserialize, Serialize};
+
+use crate::builders::{CuisineSales, MarketShareBuffer};
+use crate::context::SimulationContext;
+use crate::idents::SiteId;
+use crate::{Cuisine, Error, EventPayload, ExchangeRates, Money, Result};
+
+static ORDER_CREATED_TYPE: &str = "io.caspers.orders.created";
+
+/// Relative weights with which customers choose menu items of each cuisine.
+///
+/// Items of cuisines without a weight, and of uncategorized brands, have a weight of one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CuisinePreferences {
+    pub weights: BTreeMap<Cuisine, f64>,
+}
+
+impl CuisinePreferences {
+    /// Choose items of `cuisine` with a relative `weight`.
+    pub fn with_weight(mut self, cuisine: Cuisine, weight: f64) -> Self {
+        self.weights.insert(cuisine, weight);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (cuisine, weight) in &self.weights {
+            if !(*weight >= 0.0 && weight.is_finite()) {
+                return Err(Error::invalid_data(format!(
+                    "cuisine preference for '{cuisine}' needs a non-negative weight"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Weight of an item of `cuisine`.
+    pub fn weight(&self, cuisine: Option<Cuisine>) -> f64 {
+        cuisine
+            .and_then(|cuisine| self.weights.get(&cuisine).copied())
+            .unwrap_or(1.0)
+    }
+
+    /// Weights of menu items of the given cuisines, `None` if items are chosen uniformly.
+    pub(crate) fn item_weights(&self, cuisines: &[Option<Cuisine>]) -> Option<Vec<f64>> {
+        (!self.weights.is_empty()).then(|| {
+            cuisines
+                .iter()
+                .map(|cuisine| self.weight(*cuisine))
+                .collect()
+        })
+    }
+}
+
+/// Aggregates created orders into the sales of each cuisine per site.
+pub(crate) struct CuisineMarketShare {
+    rates: ExchangeRates,
+    sales: HashMap<(SiteId, Option<Cuisine>), CuisineSales>,
+}
+
+impl CuisineMarketShare {
+    pub(crate) fn new(rates: ExchangeRates) -> Self {
+        Self {
+            rates,
+            sales: HashMap::new(),
+        }
+    }
+
+    /// Account for a recorded event.
+    pub(crate) fn record(&mut self, event: &EventPayload) -> Result<()> {
+        let EventPayload::OrderCreated(payload) = event else {
+            return Ok(());
+        };
+        if payload.items.is_empty() {
+            return Ok(());
+        }
+        let revenue = self.rates.convert(
+            Money::new(payload.total, payload.currency),
+            self.rates.base(),
+        )?;
+
+        let mut items = BTreeMap::<_, u64>::new();
+        for idx in 0..payload.items.len() {
+            let cuisine = payload.cuisines.get(idx).copied().flatten();
+            *items.entry(cuisine).or_default() += 1;
+        }
+        for (cuisine, count) in items {
+            let share = count as f64 / payload.items.len() as f64;
+            let entry = self
+                .sales
+                .entry((payload.site_id, cuisine))
+                .or_insert_with(|| CuisineSales {
+                    site_id: payload.site_id,
+                    cuisine,
+                    orders: 0,
+                    items: 0,
+                    revenue: Money::zero(self.rates.base()),
+                });
+            entry.orders += 1;
+            entry.items += count;
+            entry.revenue = entry
+                .revenue
+                .checked_add(Money::new(revenue.amount() * share, revenue.currency()))?;
+        }
+        Ok(())
+    }
+
+    /// Account for the orders created in any run of the simulation up to `end`.
+    pub(crate) async fn record_results(
+        &mut self,
+        ctx: &SimulationContext,
+        end: DateTime<Utc>,
+    ) -> Result<()> {
+        let recorded = ctx
+            .results()
+            .events_between(DateTime::UNIX_EPOCH, end)
+            .await?
+            .filter(col("type").eq(lit(ORDER_CREATED_TYPE)))?
+            .select_columns(&["data"])?;
+        for batch in ctx.collect(recorded).await? {
+            for data in batch.column(0).as_string::<i64>().iter().flatten() {
+                self.record(&serde_json::from_str(data)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<RecordBatch> {
+        let mut site_totals = HashMap::<SiteId, (u64, f64)>::new();
+        for sales in self.sales.values() {
+            let totals = site_totals.entry(sales.site_id).or_default();
+            totals.0 += sales.items;
+            totals.1 += sales.revenue.amount();
+        }
+
+        let mut sales: Vec<_> = self.sales.into_values().collect();
+        sales.sort_by_key(|sales| (sales.site_id.to_string(), sales.cuisine));
+        let mut buffer = MarketShareBuffer::new();
+        for sales in &sales {
+            let (items, revenue) = site_totals[&sales.site_id];
+            buffer.push(sales, items, revenue)?;
+        }
+        buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Array as _;
+    use arrow::datatypes::{Float64Type, Int64Type};
+    use chrono::Duration;
+
+    use super::*;
+    use crate::OrderCreatedPayload;
+    use crate::idents::{BrandId, MenuItemId, OrderId};
+    use crate::test_utils::order_created;
+
+    fn created(cuisines: Vec<Option<Cuisine>>, total: f64) -> EventPayload {
+        let item = (
+            BrandId::from_name("asian"),
+            MenuItemId::from_names("asian", "Vegetable Fried Rice"),
+        );
+        EventPayload::OrderCreated(OrderCreatedPayload {
+            items: vec![item; cuisines.len()],
+            cuisines,
+            total,
+            promised_at: Utc::now() + Duration::minutes(30),
+            ..order_created(OrderId::new())
+        })
+    }
+
+    #[test]
+    fn test_item_weights() {
+        let preferences = CuisinePreferences::default();
+        preferences.validate().unwrap();
+        assert!(preferences.item_weights(&[Some(Cuisine::Asian)]).is_none());
+
+        let preferences = preferences.with_weight(Cuisine::Mexican, 3.0);
+        assert_eq!(
+            preferences
+                .item_weights(&[Some(Cuisine::Asian), Some(Cuisine::Mexican), None])
+                .unwrap(),
+            vec![1.0, 3.0, 1.0]
+        );
+        assert!(
+            CuisinePreferences::default()
+                .with_weight(Cuisine::Asian, -1.0)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_market_share() -> Result<()> {
+        let mut market_share = CuisineMarketShare::new(ExchangeRates::default());
+        let (asian, mexican) = (Some(Cuisine::Asian), Some(Cuisine::Mexican));
+        market_share.record(&created(vec![asian, asian, mexican], 30.0))?;
+        market_share.record(&created(vec![mexican], 10.0))?;
+        market_share.record(&created(vec![None], 20.0))?;
+
+        let batch = market_share.finish()?;
+        assert_eq!(batch.num_rows(), 3);
+        let cuisines = batch.column(1).as_string_view();
+        let orders = batch.column(2).as_primitive::<Int64Type>();
+        let items = batch.column(3).as_primitive::<Int64Type>();
+        let revenue = batch.column(5).as_primitive::<Float64Type>();
+        let item_shares = batch.column(6).as_primitive::<Float64Type>();
+        let revenue_shares = batch.column(7).as_primitive::<Float64Type>();
+
+        // uncategorized brands are sorted first
+        assert!(cuisines.is_null(0));
+        assert_eq!(cuisines.value(1), "asian");
+        assert_eq!((orders.value(1), items.value(1)), (1, 2));
+        assert_eq!(revenue.value(1), 20.0);
+        assert_eq!(item_shares.value(1), 0.4);
+        assert!((revenue_shares.value(1) - 1.0 / 3.0).abs() < 1e-9);
+
+        assert_eq!(cuisines.value(2), "mexican");
+        assert_eq!((orders.value(2), items.value(2)), (2, 2));
+        assert_eq!(revenue.value(2), 20.0);
+        Ok(())
+    }
+}
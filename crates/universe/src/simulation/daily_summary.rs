@@ -0,0 +1,248 @@
+//! Daily rollups of orders, revenue, delivery performance and courier load.
+//!
+//! Reporting queries mostly ask for per-day figures, which would otherwise require
+//! a full scan of the raw events. When enabled, the events of each step are rolled
+//! up while the simulation runs, and every time the simulation clock passes midnight
+//! (UTC) the completed day is written to the `daily_summary` results table.
+//!
+//! The day a run ends in is written at the end of the run with the steps it covered,
+//! so a day split across runs is reported by each of them for its part. Revenue, and
+//! the membership fees billed during the day as recurring revenue, are reported in
+//! the base currency of the configured [`ExchangeRates`]. Deliveries of
+//! orders placed before the start of the run count as delivered, but are left out
+//! of the on-time rate and delivery time, since their promised time is not known.
+
+use std::collections::HashMap;
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, DurationRound as _, TimeDelta, Utc};
+
+use crate::builders::{DailySummaryBuffer, DaySummary};
+use crate::idents::OrderId;
+use crate::state::{OrderStatus, SimulationStats};
+use crate::{Error, EventPayload, ExchangeRates, Money, Result};
+
+struct PlacedOrder {
+    placed_at: DateTime<Utc>,
+    promised_at: DateTime<Utc>,
+}
+
+/// Rolls up the events of each simulated day.
+pub(crate) struct DailySummary {
+    rates: ExchangeRates,
+    orders: HashMap<OrderId, PlacedOrder>,
+    /// The day currently rolled up
+    current: Option<DaySummary>,
+    /// Completed days waiting to be written
+    buffer: DailySummaryBuffer,
+}
+
+impl DailySummary {
+    pub(crate) fn new(rates: ExchangeRates) -> Self {
+        Self {
+            rates,
+            orders: HashMap::new(),
+            current: None,
+            buffer: DailySummaryBuffer::new(),
+        }
+    }
+
+    /// Account for the events of the step at `now` and the stats after the step.
+    ///
+    /// The previous day is completed once a step starts on a new day.
+    pub(crate) fn record(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+        stats: &SimulationStats,
+    ) -> Result<()> {
+        let day = now
+            .duration_trunc(TimeDelta::days(1))
+            .map_err(|e| Error::invalid_data(format!("invalid step time: {e}")))?;
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|current| current.day != day)
+        {
+            self.finish_day();
+        }
+        let base = self.rates.base();
+        let summary = self
+            .current
+            .get_or_insert_with(|| DaySummary::new(day, Money::zero(base)));
+
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(payload) => {
+                    summary.orders += 1;
+                    let revenue = self
+                        .rates
+                        .convert(Money::new(payload.total, payload.currency), base)?;
+                    summary.revenue = summary.revenue.checked_add(revenue)?;
+                    self.orders.insert(
+                        payload.order_id,
+                        PlacedOrder {
+                            placed_at: now,
+                            promised_at: payload.promised_at,
+                        },
+                    );
+                }
+                EventPayload::MembershipBilled(payload) => {
+                    let fee = self
+                        .rates
+                        .convert(Money::new(payload.amount, payload.currency), base)?;
+                    summary.membership_revenue = summary.membership_revenue.checked_add(fee)?;
+                }
+                EventPayload::OrderUpdated(payload) => match payload.status {
+                    OrderStatus::Delivered => {
+                        summary.delivered_orders += 1;
+                        let Some(order) = self.orders.remove(&payload.order_id) else {
+                            continue;
+                        };
+                        summary.promised_deliveries += 1;
+                        if now <= order.promised_at {
+                            summary.on_time_deliveries += 1;
+                        }
+                        summary.delivery_time_s +=
+                            (now - order.placed_at).num_milliseconds() as f64 / 1000.0;
+                    }
+                    OrderStatus::Cancelled | OrderStatus::Failed => {
+                        summary.failed_orders += 1;
+                        self.orders.remove(&payload.order_id);
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+
+        summary.steps += 1;
+        summary.courier_utilization += stats.courier_utilization();
+        Ok(())
+    }
+
+    /// Complete the day currently rolled up, e.g. at the end of a run.
+    pub(crate) fn finish_day(&mut self) {
+        if let Some(summary) = self.current.take() {
+            self.buffer.push(&summary);
+        }
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::idents::PersonId;
+    use crate::test_utils::order_created;
+    use crate::{Currency, OrderCreatedPayload};
+    use arrow::array::Array as _;
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::{Float64Type, Int64Type, TimestampMillisecondType};
+    use chrono::Duration;
+
+    use super::*;
+
+    fn created(order_id: OrderId, total: f64, promised_at: DateTime<Utc>) -> EventPayload {
+        EventPayload::OrderCreated(OrderCreatedPayload {
+            total,
+            promised_at,
+            ..order_created(order_id)
+        })
+    }
+
+    fn updated(order_id: OrderId, status: OrderStatus) -> EventPayload {
+        EventPayload::order_updated(order_id, status, None)
+    }
+
+    fn stats(busy_couriers: usize) -> SimulationStats {
+        SimulationStats {
+            people_by_role: BTreeMap::from([("courier".to_string(), 4)]),
+            busy_couriers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_daily_summary() -> Result<()> {
+        let mut summary = DailySummary::new(ExchangeRates::new(Currency::USD));
+        let evening = "2025-01-01T23:30:00Z".parse::<DateTime<Utc>>().unwrap();
+        let (late, on_time, failed) = (OrderId::new(), OrderId::new(), OrderId::new());
+
+        summary.record(
+            evening,
+            &[
+                created(late, 10.0, evening + Duration::minutes(20)),
+                created(on_time, 12.5, evening + Duration::minutes(40)),
+                created(failed, 8.0, evening + Duration::minutes(40)),
+                EventPayload::membership_billed(PersonId::new(), 9.99, Currency::USD),
+            ],
+            &stats(0),
+        )?;
+        summary.record(
+            evening + Duration::minutes(15),
+            &[updated(failed, OrderStatus::Failed)],
+            &stats(2),
+        )?;
+        assert!(!summary.has_pending());
+
+        // the first step after midnight completes the previous day
+        let night = evening + Duration::minutes(35);
+        summary.record(
+            night,
+            &[
+                updated(late, OrderStatus::Delivered),
+                updated(on_time, OrderStatus::Delivered),
+                // deliveries of orders placed before the run are counted, but not timed
+                updated(OrderId::new(), OrderStatus::Delivered),
+            ],
+            &stats(1),
+        )?;
+        assert!(summary.has_pending());
+        summary.finish_day();
+
+        let batch = summary.flush()?;
+        assert_eq!(batch.num_rows(), 2);
+        let days = batch.column(0).as_primitive::<TimestampMillisecondType>();
+        let int = |idx: usize| {
+            batch
+                .column(idx)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec()
+        };
+        let float = |idx: usize| batch.column(idx).as_primitive::<Float64Type>();
+        assert_eq!(
+            days.values(),
+            &[
+                "2025-01-01T00:00:00Z"
+                    .parse::<DateTime<Utc>>()
+                    .unwrap()
+                    .timestamp_millis(),
+                "2025-01-02T00:00:00Z"
+                    .parse::<DateTime<Utc>>()
+                    .unwrap()
+                    .timestamp_millis(),
+            ]
+        );
+        assert_eq!(int(1), [2, 1]);
+        assert_eq!(int(2), [3, 0]);
+        assert_eq!(int(3), [0, 3]);
+        assert_eq!(int(4), [1, 0]);
+        assert_eq!(float(6).values(), &[30.5, 0.0]);
+        assert_eq!(float(7).values(), &[9.99, 0.0]);
+        assert!(float(8).is_null(0));
+        assert_eq!(float(8).value(1), 0.5);
+        assert_eq!(float(9).value(1), 2100.0);
+        assert_eq!(float(10).values(), &[0.25, 0.25]);
+        Ok(())
+    }
+}
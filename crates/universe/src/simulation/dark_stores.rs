@@ -0,0 +1,689 @@
+//! Grocery dark stores.
+//!
+//! A site may be run as a dark store instead of a ghost kitchen. Its brands and menu
+//! items make up the catalog, with every menu item stocked as a SKU. Rather than being
+//! cooked at kitchen stations, the lines of an order are picked from the shelves by one
+//! of the pickers of the store, which takes a fixed time per SKU. Picked orders are
+//! packed and handed to couriers like any other order.
+//!
+//! Every SKU starts from its initial stock, and picking a line takes one unit. Stocks
+//! are replenished to their initial level at a fixed interval.
+//!
+//! When a SKU is out of stock as its line is picked, the first of its configured
+//! substitutes still in stock is suggested to the customer, reserving one unit of it.
+//! The customer responds after a while: accepted substitutes are picked in place of the
+//! original SKU and the order total changes by the difference in price, rejected ones
+//! remove the line and its price from the order. Orders without a substitute in stock
+//! fail as a whole, as do orders of which every line was removed.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::OrderLine;
+use crate::idents::{OrderId, OrderLineId, SiteId};
+use crate::state::OrderLineStatus;
+use crate::{Error, EventPayload, Result, SubstitutionStatus};
+
+/// Picking time and stock of a single SKU, overriding the defaults of the store.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkuConfig {
+    /// Seconds it takes to pick the SKU
+    pub pick_secs: Option<i64>,
+
+    /// Units of the SKU in stock at the start of a run and after every restock
+    pub initial_stock: Option<u32>,
+
+    /// SKUs suggested in place of this one when it is out of stock, in order of preference
+    pub substitutes: Vec<String>,
+}
+
+/// How customers respond to substitutes suggested for items out of stock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubstitutionConfig {
+    /// Probability that a customer accepts a suggested substitute
+    pub acceptance_rate: f64,
+
+    /// Seconds customers take to respond to a suggestion on average
+    pub response_secs: i64,
+}
+
+impl Default for SubstitutionConfig {
+    fn default() -> Self {
+        Self {
+            acceptance_rate: 0.7,
+            response_secs: 120,
+        }
+    }
+}
+
+impl SubstitutionConfig {
+    fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.acceptance_rate) {
+            return Err(Error::invalid_data(
+                "substitution acceptance rate must be between 0 and 1",
+            ));
+        }
+        if self.response_secs < 0 {
+            return Err(Error::invalid_data(
+                "substitution response times must not be negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sample whether a customer accepts a substitute and how long they take to respond.
+    fn sample(&self, rng: &mut impl Rng) -> (bool, Duration) {
+        let accepted = rng.random_bool(self.acceptance_rate);
+        let response_secs = rng.random_range(0..=self.response_secs * 2);
+        (accepted, Duration::seconds(response_secs))
+    }
+}
+
+/// Pickers, picking times and inventory of a grocery dark store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DarkStoreConfig {
+    /// Number of order lines that can be picked at the same time
+    pub pickers: usize,
+
+    /// Seconds it takes to pick a SKU
+    pub pick_secs: i64,
+
+    /// Units of every SKU in stock at the start of a run and after every restock
+    pub initial_stock: u32,
+
+    /// Hours between deliveries replenishing all SKUs to their initial stock
+    pub restock_hours: f64,
+
+    /// Picking times, stock and substitutes of individual SKUs, keyed by the name of the menu item
+    pub skus: HashMap<String, SkuConfig>,
+
+    /// Customer responses to substitutes of SKUs out of stock
+    pub substitutions: SubstitutionConfig,
+}
+
+impl Default for DarkStoreConfig {
+    fn default() -> Self {
+        Self {
+            pickers: 3,
+            pick_secs: 45,
+            initial_stock: 50,
+            restock_hours: 24.0,
+            skus: HashMap::new(),
+            substitutions: SubstitutionConfig::default(),
+        }
+    }
+}
+
+impl DarkStoreConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.pickers == 0 {
+            return Err(Error::invalid_data("dark stores need at least one picker"));
+        }
+        let pick_secs = self.skus.values().filter_map(|sku| sku.pick_secs);
+        if std::iter::once(self.pick_secs)
+            .chain(pick_secs)
+            .any(|secs| secs < 0)
+        {
+            return Err(Error::invalid_data("picking times must not be negative"));
+        }
+        if self.restock_hours.is_nan() || self.restock_hours <= 0.0 {
+            return Err(Error::invalid_data(
+                "dark stores need a positive restock interval",
+            ));
+        }
+        self.substitutions.validate()
+    }
+
+    fn pick_time(&self, sku: &str) -> Duration {
+        let secs = self.skus.get(sku).and_then(|sku| sku.pick_secs);
+        Duration::seconds(secs.unwrap_or(self.pick_secs))
+    }
+
+    fn initial_stock(&self, sku: &str) -> u32 {
+        let stock = self.skus.get(sku).and_then(|sku| sku.initial_stock);
+        stock.unwrap_or(self.initial_stock)
+    }
+
+    fn substitutes(&self, sku: &str) -> &[String] {
+        self.skus
+            .get(sku)
+            .map_or(&[], |sku| sku.substitutes.as_slice())
+    }
+
+    fn restock_interval(&self) -> Duration {
+        Duration::milliseconds((self.restock_hours * 3_600_000.0) as i64)
+    }
+}
+
+/// An order line and the SKU it is picked from.
+#[derive(Clone)]
+struct PickLine {
+    order_line: OrderLine,
+    sku: String,
+    /// Whether a unit of the SKU was reserved for the line when it was suggested as a substitute
+    reserved: bool,
+}
+
+/// A substitute suggested to the customer, waiting for their response.
+#[derive(Clone)]
+struct Suggestion {
+    line: PickLine,
+    substitute: String,
+    accepted: bool,
+    respond_at: DateTime<Utc>,
+}
+
+/// Picks order lines from the inventory of a dark store.
+#[derive(Clone)]
+pub(crate) struct DarkStore {
+    site_id: SiteId,
+    config: DarkStoreConfig,
+    /// Prices of the SKUs in the currency of the site
+    prices: HashMap<String, f64>,
+    /// Units in stock by SKU, populated when a SKU is first ordered
+    stock: HashMap<String, u32>,
+    /// Time the next delivery replenishes the stock
+    restock_at: Option<DateTime<Utc>>,
+    /// Lines waiting for a picker
+    queue: VecDeque<PickLine>,
+    /// Lines being picked and the time they are picked at
+    picking: Vec<(PickLine, DateTime<Utc>)>,
+    /// Lines out of stock waiting for the customer to respond to a substitute
+    suggested: Vec<Suggestion>,
+    completed: Vec<(OrderId, OrderLineId)>,
+    /// Lines removed from their orders as the customer rejected the substitute
+    removed: Vec<(OrderId, OrderLineId)>,
+    failed: Vec<OrderId>,
+}
+
+impl DarkStore {
+    /// Create a dark store selling SKUs at `prices`, given in the currency of the site.
+    pub(crate) fn new(
+        site_id: SiteId,
+        config: DarkStoreConfig,
+        prices: HashMap<String, f64>,
+    ) -> Self {
+        Self {
+            site_id,
+            config,
+            prices,
+            stock: HashMap::new(),
+            restock_at: None,
+            queue: VecDeque::new(),
+            picking: Vec::new(),
+            suggested: Vec::new(),
+            completed: Vec::new(),
+            removed: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    /// Queue an order line to be picked from the given SKU.
+    pub(crate) fn queue_order_line(&mut self, order_line: OrderLine, sku: String) -> EventPayload {
+        let event =
+            EventPayload::order_line_updated(order_line.id, OrderLineStatus::Assigned, None, None);
+        self.queue.push_back(PickLine {
+            order_line,
+            sku,
+            reserved: false,
+        });
+        event
+    }
+
+    /// Advance picking over the step from `now` to `next`.
+    ///
+    /// Customer responses to substitutes due by `now` are applied, then waiting lines
+    /// are started on idle pickers at `now`, and lines picked by `next` are completed.
+    /// Lines out of stock are offered a substitute, or fail their order if there is none.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        next: DateTime<Utc>,
+        rng: &mut impl Rng,
+    ) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        let restock_at = *self
+            .restock_at
+            .get_or_insert_with(|| now + self.config.restock_interval());
+        if restock_at <= now {
+            tracing::debug!(site_id = %self.site_id, "dark store restocked");
+            self.stock.clear();
+            self.restock_at = Some(now + self.config.restock_interval());
+        }
+
+        // accepted substitutes are picked right away with the unit reserved for them
+        let (responded, suggested) = std::mem::take(&mut self.suggested)
+            .into_iter()
+            .partition::<Vec<_>, _>(|suggestion| suggestion.respond_at <= now);
+        self.suggested = suggested;
+        for suggestion in responded.into_iter().rev() {
+            self.respond(suggestion, &mut events);
+        }
+
+        while self.picking.len() < self.config.pickers
+            && let Some(line) = self.queue.pop_front()
+        {
+            if !line.reserved && !self.take_stock(&line.sku) {
+                self.suggest(line, now, rng, &mut events);
+                continue;
+            }
+            events.push(EventPayload::order_line_updated(
+                line.order_line.id,
+                OrderLineStatus::Processing,
+                None,
+                None,
+            ));
+            let picked_at = now + self.config.pick_time(&line.sku);
+            self.picking.push((line, picked_at));
+        }
+
+        let completed = &mut self.completed;
+        self.picking.retain(|(line, picked_at)| {
+            if *picked_at > next {
+                return true;
+            }
+            completed.push((line.order_line.order_id, line.order_line.id));
+            false
+        });
+        events
+    }
+
+    /// Take a unit of a SKU from the shelves, if any is left.
+    fn take_stock(&mut self, sku: &str) -> bool {
+        let config = &self.config;
+        let stock = self
+            .stock
+            .entry(sku.to_string())
+            .or_insert_with(|| config.initial_stock(sku));
+        if *stock == 0 {
+            return false;
+        }
+        *stock -= 1;
+        true
+    }
+
+    fn price(&self, sku: &str) -> f64 {
+        self.prices.get(sku).copied().unwrap_or_default()
+    }
+
+    /// Suggest the first substitute in stock for a line out of stock, or fail its order.
+    fn suggest(
+        &mut self,
+        line: PickLine,
+        now: DateTime<Utc>,
+        rng: &mut impl Rng,
+        events: &mut Vec<EventPayload>,
+    ) {
+        let substitute = self
+            .config
+            .substitutes(&line.sku)
+            .to_vec()
+            .into_iter()
+            .find(|substitute| *substitute != line.sku && self.take_stock(substitute));
+        let Some(substitute) = substitute else {
+            self.fail(line.order_line.order_id, events);
+            return;
+        };
+        let price_delta = round_cents(self.price(&substitute) - self.price(&line.sku));
+        events.push(EventPayload::substitution_updated(
+            line.order_line.order_id,
+            line.order_line.id,
+            self.site_id,
+            line.sku.clone(),
+            substitute.clone(),
+            SubstitutionStatus::Suggested,
+            price_delta,
+        ));
+        let (accepted, response_time) = self.config.substitutions.sample(rng);
+        self.suggested.push(Suggestion {
+            line,
+            substitute,
+            accepted,
+            respond_at: now + response_time,
+        });
+    }
+
+    /// Apply the response of a customer to a suggested substitute.
+    fn respond(&mut self, suggestion: Suggestion, events: &mut Vec<EventPayload>) {
+        let Suggestion {
+            line,
+            substitute,
+            accepted,
+            ..
+        } = suggestion;
+        let (status, price_delta) = if accepted {
+            (
+                SubstitutionStatus::Accepted,
+                round_cents(self.price(&substitute) - self.price(&line.sku)),
+            )
+        } else {
+            (SubstitutionStatus::Rejected, -self.price(&line.sku))
+        };
+        events.push(EventPayload::substitution_updated(
+            line.order_line.order_id,
+            line.order_line.id,
+            self.site_id,
+            line.sku.clone(),
+            substitute.clone(),
+            status,
+            price_delta,
+        ));
+        if accepted {
+            self.queue.push_front(PickLine {
+                order_line: line.order_line,
+                sku: substitute,
+                reserved: true,
+            });
+        } else {
+            // the reserved unit goes back on the shelf
+            if let Some(stock) = self.stock.get_mut(&substitute) {
+                *stock += 1;
+            }
+            self.removed
+                .push((line.order_line.order_id, line.order_line.id));
+        }
+    }
+
+    /// Fail an order and drop its remaining lines.
+    fn fail(&mut self, order_id: OrderId, events: &mut Vec<EventPayload>) {
+        tracing::debug!(site_id = %self.site_id, ?order_id, "order line out of stock");
+        self.queue
+            .retain(|line| line.order_line.order_id != order_id);
+        self.picking
+            .retain(|(line, _)| line.order_line.order_id != order_id);
+        self.suggested
+            .retain(|suggestion| suggestion.line.order_line.order_id != order_id);
+        self.failed.push(order_id);
+        events.push(EventPayload::order_failed(order_id, None));
+    }
+
+    /// Earliest time a line is picked or a customer responds to a substitute.
+    ///
+    /// Lines waiting for a picker and lines not taken yet are due at `now`.
+    pub(crate) fn next_event(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if !(self.queue.is_empty()
+            && self.completed.is_empty()
+            && self.removed.is_empty()
+            && self.failed.is_empty())
+        {
+            return Some(now);
+        }
+        self.picking
+            .iter()
+            .map(|(_, picked_at)| *picked_at)
+            .chain(
+                self.suggested
+                    .iter()
+                    .map(|suggestion| suggestion.respond_at),
+            )
+            .min()
+    }
+
+    /// Take the order lines picked since the last call.
+    pub(crate) fn take_completed(&mut self) -> Vec<(OrderId, OrderLineId)> {
+        std::mem::take(&mut self.completed)
+    }
+
+    /// Take the order lines removed since the last call as their substitute was rejected.
+    pub(crate) fn take_removed(&mut self) -> Vec<(OrderId, OrderLineId)> {
+        std::mem::take(&mut self.removed)
+    }
+
+    /// Take the orders failed since the last call because a SKU was out of stock.
+    ///
+    /// Lines of these orders picked earlier are not taken back, as the order is
+    /// failed before it is packed.
+    pub(crate) fn take_failed(&mut self) -> HashSet<OrderId> {
+        let failed: HashSet<_> = self.failed.drain(..).collect();
+        self.completed
+            .retain(|(order_id, _)| !failed.contains(order_id));
+        self.removed
+            .retain(|(order_id, _)| !failed.contains(order_id));
+        failed
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idents::{BrandId, MenuItemId};
+
+    fn line(order_id: OrderId) -> OrderLine {
+        OrderLine {
+            id: OrderLineId::new(),
+            order_id,
+            item: (
+                BrandId::from_name("grocer"),
+                MenuItemId::from_names("grocer", "item"),
+            ),
+            priority: Default::default(),
+        }
+    }
+
+    fn statuses(events: &[EventPayload]) -> Vec<OrderLineStatus> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::OrderLineUpdated(payload) => Some(payload.status),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn failed(events: &[EventPayload]) -> Vec<OrderId> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::OrderUpdated(payload) => Some(payload.order_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pick_lines() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let minute = Duration::minutes(1);
+        let mut store = DarkStore::new(
+            SiteId::from_name("london"),
+            DarkStoreConfig {
+                pickers: 1,
+                pick_secs: 30,
+                skus: HashMap::from([(
+                    "melon".to_string(),
+                    SkuConfig {
+                        pick_secs: Some(90),
+                        ..Default::default()
+                    },
+                )]),
+                ..Default::default()
+            },
+            HashMap::new(),
+        );
+        let order_id = OrderId::new();
+        let (apples, melon) = (line(order_id), line(order_id));
+        store.queue_order_line(apples.clone(), "apples".into());
+        store.queue_order_line(melon.clone(), "melon".into());
+
+        // the only picker fetches the apples first
+        let events = store.step(start, start + minute, &mut rand::rng());
+        assert_eq!(statuses(&events), [OrderLineStatus::Processing]);
+        assert_eq!(store.take_completed(), [(order_id, apples.id)]);
+
+        // picking the melon takes longer than a step
+        store.step(start + minute, start + minute * 2, &mut rand::rng());
+        assert!(store.take_completed().is_empty());
+        store.step(start + minute * 2, start + minute * 3, &mut rand::rng());
+        assert_eq!(store.take_completed(), [(order_id, melon.id)]);
+    }
+
+    #[test]
+    fn test_out_of_stock() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let minute = Duration::minutes(1);
+        let mut store = DarkStore::new(
+            SiteId::from_name("london"),
+            DarkStoreConfig {
+                pickers: 2,
+                initial_stock: 1,
+                restock_hours: 1.0,
+                ..Default::default()
+            },
+            HashMap::new(),
+        );
+        let (first, second) = (OrderId::new(), OrderId::new());
+        store.queue_order_line(line(first), "milk".into());
+        store.queue_order_line(line(second), "milk".into());
+        store.queue_order_line(line(second), "bread".into());
+
+        // the second order fails as the last bottle of milk went to the first order
+        let events = store.step(start, start + minute, &mut rand::rng());
+        assert_eq!(failed(&events), [second]);
+        assert_eq!(store.take_failed(), HashSet::from([second]));
+        assert_eq!(store.take_completed().len(), 1);
+
+        // milk is available again once the store is restocked
+        let third = OrderId::new();
+        store.queue_order_line(line(third), "milk".into());
+        assert_eq!(
+            failed(&store.step(start + minute, start + minute * 2, &mut rand::rng())),
+            [third]
+        );
+        store.take_failed();
+        store.queue_order_line(line(third), "milk".into());
+        let events = store.step(
+            start + Duration::hours(1),
+            start + Duration::hours(1) + minute,
+            &mut rand::rng(),
+        );
+        assert!(failed(&events).is_empty());
+        assert_eq!(store.take_completed().len(), 1);
+    }
+
+    fn substitutions(events: &[EventPayload]) -> Vec<(SubstitutionStatus, f64)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::SubstitutionUpdated(payload) => {
+                    Some((payload.status, payload.price_delta))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_substitutions() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let minute = Duration::minutes(1);
+        let store = |acceptance_rate| {
+            DarkStore::new(
+                SiteId::from_name("london"),
+                DarkStoreConfig {
+                    initial_stock: 0,
+                    skus: HashMap::from([
+                        (
+                            "oat milk".to_string(),
+                            SkuConfig {
+                                substitutes: vec!["almond milk".into(), "soy milk".into()],
+                                ..Default::default()
+                            },
+                        ),
+                        (
+                            "soy milk".to_string(),
+                            SkuConfig {
+                                initial_stock: Some(1),
+                                ..Default::default()
+                            },
+                        ),
+                    ]),
+                    substitutions: SubstitutionConfig {
+                        acceptance_rate,
+                        response_secs: 0,
+                    },
+                    ..Default::default()
+                },
+                HashMap::from([("oat milk".into(), 2.5), ("soy milk".into(), 2.0)]),
+            )
+        };
+
+        // the first substitute in stock is suggested and picked once accepted
+        let mut accepting = store(1.0);
+        let order_id = OrderId::new();
+        let oat_milk = line(order_id);
+        accepting.queue_order_line(oat_milk.clone(), "oat milk".into());
+        let events = accepting.step(start, start + minute, &mut rand::rng());
+        assert_eq!(
+            substitutions(&events),
+            [(SubstitutionStatus::Suggested, -0.5)]
+        );
+        assert!(failed(&events).is_empty());
+        let events = accepting.step(start + minute, start + minute * 2, &mut rand::rng());
+        assert_eq!(
+            substitutions(&events),
+            [(SubstitutionStatus::Accepted, -0.5)]
+        );
+        assert_eq!(accepting.take_completed(), [(order_id, oat_milk.id)]);
+
+        // rejected substitutes remove the line and its price from the order
+        let mut rejecting = store(0.0);
+        let oat_milk = line(order_id);
+        rejecting.queue_order_line(oat_milk.clone(), "oat milk".into());
+        rejecting.step(start, start + minute, &mut rand::rng());
+        let events = rejecting.step(start + minute, start + minute * 2, &mut rand::rng());
+        assert_eq!(
+            substitutions(&events),
+            [(SubstitutionStatus::Rejected, -2.5)]
+        );
+        assert_eq!(rejecting.take_removed(), [(order_id, oat_milk.id)]);
+        assert!(rejecting.take_completed().is_empty());
+
+        // the returned unit of soy milk is suggested for the next order, and
+        // orders fail while no other substitute is left in stock
+        let other = OrderId::new();
+        rejecting.queue_order_line(line(OrderId::new()), "oat milk".into());
+        rejecting.queue_order_line(line(other), "oat milk".into());
+        let events = rejecting.step(start + minute * 2, start + minute * 3, &mut rand::rng());
+        assert_eq!(substitutions(&events).len(), 1);
+        assert_eq!(failed(&events), [other]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(DarkStoreConfig::default().validate().is_ok());
+        let config = DarkStoreConfig {
+            pickers: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = DarkStoreConfig {
+            skus: HashMap::from([(
+                "milk".to_string(),
+                SkuConfig {
+                    pick_secs: Some(-1),
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        let config = DarkStoreConfig {
+            substitutions: SubstitutionConfig {
+                acceptance_rate: 1.5,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
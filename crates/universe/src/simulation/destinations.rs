@@ -0,0 +1,456 @@
+//! Home and workplace delivery destinations of customers.
+//!
+//! By default orders are delivered to wherever the customer is when ordering. With a
+//! [`DestinationConfig`], each customer has a home, the position they start the run
+//! at, and a share of customers also has a workplace. Orders placed during working
+//! hours are delivered to the workplace, all other orders to the home, so lunch
+//! demand concentrates around offices while dinner demand spreads over residential
+//! areas.
+//!
+//! Workplaces are placed within one of the configured [`WorkDistrict`]s, or within
+//! commuting distance of the home if none are configured. Whether a customer works,
+//! and where, is derived from their id, so it is stable across runs.
+//!
+//! A small share of orders goes to neither, but to a spot the customer is visiting,
+//! e.g. a park or an event venue. Spots are sampled from the configured
+//! [`DeliverySpot`]s, or anywhere near the customer if none are configured. A spot is
+//! only used if it lies within the delivery zone of the site and near a street the
+//! courier can be routed along, otherwise the order goes to the home or workplace.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike as _, Timelike as _, Utc, Weekday};
+use geo::{Destination as _, Haversine, Point};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::PersonId;
+use crate::state::{PersonRole, State};
+use crate::{Error, Result};
+
+/// An area offices are located in, e.g. a business district or an industrial park.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkDistrict {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+
+    /// Radius in meters around the center workplaces are placed within
+    pub radius_m: f64,
+
+    /// Relative share of workplaces within the district
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+impl WorkDistrict {
+    fn validate(&self) -> Result<()> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(Error::invalid_data(format!(
+                "work district '{}' has invalid coordinates",
+                self.name
+            )));
+        }
+        if !(self.radius_m.is_finite() && self.radius_m >= 0.0) {
+            return Err(Error::invalid_data(format!(
+                "work district '{}' needs a non-negative radius",
+                self.name
+            )));
+        }
+        if !(self.weight.is_finite() && self.weight >= 0.0) {
+            return Err(Error::invalid_data(format!(
+                "work district '{}' needs a non-negative weight",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A place orders are delivered to away from homes and workplaces, e.g. a park.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliverySpot {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+
+    /// Radius in meters around the center orders are delivered within
+    #[serde(default)]
+    pub radius_m: f64,
+
+    /// Relative share of spot deliveries going to the spot
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+impl DeliverySpot {
+    fn validate(&self) -> Result<()> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(Error::invalid_data(format!(
+                "delivery spot '{}' has invalid coordinates",
+                self.name
+            )));
+        }
+        if !(self.radius_m.is_finite() && self.radius_m >= 0.0) {
+            return Err(Error::invalid_data(format!(
+                "delivery spot '{}' needs a non-negative radius",
+                self.name
+            )));
+        }
+        if !(self.weight.is_finite() && self.weight >= 0.0) {
+            return Err(Error::invalid_data(format!(
+                "delivery spot '{}' needs a non-negative weight",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Where customers have their orders delivered depending on the time of day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DestinationConfig {
+    /// Share of customers with a workplace
+    pub employed_share: f64,
+
+    /// Hour of the day (UTC) from which orders go to the workplace
+    pub work_start_hour: u32,
+
+    /// Hour of the day (UTC) from which orders go to the home again
+    pub work_end_hour: u32,
+
+    /// Whether customers work on Saturdays and Sundays
+    pub work_on_weekends: bool,
+
+    /// Farthest distance in meters of workplaces from homes, without work districts
+    pub max_commute_m: f64,
+
+    /// Areas workplaces are located in
+    pub districts: Vec<WorkDistrict>,
+
+    /// Share of orders delivered to a spot away from homes and workplaces
+    pub spot_share: f64,
+
+    /// Farthest distance in meters of spots from the customer, without delivery spots
+    pub max_spot_distance_m: f64,
+
+    /// Places spot deliveries go to
+    pub spots: Vec<DeliverySpot>,
+}
+
+impl Default for DestinationConfig {
+    fn default() -> Self {
+        Self {
+            employed_share: 0.6,
+            work_start_hour: 9,
+            work_end_hour: 17,
+            work_on_weekends: false,
+            max_commute_m: 5000.0,
+            districts: Vec::new(),
+            spot_share: 0.02,
+            max_spot_distance_m: 1500.0,
+            spots: Vec::new(),
+        }
+    }
+}
+
+impl DestinationConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.employed_share) {
+            return Err(Error::invalid_data(format!(
+                "employed share {} outside of [0, 1]",
+                self.employed_share
+            )));
+        }
+        if self.work_start_hour >= self.work_end_hour || self.work_end_hour > 24 {
+            return Err(Error::invalid_data(
+                "working hours need to start before they end within [0, 24]",
+            ));
+        }
+        if !(self.max_commute_m.is_finite() && self.max_commute_m >= 0.0) {
+            return Err(Error::invalid_data(
+                "maximum commute needs to be a non-negative distance",
+            ));
+        }
+        for district in &self.districts {
+            district.validate()?;
+        }
+        if !self.districts.is_empty() && self.districts.iter().all(|d| d.weight == 0.0) {
+            return Err(Error::invalid_data(
+                "work districts need at least one positive weight",
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.spot_share) {
+            return Err(Error::invalid_data(format!(
+                "spot share {} outside of [0, 1]",
+                self.spot_share
+            )));
+        }
+        if !(self.max_spot_distance_m.is_finite() && self.max_spot_distance_m >= 0.0) {
+            return Err(Error::invalid_data(
+                "maximum spot distance needs to be a non-negative distance",
+            ));
+        }
+        for spot in &self.spots {
+            spot.validate()?;
+        }
+        if !self.spots.is_empty() && self.spots.iter().all(|s| s.weight == 0.0) {
+            return Err(Error::invalid_data(
+                "delivery spots need at least one positive weight",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether orders placed at `time` go to the workplace.
+    fn is_working(&self, time: DateTime<Utc>) -> bool {
+        let weekend = matches!(time.weekday(), Weekday::Sat | Weekday::Sun);
+        (self.work_on_weekends || !weekend)
+            && (self.work_start_hour..self.work_end_hour).contains(&time.hour())
+    }
+}
+
+/// Delivery destinations of customers at their homes and workplaces.
+pub(crate) struct Destinations {
+    config: DestinationConfig,
+    homes: HashMap<PersonId, Point>,
+}
+
+impl Destinations {
+    /// Destinations of the customers, homed at their positions in `state`.
+    pub(crate) fn try_new(config: DestinationConfig, state: &State) -> Result<Self> {
+        let homes = state
+            .population()
+            .people_with_role_status(&PersonRole::Customer)?
+            .into_iter()
+            .map(|(person_id, _, position)| (person_id, position))
+            .collect();
+        Ok(Self { config, homes })
+    }
+
+    /// Home of the customer, customers joining during a run are at home wherever they order.
+    fn home(&self, person_id: &PersonId, position: Point) -> Point {
+        self.homes.get(person_id).copied().unwrap_or(position)
+    }
+
+    /// Workplace of the customer living at `home`, if they have one.
+    pub(crate) fn workplace(&self, person_id: &PersonId, home: Point) -> Option<Point> {
+        let id: &[u8] = person_id.as_ref();
+        let seed = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+        let mut rng = StdRng::seed_from_u64(seed);
+        if !rng.random_bool(self.config.employed_share) {
+            return None;
+        }
+
+        let (center, radius_m) = if self.config.districts.is_empty() {
+            (home, self.config.max_commute_m)
+        } else {
+            let district = pick_weighted(&self.config.districts, |d| d.weight, &mut rng)?;
+            (
+                Point::new(district.longitude, district.latitude),
+                district.radius_m,
+            )
+        };
+        Some(point_within(center, radius_m, &mut rng))
+    }
+
+    /// Spot the customer at `position` has their order delivered to, if any.
+    ///
+    /// The spot still needs to be checked to be within the delivery zone and routable.
+    pub(crate) fn spot(&self, position: Point, rng: &mut impl Rng) -> Option<Point> {
+        if !rng.random_bool(self.config.spot_share) {
+            return None;
+        }
+        if self.config.spots.is_empty() {
+            return Some(point_within(position, self.config.max_spot_distance_m, rng));
+        }
+        let spot = pick_weighted(&self.config.spots, |s| s.weight, rng)?;
+        let center = Point::new(spot.longitude, spot.latitude);
+        Some(point_within(center, spot.radius_m, rng))
+    }
+
+    /// Where an order the customer at `position` places at `time` is delivered to.
+    pub(crate) fn destination(
+        &self,
+        person_id: &PersonId,
+        position: Point,
+        time: DateTime<Utc>,
+    ) -> Point {
+        let home = self.home(person_id, position);
+        if self.config.is_working(time) {
+            self.workplace(person_id, home).unwrap_or(home)
+        } else {
+            home
+        }
+    }
+}
+
+/// Pick one of `items` with a probability proportional to its weight.
+fn pick_weighted<'a, T>(
+    items: &'a [T],
+    weight: impl Fn(&T) -> f64,
+    rng: &mut impl Rng,
+) -> Option<&'a T> {
+    let total: f64 = items.iter().map(&weight).sum();
+    let mut sample = rng.random::<f64>() * total;
+    items
+        .iter()
+        .find(|item| {
+            sample -= weight(item);
+            sample < 0.0
+        })
+        // only missed through rounding, fall back to the last weighted item
+        .or_else(|| items.iter().rfind(|item| weight(item) > 0.0))
+}
+
+/// A random point within `radius_m` meters of `center`.
+fn point_within(center: Point, radius_m: f64, rng: &mut impl Rng) -> Point {
+    // the square root spreads points evenly over the area of the circle
+    let distance = radius_m * rng.random::<f64>().sqrt();
+    let bearing = rng.random_range(0.0..360.0);
+    Haversine.destination(center, bearing, distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use geo::{Distance as _, Haversine};
+
+    use super::*;
+
+    fn with_config(config: DestinationConfig) -> Destinations {
+        Destinations {
+            config,
+            homes: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_destination_by_time_of_day() {
+        let destinations = with_config(DestinationConfig {
+            employed_share: 1.0,
+            ..Default::default()
+        });
+        let person_id = PersonId::new();
+        let home = Point::new(-0.1278, 51.5074);
+        let at = |time: &str| {
+            destinations.destination(&person_id, home, time.parse::<DateTime<Utc>>().unwrap())
+        };
+
+        // Wednesday lunch goes to the office, dinner and weekends to the home
+        let lunch = at("2025-01-01T12:30:00Z");
+        assert_ne!(lunch, home);
+        assert!(Haversine.distance(home, lunch) <= 5000.0 + 1e-6);
+        assert_eq!(at("2025-01-01T13:30:00Z"), lunch);
+        assert_eq!(at("2025-01-01T19:30:00Z"), home);
+        assert_eq!(at("2025-01-04T12:30:00Z"), home);
+
+        let unemployed = with_config(DestinationConfig {
+            employed_share: 0.0,
+            ..Default::default()
+        });
+        assert_eq!(
+            unemployed.destination(&person_id, home, "2025-01-01T12:30:00Z".parse().unwrap()),
+            home
+        );
+    }
+
+    #[test]
+    fn test_workplaces_in_districts() {
+        let canary_wharf = WorkDistrict {
+            name: "canary_wharf".to_string(),
+            latitude: 51.5054,
+            longitude: -0.0235,
+            radius_m: 800.0,
+            weight: 1.0,
+        };
+        let destinations = with_config(DestinationConfig {
+            employed_share: 1.0,
+            districts: vec![canary_wharf.clone()],
+            ..Default::default()
+        });
+        let center = Point::new(canary_wharf.longitude, canary_wharf.latitude);
+        for _ in 0..20 {
+            let workplace = destinations
+                .workplace(&PersonId::new(), Point::new(-0.1278, 51.5074))
+                .unwrap();
+            assert!(Haversine.distance(center, workplace) <= 800.0 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_spots() {
+        let park = DeliverySpot {
+            name: "hyde_park".to_string(),
+            latitude: 51.5073,
+            longitude: -0.1657,
+            radius_m: 300.0,
+            weight: 1.0,
+        };
+        let position = Point::new(-0.1278, 51.5074);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let never = with_config(DestinationConfig {
+            spot_share: 0.0,
+            spots: vec![park.clone()],
+            ..Default::default()
+        });
+        assert!(never.spot(position, &mut rng).is_none());
+
+        let always = with_config(DestinationConfig {
+            spot_share: 1.0,
+            spots: vec![park.clone()],
+            ..Default::default()
+        });
+        let center = Point::new(park.longitude, park.latitude);
+        for _ in 0..20 {
+            let spot = always.spot(position, &mut rng).unwrap();
+            assert!(Haversine.distance(center, spot) <= 300.0 + 1e-6);
+        }
+
+        // without spots, orders go anywhere near the customer
+        let nearby = with_config(DestinationConfig {
+            spot_share: 1.0,
+            ..Default::default()
+        });
+        let spot = nearby.spot(position, &mut rng).unwrap();
+        assert!(Haversine.distance(position, spot) <= 1500.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(DestinationConfig::default().validate().is_ok());
+        for config in [
+            DestinationConfig {
+                employed_share: 1.5,
+                ..Default::default()
+            },
+            DestinationConfig {
+                work_start_hour: 17,
+                work_end_hour: 9,
+                ..Default::default()
+            },
+            DestinationConfig {
+                spot_share: -0.1,
+                ..Default::default()
+            },
+            DestinationConfig {
+                districts: vec![WorkDistrict {
+                    name: "nowhere".to_string(),
+                    latitude: 51.5,
+                    longitude: 0.0,
+                    radius_m: 500.0,
+                    weight: 0.0,
+                }],
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Selection of the events persisted to the `events` table.
+//!
+//! Large backfills produce far more events than most analyses need. An
+//! [`EventFilter`] restricts the written events to an allowlist of event kinds and
+//! keeps only a random share of the events of selected kinds. Filtering only
+//! affects what is written, the simulation itself and the metrics derived from it
+//! always see every event.
+
+use std::collections::{HashMap, HashSet};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, EventKind, EventPayload, Result};
+
+/// Events written to the results, by default all of them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilter {
+    /// Kinds of events to write, all kinds if not set
+    pub include: Option<HashSet<EventKind>>,
+
+    /// Share of events of a kind to write, e.g. 0.01 to keep every hundredth
+    pub sample_rates: HashMap<EventKind, f64>,
+}
+
+impl EventFilter {
+    /// Only write events of the given kinds.
+    pub fn with_include(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.include = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// Write a random share `rate` of the events of `kind`.
+    pub fn with_sample_rate(mut self, kind: EventKind, rate: f64) -> Self {
+        self.sample_rates.insert(kind, rate);
+        self
+    }
+
+    /// Whether all events are written.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.sample_rates.is_empty()
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (kind, rate) in &self.sample_rates {
+            if !(0.0..=1.0).contains(rate) {
+                return Err(Error::invalid_data(format!(
+                    "sample rate {rate} for {kind} events outside of [0, 1]"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sample whether `event` is written.
+    pub(crate) fn keep(&self, event: &EventPayload, rng: &mut impl Rng) -> bool {
+        let kind = event.kind();
+        if self
+            .include
+            .as_ref()
+            .is_some_and(|include| !include.contains(&kind))
+        {
+            return false;
+        }
+        match self.sample_rates.get(&kind) {
+            Some(rate) => rng.random_bool(rate.clamp(0.0, 1.0)),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn test_event_filter() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let started = EventPayload::step_started(Utc::now());
+        let finished = EventPayload::step_finished(Utc::now(), 0);
+
+        assert!(EventFilter::default().is_empty());
+        assert!(EventFilter::default().keep(&started, &mut rng));
+
+        let filter = EventFilter::default().with_include([EventKind::StepStarted]);
+        assert!(filter.keep(&started, &mut rng));
+        assert!(!filter.keep(&finished, &mut rng));
+
+        let filter = EventFilter::default()
+            .with_sample_rate(EventKind::StepStarted, 0.25)
+            .with_sample_rate(EventKind::StepFinished, 0.0);
+        let kept = (0..4_000)
+            .filter(|_| filter.keep(&started, &mut rng))
+            .count();
+        assert!((800..1_200).contains(&kept));
+        assert!(!filter.keep(&finished, &mut rng));
+
+        let filter: EventFilter = serde_json::from_str(
+            r#"{"include": ["order_created"], "sample_rates": {"order_created": 0.5}}"#,
+        )?;
+        assert_eq!(
+            filter,
+            EventFilter::default()
+                .with_include([EventKind::OrderCreated])
+                .with_sample_rate(EventKind::OrderCreated, 0.5)
+        );
+
+        assert!(
+            EventFilter::default()
+                .with_sample_rate(EventKind::OrderCreated, 1.5)
+                .validate()
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+//! Writer of the `events` table, subscribed to the [`EventBus`](super::bus::EventBus)
+//! like any other sink.
+//!
+//! The events of a step are spread randomly over the simulated time the step covers,
+//! keeping the order in which they were emitted, and filtered by the configured
+//! [`EventFilter`]. With [`EventCoalescing`], the batches of consecutive steps are
+//! buffered and written together, see [`EventBatches`].
+
+use async_trait::async_trait;
+use itertools::Itertools as _;
+use rand::Rng as _;
+use rand::distr::{Distribution, Uniform};
+use rand::rngs::StdRng;
+
+use crate::builders::EventDataBuilder;
+use crate::context::SimulationContext;
+use crate::{EventPayload, PersonUpdatedPayload, Result};
+
+use super::bus::{EventSink, PublishedStep};
+use super::coalescing::EventBatches;
+use super::{EventCoalescing, EventFilter, SimulationConfig};
+
+/// Sink writing the published events to the `events` table.
+#[derive(Debug)]
+pub(crate) struct EventTableWriter {
+    filter: EventFilter,
+    /// Tolerance journeys are simplified with before they are written
+    journey_tolerance_m: Option<f64>,
+    coalescing: Option<EventCoalescing>,
+    /// Events of recent steps waiting to be written, if events are coalesced
+    batches: EventBatches,
+    /// Draws the timestamps of the events and which of them are sampled
+    rng: StdRng,
+}
+
+impl EventTableWriter {
+    pub(crate) fn new(config: &SimulationConfig, rng: StdRng) -> Self {
+        Self {
+            filter: config.event_filter.clone(),
+            journey_tolerance_m: config.journey_tolerance_m,
+            coalescing: config.event_coalescing.clone(),
+            batches: EventBatches::default(),
+            rng,
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for EventTableWriter {
+    async fn write(&mut self, ctx: &SimulationContext, step: &PublishedStep<'_>) -> Result<()> {
+        tracing::info!(
+            target: "caspers::simulation",
+            "writing events at {} ({})",
+            step.state_time.to_rfc3339(),
+            ctx.simulation_id()
+        );
+
+        let range = Uniform::new(0.0_f32, 0.9999_f32).unwrap();
+        let rng = &mut self.rng;
+        // events are spread randomly over the step, but keep the order in which they
+        // were emitted so replaying them by time reproduces the state
+        let mut offsets = range
+            .sample_iter(&mut *rng)
+            .take(step.events.len())
+            .collect_vec();
+        offsets.sort_by(f32::total_cmp);
+        let mut builder = EventDataBuilder::with_capacity(step.events.len())
+            .with_traceparent(step.traceparent.clone())
+            .with_seed(rng.random());
+        for (payload, offset) in step.events.iter().zip(offsets) {
+            if !self.filter.keep(payload, rng) {
+                continue;
+            }
+            // step boundaries are pinned to the start and end of the step
+            let multiplier = match payload {
+                EventPayload::StepStarted(_) => 0.0,
+                EventPayload::StepFinished(_) => 0.9999,
+                _ => offset,
+            };
+            let timestamp = step.state_time + step.time_step.mul_f32(multiplier);
+            // journeys are stored simplified, while the state keeps every vertex
+            if let (EventPayload::PersonUpdated(person), Some(tolerance_m)) =
+                (payload, self.journey_tolerance_m)
+            {
+                let payload = EventPayload::PersonUpdated(PersonUpdatedPayload {
+                    person_id: person.person_id,
+                    status: person.status.with_simplified_journey(tolerance_m),
+                });
+                builder.add_payload(timestamp, &payload)?;
+                continue;
+            }
+            builder.add_payload(timestamp, payload)?;
+        }
+        let batch = builder.build()?;
+        let Some(coalescing) = &self.coalescing else {
+            let data = ctx.ctx().read_batch(batch)?;
+            return ctx.results().write_events(data).await;
+        };
+        self.batches.push(step.state_time, batch);
+        if self.batches.is_due(coalescing, step.state_time) {
+            self.flush(ctx).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self, ctx: &SimulationContext) -> Result<()> {
+        if !self.batches.has_pending() {
+            return Ok(());
+        }
+        let data = ctx.ctx().read_batch(self.batches.flush()?)?;
+        ctx.results().write_events(data).await
+    }
+}
@@ -1,13 +1,22 @@
 use chrono::{DateTime, Utc};
 use datafusion::common::HashMap;
 use geo::Point;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
 use tracing::info_span;
 use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+use uuid::Uuid;
 
-use crate::State;
-use crate::idents::{BrandId, KitchenId, MenuItemId, OrderId, OrderLineId, PersonId, SiteId};
-use crate::state::{OrderLineStatus, OrderStatus, PersonStatus};
+use crate::idents::{
+    BrandId, KitchenId, MenuItemId, NotificationId, OrderId, OrderLineId, PersonId, SessionId,
+    SiteId, SupplierOrderId,
+};
+use crate::state::{ObjectLabel, OrderLineStatus, OrderStatus, PersonStatus};
+use crate::{
+    CourierOffer, Cuisine, Currency, FunnelStage, NotificationChannel, NotificationStatus,
+    NotificationTrigger, PriorityTier, State,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
@@ -15,12 +24,58 @@ pub struct Event {
     pub payload: EventPayload,
 }
 
+/// Channel through which an order was placed.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum OrderChannel {
+    App,
+    Web,
+    Phone,
+}
+
+impl OrderChannel {
+    /// Sample a channel, most orders are placed through the app.
+    pub(crate) fn sample(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..100) {
+            0..70 => OrderChannel::App,
+            70..95 => OrderChannel::Web,
+            _ => OrderChannel::Phone,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderCreatedPayload {
+    /// Id of the new order, orders recorded before ids were assigned get a random one
+    #[serde(default = "OrderId::new")]
+    pub order_id: OrderId,
     pub site_id: SiteId,
     pub person_id: PersonId,
     pub items: Vec<(BrandId, MenuItemId)>,
+    /// Cuisine of the brand of each item, if the brand is categorized
+    #[serde(default)]
+    pub cuisines: Vec<Option<Cuisine>>,
     pub destination: Point,
+    /// Order total in `currency`
+    pub total: f64,
+    /// Currency of the site the order was placed at
+    #[serde(default)]
+    pub currency: Currency,
+    pub channel: OrderChannel,
+    /// Priority tier of the customer, orders recorded before tiers were assigned are normal
+    #[serde(default)]
+    pub priority: PriorityTier,
+    /// Time by which the order is promised to be delivered
+    pub promised_at: DateTime<Utc>,
+    /// Names of campaigns applied to the order
+    #[serde(default)]
+    pub campaigns: Vec<String>,
+    /// Tip in `currency`, if any
+    #[serde(default)]
+    pub tip: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +99,423 @@ pub struct OrderLineUpdatedPayload {
     pub actor_id: Option<PersonId>,
 }
 
+/// A person arrived at a site.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteCheckInPayload {
+    pub site_id: SiteId,
+    pub person_id: PersonId,
+}
+
+/// A person left a site, carrying the listed orders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteCheckOutPayload {
+    pub site_id: SiteId,
+    pub person_id: PersonId,
+    pub order_ids: Vec<OrderId>,
+}
+
+/// Kind of change applied to a simulation object.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectChange {
+    Created,
+    Updated,
+}
+
+/// An object (site, kitchen, station, brand or menu item) was added or changed at runtime.
+///
+/// See [`ObjectData`](crate::ObjectData) for the operations producing these events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectChangedPayload {
+    pub object_id: Uuid,
+    pub label: ObjectLabel,
+    pub uri_ref: Option<String>,
+    pub change: ObjectChange,
+}
+
+/// Step in a courier's handling of a delivery.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CourierActivity {
+    /// Courier was offered a delivery
+    OfferReceived,
+    /// Courier accepted the offered delivery
+    OfferAccepted,
+    /// Courier declined the offered delivery
+    OfferDeclined,
+    /// Courier picked up the order at the site
+    PickedUp,
+    /// Courier arrived at the delivery destination
+    ArrivedAtCustomer,
+    /// Courier handed the order to the customer
+    Delivered,
+    /// Courier did not respond to the offered delivery in time
+    OfferExpired,
+}
+
+/// A courier made progress on a delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourierUpdatedPayload {
+    pub courier_id: PersonId,
+    pub order_id: OrderId,
+    pub activity: CourierActivity,
+    /// The offer the courier responded to, for offer activities
+    #[serde(default)]
+    pub offer: Option<CourierOffer>,
+}
+
+/// Why a courier takes a break.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BreakReason {
+    /// Courier charges the battery of their e-bike
+    Charging,
+    /// Courier rests after working for a long time
+    Rest,
+}
+
+/// Whether a break started or ended.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BreakActivity {
+    /// Courier became unavailable for deliveries
+    Started,
+    /// Courier is available for deliveries again
+    Ended,
+}
+
+/// A courier working at a site started or ended a break.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourierBreakPayload {
+    pub courier_id: PersonId,
+    pub site_id: SiteId,
+    pub reason: BreakReason,
+    pub activity: BreakActivity,
+}
+
+/// Kind of an autonomous delivery vehicle.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+    EnumString,
+    Display,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RobotKind {
+    /// Sidewalk robot following the streets open to pedestrians
+    #[default]
+    Robot,
+    /// Drone flying straight to the customer
+    Drone,
+}
+
+/// Progress of a delivery by a robot.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RobotActivity {
+    /// Robot left the site with the order
+    Dispatched,
+    /// Robot handed the order to the customer
+    Delivered,
+    /// Robot is back at the site and available for deliveries
+    Returned,
+}
+
+/// A robot of a site made progress on a delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobotDeliveryPayload {
+    pub robot_id: Uuid,
+    pub site_id: SiteId,
+    pub order_id: OrderId,
+    pub kind: RobotKind,
+    pub activity: RobotActivity,
+    /// Distance from the site to the customer in meters
+    pub distance_m: f64,
+}
+
+/// Progress of substituting an item that is out of stock.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SubstitutionStatus {
+    /// Substitute was suggested to the customer
+    Suggested,
+    /// Customer accepted the substitute
+    Accepted,
+    /// Customer rejected the substitute, the item is removed from the order
+    Rejected,
+}
+
+/// An item of an order was out of stock at a dark store and substituted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstitutionUpdatedPayload {
+    pub order_id: OrderId,
+    pub order_line_id: OrderLineId,
+    pub site_id: SiteId,
+    /// Name of the SKU that was out of stock
+    pub sku: String,
+    /// Name of the SKU suggested in its place
+    pub substitute: String,
+    pub status: SubstitutionStatus,
+    /// Change of the order total in the order currency
+    ///
+    /// Suggestions report the change if the customer accepts, rejections
+    /// take the price of the removed item off the total.
+    pub price_delta: f64,
+}
+
+/// A setting of a running simulation was changed through its control handle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChangedPayload {
+    /// Name of the setting, e.g. `demand_multiplier`
+    pub setting: String,
+    /// Value before the change, `None` if the setting was not set
+    pub previous: Option<f64>,
+    /// Value from this step on, `None` if the setting was cleared
+    pub value: Option<f64>,
+}
+
+/// A member was billed the monthly fee of their delivery subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MembershipBilledPayload {
+    pub person_id: PersonId,
+    /// Membership fee in `currency`
+    pub amount: f64,
+    pub currency: Currency,
+}
+
+/// Stage of a customer's lifecycle.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleStage {
+    /// Customer joined the simulation
+    SignedUp,
+    /// Customer placed their first order
+    FirstOrder,
+    /// Customer did not order for longer than the churn period
+    Churned,
+}
+
+/// A customer reached a stage of their lifecycle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonLifecyclePayload {
+    pub person_id: PersonId,
+    pub stage: LifecycleStage,
+    /// The order placed, for first orders
+    #[serde(default)]
+    pub order_id: Option<OrderId>,
+}
+
+/// A notification sent to a customer made progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationUpdatedPayload {
+    pub notification_id: NotificationId,
+    pub person_id: PersonId,
+    pub order_id: OrderId,
+    pub channel: NotificationChannel,
+    pub trigger: NotificationTrigger,
+    pub status: NotificationStatus,
+}
+
+/// An app session of a customer reached a stage of the ordering funnel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionUpdatedPayload {
+    pub session_id: SessionId,
+    pub person_id: PersonId,
+    /// Site whose menu the customer browsed
+    pub site_id: SiteId,
+    pub stage: FunnelStage,
+    /// Menu items viewed while browsing, or in the cart at the later stages
+    pub menu_item_ids: Vec<MenuItemId>,
+    /// The order placed, at checkout
+    #[serde(default)]
+    pub order_id: Option<OrderId>,
+    /// Time the session reached the stage, which may precede the step
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A customer received a voucher for a late delivery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompensationIssuedPayload {
+    pub order_id: OrderId,
+    pub person_id: PersonId,
+    /// Name of the compensation rule that applied
+    pub rule: String,
+    /// Time the order was delivered after the promised time
+    pub late_s: f64,
+    /// Voucher amount in `currency`
+    pub amount: f64,
+    pub currency: Currency,
+}
+
+/// Activity of the packaging supplies of a site.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyActivity {
+    /// Supplies fell to the reorder point and were reordered
+    Reordered,
+    /// Reordered supplies arrived at the site
+    Restocked,
+    /// Supplies ran out, holding up packing until they are restocked
+    Depleted,
+}
+
+/// Packaging supplies of a site were reordered, restocked or ran out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupplyUpdatedPayload {
+    pub site_id: SiteId,
+    /// Name of the packaging supply
+    pub supply: String,
+    pub activity: SupplyActivity,
+    /// Units of the supply in stock after the activity
+    pub stock: f64,
+}
+
+/// Activity of a supplier order replenishing the ingredient stock of a site.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ProcurementActivity {
+    /// The site ordered the ingredient from its supplier
+    Ordered,
+    /// The delivery of the ingredient is running late
+    Delayed,
+    /// The ingredient was delivered, possibly short of the ordered quantity
+    Delivered,
+}
+
+/// An ingredient of a supplier order was ordered, delayed or delivered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcurementUpdatedPayload {
+    pub supplier_order_id: SupplierOrderId,
+    pub site_id: SiteId,
+    /// Reference of the ingredient in the ingredient catalog
+    pub ingredient: String,
+    /// Unit the quantity is measured in
+    pub unit: String,
+    pub activity: ProcurementActivity,
+    /// Quantity ordered, or delivered once the order arrived
+    pub quantity: f64,
+    /// Time the delivery is expected to arrive
+    pub expected_at: DateTime<Utc>,
+}
+
+/// Reason ingredient stock of a site was wasted.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WasteReason {
+    /// The stock passed its shelf life
+    Expired,
+    /// The stock was discarded at the end of the day, e.g. prepped but unused
+    Discarded,
+}
+
+/// Ingredient stock of a site expired or was discarded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngredientWastedPayload {
+    pub site_id: SiteId,
+    /// Reference of the ingredient in the ingredient catalog
+    pub ingredient: String,
+    /// Unit the quantity is measured in
+    pub unit: String,
+    pub reason: WasteReason,
+    /// Quantity wasted
+    pub quantity: f64,
+}
+
+/// Means of transport delivering an order.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    EnumString,
+    Display,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryMode {
+    /// Courier on foot
+    Foot,
+    /// Courier riding a bicycle
+    Bicycle,
+    /// Courier driving a car or scooter
+    Car,
+    /// Sidewalk robot
+    Robot,
+    /// Drone flying straight to the customer
+    Drone,
+}
+
+/// Carbon footprint of delivering an order, reported once the delivery started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryFootprintPayload {
+    pub order_id: OrderId,
+    pub site_id: SiteId,
+    pub mode: DeliveryMode,
+    /// Distance travelled for the delivery in meters
+    pub distance_m: f64,
+    /// Grams of CO2 emitted for the delivery
+    pub co2_g: f64,
+}
+
+/// The simulation started advancing by one time step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepStartedPayload {
+    pub simulation_time: DateTime<Utc>,
+}
+
+/// The simulation finished advancing by one time step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepFinishedPayload {
+    pub simulation_time: DateTime<Utc>,
+    pub num_events: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EventPayload {
@@ -51,9 +523,88 @@ pub enum EventPayload {
     OrderUpdated(OrderUpdatedPayload),
     OrderLineUpdated(OrderLineUpdatedPayload),
     OrderCreated(OrderCreatedPayload),
+    SiteCheckIn(SiteCheckInPayload),
+    SiteCheckOut(SiteCheckOutPayload),
+    StepStarted(StepStartedPayload),
+    StepFinished(StepFinishedPayload),
+    ObjectChanged(ObjectChangedPayload),
+    CourierUpdated(CourierUpdatedPayload),
+    PersonLifecycle(PersonLifecyclePayload),
+    NotificationUpdated(NotificationUpdatedPayload),
+    CompensationIssued(CompensationIssuedPayload),
+    SupplyUpdated(SupplyUpdatedPayload),
+    CourierBreak(CourierBreakPayload),
+    RobotDelivery(RobotDeliveryPayload),
+    SubstitutionUpdated(SubstitutionUpdatedPayload),
+    ConfigChanged(ConfigChangedPayload),
+    MembershipBilled(MembershipBilledPayload),
+    SessionUpdated(SessionUpdatedPayload),
+    ProcurementUpdated(ProcurementUpdatedPayload),
+    IngredientWasted(IngredientWastedPayload),
+    DeliveryFootprint(DeliveryFootprintPayload),
+}
+
+/// Kind of an event, matching the variant names of [`EventPayload`].
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    PersonUpdated,
+    OrderUpdated,
+    OrderLineUpdated,
+    OrderCreated,
+    SiteCheckIn,
+    SiteCheckOut,
+    StepStarted,
+    StepFinished,
+    ObjectChanged,
+    CourierUpdated,
+    PersonLifecycle,
+    NotificationUpdated,
+    CompensationIssued,
+    SupplyUpdated,
+    CourierBreak,
+    RobotDelivery,
+    SubstitutionUpdated,
+    ConfigChanged,
+    MembershipBilled,
+    SessionUpdated,
+    ProcurementUpdated,
+    IngredientWasted,
+    DeliveryFootprint,
 }
 
 impl EventPayload {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            EventPayload::PersonUpdated(_) => EventKind::PersonUpdated,
+            EventPayload::OrderUpdated(_) => EventKind::OrderUpdated,
+            EventPayload::OrderLineUpdated(_) => EventKind::OrderLineUpdated,
+            EventPayload::OrderCreated(_) => EventKind::OrderCreated,
+            EventPayload::SiteCheckIn(_) => EventKind::SiteCheckIn,
+            EventPayload::SiteCheckOut(_) => EventKind::SiteCheckOut,
+            EventPayload::StepStarted(_) => EventKind::StepStarted,
+            EventPayload::StepFinished(_) => EventKind::StepFinished,
+            EventPayload::ObjectChanged(_) => EventKind::ObjectChanged,
+            EventPayload::CourierUpdated(_) => EventKind::CourierUpdated,
+            EventPayload::PersonLifecycle(_) => EventKind::PersonLifecycle,
+            EventPayload::NotificationUpdated(_) => EventKind::NotificationUpdated,
+            EventPayload::CompensationIssued(_) => EventKind::CompensationIssued,
+            EventPayload::SupplyUpdated(_) => EventKind::SupplyUpdated,
+            EventPayload::CourierBreak(_) => EventKind::CourierBreak,
+            EventPayload::RobotDelivery(_) => EventKind::RobotDelivery,
+            EventPayload::SubstitutionUpdated(_) => EventKind::SubstitutionUpdated,
+            EventPayload::ConfigChanged(_) => EventKind::ConfigChanged,
+            EventPayload::MembershipBilled(_) => EventKind::MembershipBilled,
+            EventPayload::SessionUpdated(_) => EventKind::SessionUpdated,
+            EventPayload::ProcurementUpdated(_) => EventKind::ProcurementUpdated,
+            EventPayload::IngredientWasted(_) => EventKind::IngredientWasted,
+            EventPayload::DeliveryFootprint(_) => EventKind::DeliveryFootprint,
+        }
+    }
+
     pub fn person_updated(person_id: PersonId, status: PersonStatus) -> Self {
         Self::PersonUpdated(PersonUpdatedPayload { person_id, status })
     }
@@ -91,6 +642,257 @@ impl EventPayload {
             actor_id,
         })
     }
+
+    pub fn site_check_in(site_id: SiteId, person_id: PersonId) -> Self {
+        Self::SiteCheckIn(SiteCheckInPayload { site_id, person_id })
+    }
+
+    pub fn site_check_out(site_id: SiteId, person_id: PersonId, order_ids: Vec<OrderId>) -> Self {
+        Self::SiteCheckOut(SiteCheckOutPayload {
+            site_id,
+            person_id,
+            order_ids,
+        })
+    }
+
+    pub fn courier_updated(
+        courier_id: PersonId,
+        order_id: OrderId,
+        activity: CourierActivity,
+        offer: Option<CourierOffer>,
+    ) -> Self {
+        Self::CourierUpdated(CourierUpdatedPayload {
+            courier_id,
+            order_id,
+            activity,
+            offer,
+        })
+    }
+
+    pub fn courier_break(
+        courier_id: PersonId,
+        site_id: SiteId,
+        reason: BreakReason,
+        activity: BreakActivity,
+    ) -> Self {
+        Self::CourierBreak(CourierBreakPayload {
+            courier_id,
+            site_id,
+            reason,
+            activity,
+        })
+    }
+
+    pub fn robot_delivery(
+        robot_id: Uuid,
+        site_id: SiteId,
+        order_id: OrderId,
+        kind: RobotKind,
+        activity: RobotActivity,
+        distance_m: f64,
+    ) -> Self {
+        Self::RobotDelivery(RobotDeliveryPayload {
+            robot_id,
+            site_id,
+            order_id,
+            kind,
+            activity,
+            distance_m,
+        })
+    }
+
+    pub fn substitution_updated(
+        order_id: OrderId,
+        order_line_id: OrderLineId,
+        site_id: SiteId,
+        sku: String,
+        substitute: String,
+        status: SubstitutionStatus,
+        price_delta: f64,
+    ) -> Self {
+        Self::SubstitutionUpdated(SubstitutionUpdatedPayload {
+            order_id,
+            order_line_id,
+            site_id,
+            sku,
+            substitute,
+            status,
+            price_delta,
+        })
+    }
+
+    pub fn config_changed(setting: &str, previous: Option<f64>, value: Option<f64>) -> Self {
+        Self::ConfigChanged(ConfigChangedPayload {
+            setting: setting.to_string(),
+            previous,
+            value,
+        })
+    }
+
+    pub fn person_lifecycle(
+        person_id: PersonId,
+        stage: LifecycleStage,
+        order_id: Option<OrderId>,
+    ) -> Self {
+        Self::PersonLifecycle(PersonLifecyclePayload {
+            person_id,
+            stage,
+            order_id,
+        })
+    }
+
+    pub fn notification_updated(
+        notification_id: NotificationId,
+        person_id: PersonId,
+        order_id: OrderId,
+        channel: NotificationChannel,
+        trigger: NotificationTrigger,
+        status: NotificationStatus,
+    ) -> Self {
+        Self::NotificationUpdated(NotificationUpdatedPayload {
+            notification_id,
+            person_id,
+            order_id,
+            channel,
+            trigger,
+            status,
+        })
+    }
+
+    pub fn membership_billed(person_id: PersonId, amount: f64, currency: Currency) -> Self {
+        Self::MembershipBilled(MembershipBilledPayload {
+            person_id,
+            amount,
+            currency,
+        })
+    }
+
+    pub fn session_updated(
+        session_id: SessionId,
+        person_id: PersonId,
+        site_id: SiteId,
+        stage: FunnelStage,
+        menu_item_ids: Vec<MenuItemId>,
+        order_id: Option<OrderId>,
+        occurred_at: DateTime<Utc>,
+    ) -> Self {
+        Self::SessionUpdated(SessionUpdatedPayload {
+            session_id,
+            person_id,
+            site_id,
+            stage,
+            menu_item_ids,
+            order_id,
+            occurred_at,
+        })
+    }
+
+    pub fn compensation_issued(
+        order_id: OrderId,
+        person_id: PersonId,
+        rule: String,
+        late_s: f64,
+        amount: f64,
+        currency: Currency,
+    ) -> Self {
+        Self::CompensationIssued(CompensationIssuedPayload {
+            order_id,
+            person_id,
+            rule,
+            late_s,
+            amount,
+            currency,
+        })
+    }
+
+    pub fn supply_updated(
+        site_id: SiteId,
+        supply: String,
+        activity: SupplyActivity,
+        stock: f64,
+    ) -> Self {
+        Self::SupplyUpdated(SupplyUpdatedPayload {
+            site_id,
+            supply,
+            activity,
+            stock,
+        })
+    }
+
+    pub fn procurement_updated(
+        supplier_order_id: SupplierOrderId,
+        site_id: SiteId,
+        (ingredient, unit): (String, String),
+        activity: ProcurementActivity,
+        quantity: f64,
+        expected_at: DateTime<Utc>,
+    ) -> Self {
+        Self::ProcurementUpdated(ProcurementUpdatedPayload {
+            supplier_order_id,
+            site_id,
+            ingredient,
+            unit,
+            activity,
+            quantity,
+            expected_at,
+        })
+    }
+
+    pub fn ingredient_wasted(
+        site_id: SiteId,
+        (ingredient, unit): (String, String),
+        reason: WasteReason,
+        quantity: f64,
+    ) -> Self {
+        Self::IngredientWasted(IngredientWastedPayload {
+            site_id,
+            ingredient,
+            unit,
+            reason,
+            quantity,
+        })
+    }
+
+    pub fn delivery_footprint(
+        order_id: OrderId,
+        site_id: SiteId,
+        mode: DeliveryMode,
+        distance_m: f64,
+        co2_g: f64,
+    ) -> Self {
+        Self::DeliveryFootprint(DeliveryFootprintPayload {
+            order_id,
+            site_id,
+            mode,
+            distance_m,
+            co2_g,
+        })
+    }
+
+    pub fn step_started(simulation_time: DateTime<Utc>) -> Self {
+        Self::StepStarted(StepStartedPayload { simulation_time })
+    }
+
+    pub fn step_finished(simulation_time: DateTime<Utc>, num_events: usize) -> Self {
+        Self::StepFinished(StepFinishedPayload {
+            simulation_time,
+            num_events,
+        })
+    }
+
+    pub fn object_changed(
+        object_id: Uuid,
+        label: ObjectLabel,
+        uri_ref: Option<String>,
+        change: ObjectChange,
+    ) -> Self {
+        Self::ObjectChanged(ObjectChangedPayload {
+            object_id,
+            label,
+            uri_ref,
+            change,
+        })
+    }
 }
 
 pub struct EventTracker {
@@ -128,7 +930,26 @@ impl EventTracker {
 
     fn handle_event(&mut self, event: &EventPayload, ctx: &State) {
         match event {
-            EventPayload::OrderCreated(_) => {}
+            EventPayload::OrderCreated(_)
+            | EventPayload::SiteCheckIn(_)
+            | EventPayload::SiteCheckOut(_)
+            | EventPayload::StepStarted(_)
+            | EventPayload::StepFinished(_)
+            | EventPayload::ObjectChanged(_)
+            | EventPayload::CourierUpdated(_)
+            | EventPayload::PersonLifecycle(_)
+            | EventPayload::NotificationUpdated(_)
+            | EventPayload::CompensationIssued(_)
+            | EventPayload::SupplyUpdated(_)
+            | EventPayload::CourierBreak(_)
+            | EventPayload::RobotDelivery(_)
+            | EventPayload::SubstitutionUpdated(_)
+            | EventPayload::ConfigChanged(_)
+            | EventPayload::MembershipBilled(_)
+            | EventPayload::SessionUpdated(_)
+            | EventPayload::ProcurementUpdated(_)
+            | EventPayload::IngredientWasted(_)
+            | EventPayload::DeliveryFootprint(_) => {}
             EventPayload::OrderUpdated(payload) => self.handle_order_updated(payload, ctx),
             EventPayload::OrderLineUpdated(payload) => self.handle_order_line_updated(payload, ctx),
             EventPayload::PersonUpdated(payload) => self.handle_person_updated(payload, ctx),
@@ -255,6 +1076,8 @@ pub struct EventStats {
     pub num_orders_updated: u32,
     pub num_order_lines_updated: u32,
     pub num_people_updated: u32,
+    pub num_site_check_ins: u32,
+    pub num_site_check_outs: u32,
 }
 
 impl Default for EventStats {
@@ -270,6 +1093,8 @@ impl EventStats {
             num_orders_updated: 0,
             num_order_lines_updated: 0,
             num_people_updated: 0,
+            num_site_check_ins: 0,
+            num_site_check_outs: 0,
         }
     }
 
@@ -278,6 +1103,8 @@ impl EventStats {
         self.num_orders_updated += other.num_orders_updated;
         self.num_order_lines_updated += other.num_order_lines_updated;
         self.num_people_updated += other.num_people_updated;
+        self.num_site_check_ins += other.num_site_check_ins;
+        self.num_site_check_outs += other.num_site_check_outs;
     }
 
     pub fn handle_event(&mut self, event: &EventPayload) {
@@ -286,6 +1113,25 @@ impl EventStats {
             EventPayload::OrderUpdated(_) => self.num_orders_updated += 1,
             EventPayload::OrderLineUpdated(_) => self.num_order_lines_updated += 1,
             EventPayload::PersonUpdated(_) => self.num_people_updated += 1,
+            EventPayload::SiteCheckIn(_) => self.num_site_check_ins += 1,
+            EventPayload::SiteCheckOut(_) => self.num_site_check_outs += 1,
+            EventPayload::StepStarted(_)
+            | EventPayload::StepFinished(_)
+            | EventPayload::ObjectChanged(_)
+            | EventPayload::CourierUpdated(_)
+            | EventPayload::PersonLifecycle(_)
+            | EventPayload::NotificationUpdated(_)
+            | EventPayload::CompensationIssued(_)
+            | EventPayload::SupplyUpdated(_)
+            | EventPayload::CourierBreak(_)
+            | EventPayload::RobotDelivery(_)
+            | EventPayload::SubstitutionUpdated(_)
+            | EventPayload::ConfigChanged(_)
+            | EventPayload::MembershipBilled(_)
+            | EventPayload::SessionUpdated(_)
+            | EventPayload::ProcurementUpdated(_)
+            | EventPayload::IngredientWasted(_)
+            | EventPayload::DeliveryFootprint(_) => (),
         }
     }
 }
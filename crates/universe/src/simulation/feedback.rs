@@ -0,0 +1,321 @@
+//! Rating prompts for delivered orders.
+//!
+//! Only a share of delivered orders prompts the customer for a rating, and only some
+//! customers respond. Both the rating and the probability to respond depend on the
+//! quality of the experience, measured by how late the order was delivered compared
+//! to the time promised when it was placed. Dissatisfied customers are more likely to
+//! respond, so the ratings that are given are biased towards bad experiences just
+//! like real-world feedback.
+//!
+//! Prompts are written to the `order_feedback` results table alongside the
+//! simulation metrics, with the rating left empty for customers who did not respond.
+//! Orders placed before the start of the run are not prompted, since the time they
+//! were placed and promised at is not known.
+
+use std::collections::HashMap;
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::builders::{FeedbackBuffer, OrderFeedback};
+use crate::idents::{OrderId, PersonId, SiteId, uuid_v7_from_rng};
+use crate::state::{OrderStatus, State};
+use crate::{Error, EventPayload, Result};
+
+use super::tipping::standard_normal;
+
+/// Rating prompts and response behavior of customers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    /// Share of delivered orders whose customer is prompted for a rating
+    pub prompt_rate: f64,
+
+    /// Probability that a customer responds to the prompt after a flawless delivery
+    pub response_rate: f64,
+
+    /// Increase of the response probability after the worst deliveries
+    pub dissatisfied_response_boost: f64,
+
+    /// Minutes past the promised time at which the experience is at its worst
+    pub max_late_minutes: f64,
+
+    /// Standard deviation of ratings given for the same experience
+    pub rating_std_dev: f64,
+
+    /// Mean time in seconds from the prompt to the response
+    pub mean_response_delay_s: f64,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            prompt_rate: 0.5,
+            response_rate: 0.15,
+            dissatisfied_response_boost: 0.35,
+            max_late_minutes: 30.0,
+            rating_std_dev: 0.7,
+            mean_response_delay_s: 900.0,
+        }
+    }
+}
+
+impl FeedbackConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (name, rate) in [
+            ("prompt rate", self.prompt_rate),
+            ("response rate", self.response_rate),
+            (
+                "maximum response rate",
+                self.response_rate + self.dissatisfied_response_boost,
+            ),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(Error::invalid_data(format!(
+                    "feedback {name} {rate} outside of [0, 1]"
+                )));
+            }
+        }
+        if self.max_late_minutes.is_nan() || self.max_late_minutes <= 0.0 {
+            return Err(Error::invalid_data(
+                "feedback max late minutes must be positive",
+            ));
+        }
+        if !(self.rating_std_dev >= 0.0 && self.mean_response_delay_s >= 0.0) {
+            return Err(Error::invalid_data(
+                "feedback rating std dev and response delay must not be negative",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Dissatisfaction with a delivery from 0 (flawless) to 1 (worst) given how late it was.
+    fn dissatisfaction(&self, late: Duration) -> f64 {
+        let late_minutes = late.num_seconds() as f64 / 60.0;
+        (late_minutes / self.max_late_minutes).clamp(0.0, 1.0)
+    }
+
+    /// Sample the customer's response to a prompt, `None` if they do not respond.
+    fn sample_rating(&self, rng: &mut impl Rng, late: Duration) -> Option<u8> {
+        let dissatisfaction = self.dissatisfaction(late);
+        let response_rate = self.response_rate + self.dissatisfied_response_boost * dissatisfaction;
+        if !rng.random_bool(response_rate.clamp(0.0, 1.0)) {
+            return None;
+        }
+        let rating = 5.0 - 4.0 * dissatisfaction + self.rating_std_dev * standard_normal(rng);
+        Some(rating.round().clamp(1.0, 5.0) as u8)
+    }
+}
+
+struct PlacedOrder {
+    placed_at: DateTime<Utc>,
+    promised_at: DateTime<Utc>,
+}
+
+/// Prompts customers for ratings of delivered orders.
+pub(crate) struct FeedbackCollector {
+    config: FeedbackConfig,
+    orders: HashMap<OrderId, PlacedOrder>,
+    buffer: FeedbackBuffer,
+}
+
+impl FeedbackCollector {
+    pub(crate) fn new(config: FeedbackConfig) -> Self {
+        Self {
+            config,
+            orders: HashMap::new(),
+            buffer: FeedbackBuffer::new(),
+        }
+    }
+
+    /// Prompt customers of orders delivered in `events` for a rating.
+    pub(crate) fn record(
+        &mut self,
+        events: &[EventPayload],
+        state: &State,
+        rng: &mut impl Rng,
+    ) -> Result<()> {
+        let now = state.current_time();
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(payload) => {
+                    self.orders.insert(
+                        payload.order_id,
+                        PlacedOrder {
+                            placed_at: now,
+                            promised_at: payload.promised_at,
+                        },
+                    );
+                }
+                EventPayload::OrderUpdated(payload) if !payload.status.is_open() => {
+                    let Some(placed) = self.orders.remove(&payload.order_id) else {
+                        continue;
+                    };
+                    if payload.status != OrderStatus::Delivered
+                        || !rng.random_bool(self.config.prompt_rate)
+                    {
+                        continue;
+                    }
+                    let Some(order) = state.orders().order(&payload.order_id) else {
+                        continue;
+                    };
+                    let feedback = self.prompt(
+                        rng,
+                        payload.order_id,
+                        order.site_id().try_into()?,
+                        order.customer_person_id().try_into()?,
+                        &placed,
+                        now,
+                    );
+                    self.buffer.push(&feedback)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn prompt(
+        &self,
+        rng: &mut impl Rng,
+        order_id: OrderId,
+        site_id: SiteId,
+        customer_id: PersonId,
+        placed: &PlacedOrder,
+        delivered_at: DateTime<Utc>,
+    ) -> OrderFeedback {
+        let id = uuid_v7_from_rng(delivered_at, rng);
+        let late = (delivered_at - placed.promised_at).max(Duration::zero());
+        let response = self.config.sample_rating(rng, late).map(|rating| {
+            // shift into (0, 1] to keep the logarithm finite
+            let u: f64 = 1.0 - rng.random::<f64>();
+            let delay = -self.config.mean_response_delay_s * u.ln();
+            (
+                delivered_at + Duration::milliseconds((delay * 1000.0) as i64),
+                rating,
+            )
+        });
+        OrderFeedback {
+            id,
+            order_id,
+            site_id,
+            customer_id,
+            prompted_at: delivered_at,
+            delivery_time_s: (delivered_at - placed.placed_at).num_milliseconds() as f64 / 1000.0,
+            late_s: late.num_milliseconds() as f64 / 1000.0,
+            response,
+        }
+    }
+
+    /// Whether prompts are waiting to be written.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::Int64Type;
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::context::SimulationContext;
+
+    #[test]
+    fn test_response_bias() {
+        let config = FeedbackConfig::default();
+        config.validate().unwrap();
+        let collector = FeedbackCollector::new(config);
+        let mut rng = StdRng::seed_from_u64(42);
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let placed = PlacedOrder {
+            placed_at: start,
+            promised_at: start + Duration::minutes(30),
+        };
+
+        let mut sample = |delivered_at| {
+            let prompts = (0..2000)
+                .map(|_| {
+                    collector.prompt(
+                        &mut rng,
+                        OrderId::new(),
+                        SiteId::from_name("london"),
+                        PersonId::new(),
+                        &placed,
+                        delivered_at,
+                    )
+                })
+                .collect::<Vec<_>>();
+            let ratings = prompts
+                .iter()
+                .filter_map(|p| p.response.map(|(_, rating)| rating as f64))
+                .collect::<Vec<_>>();
+            let response_rate = ratings.len() as f64 / prompts.len() as f64;
+            (
+                response_rate,
+                ratings.iter().sum::<f64>() / ratings.len() as f64,
+            )
+        };
+
+        let (on_time_rate, on_time_rating) = sample(start + Duration::minutes(25));
+        let (late_rate, late_rating) = sample(start + Duration::minutes(70));
+        // customers with bad experiences respond more often and rate worse
+        assert!(late_rate > on_time_rate + 0.2);
+        assert!(on_time_rating > 4.0);
+        assert!(late_rating < 2.0);
+        assert!((0.1..0.2).contains(&on_time_rate));
+    }
+
+    #[tokio::test]
+    async fn test_write_feedback() -> Result<()> {
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .build()
+            .await?;
+        let now = Utc::now();
+        let mut buffer = FeedbackBuffer::new();
+        for response in [None, Some((now, 4))] {
+            buffer.push(&OrderFeedback {
+                id: Uuid::now_v7(),
+                order_id: OrderId::new(),
+                site_id: SiteId::from_name("london"),
+                customer_id: PersonId::new(),
+                prompted_at: now,
+                delivery_time_s: 1800.0,
+                late_s: 0.0,
+                response,
+            })?;
+        }
+        ctx.results()
+            .write_order_feedback(ctx.ctx().read_batch(buffer.flush()?)?)
+            .await?;
+
+        let batches = ctx.results().order_feedback().await?.collect().await?;
+        let ratings = batches
+            .iter()
+            .flat_map(|b| b.column(8).as_primitive::<Int64Type>().iter())
+            .collect::<Vec<_>>();
+        assert_eq!(ratings.len(), 2);
+        assert!(ratings.contains(&None) && ratings.contains(&Some(4)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let config = FeedbackConfig {
+            response_rate: 0.8,
+            dissatisfied_response_boost: 0.5,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
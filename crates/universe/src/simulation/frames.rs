@@ -0,0 +1,514 @@
+//! Headless rendering of animation frames from simulation results.
+//!
+//! People are replayed from their `person_updated` events and their positions are
+//! sampled at a fixed interval of simulated time. Every sample is a [`Frame`] that
+//! can be written as a GeoJSON `FeatureCollection`, which makes it straightforward
+//! to turn a simulation run into a video without a live server.
+//!
+//! For playback in the browser, frames are aggregated into [`PlaybackFrame`]s which
+//! only carry the number of people per H3 cell, keeping the payload small enough to
+//! scrub through a whole run.
+
+use std::collections::{BTreeMap, HashMap};
+
+use arrow::array::Array as _;
+use arrow::array::RecordBatch;
+use arrow::array::cast::AsArray as _;
+use chrono::{DateTime, Duration, Utc};
+use datafusion::prelude::{col, lit};
+use geo::Point;
+use geo_traits::to_geo::ToGeoPoint as _;
+use geoarrow::array::PointArray;
+use geoarrow_array::GeoArrowArrayAccessor as _;
+use geoarrow_schema::{Dimension, PointType};
+use h3o::{CellIndex, LatLng, Resolution};
+use serde::Serialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::context::SimulationContext;
+use crate::idents::{OrderId, PersonId};
+use crate::state::{Journey, PersonStatus, PersonStatusFlag};
+use crate::{Error, Result};
+
+use super::{Event, EventPayload};
+
+static PERSON_UPDATED_TYPE: &str = "io.caspers.persons.updated";
+
+/// Position and status of a single person in a frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramePerson {
+    pub person_id: PersonId,
+    pub role: Option<String>,
+    pub status: PersonStatusFlag,
+    pub order_id: Option<OrderId>,
+    pub position: Point,
+}
+
+/// Positions of all people at a point in simulated time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Sequence number of the frame, starting at zero.
+    pub index: usize,
+    pub time: DateTime<Utc>,
+    pub people: Vec<FramePerson>,
+}
+
+impl Frame {
+    /// Render the frame as a GeoJSON `FeatureCollection` of points.
+    pub fn to_geojson(&self) -> Value {
+        let features = self
+            .people
+            .iter()
+            .map(|person| {
+                json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Point",
+                        "coordinates": [person.position.x(), person.position.y()],
+                    },
+                    "properties": {
+                        "person_id": person.person_id.to_string(),
+                        "role": person.role,
+                        "status": person.status.as_ref(),
+                        "order_id": person.order_id.map(|id| id.to_string()),
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "type": "FeatureCollection",
+            "properties": {
+                "frame": self.index,
+                "time": self.time.to_rfc3339(),
+            },
+            "features": features,
+        })
+    }
+}
+
+/// Number of people within an H3 cell at a point in simulated time.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlaybackCell {
+    /// H3 index of the cell.
+    pub cell: String,
+    /// Longitude and latitude of the cell center.
+    pub position: [f64; 2],
+    pub count: usize,
+    /// Number of people in the cell by status.
+    pub statuses: BTreeMap<String, usize>,
+}
+
+/// A [`Frame`] aggregated per H3 cell.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlaybackFrame {
+    pub index: usize,
+    pub time: DateTime<Utc>,
+    /// Occupied cells, ordered by their index.
+    pub cells: Vec<PlaybackCell>,
+}
+
+impl Frame {
+    /// Count the people in the frame per H3 cell of the given resolution.
+    ///
+    /// Positions are expected as longitude and latitude.
+    pub fn aggregate(&self, resolution: u8) -> Result<PlaybackFrame> {
+        let resolution = Resolution::try_from(resolution)
+            .map_err(|e| Error::invalid_data(format!("invalid H3 resolution: {e}")))?;
+        let mut cells = BTreeMap::<CellIndex, PlaybackCell>::new();
+        for person in &self.people {
+            let cell = LatLng::new(person.position.y(), person.position.x())?.to_cell(resolution);
+            let entry = cells.entry(cell).or_insert_with(|| {
+                let center = LatLng::from(cell);
+                PlaybackCell {
+                    cell: cell.to_string(),
+                    position: [center.lng(), center.lat()],
+                    count: 0,
+                    statuses: BTreeMap::new(),
+                }
+            });
+            entry.count += 1;
+            *entry
+                .statuses
+                .entry(person.status.as_ref().to_string())
+                .or_default() += 1;
+        }
+        Ok(PlaybackFrame {
+            index: self.index,
+            time: self.time,
+            cells: cells.into_values().collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Anchor {
+    Static(Option<Point>),
+    Moving {
+        since: DateTime<Utc>,
+        origin: Option<Point>,
+        journey: Journey,
+    },
+}
+
+impl Anchor {
+    fn position(&self, time: DateTime<Utc>) -> Option<Point> {
+        match self {
+            Anchor::Static(position) => *position,
+            Anchor::Moving {
+                since,
+                origin,
+                journey,
+            } => {
+                let elapsed_s = (time - *since).num_milliseconds().max(0) as f64 / 1000.0;
+                let distance_m =
+                    journey.distance_completed_m() + journey.velocity_m_s() * elapsed_s;
+                journey.position_at(*origin, distance_m)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Track {
+    role: Option<String>,
+    status: PersonStatusFlag,
+    order_id: Option<OrderId>,
+    anchor: Anchor,
+}
+
+/// Samples positions of people from simulation events at a fixed frame interval.
+#[derive(Debug, Clone)]
+pub struct FrameRenderer {
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    interval: Duration,
+    tracks: HashMap<PersonId, Track>,
+}
+
+impl FrameRenderer {
+    /// Render frames for the time range `[start, end]`, one per simulated minute by default.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start,
+            end,
+            interval: Duration::minutes(1),
+            tracks: HashMap::new(),
+        }
+    }
+
+    /// Set the simulated time between two consecutive frames.
+    ///
+    /// The interval must be at least one millisecond, otherwise rendering fails.
+    pub fn with_frame_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the initial position and role of a person.
+    ///
+    /// People without a known position only appear in frames once they start a journey.
+    pub fn with_person(
+        mut self,
+        person_id: PersonId,
+        position: Point,
+        role: Option<String>,
+    ) -> Self {
+        self.tracks.insert(
+            person_id,
+            Track {
+                role,
+                status: PersonStatusFlag::Idle,
+                order_id: None,
+                anchor: Anchor::Static(Some(position)),
+            },
+        );
+        self
+    }
+
+    /// Number of frames in the configured time range.
+    pub fn num_frames(&self) -> usize {
+        if self.interval.num_milliseconds() <= 0 || self.end < self.start {
+            return 0;
+        }
+        ((self.end - self.start).num_milliseconds() / self.interval.num_milliseconds()) as usize + 1
+    }
+
+    /// Replay the events and sample a frame at every frame interval.
+    pub fn render(&self, events: impl IntoIterator<Item = Event>) -> Result<Vec<Frame>> {
+        if self.interval.num_milliseconds() <= 0 {
+            return Err(Error::invalid_data(format!(
+                "frame interval must be at least one millisecond, got {}",
+                self.interval
+            )));
+        }
+
+        let mut events = events
+            .into_iter()
+            .filter(|event| matches!(event.payload, EventPayload::PersonUpdated(_)))
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| event.timestamp);
+        let mut events = events.into_iter().peekable();
+
+        let mut tracks = self.tracks.clone();
+        let mut frames = Vec::with_capacity(self.num_frames());
+        for index in 0..self.num_frames() {
+            let time = self.start + self.interval * index as i32;
+            while let Some(event) = events.next_if(|event| event.timestamp <= time) {
+                let EventPayload::PersonUpdated(payload) = event.payload else {
+                    continue;
+                };
+                let track = tracks.entry(payload.person_id).or_insert_with(|| Track {
+                    role: None,
+                    status: PersonStatusFlag::Idle,
+                    order_id: None,
+                    anchor: Anchor::Static(None),
+                });
+                let position = track.anchor.position(event.timestamp);
+                track.status = payload.status.flag();
+                (track.order_id, track.anchor) = match payload.status {
+                    PersonStatus::Idle | PersonStatus::Eating(_) => {
+                        (None, Anchor::Static(position))
+                    }
+                    PersonStatus::AwaitingOrder(order_id) => {
+                        (Some(order_id), Anchor::Static(position))
+                    }
+                    PersonStatus::WaitingForCustomer(order_id, _) => {
+                        (Some(order_id), Anchor::Static(position))
+                    }
+                    PersonStatus::Moving(journey) => (
+                        None,
+                        Anchor::Moving {
+                            since: event.timestamp,
+                            origin: position,
+                            journey,
+                        },
+                    ),
+                    PersonStatus::Delivering(order_id, journey) => (
+                        Some(order_id),
+                        Anchor::Moving {
+                            since: event.timestamp,
+                            origin: position,
+                            journey,
+                        },
+                    ),
+                };
+            }
+
+            let mut people = tracks
+                .iter()
+                .filter_map(|(person_id, track)| {
+                    Some(FramePerson {
+                        person_id: *person_id,
+                        role: track.role.clone(),
+                        status: track.status,
+                        order_id: track.order_id,
+                        position: track.anchor.position(time)?,
+                    })
+                })
+                .collect::<Vec<_>>();
+            people.sort_by_key(|person| *AsRef::<Uuid>::as_ref(&person.person_id));
+            frames.push(Frame {
+                index,
+                time,
+                people,
+            });
+        }
+
+        Ok(frames)
+    }
+
+    /// Render frames from the events and population snapshot stored for a simulation.
+    pub async fn render_results(self, ctx: &SimulationContext) -> Result<Vec<Frame>> {
+        let renderer = self.with_population_snapshot(ctx).await?;
+        let batches = ctx
+            .results()
+            .events()
+            .await?
+            .filter(col("type").eq(lit(PERSON_UPDATED_TYPE)))?
+            .select_columns(&["time", "data"])?
+            .collect()
+            .await?;
+        let events = renderer.parse_events(&batches)?;
+        renderer.render(events)
+    }
+
+    /// Render frames from the population snapshot of the context and the events of all
+    /// runs of the simulation.
+    ///
+    /// Unlike [`render_results`](Self::render_results), events recorded after later
+    /// snapshots of the simulation are replayed as well, so a whole run can be rendered
+    /// from the snapshot it was started from.
+    pub async fn render_run(self, ctx: &SimulationContext) -> Result<Vec<Frame>> {
+        let renderer = self.with_population_snapshot(ctx).await?;
+        let recorded = ctx
+            .results()
+            .events_between(DateTime::UNIX_EPOCH, renderer.end)
+            .await?
+            .filter(col("type").eq(lit(PERSON_UPDATED_TYPE)))?
+            .select_columns(&["time", "data"])?;
+        let events = renderer.parse_events(&ctx.collect(recorded).await?)?;
+        renderer.render(events)
+    }
+
+    async fn with_population_snapshot(mut self, ctx: &SimulationContext) -> Result<Self> {
+        let population = ctx
+            .snapshots()
+            .population()
+            .await?
+            .select_columns(&["id", "role", "position"])?
+            .collect()
+            .await?;
+        let point_type = PointType::new(Dimension::XY, Default::default());
+        for batch in population {
+            let ids = batch.column(0).as_fixed_size_binary();
+            let roles = batch.column(1).as_string_view();
+            let positions: PointArray =
+                (batch.column(2).as_struct(), point_type.clone()).try_into()?;
+            for (idx, position) in positions.iter().enumerate() {
+                let Some(Ok(position)) = position else {
+                    continue;
+                };
+                let person_id = PersonId::from(Uuid::from_slice(ids.value(idx))?);
+                let role = roles.is_valid(idx).then(|| roles.value(idx).to_string());
+                self = self.with_person(person_id, position.to_point(), role);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Parse events from batches of `time` and `data` columns, skipping those after the last frame.
+    fn parse_events(&self, batches: &[RecordBatch]) -> Result<Vec<Event>> {
+        let mut events = Vec::new();
+        for batch in batches {
+            let times = batch.column(0).as_string::<i64>();
+            let data = batch.column(1).as_string::<i64>();
+            for (time, data) in times.iter().zip(data.iter()) {
+                let (Some(time), Some(data)) = (time, data) else {
+                    continue;
+                };
+                let timestamp = DateTime::parse_from_rfc3339(time)
+                    .map_err(|e| Error::invalid_data(format!("invalid event time '{time}': {e}")))?
+                    .with_timezone(&Utc);
+                if timestamp > self.end {
+                    continue;
+                }
+                events.push(Event {
+                    timestamp,
+                    payload: serde_json::from_str(data)?,
+                });
+            }
+        }
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_frames() -> Result<()> {
+        let start = Utc::now();
+        let courier = PersonId::new();
+        let customer = PersonId::new();
+        let order_id = OrderId::new();
+
+        // 1000m east-bound journey at bicycle speed (~250m per minute)
+        let journey: Journey = [(Point::new(1000.0, 0.0), 1000)].into_iter().collect();
+        let events = vec![Event {
+            timestamp: start + Duration::minutes(1),
+            payload: EventPayload::person_updated(
+                courier,
+                PersonStatus::Delivering(order_id, journey),
+            ),
+        }];
+
+        let frames = FrameRenderer::new(start, start + Duration::minutes(10))
+            .with_frame_interval(Duration::minutes(2))
+            .with_person(courier, Point::new(0.0, 0.0), Some("courier".into()))
+            .with_person(customer, Point::new(1000.0, 0.0), Some("customer".into()))
+            .render(events)?;
+        assert_eq!(frames.len(), 6);
+
+        let courier_at = |frame: &Frame| {
+            frame
+                .people
+                .iter()
+                .find(|p| p.person_id == courier)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(courier_at(&frames[0]).status, PersonStatusFlag::Idle);
+        assert_eq!(courier_at(&frames[0]).position, Point::new(0.0, 0.0));
+
+        let moving = courier_at(&frames[1]);
+        assert_eq!(moving.status, PersonStatusFlag::Delivering);
+        assert_eq!(moving.order_id, Some(order_id));
+        assert!(moving.position.x() > 0.0 && moving.position.x() < 1000.0);
+
+        // the journey is completed and the courier stays at the destination
+        assert_eq!(courier_at(&frames[5]).position, Point::new(1000.0, 0.0));
+        assert_eq!(frames[5].people.len(), 2);
+
+        let geojson = frames[1].to_geojson();
+        assert_eq!(geojson["type"], "FeatureCollection");
+        assert_eq!(geojson["features"].as_array().unwrap().len(), 2);
+        assert_eq!(geojson["properties"]["frame"], 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_frame() -> Result<()> {
+        let person = |status, position| FramePerson {
+            person_id: PersonId::new(),
+            role: None,
+            status,
+            order_id: None,
+            position,
+        };
+        let frame = Frame {
+            index: 3,
+            time: Utc::now(),
+            people: vec![
+                person(PersonStatusFlag::Idle, Point::new(-0.1278, 51.5074)),
+                person(PersonStatusFlag::Delivering, Point::new(-0.1278, 51.5074)),
+                person(PersonStatusFlag::Idle, Point::new(13.4050, 52.5200)),
+            ],
+        };
+
+        let playback = frame.aggregate(9)?;
+        assert_eq!(playback.index, 3);
+        assert_eq!(playback.cells.len(), 2);
+        let london = LatLng::new(51.5074, -0.1278)?
+            .to_cell(Resolution::Nine)
+            .to_string();
+        let cell = playback.cells.iter().find(|c| c.cell == london).unwrap();
+        assert_eq!(cell.count, 2);
+        assert_eq!(cell.statuses["idle"], 1);
+        assert_eq!(cell.statuses["delivering"], 1);
+        assert!((cell.position[0] + 0.1278).abs() < 0.01);
+        assert!((cell.position[1] - 51.5074).abs() < 0.01);
+
+        let coarse = frame.aggregate(0)?;
+        assert_eq!(coarse.cells.iter().map(|c| c.count).sum::<usize>(), 3);
+        assert!(frame.aggregate(16).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_frame_interval() {
+        let start = Utc::now();
+        for interval in [
+            Duration::zero(),
+            Duration::microseconds(500),
+            Duration::minutes(-1),
+        ] {
+            let renderer = FrameRenderer::new(start, start + Duration::minutes(10))
+                .with_frame_interval(interval);
+            assert_eq!(renderer.num_frames(), 0);
+            assert!(renderer.render(vec![]).is_err());
+        }
+    }
+}
@@ -0,0 +1,533 @@
+//! Green delivery, a checkout option trading a longer delivery window for bundled,
+//! lower-emission deliveries.
+//!
+//! Without a [`GreenDeliveryConfig`] every order is delivered on its own. With one, a
+//! share of customers chooses green delivery at checkout, and their orders are
+//! promised [`GreenDeliveryConfig::extra_window_mins`] later. Whether a customer
+//! chooses green delivery is derived from their id, so they keep their choice across
+//! steps and runs.
+//!
+//! Dispatchers hold ready green orders back until enough green orders to nearby
+//! destinations are ready to fill a bundle, or the first of them was held for
+//! [`GreenDeliveryConfig::max_hold_mins`]. A bundle is offered to couriers as a single
+//! delivery: the courier taking it rides to the destination of its first order and
+//! hands over the other orders, whose destinations are close by, on the way. All
+//! orders of a bundle are delivered once its first order is. With a
+//! [`CarbonConfig`](super::CarbonConfig), the footprint of the trip, including the
+//! detours between the destinations, is shared among the orders of the bundle.
+//!
+//! Orders, deliveries and emissions of green and standard orders are rolled up per
+//! site and day in the `green_delivery` results table, to compare the emissions saved
+//! with the longer delivery times. As in the daily summary, deliveries of orders
+//! placed before the start of the run are not reported.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, Duration, DurationRound as _, TimeDelta, Utc};
+use h3o::LatLng;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+
+use crate::builders::{GreenDeliveryBuffer, GreenDeliveryDay};
+use crate::idents::{OrderId, PersonId, SiteId};
+use crate::state::{OrderStatus, PersonStatus, State};
+use crate::{CourierActivity, Error, EventPayload, Result};
+
+/// Mixed into the ids of customers, so the choice of green delivery is independent of
+/// other traits derived from the id, e.g. the priority tier.
+const GREEN_SALT: u64 = 0x6772_6565_6e5f_6f6b;
+
+/// Minutes customers eat once their order arrived.
+const EATING_MINS: i64 = 30;
+
+/// Opt-in and bundling of green deliveries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GreenDeliveryConfig {
+    /// Share of customers choosing green delivery at checkout
+    pub opt_in_share: f64,
+
+    /// Minutes green orders are promised later than standard orders
+    pub extra_window_mins: i64,
+
+    /// Most orders delivered together in one bundle
+    pub bundle_size: usize,
+
+    /// Longest time in minutes a ready green order is held back waiting for a bundle
+    pub max_hold_mins: i64,
+
+    /// Largest distance in meters of the destinations of a bundle from its first one
+    pub bundle_radius_m: f64,
+}
+
+impl Default for GreenDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            opt_in_share: 0.2,
+            extra_window_mins: 30,
+            bundle_size: 3,
+            max_hold_mins: 15,
+            bundle_radius_m: 1_500.0,
+        }
+    }
+}
+
+impl GreenDeliveryConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.opt_in_share) {
+            return Err(Error::invalid_data(format!(
+                "green delivery share {} outside of [0, 1]",
+                self.opt_in_share
+            )));
+        }
+        if self.extra_window_mins < 0 || self.max_hold_mins < 0 {
+            return Err(Error::invalid_data(
+                "green delivery windows must not be negative",
+            ));
+        }
+        if self.bundle_size == 0 {
+            return Err(Error::invalid_data("bundles must hold at least one order"));
+        }
+        if !(self.bundle_radius_m.is_finite() && self.bundle_radius_m >= 0.0) {
+            return Err(Error::invalid_data(
+                "bundle radius must be a non-negative number",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the customer with id `person_id` chooses green delivery.
+    pub(crate) fn opts_in(&self, person_id: &PersonId) -> bool {
+        let id: &[u8] = person_id.as_ref();
+        let key = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+        StdRng::seed_from_u64(key ^ GREEN_SALT).random_bool(self.opt_in_share)
+    }
+
+    /// Time by which a green order is delivered, if it were a standard order by `promised_at`.
+    pub(crate) fn promised_at(&self, promised_at: DateTime<Utc>) -> DateTime<Utc> {
+        promised_at + Duration::minutes(self.extra_window_mins)
+    }
+}
+
+/// Bundles the green orders of a site and completes the bundles in delivery.
+#[derive(Debug, Clone)]
+pub(crate) struct Bundler {
+    config: GreenDeliveryConfig,
+    /// Ready green orders, with the time they were first seen ready
+    waiting: HashMap<OrderId, DateTime<Utc>>,
+    /// Courier and the orders handed over on the way, by the first order of a bundle
+    in_delivery: BTreeMap<OrderId, (PersonId, Vec<OrderId>)>,
+}
+
+impl Bundler {
+    pub(crate) fn new(config: GreenDeliveryConfig) -> Self {
+        Self {
+            config,
+            waiting: HashMap::new(),
+            in_delivery: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn is_green(&self, person_id: &PersonId) -> bool {
+        self.config.opts_in(person_id)
+    }
+
+    /// Group the ready green `orders` into bundles released for dispatch at `now`.
+    ///
+    /// Orders are bundled with the orders to nearby destinations which became ready
+    /// after them. Bundles which are neither full nor held long enough wait for more
+    /// orders, and orders of bundles nobody took are bundled again in the next step.
+    pub(crate) fn bundle(
+        &mut self,
+        now: DateTime<Utc>,
+        orders: Vec<(OrderId, LatLng)>,
+    ) -> Vec<Vec<OrderId>> {
+        let ready: HashSet<_> = orders.iter().map(|(order_id, _)| *order_id).collect();
+        self.waiting.retain(|order_id, _| ready.contains(order_id));
+        for (order_id, _) in &orders {
+            self.waiting.entry(*order_id).or_insert(now);
+        }
+
+        let mut remaining = orders;
+        remaining.sort_by_key(|(order_id, _)| (self.waiting[order_id], *order_id));
+        let max_hold = Duration::minutes(self.config.max_hold_mins);
+        let mut bundles = Vec::new();
+        while !remaining.is_empty() {
+            let (first, destination) = remaining.remove(0);
+            let mut bundle = vec![first];
+            remaining.retain(|(order_id, other)| {
+                if bundle.len() < self.config.bundle_size
+                    && destination.distance_m(*other) <= self.config.bundle_radius_m
+                {
+                    bundle.push(*order_id);
+                    return false;
+                }
+                true
+            });
+            if bundle.len() >= self.config.bundle_size || now - self.waiting[&first] >= max_hold {
+                bundles.push(bundle);
+            }
+        }
+        bundles
+    }
+
+    /// Track the `others` orders of a bundle handed over by `courier` on the way to `first`.
+    pub(crate) fn dispatch(&mut self, first: OrderId, courier: PersonId, others: Vec<OrderId>) {
+        if !others.is_empty() {
+            self.in_delivery.insert(first, (courier, others));
+        }
+    }
+
+    /// Whether the first order of any bundle in delivery was delivered or failed.
+    pub(crate) fn has_completed(&self, state: &State) -> bool {
+        self.in_delivery
+            .keys()
+            .any(|first| status(state, first).is_none_or(|status| !status.is_open()))
+    }
+
+    /// Deliver the other orders of bundles whose first order was delivered.
+    ///
+    /// The other orders fail with the first order of their bundle.
+    pub(crate) fn complete(&mut self, state: &State) -> Result<Vec<EventPayload>> {
+        let now = state.current_time();
+        let mut events = Vec::new();
+        let mut completed = Vec::new();
+        for (first, (courier, others)) in &self.in_delivery {
+            match status(state, first) {
+                Some(OrderStatus::Delivered) => {
+                    for order_id in others {
+                        let Some(order) = state.orders().order(order_id) else {
+                            continue;
+                        };
+                        events.push(EventPayload::order_updated(
+                            *order_id,
+                            OrderStatus::Delivered,
+                            None,
+                        ));
+                        events.push(EventPayload::courier_updated(
+                            *courier,
+                            *order_id,
+                            CourierActivity::Delivered,
+                            None,
+                        ));
+                        events.push(EventPayload::person_updated(
+                            order.customer_person_id().try_into()?,
+                            PersonStatus::Eating(now + Duration::minutes(EATING_MINS)),
+                        ));
+                    }
+                }
+                Some(OrderStatus::Cancelled | OrderStatus::Failed) => {
+                    events.extend(
+                        others
+                            .iter()
+                            .map(|order_id| EventPayload::order_failed(*order_id, Some(*courier))),
+                    );
+                }
+                Some(_) => continue,
+                // bundles of unknown orders are dropped
+                None => (),
+            }
+            completed.push(*first);
+        }
+        for first in completed {
+            self.in_delivery.remove(&first);
+        }
+        Ok(events)
+    }
+}
+
+/// Status of the order with id `order_id`, if known.
+fn status(state: &State, order_id: &OrderId) -> Option<OrderStatus> {
+    state
+        .orders()
+        .order(order_id)
+        .and_then(|order| order.status().parse().ok())
+}
+
+/// Green or standard orders of a site during the current day.
+#[derive(Debug, Clone, Default)]
+struct OptionTotals {
+    orders: i64,
+    delivered_orders: i64,
+    on_time_deliveries: i64,
+    bundled_deliveries: i64,
+    delivery_time_s: f64,
+    co2_g: f64,
+}
+
+/// An order placed during the run and not yet delivered.
+struct GreenOrder {
+    site_id: SiteId,
+    green: bool,
+    placed_at: DateTime<Utc>,
+    promised_at: DateTime<Utc>,
+    bundled: bool,
+    co2_g: f64,
+}
+
+/// Rolls up the orders of each delivery option per site and day.
+pub(crate) struct GreenDeliveryReport {
+    config: GreenDeliveryConfig,
+    orders: HashMap<OrderId, GreenOrder>,
+    /// The day currently rolled up
+    day: Option<DateTime<Utc>>,
+    totals: BTreeMap<(SiteId, bool), OptionTotals>,
+    /// Completed days waiting to be written
+    buffer: GreenDeliveryBuffer,
+}
+
+impl GreenDeliveryReport {
+    pub(crate) fn new(config: GreenDeliveryConfig) -> Self {
+        Self {
+            config,
+            orders: HashMap::new(),
+            day: None,
+            totals: BTreeMap::new(),
+            buffer: GreenDeliveryBuffer::new(),
+        }
+    }
+
+    /// Account for the events of the step at `now`.
+    ///
+    /// The previous day is completed once a step starts on a new day.
+    pub(crate) fn record(&mut self, now: DateTime<Utc>, events: &[EventPayload]) -> Result<()> {
+        let day = now
+            .duration_trunc(TimeDelta::days(1))
+            .map_err(|e| Error::invalid_data(format!("invalid step time: {e}")))?;
+        if self.day.is_some_and(|current| current != day) {
+            self.finish_day()?;
+        }
+        self.day = Some(day);
+
+        // couriers picking up several orders at once deliver them in a bundle
+        let mut pickups: HashMap<PersonId, Vec<OrderId>> = HashMap::new();
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(payload) => {
+                    let green = self.config.opts_in(&payload.person_id);
+                    self.totals
+                        .entry((payload.site_id, green))
+                        .or_default()
+                        .orders += 1;
+                    self.orders.insert(
+                        payload.order_id,
+                        GreenOrder {
+                            site_id: payload.site_id,
+                            green,
+                            placed_at: now,
+                            promised_at: payload.promised_at,
+                            bundled: false,
+                            co2_g: 0.0,
+                        },
+                    );
+                }
+                EventPayload::DeliveryFootprint(payload) => {
+                    if let Some(order) = self.orders.get_mut(&payload.order_id) {
+                        order.co2_g += payload.co2_g;
+                    }
+                }
+                EventPayload::OrderUpdated(payload) => match payload.status {
+                    OrderStatus::PickedUp => {
+                        if let Some(courier) = payload.actor_id {
+                            pickups.entry(courier).or_default().push(payload.order_id);
+                        }
+                    }
+                    OrderStatus::Delivered => {
+                        let Some(order) = self.orders.remove(&payload.order_id) else {
+                            continue;
+                        };
+                        let totals = self.totals.entry((order.site_id, order.green)).or_default();
+                        totals.delivered_orders += 1;
+                        if now <= order.promised_at {
+                            totals.on_time_deliveries += 1;
+                        }
+                        if order.bundled {
+                            totals.bundled_deliveries += 1;
+                        }
+                        totals.delivery_time_s +=
+                            (now - order.placed_at).num_milliseconds() as f64 / 1000.0;
+                        totals.co2_g += order.co2_g;
+                    }
+                    OrderStatus::Cancelled | OrderStatus::Failed => {
+                        self.orders.remove(&payload.order_id);
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+        for order_ids in pickups
+            .into_values()
+            .filter(|order_ids| order_ids.len() > 1)
+        {
+            for order_id in order_ids {
+                if let Some(order) = self.orders.get_mut(&order_id) {
+                    order.bundled = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete the day currently rolled up, e.g. at the end of a run.
+    pub(crate) fn finish_day(&mut self) -> Result<()> {
+        let Some(day) = self.day.take() else {
+            return Ok(());
+        };
+        for ((site_id, green), totals) in std::mem::take(&mut self.totals) {
+            self.buffer.push(&GreenDeliveryDay {
+                day,
+                site_id,
+                green,
+                orders: totals.orders,
+                delivered_orders: totals.delivered_orders,
+                on_time_deliveries: totals.on_time_deliveries,
+                bundled_deliveries: totals.bundled_deliveries,
+                delivery_time_s: totals.delivery_time_s,
+                co2_g: totals.co2_g,
+            })?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .to_utc()
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let config = GreenDeliveryConfig {
+            opt_in_share: 0.3,
+            ..Default::default()
+        };
+        let customers: Vec<_> = (0..2000).map(|_| PersonId::new()).collect();
+        let green = customers.iter().filter(|c| config.opts_in(c)).count();
+        assert!((500..700).contains(&green));
+        assert!(
+            customers
+                .iter()
+                .all(|c| config.opts_in(c) == config.opts_in(c))
+        );
+        assert_eq!(config.promised_at(start()), start() + Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_bundle() {
+        let mut bundler = Bundler::new(GreenDeliveryConfig {
+            bundle_size: 2,
+            max_hold_mins: 10,
+            bundle_radius_m: 1_000.0,
+            ..Default::default()
+        });
+        let near = LatLng::new(52.370, 4.890).unwrap();
+        let next_door = LatLng::new(52.372, 4.891).unwrap();
+        let far = LatLng::new(52.400, 4.950).unwrap();
+        let (a, b, c) = (OrderId::new(), OrderId::new(), OrderId::new());
+
+        // a lone order is held back
+        assert!(bundler.bundle(start(), vec![(a, near)]).is_empty());
+        // an order to a far destination does not join the bundle
+        assert!(
+            bundler
+                .bundle(start(), vec![(a, near), (c, far)])
+                .is_empty()
+        );
+        let bundles = bundler.bundle(
+            start() + Duration::minutes(1),
+            vec![(a, near), (b, next_door), (c, far)],
+        );
+        assert_eq!(bundles, vec![vec![a, b]]);
+
+        // orders held long enough are released on their own
+        let bundles = bundler.bundle(start() + Duration::minutes(10), vec![(c, far)]);
+        assert_eq!(bundles, vec![vec![c]]);
+    }
+
+    #[test]
+    fn test_report() -> Result<()> {
+        let config = GreenDeliveryConfig {
+            opt_in_share: 1.0,
+            ..Default::default()
+        };
+        let mut report = GreenDeliveryReport::new(config);
+        let site_id = SiteId::from_name("london");
+        let courier = PersonId::new();
+        let (a, b) = (OrderId::new(), OrderId::new());
+        for order_id in [a, b] {
+            report.orders.insert(
+                order_id,
+                GreenOrder {
+                    site_id,
+                    green: true,
+                    placed_at: start(),
+                    promised_at: start() + Duration::minutes(60),
+                    bundled: false,
+                    co2_g: 0.0,
+                },
+            );
+        }
+
+        report.record(
+            start() + Duration::minutes(20),
+            &[
+                EventPayload::order_updated(a, OrderStatus::PickedUp, Some(courier)),
+                EventPayload::order_updated(b, OrderStatus::PickedUp, Some(courier)),
+            ],
+        )?;
+        report.record(
+            start() + Duration::minutes(40),
+            &[
+                EventPayload::order_updated(a, OrderStatus::Delivered, None),
+                EventPayload::order_updated(b, OrderStatus::Delivered, None),
+            ],
+        )?;
+        let totals = &report.totals[&(site_id, true)];
+        assert_eq!(totals.delivered_orders, 2);
+        assert_eq!(totals.on_time_deliveries, 2);
+        assert_eq!(totals.bundled_deliveries, 2);
+        assert_eq!(totals.delivery_time_s, 4_800.0);
+
+        assert!(!report.has_pending());
+        report.finish_day()?;
+        assert_eq!(report.flush()?.num_rows(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(GreenDeliveryConfig::default().validate().is_ok());
+        for config in [
+            GreenDeliveryConfig {
+                opt_in_share: 1.5,
+                ..Default::default()
+            },
+            GreenDeliveryConfig {
+                bundle_size: 0,
+                ..Default::default()
+            },
+            GreenDeliveryConfig {
+                max_hold_mins: -1,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
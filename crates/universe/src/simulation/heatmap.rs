@@ -0,0 +1,262 @@
+//! Order heatmap materialized after each run.
+//!
+//! Analytics on where and when orders are placed all start from the same aggregation
+//! of the raw events. After a run, the `order_created` and `order_updated` events of
+//! all runs of the simulation are therefore aggregated per H3 cell of the order
+//! destination and hour of placement, and written to the `order_heatmap` results
+//! table with the snapshot taken at the end of the run.
+//!
+//! Revenue is reported in the base currency of the configured [`ExchangeRates`].
+//! Only events that were written are accounted for, so sampling order events with an
+//! [`EventFilter`](super::EventFilter) thins out the heatmap accordingly.
+
+use std::collections::{BTreeMap, HashMap};
+
+use arrow::array::RecordBatch;
+use arrow::array::cast::AsArray as _;
+use chrono::{DateTime, DurationRound as _, TimeDelta, Utc};
+use datafusion::prelude::{col, lit};
+use h3o::{CellIndex, LatLng, Resolution};
+
+use crate::builders::{HeatmapBuffer, HeatmapCell};
+use crate::context::SimulationContext;
+use crate::idents::OrderId;
+use crate::state::OrderStatus;
+use crate::{Error, EventPayload, ExchangeRates, Money, Result};
+
+/// H3 resolution of the order heatmap, cells span roughly 0.7 km².
+pub const DEFAULT_HEATMAP_RESOLUTION: u8 = 8;
+
+static ORDER_EVENT_TYPES: [&str; 2] = ["io.caspers.orders.created", "io.caspers.orders.updated"];
+
+pub(crate) fn heatmap_resolution(resolution: u8) -> Result<Resolution> {
+    Resolution::try_from(resolution)
+        .map_err(|e| Error::invalid_data(format!("invalid heatmap resolution: {e}")))
+}
+
+struct PlacedOrder {
+    key: (CellIndex, DateTime<Utc>),
+    placed_at: DateTime<Utc>,
+}
+
+/// Aggregates order events into heatmap cells.
+pub(crate) struct OrderHeatmap {
+    resolution: Resolution,
+    rates: ExchangeRates,
+    orders: HashMap<OrderId, PlacedOrder>,
+    cells: BTreeMap<(CellIndex, DateTime<Utc>), HeatmapCell>,
+}
+
+impl OrderHeatmap {
+    pub(crate) fn new(resolution: Resolution, rates: ExchangeRates) -> Self {
+        Self {
+            resolution,
+            rates,
+            orders: HashMap::new(),
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Account for an event recorded at `timestamp`.
+    ///
+    /// Deliveries of orders placed before the first recorded event are ignored.
+    pub(crate) fn record(&mut self, timestamp: DateTime<Utc>, event: &EventPayload) -> Result<()> {
+        match event {
+            EventPayload::OrderCreated(payload) => {
+                let destination = &payload.destination;
+                let cell = LatLng::new(destination.y(), destination.x())?.to_cell(self.resolution);
+                let hour = timestamp
+                    .duration_trunc(TimeDelta::hours(1))
+                    .map_err(|e| Error::invalid_data(format!("invalid order time: {e}")))?;
+                let revenue = self.rates.convert(
+                    Money::new(payload.total, payload.currency),
+                    self.rates.base(),
+                )?;
+                let entry = self
+                    .cells
+                    .entry((cell, hour))
+                    .or_insert_with(|| HeatmapCell {
+                        cell,
+                        hour,
+                        orders: 0,
+                        delivered_orders: 0,
+                        revenue: Money::zero(self.rates.base()),
+                        delivery_time_s: 0.0,
+                    });
+                entry.orders += 1;
+                entry.revenue = entry.revenue.checked_add(revenue)?;
+                self.orders.insert(
+                    payload.order_id,
+                    PlacedOrder {
+                        key: (cell, hour),
+                        placed_at: timestamp,
+                    },
+                );
+            }
+            EventPayload::OrderUpdated(payload) if payload.status == OrderStatus::Delivered => {
+                let Some(order) = self.orders.remove(&payload.order_id) else {
+                    return Ok(());
+                };
+                if let Some(entry) = self.cells.get_mut(&order.key) {
+                    entry.delivered_orders += 1;
+                    entry.delivery_time_s +=
+                        (timestamp - order.placed_at).num_milliseconds() as f64 / 1000.0;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Account for the order events recorded in any run of the simulation up to `end`.
+    pub(crate) async fn record_results(
+        &mut self,
+        ctx: &SimulationContext,
+        end: DateTime<Utc>,
+    ) -> Result<()> {
+        let recorded = ctx
+            .results()
+            .events_between(DateTime::UNIX_EPOCH, end)
+            .await?
+            .filter(col("type").in_list(ORDER_EVENT_TYPES.map(lit).to_vec(), false))?
+            .select_columns(&["time", "data"])?;
+        for batch in ctx.collect(recorded).await? {
+            let times = batch.column(0).as_string::<i64>();
+            let data = batch.column(1).as_string::<i64>();
+            for (time, data) in times.iter().zip(data.iter()) {
+                let (Some(time), Some(data)) = (time, data) else {
+                    continue;
+                };
+                let timestamp = DateTime::parse_from_rfc3339(time)
+                    .map_err(|e| Error::invalid_data(format!("invalid event time '{time}': {e}")))?
+                    .with_timezone(&Utc);
+                self.record(timestamp, &serde_json::from_str(data)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(self) -> Result<RecordBatch> {
+        let mut buffer = HeatmapBuffer::new();
+        for cell in self.cells.values() {
+            buffer.push(cell);
+        }
+        buffer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Array as _;
+    use arrow::datatypes::{Float64Type, Int64Type};
+    use chrono::Duration;
+    use geo::Point;
+
+    use crate::test_utils::order_created;
+    use crate::{Currency, EventDataBuilder, OrderCreatedPayload};
+
+    use super::*;
+
+    fn created(order_id: OrderId, destination: Point, total: f64, currency: &str) -> EventPayload {
+        EventPayload::OrderCreated(OrderCreatedPayload {
+            destination,
+            total,
+            currency: currency.parse().unwrap(),
+            ..order_created(order_id)
+        })
+    }
+
+    fn delivered(order_id: OrderId) -> EventPayload {
+        EventPayload::order_updated(order_id, OrderStatus::Delivered, None)
+    }
+
+    #[test]
+    fn test_order_heatmap() -> Result<()> {
+        let rates = ExchangeRates::new(Currency::USD).with_rate("EUR".parse()?, 1.1);
+        let mut heatmap = OrderHeatmap::new(heatmap_resolution(8)?, rates);
+
+        let start = "2025-01-01T12:10:00Z".parse::<DateTime<Utc>>().unwrap();
+        let london = Point::new(-0.1278, 51.5074);
+        let (first, second, third) = (OrderId::new(), OrderId::new(), OrderId::new());
+        let events = [
+            (start, created(first, london, 10.0, "USD")),
+            (start, created(second, london, 10.0, "EUR")),
+            (start + Duration::minutes(30), delivered(first)),
+            (
+                start + Duration::hours(1),
+                created(third, london, 5.0, "USD"),
+            ),
+            // deliveries of unknown orders are skipped
+            (start + Duration::hours(1), delivered(OrderId::new())),
+        ];
+        for (timestamp, event) in &events {
+            heatmap.record(*timestamp, event)?;
+        }
+
+        let batch = heatmap.finish()?;
+        assert_eq!(batch.num_rows(), 2);
+        let orders = batch.column(3).as_primitive::<Int64Type>();
+        let delivered = batch.column(4).as_primitive::<Int64Type>();
+        let revenue = batch.column(6).as_primitive::<Float64Type>();
+        let delivery_times = batch.column(7).as_primitive::<Float64Type>();
+        assert_eq!(orders.values(), &[2, 1]);
+        assert_eq!(delivered.values(), &[1, 0]);
+        assert_eq!(revenue.values(), &[21.0, 5.0]);
+        assert_eq!(delivery_times.value(0), 1800.0);
+        assert!(delivery_times.is_null(1));
+
+        assert!(heatmap_resolution(16).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_materialize_heatmap() -> Result<()> {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .with_simulation_start_time(start)
+            .build()
+            .await?;
+
+        let order_id = OrderId::new();
+        let mut events = EventDataBuilder::new();
+        events.add_payload(
+            start + Duration::seconds(10),
+            &created(order_id, Point::new(4.89, 52.37), 12.5, "USD"),
+        )?;
+        events.add_payload(start + Duration::minutes(20), &delivered(order_id))?;
+        // events after the end of the run are not accounted for
+        events.add_payload(
+            start + Duration::hours(2),
+            &created(OrderId::new(), Point::new(4.89, 52.37), 12.5, "USD"),
+        )?;
+        ctx.results()
+            .write_events(ctx.ctx().read_batch(events.build()?)?)
+            .await?;
+
+        let mut heatmap = OrderHeatmap::new(
+            heatmap_resolution(DEFAULT_HEATMAP_RESOLUTION)?,
+            ExchangeRates::default(),
+        );
+        heatmap
+            .record_results(&ctx, start + Duration::hours(1))
+            .await?;
+        let data = ctx.ctx().read_batch(heatmap.finish()?)?;
+        ctx.results().write_order_heatmap(data).await?;
+
+        let batches = ctx.results().order_heatmap().await?.collect().await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+        let batch = batches.iter().find(|b| b.num_rows() > 0).unwrap();
+        let cell = LatLng::new(52.37, 4.89)?.to_cell(Resolution::Eight);
+        assert_eq!(
+            batch.column(0).as_primitive::<Int64Type>().value(0),
+            u64::from(cell) as i64
+        );
+        assert_eq!(batch.column(3).as_primitive::<Int64Type>().value(0), 1);
+        assert_eq!(
+            batch.column(7).as_primitive::<Float64Type>().value(0),
+            1190.0
+        );
+        Ok(())
+    }
+}
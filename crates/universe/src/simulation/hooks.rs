@@ -0,0 +1,215 @@
+//! SQL-defined hooks to customize key behaviors of the simulation.
+//!
+//! Hooks are DataFusion SQL expressions that are evaluated every step, which allows
+//! tuning demand and customer behavior without recompiling the crate.
+//!
+//! `order_probability` and `basket_size` are evaluated over the idle customers of a
+//! site, with the population columns (`id`, `role`, `status`, `properties`, `position`,
+//! `state`, `schedule`, `age_bracket`, `household_size`, `income_band`, `preferences`)
+//! available in addition to `hour_of_day`. `tip_amount` is evaluated over the
+//! newly created orders with the columns `person_id`, `total`, `num_items`, `channel`
+//! and `hour_of_day`.
+//!
+//! ```
+//! use caspers_universe::BehaviorHooks;
+//!
+//! let hooks = BehaviorHooks::default()
+//!     .with_order_probability("CASE WHEN hour_of_day BETWEEN 11 AND 14 THEN 0.05 ELSE 0.005 END")
+//!     .with_tip_amount("round(total * 0.1, 2)");
+//! ```
+
+use std::sync::Arc;
+
+use arrow::array::{
+    Array as _, AsArray as _, FixedSizeBinaryBuilder, Float64Builder, RecordBatch,
+    StringViewBuilder, UInt64Builder,
+};
+use arrow::datatypes::Float64Type;
+use arrow_schema::{DataType, Field, Schema};
+use chrono::{DateTime, Timelike as _, Utc};
+use datafusion::prelude::{DataFrame, Expr, cast, lit};
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, OrderChannel, PersonId, Result, SimulationContext};
+
+/// SQL expressions overriding default simulation behaviors.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BehaviorHooks {
+    /// Probability that an idle customer places an order in the current step.
+    pub order_probability: Option<String>,
+
+    /// Number of items in an order placed by a customer, clamped to between 1 and 20.
+    pub basket_size: Option<String>,
+
+    /// Tip in the order currency added to a newly created order.
+    pub tip_amount: Option<String>,
+}
+
+impl BehaviorHooks {
+    pub fn with_order_probability(mut self, sql: impl Into<String>) -> Self {
+        self.order_probability = Some(sql.into());
+        self
+    }
+
+    pub fn with_basket_size(mut self, sql: impl Into<String>) -> Self {
+        self.basket_size = Some(sql.into());
+        self
+    }
+
+    pub fn with_tip_amount(mut self, sql: impl Into<String>) -> Self {
+        self.tip_amount = Some(sql.into());
+        self
+    }
+
+    /// Whether the order creation hooks are configured.
+    pub(crate) fn has_order_hooks(&self) -> bool {
+        self.order_probability.is_some() || self.basket_size.is_some()
+    }
+
+    /// Add the columns available to hooks evaluated over the population.
+    pub(crate) fn population_frame(df: DataFrame, time: DateTime<Utc>) -> Result<DataFrame> {
+        Ok(df.with_column("hour_of_day", lit(hour_of_day(time)))?)
+    }
+
+    /// Expression for the order probability, `NULL` if not configured.
+    pub(crate) fn order_probability_expr(&self, df: &DataFrame) -> Result<Expr> {
+        parse_or_null(
+            df,
+            "order_probability",
+            &self.order_probability,
+            DataType::Float64,
+        )
+    }
+
+    /// Expression for the basket size, `NULL` if not configured.
+    pub(crate) fn basket_size_expr(&self, df: &DataFrame) -> Result<Expr> {
+        parse_or_null(df, "basket_size", &self.basket_size, DataType::Int64)
+    }
+
+    /// Check that all hooks parse against the columns they are evaluated over.
+    pub(crate) fn validate(&self, ctx: &SimulationContext, population: DataFrame) -> Result<()> {
+        let population = Self::population_frame(population, Utc::now())?;
+        self.order_probability_expr(&population)?;
+        self.basket_size_expr(&population)?;
+
+        let orders = ctx
+            .ctx()
+            .read_batch(RecordBatch::new_empty(Arc::new(tip_schema())))?;
+        parse_or_null(&orders, "tip_amount", &self.tip_amount, DataType::Float64)?;
+        Ok(())
+    }
+
+    /// Evaluate the tip amount for newly created orders.
+    ///
+    /// Returns `None` for every order if no tip hook is configured.
+    pub(crate) async fn tip_amounts(
+        &self,
+        ctx: &SimulationContext,
+        time: DateTime<Utc>,
+        orders: &[(PersonId, f64, usize, OrderChannel)],
+    ) -> Result<Vec<Option<f64>>> {
+        if self.tip_amount.is_none() || orders.is_empty() {
+            return Ok(vec![None; orders.len()]);
+        }
+
+        let mut person_ids = FixedSizeBinaryBuilder::new(16);
+        let mut totals = Float64Builder::new();
+        let mut num_items = UInt64Builder::new();
+        let mut channels = StringViewBuilder::new();
+        let mut hours = Float64Builder::new();
+        for (person_id, total, items, channel) in orders {
+            person_ids.append_value(person_id)?;
+            totals.append_value(*total);
+            num_items.append_value(*items as u64);
+            channels.append_value(channel.as_ref());
+            hours.append_value(hour_of_day(time));
+        }
+        let batch = RecordBatch::try_new(
+            Arc::new(tip_schema()),
+            vec![
+                Arc::new(person_ids.finish()),
+                Arc::new(totals.finish()),
+                Arc::new(num_items.finish()),
+                Arc::new(channels.finish()),
+                Arc::new(hours.finish()),
+            ],
+        )?;
+
+        let df = ctx.ctx().read_batch(batch)?;
+        let tip = parse_or_null(&df, "tip_amount", &self.tip_amount, DataType::Float64)?;
+        let batches = df.select(vec![tip.alias("tip")])?.collect().await?;
+        Ok(batches
+            .iter()
+            .flat_map(|b| {
+                let tips = b.column(0).as_primitive::<Float64Type>();
+                (0..tips.len()).map(move |i| tips.is_valid(i).then(|| tips.value(i)))
+            })
+            .collect())
+    }
+}
+
+fn tip_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("person_id", DataType::FixedSizeBinary(16), false),
+        Field::new("total", DataType::Float64, false),
+        Field::new("num_items", DataType::UInt64, false),
+        Field::new("channel", DataType::Utf8View, false),
+        Field::new("hour_of_day", DataType::Float64, false),
+    ])
+}
+
+/// Fractional hour of the day, e.g. `12.5` at half past twelve.
+pub(crate) fn hour_of_day(time: DateTime<Utc>) -> f64 {
+    time.hour() as f64 + time.minute() as f64 / 60.0
+}
+
+fn parse_or_null(
+    df: &DataFrame,
+    name: &str,
+    sql: &Option<String>,
+    data_type: DataType,
+) -> Result<Expr> {
+    let Some(sql) = sql else {
+        return Ok(lit(ScalarValue::try_from(&data_type)?));
+    };
+    let expr = df
+        .parse_sql_expr(sql)
+        .map_err(|e| Error::invalid_data(format!("invalid '{name}' hook '{sql}': {e}")))?;
+    Ok(cast(expr, data_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tip_amounts() -> Result<()> {
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .build()
+            .await?;
+        let orders = vec![
+            (PersonId::new(), 20.0, 2, OrderChannel::App),
+            (PersonId::new(), 50.0, 4, OrderChannel::Web),
+        ];
+
+        let hooks = BehaviorHooks::default();
+        assert_eq!(
+            hooks.tip_amounts(&ctx, Utc::now(), &orders).await?,
+            vec![None, None]
+        );
+
+        let hooks = hooks.with_tip_amount("CASE WHEN channel = 'app' THEN total * 0.1 END");
+        assert_eq!(
+            hooks.tip_amounts(&ctx, Utc::now(), &orders).await?,
+            vec![Some(2.0), None]
+        );
+
+        let hooks = hooks.with_tip_amount("no_such_column * 2");
+        assert!(hooks.tip_amounts(&ctx, Utc::now(), &orders).await.is_err());
+
+        Ok(())
+    }
+}
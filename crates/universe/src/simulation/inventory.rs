@@ -0,0 +1,825 @@
+//! Ingredient stock of the kitchens at each site.
+//!
+//! Every site keeps all ingredients of the menu in stock. Preparing an order line
+//! consumes the quantities of the ingredients listed for its menu item, and once a
+//! day at [`InventoryConfig::restock_hour`] the stock of every ingredient is
+//! replenished up to its par level. The par level covers
+//! [`InventoryConfig::par_portions`] of the largest portion of the ingredient used by
+//! any menu item. Quantities which cannot be served from the stock on hand are
+//! reported as unmet, without holding up the order.
+//!
+//! Without [`InventoryConfig::procurement`], stock is replenished instantly. With it,
+//! every site instead places a supplier order for the quantities missing to the par
+//! levels, net of what is still on order. The order is expected after the lead time,
+//! may arrive late, and single ingredients may be delivered short of the ordered
+//! quantity. Orders, delays and deliveries are reported as `ProcurementUpdated`
+//! events, one per ingredient of a supplier order.
+//!
+//! With [`InventoryConfig::waste`], stock keeps for the shelf life of its ingredient
+//! after it was received and is consumed oldest first. Stock passing its shelf life
+//! expires, and a share of the stock on hand is discarded at the end of every day,
+//! e.g. prepped ingredients left unused. Both are reported as `IngredientWasted`
+//! events and rolled up per day in the `ingredient_waste` results table.
+//!
+//! The stock movements of each simulated day are written to the `ingredient_inventory`
+//! results table along with calendar features of the day, as a data feed for demand
+//! forecasting whose generating process is fully known. Quantities are parsed from the
+//! menu, e.g. `130g` or `2 slices`, and ingredients used in different units are tracked
+//! separately per unit. Stock is not part of the snapshots, so every run starts with
+//! all ingredients at their par level.
+
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, DurationRound as _, TimeDelta, Timelike as _, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::builders::{IngredientDay, IngredientWaste, InventoryBuffer, WasteBuffer};
+use crate::idents::{MenuItemId, OrderId, OrderLineId, SiteId, SupplierOrderId};
+use crate::state::{OrderLineStatus, State};
+use crate::{
+    EntityView as _, Error, EventPayload, IngredientQuantity, ProcurementActivity, Result,
+    WasteReason,
+};
+
+/// Par levels and replenishment of ingredient stock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InventoryConfig {
+    /// Portions of each ingredient in stock after replenishing
+    pub par_portions: f64,
+
+    /// Hour of the day (UTC) at which the stock is replenished
+    pub restock_hour: u32,
+
+    /// Supplier orders replenishing the stock, replenished instantly if not set
+    pub procurement: Option<ProcurementConfig>,
+
+    /// Shelf life and discarding of ingredient stock, no stock is wasted if not set
+    pub waste: Option<WasteConfig>,
+}
+
+impl Default for InventoryConfig {
+    fn default() -> Self {
+        Self {
+            par_portions: 150.0,
+            restock_hour: 6,
+            procurement: None,
+            waste: None,
+        }
+    }
+}
+
+/// Lead times and reliability of the suppliers of ingredients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcurementConfig {
+    /// Hours from placing a supplier order until it is expected at the site
+    pub lead_time_hours: f64,
+
+    /// Probability that a supplier order arrives late
+    pub delay_probability: f64,
+
+    /// Hours by which late supplier orders arrive after they were expected
+    pub delay_hours: f64,
+
+    /// Probability that an ingredient is delivered short of the ordered quantity
+    pub shortfall_probability: f64,
+}
+
+impl Default for ProcurementConfig {
+    fn default() -> Self {
+        Self {
+            lead_time_hours: 20.0,
+            delay_probability: 0.1,
+            delay_hours: 4.0,
+            shortfall_probability: 0.05,
+        }
+    }
+}
+
+impl ProcurementConfig {
+    fn validate(&self) -> Result<()> {
+        if ![self.lead_time_hours, self.delay_hours]
+            .iter()
+            .all(|hours| hours.is_finite() && *hours >= 0.0)
+        {
+            return Err(Error::invalid_data(
+                "lead time and delay of supplier orders must not be negative",
+            ));
+        }
+        for (name, probability) in [
+            ("delay", self.delay_probability),
+            ("shortfall", self.shortfall_probability),
+        ] {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(Error::invalid_data(format!(
+                    "{name} probability {probability} outside of [0, 1]"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shelf life of ingredients and the stock discarded at the end of the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasteConfig {
+    /// Days ingredient stock keeps after it was received
+    pub shelf_life_days: f64,
+
+    /// Shelf life of single ingredients in days, keyed by ingredient reference
+    pub ingredient_shelf_life_days: HashMap<String, f64>,
+
+    /// Share of the stock on hand discarded at the end of every day
+    pub discard_share: f64,
+}
+
+impl Default for WasteConfig {
+    fn default() -> Self {
+        Self {
+            shelf_life_days: 4.0,
+            ingredient_shelf_life_days: HashMap::new(),
+            discard_share: 0.02,
+        }
+    }
+}
+
+impl WasteConfig {
+    fn validate(&self) -> Result<()> {
+        for (ingredient, days) in std::iter::once(("default", &self.shelf_life_days)).chain(
+            self.ingredient_shelf_life_days
+                .iter()
+                .map(|(k, v)| (k.as_str(), v)),
+        ) {
+            if !(days.is_finite() && *days > 0.0) {
+                return Err(Error::invalid_data(format!(
+                    "shelf life of {ingredient} must be a positive number of days"
+                )));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.discard_share) {
+            return Err(Error::invalid_data(format!(
+                "discard share {} outside of [0, 1]",
+                self.discard_share
+            )));
+        }
+        Ok(())
+    }
+
+    /// Time at which stock of `ingredient` received at `received_at` expires.
+    fn expires_at(&self, ingredient: &str, received_at: DateTime<Utc>) -> DateTime<Utc> {
+        let days = self
+            .ingredient_shelf_life_days
+            .get(ingredient)
+            .copied()
+            .unwrap_or(self.shelf_life_days);
+        received_at + hours(days * 24.0)
+    }
+}
+
+fn hours(hours: f64) -> TimeDelta {
+    TimeDelta::milliseconds((hours * 3_600_000.0) as i64)
+}
+
+impl InventoryConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(self.par_portions.is_finite() && self.par_portions > 0.0) {
+            return Err(Error::invalid_data(
+                "par portions must be a positive number",
+            ));
+        }
+        if self.restock_hour > 23 {
+            return Err(Error::invalid_data(format!(
+                "restock hour {} outside of [0, 23]",
+                self.restock_hour
+            )));
+        }
+        if let Some(procurement) = &self.procurement {
+            procurement.validate()?;
+        }
+        if let Some(waste) = &self.waste {
+            waste.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// Ingredient reference and the unit it is measured in.
+type IngredientKey = (String, String);
+
+/// Stock of an ingredient at a site and its movements during the current day.
+#[derive(Debug, Clone)]
+struct StockLevel {
+    par: f64,
+    on_hand: f64,
+    /// Quantity ordered from the supplier but not delivered yet
+    on_order: f64,
+    /// Quantities on hand and the time they expire, oldest first
+    lots: VecDeque<(Option<DateTime<Utc>>, f64)>,
+    opening: f64,
+    received: f64,
+    consumed: f64,
+    unmet: f64,
+    expired: f64,
+    discarded: f64,
+}
+
+impl StockLevel {
+    fn new(par: f64, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            par,
+            on_hand: par,
+            on_order: 0.0,
+            lots: VecDeque::from([(expires_at, par)]),
+            opening: par,
+            received: 0.0,
+            consumed: 0.0,
+            unmet: 0.0,
+            expired: 0.0,
+            discarded: 0.0,
+        }
+    }
+
+    fn consume(&mut self, quantity: f64) {
+        let served = quantity.min(self.on_hand);
+        self.take(served);
+        self.consumed += served;
+        self.unmet += quantity - served;
+    }
+
+    fn replenish(&mut self, expires_at: Option<DateTime<Utc>>) {
+        self.receive((self.par - self.on_hand).max(0.0), expires_at);
+    }
+
+    fn receive(&mut self, quantity: f64, expires_at: Option<DateTime<Utc>>) {
+        if quantity > 0.0 {
+            self.lots.push_back((expires_at, quantity));
+        }
+        self.on_hand += quantity;
+        self.received += quantity;
+    }
+
+    /// Remove `quantity` from the stock on hand, oldest lots first.
+    fn take(&mut self, quantity: f64) {
+        self.on_hand = (self.on_hand - quantity).max(0.0);
+        let mut remaining = quantity;
+        while remaining > 0.0
+            && let Some((_, lot)) = self.lots.front_mut()
+        {
+            if *lot > remaining {
+                *lot -= remaining;
+                break;
+            }
+            remaining -= *lot;
+            self.lots.pop_front();
+        }
+    }
+
+    /// Remove the lots expired by `now`, returning the expired quantity.
+    fn expire(&mut self, now: DateTime<Utc>) -> f64 {
+        let mut quantity = 0.0;
+        while let Some((Some(expires_at), lot)) = self.lots.front()
+            && *expires_at <= now
+        {
+            quantity += lot;
+            self.lots.pop_front();
+        }
+        self.on_hand = (self.on_hand - quantity).max(0.0);
+        self.expired += quantity;
+        quantity
+    }
+
+    /// Discard `share` of the stock on hand, returning the discarded quantity.
+    fn discard(&mut self, share: f64) -> f64 {
+        let quantity = self.on_hand * share;
+        self.take(quantity);
+        self.discarded += quantity;
+        quantity
+    }
+
+    /// Start rolling up the movements of a new day.
+    fn start_day(&mut self) {
+        self.opening = self.on_hand;
+        self.received = 0.0;
+        self.consumed = 0.0;
+        self.unmet = 0.0;
+        self.expired = 0.0;
+        self.discarded = 0.0;
+    }
+
+    /// Quantity to order for the stock to reach its par level.
+    fn shortage(&self) -> f64 {
+        (self.par - self.on_hand - self.on_order).max(0.0)
+    }
+}
+
+/// Ingredients ordered by a site from its supplier.
+#[derive(Debug, Clone)]
+struct SupplierOrder {
+    id: SupplierOrderId,
+    site_id: SiteId,
+    lines: Vec<(IngredientKey, f64)>,
+    expected_at: DateTime<Utc>,
+    /// Whether the order was already reported late
+    delayed: bool,
+}
+
+/// Tracks the ingredient stock of all sites and rolls up its daily movements.
+pub(crate) struct IngredientInventory {
+    config: InventoryConfig,
+    /// Ingredients and quantities of each menu item
+    recipes: HashMap<MenuItemId, Vec<(IngredientKey, f64)>>,
+    stock: HashMap<(SiteId, IngredientKey), StockLevel>,
+    /// The day currently rolled up
+    day: Option<DateTime<Utc>>,
+    /// Day on which the stock was last replenished
+    restocked: Option<DateTime<Utc>>,
+    /// Supplier orders not delivered yet
+    supplier_orders: Vec<SupplierOrder>,
+    /// Completed days waiting to be written
+    buffer: InventoryBuffer,
+    /// Wasted stock of completed days waiting to be written
+    waste_buffer: WasteBuffer,
+}
+
+impl IngredientInventory {
+    /// Stock all ingredients of the menu at every site at their par level.
+    pub(crate) fn try_new(config: InventoryConfig, state: &State) -> Result<Self> {
+        let objects = state.objects();
+        let mut recipes = HashMap::new();
+        let mut portions: HashMap<IngredientKey, f64> = HashMap::new();
+        for item_id in objects.menu_item_ids() {
+            let recipe = recipe(&objects.menu_item(item_id)?.ingredients);
+            for (key, quantity) in &recipe {
+                let portion = portions.entry(key.clone()).or_default();
+                *portion = portion.max(*quantity);
+            }
+            recipes.insert(*item_id, recipe);
+        }
+        let now = state.current_time();
+        let mut stock = HashMap::new();
+        for site in objects.sites()? {
+            for (key, portion) in &portions {
+                let par = portion * config.par_portions;
+                let expires_at = config.waste.as_ref().map(|w| w.expires_at(&key.0, now));
+                stock.insert((site.id(), key.clone()), StockLevel::new(par, expires_at));
+            }
+        }
+        Ok(Self {
+            config,
+            recipes,
+            stock,
+            day: None,
+            restocked: None,
+            supplier_orders: Vec::new(),
+            buffer: InventoryBuffer::new(),
+            waste_buffer: WasteBuffer::new(),
+        })
+    }
+
+    /// Replenish the stock in the step at `now`, returning the procurement and waste
+    /// events.
+    ///
+    /// The previous day is completed once a step starts on a new day, after discarding
+    /// the share of its remaining stock. Expired stock is removed, supplier orders due
+    /// by `now` are delivered, and the stock is replenished or reordered in the first
+    /// step at or after the restock hour of a day.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<EventPayload>> {
+        let day = now
+            .duration_trunc(TimeDelta::days(1))
+            .map_err(|e| Error::invalid_data(format!("invalid step time: {e}")))?;
+        let mut events = Vec::new();
+        let waste = self.config.waste.clone();
+        if self.day.is_some_and(|current| current != day) {
+            if let Some(waste) = &waste {
+                events.extend(self.waste(WasteReason::Discarded, |level| {
+                    level.discard(waste.discard_share)
+                }));
+            }
+            self.finish_day()?;
+        }
+        self.day = Some(day);
+
+        if waste.is_some() {
+            events.extend(self.waste(WasteReason::Expired, |level| level.expire(now)));
+        }
+        let procurement = self.config.procurement.clone();
+        if let Some(procurement) = &procurement {
+            events.extend(self.deliver(now, procurement, rng));
+        }
+        if now.hour() >= self.config.restock_hour && self.restocked != Some(day) {
+            match &procurement {
+                Some(procurement) => events.extend(self.place_orders(now, procurement, rng)),
+                None => {
+                    for ((_, (ingredient, _)), level) in self.stock.iter_mut() {
+                        let expires_at = waste.as_ref().map(|w| w.expires_at(ingredient, now));
+                        level.replenish(expires_at);
+                    }
+                }
+            }
+            self.restocked = Some(day);
+        }
+        Ok(events)
+    }
+
+    /// Waste stock of all ingredients, returning an event for every wasted quantity.
+    fn waste(
+        &mut self,
+        reason: WasteReason,
+        mut waste: impl FnMut(&mut StockLevel) -> f64,
+    ) -> Vec<EventPayload> {
+        let mut wasted: Vec<_> = self
+            .stock
+            .iter_mut()
+            .filter_map(|((site_id, key), level)| {
+                let quantity = waste(level);
+                (quantity > 0.0).then(|| (*site_id, key.clone(), quantity))
+            })
+            .collect();
+        wasted.sort_by_key(|(site_id, key, _)| (*AsRef::<Uuid>::as_ref(site_id), key.clone()));
+        wasted
+            .into_iter()
+            .map(|(site_id, key, quantity)| {
+                EventPayload::ingredient_wasted(site_id, key, reason, quantity)
+            })
+            .collect()
+    }
+
+    /// Order the shortage to the par levels of all ingredients from the suppliers.
+    fn place_orders(
+        &mut self,
+        now: DateTime<Utc>,
+        procurement: &ProcurementConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<EventPayload> {
+        let mut shortages: BTreeMap<SiteId, Vec<(IngredientKey, f64)>> = BTreeMap::new();
+        for ((site_id, key), level) in self.stock.iter_mut() {
+            let quantity = level.shortage();
+            if quantity > 0.0 {
+                level.on_order += quantity;
+                shortages
+                    .entry(*site_id)
+                    .or_default()
+                    .push((key.clone(), quantity));
+            }
+        }
+
+        let expected_at = now + hours(procurement.lead_time_hours);
+        let mut events = Vec::new();
+        for (site_id, mut lines) in shortages {
+            lines.sort_by(|(a, _), (b, _)| a.cmp(b));
+            let order = SupplierOrder {
+                id: SupplierOrderId::from_rng(now, rng),
+                site_id,
+                lines,
+                expected_at,
+                delayed: false,
+            };
+            events.extend(order.events(ProcurementActivity::Ordered));
+            self.supplier_orders.push(order);
+        }
+        events
+    }
+
+    /// Deliver the supplier orders due by `now`, unless they are running late.
+    fn deliver(
+        &mut self,
+        now: DateTime<Utc>,
+        procurement: &ProcurementConfig,
+        rng: &mut impl Rng,
+    ) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        let mut pending = Vec::new();
+        for mut order in std::mem::take(&mut self.supplier_orders) {
+            if order.expected_at > now {
+                pending.push(order);
+                continue;
+            }
+            // orders are late at most once, so every order arrives eventually
+            if !order.delayed && rng.random_bool(procurement.delay_probability) {
+                order.delayed = true;
+                order.expected_at += hours(procurement.delay_hours);
+                events.extend(order.events(ProcurementActivity::Delayed));
+                pending.push(order);
+                continue;
+            }
+            for (key, quantity) in &order.lines {
+                let delivered = if rng.random_bool(procurement.shortfall_probability) {
+                    quantity * rng.random::<f64>()
+                } else {
+                    *quantity
+                };
+                if let Some(level) = self.stock.get_mut(&(order.site_id, key.clone())) {
+                    let expires_at = self
+                        .config
+                        .waste
+                        .as_ref()
+                        .map(|w| w.expires_at(&key.0, now));
+                    level.on_order = (level.on_order - quantity).max(0.0);
+                    level.receive(delivered, expires_at);
+                }
+                events.push(EventPayload::procurement_updated(
+                    order.id,
+                    order.site_id,
+                    key.clone(),
+                    ProcurementActivity::Delivered,
+                    delivered,
+                    order.expected_at,
+                ));
+            }
+        }
+        self.supplier_orders = pending;
+        events
+    }
+
+    /// Account for the order lines prepared in a step.
+    pub(crate) fn record(&mut self, events: &[EventPayload], state: &State) -> Result<()> {
+        for event in events {
+            let EventPayload::OrderLineUpdated(payload) = event else {
+                continue;
+            };
+            if payload.status != OrderLineStatus::Processing {
+                continue;
+            }
+            let Some((site_id, item_id)) = line_item(state, &payload.order_line_id)? else {
+                continue;
+            };
+            if let Entry::Vacant(entry) = self.recipes.entry(item_id) {
+                let item = state.objects().menu_item(&item_id)?;
+                entry.insert(recipe(&item.ingredients));
+            }
+            for (key, quantity) in &self.recipes[&item_id] {
+                // ingredients first used by menu items added during the run start at par
+                self.stock
+                    .entry((site_id, key.clone()))
+                    .or_insert_with(|| {
+                        let expires_at = self
+                            .config
+                            .waste
+                            .as_ref()
+                            .map(|w| w.expires_at(&key.0, state.current_time()));
+                        StockLevel::new(quantity * self.config.par_portions, expires_at)
+                    })
+                    .consume(*quantity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Complete the day currently rolled up, e.g. at the end of a run.
+    pub(crate) fn finish_day(&mut self) -> Result<()> {
+        let Some(day) = self.day.take() else {
+            return Ok(());
+        };
+        let mut rows: Vec<_> = self.stock.iter_mut().collect();
+        rows.sort_by_key(|((site_id, key), _)| (*AsRef::<Uuid>::as_ref(site_id), key.clone()));
+        for ((site_id, (ingredient, unit)), level) in rows {
+            self.buffer.push(&IngredientDay {
+                day,
+                site_id: *site_id,
+                ingredient: ingredient.clone(),
+                unit: unit.clone(),
+                opening_stock: level.opening,
+                received: level.received,
+                consumed: level.consumed,
+                unmet: level.unmet,
+                closing_stock: level.on_hand,
+            })?;
+            if level.expired > 0.0 || level.discarded > 0.0 {
+                self.waste_buffer.push(&IngredientWaste {
+                    day,
+                    site_id: *site_id,
+                    ingredient: ingredient.clone(),
+                    unit: unit.clone(),
+                    expired: level.expired,
+                    discarded: level.discarded,
+                })?;
+            }
+            level.start_day();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+
+    pub(crate) fn has_pending_waste(&self) -> bool {
+        self.waste_buffer.len() > 0
+    }
+
+    pub(crate) fn flush_waste(&mut self) -> Result<RecordBatch> {
+        self.waste_buffer.flush()
+    }
+}
+
+impl SupplierOrder {
+    /// Events reporting `activity` for every ordered ingredient.
+    fn events(&self, activity: ProcurementActivity) -> impl Iterator<Item = EventPayload> + '_ {
+        self.lines.iter().map(move |(key, quantity)| {
+            EventPayload::procurement_updated(
+                self.id,
+                self.site_id,
+                key.clone(),
+                activity,
+                *quantity,
+                self.expected_at,
+            )
+        })
+    }
+}
+
+/// Site and menu item of an order line.
+fn line_item(state: &State, order_line_id: &OrderLineId) -> Result<Option<(SiteId, MenuItemId)>> {
+    let Some(line) = state.orders().order_line(order_line_id) else {
+        return Ok(None);
+    };
+    let order_id = OrderId::try_from(line.order_id())?;
+    let Some(order) = state.orders().order(&order_id) else {
+        return Ok(None);
+    };
+    Ok(Some((
+        SiteId::try_from(order.site_id())?,
+        MenuItemId::try_from(line.menu_item_id())?,
+    )))
+}
+
+/// Parsed ingredient quantities of a menu item, skipping quantities without a number.
+fn recipe(ingredients: &[IngredientQuantity]) -> Vec<(IngredientKey, f64)> {
+    ingredients
+        .iter()
+        .filter_map(|ingredient| {
+            let (quantity, unit) = parse_quantity(&ingredient.quantity)?;
+            Some(((ingredient.ingredient_ref.clone(), unit), quantity))
+        })
+        .collect()
+}
+
+/// Amount and singular unit of a quantity such as `130g`, `2 slices` or `1`.
+///
+/// Quantities without a unit are counted in pieces.
+fn parse_quantity(quantity: &str) -> Option<(f64, String)> {
+    let quantity = quantity.trim();
+    let split = quantity
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(quantity.len());
+    let (amount, unit) = quantity.split_at(split);
+    let amount = amount.parse::<f64>().ok()?;
+    let unit = match unit.trim() {
+        "" => "piece",
+        "leaves" => "leaf",
+        unit => unit
+            .strip_suffix('s')
+            .filter(|_| unit.len() > 2)
+            .unwrap_or(unit),
+    };
+    Some((amount, unit.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("130g"), Some((130.0, "g".to_string())));
+        assert_eq!(parse_quantity("2 slices"), Some((2.0, "slice".to_string())));
+        assert_eq!(parse_quantity("1 clove"), Some((1.0, "clove".to_string())));
+        assert_eq!(parse_quantity("3 leaves"), Some((3.0, "leaf".to_string())));
+        assert_eq!(parse_quantity("1.5 tbsp"), Some((1.5, "tbsp".to_string())));
+        assert_eq!(parse_quantity("2"), Some((2.0, "piece".to_string())));
+        assert_eq!(parse_quantity("a pinch"), None);
+    }
+
+    #[test]
+    fn test_stock_level() {
+        let mut level = StockLevel::new(100.0, None);
+        level.consume(70.0);
+        level.consume(50.0);
+        assert_eq!(level.on_hand, 0.0);
+        assert_eq!(level.consumed, 100.0);
+        assert_eq!(level.unmet, 20.0);
+        level.replenish(None);
+        assert_eq!(level.on_hand, 100.0);
+        assert_eq!(level.received, 100.0);
+
+        level.consume(60.0);
+        level.on_order = 40.0;
+        assert_eq!(level.shortage(), 20.0);
+    }
+
+    #[test]
+    fn test_waste() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T06:00:00Z")
+            .unwrap()
+            .to_utc();
+        let mut level = StockLevel::new(100.0, Some(now + hours(24.0)));
+        level.receive(50.0, Some(now + hours(48.0)));
+
+        // the oldest stock is consumed first
+        level.consume(60.0);
+        assert_eq!(level.expire(now + hours(24.0)), 40.0);
+        assert_eq!(level.on_hand, 50.0);
+
+        assert_eq!(level.discard(0.1), 5.0);
+        assert_eq!(level.expire(now + hours(48.0)), 45.0);
+        assert_eq!(level.on_hand, 0.0);
+        assert_eq!(level.expired, 85.0);
+        assert_eq!(level.discarded, 5.0);
+        assert!(level.lots.is_empty());
+    }
+
+    #[test]
+    fn test_supplier_orders() {
+        use rand::SeedableRng as _;
+
+        let site_id = SiteId::from_name("amsterdam");
+        let key = ("ingredients/rice".to_string(), "g".to_string());
+        let mut level = StockLevel::new(100.0, None);
+        level.consume(80.0);
+        let mut inventory = IngredientInventory {
+            config: InventoryConfig::default(),
+            recipes: HashMap::new(),
+            stock: HashMap::from([((site_id, key.clone()), level)]),
+            day: None,
+            restocked: None,
+            supplier_orders: Vec::new(),
+            buffer: InventoryBuffer::new(),
+            waste_buffer: WasteBuffer::new(),
+        };
+        let procurement = ProcurementConfig {
+            lead_time_hours: 2.0,
+            delay_probability: 0.0,
+            shortfall_probability: 0.0,
+            ..Default::default()
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let now = DateTime::parse_from_rfc3339("2025-01-01T06:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        let ordered = inventory.place_orders(now, &procurement, &mut rng);
+        assert_eq!(ordered.len(), 1);
+        // quantities on order are not ordered again
+        assert!(
+            inventory
+                .place_orders(now, &procurement, &mut rng)
+                .is_empty()
+        );
+
+        assert!(inventory.deliver(now, &procurement, &mut rng).is_empty());
+        let delivered = inventory.deliver(now + hours(2.0), &procurement, &mut rng);
+        assert_eq!(delivered.len(), 1);
+        let level = &inventory.stock[&(site_id, key)];
+        assert_eq!(level.on_hand, 100.0);
+        assert_eq!(level.on_order, 0.0);
+        assert!(inventory.supplier_orders.is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(InventoryConfig::default().validate().is_ok());
+        for config in [
+            InventoryConfig {
+                par_portions: 0.0,
+                ..Default::default()
+            },
+            InventoryConfig {
+                restock_hour: 24,
+                ..Default::default()
+            },
+            InventoryConfig {
+                procurement: Some(ProcurementConfig {
+                    delay_probability: 1.5,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            InventoryConfig {
+                waste: Some(WasteConfig {
+                    ingredient_shelf_life_days: HashMap::from([(
+                        "ingredients/milk".to_string(),
+                        0.0,
+                    )]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Invoices for delivered orders.
+//!
+//! Every delivered order is invoiced by the site it was prepared at. Invoice numbers
+//! are sequential per site and continue where earlier runs of the same simulation
+//! left off. Order totals are gross amounts including VAT, which is broken out at
+//! the configured rate. Tips are passed on to couriers and listed without tax.
+//!
+//! Invoices are issued in the currency the order was charged in. Their total is also
+//! reported in the base currency of the configured [`ExchangeRates`], so finance
+//! tables spanning several countries can be aggregated without mixing units.
+//!
+//! Invoices are buffered and written to the `invoices` results table alongside the
+//! simulation metrics.
+
+use std::collections::HashMap;
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::builders::{Invoice, InvoiceBuffer};
+use crate::idents::{OrderId, PersonId, SiteId, uuid_v7_from_rng};
+use crate::state::{OrderStatus, State};
+use crate::{Error, EventPayload, ExchangeRates, Money, Result};
+
+/// Tax settings of generated invoices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InvoiceConfig {
+    /// VAT rate included in order totals, e.g. 0.2 for 20%
+    pub tax_rate: f64,
+}
+
+impl InvoiceConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.tax_rate) {
+            return Err(Error::invalid_data(format!(
+                "invoice tax rate {} outside of [0, 1]",
+                self.tax_rate
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for InvoiceConfig {
+    fn default() -> Self {
+        Self { tax_rate: 0.2 }
+    }
+}
+
+/// Issues invoices for delivered orders.
+pub(crate) struct Invoicer {
+    config: InvoiceConfig,
+    rates: ExchangeRates,
+    /// Number of the last invoice issued by each site
+    last_numbers: HashMap<SiteId, u64>,
+    buffer: InvoiceBuffer,
+    /// Draws the ids of invoices, so seeded runs issue the same ids
+    rng: StdRng,
+}
+
+impl Invoicer {
+    pub(crate) fn new(
+        config: InvoiceConfig,
+        rates: ExchangeRates,
+        last_numbers: HashMap<SiteId, u64>,
+        rng: StdRng,
+    ) -> Self {
+        Self {
+            config,
+            rates,
+            last_numbers,
+            buffer: InvoiceBuffer::new(),
+            rng,
+        }
+    }
+
+    /// Issue invoices for all orders delivered in `events`.
+    ///
+    /// Orders without a recorded total are skipped, they were created before totals
+    /// were tracked on orders.
+    pub(crate) fn record(&mut self, events: &[EventPayload], state: &State) -> Result<()> {
+        for event in events {
+            let EventPayload::OrderUpdated(payload) = event else {
+                continue;
+            };
+            if payload.status != OrderStatus::Delivered {
+                continue;
+            }
+            let Some(order) = state.orders().order(&payload.order_id) else {
+                continue;
+            };
+            let Some(total) = order.total() else {
+                continue;
+            };
+            let site_id = order.site_id().try_into()?;
+            let invoice = self.issue(
+                site_id,
+                *order.id(),
+                order.customer_person_id().try_into()?,
+                state.current_time(),
+                Money::new(total, self.rates.resolve(order.currency())?),
+                order.tip().unwrap_or_default(),
+            )?;
+            self.buffer.push(&invoice)?;
+        }
+        Ok(())
+    }
+
+    fn issue(
+        &mut self,
+        site_id: SiteId,
+        order_id: OrderId,
+        customer_id: PersonId,
+        issued_at: DateTime<Utc>,
+        gross: Money,
+        tip: f64,
+    ) -> Result<Invoice> {
+        let charged = Money::new(gross.amount() + tip, gross.currency());
+        let base_total = self
+            .rates
+            .convert(charged, self.rates.base())?
+            .round_cents();
+        let number = self.last_numbers.entry(site_id).or_default();
+        *number += 1;
+        let gross_amount = gross.amount();
+        let (net_amount, tax_amount) = tax_breakdown(gross_amount, self.config.tax_rate);
+        Ok(Invoice {
+            id: uuid_v7_from_rng(issued_at, &mut self.rng),
+            site_id,
+            invoice_number: *number,
+            order_id,
+            customer_id,
+            issued_at,
+            currency: gross.currency(),
+            net_amount,
+            tax_rate: self.config.tax_rate,
+            tax_amount,
+            gross_amount,
+            tip,
+            base_total,
+        })
+    }
+
+    /// Whether invoices are waiting to be written.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.buffer.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.buffer.flush()
+    }
+}
+
+/// Split a gross amount into its net amount and the included tax, rounded to cents.
+fn tax_breakdown(gross_amount: f64, tax_rate: f64) -> (f64, f64) {
+    let net_amount = (gross_amount / (1.0 + tax_rate.max(0.0)) * 100.0).round() / 100.0;
+    let tax_amount = ((gross_amount - net_amount) * 100.0).round() / 100.0;
+    (net_amount, tax_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use super::*;
+    use crate::Currency;
+
+    #[test]
+    fn test_tax_breakdown() {
+        assert_eq!(tax_breakdown(12.0, 0.2), (10.0, 2.0));
+        assert_eq!(tax_breakdown(10.0, 0.0), (10.0, 0.0));
+        // net and tax always add up to the gross amount
+        let (net, tax) = tax_breakdown(19.99, 0.19);
+        assert_eq!(((net + tax) * 100.0).round() / 100.0, 19.99);
+    }
+
+    #[test]
+    fn test_invoice_numbers() -> Result<()> {
+        let site = SiteId::from_name("site");
+        let other = SiteId::from_name("other");
+        let eur = "EUR".parse()?;
+        let mut invoicer = Invoicer::new(
+            InvoiceConfig::default(),
+            ExchangeRates::default().with_rate(eur, 1.1),
+            HashMap::from([(other, 41)]),
+            StdRng::seed_from_u64(42),
+        );
+
+        let mut issue = |site_id| {
+            invoicer.issue(
+                site_id,
+                OrderId::new(),
+                PersonId::new(),
+                Utc::now(),
+                Money::new(12.0, eur),
+                2.0,
+            )
+        };
+        assert_eq!(issue(site)?.invoice_number, 1);
+        assert_eq!(issue(site)?.invoice_number, 2);
+
+        // numbering continues for sites that issued invoices before
+        let invoice = issue(other)?;
+        assert_eq!(invoice.invoice_number, 42);
+        assert_eq!(invoice.currency, eur);
+        assert_eq!(invoice.net_amount, 10.0);
+        assert_eq!(invoice.tax_amount, 2.0);
+        assert_eq!(invoice.total(), 14.0);
+
+        // totals are also reported in the base currency
+        assert_eq!(invoice.base_total, Money::new(15.4, Currency::USD));
+        Ok(())
+    }
+}
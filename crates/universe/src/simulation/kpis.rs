@@ -0,0 +1,178 @@
+//! Domain KPIs exported as OpenTelemetry metrics.
+//!
+//! KPIs are recorded once per step while the OpenTelemetry context of the step span
+//! is attached, so exemplars sampled by the metrics SDK link data points back to the
+//! trace of the step that produced them.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Gauge, Histogram};
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+use uuid::Uuid;
+
+use crate::idents::OrderId;
+use crate::state::{OrderStatus, PersonStatusFlag, State};
+
+use super::EventPayload;
+
+/// KPIs observed during a single simulation step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepKpis {
+    pub orders_created: u64,
+    /// Orders created per simulated minute.
+    pub orders_per_minute: f64,
+    /// Simulated seconds from submission to delivery for orders delivered in this step.
+    pub delivery_times_s: Vec<f64>,
+    /// Couriers currently out on a delivery.
+    pub active_couriers: u64,
+}
+
+impl StepKpis {
+    /// 90th percentile of the delivery times in this step.
+    pub fn p90_delivery_time_s(&self) -> Option<f64> {
+        if self.delivery_times_s.is_empty() {
+            return None;
+        }
+        let mut times = self.delivery_times_s.clone();
+        times.sort_by(f64::total_cmp);
+        let rank = ((times.len() as f64) * 0.9).ceil() as usize;
+        Some(times[rank.saturating_sub(1)])
+    }
+}
+
+pub(crate) struct KpiRecorder {
+    orders_created: Counter<u64>,
+    orders_per_minute: Gauge<f64>,
+    delivery_time: Histogram<f64>,
+    p90_delivery_time: Gauge<f64>,
+    active_couriers: Gauge<u64>,
+    attributes: Vec<KeyValue>,
+
+    /// Submission times of orders that are not yet delivered.
+    submitted_at: HashMap<OrderId, DateTime<Utc>>,
+}
+
+impl KpiRecorder {
+    pub(crate) fn new(simulation_id: &Uuid) -> Self {
+        let meter = opentelemetry::global::meter("caspers_universe");
+        Self {
+            orders_created: meter
+                .u64_counter("caspers.orders.created")
+                .with_description("Orders created by customers")
+                .with_unit("{order}")
+                .build(),
+            orders_per_minute: meter
+                .f64_gauge("caspers.orders.rate")
+                .with_description("Orders created per simulated minute")
+                .with_unit("{order}/min")
+                .build(),
+            delivery_time: meter
+                .f64_histogram("caspers.delivery.duration")
+                .with_description("Simulated time from order submission to delivery")
+                .with_unit("s")
+                .build(),
+            p90_delivery_time: meter
+                .f64_gauge("caspers.delivery.duration.p90")
+                .with_description("90th percentile delivery time of orders delivered in a step")
+                .with_unit("s")
+                .build(),
+            active_couriers: meter
+                .u64_gauge("caspers.couriers.active")
+                .with_description("Couriers currently out on a delivery")
+                .with_unit("{courier}")
+                .build(),
+            attributes: vec![KeyValue::new(
+                "caspers.simulation_id",
+                simulation_id.to_string(),
+            )],
+            submitted_at: HashMap::new(),
+        }
+    }
+
+    /// Compute the KPIs for a step without recording them.
+    pub(crate) fn observe(&mut self, events: &[EventPayload], state: &State) -> StepKpis {
+        let current_time = state.current_time();
+        let mut kpis = StepKpis::default();
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(_) => kpis.orders_created += 1,
+                EventPayload::OrderUpdated(payload) => match payload.status {
+                    OrderStatus::Submitted => {
+                        self.submitted_at.insert(payload.order_id, current_time);
+                    }
+                    OrderStatus::Delivered => {
+                        if let Some(submitted_at) = self.submitted_at.remove(&payload.order_id) {
+                            let duration = current_time - submitted_at;
+                            kpis.delivery_times_s
+                                .push(duration.num_milliseconds() as f64 / 1000.0);
+                        }
+                    }
+                    OrderStatus::Cancelled | OrderStatus::Failed => {
+                        self.submitted_at.remove(&payload.order_id);
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+
+        let step_minutes = state.time_step().as_secs_f64() / 60.0;
+        if step_minutes > 0.0 {
+            kpis.orders_per_minute = kpis.orders_created as f64 / step_minutes;
+        }
+        kpis.active_couriers = state.population().count_with_status(&[
+            PersonStatusFlag::Delivering,
+            PersonStatusFlag::WaitingForCustomer,
+        ]) as u64;
+
+        kpis
+    }
+
+    /// Compute and record the KPIs for a step within the context of the current span.
+    pub(crate) fn record(&mut self, events: &[EventPayload], state: &State) -> StepKpis {
+        let kpis = self.observe(events, state);
+
+        // attach the step span so exemplars reference its trace
+        let _guard = tracing::Span::current().context().attach();
+
+        self.orders_created
+            .add(kpis.orders_created, &self.attributes);
+        self.orders_per_minute
+            .record(kpis.orders_per_minute, &self.attributes);
+        for duration in &kpis.delivery_times_s {
+            self.delivery_time.record(*duration, &self.attributes);
+        }
+        if let Some(p90) = kpis.p90_delivery_time_s() {
+            self.p90_delivery_time.record(p90, &self.attributes);
+        }
+        self.active_couriers
+            .record(kpis.active_couriers, &self.attributes);
+
+        kpis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p90_delivery_time() {
+        let kpis = StepKpis::default();
+        assert_eq!(kpis.p90_delivery_time_s(), None);
+
+        let kpis = StepKpis {
+            delivery_times_s: (1..=10).rev().map(|t| t as f64 * 60.0).collect(),
+            ..Default::default()
+        };
+        assert_eq!(kpis.p90_delivery_time_s(), Some(540.0));
+
+        let kpis = StepKpis {
+            delivery_times_s: vec![120.0],
+            ..Default::default()
+        };
+        assert_eq!(kpis.p90_delivery_time_s(), Some(120.0));
+    }
+}
@@ -0,0 +1,252 @@
+//! Customer lifecycle events.
+//!
+//! CRM-style funnels need to know when customers joined, converted and dropped off,
+//! which is tedious to derive from the raw order events. The simulation therefore
+//! emits [`PersonLifecyclePayload`](crate::PersonLifecyclePayload) events when
+//!
+//! - a customer is first part of a run, i.e. signs up,
+//! - a customer places their first order, and
+//! - a customer who ordered before did not order for longer than the churn period.
+//!
+//! Customers who order again after churning can churn again later on. The lifecycle
+//! of every customer is restored from the events recorded in earlier runs of the
+//! simulation, so sampling lifecycle or order events with an
+//! [`EventFilter`](super::EventFilter) may repeat stages in later runs.
+
+use arrow::array::cast::AsArray as _;
+use chrono::{DateTime, Duration, Utc};
+use datafusion::prelude::{col, lit};
+use indexmap::IndexMap;
+
+use crate::context::SimulationContext;
+use crate::idents::PersonId;
+use crate::{Error, EventPayload, LifecycleStage, Result};
+
+/// Customers who did not order for this long are marked as churned.
+pub const DEFAULT_CHURN_AFTER: Duration = Duration::days(30);
+
+static LIFECYCLE_EVENT_TYPES: [&str; 2] =
+    ["io.caspers.persons.lifecycle", "io.caspers.orders.created"];
+
+#[derive(Debug, Default)]
+struct CustomerRecord {
+    signed_up: bool,
+    last_order: Option<DateTime<Utc>>,
+    churned: bool,
+}
+
+/// Tracks the lifecycle stages reached by customers.
+pub(crate) struct CustomerLifecycle {
+    churn_after: Option<Duration>,
+    customers: IndexMap<PersonId, CustomerRecord>,
+}
+
+impl CustomerLifecycle {
+    /// Track the lifecycle of `customers`, who sign up in the next step unless
+    /// recorded otherwise.
+    ///
+    /// Customers are never marked as churned if `churn_after` is `None`.
+    pub(crate) fn new(
+        churn_after: Option<Duration>,
+        customers: impl IntoIterator<Item = PersonId>,
+    ) -> Self {
+        Self {
+            churn_after,
+            customers: customers
+                .into_iter()
+                .map(|id| (id, CustomerRecord::default()))
+                .collect(),
+        }
+    }
+
+    /// Account for an event recorded at `timestamp` in an earlier run.
+    pub(crate) fn record(&mut self, timestamp: DateTime<Utc>, event: &EventPayload) {
+        match event {
+            EventPayload::PersonLifecycle(payload) => {
+                let customer = self.customers.entry(payload.person_id).or_default();
+                match payload.stage {
+                    LifecycleStage::SignedUp => customer.signed_up = true,
+                    LifecycleStage::FirstOrder => {
+                        customer.last_order.get_or_insert(timestamp);
+                    }
+                    LifecycleStage::Churned => customer.churned = true,
+                }
+            }
+            EventPayload::OrderCreated(payload) => {
+                let customer = self.customers.entry(payload.person_id).or_default();
+                customer.last_order = Some(timestamp);
+                customer.churned = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Account for the lifecycle and order events recorded in any run of the simulation up to `end`.
+    pub(crate) async fn record_results(
+        &mut self,
+        ctx: &SimulationContext,
+        end: DateTime<Utc>,
+    ) -> Result<()> {
+        let recorded = ctx
+            .results()
+            .events_between(DateTime::UNIX_EPOCH, end)
+            .await?
+            .filter(col("type").in_list(LIFECYCLE_EVENT_TYPES.map(lit).to_vec(), false))?
+            .select_columns(&["time", "data"])?;
+        for batch in ctx.collect(recorded).await? {
+            let times = batch.column(0).as_string::<i64>();
+            let data = batch.column(1).as_string::<i64>();
+            for (time, data) in times.iter().zip(data.iter()) {
+                let (Some(time), Some(data)) = (time, data) else {
+                    continue;
+                };
+                let timestamp = DateTime::parse_from_rfc3339(time)
+                    .map_err(|e| Error::invalid_data(format!("invalid event time '{time}': {e}")))?
+                    .with_timezone(&Utc);
+                self.record(timestamp, &serde_json::from_str(data)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lifecycle events of a step at `now` that emitted `events`.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+    ) -> Vec<EventPayload> {
+        let mut lifecycle = Vec::new();
+        for (person_id, customer) in self.customers.iter_mut() {
+            if !customer.signed_up {
+                customer.signed_up = true;
+                lifecycle.push(EventPayload::person_lifecycle(
+                    *person_id,
+                    LifecycleStage::SignedUp,
+                    None,
+                ));
+            }
+        }
+
+        for event in events {
+            let EventPayload::OrderCreated(payload) = event else {
+                continue;
+            };
+            let customer = self.customers.entry(payload.person_id).or_default();
+            if customer.last_order.is_none() {
+                lifecycle.push(EventPayload::person_lifecycle(
+                    payload.person_id,
+                    LifecycleStage::FirstOrder,
+                    Some(payload.order_id),
+                ));
+            }
+            customer.last_order = Some(now);
+            customer.churned = false;
+        }
+
+        if let Some(churn_after) = self.churn_after {
+            for (person_id, customer) in self.customers.iter_mut() {
+                if !customer.churned
+                    && customer
+                        .last_order
+                        .is_some_and(|last_order| now - last_order >= churn_after)
+                {
+                    customer.churned = true;
+                    lifecycle.push(EventPayload::person_lifecycle(
+                        *person_id,
+                        LifecycleStage::Churned,
+                        None,
+                    ));
+                }
+            }
+        }
+        lifecycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::idents::OrderId;
+    use crate::test_utils::order_created;
+    use crate::{EventDataBuilder, OrderCreatedPayload};
+
+    use super::*;
+
+    fn created(person_id: PersonId) -> EventPayload {
+        EventPayload::OrderCreated(OrderCreatedPayload {
+            person_id,
+            ..order_created(OrderId::new())
+        })
+    }
+
+    fn stages(events: &[EventPayload]) -> Vec<(PersonId, LifecycleStage)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::PersonLifecycle(p) => Some((p.person_id, p.stage)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_customer_lifecycle() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let customer = PersonId::new();
+        let mut lifecycle = CustomerLifecycle::new(Some(Duration::days(1)), [customer]);
+
+        let events = lifecycle.step(start, &[]);
+        assert_eq!(stages(&events), [(customer, LifecycleStage::SignedUp)]);
+
+        let order = created(customer);
+        let events = lifecycle.step(start + Duration::hours(1), std::slice::from_ref(&order));
+        assert_eq!(stages(&events), [(customer, LifecycleStage::FirstOrder)]);
+        assert!(
+            lifecycle
+                .step(start + Duration::hours(2), &[order])
+                .is_empty()
+        );
+
+        let events = lifecycle.step(start + Duration::hours(26), &[]);
+        assert_eq!(stages(&events), [(customer, LifecycleStage::Churned)]);
+        assert!(lifecycle.step(start + Duration::hours(27), &[]).is_empty());
+
+        // customers ordering again can churn again
+        assert!(
+            lifecycle
+                .step(start + Duration::hours(28), &[created(customer)])
+                .is_empty()
+        );
+        let events = lifecycle.step(start + Duration::hours(52), &[]);
+        assert_eq!(stages(&events), [(customer, LifecycleStage::Churned)]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_lifecycle() -> Result<()> {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .with_simulation_start_time(start)
+            .build()
+            .await?;
+
+        let (regular, new) = (PersonId::new(), PersonId::new());
+        let mut events = EventDataBuilder::new();
+        events.add_payload(
+            start,
+            &EventPayload::person_lifecycle(regular, LifecycleStage::SignedUp, None),
+        )?;
+        events.add_payload(start + Duration::minutes(5), &created(regular))?;
+        ctx.results()
+            .write_events(ctx.ctx().read_batch(events.build()?)?)
+            .await?;
+
+        let mut lifecycle = CustomerLifecycle::new(None, [regular, new]);
+        lifecycle
+            .record_results(&ctx, start + Duration::hours(1))
+            .await?;
+
+        let events = lifecycle.step(start + Duration::hours(1), &[created(regular)]);
+        assert_eq!(stages(&events), [(new, LifecycleStage::SignedUp)]);
+        Ok(())
+    }
+}
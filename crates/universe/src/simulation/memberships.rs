@@ -0,0 +1,235 @@
+//! Delivery memberships held by a share of customers.
+//!
+//! Without a [`MembershipConfig`], customers pay for their orders only. With one, a
+//! share of customers holds a delivery membership, which is billed monthly. Members
+//! have the delivery fee charged to the orders of other customers waived, order more
+//! often and are served in at least the priority tier of the membership. Membership
+//! is derived from the id of the customer, so a customer stays a member across steps
+//! and runs.
+//!
+//! Members are billed in the step the simulation clock passes the start of a month
+//! (UTC), so a month split across runs is billed once. Each fee is reported as a
+//! `membership_billed` event, which the daily summary reports as recurring revenue
+//! next to the revenue of orders.
+
+use chrono::{DateTime, Datelike as _, TimeZone as _, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::PersonId;
+use crate::state::{PersonRole, State};
+use crate::{Currency, Error, EventPayload, PriorityTier, Result};
+
+/// Mixed into the ids of customers, so membership is independent of other traits
+/// derived from the id, e.g. the priority tier.
+const MEMBERSHIP_SALT: u64 = 0x6d65_6d62_6572_7321;
+
+/// Fees and perks of delivery memberships.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MembershipConfig {
+    /// Share of customers holding a membership
+    pub member_share: f64,
+
+    /// Fee billed to members every month, in the base currency of the exchange rates
+    pub monthly_fee: f64,
+
+    /// Fee added to the orders of customers without a membership, in the base currency
+    pub delivery_fee: f64,
+
+    /// Factor applied to the probability of members placing an order
+    pub order_rate_multiplier: f64,
+
+    /// Lowest priority tier the orders of members are placed in
+    pub tier: PriorityTier,
+}
+
+impl Default for MembershipConfig {
+    fn default() -> Self {
+        Self {
+            member_share: 0.15,
+            monthly_fee: 9.99,
+            delivery_fee: 2.99,
+            order_rate_multiplier: 1.4,
+            tier: PriorityTier::Priority,
+        }
+    }
+}
+
+impl MembershipConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.member_share) {
+            return Err(Error::invalid_data(format!(
+                "member share {} outside of [0, 1]",
+                self.member_share
+            )));
+        }
+        for (name, fee) in [
+            ("monthly", self.monthly_fee),
+            ("delivery", self.delivery_fee),
+        ] {
+            if !(fee.is_finite() && fee >= 0.0) {
+                return Err(Error::invalid_data(format!(
+                    "{name} fee must be a non-negative number"
+                )));
+            }
+        }
+        if !(self.order_rate_multiplier.is_finite() && self.order_rate_multiplier > 0.0) {
+            return Err(Error::invalid_data(
+                "order rate multiplier of members must be positive",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the customer with id `person_id` holds a membership.
+    pub(crate) fn is_member(&self, person_id: &PersonId) -> bool {
+        let id: &[u8] = person_id.as_ref();
+        let key = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+        StdRng::seed_from_u64(key ^ MEMBERSHIP_SALT).random_bool(self.member_share)
+    }
+
+    /// Factor applied to the order probability of all customers.
+    ///
+    /// Orders are drawn at the higher rate of members and non-members, and then
+    /// thinned to the rate of the customer with [`Self::keep_probability`].
+    pub(crate) fn demand_boost(&self) -> f64 {
+        self.order_rate_multiplier.max(1.0)
+    }
+
+    /// Probability that an order drawn at the [`Self::demand_boost`] is placed.
+    pub(crate) fn keep_probability(&self, member: bool) -> f64 {
+        let rate = if member {
+            self.order_rate_multiplier
+        } else {
+            1.0
+        };
+        rate / self.demand_boost()
+    }
+}
+
+/// Bills the monthly fees of members.
+pub(crate) struct MembershipBilling {
+    config: MembershipConfig,
+    /// Currency fees are billed in
+    currency: Currency,
+}
+
+impl MembershipBilling {
+    pub(crate) fn new(config: MembershipConfig, currency: Currency) -> Self {
+        Self { config, currency }
+    }
+
+    /// Fees billed in the step, once the step passes the start of a month.
+    pub(crate) fn step(&self, state: &State) -> Result<Vec<EventPayload>> {
+        if !starts_month(state.current_time(), state.next_time()) {
+            return Ok(Vec::new());
+        }
+        let events: Vec<_> = state
+            .population()
+            .people_with_role(&PersonRole::Customer)?
+            .into_iter()
+            .filter(|person_id| self.config.is_member(person_id))
+            .map(|person_id| {
+                EventPayload::membership_billed(person_id, self.config.monthly_fee, self.currency)
+            })
+            .collect();
+        tracing::debug!(
+            target: "caspers::simulation::memberships",
+            "billed {} memberships",
+            events.len()
+        );
+        Ok(events)
+    }
+}
+
+/// Whether a month starts after `start` and no later than `end`.
+fn starts_month(start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+    let month_start = Utc
+        .with_ymd_and_hms(end.year(), end.month(), 1, 0, 0, 0)
+        .single()
+        .expect("months start at midnight");
+    start < month_start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_members() {
+        let config = MembershipConfig {
+            member_share: 0.3,
+            ..Default::default()
+        };
+        let customers: Vec<_> = (0..2000).map(|_| PersonId::new()).collect();
+        let members = customers.iter().filter(|c| config.is_member(c)).count();
+        assert!((500..700).contains(&members));
+        assert!(
+            customers
+                .iter()
+                .all(|c| config.is_member(c) == config.is_member(c))
+        );
+
+        assert_eq!(config.demand_boost(), 1.4);
+        assert_eq!(config.keep_probability(true), 1.0);
+        assert!((config.keep_probability(false) - 1.0 / 1.4).abs() < 1e-9);
+
+        // members ordering less often do not raise the demand of others
+        let fewer = MembershipConfig {
+            order_rate_multiplier: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(fewer.demand_boost(), 1.0);
+        assert_eq!(fewer.keep_probability(false), 1.0);
+        assert_eq!(fewer.keep_probability(true), 0.5);
+    }
+
+    #[test]
+    fn test_starts_month() {
+        let time = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        assert!(starts_month(
+            time("2025-01-31T23:55:00Z"),
+            time("2025-02-01T00:05:00Z")
+        ));
+        // the step ending at midnight bills the month, not the one starting there
+        assert!(starts_month(
+            time("2025-01-31T23:55:00Z"),
+            time("2025-02-01T00:00:00Z")
+        ));
+        assert!(!starts_month(
+            time("2025-02-01T00:00:00Z"),
+            time("2025-02-01T00:05:00Z")
+        ));
+        assert!(!starts_month(
+            time("2025-02-10T12:00:00Z"),
+            time("2025-02-10T12:05:00Z")
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(MembershipConfig::default().validate().is_ok());
+        for config in [
+            MembershipConfig {
+                member_share: 1.5,
+                ..Default::default()
+            },
+            MembershipConfig {
+                monthly_fee: -1.0,
+                ..Default::default()
+            },
+            MembershipConfig {
+                delivery_fee: f64::NAN,
+                ..Default::default()
+            },
+            MembershipConfig {
+                order_rate_multiplier: 0.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Background mobility of customers between orders.
+//!
+//! Without a [`MobilityConfig`], customers stay where they were placed until they
+//! order. With one, idle customers take trips over the day following a simple
+//! activity schedule, e.g. commuting in the morning and evening and running errands
+//! around lunch. Each [`MobilityActivity`] starts trips within a window of hours (UTC)
+//! at an hourly rate, to a street node within its range and by its transport.
+//! Customers stay where their trip ended, so the cells they are in, and the site
+//! serving them, change over the day.
+//!
+//! Customers with an open order stay put, and customers become idle again once they
+//! finished eating. Trips are planned on the street network of the site nearest to
+//! the customer, for at most [`MobilityConfig::max_trips_per_step`] customers of each
+//! site in a step, which bounds the routing work of large populations.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Timelike as _;
+use geo::{Destination as _, Distance as _, Haversine, Point};
+use h3o::LatLng;
+use rand::Rng;
+use rand::seq::SliceRandom as _;
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{OrderId, PersonId};
+use crate::state::{OrderStatus, PersonRole, PersonStatus, State, Transport};
+use crate::{EntityView as _, Error, EventPayload, Result};
+
+/// An activity customers take trips for during a window of the day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MobilityActivity {
+    pub name: String,
+
+    /// Hour of the day (UTC) from which trips are started
+    pub start_hour: u32,
+
+    /// Hour of the day (UTC) until which trips are started, windows may wrap midnight
+    pub end_hour: u32,
+
+    /// Probability per hour that an idle customer starts a trip within the window
+    pub hourly_rate: f64,
+
+    /// Farthest distance in meters from the customer to the destination of a trip
+    pub max_distance_m: f64,
+
+    /// Transport customers take the trip by
+    #[serde(default)]
+    pub transport: Transport,
+}
+
+impl MobilityActivity {
+    fn new(
+        name: &str,
+        hours: (u32, u32),
+        hourly_rate: f64,
+        max_distance_m: f64,
+        transport: Transport,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            start_hour: hours.0,
+            end_hour: hours.1,
+            hourly_rate,
+            max_distance_m,
+            transport,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.start_hour >= 24 || self.end_hour > 24 || self.start_hour == self.end_hour {
+            return Err(Error::invalid_data(format!(
+                "activity '{}' needs a window of hours within [0, 24]",
+                self.name
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.hourly_rate) {
+            return Err(Error::invalid_data(format!(
+                "hourly rate {} of activity '{}' outside of [0, 1]",
+                self.hourly_rate, self.name
+            )));
+        }
+        if !(self.max_distance_m.is_finite() && self.max_distance_m > 0.0) {
+            return Err(Error::invalid_data(format!(
+                "activity '{}' needs a positive maximum distance",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether trips of the activity start during `hour`.
+    fn is_active(&self, hour: u32) -> bool {
+        if self.start_hour < self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Activity schedule of customers between orders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MobilityConfig {
+    /// Activities customers take trips for
+    pub activities: Vec<MobilityActivity>,
+
+    /// Most customers of a site starting a trip in a single step
+    pub max_trips_per_step: usize,
+}
+
+impl Default for MobilityConfig {
+    fn default() -> Self {
+        Self {
+            activities: vec![
+                MobilityActivity::new("commute", (7, 9), 0.25, 6000.0, Transport::Bicycle),
+                MobilityActivity::new("errands", (11, 14), 0.08, 1500.0, Transport::Foot),
+                MobilityActivity::new("commute", (17, 19), 0.25, 6000.0, Transport::Bicycle),
+                MobilityActivity::new("leisure", (19, 22), 0.05, 2000.0, Transport::Foot),
+            ],
+            max_trips_per_step: 50,
+        }
+    }
+}
+
+impl MobilityConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for activity in &self.activities {
+            activity.validate()?;
+        }
+        if self.max_trips_per_step == 0 {
+            return Err(Error::invalid_data(
+                "mobility needs at least one trip per step",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Sends idle customers on trips according to their activity schedule.
+pub(crate) struct Mobility {
+    config: MobilityConfig,
+    /// Customers of orders placed during the run that are not yet delivered
+    open_orders: HashMap<OrderId, PersonId>,
+}
+
+impl Mobility {
+    pub(crate) fn new(config: MobilityConfig) -> Self {
+        Self {
+            config,
+            open_orders: HashMap::new(),
+        }
+    }
+
+    /// Trips started and meals finished in the step, given the events emitted so far.
+    pub(crate) fn step(
+        &mut self,
+        events: &[EventPayload],
+        state: &State,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<EventPayload>> {
+        for event in events {
+            match event {
+                EventPayload::OrderCreated(payload) => {
+                    self.open_orders.insert(payload.order_id, payload.person_id);
+                }
+                EventPayload::OrderUpdated(payload)
+                    if matches!(
+                        payload.status,
+                        OrderStatus::Delivered | OrderStatus::Cancelled | OrderStatus::Failed
+                    ) =>
+                {
+                    self.open_orders.remove(&payload.order_id);
+                }
+                _ => (),
+            }
+        }
+        let ordering: HashSet<_> = self.open_orders.values().collect();
+
+        let now = state.current_time();
+        let step_hours = state.time_step().as_secs_f64() / 3600.0;
+        let active = self
+            .config
+            .activities
+            .iter()
+            .filter(|activity| activity.is_active(now.hour()))
+            .map(|activity| {
+                let probability = 1.0 - (1.0 - activity.hourly_rate).powf(step_hours);
+                (activity, probability.clamp(0.0, 1.0))
+            })
+            .collect::<Vec<_>>();
+
+        let mut sites = Vec::new();
+        for site in state.objects().sites()? {
+            let props = site.properties()?;
+            if let Some(planner) = state.trip_planner(&site.id()) {
+                sites.push((Point::new(props.longitude, props.latitude), planner));
+            }
+        }
+
+        let mut events = Vec::new();
+        let mut travelers = vec![Vec::new(); sites.len()];
+        for (person_id, status, position) in state
+            .population()
+            .people_with_role_status(&PersonRole::Customer)?
+        {
+            match status {
+                PersonStatus::Eating(until) if *until <= now => {
+                    events.push(EventPayload::person_updated(person_id, PersonStatus::Idle));
+                }
+                PersonStatus::Idle if !ordering.contains(&person_id) => {
+                    let Some((activity, _)) = active
+                        .iter()
+                        .find(|(_, probability)| rng.random_bool(*probability))
+                    else {
+                        continue;
+                    };
+                    let nearest = sites.iter().enumerate().min_by(|(_, a), (_, b)| {
+                        let a = Haversine.distance(a.0, position);
+                        let b = Haversine.distance(b.0, position);
+                        a.total_cmp(&b)
+                    });
+                    if let Some((index, _)) = nearest {
+                        travelers[index].push((person_id, position, *activity));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for ((_, planner), mut travelers) in sites.iter().zip(travelers) {
+            if travelers.is_empty() {
+                continue;
+            }
+            travelers.shuffle(rng);
+            travelers.truncate(self.config.max_trips_per_step);
+            let mut router = planner.get_router();
+            for (person_id, position, activity) in travelers {
+                let Some((origin, _)) =
+                    planner.node_near(&LatLng::new(position.y(), position.x())?)
+                else {
+                    continue;
+                };
+                let bearing = rng.random_range(0.0..360.0);
+                let distance = rng.random_range(0.0..=activity.max_distance_m);
+                let target = Haversine.destination(position, bearing, distance);
+                let Some((destination, _)) =
+                    planner.node_near(&LatLng::new(target.y(), target.x())?)
+                else {
+                    continue;
+                };
+                let journey =
+                    planner.plan(&mut router, origin, destination, activity.transport, now);
+                if let Some(journey) = journey.filter(|journey| journey.distance_m() > 0) {
+                    events.push(EventPayload::person_updated(
+                        person_id,
+                        PersonStatus::Moving(journey),
+                    ));
+                }
+            }
+        }
+        tracing::debug!(
+            target: "caspers::simulation::mobility",
+            "{} customers started a trip or finished eating",
+            events.len()
+        );
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_window() {
+        let commute = MobilityActivity::new("commute", (7, 9), 0.25, 6000.0, Transport::Bicycle);
+        assert!(!commute.is_active(6));
+        assert!(commute.is_active(7));
+        assert!(commute.is_active(8));
+        assert!(!commute.is_active(9));
+
+        let night = MobilityActivity::new("night", (22, 2), 0.1, 1000.0, Transport::Foot);
+        assert!(night.is_active(23));
+        assert!(night.is_active(1));
+        assert!(!night.is_active(2));
+        assert!(!night.is_active(12));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(MobilityConfig::default().validate().is_ok());
+
+        let config: MobilityConfig = serde_json::from_str(
+            r#"{"activities": [{"name": "gym", "start_hour": 18, "end_hour": 20,
+                "hourly_rate": 0.1, "max_distance_m": 3000, "transport": "foot"}]}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.activities[0].transport, Transport::Foot);
+        assert_eq!(config.max_trips_per_step, 50);
+
+        for activity in [
+            MobilityActivity::new("late", (8, 25), 0.1, 1000.0, Transport::Foot),
+            MobilityActivity::new("empty", (8, 8), 0.1, 1000.0, Transport::Foot),
+            MobilityActivity::new("often", (8, 9), 1.5, 1000.0, Transport::Foot),
+            MobilityActivity::new("nowhere", (8, 9), 0.1, 0.0, Transport::Foot),
+        ] {
+            let config = MobilityConfig {
+                activities: vec![activity],
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -1,47 +1,238 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use itertools::Itertools as _;
-use rand::distr::{Distribution, Uniform};
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::FutureExt as _;
+use futures::future::BoxFuture;
+use opentelemetry::trace::TraceContextExt as _;
+use rand::rngs::StdRng;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
 use tracing::{Level, Span, field, instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
 
-use crate::Result;
 use crate::agents::{PopulationRunner, SiteRunner};
-use crate::builders::{EventDataBuilder, EventStatsBuffer};
+use crate::builders::EventStatsBuffer;
 use crate::context::SimulationContext;
 use crate::idents::SiteId;
-use crate::state::State;
+use crate::state::{
+    EntityView as _, ObjectData, ObjectLabel, PersonStatusFlag, SimulationStats, State, StateStats,
+};
+use crate::{Error, Result, ResultExt as _};
 
+use self::bus::{EventBus, PublishedStep};
+use self::carbon::FootprintTracker;
+use self::compensation::Compensator;
+use self::controls::Controls;
+use self::cuisines::CuisineMarketShare;
+use self::daily_summary::DailySummary;
+use self::feedback::FeedbackCollector;
+use self::green::GreenDeliveryReport;
+use self::heatmap::{OrderHeatmap, heatmap_resolution};
+use self::inventory::IngredientInventory;
+use self::invoices::Invoicer;
+use self::kpis::KpiRecorder;
+use self::lifecycle::CustomerLifecycle;
+use self::memberships::MembershipBilling;
+use self::mobility::Mobility;
+use self::notifications::Notifier;
+use self::quarantine::{SiteCheckpoints, SiteQuarantine};
+use self::sessions::AppSessions;
+use self::stop::RunProgress;
+use self::verify::EventDigest;
+use self::waves::site_waves;
+
+pub use self::agent::{Agent, AgentStep};
+pub(crate) use self::breaks::BreakTracker;
+pub use self::breaks::CourierBreaks;
 pub use self::builder::*;
+pub use self::bus::{DEFAULT_EVENT_BUS_CAPACITY, EventBatch, EventCallback, EventSubscription};
+pub(crate) use self::calendar::Calendar;
+pub use self::calendar::{CalendarConfig, Holiday, SpecialEvent};
+pub use self::campaigns::*;
+pub use self::carbon::CarbonConfig;
+pub use self::coalescing::EventCoalescing;
+pub use self::compensation::{CompensationPolicy, CompensationRule, Voucher};
+pub(crate) use self::controls::on_shift;
+pub use self::controls::{RuntimeSettings, SettingsUpdate, SimulationControl};
+pub use self::couriers::*;
+pub use self::cuisines::CuisinePreferences;
+pub(crate) use self::dark_stores::DarkStore;
+pub use self::dark_stores::{DarkStoreConfig, SkuConfig, SubstitutionConfig};
+pub(crate) use self::destinations::Destinations;
+pub use self::destinations::{DeliverySpot, DestinationConfig, WorkDistrict};
+pub use self::event_filter::*;
 pub use self::events::*;
+pub use self::feedback::FeedbackConfig;
+pub use self::frames::*;
+pub(crate) use self::green::Bundler;
+pub use self::green::GreenDeliveryConfig;
+pub use self::heatmap::DEFAULT_HEATMAP_RESOLUTION;
+pub use self::hooks::*;
+pub use self::inventory::{InventoryConfig, ProcurementConfig, WasteConfig};
+pub use self::invoices::InvoiceConfig;
+pub use self::kpis::StepKpis;
+pub use self::lifecycle::DEFAULT_CHURN_AFTER;
+pub use self::memberships::MembershipConfig;
+pub use self::mobility::{MobilityActivity, MobilityConfig};
 pub use self::next::*;
+pub use self::notifications::*;
+pub(crate) use self::packing::Packer;
+pub use self::packing::{PackagingSupply, PackingConfig};
+pub use self::plugins::*;
 pub use self::population_event_schemas::*;
+pub use self::priority::{PriorityConfig, PriorityTier};
+pub use self::quarantine::DEFAULT_SITE_FAILURE_THRESHOLD;
+pub use self::robots::DeliveryRobots;
+pub(crate) use self::robots::RobotFleet;
+pub use self::scenario::{FailureProfile, OutputConfig, Scenario};
+pub use self::scheduler::{AdaptiveTimeStep, EventScheduler};
+pub use self::seasonality::{SchoolHoliday, SeasonalityConfig};
+pub use self::sessions::{FunnelStage, SessionConfig};
+pub use self::stop::{StopConditions, StopPredicate};
+pub use self::timings::*;
+pub use self::tipping::*;
+pub(crate) use self::verify::ReplayRecord;
+pub use self::verify::{ReplayVerification, verify_simulation};
 
+mod agent;
+mod breaks;
 mod builder;
+mod bus;
+mod calendar;
+mod campaigns;
+mod carbon;
+mod coalescing;
+mod compensation;
+mod controls;
+mod couriers;
+mod cuisines;
+mod daily_summary;
+mod dark_stores;
+mod destinations;
+mod event_filter;
+mod event_writer;
 mod events;
+mod feedback;
+mod frames;
+mod green;
+mod heatmap;
+mod hooks;
+mod inventory;
+mod invoices;
+mod kpis;
+mod lifecycle;
+mod memberships;
+mod mobility;
 mod next;
+mod notifications;
+mod packing;
+mod plugins;
 mod population_event_schemas;
+mod priority;
+mod quarantine;
+mod robots;
+mod scenario;
+mod scheduler;
+mod seasonality;
+mod sessions;
+mod stop;
+mod timings;
+mod tipping;
+mod verify;
+mod waves;
 
 /// The main simulation engine
 ///
 /// Single entry point to run simulations.
 /// This will drive progress in all entities and make sure results are reported.
 pub struct Simulation {
-    ctx: SimulationContext,
+    /// Shared with the tasks stepping the sites
+    ctx: Arc<SimulationContext>,
 
     config: SimulationConfig,
 
-    /// Global simulation state
-    state: State,
+    /// Global simulation state, shared with the tasks stepping the sites
+    state: Arc<State>,
 
-    /// all ghost kitchen sites.
-    sites: HashMap<SiteId, SiteRunner>,
+    /// all ghost kitchen sites, stepped in the order of their ids.
+    sites: BTreeMap<SiteId, SiteRunner>,
 
     population: PopulationRunner,
 
+    /// User-defined agents, stepped in the order they were added
+    agents: Vec<Box<dyn Agent>>,
+
     /// The event stats for the simulation
     event_tracker: EventTracker,
 
     stats_buffer: EventStatsBuffer,
+
+    /// Invoices of delivered orders waiting to be written
+    invoicer: Invoicer,
+
+    /// Rating prompts of delivered orders waiting to be written
+    feedback: FeedbackCollector,
+
+    /// Vouchers issued for late deliveries
+    compensator: Compensator,
+
+    /// Lifecycle stages reached by customers
+    lifecycle: CustomerLifecycle,
+
+    /// Notifications sent to customers at order milestones
+    notifier: Option<Notifier>,
+
+    /// Trips of idle customers between orders
+    mobility: Option<Mobility>,
+
+    /// Monthly fees of delivery memberships
+    memberships: Option<MembershipBilling>,
+
+    /// App sessions preceding orders and abandoned sessions, with the menu items
+    /// shown in them waiting to be written
+    sessions: Option<AppSessions>,
+
+    /// Daily rollups of the run waiting to be written
+    daily_summary: Option<DailySummary>,
+
+    /// Ingredient stock of the sites, with its daily movements waiting to be written
+    inventory: Option<IngredientInventory>,
+
+    /// Carbon footprint of deliveries, with its daily rollups waiting to be written
+    footprints: Option<FootprintTracker>,
+
+    /// Green and standard deliveries, with their daily rollups waiting to be written
+    green_deliveries: Option<GreenDeliveryReport>,
+
+    /// Holidays and special events shifting demand and courier supply of the sites
+    calendar: Option<Calendar>,
+
+    /// Domain KPIs exported as OpenTelemetry metrics
+    kpis: KpiRecorder,
+
+    /// Sites excluded from the run after repeatedly failing to step
+    quarantine: SiteQuarantine,
+
+    /// Events of failed site steps, re-delivered to the site in the next step
+    pending_site_events: HashMap<SiteId, Vec<EventPayload>>,
+
+    /// Order and population counts, refreshed after every step
+    stats: watch::Sender<SimulationStats>,
+
+    /// Sinks receiving the events of every step
+    bus: EventBus,
+
+    /// Settings changed while the simulation runs
+    controls: Controls,
+
+    /// Random numbers of the agents without their own, seeded if configured
+    rng: StdRng,
+
+    /// Hash of the events of a seeded run, recorded with its snapshots to verify replays
+    replay: Option<EventDigest>,
 }
 
 impl Simulation {
@@ -61,87 +252,636 @@ impl Simulation {
         &self.state
     }
 
+    /// Mutable access to the simulation objects, e.g. to onboard new menu items.
+    pub fn objects_mut(&mut self) -> Result<&mut ObjectData> {
+        Ok(exclusive(&mut self.state)?.objects_mut())
+    }
+
+    /// Sites that no longer advance because their steps failed repeatedly.
+    pub fn quarantined_sites(&self) -> impl Iterator<Item = &SiteId> {
+        self.quarantine.quarantined().iter()
+    }
+
+    /// Order and population counts as of the last completed step.
+    pub fn stats(&self) -> SimulationStats {
+        self.stats.borrow().clone()
+    }
+
+    /// Receive the order and population counts whenever a step completes.
+    pub fn subscribe_stats(&self) -> watch::Receiver<SimulationStats> {
+        self.stats.subscribe()
+    }
+
+    /// Receive the events of every step on the given topics, or all events if empty.
+    ///
+    /// Up to `capacity` steps are buffered, beyond that the simulation waits for the
+    /// subscriber to catch up. Dropping the subscription unsubscribes.
+    pub fn subscribe_events(
+        &mut self,
+        topics: impl IntoIterator<Item = EventKind>,
+        capacity: usize,
+    ) -> EventSubscription {
+        self.bus.subscribe(topics, capacity)
+    }
+
+    /// Handle to change settings of the running simulation, applied at the next step.
+    pub fn control(&self) -> SimulationControl {
+        self.controls.handle()
+    }
+
     pub fn event_stats(&self) -> &EventStats {
         &self.event_tracker.total_stats
     }
 
+    /// Size and cardinality report for the simulation state and all registered tables.
+    ///
+    /// Row and null counts of tables are computed by scanning them in full.
+    pub async fn state_stats(&self) -> Result<StateStats> {
+        let mut stats = self.state.stats();
+        stats.tables = self.ctx.table_stats().await?;
+        Ok(stats)
+    }
+
     /// Advance the simulation time by one step (for testing)
     #[cfg(any(test, feature = "templates"))]
-    pub fn advance_time(&mut self) {
-        self.state.step_time();
+    pub fn advance_time(&mut self) -> Result<()> {
+        exclusive(&mut self.state)?.step_time();
+        Ok(())
     }
 
     /// Run the simulation for a specified number of steps
-    #[instrument(skip(self))]
+    ///
+    /// A snapshot is taken at the end of the run, every `snapshot_interval` steps and
+    /// every `checkpoint_days` simulated days if configured. With an [`EventScheduler`],
+    /// steps count time increments, which the run covers in fewer but longer steps
+    /// where possible.
+    ///
+    /// While the simulation is paused, e.g. through a [`SimulationControl`], the run
+    /// waits before starting its next step until the simulation is resumed. Once the
+    /// simulation is stopped, the run ends early after the step in progress, writing
+    /// its pending results and the final snapshot as usual.
     pub async fn run(&mut self, steps: usize) -> Result<()> {
+        self.run_until(StopConditions::steps(steps)).await
+    }
+
+    /// Run the simulation until any of the `stop` conditions is met
+    ///
+    /// Runs like [`run`](Self::run), but may end at a simulated time, after a budget
+    /// of wall-clock time, once a number of orders were created or once a predicate
+    /// over the state holds, e.g. to generate a fixed number of orders.
+    #[instrument(skip(self))]
+    pub async fn run_until(&mut self, stop: StopConditions) -> Result<()> {
+        stop.validate()?;
         tracing::info!(
             target: "caspers::simulation",
-            "statrting simulation run for {} steps ({} / {})",
-            steps,
+            "starting simulation run until {:?} ({} / {})",
+            stop,
             self.ctx.simulation_id(),
             self.ctx.snapshot_id()
         );
 
-        for step in 0..steps {
-            self.step().await?;
-            if step % 8192 == 0 && step != 0 {
+        let start = self.state.current_time();
+        let end = stop.end_time(start, self.config.time_increment);
+        let started = Instant::now();
+        let orders_before = self.event_stats().num_orders_created;
+        let progress = |simulation: &Self, steps| RunProgress {
+            steps,
+            current_time: simulation.state.current_time(),
+            elapsed: started.elapsed(),
+            orders: (simulation.event_stats().num_orders_created - orders_before) as usize,
+        };
+        let mut covered = 0;
+        let met = loop {
+            if let Some(met) = stop.met(&progress(self, covered), &self.state) {
+                break met;
+            }
+            if self.controls.is_paused() {
+                tracing::info!(target: "caspers::simulation", "simulation paused");
                 self.write_event_stats().await?;
-            };
-        }
+                self.controls.resumed().await;
+                tracing::info!(target: "caspers::simulation", "simulation resumed");
+            }
+            if self.controls.is_stopped() {
+                break "simulation stopped";
+            }
+            let step_time = self.state.current_time();
+            self.advance(end).await?;
+            let previous = covered;
+            // every step spans at least one time increment
+            covered = self.increments_since(start).max(previous + 1);
+            let interval_due = self
+                .config
+                .snapshot_interval
+                .is_some_and(|interval| covered / interval > previous / interval);
+            let checkpoint_due = self.config.checkpoint_days.is_some_and(|days| {
+                let days = i64::from(days);
+                day_number(self.state.current_time()) / days > day_number(step_time) / days
+            });
+            // the last step is covered by the snapshot at the end of the run
+            if (interval_due || checkpoint_due)
+                && stop.met(&progress(self, covered), &self.state).is_none()
+                && !self.config.dry_run
+            {
+                self.write_event_stats().await?;
+                if checkpoint_due {
+                    self.checkpoint().await?;
+                } else {
+                    self.snapshot().await?;
+                }
+            }
+        };
+        tracing::info!(
+            target: "caspers::simulation",
+            "simulation run ended after {covered} steps: {met}"
+        );
 
+        // the day the run ends in is reported for the steps covered so far
+        if let Some(summary) = self.daily_summary.as_mut() {
+            summary.finish_day();
+        }
+        if let Some(inventory) = self.inventory.as_mut() {
+            inventory.finish_day()?;
+        }
+        if let Some(footprints) = self.footprints.as_mut() {
+            footprints.finish_day()?;
+        }
+        if let Some(green) = self.green_deliveries.as_mut() {
+            green.finish_day()?;
+        }
         self.write_event_stats().await?;
 
         // snapshot the state
         if !self.config().dry_run {
             self.snapshot().await?;
+            if let Some(resolution) = self.config.heatmap_resolution {
+                self.materialize_heatmap(resolution).await?;
+            }
+            self.materialize_market_share().await?;
         }
         Ok(())
     }
 
-    /// Advance the simulation by one time step
+    /// Advance the simulation by a single step, also while it is paused.
+    ///
+    /// Unlike [`run`](Self::run), the day summary, snapshot and materialized results
+    /// are not written after the step, so a host driving the simulation step by step
+    /// decides when to call [`snapshot`](Self::snapshot).
+    pub async fn step_once(&mut self) -> Result<()> {
+        self.advance(None).await
+    }
+
+    /// Advance the simulation by a single step not passing `end`.
+    async fn advance(&mut self, end: Option<DateTime<Utc>>) -> Result<()> {
+        let step = self.stats.borrow().steps;
+        self.step(end).await?;
+        if step.is_multiple_of(8192) && step != 0 {
+            self.write_event_stats().await?;
+        };
+        if let Some(interval) = self.config.state_stats_interval
+            && step.is_multiple_of(interval)
+        {
+            self.log_state_stats().await?;
+        }
+        Ok(())
+    }
+
+    /// Hold the simulation before its next step, e.g. to inspect or snapshot the state.
+    ///
+    /// Results buffered so far, e.g. event stats and invoices, are written, so they
+    /// are consistent with the state while paused.
+    pub async fn pause(&mut self) -> Result<()> {
+        self.controls.set_paused(true);
+        self.write_event_stats().await
+    }
+
+    /// Continue a paused simulation.
+    pub fn resume(&mut self) {
+        self.controls.set_paused(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.controls.is_paused()
+    }
+
+    /// Time increments covered since `start`.
+    fn increments_since(&self, start: DateTime<Utc>) -> usize {
+        let elapsed = (self.state.current_time() - start).num_milliseconds();
+        (elapsed / self.config.time_increment.num_milliseconds().max(1)).max(0) as usize
+    }
+
+    /// Whether events are waiting to be re-delivered to a site which is not quarantined.
+    fn has_pending_site_events(&self) -> bool {
+        self.pending_site_events
+            .keys()
+            .any(|site_id| !self.quarantine.is_quarantined(site_id))
+    }
+
+    /// Whether orders are open, people are on their way or site events are pending.
+    ///
+    /// Orders and events of quarantined sites are ignored, they are never processed.
+    fn is_active(&self) -> bool {
+        self.has_pending_site_events()
+            || self
+                .state
+                .orders()
+                .has_open_orders(self.quarantine.quarantined())
+            || self.state.population().count_with_status(&[
+                PersonStatusFlag::Moving,
+                PersonStatusFlag::Delivering,
+                PersonStatusFlag::WaitingForCustomer,
+            ]) > 0
+    }
+
+    /// Earliest time at which an agent changes on its own, `None` if all are idle.
+    fn next_event(&self) -> Result<Option<DateTime<Utc>>> {
+        let now = self.state.current_time();
+        if self.has_pending_site_events() {
+            return Ok(Some(now));
+        }
+        let mut next = self.state.population().next_transition(now);
+        if let Some(time) = self.calendar.as_ref().and_then(|c| c.next_change(now)) {
+            next = Some(next.map_or(time, |next| next.min(time)));
+        }
+        for (site_id, site) in &self.sites {
+            if self.quarantine.is_quarantined(site_id) {
+                continue;
+            }
+            if let Some(time) = site.next_event(&self.state)? {
+                next = Some(next.map_or(time, |next| next.min(time)));
+            }
+        }
+        Ok(next)
+    }
+
+    /// Advance the simulation by one time step, or up to the next event if scheduled
     #[instrument(skip(self), fields(caspers.total_events_generated = field::Empty))]
-    async fn step(&mut self) -> Result<()> {
+    async fn step(&mut self, end: Option<DateTime<Utc>>) -> Result<()> {
+        let step_time = self.state.current_time();
+        let mut events = vec![EventPayload::step_started(step_time)];
+
+        // report changes applied to objects since the last step and make
+        // them visible to the agents
+        let changes = exclusive(&mut self.state)?.objects_mut().take_changes();
+        self.refresh_agents(&changes).await?;
+        events.extend(changes);
+
+        // settings changed through the controls apply from this step on
+        let settings_changes = self.controls.take_changes();
+        let calendar_changed = self
+            .calendar
+            .as_mut()
+            .is_some_and(|calendar| calendar.update(step_time));
+        if !settings_changes.is_empty() || calendar_changed {
+            self.apply_settings();
+            events.extend(settings_changes);
+        }
+
+        // the event scheduler extends the step up to the next event, adaptive steps
+        // grow while the simulation is quiet, customers place orders at the rate of
+        // all time increments the step spans
+        let increments = match (&self.config.scheduler, &self.config.adaptive_step) {
+            (Some(scheduler), _) => Some(scheduler.step_increments(
+                self.config.time_increment,
+                step_time,
+                self.next_event()?,
+                end,
+            )),
+            (None, Some(adaptive)) => {
+                let previous = TimeDelta::from_std(self.state.time_step())
+                    .unwrap_or(self.config.time_increment);
+                Some(adaptive.step_increments(
+                    self.config.time_increment,
+                    previous,
+                    self.is_active(),
+                    step_time,
+                    end,
+                ))
+            }
+            (None, None) => None,
+        };
+        if let Some(increments) = increments {
+            let increment =
+                Duration::from_secs(self.config.time_increment.num_seconds().max(0) as u64);
+            exclusive(&mut self.state)?.set_time_step(increment * increments as u32);
+            let demand_multiplier = self.controls.settings().demand_multiplier;
+            self.population
+                .set_demand_multiplier(demand_multiplier * increments as f64);
+        }
+
+        // demand follows the season and weather of the step
+        self.population.advance_season(step_time);
+
+        let mut timings = StepTimings::default();
+
         // move people
-        let mut events = self.state.move_people(&self.ctx).await?;
-
-        // advance all sites and collect events
-        for (site_id, site) in self.sites.iter_mut() {
-            // query population to get new orders for the site
-            let population_events = self
-                .population
-                .step(&self.ctx, site_id, &self.state)
-                .await?
-                .collect_vec();
-
-            // update the site state with new orders
-            let interactions_events = self.state.process_population_events(&population_events)?;
-            events.extend(population_events);
-
-            // advance the site and collect events
-            if let Ok(site_events) = site
-                .step(&self.ctx, &interactions_events, &self.state)
-                .await
-            {
-                events.extend(interactions_events);
-                self.state.process_site_events(&site_events)?;
-                events.extend(site_events);
-            } else {
-                tracing::error!(target: "simulation", "Failed to step site {:?}", site.id());
+        let start = Instant::now();
+        events.extend(exclusive(&mut self.state)?.move_people(&self.ctx).await?);
+        timings.record(StepPhase::MovePeople, start);
+
+        // advance all sites and collect events, sites which may share couriers are
+        // stepped in separate waves, all sites of a wave concurrently
+        let mut areas = Vec::with_capacity(self.sites.len());
+        for (site_id, site) in &self.sites {
+            if !self.quarantine.is_quarantined(site_id) {
+                areas.push((*site_id, site.courier_cells(&self.state)?));
+            }
+        }
+        for wave in site_waves(areas) {
+            let mut interactions = BTreeMap::new();
+            for site_id in &wave {
+                // events are appended to the step events right away and processed
+                // as slices thereof, so they are not copied between collections
+                let start = Instant::now();
+                let population_offset = events.len();
+                events.extend(
+                    self.population
+                        .step(&self.ctx, site_id, &self.state, &mut self.rng)
+                        .await?,
+                );
+                timings.record(StepPhase::PopulationStep, start);
+
+                // update the site state with new orders
+                let start = Instant::now();
+                let mut interactions_events =
+                    self.pending_site_events.remove(site_id).unwrap_or_default();
+                interactions_events.extend(
+                    exclusive(&mut self.state)?
+                        .process_population_events(&events[population_offset..])?,
+                );
+                timings.record(StepPhase::StateUpdate, start);
+                interactions.insert(*site_id, interactions_events);
+            }
+
+            // advance the sites and collect events, each site in a task of its own so
+            // that sites step in parallel. A site which keeps running after a failed
+            // step is rolled back so that no partial progress is applied
+            let start = Instant::now();
+            let mut checkpoints = SiteCheckpoints::new(&mut self.sites);
+            let mut site_steps = JoinSet::new();
+            for (site_id, interactions_events) in interactions {
+                let save = self.quarantine.survives_failure(&site_id);
+                let Some(mut site) = checkpoints.take(&site_id, save) else {
+                    continue;
+                };
+                let (ctx, state) = (Arc::clone(&self.ctx), Arc::clone(&self.state));
+                site_steps.spawn(async move {
+                    // a panicking step fails like any other, so its runner is handed back
+                    let site_result =
+                        AssertUnwindSafe(site.step(&ctx, &interactions_events, &state))
+                            .catch_unwind()
+                            .await
+                            .unwrap_or_else(|_| Err(Error::internal("site step panicked")));
+                    (site_id, site, interactions_events, site_result)
+                });
+            }
+            let mut site_results = Vec::with_capacity(site_steps.len());
+            while let Some(site_step) = site_steps.join_next().await {
+                let (site_id, site, interactions_events, site_result) =
+                    site_step.map_err(Error::internal)?;
+                checkpoints.restore(site_id, site, site_result.is_err());
+                site_results.push((site_id, site_result, interactions_events));
             }
+            drop(checkpoints);
+            // tasks finish in any order
+            site_results.sort_by_key(|(site_id, ..)| *site_id);
+            timings.record(StepPhase::SiteStep, start);
+
+            // both are ordered by site id, so events are merged deterministically
+            for (site_id, site_result, interactions_events) in site_results {
+                match site_result {
+                    Ok(site_events) => {
+                        self.quarantine.record_success(&site_id);
+                        events.extend(interactions_events);
+                        let site_offset = events.len();
+                        events.extend(site_events);
+                        let start = Instant::now();
+                        exclusive(&mut self.state)?.process_site_events(&events[site_offset..])?;
+                        timings.record(StepPhase::StateUpdate, start);
+                    }
+                    Err(err) => {
+                        tracing::error!(
+                            target: "caspers::simulation",
+                            "failed to step site {site_id}: {err}"
+                        );
+                        // events of quarantined sites are dropped, so they do not keep
+                        // the simulation busy
+                        if self.quarantine.record_failure(&site_id) {
+                            tracing::error!(
+                                target: "caspers::simulation",
+                                "quarantining site {site_id} after repeated failures, {} submitted orders will not be processed",
+                                interactions_events.len()
+                            );
+                        } else {
+                            self.pending_site_events
+                                .insert(site_id, interactions_events);
+                        }
+                    }
+                }
+            }
+        }
+
+        // the footprint of deliveries started in this step is recorded with the orders
+        if let Some(footprints) = self.footprints.as_mut() {
+            let emitted = footprints.record(step_time, &events, &self.state)?;
+            exclusive(&mut self.state)?.process_site_events(&emitted)?;
+            events.extend(emitted);
+        }
+
+        // customers who ordered in this step are not sent on a trip
+        if let Some(mobility) = self.mobility.as_mut() {
+            let start = Instant::now();
+            let trips = mobility.step(&events, &self.state, &mut self.rng)?;
+            events.extend(trips);
+            timings.record(StepPhase::MovePeople, start);
         }
 
+        if let Some(memberships) = &self.memberships {
+            events.extend(memberships.step(&self.state)?);
+        }
+        if let Some(sessions) = self.sessions.as_mut() {
+            let start = Instant::now();
+            let funnel = sessions.step(step_time, &events, &self.state, &mut self.rng)?;
+            events.extend(funnel);
+            timings.record(StepPhase::PopulationStep, start);
+        }
+        if let Some(inventory) = self.inventory.as_mut() {
+            let procurement = inventory.step(step_time, &mut self.rng)?;
+            events.extend(procurement);
+        }
+
+        let compensations = self.compensator.step(step_time, &events);
+        events.extend(compensations);
+        let lifecycle = self.lifecycle.step(step_time, &events);
+        events.extend(lifecycle);
+        if let Some(notifier) = self.notifier.as_mut() {
+            let notifications = notifier.step(step_time, &events, &self.state, &mut self.rng)?;
+            events.extend(notifications);
+        }
+
+        events.push(EventPayload::step_finished(step_time, events.len() - 1));
+
         let stats = self.event_tracker.process_events(&events, &self.state);
         let span = Span::current();
         span.record("caspers.total_events_generated", stats.num_orders_created);
 
         self.stats_buffer
             .push_stats(self.state.current_time(), "simulation", &stats)?;
+        self.kpis.record(&events, &self.state);
+        self.invoicer.record(&events, &self.state)?;
+        self.feedback.record(&events, &self.state, &mut self.rng)?;
+        if let Some(inventory) = self.inventory.as_mut() {
+            inventory.record(&events, &self.state)?;
+        }
+        if let Some(green) = self.green_deliveries.as_mut() {
+            green.record(step_time, &events)?;
+        }
 
         // update the state with the collected events
-        self.state.step(&self.ctx, &events).await?;
+        let start = Instant::now();
+        exclusive(&mut self.state)?
+            .step(&self.ctx, events.iter())
+            .await?;
+        timings.record(StepPhase::StateUpdate, start);
+
+        if !self.agents.is_empty() {
+            let control = self.controls.handle();
+            for agent in &mut self.agents {
+                let step = AgentStep {
+                    time: step_time,
+                    ctx: &self.ctx,
+                    state: &self.state,
+                    events: &events,
+                    control: &control,
+                };
+                agent
+                    .step(step)
+                    .await
+                    .with_context(|| format!("stepping agent '{}'", agent.name()))?;
+            }
+        }
+
+        if let Some(replay) = self.replay.as_mut() {
+            replay.record(&events)?;
+        }
 
-        self.write_events(events).await?;
+        // the events table is written by a sink of the bus
+        let start = Instant::now();
+        let published = PublishedStep {
+            step_time,
+            state_time: self.state.current_time(),
+            time_step: self.state.time_step(),
+            traceparent: traceparent(&span),
+            events: &events,
+        };
+        self.bus.publish(&self.ctx, &published).await?;
+        timings.record(StepPhase::EventWrite, start);
 
+        self.stats_buffer.push_timings(step_time, &timings)?;
+        let mut assignments_by_ring = BTreeMap::new();
+        for site in self.sites.values_mut() {
+            for (ring, count) in site.take_assignments_by_ring() {
+                *assignments_by_ring.entry(ring).or_default() += count;
+            }
+        }
+        self.stats_buffer
+            .push_assignments_by_ring(step_time, &assignments_by_ring)?;
+
+        let mut stats = self.state.simulation_stats()?;
+        for site in self.state.objects().sites()? {
+            let (Some(runner), Some(load)) = (
+                self.sites.get(&site.id()),
+                stats.sites.get_mut(&site.properties()?.name),
+            ) else {
+                continue;
+            };
+            load.packing = runner.packing_queued();
+        }
+        {
+            let previous = self.stats.borrow();
+            stats.steps = previous.steps + 1;
+            stats.events_by_kind = previous.events_by_kind.clone();
+        }
+        for event in &events {
+            *stats
+                .events_by_kind
+                .entry(event.kind().to_string())
+                .or_default() += 1;
+        }
+        if let Some(summary) = self.daily_summary.as_mut() {
+            summary.record(step_time, &events, &stats)?;
+            if summary.has_pending() {
+                let data = self.ctx.ctx().read_batch(summary.flush()?)?;
+                self.ctx.results().write_daily_summary(data).await?;
+            }
+        }
+        self.stats.send_replace(stats);
+
+        Ok(())
+    }
+
+    /// Reload agent data derived from objects that changed during the run.
+    async fn refresh_agents(&mut self, changes: &[EventPayload]) -> Result<()> {
+        let changed = |label: ObjectLabel| {
+            changes
+                .iter()
+                .any(|change| matches!(change, EventPayload::ObjectChanged(p) if p.label == label))
+        };
+        if changed(ObjectLabel::MenuItem) {
+            self.population
+                .refresh_menu(&self.ctx, self.state.objects())
+                .await?;
+        }
+        if changed(ObjectLabel::Station) {
+            for site in self.sites.values_mut() {
+                site.refresh_stations(&self.state)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand the settings steered through the controls to the agents.
+    fn apply_settings(&mut self) {
+        let settings = self.controls.settings();
+        self.population
+            .set_demand_multiplier(settings.demand_multiplier);
+        let couriers = self
+            .stats
+            .borrow()
+            .people_by_role
+            .get("courier")
+            .copied()
+            .unwrap_or_default();
+        let courier_share = settings.courier_share(couriers);
+        for (site_id, site) in &mut self.sites {
+            let courier_factor = self
+                .calendar
+                .as_ref()
+                .map_or(1.0, |calendar| calendar.courier_factor(site_id));
+            site.set_controls(
+                settings.order_failure_rate,
+                (courier_share * courier_factor).min(1.0),
+            );
+        }
+        if let Some(calendar) = &self.calendar {
+            self.population
+                .set_site_demand(calendar.demand_factors().clone());
+        }
+    }
+
+    #[instrument(skip_all, level = Level::TRACE)]
+    async fn log_state_stats(&self) -> Result<()> {
+        let stats = if self.config.table_stats {
+            self.state_stats().await?
+        } else {
+            self.state.stats()
+        };
+        tracing::info!(
+            target: "caspers::simulation::stats",
+            "state stats at {} ({} bytes in memory)\n{}",
+            self.state.current_time().to_rfc3339(),
+            stats.memory_bytes(),
+            stats
+        );
         Ok(())
     }
 
@@ -154,46 +894,191 @@ impl Simulation {
             self.ctx.simulation_id()
         );
 
+        // buffers are flushed up front, so the tables can be written concurrently
+        self.bus.flush(&self.ctx).await?;
+        let results = self.ctx.results();
+        let mut writes: Vec<(String, BoxFuture<'_, Result<()>>)> = Vec::new();
+
         let data = self.ctx.ctx().read_batch(self.stats_buffer.flush()?)?;
-        self.ctx.results().write_metrics(data).await
+        writes.push(("metrics".into(), results.write_metrics(data).boxed()));
+
+        if self.invoicer.has_pending() {
+            let data = self.ctx.ctx().read_batch(self.invoicer.flush()?)?;
+            writes.push(("invoices".into(), results.write_invoices(data).boxed()));
+        }
+        if self.feedback.has_pending() {
+            let data = self.ctx.ctx().read_batch(self.feedback.flush()?)?;
+            writes.push((
+                "order_feedback".into(),
+                results.write_order_feedback(data).boxed(),
+            ));
+        }
+        if let Some(sessions) = self.sessions.as_mut()
+            && sessions.has_pending()
+        {
+            let data = self.ctx.ctx().read_batch(sessions.flush()?)?;
+            writes.push((
+                "impressions".into(),
+                results.write_impressions(data).boxed(),
+            ));
+        }
+        if let Some(summary) = self.daily_summary.as_mut()
+            && summary.has_pending()
+        {
+            let data = self.ctx.ctx().read_batch(summary.flush()?)?;
+            writes.push((
+                "daily_summary".into(),
+                results.write_daily_summary(data).boxed(),
+            ));
+        }
+        if let Some(inventory) = self.inventory.as_mut()
+            && inventory.has_pending()
+        {
+            let data = self.ctx.ctx().read_batch(inventory.flush()?)?;
+            writes.push((
+                "ingredient_inventory".into(),
+                results.write_ingredient_inventory(data).boxed(),
+            ));
+        }
+        if let Some(inventory) = self.inventory.as_mut()
+            && inventory.has_pending_waste()
+        {
+            let data = self.ctx.ctx().read_batch(inventory.flush_waste()?)?;
+            writes.push((
+                "ingredient_waste".into(),
+                results.write_ingredient_waste(data).boxed(),
+            ));
+        }
+        if let Some(footprints) = self.footprints.as_mut()
+            && footprints.has_pending()
+        {
+            let data = self.ctx.ctx().read_batch(footprints.flush()?)?;
+            writes.push((
+                "delivery_footprint".into(),
+                results.write_delivery_footprint(data).boxed(),
+            ));
+        }
+        if let Some(green) = self.green_deliveries.as_mut()
+            && green.has_pending()
+        {
+            let data = self.ctx.ctx().read_batch(green.flush()?)?;
+            writes.push((
+                "green_delivery".into(),
+                results.write_green_delivery(data).boxed(),
+            ));
+        }
+        self.ctx.write_tables(writes).await?;
+        Ok(())
     }
 
-    #[instrument(skip_all, level = Level::TRACE)]
-    async fn write_events(&self, events: impl IntoIterator<Item = EventPayload>) -> Result<()> {
+    /// Aggregate the recorded order events into the order heatmap of the latest snapshot
+    #[instrument(skip(self))]
+    async fn materialize_heatmap(&self, resolution: u8) -> Result<()> {
         tracing::info!(
             target: "caspers::simulation",
-            "writing events at {} ({})",
+            "materializing order heatmap at {} ({})",
             self.state.current_time().to_rfc3339(),
             self.ctx.simulation_id()
         );
+        let mut heatmap = OrderHeatmap::new(
+            heatmap_resolution(resolution)?,
+            self.config.exchange_rates.clone(),
+        );
+        // events of the last step are spread over the step following its start time
+        let end = self.state.current_time() + self.state.time_step();
+        heatmap.record_results(&self.ctx, end).await?;
+        let data = self.ctx.ctx().read_batch(heatmap.finish()?)?;
+        self.ctx.results().write_order_heatmap(data).await
+    }
 
-        let range = Uniform::new(0.0_f32, 0.9999_f32).unwrap();
-        let events = events.into_iter().map(|payload| {
-            let multiplier = range.sample(&mut rand::rng());
-            let timestamp = self.state.current_time() + self.state.time_step().mul_f32(multiplier);
-            Event { timestamp, payload }
-        });
-        let mut builder = EventDataBuilder::new();
-        for event in events {
-            builder.add_event(&event)?;
-        }
-        let data = self.ctx.ctx().read_batch(builder.build()?)?;
-        self.ctx.results().write_events(data).await
+    /// Aggregate the recorded orders into the cuisine market share of the latest snapshot
+    #[instrument(skip(self))]
+    async fn materialize_market_share(&self) -> Result<()> {
+        tracing::info!(
+            target: "caspers::simulation",
+            "materializing cuisine market share at {} ({})",
+            self.state.current_time().to_rfc3339(),
+            self.ctx.simulation_id()
+        );
+        let mut market_share = CuisineMarketShare::new(self.config.exchange_rates.clone());
+        let end = self.state.current_time() + self.state.time_step();
+        market_share.record_results(&self.ctx, end).await?;
+        let data = self.ctx.ctx().read_batch(market_share.finish()?)?;
+        self.ctx.results().write_cuisine_market_share(data).await
     }
 
-    /// Snapshot the state of the simulation
+    /// Snapshot the state of the simulation as of the last completed step.
+    ///
+    /// Called at the end of every run, and may be called between steps, e.g. while
+    /// the simulation is paused.
     #[instrument(skip(self))]
-    async fn snapshot(&mut self) -> Result<()> {
+    pub async fn snapshot(&mut self) -> Result<()> {
         tracing::info!(
             target: "caspers::simulation",
             "creating new snapshot at {} ({})",
             self.state.current_time().to_rfc3339(),
             self.ctx.simulation_id()
         );
-        self.ctx.write_snapshot(&self.state).await
+        // events are written up to the state of the snapshot
+        self.bus.flush(&self.ctx).await?;
+        match &self.replay {
+            Some(replay) => {
+                let record = replay.replay_record(&self.config, self.state.current_time());
+                exclusive(&mut self.ctx)?
+                    .write_replay_snapshot(&self.state, &record)
+                    .await
+            }
+            None => exclusive(&mut self.ctx)?.write_snapshot(&self.state).await,
+        }
+    }
+
+    /// Snapshot the state at the end of a simulated day, marked as a checkpoint.
+    async fn checkpoint(&mut self) -> Result<()> {
+        // the state is taken at midnight or just after it, and completes the day before
+        let Some(day) = self.state.current_time().date_naive().pred_opt() else {
+            return self.snapshot().await;
+        };
+        tracing::info!(
+            target: "caspers::simulation",
+            "checkpointing {day} at {} ({})",
+            self.state.current_time().to_rfc3339(),
+            self.ctx.simulation_id()
+        );
+        exclusive(&mut self.ctx)?
+            .write_checkpoint(&self.state, day)
+            .await
     }
 }
 
+/// Exclusive access to the state or context of a simulation.
+///
+/// Both are shared with the tasks stepping the sites of a wave, which are awaited
+/// before the wave ends. Tasks of a step dropped part way are aborted, but may still
+/// hold on to their references for a moment.
+fn exclusive<T>(shared: &mut Arc<T>) -> Result<&mut T> {
+    Arc::get_mut(shared)
+        .ok_or_else(|| Error::internal("site steps of an aborted step are still in flight"))
+}
+
+/// Days since the Unix epoch up to `time`, starting at midnight UTC.
+fn day_number(time: DateTime<Utc>) -> i64 {
+    time.timestamp().div_euclid(86_400)
+}
+
+/// W3C `traceparent` header value of the OpenTelemetry span backing `span`.
+fn traceparent(span: &Span) -> Option<String> {
+    let context = span.context();
+    let span_context = context.span().span_context().clone();
+    span_context.is_valid().then(|| {
+        format!(
+            "00-{}-{}-{:02x}",
+            span_context.trace_id(),
+            span_context.span_id(),
+            span_context.trace_flags().to_u8()
+        )
+    })
+}
+
 #[cfg(any(test, feature = "templates"))]
 impl Simulation {
     pub async fn try_new_with_template(
@@ -45,9 +45,11 @@ impl SimulationRunnerBuilder {
         let batches = self
             .ctx
             .snapshots()
-            .objects()
+            .cached(
+                "objects",
+                Some(col("label").eq(lit(ObjectLabel::MenuItem.as_ref()))),
+            )
             .await?
-            .filter(col("label").eq(lit(ObjectLabel::MenuItem.as_ref())))?
             .select([
                 col("parent_id").alias("brand_id"),
                 col("id").alias("menu_item_id"),
@@ -0,0 +1,428 @@
+//! Notifications sent to customers at order milestones.
+//!
+//! Customers are notified when their order is confirmed, when the courier arrives
+//! at the delivery destination, and when the order is delivered. Every customer
+//! prefers one channel, sampled once from the channel weights based on their id, so
+//! a customer receives all notifications through the same channel.
+//!
+//! Each notification is reported as a sequence of
+//! [`NotificationUpdatedPayload`](crate::NotificationUpdatedPayload) events: it is
+//! sent, then either delivered or failed right away. Delivered notifications may be
+//! opened after an exponentially distributed delay, and opened notifications may be
+//! clicked thereafter. Opens and clicks still pending at the end of a run are dropped.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+use crate::idents::{NotificationId, OrderId, PersonId};
+use crate::state::{OrderStatus, State};
+use crate::{CourierActivity, Error, EventPayload, Result};
+
+/// Channel a notification is sent through.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    Push,
+    Sms,
+    Email,
+}
+
+/// Order milestone a notification is sent for.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationTrigger {
+    /// The order was submitted to the site
+    OrderConfirmed,
+    /// The courier arrived at the delivery destination
+    CourierNearby,
+    /// The order was handed to the customer
+    OrderDelivered,
+}
+
+/// Step in the delivery of a notification.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationStatus {
+    /// Notification was handed to the provider of the channel
+    Sent,
+    /// Notification reached the customer
+    Delivered,
+    /// Notification could not be delivered
+    Failed,
+    /// Customer opened the notification
+    Opened,
+    /// Customer followed the link in the notification
+    Clicked,
+}
+
+/// Engagement with notifications sent through one channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelModel {
+    /// Relative share of customers preferring the channel
+    pub weight: f64,
+
+    /// Probability that a sent notification is delivered
+    pub delivery_rate: f64,
+
+    /// Probability that a delivered notification is opened
+    pub open_rate: f64,
+
+    /// Probability that an opened notification is clicked
+    pub click_rate: f64,
+
+    /// Mean time in seconds from delivery to opening the notification
+    pub mean_open_delay_s: f64,
+
+    /// Mean time in seconds from opening to clicking the notification
+    pub mean_click_delay_s: f64,
+}
+
+impl ChannelModel {
+    fn validate(&self, channel: NotificationChannel) -> Result<()> {
+        for (name, rate) in [
+            ("delivery rate", self.delivery_rate),
+            ("open rate", self.open_rate),
+            ("click rate", self.click_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(Error::invalid_data(format!(
+                    "{channel} notification {name} {rate} outside of [0, 1]"
+                )));
+            }
+        }
+        for (name, value) in [
+            ("weight", self.weight),
+            ("mean open delay", self.mean_open_delay_s),
+            ("mean click delay", self.mean_click_delay_s),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(Error::invalid_data(format!(
+                    "{channel} notification {name} {value} must be a non-negative number"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Channels and engagement rates of notifications sent to customers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Milestones customers are notified about
+    pub triggers: Vec<NotificationTrigger>,
+
+    pub push: ChannelModel,
+
+    pub sms: ChannelModel,
+
+    pub email: ChannelModel,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            triggers: vec![
+                NotificationTrigger::OrderConfirmed,
+                NotificationTrigger::CourierNearby,
+                NotificationTrigger::OrderDelivered,
+            ],
+            push: ChannelModel {
+                weight: 0.6,
+                delivery_rate: 0.95,
+                open_rate: 0.35,
+                click_rate: 0.1,
+                mean_open_delay_s: 120.0,
+                mean_click_delay_s: 20.0,
+            },
+            sms: ChannelModel {
+                weight: 0.25,
+                delivery_rate: 0.98,
+                open_rate: 0.9,
+                click_rate: 0.05,
+                mean_open_delay_s: 60.0,
+                mean_click_delay_s: 30.0,
+            },
+            email: ChannelModel {
+                weight: 0.15,
+                delivery_rate: 0.97,
+                open_rate: 0.2,
+                click_rate: 0.04,
+                mean_open_delay_s: 1800.0,
+                mean_click_delay_s: 60.0,
+            },
+        }
+    }
+}
+
+impl NotificationConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for channel in CHANNELS {
+            self.channel(channel).validate(channel)?;
+        }
+        if CHANNELS.iter().all(|c| self.channel(*c).weight == 0.0) {
+            return Err(Error::invalid_data(
+                "at least one notification channel needs a positive weight",
+            ));
+        }
+        Ok(())
+    }
+
+    fn channel(&self, channel: NotificationChannel) -> &ChannelModel {
+        match channel {
+            NotificationChannel::Push => &self.push,
+            NotificationChannel::Sms => &self.sms,
+            NotificationChannel::Email => &self.email,
+        }
+    }
+
+    /// Channel preferred by the customer `person_id`.
+    pub fn preferred_channel(&self, person_id: &PersonId) -> NotificationChannel {
+        let id: &[u8] = person_id.as_ref();
+        let seed = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+        let total: f64 = CHANNELS.iter().map(|c| self.channel(*c).weight).sum();
+        let mut sample = StdRng::seed_from_u64(seed).random::<f64>() * total;
+        for channel in CHANNELS {
+            let weight = self.channel(channel).weight;
+            if sample < weight {
+                return channel;
+            }
+            sample -= weight;
+        }
+        // only reached through rounding, fall back to the last weighted channel
+        CHANNELS
+            .into_iter()
+            .rev()
+            .find(|c| self.channel(*c).weight > 0.0)
+            .unwrap_or(NotificationChannel::Push)
+    }
+}
+
+const CHANNELS: [NotificationChannel; 3] = [
+    NotificationChannel::Push,
+    NotificationChannel::Sms,
+    NotificationChannel::Email,
+];
+
+/// Sends notifications for order milestones and simulates engagement with them.
+pub(crate) struct Notifier {
+    config: NotificationConfig,
+    /// Opens and clicks scheduled for later steps
+    pending: Vec<(DateTime<Utc>, EventPayload)>,
+}
+
+impl Notifier {
+    pub(crate) fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Notification events of a step at `now` that emitted `events`.
+    ///
+    /// Includes opens and clicks of earlier notifications that are due by `now`.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+        state: &State,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<EventPayload>> {
+        let (mut notifications, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(due, _)| *due <= now);
+        self.pending = pending;
+        notifications.sort_by_key(|(due, _)| *due);
+        let mut notifications = notifications
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect::<Vec<_>>();
+
+        for event in events {
+            let (order_id, trigger) = match event {
+                EventPayload::OrderUpdated(p) if p.status == OrderStatus::Submitted => {
+                    (p.order_id, NotificationTrigger::OrderConfirmed)
+                }
+                EventPayload::OrderUpdated(p) if p.status == OrderStatus::Delivered => {
+                    (p.order_id, NotificationTrigger::OrderDelivered)
+                }
+                EventPayload::CourierUpdated(p)
+                    if p.activity == CourierActivity::ArrivedAtCustomer =>
+                {
+                    (p.order_id, NotificationTrigger::CourierNearby)
+                }
+                _ => continue,
+            };
+            if !self.config.triggers.contains(&trigger) {
+                continue;
+            }
+            let Some(order) = state.orders().order(&order_id) else {
+                continue;
+            };
+            let person_id = order.customer_person_id().try_into()?;
+            notifications.extend(self.notify(now, person_id, order_id, trigger, rng));
+        }
+        Ok(notifications)
+    }
+
+    /// Send a notification and schedule the customer's engagement with it.
+    ///
+    /// Returns the events of sending and delivering the notification.
+    fn notify(
+        &mut self,
+        now: DateTime<Utc>,
+        person_id: PersonId,
+        order_id: OrderId,
+        trigger: NotificationTrigger,
+        rng: &mut impl Rng,
+    ) -> Vec<EventPayload> {
+        let channel = self.config.preferred_channel(&person_id);
+        let model = self.config.channel(channel);
+        let notification_id = NotificationId::from_rng(now, rng);
+        let event = |status| {
+            EventPayload::notification_updated(
+                notification_id,
+                person_id,
+                order_id,
+                channel,
+                trigger,
+                status,
+            )
+        };
+
+        if !rng.random_bool(model.delivery_rate) {
+            return vec![
+                event(NotificationStatus::Sent),
+                event(NotificationStatus::Failed),
+            ];
+        }
+        if rng.random_bool(model.open_rate) {
+            let opened_at = now + exponential_delay(rng, model.mean_open_delay_s);
+            self.pending
+                .push((opened_at, event(NotificationStatus::Opened)));
+            if rng.random_bool(model.click_rate) {
+                let clicked_at = opened_at + exponential_delay(rng, model.mean_click_delay_s);
+                self.pending
+                    .push((clicked_at, event(NotificationStatus::Clicked)));
+            }
+        }
+        vec![
+            event(NotificationStatus::Sent),
+            event(NotificationStatus::Delivered),
+        ]
+    }
+}
+
+/// Sample an exponentially distributed delay with mean `mean_s` seconds.
+pub(crate) fn exponential_delay(rng: &mut impl Rng, mean_s: f64) -> Duration {
+    // shift into (0, 1] to keep the logarithm finite
+    let u: f64 = 1.0 - rng.random::<f64>();
+    Duration::milliseconds((-mean_s * u.ln() * 1000.0) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(events: &[EventPayload]) -> Vec<NotificationStatus> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::NotificationUpdated(p) => Some(p.status),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn model(delivery_rate: f64, open_rate: f64, click_rate: f64) -> ChannelModel {
+        ChannelModel {
+            weight: 1.0,
+            delivery_rate,
+            open_rate,
+            click_rate,
+            mean_open_delay_s: 60.0,
+            mean_click_delay_s: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_notify() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let now = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let (person_id, order_id) = (PersonId::new(), OrderId::new());
+
+        let config = NotificationConfig {
+            push: model(1.0, 1.0, 1.0),
+            sms: ChannelModel {
+                weight: 0.0,
+                ..model(1.0, 1.0, 1.0)
+            },
+            email: ChannelModel {
+                weight: 0.0,
+                ..model(1.0, 1.0, 1.0)
+            },
+            ..Default::default()
+        };
+        config.validate().unwrap();
+        assert_eq!(
+            config.preferred_channel(&person_id),
+            NotificationChannel::Push
+        );
+
+        let mut notifier = Notifier::new(config);
+        let events = notifier.notify(
+            now,
+            person_id,
+            order_id,
+            NotificationTrigger::OrderDelivered,
+            &mut rng,
+        );
+        assert_eq!(
+            statuses(&events),
+            [NotificationStatus::Sent, NotificationStatus::Delivered]
+        );
+        assert_eq!(notifier.pending.len(), 2);
+        let (opened_at, _) = notifier.pending[0];
+        let (clicked_at, _) = notifier.pending[1];
+        assert!(opened_at >= now && clicked_at >= opened_at);
+
+        // failed notifications are never opened
+        notifier.config.push = model(0.0, 1.0, 1.0);
+        notifier.pending.clear();
+        let events = notifier.notify(
+            now,
+            person_id,
+            order_id,
+            NotificationTrigger::OrderConfirmed,
+            &mut rng,
+        );
+        assert_eq!(
+            statuses(&events),
+            [NotificationStatus::Sent, NotificationStatus::Failed]
+        );
+        assert!(notifier.pending.is_empty());
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(NotificationConfig::default().validate().is_ok());
+        let config = NotificationConfig {
+            email: model(1.5, 0.5, 0.5),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
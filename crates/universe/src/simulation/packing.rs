@@ -0,0 +1,500 @@
+//! Packing of orders at a site.
+//!
+//! The lines of an order are cooked in parallel, possibly in different kitchens of
+//! the site. Once the last line is cooked, the order waits for one of the packing
+//! stations of the site, where it is packed for [`PackingConfig::duration`]. Only
+//! then are its lines reported ready, so the order becomes ready for pickup as a
+//! whole. The time to prepare an order is therefore determined by its slowest line
+//! plus packing, rather than the sum over all lines.
+//!
+//! Packing and sealing an order consumes packaging supplies, e.g. bags, containers
+//! and seals, which every site keeps in stock. Supplies are reordered once they fall
+//! to their reorder point and arrive after a lead time. Orders cannot be packed while
+//! any of the supplies they need is out of stock, so sites with more demand during
+//! the lead time than the reorder point covers run into shortages that hold up
+//! orders. Stocks start from the configured initial stock in every run.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::idents::{OrderId, OrderLineId, SiteId};
+use crate::state::OrderLineStatus;
+use crate::{Error, EventPayload, Result, SupplyActivity};
+
+/// A packaging supply used when packing orders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackagingSupply {
+    /// Name of the supply, reported on supply events
+    pub name: String,
+
+    /// Units used to pack every order
+    #[serde(default)]
+    pub per_order: f64,
+
+    /// Additional units used for every line of an order
+    #[serde(default)]
+    pub per_line: f64,
+
+    /// Units in stock at every site at the start of a run
+    pub initial_stock: f64,
+
+    /// Stock at or below which the supply is reordered
+    pub reorder_point: f64,
+
+    /// Units delivered with every reorder
+    pub reorder_quantity: f64,
+
+    /// Hours from reordering the supply until it arrives at the site
+    pub lead_time_hours: f64,
+}
+
+impl PackagingSupply {
+    fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(Error::invalid_data("packaging supplies need a name"));
+        }
+        let valid = [
+            self.per_order,
+            self.per_line,
+            self.initial_stock,
+            self.reorder_point,
+            self.lead_time_hours,
+        ]
+        .iter()
+        .all(|value| *value >= 0.0 && value.is_finite());
+        if !valid || self.reorder_quantity.is_nan() || self.reorder_quantity <= 0.0 {
+            return Err(Error::invalid_data(format!(
+                "packaging supply '{}' needs non-negative usage and stock and a positive reorder quantity",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Units needed to pack an order with `lines` order lines.
+    fn required(&self, lines: usize) -> f64 {
+        self.per_order + self.per_line * lines as f64
+    }
+
+    fn lead_time(&self) -> Duration {
+        Duration::milliseconds((self.lead_time_hours * 3_600_000.0) as i64)
+    }
+}
+
+fn default_supplies() -> Vec<PackagingSupply> {
+    vec![
+        PackagingSupply {
+            name: "bags".into(),
+            per_order: 1.0,
+            per_line: 0.0,
+            initial_stock: 150.0,
+            reorder_point: 40.0,
+            reorder_quantity: 150.0,
+            lead_time_hours: 6.0,
+        },
+        PackagingSupply {
+            name: "containers".into(),
+            per_order: 0.0,
+            per_line: 1.0,
+            initial_stock: 400.0,
+            reorder_point: 100.0,
+            reorder_quantity: 400.0,
+            lead_time_hours: 6.0,
+        },
+        PackagingSupply {
+            name: "seals".into(),
+            per_order: 1.0,
+            per_line: 0.0,
+            initial_stock: 300.0,
+            reorder_point: 60.0,
+            reorder_quantity: 300.0,
+            lead_time_hours: 12.0,
+        },
+    ]
+}
+
+/// Packing stations of a site and the time it takes to pack an order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackingConfig {
+    /// Number of orders that can be packed at the same time at a site
+    pub stations: usize,
+
+    /// Seconds it takes to pack an order regardless of its size
+    pub base_secs: i64,
+
+    /// Additional seconds it takes to pack each line of an order
+    pub per_line_secs: i64,
+
+    /// Packaging supplies consumed when packing orders
+    pub supplies: Vec<PackagingSupply>,
+}
+
+impl Default for PackingConfig {
+    fn default() -> Self {
+        Self {
+            stations: 2,
+            base_secs: 60,
+            per_line_secs: 15,
+            supplies: default_supplies(),
+        }
+    }
+}
+
+impl PackingConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.stations == 0 {
+            return Err(Error::invalid_data(
+                "sites need at least one packing station",
+            ));
+        }
+        if self.base_secs < 0 || self.per_line_secs < 0 {
+            return Err(Error::invalid_data("packing times must not be negative"));
+        }
+        for (idx, supply) in self.supplies.iter().enumerate() {
+            supply.validate()?;
+            if self.supplies[..idx]
+                .iter()
+                .any(|other| other.name == supply.name)
+            {
+                return Err(Error::invalid_data(format!(
+                    "duplicate packaging supply '{}'",
+                    supply.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Time it takes to pack an order with `lines` order lines.
+    pub fn duration(&self, lines: usize) -> Duration {
+        Duration::seconds(self.base_secs + self.per_line_secs * lines as i64)
+    }
+}
+
+/// Lines of an order, and which of them are cooked.
+#[derive(Debug, Clone, Default)]
+struct PackingOrder {
+    lines: Vec<OrderLineId>,
+    cooked: usize,
+}
+
+/// Stock of a packaging supply at a site.
+#[derive(Debug, Clone)]
+struct SupplyStock {
+    supply: PackagingSupply,
+    stock: f64,
+    /// Time reordered supplies arrive, if reordered
+    restock_at: Option<DateTime<Utc>>,
+    /// Whether orders are held up by the supply being out of stock
+    depleted: bool,
+}
+
+/// Synchronizes the cooked lines of orders and packs complete orders.
+#[derive(Debug, Clone)]
+pub(crate) struct Packer {
+    site_id: SiteId,
+    config: PackingConfig,
+    supplies: Vec<SupplyStock>,
+    orders: HashMap<OrderId, PackingOrder>,
+    /// Orders with all lines cooked, waiting for a packing station
+    waiting: VecDeque<OrderId>,
+    /// Orders being packed and the time they are packed at
+    packing: Vec<(OrderId, DateTime<Utc>)>,
+}
+
+impl Packer {
+    pub(crate) fn new(site_id: SiteId, config: PackingConfig) -> Self {
+        let supplies = config
+            .supplies
+            .iter()
+            .map(|supply| SupplyStock {
+                supply: supply.clone(),
+                stock: supply.initial_stock,
+                restock_at: None,
+                depleted: false,
+            })
+            .collect();
+        Self {
+            site_id,
+            config,
+            supplies,
+            orders: HashMap::new(),
+            waiting: VecDeque::new(),
+            packing: Vec::new(),
+        }
+    }
+
+    /// Expect the given lines of an order to be cooked before packing it.
+    pub(crate) fn expect(&mut self, order_id: OrderId, lines: Vec<OrderLineId>) {
+        if lines.is_empty() {
+            return;
+        }
+        self.orders
+            .insert(order_id, PackingOrder { lines, cooked: 0 });
+    }
+
+    /// Record a cooked order line.
+    ///
+    /// Returns `false` if the order is not expected, in which case the line
+    /// does not need packing.
+    pub(crate) fn cooked(&mut self, order_id: &OrderId) -> bool {
+        let Some(order) = self.orders.get_mut(order_id) else {
+            return false;
+        };
+        order.cooked += 1;
+        if order.cooked == order.lines.len() {
+            self.waiting.push_back(*order_id);
+        }
+        true
+    }
+
+    /// Stop expecting a line which was removed from its order.
+    ///
+    /// Returns `true` if no lines of the order are left to pack.
+    pub(crate) fn remove_line(&mut self, order_id: &OrderId, line_id: &OrderLineId) -> bool {
+        let Some(order) = self.orders.get_mut(order_id) else {
+            return false;
+        };
+        order.lines.retain(|id| id != line_id);
+        if order.lines.is_empty() {
+            self.orders.remove(order_id);
+            return true;
+        }
+        if order.cooked == order.lines.len() {
+            self.waiting.push_back(*order_id);
+        }
+        false
+    }
+
+    /// Forget an order which failed before it was packed.
+    pub(crate) fn discard(&mut self, order_id: &OrderId) {
+        self.orders.remove(order_id);
+        self.waiting.retain(|id| id != order_id);
+    }
+
+    /// Advance packing to `now`.
+    ///
+    /// Returns ready updates for the lines of all orders packed by then,
+    /// and updates of the packaging supplies.
+    pub(crate) fn step(&mut self, now: DateTime<Utc>) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        for stock in self.supplies.iter_mut() {
+            if stock.restock_at.is_some_and(|restock_at| restock_at <= now) {
+                stock.restock_at = None;
+                stock.depleted = false;
+                stock.stock += stock.supply.reorder_quantity;
+                events.push(supply_updated(
+                    self.site_id,
+                    stock,
+                    SupplyActivity::Restocked,
+                ));
+            }
+        }
+
+        let orders = &mut self.orders;
+        self.packing.retain(|(order_id, packed_at)| {
+            if *packed_at > now {
+                return true;
+            }
+            if let Some(order) = orders.remove(order_id) {
+                events.extend(order.lines.into_iter().map(|id| {
+                    EventPayload::order_line_updated(id, OrderLineStatus::Ready, None, None)
+                }));
+            }
+            false
+        });
+
+        while self.packing.len() < self.config.stations
+            && let Some(order_id) = self.waiting.front().copied()
+        {
+            let lines = self.orders.get(&order_id).map_or(0, |o| o.lines.len());
+            let mut short = false;
+            for stock in self.supplies.iter_mut() {
+                if stock.stock >= stock.supply.required(lines) {
+                    continue;
+                }
+                short = true;
+                if !stock.depleted {
+                    stock.depleted = true;
+                    events.push(supply_updated(
+                        self.site_id,
+                        stock,
+                        SupplyActivity::Depleted,
+                    ));
+                }
+            }
+            if !short {
+                for stock in self.supplies.iter_mut() {
+                    stock.stock -= stock.supply.required(lines);
+                }
+                self.waiting.pop_front();
+                self.packing
+                    .push((order_id, now + self.config.duration(lines)));
+            }
+            for stock in self.supplies.iter_mut() {
+                if stock.restock_at.is_none() && stock.stock <= stock.supply.reorder_point {
+                    stock.restock_at = Some(now + stock.supply.lead_time());
+                    events.push(supply_updated(
+                        self.site_id,
+                        stock,
+                        SupplyActivity::Reordered,
+                    ));
+                }
+            }
+            if short {
+                break;
+            }
+        }
+        events
+    }
+
+    /// Earliest time an order is packed, `now` if orders wait without any being packed.
+    pub(crate) fn next_event(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let packed = self.packing.iter().map(|(_, packed_at)| *packed_at).min();
+        if self.waiting.is_empty() {
+            packed
+        } else {
+            Some(packed.unwrap_or(now))
+        }
+    }
+
+    /// Number of orders waiting for or occupying a packing station.
+    pub(crate) fn queued(&self) -> usize {
+        self.waiting.len() + self.packing.len()
+    }
+}
+
+fn supply_updated(site_id: SiteId, stock: &SupplyStock, activity: SupplyActivity) -> EventPayload {
+    EventPayload::supply_updated(site_id, stock.supply.name.clone(), activity, stock.stock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready(events: &[EventPayload]) -> Vec<OrderLineId> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::OrderLineUpdated(p) if p.status == OrderLineStatus::Ready => {
+                    Some(p.order_line_id)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn supplies(events: &[EventPayload]) -> Vec<(SupplyActivity, f64)> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::SupplyUpdated(p) => Some((p.activity, p.stock)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pack_complete_orders() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut packer = Packer::new(
+            SiteId::from_name("london"),
+            PackingConfig {
+                stations: 1,
+                base_secs: 60,
+                per_line_secs: 30,
+                supplies: vec![],
+            },
+        );
+        let (large, small) = (OrderId::new(), OrderId::new());
+        let large_lines = vec![OrderLineId::new(), OrderLineId::new()];
+        packer.expect(large, large_lines.clone());
+        packer.expect(small, vec![OrderLineId::new()]);
+
+        // orders are only packed once all their lines are cooked
+        assert!(packer.cooked(&large));
+        assert!(packer.cooked(&small));
+        assert!(!packer.cooked(&OrderId::new()));
+        assert!(packer.step(start).is_empty());
+        assert_eq!(packer.queued(), 1);
+
+        // the small order occupies the only packing station for 90 seconds
+        assert!(packer.cooked(&large));
+        assert!(packer.step(start + Duration::seconds(60)).is_empty());
+        assert_eq!(packer.queued(), 2);
+        assert_eq!(ready(&packer.step(start + Duration::seconds(90))).len(), 1);
+
+        // packing the large order takes two minutes
+        assert!(packer.step(start + Duration::seconds(180)).is_empty());
+        assert_eq!(
+            ready(&packer.step(start + Duration::seconds(210))),
+            large_lines
+        );
+        assert_eq!(packer.queued(), 0);
+    }
+
+    #[test]
+    fn test_supply_shortage() {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut packer = Packer::new(
+            SiteId::from_name("london"),
+            PackingConfig {
+                stations: 2,
+                base_secs: 0,
+                per_line_secs: 0,
+                supplies: vec![PackagingSupply {
+                    name: "bags".into(),
+                    per_order: 1.0,
+                    per_line: 0.0,
+                    initial_stock: 1.0,
+                    reorder_point: 0.0,
+                    reorder_quantity: 5.0,
+                    lead_time_hours: 1.0,
+                }],
+            },
+        );
+        let (first, second) = (OrderId::new(), OrderId::new());
+        let second_line = OrderLineId::new();
+        packer.expect(first, vec![OrderLineId::new()]);
+        packer.expect(second, vec![second_line]);
+        packer.cooked(&first);
+        packer.cooked(&second);
+
+        // the first order uses up the last bag, holding up the second order
+        let events = packer.step(start);
+        assert_eq!(
+            supplies(&events),
+            [
+                (SupplyActivity::Reordered, 0.0),
+                (SupplyActivity::Depleted, 0.0)
+            ]
+        );
+        assert_eq!(ready(&packer.step(start + Duration::minutes(1))).len(), 1);
+        assert!(packer.step(start + Duration::minutes(30)).is_empty());
+        assert_eq!(packer.queued(), 1);
+
+        // the order is packed once bags are restocked
+        let events = packer.step(start + Duration::hours(1));
+        assert_eq!(supplies(&events), [(SupplyActivity::Restocked, 5.0)]);
+        let events = packer.step(start + Duration::hours(1) + Duration::minutes(1));
+        assert_eq!(ready(&events), [second_line]);
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(PackingConfig::default().validate().is_ok());
+        let config = PackingConfig {
+            stations: 0,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+        assert_eq!(PackingConfig::default().duration(3), Duration::seconds(105));
+
+        let mut config = PackingConfig::default();
+        config.supplies[1].name = "bags".into();
+        assert!(config.validate().is_err());
+    }
+}
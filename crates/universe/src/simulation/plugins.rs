@@ -0,0 +1,55 @@
+//! Plugins customizing behavior models of the simulation.
+//!
+//! Where [`BehaviorHooks`](super::BehaviorHooks) cover behaviors that can be expressed
+//! as SQL, plugins are called from within the simulation agents and may implement
+//! arbitrary logic. Each hook has a default implementation that keeps the built-in
+//! behavior, so plugins only need to implement the hooks they care about.
+//!
+//! With the `wasm` feature enabled, plugins can be provided as WebAssembly modules
+//! via `WasmPlugin`, which allows extending the simulation without linking Rust code.
+
+use std::fmt::Debug;
+
+use crate::Result;
+
+#[cfg(feature = "wasm")]
+pub use self::wasm::*;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+/// Hooks called by the simulation agents to make behavioral decisions.
+pub trait BehaviorPlugin: Debug + Send + Sync {
+    /// Probability that an idle customer places an order in the current step.
+    ///
+    /// `default` is the probability the simulation would use without the plugin,
+    /// including the result of a configured `order_probability` SQL hook. The result
+    /// is clamped to `[0, 1]`, NaN is treated as zero.
+    fn score_order_probability(&self, hour_of_day: f64, default: f64) -> Result<f64> {
+        let _ = hour_of_day;
+        Ok(default)
+    }
+
+    /// Choose the menu items for a new order.
+    ///
+    /// Returns indices into the `num_candidates` available menu items, or `None`
+    /// to pick `basket_size` items at random.
+    fn choose_menu_items(
+        &self,
+        num_candidates: usize,
+        basket_size: usize,
+    ) -> Result<Option<Vec<usize>>> {
+        let _ = (num_candidates, basket_size);
+        Ok(None)
+    }
+
+    /// Whether a courier accepts the delivery of an order.
+    ///
+    /// Returns `None` to sample the decision from the configured
+    /// [`CourierAcceptance`](super::CourierAcceptance) model. Rejected orders remain
+    /// ready for pickup and are offered again in the next step.
+    fn accept_assignment(&self, distance_m: f64, hour_of_day: f64) -> Result<Option<bool>> {
+        let _ = (distance_m, hour_of_day);
+        Ok(None)
+    }
+}
@@ -0,0 +1,244 @@
+//! WebAssembly implementation of [`BehaviorPlugin`].
+//!
+//! A plugin module may export any of the following functions, missing exports fall
+//! back to the built-in behavior:
+//!
+//! - `score_order_probability(hour_of_day: f64, default: f64) -> f64`
+//! - `choose_menu_items(num_candidates: i32, basket_size: i32)`: selects items by calling
+//!   the imported `caspers.select_menu_item(index: i32)` once per item. If no item is
+//!   selected, items are chosen at random.
+//! - `accept_assignment(distance_m: f64, hour_of_day: f64) -> i32`: non-zero to accept. If not
+//!   exported, the configured courier acceptance model decides.
+//!
+//! Modules are sandboxed: `caspers.select_menu_item` is the only host function available,
+//! memory is capped and every call is limited in the amount of work it may perform.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use wasmtime::{
+    Caller, Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc, WasmParams, WasmResults,
+};
+
+use crate::{Error, Result};
+
+use super::BehaviorPlugin;
+
+/// Maximum linear memory a plugin may allocate.
+const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Fuel available to every single hook invocation.
+const FUEL_PER_CALL: u64 = 1_000_000;
+
+struct HostState {
+    selection: Vec<i32>,
+    limits: StoreLimits,
+}
+
+struct PluginInstance {
+    store: Store<HostState>,
+    score_order_probability: Option<TypedFunc<(f64, f64), f64>>,
+    choose_menu_items: Option<TypedFunc<(i32, i32), ()>>,
+    accept_assignment: Option<TypedFunc<(f64, f64), i32>>,
+}
+
+/// A behavior plugin compiled from a WebAssembly module.
+pub struct WasmPlugin {
+    name: String,
+    instance: Mutex<PluginInstance>,
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPlugin")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmPlugin {
+    /// Compile and instantiate a plugin from a WebAssembly binary or text module.
+    pub fn try_new(name: impl Into<String>, module: impl AsRef<[u8]>) -> Result<Self> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+        let module = Module::new(&engine, module)?;
+
+        let mut linker = Linker::new(&engine);
+        linker.func_wrap(
+            "caspers",
+            "select_menu_item",
+            |mut caller: Caller<'_, HostState>, index: i32| {
+                caller.data_mut().selection.push(index);
+            },
+        )?;
+
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                selection: Vec::new(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(MAX_MEMORY_BYTES)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CALL)?;
+        let instance = linker.instantiate(&mut store, &module)?;
+
+        let score_order_probability = typed_func(&mut store, &instance, "score_order_probability")?;
+        let choose_menu_items = typed_func(&mut store, &instance, "choose_menu_items")?;
+        let accept_assignment = typed_func(&mut store, &instance, "accept_assignment")?;
+
+        Ok(Self {
+            name: name.into(),
+            instance: Mutex::new(PluginInstance {
+                store,
+                score_order_probability,
+                choose_menu_items,
+                accept_assignment,
+            }),
+        })
+    }
+
+    /// Load a plugin from a `.wasm` or `.wat` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let module = std::fs::read(path)?;
+        Self::try_new(path.display().to_string(), module)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn with_instance<T>(&self, f: impl FnOnce(&mut PluginInstance) -> Result<T>) -> Result<T> {
+        let mut instance = self
+            .instance
+            .lock()
+            .map_err(|_| Error::internal(format!("plugin '{}' is poisoned", self.name)))?;
+        instance.store.set_fuel(FUEL_PER_CALL)?;
+        f(&mut instance)
+    }
+}
+
+impl BehaviorPlugin for WasmPlugin {
+    fn score_order_probability(&self, hour_of_day: f64, default: f64) -> Result<f64> {
+        self.with_instance(|instance| match &instance.score_order_probability {
+            Some(func) => Ok(func.call(&mut instance.store, (hour_of_day, default))?),
+            None => Ok(default),
+        })
+    }
+
+    fn choose_menu_items(
+        &self,
+        num_candidates: usize,
+        basket_size: usize,
+    ) -> Result<Option<Vec<usize>>> {
+        self.with_instance(|instance| {
+            let Some(func) = &instance.choose_menu_items else {
+                return Ok(None);
+            };
+            instance.store.data_mut().selection.clear();
+            func.call(
+                &mut instance.store,
+                (num_candidates as i32, basket_size as i32),
+            )?;
+            let selection = std::mem::take(&mut instance.store.data_mut().selection);
+            if selection.is_empty() {
+                return Ok(None);
+            }
+            selection
+                .into_iter()
+                .map(|idx| match usize::try_from(idx) {
+                    Ok(idx) if idx < num_candidates => Ok(idx),
+                    _ => Err(Error::invalid_data(format!(
+                        "plugin '{}' selected menu item {idx} out of {num_candidates}",
+                        self.name
+                    ))),
+                })
+                .collect::<Result<_>>()
+                .map(Some)
+        })
+    }
+
+    fn accept_assignment(&self, distance_m: f64, hour_of_day: f64) -> Result<Option<bool>> {
+        self.with_instance(|instance| match &instance.accept_assignment {
+            Some(func) => Ok(Some(
+                func.call(&mut instance.store, (distance_m, hour_of_day))? != 0,
+            )),
+            None => Ok(None),
+        })
+    }
+}
+
+/// Look up an optional export, failing if it exists with an unexpected signature.
+fn typed_func<P: WasmParams, R: WasmResults>(
+    store: &mut Store<HostState>,
+    instance: &Instance,
+    name: &str,
+) -> Result<Option<TypedFunc<P, R>>> {
+    if instance.get_export(&mut *store, name).is_none() {
+        return Ok(None);
+    }
+    Ok(Some(instance.get_typed_func(&mut *store, name)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLUGIN: &str = r#"
+        (module
+            (import "caspers" "select_menu_item" (func $select (param i32)))
+            (func (export "score_order_probability") (param f64 f64) (result f64)
+                (f64.mul (local.get 1) (f64.const 2)))
+            (func (export "choose_menu_items") (param i32 i32)
+                (call $select (i32.sub (local.get 0) (i32.const 1)))
+                (call $select (i32.const 0)))
+            (func (export "accept_assignment") (param f64 f64) (result i32)
+                (f64.lt (local.get 0) (f64.const 1000))))
+    "#;
+
+    #[test]
+    fn test_wasm_plugin() -> Result<()> {
+        let plugin = WasmPlugin::try_new("test", PLUGIN)?;
+
+        assert_eq!(plugin.score_order_probability(12.0, 0.1)?, 0.2);
+        assert_eq!(plugin.choose_menu_items(5, 3)?, Some(vec![4, 0]));
+        assert_eq!(plugin.accept_assignment(500.0, 12.0)?, Some(true));
+        assert_eq!(plugin.accept_assignment(1500.0, 12.0)?, Some(false));
+
+        // out of range selections are rejected
+        assert!(plugin.choose_menu_items(0, 3).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wasm_plugin_defaults() -> Result<()> {
+        let plugin = WasmPlugin::try_new("empty", "(module)")?;
+        assert_eq!(plugin.score_order_probability(12.0, 0.1)?, 0.1);
+        assert_eq!(plugin.choose_menu_items(5, 3)?, None);
+        assert!(plugin.accept_assignment(1500.0, 12.0)?);
+
+        // runaway plugins are stopped
+        let plugin = WasmPlugin::try_new(
+            "loop",
+            r#"(module (func (export "accept_assignment") (param f64 f64) (result i32)
+                (loop (br 0)) (i32.const 1)))"#,
+        )?;
+        assert!(plugin.accept_assignment(0.0, 0.0).is_err());
+
+        // unexpected signatures fail on load
+        let result = WasmPlugin::try_new(
+            "invalid",
+            r#"(module (func (export "accept_assignment") (result i32) (i32.const 1)))"#,
+        );
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
@@ -75,6 +75,19 @@ static ORDER_CREATED_FIELD: LazyLock<FieldRef> = LazyLock::new(|| {
                     ))),
                     false,
                 ),
+                Field::new("total", DataType::Float64, false),
+                Field::new("channel", DataType::Utf8, false),
+                Field::new(
+                    "promised_at",
+                    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                    false,
+                ),
+                Field::new(
+                    "campaigns",
+                    DataType::List(Arc::new(Field::new("item", DataType::Utf8, false))),
+                    false,
+                ),
+                Field::new("tip", DataType::Float64, true),
             ]
             .into(),
         ),
@@ -93,6 +106,7 @@ static SITE_CHECK_IN_FIELD: LazyLock<FieldRef> = LazyLock::new(|| {
                     false,
                 ),
                 Field::new("site_id", DataType::FixedSizeBinary(16), false),
+                Field::new("person_id", DataType::FixedSizeBinary(16), false),
             ]
             .into(),
         ),
@@ -102,7 +116,7 @@ static SITE_CHECK_IN_FIELD: LazyLock<FieldRef> = LazyLock::new(|| {
 
 static SITE_CHECK_OUT_FIELD: LazyLock<FieldRef> = LazyLock::new(|| {
     FieldRef::new(Field::new(
-        "check_in",
+        "check_out",
         DataType::Struct(
             vec![
                 Field::new(
@@ -110,6 +124,8 @@ static SITE_CHECK_OUT_FIELD: LazyLock<FieldRef> = LazyLock::new(|| {
                     DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
                     false,
                 ),
+                Field::new("site_id", DataType::FixedSizeBinary(16), false),
+                Field::new("person_id", DataType::FixedSizeBinary(16), false),
                 Field::new(
                     "orders",
                     DataType::List(Arc::new(Field::new(
@@ -0,0 +1,146 @@
+//! Priority tiers of customers and the orders they place.
+//!
+//! Without a [`PriorityConfig`] all orders are placed in the [`PriorityTier::Normal`]
+//! tier. With one, a share of customers holds a priority subscription and a smaller
+//! share is treated as VIP. The tier is derived from the id of the customer, so a
+//! customer keeps their tier across steps and runs.
+//!
+//! The tier is recorded on the `order_created` event and the order. Kitchens start
+//! the lines of higher tiers first and dispatchers hand their orders to couriers
+//! first, so under load lower tiers wait longer, which the recorded tier allows to
+//! analyze.
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+use crate::idents::PersonId;
+use crate::{Error, Result};
+
+/// Priority tier of the customer placing an order, higher tiers are served first.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumString,
+    Display,
+    AsRefStr,
+    Serialize,
+    Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityTier {
+    #[default]
+    Normal,
+    Priority,
+    Vip,
+}
+
+/// Shares of customers in the tiers above [`PriorityTier::Normal`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriorityConfig {
+    /// Share of customers with a priority subscription
+    pub priority_share: f64,
+
+    /// Share of customers treated as VIP
+    pub vip_share: f64,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            priority_share: 0.1,
+            vip_share: 0.02,
+        }
+    }
+}
+
+impl PriorityConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (name, share) in [("priority", self.priority_share), ("vip", self.vip_share)] {
+            if !(0.0..=1.0).contains(&share) {
+                return Err(Error::invalid_data(format!(
+                    "{name} share {share} outside of [0, 1]"
+                )));
+            }
+        }
+        if self.priority_share + self.vip_share > 1.0 {
+            return Err(Error::invalid_data(
+                "priority and vip shares cannot exceed all customers",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tier of the customer with id `person_id`.
+    pub(crate) fn tier(&self, person_id: &PersonId) -> PriorityTier {
+        let id: &[u8] = person_id.as_ref();
+        let key = u64::from_le_bytes(id[8..].try_into().expect("uuids have 16 bytes"));
+        let fraction = key as f64 / u64::MAX as f64;
+        if fraction < self.vip_share {
+            PriorityTier::Vip
+        } else if fraction < self.vip_share + self.priority_share {
+            PriorityTier::Priority
+        } else {
+            PriorityTier::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers() {
+        let config = PriorityConfig {
+            priority_share: 0.2,
+            vip_share: 0.1,
+        };
+        let customers: Vec<_> = (0..2000).map(|_| PersonId::new()).collect();
+        let count = |tier| customers.iter().filter(|c| config.tier(c) == tier).count();
+        assert!((100..300).contains(&count(PriorityTier::Vip)));
+        assert!((300..500).contains(&count(PriorityTier::Priority)));
+        assert!(customers.iter().all(|c| config.tier(c) == config.tier(c)));
+
+        let nobody = PriorityConfig {
+            priority_share: 0.0,
+            vip_share: 0.0,
+        };
+        assert!(
+            customers
+                .iter()
+                .all(|c| nobody.tier(c) == PriorityTier::Normal)
+        );
+        assert!(PriorityTier::Vip > PriorityTier::Priority);
+        assert!(PriorityTier::Priority > PriorityTier::Normal);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PriorityConfig::default().validate().is_ok());
+        for config in [
+            PriorityConfig {
+                priority_share: 1.5,
+                ..Default::default()
+            },
+            PriorityConfig {
+                vip_share: -0.1,
+                ..Default::default()
+            },
+            PriorityConfig {
+                priority_share: 0.6,
+                vip_share: 0.5,
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Isolation of sites whose steps keep failing.
+//!
+//! A failing site step must not abort the whole run. Its runner is rolled back to
+//! the state before the step and the events it was given are re-delivered in the
+//! next step. Sites that fail for a number of consecutive steps are quarantined:
+//! they no longer receive orders or advance, while all other sites continue.
+//! Runners are only saved before a step if a failure would not quarantine them,
+//! as a quarantined runner is never stepped again.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::agents::SiteRunner;
+use crate::idents::SiteId;
+
+/// Default number of consecutive failed steps after which a site is quarantined.
+pub const DEFAULT_SITE_FAILURE_THRESHOLD: usize = 3;
+
+/// Circuit breaker tracking consecutive step failures per site.
+#[derive(Debug, Clone)]
+pub(crate) struct SiteQuarantine {
+    threshold: usize,
+    failures: HashMap<SiteId, usize>,
+    quarantined: HashSet<SiteId>,
+}
+
+impl SiteQuarantine {
+    /// Quarantine sites after `threshold` consecutive failures.
+    ///
+    /// A threshold of zero is treated as one, i.e. sites are quarantined on their
+    /// first failure.
+    pub(crate) fn new(threshold: usize) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            failures: HashMap::new(),
+            quarantined: HashSet::new(),
+        }
+    }
+
+    pub(crate) fn is_quarantined(&self, site_id: &SiteId) -> bool {
+        self.quarantined.contains(site_id)
+    }
+
+    pub(crate) fn quarantined(&self) -> &HashSet<SiteId> {
+        &self.quarantined
+    }
+
+    /// Whether a site keeps running if its next step fails, i.e. whether the
+    /// failure would not quarantine it.
+    pub(crate) fn survives_failure(&self, site_id: &SiteId) -> bool {
+        self.failures.get(site_id).copied().unwrap_or_default() + 1 < self.threshold
+    }
+
+    /// Reset the failure count of a site after a successful step.
+    pub(crate) fn record_success(&mut self, site_id: &SiteId) {
+        self.failures.remove(site_id);
+    }
+
+    /// Count a failed step of a site.
+    ///
+    /// Returns `true` if the site was quarantined by this failure.
+    pub(crate) fn record_failure(&mut self, site_id: &SiteId) -> bool {
+        let failures = self.failures.entry(*site_id).or_default();
+        *failures += 1;
+        *failures >= self.threshold && self.quarantined.insert(*site_id)
+    }
+}
+
+impl Default for SiteQuarantine {
+    fn default() -> Self {
+        Self::new(DEFAULT_SITE_FAILURE_THRESHOLD)
+    }
+}
+
+/// Runners taken from a simulation to step their sites, with copies saved before the
+/// step for sites which survive a failure.
+///
+/// The steps run in tasks of their own, which are aborted if the step of the
+/// simulation is dropped part way and take their runners with them. Saved copies not
+/// yet handed back are restored when the checkpoints are dropped.
+pub(crate) struct SiteCheckpoints<'a> {
+    sites: &'a mut BTreeMap<SiteId, SiteRunner>,
+    saved: HashMap<SiteId, SiteRunner>,
+}
+
+impl<'a> SiteCheckpoints<'a> {
+    pub(crate) fn new(sites: &'a mut BTreeMap<SiteId, SiteRunner>) -> Self {
+        Self {
+            sites,
+            saved: HashMap::new(),
+        }
+    }
+
+    /// Take the runner of a site to step it, saving a copy first if `save`.
+    pub(crate) fn take(&mut self, site_id: &SiteId, save: bool) -> Option<SiteRunner> {
+        let site = self.sites.remove(site_id)?;
+        if save {
+            self.saved.insert(*site_id, site.clone());
+        }
+        Some(site)
+    }
+
+    /// Hand back the runner of a site after its step, rolled back to the saved copy
+    /// if the step `failed`.
+    pub(crate) fn restore(&mut self, site_id: SiteId, site: SiteRunner, failed: bool) {
+        let site = match self.saved.remove(&site_id) {
+            Some(saved) if failed => saved,
+            _ => site,
+        };
+        self.sites.insert(site_id, site);
+    }
+}
+
+impl Drop for SiteCheckpoints<'_> {
+    fn drop(&mut self) {
+        for (site_id, saved) in self.saved.drain() {
+            self.sites.entry(site_id).or_insert(saved);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_quarantine() {
+        let site = SiteId::from_name("site");
+        let other = SiteId::from_name("other");
+        let mut quarantine = SiteQuarantine::new(2);
+        assert!(!SiteQuarantine::new(1).survives_failure(&site));
+
+        // failures need to be consecutive
+        assert!(!quarantine.record_failure(&site));
+        quarantine.record_success(&site);
+        assert!(quarantine.survives_failure(&site));
+        assert!(!quarantine.record_failure(&site));
+        assert!(!quarantine.is_quarantined(&site));
+
+        // the next failure quarantines the site, so it is not rolled back
+        assert!(!quarantine.survives_failure(&site));
+        assert!(quarantine.survives_failure(&other));
+        assert!(quarantine.record_failure(&site));
+        assert!(quarantine.is_quarantined(&site));
+        assert!(!quarantine.is_quarantined(&other));
+
+        // sites are only reported once
+        assert!(!quarantine.record_failure(&site));
+        assert_eq!(quarantine.quarantined().len(), 1);
+    }
+}
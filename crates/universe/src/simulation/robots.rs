@@ -0,0 +1,285 @@
+//! Autonomous delivery by robots and drones (experimental).
+//!
+//! Sites may operate a fleet of [`DeliveryRobots`] next to their couriers, so
+//! futuristic fulfillment can be compared with bike couriers in the same run. Sidewalk
+//! robots follow the streets open to pedestrians, drones fly straight to the customer.
+//! Both travel at a fixed speed and carry a single order, returning to the site before
+//! they take the next one.
+//!
+//! Ready orders are handed to an idle robot whenever the customer is within the range
+//! of the fleet, and are offered to couriers otherwise. Robots are not part of the
+//! population; their progress is reported as
+//! [`RobotDeliveryPayload`](crate::RobotDeliveryPayload) events.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::idents::{OrderId, PersonId, SiteId};
+use crate::state::{OrderStatus, PersonStatus};
+use crate::{Error, EventPayload, Result, RobotActivity, RobotKind};
+
+/// Minutes customers spend eating after their order arrived.
+const EATING_MINS: i64 = 30;
+
+/// A fleet of robots delivering the orders of a site.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliveryRobots {
+    /// Kind of the robots
+    pub kind: RobotKind,
+
+    /// Number of robots operated by the site
+    pub count: usize,
+
+    /// Speed of the robots in km/h
+    pub speed_km_h: f64,
+
+    /// Maximum distance in kilometers from the site to customers served by robots
+    pub max_distance_km: f64,
+}
+
+impl Default for DeliveryRobots {
+    fn default() -> Self {
+        Self {
+            kind: RobotKind::Robot,
+            count: 2,
+            speed_km_h: 6.0,
+            max_distance_km: 2.0,
+        }
+    }
+}
+
+impl DeliveryRobots {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.speed_km_h.is_nan() || self.speed_km_h <= 0.0 {
+            return Err(Error::invalid_data("delivery robots need a positive speed"));
+        }
+        if self.max_distance_km.is_nan() || self.max_distance_km <= 0.0 {
+            return Err(Error::invalid_data(
+                "delivery robots need a positive maximum distance",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Time it takes to travel `distance_m` meters.
+    fn travel_time(&self, distance_m: f64) -> Duration {
+        let seconds = distance_m.max(0.0) / (self.speed_km_h / 3.6);
+        Duration::milliseconds((seconds * 1000.0) as i64)
+    }
+}
+
+/// A delivery a robot is on.
+#[derive(Debug, Clone)]
+struct RobotTrip {
+    order_id: OrderId,
+    customer_id: PersonId,
+    distance_m: f64,
+    delivered_at: DateTime<Utc>,
+    returns_at: DateTime<Utc>,
+    delivered: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Robot {
+    id: Uuid,
+    trip: Option<RobotTrip>,
+}
+
+/// The robots of a site and the deliveries they are on.
+#[derive(Debug, Clone)]
+pub(crate) struct RobotFleet {
+    site_id: SiteId,
+    config: DeliveryRobots,
+    robots: Vec<Robot>,
+}
+
+impl RobotFleet {
+    pub(crate) fn new(site_id: SiteId, config: DeliveryRobots) -> Self {
+        let namespace: &Uuid = site_id.as_ref();
+        let robots = (0..config.count)
+            .map(|index| Robot {
+                // robots keep their ids between runs of a site
+                id: Uuid::new_v5(namespace, format!("robot-{index}").as_bytes()),
+                trip: None,
+            })
+            .collect();
+        Self {
+            site_id,
+            config,
+            robots,
+        }
+    }
+
+    pub(crate) fn kind(&self) -> RobotKind {
+        self.config.kind
+    }
+
+    /// Whether a robot is waiting at the site.
+    pub(crate) fn is_available(&self) -> bool {
+        self.robots.iter().any(|robot| robot.trip.is_none())
+    }
+
+    /// Whether customers `distance_m` meters away from the site are served by robots.
+    pub(crate) fn reaches(&self, distance_m: f64) -> bool {
+        distance_m <= self.config.max_distance_km * 1000.0
+    }
+
+    /// Send an idle robot to deliver the order, returning the events if one was available.
+    pub(crate) fn dispatch(
+        &mut self,
+        order_id: OrderId,
+        customer_id: PersonId,
+        distance_m: f64,
+        now: DateTime<Utc>,
+    ) -> Option<Vec<EventPayload>> {
+        let travel_time = self.config.travel_time(distance_m);
+        let robot = self.robots.iter_mut().find(|robot| robot.trip.is_none())?;
+        robot.trip = Some(RobotTrip {
+            order_id,
+            customer_id,
+            distance_m,
+            delivered_at: now + travel_time,
+            returns_at: now + travel_time * 2,
+            delivered: false,
+        });
+        Some(vec![
+            EventPayload::order_updated(order_id, OrderStatus::PickedUp, None),
+            EventPayload::robot_delivery(
+                robot.id,
+                self.site_id,
+                order_id,
+                self.config.kind,
+                RobotActivity::Dispatched,
+                distance_m,
+            ),
+        ])
+    }
+
+    /// Earliest time a robot arrives at its customer or returns to the site.
+    pub(crate) fn next_event(&self) -> Option<DateTime<Utc>> {
+        self.robots
+            .iter()
+            .filter_map(|robot| robot.trip.as_ref())
+            .map(|trip| {
+                if trip.delivered {
+                    trip.returns_at
+                } else {
+                    trip.delivered_at
+                }
+            })
+            .min()
+    }
+
+    /// Hand over orders robots arrived with and return robots to the site at `now`.
+    pub(crate) fn advance(&mut self, now: DateTime<Utc>) -> Vec<EventPayload> {
+        let mut events = Vec::new();
+        for robot in self.robots.iter_mut() {
+            let Some(trip) = robot.trip.as_mut() else {
+                continue;
+            };
+            let (robot_id, order_id, distance_m) = (robot.id, trip.order_id, trip.distance_m);
+            let progress = |activity| {
+                EventPayload::robot_delivery(
+                    robot_id,
+                    self.site_id,
+                    order_id,
+                    self.config.kind,
+                    activity,
+                    distance_m,
+                )
+            };
+            if !trip.delivered && trip.delivered_at <= now {
+                events.push(EventPayload::order_updated(
+                    order_id,
+                    OrderStatus::Delivered,
+                    None,
+                ));
+                events.push(EventPayload::person_updated(
+                    trip.customer_id,
+                    PersonStatus::Eating(now + Duration::minutes(EATING_MINS)),
+                ));
+                events.push(progress(RobotActivity::Delivered));
+                trip.delivered = true;
+            }
+            if trip.returns_at <= now {
+                events.push(progress(RobotActivity::Returned));
+                robot.trip = None;
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activities(events: &[EventPayload]) -> Vec<RobotActivity> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::RobotDelivery(payload) => Some(payload.activity),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_robot_fleet() {
+        let config = DeliveryRobots {
+            kind: RobotKind::Drone,
+            count: 1,
+            speed_km_h: 36.0,
+            max_distance_km: 5.0,
+        };
+        let site_id = SiteId::from_name("london");
+        let mut fleet = RobotFleet::new(site_id, config.clone());
+        assert!(fleet.reaches(5_000.0));
+        assert!(!fleet.reaches(5_001.0));
+        let now = Utc::now();
+
+        // 3km at 10m/s take five minutes each way
+        let order_id = OrderId::new();
+        let customer_id = PersonId::new();
+        let events = fleet.dispatch(order_id, customer_id, 3_000.0, now).unwrap();
+        assert_eq!(activities(&events), vec![RobotActivity::Dispatched]);
+        assert!(!fleet.is_available());
+        assert!(
+            fleet
+                .dispatch(OrderId::new(), customer_id, 100.0, now)
+                .is_none()
+        );
+
+        assert!(fleet.advance(now + Duration::minutes(4)).is_empty());
+        let events = fleet.advance(now + Duration::minutes(5));
+        assert_eq!(activities(&events), vec![RobotActivity::Delivered]);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            EventPayload::OrderUpdated(payload)
+                if payload.order_id == order_id && payload.status == OrderStatus::Delivered
+        )));
+        assert!(!fleet.is_available());
+
+        let events = fleet.advance(now + Duration::minutes(10));
+        assert_eq!(activities(&events), vec![RobotActivity::Returned]);
+        assert!(fleet.is_available());
+
+        // robot ids are stable for a site
+        assert_eq!(
+            RobotFleet::new(site_id, config).robots[0].id,
+            fleet.robots[0].id
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(DeliveryRobots::default().validate().is_ok());
+        let invalid = DeliveryRobots {
+            speed_km_h: 0.0,
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
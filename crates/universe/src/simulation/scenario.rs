@@ -0,0 +1,291 @@
+//! Scenario files declaring a whole simulation run.
+//!
+//! A scenario collects the settings of a run which otherwise are passed to the
+//! [`SimulationBuilder`] one by one: the sites and brands expected in the setup,
+//! how long the run lasts and at which resolution, how often snapshots are taken,
+//! how often sites fail, and where results are stored. Scenarios are read from TOML
+//! files, or JSON files if the path ends in `.json`.
+//!
+//! ```toml
+//! sites = ["amsterdam"]
+//! brands = ["asian", "mexican"]
+//! duration_minutes = 1440
+//! time_step_seconds = 60
+//! snapshot_interval_minutes = 240
+//!
+//! [failures]
+//! site_failure_threshold = 5
+//! order_failure_rate = 0.02
+//!
+//! [output]
+//! working_directory = "./.caspers"
+//! run_name = "baseline"
+//! ```
+
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{DEFAULT_SITE_FAILURE_THRESHOLD, RuntimeSettings, SimulationBuilder};
+use crate::idents::BrandId;
+use crate::state::{EntityView as _, State};
+use crate::{Error, Result, resolve_url};
+
+/// Settings of a simulation run declared in a scenario file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Names of the sites the setup must contain, not checked if empty
+    pub sites: Vec<String>,
+
+    /// Names of the brands the setup must contain, not checked if empty
+    pub brands: Vec<String>,
+
+    /// Start of the simulation when not resuming a snapshot, now if not set
+    pub start: Option<DateTime<Utc>>,
+
+    /// Simulated time covered by the run
+    pub duration_minutes: i64,
+
+    /// Simulated time advanced by each step
+    pub time_step_seconds: i64,
+
+    /// Simulated time between snapshots taken during the run, only at the end if not set
+    pub snapshot_interval_minutes: Option<i64>,
+
+    /// Failures of sites during the run
+    pub failures: FailureProfile,
+
+    /// Locations the results of the run are stored at
+    pub output: OutputConfig,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            sites: Vec::new(),
+            brands: Vec::new(),
+            start: None,
+            duration_minutes: 100,
+            time_step_seconds: 60,
+            snapshot_interval_minutes: None,
+            failures: FailureProfile::default(),
+            output: OutputConfig::default(),
+        }
+    }
+}
+
+/// Failures of sites injected into a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FailureProfile {
+    /// Consecutive failed steps after which a site is quarantined
+    pub site_failure_threshold: usize,
+
+    /// Probability that a site fails an order right after it was submitted
+    pub order_failure_rate: f64,
+}
+
+impl Default for FailureProfile {
+    fn default() -> Self {
+        Self {
+            site_failure_threshold: DEFAULT_SITE_FAILURE_THRESHOLD,
+            order_failure_rate: 0.0,
+        }
+    }
+}
+
+/// Storage locations of the results of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Path or URL of the working directory, `.caspers/` in the current directory if not set
+    pub working_directory: Option<String>,
+
+    /// Human readable label stored with the snapshots of the run
+    pub run_name: Option<String>,
+
+    /// Whether to write the events of the run
+    pub write_events: bool,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            working_directory: None,
+            run_name: None,
+            write_events: true,
+        }
+    }
+}
+
+impl Scenario {
+    /// Read a scenario from a TOML file, or a JSON file if `path` ends in `.json`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let scenario: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content).map_err(|e| {
+                Error::invalid_data(format!("invalid scenario '{}': {e}", path.display()))
+            })?,
+        };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.time_step_seconds <= 0 {
+            return Err(Error::invalid_data("time step must be positive"));
+        }
+        if self.duration_minutes < 0 {
+            return Err(Error::invalid_data("duration must not be negative"));
+        }
+        if self
+            .snapshot_interval_minutes
+            .is_some_and(|interval| interval <= 0)
+        {
+            return Err(Error::invalid_data("snapshot interval must be positive"));
+        }
+        self.runtime_settings().validate()
+    }
+
+    pub fn time_step(&self) -> Duration {
+        Duration::seconds(self.time_step_seconds)
+    }
+
+    /// Number of steps covering the duration of the run.
+    pub fn steps(&self) -> usize {
+        steps_covering(Duration::minutes(self.duration_minutes), self.time_step())
+    }
+
+    /// Number of steps between snapshots taken during the run.
+    pub fn snapshot_interval(&self) -> Option<usize> {
+        self.snapshot_interval_minutes
+            .map(|interval| steps_covering(Duration::minutes(interval), self.time_step()))
+    }
+
+    /// Resolved location of the working directory.
+    pub fn working_directory(&self) -> Result<url::Url> {
+        resolve_url(self.output.working_directory.as_ref())
+    }
+
+    pub(crate) fn runtime_settings(&self) -> RuntimeSettings {
+        RuntimeSettings {
+            order_failure_rate: self.failures.order_failure_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Ensure the sites and brands of the scenario are part of the setup.
+    pub(crate) fn check_setup(&self, state: &State) -> Result<()> {
+        let site_names: Vec<_> = state
+            .objects()
+            .sites()?
+            .map(|site| Ok::<_, Error>(site.properties()?.name))
+            .collect::<Result<_>>()?;
+        if let Some(name) = self.sites.iter().find(|name| !site_names.contains(name)) {
+            return Err(Error::missing_input(format!(
+                "scenario site '{name}' is not part of the setup, initialize the working directory with `caspers init`"
+            )));
+        }
+        if let Some(name) = self
+            .brands
+            .iter()
+            .find(|name| state.objects().uri_ref(&BrandId::from_name(name)).is_none())
+        {
+            return Err(Error::missing_input(format!(
+                "scenario brand '{name}' is not part of the setup, initialize the working directory with `caspers init`"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Steps of `time_step` needed to cover `duration`, at least one.
+fn steps_covering(duration: Duration, time_step: Duration) -> usize {
+    let step = time_step.num_milliseconds();
+    (duration.num_milliseconds() + step - 1)
+        .div_euclid(step)
+        .max(1) as usize
+}
+
+impl SimulationBuilder {
+    /// Create a builder configured by the scenario file at `path`.
+    ///
+    /// Run the built simulation for [`Scenario::steps`] to cover the duration of the
+    /// scenario.
+    pub fn from_scenario(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new().with_scenario(Scenario::from_file(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scenario() {
+        let scenario: Scenario = toml::from_str(
+            r#"
+            sites = ["amsterdam"]
+            duration_minutes = 90
+            time_step_seconds = 120
+            snapshot_interval_minutes = 30
+
+            [failures]
+            order_failure_rate = 0.1
+            "#,
+        )
+        .unwrap();
+        assert!(scenario.validate().is_ok());
+        assert_eq!(scenario.sites, vec!["amsterdam".to_string()]);
+        assert_eq!(scenario.steps(), 45);
+        assert_eq!(scenario.snapshot_interval(), Some(15));
+        assert_eq!(
+            scenario.failures.site_failure_threshold,
+            DEFAULT_SITE_FAILURE_THRESHOLD
+        );
+        assert_eq!(scenario.runtime_settings().order_failure_rate, 0.1);
+        assert!(scenario.output.write_events);
+    }
+
+    #[test]
+    fn test_steps_round_up() {
+        let scenario = Scenario {
+            duration_minutes: 1,
+            time_step_seconds: 40,
+            ..Default::default()
+        };
+        assert_eq!(scenario.steps(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        for scenario in [
+            Scenario {
+                time_step_seconds: 0,
+                ..Default::default()
+            },
+            Scenario {
+                snapshot_interval_minutes: Some(0),
+                ..Default::default()
+            },
+            Scenario {
+                failures: FailureProfile {
+                    order_failure_rate: 1.5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ] {
+            assert!(scenario.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        assert!(toml::from_str::<Scenario>("duraton_minutes = 10").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Event-driven stepping of the simulation.
+//!
+//! By default every step advances the simulation by the configured time increment.
+//! With the event scheduler, a step instead advances to the next time an agent
+//! changes on its own, e.g. a kitchen completes an instruction, a courier arrives
+//! or a customer is done eating, but at most by [`EventScheduler::max_step_minutes`].
+//! Steps always span whole time increments, so quiet periods such as nights pass in
+//! few long steps while busy periods are stepped at the configured resolution.
+//!
+//! An [`AdaptiveTimeStep`] instead lengthens steps while the simulation is quiet,
+//! i.e. no order is open and nobody is on their way, and returns to the shortest step
+//! as soon as there is activity again. Unlike the event scheduler, it does not need to
+//! know when agents are due, so nights pass in few steps also while customers are
+//! eating or idle.
+//!
+//! Customers place orders with a probability per time increment, so the demand of
+//! a step is scaled by the number of increments it spans. Orders placed during a
+//! long step are submitted at its start.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Settings of the scheduler advancing the simulation from event to event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventScheduler {
+    /// Longest simulated time covered by a single step
+    pub max_step_minutes: i64,
+}
+
+impl Default for EventScheduler {
+    fn default() -> Self {
+        Self {
+            max_step_minutes: 60,
+        }
+    }
+}
+
+impl EventScheduler {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_step_minutes <= 0 {
+            return Err(Error::invalid_data("maximum step must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Number of time increments the step starting at `now` spans.
+    ///
+    /// The step reaches up to the first increment at or after `next_event`, without
+    /// passing `end`, and spans at least one increment.
+    pub(crate) fn step_increments(
+        &self,
+        increment: Duration,
+        now: DateTime<Utc>,
+        next_event: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> i32 {
+        let increment_ms = increment.num_milliseconds().max(1);
+        let increments_until = |time: DateTime<Utc>| {
+            let ms = (time - now).num_milliseconds();
+            (ms + increment_ms - 1).div_euclid(increment_ms)
+        };
+        let max_increments = (self.max_step_minutes * 60_000 / increment_ms).max(1);
+        [next_event, end]
+            .into_iter()
+            .flatten()
+            .map(increments_until)
+            .fold(max_increments, i64::min)
+            .clamp(1, i32::MAX as i64) as i32
+    }
+}
+
+/// Bounds of steps growing during quiet periods and shrinking back on activity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveTimeStep {
+    /// Shortest simulated time covered by a step, taken while there is activity
+    pub min_step_minutes: i64,
+
+    /// Longest simulated time covered by a step, reached in quiet periods
+    pub max_step_minutes: i64,
+
+    /// Factor by which steps grow after every quiet step
+    pub growth_factor: f64,
+}
+
+impl Default for AdaptiveTimeStep {
+    fn default() -> Self {
+        Self {
+            min_step_minutes: 1,
+            max_step_minutes: 30,
+            growth_factor: 2.0,
+        }
+    }
+}
+
+impl AdaptiveTimeStep {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.min_step_minutes <= 0 || self.max_step_minutes < self.min_step_minutes {
+            return Err(Error::invalid_data(
+                "adaptive steps need a positive minimum not above their maximum",
+            ));
+        }
+        if !(self.growth_factor.is_finite() && self.growth_factor > 1.0) {
+            return Err(Error::invalid_data(
+                "adaptive steps must grow by a factor above one",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of time increments the step starting at `now` spans.
+    ///
+    /// Quiet steps grow from the `previous` step by the growth factor up to the
+    /// longest step, active steps take the shortest step. Steps span whole
+    /// increments, at least one, and do not pass `end`.
+    pub(crate) fn step_increments(
+        &self,
+        increment: Duration,
+        previous: Duration,
+        active: bool,
+        now: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+    ) -> i32 {
+        let increment_ms = increment.num_milliseconds().max(1);
+        let min_increments = (self.min_step_minutes * 60_000 / increment_ms).max(1);
+        let max_increments = (self.max_step_minutes * 60_000 / increment_ms).max(min_increments);
+        let increments = if active {
+            min_increments
+        } else {
+            let previous = previous.num_milliseconds().div_euclid(increment_ms).max(1);
+            ((previous as f64 * self.growth_factor).ceil() as i64)
+                .clamp(min_increments, max_increments)
+        };
+        let until_end = end.map(|end| {
+            let ms = (end - now).num_milliseconds();
+            (ms + increment_ms - 1).div_euclid(increment_ms)
+        });
+        until_end
+            .map_or(increments, |until_end| increments.min(until_end))
+            .clamp(1, i32::MAX as i64) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_increments() {
+        let scheduler = EventScheduler::default();
+        let increment = Duration::minutes(1);
+        let now = DateTime::parse_from_rfc3339("2025-01-01T02:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        // quiet periods are skipped up to the longest step
+        assert_eq!(scheduler.step_increments(increment, now, None, None), 60);
+        // steps end at the first increment at or after the next event
+        let next_event = now + Duration::seconds(150);
+        assert_eq!(
+            scheduler.step_increments(increment, now, Some(next_event), None),
+            3
+        );
+        // steps do not pass the end of the run
+        let end = now + Duration::minutes(10);
+        assert_eq!(
+            scheduler.step_increments(increment, now, None, Some(end)),
+            10
+        );
+        // events due already are handled in a single increment
+        assert_eq!(
+            scheduler.step_increments(increment, now, Some(now), None),
+            1
+        );
+    }
+
+    #[test]
+    fn test_adaptive_increments() {
+        let adaptive = AdaptiveTimeStep::default();
+        let increment = Duration::minutes(1);
+        let now = DateTime::parse_from_rfc3339("2025-01-01T02:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        // quiet steps grow up to the longest step
+        let step = |previous: i64, active| {
+            adaptive.step_increments(increment, Duration::minutes(previous), active, now, None)
+        };
+        assert_eq!(step(1, false), 2);
+        assert_eq!(step(8, false), 16);
+        assert_eq!(step(16, false), 30);
+        assert_eq!(step(30, false), 30);
+        // activity returns to the shortest step
+        assert_eq!(step(30, true), 1);
+        // steps do not pass the end of the run
+        let end = now + Duration::minutes(10);
+        assert_eq!(
+            adaptive.step_increments(increment, Duration::minutes(30), false, now, Some(end)),
+            10
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(EventScheduler::default().validate().is_ok());
+        let scheduler = EventScheduler {
+            max_step_minutes: 0,
+        };
+        assert!(scheduler.validate().is_err());
+
+        assert!(AdaptiveTimeStep::default().validate().is_ok());
+        let adaptive = AdaptiveTimeStep {
+            min_step_minutes: 10,
+            max_step_minutes: 5,
+            ..Default::default()
+        };
+        assert!(adaptive.validate().is_err());
+        let adaptive = AdaptiveTimeStep {
+            growth_factor: 1.0,
+            ..Default::default()
+        };
+        assert!(adaptive.validate().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Low-frequency structure of demand over the year.
+//!
+//! A [`SeasonalityConfig`] scales demand by a factor per calendar month, e.g. a dip
+//! in summer and a peak in December, and during school terms or school holidays.
+//! Monthly factors apply in the middle of each month and are interpolated linearly in
+//! between, so demand follows a smooth curve over a year-long backfill rather than
+//! jumping at the turn of a month.
+//!
+//! The weather follows the seasons as well: the rain probability of the
+//! [`TippingModel`] is scaled per month in the same way, and customers order more
+//! during rainy hours. Tips see the same, seasonal weather.
+
+use chrono::{DateTime, Datelike as _, Months, NaiveDate, Timelike as _, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+use super::{TippingModel, Weather};
+
+/// Monthly and school-term variation of demand and weather.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeasonalityConfig {
+    /// Factors applied to the probability of customers placing an order, January first
+    pub monthly_demand: [f64; 12],
+
+    /// Factors applied to the rain probability of the tipping model, January first
+    pub monthly_rain: [f64; 12],
+
+    /// Factor applied to the probability of customers placing an order in rainy hours
+    pub rain_demand_factor: f64,
+
+    /// School holidays, demand outside of them follows the school term
+    pub school_holidays: Vec<SchoolHoliday>,
+}
+
+/// Days on which schools are closed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchoolHoliday {
+    pub name: String,
+
+    /// First day of the holiday
+    pub start: NaiveDate,
+
+    /// Last day of the holiday
+    pub end: NaiveDate,
+
+    /// Factor applied to the probability of customers placing an order
+    pub demand_factor: f64,
+}
+
+impl Default for SeasonalityConfig {
+    fn default() -> Self {
+        Self {
+            monthly_demand: [
+                1.05, 1.0, 0.98, 0.95, 0.93, 0.88, 0.82, 0.85, 0.95, 1.0, 1.05, 1.2,
+            ],
+            monthly_rain: [1.3, 1.2, 1.1, 1.0, 0.9, 0.8, 0.7, 0.8, 1.0, 1.2, 1.3, 1.3],
+            rain_demand_factor: 1.2,
+            school_holidays: Vec::new(),
+        }
+    }
+}
+
+impl SeasonalityConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        let valid_factor = |factor: &f64| factor.is_finite() && *factor >= 0.0;
+        if !self.monthly_demand.iter().all(valid_factor)
+            || !self.monthly_rain.iter().all(valid_factor)
+            || !valid_factor(&self.rain_demand_factor)
+        {
+            return Err(Error::invalid_data(
+                "seasonal factors must be non-negative numbers",
+            ));
+        }
+        for holiday in &self.school_holidays {
+            if holiday.end < holiday.start {
+                return Err(Error::invalid_data(format!(
+                    "school holiday '{}' must not end before it starts",
+                    holiday.name
+                )));
+            }
+            if !valid_factor(&holiday.demand_factor) {
+                return Err(Error::invalid_data(format!(
+                    "factor of school holiday '{}' must be a non-negative number",
+                    holiday.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Factor applied to the probability of customers placing an order at `time`.
+    pub(crate) fn demand_factor(&self, time: DateTime<Utc>, weather: Weather) -> f64 {
+        let date = time.date_naive();
+        let school: f64 = self
+            .school_holidays
+            .iter()
+            .filter(|holiday| holiday.start <= date && date <= holiday.end)
+            .map(|holiday| holiday.demand_factor)
+            .product();
+        let rain = match weather {
+            Weather::Rain => self.rain_demand_factor,
+            Weather::Clear => 1.0,
+        };
+        interpolate(&self.monthly_demand, time) * school * rain
+    }
+
+    /// Weather during the hour containing `time`, with the rain probability of the season.
+    pub(crate) fn weather(&self, tipping: &TippingModel, time: DateTime<Utc>) -> Weather {
+        let rain_probability = tipping.rain_probability * interpolate(&self.monthly_rain, time);
+        tipping.weather_with(time, rain_probability)
+    }
+}
+
+/// Value of the monthly `factors` at `time`, each applying in the middle of its month.
+fn interpolate(factors: &[f64; 12], time: DateTime<Utc>) -> f64 {
+    let date = time.date_naive();
+    let first = date.with_day(1).expect("every month has a first day");
+    let days = first
+        .checked_add_months(Months::new(1))
+        .map_or(31, |next| (next - first).num_days()) as f64;
+    let day = (date.day0() as f64 + time.hour() as f64 / 24.0) / days;
+
+    // months as a continuous position, with the middle of January at zero
+    let position = date.month0() as f64 + day - 0.5;
+    let index = position.floor();
+    let fraction = position - index;
+    let month = |index: f64| factors[(index as i64).rem_euclid(12) as usize];
+    month(index) * (1.0 - fraction) + month(index + 1.0) * fraction
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_demand_factor() {
+        let config = SeasonalityConfig::default();
+        let july = config.demand_factor(time("2025-07-16T12:00:00Z"), Weather::Clear);
+        let december = config.demand_factor(time("2025-12-16T12:00:00Z"), Weather::Clear);
+        assert!(july < 0.85);
+        assert!(december > 1.15);
+
+        // demand follows a smooth curve across the turn of the year
+        let new_year = config.demand_factor(time("2026-01-01T00:00:00Z"), Weather::Clear);
+        let eve = config.demand_factor(time("2025-12-31T23:00:00Z"), Weather::Clear);
+        assert!((new_year - eve).abs() < 0.01);
+        assert!(new_year > 1.05 && new_year < 1.2);
+
+        let rain = config.demand_factor(time("2025-07-16T12:00:00Z"), Weather::Rain);
+        assert!((rain - july * config.rain_demand_factor).abs() < 1e-9);
+
+        let config = SeasonalityConfig {
+            school_holidays: vec![SchoolHoliday {
+                name: "summer".to_string(),
+                start: NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(),
+                demand_factor: 0.9,
+            }],
+            ..Default::default()
+        };
+        let holiday = config.demand_factor(time("2025-07-16T12:00:00Z"), Weather::Clear);
+        assert!((holiday - july * 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SeasonalityConfig::default().validate().is_ok());
+        let mut config = SeasonalityConfig::default();
+        config.monthly_demand[3] = -1.0;
+        assert!(config.validate().is_err());
+
+        let config = SeasonalityConfig {
+            school_holidays: vec![SchoolHoliday {
+                name: "backwards".to_string(),
+                start: NaiveDate::from_ymd_opt(2025, 8, 31).unwrap(),
+                end: NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+                demand_factor: 0.9,
+            }],
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,495 @@
+//! App sessions of customers and the ordering funnel they pass through.
+//!
+//! Without a [`SessionConfig`], customers place orders without any trace of how they
+//! got there. With one, every order is preceded by an app session in which the
+//! customer browses menu items, adds the items ordered to the cart and checks out.
+//! Alongside, idle customers open the app without ordering, so that the share of
+//! sessions converting into an order matches [`SessionConfig::conversion_rate`].
+//! Abandoned sessions browse the menu of the site nearest to the customer and may
+//! add items to the cart before the customer leaves.
+//!
+//! Each stage a session reaches is reported as a
+//! [`SessionUpdatedPayload`](crate::SessionUpdatedPayload) event with the time it was
+//! reached. Sessions end in the step their order is placed or they are abandoned, so
+//! the earlier stages are timed before the step.
+//!
+//! The menu items shown in a session are logged to the `impressions` results table.
+//! Every session opens with a ranked list of recommendations drawn uniformly from the
+//! menu, and the items browsed are clicked among them. Items put in the cart without
+//! being recommended were found through search. Each impression records the
+//! probability with which it was shown at its position, so that ranking policies can
+//! be evaluated offline against the logs.
+
+use arrow::array::RecordBatch;
+use chrono::{DateTime, Utc};
+use geo::{Distance as _, Haversine, Point};
+use rand::Rng;
+use rand::seq::IndexedRandom as _;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+use super::notifications::exponential_delay;
+use crate::builders::{Impression, ImpressionBuffer};
+use crate::idents::{BrandId, MenuItemId, OrderId, PersonId, SessionId, SiteId, uuid_v7_from_rng};
+use crate::state::{PersonRole, PersonStatus, State};
+use crate::{EntityView as _, Error, EventPayload, Result};
+
+/// Stage of the ordering funnel an app session reached.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FunnelStage {
+    /// The customer viewed menu items
+    Browse,
+    /// The customer added menu items to the cart
+    Cart,
+    /// The customer placed the order
+    Checkout,
+    /// The customer left the app without ordering
+    Abandoned,
+}
+
+/// Part of the app in which menu items are shown to customers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum ImpressionSurface {
+    /// Ranked list of menu items shown when opening the app
+    Recommendation,
+    /// Results of searching for a menu item
+    Search,
+}
+
+/// Conversion and timing of app sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Share of sessions ending in an order
+    pub conversion_rate: f64,
+
+    /// Share of abandoned sessions in which items were added to the cart
+    pub cart_rate: f64,
+
+    /// Most menu items viewed in a session besides the items ordered
+    pub max_items_viewed: usize,
+
+    /// Menu items recommended when opening the app
+    pub recommendations: usize,
+
+    /// Mean time in seconds from opening the app to adding items to the cart
+    pub mean_browse_s: f64,
+
+    /// Mean time in seconds from adding items to the cart to checking out
+    pub mean_checkout_s: f64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            conversion_rate: 0.2,
+            cart_rate: 0.35,
+            max_items_viewed: 8,
+            recommendations: 10,
+            mean_browse_s: 240.0,
+            mean_checkout_s: 90.0,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if !(self.conversion_rate > 0.0 && self.conversion_rate <= 1.0) {
+            return Err(Error::invalid_data(format!(
+                "conversion rate {} outside of (0, 1]",
+                self.conversion_rate
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.cart_rate) {
+            return Err(Error::invalid_data(format!(
+                "cart rate {} outside of [0, 1]",
+                self.cart_rate
+            )));
+        }
+        for (name, value) in [
+            ("browse", self.mean_browse_s),
+            ("checkout", self.mean_checkout_s),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(Error::invalid_data(format!(
+                    "mean {name} time must be a non-negative number"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Expected number of abandoned sessions for every session ending in an order.
+    fn abandoned_per_order(&self) -> f64 {
+        (1.0 - self.conversion_rate) / self.conversion_rate
+    }
+}
+
+/// Generates the app sessions preceding orders and the sessions that are abandoned.
+pub(crate) struct AppSessions {
+    config: SessionConfig,
+
+    /// Menu items shown in sessions, waiting to be written
+    impressions: ImpressionBuffer,
+}
+
+impl AppSessions {
+    pub(crate) fn new(config: SessionConfig) -> Self {
+        Self {
+            config,
+            impressions: ImpressionBuffer::new(),
+        }
+    }
+
+    /// Session events of a step at `now` that emitted `events`.
+    pub(crate) fn step(
+        &mut self,
+        now: DateTime<Utc>,
+        events: &[EventPayload],
+        state: &State,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<EventPayload>> {
+        let objects = state.objects();
+        let menu = objects
+            .menu_item_ids()
+            .filter_map(|item_id| objects.menu_item_data(item_id))
+            .map(|item| Ok((BrandId::try_from(item.brand_id())?, item.id())))
+            .collect::<Result<Vec<_>>>()?;
+        let mut sessions = Vec::new();
+        let mut orders = 0;
+        for event in events {
+            let EventPayload::OrderCreated(payload) = event else {
+                continue;
+            };
+            orders += 1;
+            sessions.extend(self.session(
+                now,
+                (payload.person_id, payload.site_id),
+                &menu,
+                payload.items.clone(),
+                Some(payload.order_id),
+                rng,
+            )?);
+        }
+
+        let expected = orders as f64 * self.config.abandoned_per_order();
+        let mut abandoned = expected.floor() as usize;
+        if rng.random_bool(expected.fract()) {
+            abandoned += 1;
+        }
+        if abandoned == 0 || menu.is_empty() {
+            return Ok(sessions);
+        }
+
+        let sites = state
+            .objects()
+            .sites()?
+            .map(|site| {
+                let props = site.properties()?;
+                Ok::<_, Error>((site.id(), Point::new(props.longitude, props.latitude)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let idle: Vec<_> = state
+            .population()
+            .people_with_role_status(&PersonRole::Customer)?
+            .into_iter()
+            .filter(|(_, status, _)| matches!(status, PersonStatus::Idle))
+            .map(|(person_id, _, position)| (person_id, position))
+            .collect();
+        for (person_id, position) in idle.choose_multiple(rng, abandoned) {
+            let nearest = sites.iter().min_by(|(_, a), (_, b)| {
+                let a = Haversine.distance(*a, *position);
+                let b = Haversine.distance(*b, *position);
+                a.total_cmp(&b)
+            });
+            let Some((site_id, _)) = nearest else {
+                break;
+            };
+            let carted = if rng.random_bool(self.config.cart_rate) {
+                let count = rng.random_range(1..=3);
+                menu.choose_multiple(rng, count).copied().collect()
+            } else {
+                Vec::new()
+            };
+            sessions.extend(self.session(now, (*person_id, *site_id), &menu, carted, None, rng)?);
+        }
+        tracing::debug!(
+            target: "caspers::simulation::sessions",
+            "{orders} sessions converted, {abandoned} abandoned"
+        );
+        Ok(sessions)
+    }
+
+    /// Events of a session of a customer browsing the menu of a site, which ends at
+    /// `now` and put the `carted` items in the cart.
+    ///
+    /// Sessions with an order end in checkout, all others are abandoned, right after
+    /// browsing if nothing was put in the cart. The items shown in the session are
+    /// added to the pending impressions.
+    fn session(
+        &mut self,
+        now: DateTime<Utc>,
+        (person_id, site_id): (PersonId, SiteId),
+        menu: &[(BrandId, MenuItemId)],
+        carted: Vec<(BrandId, MenuItemId)>,
+        order_id: Option<OrderId>,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<EventPayload>> {
+        let session_id = SessionId::from_rng(now, rng);
+        let event = |stage, items, occurred_at| {
+            EventPayload::session_updated(
+                session_id,
+                person_id,
+                site_id,
+                stage,
+                items,
+                order_id.filter(|_| stage == FunnelStage::Checkout),
+                occurred_at,
+            )
+        };
+
+        let recommended: Vec<_> = menu
+            .choose_multiple(rng, self.config.recommendations)
+            .copied()
+            .collect();
+        let count = rng.random_range(0..=self.config.max_items_viewed);
+        let mut viewed: Vec<_> = recommended.choose_multiple(rng, count).copied().collect();
+        for item in &carted {
+            if !viewed.contains(item) {
+                viewed.push(*item);
+            }
+        }
+
+        let checkout_s = if carted.is_empty() {
+            0.0
+        } else {
+            self.config.mean_checkout_s
+        };
+        let carted_at = now - exponential_delay(rng, checkout_s);
+        let opened_at = carted_at - exponential_delay(rng, self.config.mean_browse_s);
+
+        // every menu item is equally likely to be recommended at each position under
+        // the uniform logging policy, searched items are shown for certain
+        let recommendation = 1.0 / menu.len() as f64;
+        let shown = recommended
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| (ImpressionSurface::Recommendation, idx, recommendation, item))
+            .chain(
+                carted
+                    .iter()
+                    .filter(|item| !recommended.contains(item))
+                    .map(|item| (ImpressionSurface::Search, 0, 1.0, item)),
+            );
+        for (surface, idx, propensity, item) in shown {
+            self.impressions.push(&Impression {
+                id: uuid_v7_from_rng(opened_at, rng),
+                session_id,
+                person_id,
+                site_id,
+                item: *item,
+                surface: surface.to_string(),
+                position: idx as u32 + 1,
+                propensity,
+                shown_at: opened_at,
+                clicked: viewed.contains(item),
+                ordered: order_id.is_some() && carted.contains(item),
+            })?;
+        }
+
+        let viewed = viewed.into_iter().map(|(_, item)| item).collect();
+        let carted: Vec<_> = carted.into_iter().map(|(_, item)| item).collect();
+        let mut events = vec![event(FunnelStage::Browse, viewed, opened_at)];
+        if !carted.is_empty() {
+            events.push(event(FunnelStage::Cart, carted.clone(), carted_at));
+        }
+        let last = if order_id.is_some() {
+            FunnelStage::Checkout
+        } else {
+            FunnelStage::Abandoned
+        };
+        events.push(event(last, carted, now));
+        Ok(events)
+    }
+
+    /// Whether impressions are waiting to be written.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.impressions.len() > 0
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<RecordBatch> {
+        self.impressions.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::AsArray as _;
+    use arrow::datatypes::{Float64Type, Int64Type};
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    fn stages(events: &[EventPayload]) -> Vec<FunnelStage> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::SessionUpdated(p) => Some(p.stage),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn menu() -> Vec<(BrandId, MenuItemId)> {
+        (0..20)
+            .map(|idx| {
+                let item = format!("item {idx}");
+                (
+                    BrandId::from_name("brand"),
+                    MenuItemId::from_names("brand", &item),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_session() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let now = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let menu = menu();
+        let mut sessions = AppSessions::new(SessionConfig::default());
+        let (person_id, site_id) = (PersonId::new(), SiteId::from_name("london"));
+
+        let order_id = OrderId::new();
+        let events = sessions.session(
+            now,
+            (person_id, site_id),
+            &menu,
+            vec![menu[3]],
+            Some(order_id),
+            &mut rng,
+        )?;
+        assert_eq!(
+            stages(&events),
+            [
+                FunnelStage::Browse,
+                FunnelStage::Cart,
+                FunnelStage::Checkout
+            ]
+        );
+        let payloads: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                EventPayload::SessionUpdated(p) => Some(p),
+                _ => None,
+            })
+            .collect();
+        assert!(payloads[0].menu_item_ids.contains(&menu[3].1));
+        assert!(payloads[0].occurred_at <= payloads[1].occurred_at);
+        assert_eq!(payloads[2].occurred_at, now);
+        assert_eq!(payloads[2].order_id, Some(order_id));
+        assert_eq!(payloads[1].order_id, None);
+        assert!(
+            payloads
+                .iter()
+                .all(|p| p.session_id == payloads[0].session_id)
+        );
+
+        let events = sessions.session(now, (person_id, site_id), &menu, vec![], None, &mut rng)?;
+        assert_eq!(
+            stages(&events),
+            [FunnelStage::Browse, FunnelStage::Abandoned]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_impressions() -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let now = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let menu = menu();
+        let config = SessionConfig {
+            recommendations: 5,
+            ..Default::default()
+        };
+        let mut sessions = AppSessions::new(config);
+        let (person_id, site_id) = (PersonId::new(), SiteId::from_name("london"));
+        assert!(!sessions.has_pending());
+
+        let events = sessions.session(
+            now,
+            (person_id, site_id),
+            &menu,
+            vec![menu[3]],
+            Some(OrderId::new()),
+            &mut rng,
+        )?;
+        assert!(sessions.has_pending());
+        let batch = sessions.flush()?;
+        assert!(!sessions.has_pending());
+
+        let surfaces = batch.column(6).as_string_view();
+        let positions = batch.column(7).as_primitive::<Int64Type>();
+        let propensities = batch.column(8).as_primitive::<Float64Type>();
+        let (clicked, ordered) = (batch.column(10).as_boolean(), batch.column(11).as_boolean());
+        let recommended = (0..batch.num_rows())
+            .filter(|idx| surfaces.value(*idx) == "recommendation")
+            .collect::<Vec<_>>();
+        assert_eq!(recommended.len(), 5);
+        for (rank, idx) in recommended.iter().enumerate() {
+            assert_eq!(positions.value(*idx), rank as i64 + 1);
+            assert_eq!(propensities.value(*idx), 1.0 / 20.0);
+        }
+
+        // the ordered item is clicked and ordered, whether recommended or searched
+        let ordered_rows = (0..batch.num_rows())
+            .filter(|idx| ordered.value(*idx))
+            .collect::<Vec<_>>();
+        assert_eq!(ordered_rows.len(), 1);
+        assert!(clicked.value(ordered_rows[0]));
+        let menu_item_ids = batch.column(5).as_fixed_size_binary();
+        assert_eq!(
+            menu_item_ids.value(ordered_rows[0]),
+            AsRef::<[u8]>::as_ref(&menu[3].1)
+        );
+
+        // items clicked among the recommendations are viewed in the browse stage
+        let EventPayload::SessionUpdated(browse) = &events[0] else {
+            panic!("expected session updated event");
+        };
+        let clicks = (0..batch.num_rows())
+            .filter(|idx| clicked.value(*idx))
+            .count();
+        assert_eq!(clicks, browse.menu_item_ids.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        let config = SessionConfig::default();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.abandoned_per_order(), 4.0);
+        for config in [
+            SessionConfig {
+                conversion_rate: 0.0,
+                ..Default::default()
+            },
+            SessionConfig {
+                cart_rate: 1.5,
+                ..Default::default()
+            },
+            SessionConfig {
+                mean_browse_s: -1.0,
+                ..Default::default()
+            },
+        ] {
+            assert!(config.validate().is_err());
+        }
+    }
+}
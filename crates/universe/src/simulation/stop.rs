@@ -0,0 +1,190 @@
+//! Conditions ending a simulation run.
+//!
+//! Besides a number of steps, a run may end at a simulated time, after a budget of
+//! wall-clock time, once a number of orders were created, or once a predicate over
+//! the [`State`] holds. Conditions are checked between steps and the run ends once
+//! any of them is met, so the last step may pass a time budget or create more orders
+//! than requested. The simulated time is the only condition steps are shortened
+//! for, i.e. with the event scheduler no step passes it.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration as WallDuration;
+
+use chrono::{DateTime, Utc};
+
+use crate::state::State;
+use crate::{Error, Result};
+
+/// Predicate over the simulation state ending a run once it holds.
+pub type StopPredicate = Arc<dyn Fn(&State) -> bool + Send + Sync>;
+
+/// Conditions ending a run of [`Simulation::run_until`](super::Simulation::run_until).
+#[derive(Clone, Default)]
+pub struct StopConditions {
+    max_steps: Option<usize>,
+    until: Option<DateTime<Utc>>,
+    time_budget: Option<WallDuration>,
+    max_orders: Option<usize>,
+    predicate: Option<StopPredicate>,
+}
+
+impl fmt::Debug for StopConditions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StopConditions")
+            .field("max_steps", &self.max_steps)
+            .field("until", &self.until)
+            .field("time_budget", &self.time_budget)
+            .field("max_orders", &self.max_orders)
+            .field("predicate", &self.predicate.is_some())
+            .finish()
+    }
+}
+
+/// Progress of a run, checked against the [`StopConditions`] between steps.
+pub(crate) struct RunProgress {
+    /// Time increments covered since the start of the run
+    pub(crate) steps: usize,
+    pub(crate) current_time: DateTime<Utc>,
+    pub(crate) elapsed: WallDuration,
+    /// Orders created since the start of the run
+    pub(crate) orders: usize,
+}
+
+impl StopConditions {
+    /// End the run after `steps` time increments.
+    pub fn steps(steps: usize) -> Self {
+        Self::default().with_max_steps(steps)
+    }
+
+    /// End the run after `steps` time increments.
+    pub fn with_max_steps(mut self, steps: usize) -> Self {
+        self.max_steps = Some(steps);
+        self
+    }
+
+    /// End the run once the simulation time reaches `until`.
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// End the run after the first step completing `budget` after the run started.
+    pub fn with_time_budget(mut self, budget: WallDuration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// End the run after the first step in which `orders` orders were created in total.
+    pub fn with_max_orders(mut self, orders: usize) -> Self {
+        self.max_orders = Some(orders);
+        self
+    }
+
+    /// End the run after the first step after which `predicate` holds for the state.
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(&State) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.max_steps.is_none()
+            && self.until.is_none()
+            && self.time_budget.is_none()
+            && self.max_orders.is_none()
+            && self.predicate.is_none()
+        {
+            return Err(Error::invalid_data(
+                "runs require at least one stop condition",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Simulation time at which the run ends at the latest, if bounded.
+    pub(crate) fn end_time(
+        &self,
+        start: DateTime<Utc>,
+        increment: chrono::Duration,
+    ) -> Option<DateTime<Utc>> {
+        let steps_end = self.max_steps.map(|steps| start + increment * steps as i32);
+        match (steps_end, self.until) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// The condition met by the run with `progress`, if any.
+    pub(crate) fn met(&self, progress: &RunProgress, state: &State) -> Option<&'static str> {
+        if self.max_steps.is_some_and(|steps| progress.steps >= steps) {
+            return Some("step count reached");
+        }
+        if self
+            .until
+            .is_some_and(|until| progress.current_time >= until)
+        {
+            return Some("end time reached");
+        }
+        if self
+            .time_budget
+            .is_some_and(|budget| progress.elapsed >= budget)
+        {
+            return Some("time budget exhausted");
+        }
+        if self
+            .max_orders
+            .is_some_and(|orders| progress.orders >= orders)
+        {
+            return Some("order count reached");
+        }
+        if self
+            .predicate
+            .as_ref()
+            .is_some_and(|predicate| predicate(state))
+        {
+            return Some("stop predicate holds");
+        }
+        None
+    }
+}
+
+impl From<usize> for StopConditions {
+    fn from(steps: usize) -> Self {
+        Self::steps(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_end_time() {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let increment = chrono::Duration::minutes(1);
+        let until = start + chrono::Duration::minutes(30);
+
+        assert_eq!(
+            StopConditions::steps(60).end_time(start, increment),
+            Some(start + chrono::Duration::minutes(60))
+        );
+        // the earlier of both ends the run
+        let stop = StopConditions::steps(60).with_until(until);
+        assert_eq!(stop.end_time(start, increment), Some(until));
+        let stop = StopConditions::default().with_max_orders(1_000_000);
+        assert_eq!(stop.end_time(start, increment), None);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(StopConditions::default().validate().is_err());
+        assert!(StopConditions::steps(0).validate().is_ok());
+        let stop = StopConditions::default().with_predicate(|_| true);
+        assert!(stop.validate().is_ok());
+    }
+}
@@ -0,0 +1,67 @@
+//! Wall clock timing of the phases of a simulation step.
+
+use std::time::{Duration, Instant};
+
+use strum::{AsRefStr, Display, EnumIter, IntoEnumIterator as _};
+
+/// A phase of a simulation step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, Display, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum StepPhase {
+    /// Advancing journeys of people in the population.
+    MovePeople,
+    /// Creating orders from the population of all sites.
+    PopulationStep,
+    /// Advancing kitchens and order pickup at all sites.
+    SiteStep,
+    /// Applying events to the simulation state.
+    StateUpdate,
+    /// Writing events to the results tables.
+    EventWrite,
+}
+
+/// Time spent in each phase of a simulation step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepTimings {
+    durations: [Duration; 5],
+}
+
+impl StepTimings {
+    pub fn get(&self, phase: StepPhase) -> Duration {
+        self.durations[phase as usize]
+    }
+
+    /// Add the time elapsed since `start` to a phase.
+    pub(crate) fn record(&mut self, phase: StepPhase, start: Instant) {
+        self.durations[phase as usize] += start.elapsed();
+    }
+
+    /// Total time spent in all phases.
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (StepPhase, Duration)> + '_ {
+        StepPhase::iter().map(|phase| (phase, self.get(phase)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_timings() {
+        let mut timings = StepTimings::default();
+        let start = Instant::now();
+        std::thread::sleep(Duration::from_millis(2));
+        timings.record(StepPhase::SiteStep, start);
+        timings.record(StepPhase::SiteStep, start);
+
+        assert!(timings.get(StepPhase::SiteStep) >= Duration::from_millis(4));
+        assert_eq!(timings.get(StepPhase::MovePeople), Duration::ZERO);
+        assert_eq!(timings.total(), timings.get(StepPhase::SiteStep));
+        assert_eq!(timings.iter().count(), 5);
+        assert_eq!(StepPhase::EventWrite.as_ref(), "event_write");
+    }
+}
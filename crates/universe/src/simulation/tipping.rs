@@ -0,0 +1,189 @@
+//! Tips customers add to their orders.
+//!
+//! Whether and how much a customer tips is sampled when the order is created. The
+//! tip is a share of the order value which drops when the quoted delivery time is
+//! longer than customers expect, and rises in bad weather. Tips are recorded on the
+//! `order_created` event and the order, and are added to the earnings offered to
+//! the courier delivering the order.
+//!
+//! A `tip_amount` [`BehaviorHooks`](crate::BehaviorHooks) expression replaces the
+//! model entirely.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+/// Weather conditions affecting customer behavior.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Weather {
+    Clear,
+    Rain,
+}
+
+/// Distribution of tips relative to the order value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TippingModel {
+    /// Probability that a customer tips at all
+    pub tip_probability: f64,
+
+    /// Mean tip as a share of the order total
+    pub mean_rate: f64,
+
+    /// Standard deviation of the tip share between customers
+    pub rate_std_dev: f64,
+
+    /// Smallest tip in the order currency, given a customer tips
+    pub minimum_tip: f64,
+
+    /// Quoted delivery time in minutes customers accept without tipping less
+    pub expected_delivery_minutes: f64,
+
+    /// Reduction of the tip share per minute the quoted delivery time exceeds expectations
+    pub slow_delivery_penalty: f64,
+
+    /// Probability that it rains in any given hour
+    pub rain_probability: f64,
+
+    /// Increase of the tip share when ordering in the rain
+    pub rain_bonus: f64,
+}
+
+impl Default for TippingModel {
+    fn default() -> Self {
+        Self {
+            tip_probability: 0.7,
+            mean_rate: 0.12,
+            rate_std_dev: 0.04,
+            minimum_tip: 1.0,
+            expected_delivery_minutes: 45.0,
+            slow_delivery_penalty: 0.002,
+            rain_probability: 0.15,
+            rain_bonus: 0.05,
+        }
+    }
+}
+
+impl TippingModel {
+    /// Weather during the hour containing `time`.
+    ///
+    /// The weather only depends on the hour, so all orders placed within the same
+    /// hour see the same conditions, also across repeated runs.
+    pub fn weather(&self, time: DateTime<Utc>) -> Weather {
+        self.weather_with(time, self.rain_probability)
+    }
+
+    /// Weather during the hour containing `time`, if it rains with `rain_probability`.
+    pub(crate) fn weather_with(&self, time: DateTime<Utc>, rain_probability: f64) -> Weather {
+        let mut rng = StdRng::seed_from_u64(time.timestamp().div_euclid(3600) as u64);
+        if rng.random_bool(rain_probability.clamp(0.0, 1.0)) {
+            Weather::Rain
+        } else {
+            Weather::Clear
+        }
+    }
+
+    /// Sample the tip in the order currency for an order, `None` if the customer does not tip.
+    pub(crate) fn sample(
+        &self,
+        rng: &mut impl Rng,
+        total: f64,
+        delivery_time: Duration,
+        weather: Weather,
+    ) -> Option<f64> {
+        if total.is_nan() || total <= 0.0 || !rng.random_bool(self.tip_probability.clamp(0.0, 1.0))
+        {
+            return None;
+        }
+        let minutes_over =
+            (delivery_time.num_seconds() as f64 / 60.0 - self.expected_delivery_minutes).max(0.0);
+        let mut rate = self.mean_rate - self.slow_delivery_penalty * minutes_over;
+        if weather == Weather::Rain {
+            rate += self.rain_bonus;
+        }
+        rate += self.rate_std_dev * standard_normal(rng);
+        let tip = (total * rate.max(0.0)).max(self.minimum_tip);
+        Some((tip * 100.0).round() / 100.0)
+    }
+}
+
+/// Sample from a standard normal distribution using the Box-Muller transform.
+pub(super) fn standard_normal(rng: &mut impl Rng) -> f64 {
+    // shift into (0, 1] to keep the logarithm finite
+    let u1: f64 = 1.0 - rng.random::<f64>();
+    let u2: f64 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_tip(model: &TippingModel, delivery_time: Duration, weather: Weather) -> f64 {
+        let mut rng = StdRng::seed_from_u64(42);
+        let tips = (0..2_000)
+            .map(|_| {
+                model
+                    .sample(&mut rng, 40.0, delivery_time, weather)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+        tips.iter().sum::<f64>() / tips.len() as f64
+    }
+
+    #[test]
+    fn test_tipping_model() {
+        let model = TippingModel::default();
+        let fast = Duration::minutes(30);
+        let slow = Duration::minutes(90);
+
+        let clear = mean_tip(&model, fast, Weather::Clear);
+        assert!(clear > 0.0);
+        assert!(mean_tip(&model, slow, Weather::Clear) < clear);
+        assert!(mean_tip(&model, fast, Weather::Rain) > clear);
+
+        // tips respect the minimum and are rounded to cents
+        let mut rng = StdRng::seed_from_u64(7);
+        let always = TippingModel {
+            tip_probability: 1.0,
+            mean_rate: 0.0,
+            rate_std_dev: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            always.sample(&mut rng, 10.0, fast, Weather::Clear),
+            Some(1.0)
+        );
+        assert_eq!(always.sample(&mut rng, 0.0, fast, Weather::Clear), None);
+
+        let never = TippingModel {
+            tip_probability: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(never.sample(&mut rng, 10.0, fast, Weather::Clear), None);
+    }
+
+    #[test]
+    fn test_weather() {
+        let model = TippingModel::default();
+        let time = DateTime::parse_from_rfc3339("2025-01-01T12:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            model.weather(time),
+            model.weather(time + Duration::minutes(30))
+        );
+
+        let dry = TippingModel {
+            rain_probability: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(dry.weather(time), Weather::Clear);
+    }
+}
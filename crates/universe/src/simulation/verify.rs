@@ -0,0 +1,248 @@
+//! Verification of seeded runs by replaying them.
+//!
+//! Runs with a seed record how to replay them with every snapshot they take: the
+//! snapshot the run started from, its configuration, the number of steps taken and
+//! a SHA-256 hash over the events of all these steps. Replaying the steps from the
+//! same snapshot with the same configuration must produce the same events, so a
+//! differing hash flags nondeterminism in the engine, e.g. random choices not drawn
+//! from the seeded random numbers or events emitted in the iteration order of a hash
+//! map.
+//!
+//! Settings changed through the [`SimulationControl`](super::SimulationControl) of a
+//! run and agents added to it are not recorded, runs using them do not replay the same.
+
+use std::fmt;
+
+use arrow::array::{Array as _, AsArray as _};
+use chrono::{DateTime, Utc};
+use datafusion::prelude::{col, lit};
+use datafusion::scalar::ScalarValue;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use uuid::Uuid;
+
+use crate::{Error, EventPayload, Result, SimulationConfig, SimulationContextBuilder};
+
+use super::Simulation;
+
+/// How to replay the steps of a seeded run, stored with the properties of its snapshots.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ReplayRecord {
+    /// Snapshot of the simulation the run started from
+    from_snapshot: Uuid,
+    /// Steps taken since the run started
+    steps: usize,
+    /// Simulated time of the snapshot, no step of the replay passes it
+    until: DateTime<Utc>,
+    /// Hex encoded SHA-256 hash of the events of all steps
+    events_sha256: String,
+    config: SimulationConfig,
+}
+
+/// Running hash of the events of a seeded run.
+#[derive(Debug, Clone)]
+pub(crate) struct EventDigest {
+    from_snapshot: Uuid,
+    steps: usize,
+    hasher: Sha256,
+}
+
+impl EventDigest {
+    pub(crate) fn new(from_snapshot: Uuid) -> Self {
+        Self {
+            from_snapshot,
+            steps: 0,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Add the events of a step to the hash.
+    pub(crate) fn record(&mut self, events: &[EventPayload]) -> Result<()> {
+        for event in events {
+            self.hasher.update(serde_json::to_vec(event)?);
+        }
+        self.steps += 1;
+        Ok(())
+    }
+
+    fn events_sha256(&self) -> String {
+        self.hasher
+            .clone()
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Record of the steps so far, taken by a run with `config` up to `until`.
+    pub(crate) fn replay_record(
+        &self,
+        config: &SimulationConfig,
+        until: DateTime<Utc>,
+    ) -> ReplayRecord {
+        ReplayRecord {
+            from_snapshot: self.from_snapshot,
+            steps: self.steps,
+            until,
+            events_sha256: self.events_sha256(),
+            config: config.clone(),
+        }
+    }
+}
+
+/// Outcome of replaying a seeded run with [`verify_simulation`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReplayVerification {
+    pub simulation_id: Uuid,
+    /// Latest snapshot recording how to replay the run
+    pub snapshot_id: Uuid,
+    /// Snapshot the run was replayed from
+    pub from_snapshot: Uuid,
+    pub steps: usize,
+    /// Hash of the events recorded by the run
+    pub expected_sha256: String,
+    /// Hash of the events of the replay
+    pub actual_sha256: String,
+}
+
+impl ReplayVerification {
+    /// Whether the replay produced the same events as the run.
+    pub fn is_reproducible(&self) -> bool {
+        self.expected_sha256 == self.actual_sha256
+    }
+}
+
+impl fmt::Display for ReplayVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.is_reproducible() {
+            "reproducible"
+        } else {
+            "NOT reproducible"
+        };
+        writeln!(
+            f,
+            "simulation {} is {outcome} over {} steps from snapshot {}",
+            self.simulation_id, self.steps, self.from_snapshot
+        )?;
+        writeln!(f, "  expected events sha256: {}", self.expected_sha256)?;
+        writeln!(f, "  actual events sha256:   {}", self.actual_sha256)
+    }
+}
+
+/// Replay the latest seeded run of a simulation and compare the hash of its events.
+///
+/// The run is replayed in a fork of the snapshot it started from, named `verify`, so
+/// the results of the simulation itself are left untouched. The replay is a dry run,
+/// it does not take snapshots or write events.
+pub async fn verify_simulation(
+    builder: SimulationContextBuilder,
+    simulation_id: Uuid,
+) -> Result<ReplayVerification> {
+    let snapshots = builder
+        .load_snapshots()
+        .await?
+        .filter(
+            col("simulation_id").eq(lit(ScalarValue::Utf8View(Some(simulation_id.to_string())))),
+        )?
+        .sort(vec![col("id").sort(false, false)])?
+        .select_columns(&["id", "properties"])?
+        .collect()
+        .await?;
+    let mut latest = None;
+    'snapshots: for batch in &snapshots {
+        let (ids, properties) = (batch.column(0).as_string_view(), batch.column(1));
+        let properties = properties.as_string_view();
+        for row in 0..batch.num_rows() {
+            if properties.is_null(row) {
+                continue;
+            }
+            let mut value: serde_json::Value = serde_json::from_str(properties.value(row))?;
+            if let Some(replay) = value.get_mut("replay").map(serde_json::Value::take) {
+                let record: ReplayRecord = serde_json::from_value(replay)?;
+                latest = Some((Uuid::try_parse(ids.value(row))?, record));
+                break 'snapshots;
+            }
+        }
+    }
+    let Some((snapshot_id, record)) = latest else {
+        return Err(Error::not_found("seeded run of simulation", simulation_id));
+    };
+
+    let ctx = builder
+        .with_run_name("verify".to_string())
+        .fork_from(simulation_id, record.from_snapshot)
+        .build()
+        .await?;
+    let config = SimulationConfig {
+        simulation_start: *ctx.current_time(),
+        dry_run: true,
+        write_events: false,
+        ..record.config.clone()
+    };
+    let mut simulation = Simulation::builder()
+        .with_context(ctx)
+        .with_config(config)
+        .build()
+        .await?;
+    for _ in 0..record.steps {
+        simulation.advance(Some(record.until)).await?;
+    }
+    let Some(digest) = &simulation.replay else {
+        return Err(Error::internal("replay of a seeded run is not hashed"));
+    };
+
+    Ok(ReplayVerification {
+        simulation_id,
+        snapshot_id,
+        from_snapshot: record.from_snapshot,
+        steps: record.steps,
+        expected_sha256: record.events_sha256,
+        actual_sha256: digest.events_sha256(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulationContext;
+    use crate::test_utils::simulation_context_in;
+
+    #[test]
+    fn test_event_digest() -> Result<()> {
+        let start = "2025-01-01T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let steps = |last: usize| -> Result<EventDigest> {
+            let mut digest = EventDigest::new(Uuid::nil());
+            digest.record(&[EventPayload::step_finished(start, 0)])?;
+            digest.record(&[EventPayload::step_finished(start, last)])?;
+            Ok(digest)
+        };
+        let record = steps(1)?.replay_record(&SimulationConfig::default(), start);
+        assert_eq!(record.steps, 2);
+        assert_eq!(record.events_sha256.len(), 64);
+        assert_eq!(record.events_sha256, steps(1)?.events_sha256());
+        assert_ne!(record.events_sha256, steps(2)?.events_sha256());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_simulation() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+        let ctx = simulation_context_in(Some(location.clone()), Some(7)).await?;
+        let simulation_id = *ctx.simulation_id();
+        let start_time = *ctx.current_time();
+        let mut simulation = Simulation::builder()
+            .with_context(ctx)
+            .with_start_time(start_time)
+            .with_seed(7)
+            .build()
+            .await?;
+        simulation.run(200).await?;
+
+        let builder = SimulationContext::builder().with_working_directory(location);
+        let verification = verify_simulation(builder, simulation_id).await?;
+        assert_eq!(verification.steps, 200);
+        assert!(verification.is_reproducible(), "{verification}");
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+//! Concurrent stepping of sites.
+//!
+//! Sites only share state through the central [`State`](crate::state::State), which is
+//! updated with the events of each site once its step completed. Their steps are
+//! independent unless two sites may offer deliveries to the same couriers, i.e. the
+//! cells their couriers are searched in overlap. Sites are therefore grouped into
+//! waves of sites without overlapping cells. The sites of a wave are stepped
+//! concurrently, and their events are merged in the order of their ids, so runs
+//! remain reproducible. Sites with overlapping cells are placed in different waves
+//! and see the events of the sites stepped before them, as if all sites were stepped
+//! one after another.
+
+use std::collections::HashSet;
+
+use h3o::CellIndex;
+
+use crate::idents::SiteId;
+
+/// Group sites into waves of sites whose courier cells do not overlap.
+///
+/// Sites are placed in the order they are given, each in the first wave without an
+/// overlapping site.
+pub(crate) fn site_waves(
+    sites: impl IntoIterator<Item = (SiteId, HashSet<CellIndex>)>,
+) -> Vec<Vec<SiteId>> {
+    let mut waves: Vec<(Vec<SiteId>, HashSet<CellIndex>)> = Vec::new();
+    for (site_id, cells) in sites {
+        match waves
+            .iter_mut()
+            .find(|(_, covered)| covered.is_disjoint(&cells))
+        {
+            Some((wave, covered)) => {
+                wave.push(site_id);
+                covered.extend(cells);
+            }
+            None => waves.push((vec![site_id], cells)),
+        }
+    }
+    waves.into_iter().map(|(wave, _)| wave).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use h3o::{LatLng, Resolution};
+
+    use super::*;
+
+    fn cells(lat: f64, lng: f64) -> HashSet<CellIndex> {
+        LatLng::new(lat, lng)
+            .unwrap()
+            .to_cell(Resolution::Nine)
+            .grid_disk(2)
+    }
+
+    #[test]
+    fn test_site_waves() {
+        let amsterdam = SiteId::from_name("amsterdam");
+        let amsterdam_west = SiteId::from_name("amsterdam-west");
+        let london = SiteId::from_name("london");
+
+        // distant sites are stepped together
+        let waves = site_waves([
+            (amsterdam, cells(52.3676, 4.9041)),
+            (london, cells(51.5072, -0.1276)),
+        ]);
+        assert_eq!(waves, vec![vec![amsterdam, london]]);
+
+        // sites sharing couriers are stepped one after another
+        let waves = site_waves([
+            (amsterdam, cells(52.3676, 4.9041)),
+            (amsterdam_west, cells(52.3680, 4.9045)),
+            (london, cells(51.5072, -0.1276)),
+        ]);
+        assert_eq!(waves, vec![vec![amsterdam, london], vec![amsterdam_west]]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Time-windowed closures of streets in the routing networks.
+//!
+//! Roadworks, street festivals or accidents close streets for a while. Each
+//! [`RoadClosure`] names the streets it closes, or an area in which all streets are
+//! closed, and the window during which it applies. Routes planned within the window
+//! avoid the closed streets, so couriers take detours and deliveries take longer.
+//! Routes planned before a closure started are not re-planned, i.e. couriers already
+//! on their way pass through.
+//!
+//! Closures apply to the street networks of all sites; streets are matched by the
+//! `name` of the network edges, areas by the geometry of the edges.
+
+use chrono::{DateTime, Utc};
+use h3o::LatLng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+use super::movement::StreetEdge;
+
+/// A circular area in which all streets are closed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosureArea {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Radius of the area in meters
+    pub radius_m: f64,
+}
+
+/// Streets closed during a time window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoadClosure {
+    /// Name of the closure, e.g. the event causing it
+    pub name: String,
+
+    /// Names of the closed streets
+    #[serde(default)]
+    pub streets: Vec<String>,
+
+    /// Area in which all streets are closed
+    #[serde(default)]
+    pub area: Option<ClosureArea>,
+
+    /// Time from which the streets are closed
+    pub start: DateTime<Utc>,
+
+    /// Time at which the streets open again
+    pub end: DateTime<Utc>,
+}
+
+impl RoadClosure {
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.end <= self.start {
+            return Err(Error::invalid_data(format!(
+                "road closure '{}' must end after it starts",
+                self.name
+            )));
+        }
+        if self.streets.is_empty() && self.area.is_none() {
+            return Err(Error::invalid_data(format!(
+                "road closure '{}' needs streets or an area",
+                self.name
+            )));
+        }
+        if let Some(area) = &self.area {
+            LatLng::new(area.latitude, area.longitude)?;
+            if area.radius_m.is_nan() || area.radius_m <= 0.0 {
+                return Err(Error::invalid_data(format!(
+                    "area of road closure '{}' needs a positive radius",
+                    self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the streets are closed at `time`.
+    pub(crate) fn is_active(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Whether the closure applies to `edge`.
+    pub(super) fn closes(&self, edge: &StreetEdge<'_>) -> bool {
+        if let Some(name) = edge.name()
+            && self.streets.iter().any(|street| street == name)
+        {
+            return true;
+        }
+        let Some(area) = &self.area else {
+            return false;
+        };
+        let Ok(center) = LatLng::new(area.latitude, area.longitude) else {
+            return false;
+        };
+        edge.points().any(|point| {
+            LatLng::new(point.y(), point.x())
+                .is_ok_and(|point| point.distance_m(center) <= area.radius_m)
+        })
+    }
+}
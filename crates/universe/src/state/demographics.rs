@@ -0,0 +1,227 @@
+//! Demographic attributes of people.
+//!
+//! Every person gets an age bracket, a household size and an income band when the
+//! population is generated. They shape what customers order: larger households order
+//! more items, customers with lower incomes lean towards cheaper menu items, and the
+//! cuisines customers choose vary with their age.
+
+use std::collections::HashMap;
+
+use arrow::array::{Array as _, StringViewArray, UInt8Array};
+use rand::Rng;
+use rand::distr::Distribution as _;
+use rand::distr::weighted::WeightedIndex;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+
+use crate::Cuisine;
+
+/// Age bracket of a person.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, AsRefStr, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AgeBracket {
+    #[strum(serialize = "under_30")]
+    #[serde(rename = "under_30")]
+    Under30,
+    #[strum(serialize = "30_to_49")]
+    #[serde(rename = "30_to_49")]
+    From30To49,
+    #[strum(serialize = "50_to_64")]
+    #[serde(rename = "50_to_64")]
+    From50To64,
+    #[strum(serialize = "65_and_over")]
+    #[serde(rename = "65_and_over")]
+    From65,
+}
+
+impl AgeBracket {
+    pub const ALL: [AgeBracket; 4] = [
+        AgeBracket::Under30,
+        AgeBracket::From30To49,
+        AgeBracket::From50To64,
+        AgeBracket::From65,
+    ];
+
+    /// Relative weight with which people of the bracket choose items of `cuisine`.
+    pub fn cuisine_affinity(&self, cuisine: Option<Cuisine>) -> f64 {
+        match (self, cuisine) {
+            (AgeBracket::Under30, Some(Cuisine::FastFood)) => 1.5,
+            (AgeBracket::Under30, Some(Cuisine::Asian)) => 1.2,
+            (AgeBracket::From50To64, Some(Cuisine::FastFood)) => 0.8,
+            (AgeBracket::From65, Some(Cuisine::FastFood)) => 0.6,
+            (AgeBracket::From65, Some(Cuisine::Mexican)) => 0.8,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Income band of a person's household.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, AsRefStr, EnumString)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum IncomeBand {
+    Low,
+    Middle,
+    High,
+}
+
+impl IncomeBand {
+    pub const ALL: [IncomeBand; 3] = [IncomeBand::Low, IncomeBand::Middle, IncomeBand::High];
+
+    /// Elasticity of the choice of menu items to their price.
+    ///
+    /// Items are chosen with a weight of their price relative to the average price
+    /// raised to the negative sensitivity, so insensitive customers ignore prices.
+    pub fn price_sensitivity(&self) -> f64 {
+        match self {
+            IncomeBand::Low => 1.0,
+            IncomeBand::Middle => 0.5,
+            IncomeBand::High => 0.0,
+        }
+    }
+}
+
+/// Demographic attributes of a person.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Demographics {
+    pub age_bracket: AgeBracket,
+    pub household_size: u8,
+    pub income_band: IncomeBand,
+}
+
+impl Demographics {
+    /// Draw the attributes of a person.
+    pub(crate) fn sample(rng: &mut impl Rng) -> Self {
+        // shares of adults per bracket, households per size and households per band
+        let ages = WeightedIndex::new([0.22, 0.34, 0.25, 0.19]).expect("valid weights");
+        let households = WeightedIndex::new([0.28, 0.34, 0.16, 0.14, 0.08]).expect("valid weights");
+        let incomes = WeightedIndex::new([0.3, 0.5, 0.2]).expect("valid weights");
+        Self {
+            age_bracket: AgeBracket::ALL[ages.sample(rng)],
+            household_size: households.sample(rng) as u8 + 1,
+            income_band: IncomeBand::ALL[incomes.sample(rng)],
+        }
+    }
+
+    /// Read the attributes in `row` of the demographic columns, if the row has them.
+    pub(crate) fn from_arrays(
+        age_brackets: &StringViewArray,
+        household_sizes: &UInt8Array,
+        income_bands: &StringViewArray,
+        row: usize,
+    ) -> Option<Self> {
+        if age_brackets.is_null(row) || household_sizes.is_null(row) || income_bands.is_null(row) {
+            return None;
+        }
+        Some(Self {
+            age_bracket: age_brackets.value(row).parse().ok()?,
+            household_size: household_sizes.value(row),
+            income_band: income_bands.value(row).parse().ok()?,
+        })
+    }
+
+    /// Items added to an order of a default size, for the other members of the household.
+    pub fn extra_items(&self) -> usize {
+        usize::from(self.household_size.saturating_sub(2)).min(3)
+    }
+}
+
+/// Relative weights of menu items for customers of each age bracket and income band.
+///
+/// The `base` weight of each item, e.g. from the cuisine preferences of the simulation,
+/// is scaled by the affinity of the age bracket to the cuisine of the item, and by the
+/// price of the item relative to the average price raised to the negative price
+/// sensitivity of the income band. Prices are in a common currency; items without a
+/// price are weighed as if they had the average price.
+pub(crate) fn demographic_item_weights(
+    base: Option<&[f64]>,
+    cuisines: &[Option<Cuisine>],
+    prices: &[Option<f64>],
+) -> HashMap<(AgeBracket, IncomeBand), Vec<f64>> {
+    let known: Vec<_> = prices.iter().flatten().filter(|p| **p > 0.0).collect();
+    let average = known.iter().copied().sum::<f64>() / known.len().max(1) as f64;
+    let relative_price = |price: Option<f64>| match price {
+        Some(price) if price > 0.0 && average > 0.0 => price / average,
+        _ => 1.0,
+    };
+
+    let mut weights = HashMap::new();
+    for age_bracket in AgeBracket::ALL {
+        for income_band in IncomeBand::ALL {
+            let sensitivity = income_band.price_sensitivity();
+            let item_weights = (0..cuisines.len())
+                .map(|idx| {
+                    base.map_or(1.0, |base| base[idx])
+                        * age_bracket.cuisine_affinity(cuisines[idx])
+                        * relative_price(prices[idx]).powf(-sensitivity)
+                })
+                .collect();
+            weights.insert((age_bracket, income_band), item_weights);
+        }
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn test_demographics() {
+        assert_eq!(AgeBracket::From30To49.as_ref(), "30_to_49");
+        assert_eq!(
+            "65_and_over".parse::<AgeBracket>().unwrap(),
+            AgeBracket::From65
+        );
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let people: Vec<_> = (0..1000).map(|_| Demographics::sample(&mut rng)).collect();
+        assert!(people.iter().all(|p| (1..=5).contains(&p.household_size)));
+        for band in IncomeBand::ALL {
+            assert!(people.iter().any(|p| p.income_band == band));
+        }
+
+        let single = Demographics {
+            age_bracket: AgeBracket::Under30,
+            household_size: 1,
+            income_band: IncomeBand::Low,
+        };
+        assert_eq!(single.extra_items(), 0);
+        let family = Demographics {
+            household_size: 5,
+            ..single
+        };
+        assert_eq!(family.extra_items(), 3);
+    }
+
+    #[test]
+    fn test_demographic_item_weights() {
+        let cuisines = [Some(Cuisine::FastFood), Some(Cuisine::Asian), None];
+        let prices = [Some(5.0), Some(15.0), None];
+        let weights = demographic_item_weights(None, &cuisines, &prices);
+        assert_eq!(weights.len(), 12);
+
+        // customers with low incomes prefer cheap items, others do not mind the price
+        let low = &weights[&(AgeBracket::From30To49, IncomeBand::Low)];
+        assert!(low[0] > low[1]);
+        assert_eq!(low[2], 1.0);
+        let high = &weights[&(AgeBracket::From30To49, IncomeBand::High)];
+        assert_eq!(high, &vec![1.0, 1.0, 1.0]);
+
+        // young customers prefer fast food
+        let young = &weights[&(AgeBracket::Under30, IncomeBand::High)];
+        let senior = &weights[&(AgeBracket::From65, IncomeBand::High)];
+        assert!(young[0] > senior[0]);
+
+        let base = [2.0, 1.0, 0.0];
+        let weights = demographic_item_weights(Some(&base), &cuisines, &prices);
+        assert_eq!(
+            weights[&(AgeBracket::From30To49, IncomeBand::High)],
+            vec![2.0, 1.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Graph representation of the simulation object model.
+//!
+//! The object hierarchy (sites → kitchens → stations, brands → menu items → ingredients)
+//! can be rendered to GraphViz DOT, D2 or a JSON node/edge list to audit a generated
+//! universe before starting long simulation runs.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use arrow::array::Array as _;
+use arrow::array::cast::AsArray as _;
+use serde::Serialize;
+use strum::{AsRefStr, Display, EnumString};
+use uuid::Uuid;
+
+use crate::Error;
+use crate::error::Result;
+use crate::models::MenuItem;
+
+use super::{ObjectData, ObjectLabel};
+
+/// Output format for an [`ObjectGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    D2,
+    Json,
+}
+
+/// A node in the object graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    /// URI reference of the node, used as stable identifier in all formats
+    pub id: String,
+
+    /// Object id, not available for ingredients
+    pub object_id: Option<Uuid>,
+
+    /// Kind of node (e.g. `site`, `menu_item` or `ingredient`)
+    pub label: String,
+
+    /// Human readable name
+    pub name: String,
+}
+
+/// A parent → child relation in the object graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+}
+
+/// Object hierarchy of a simulation as nodes and edges.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ObjectGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ObjectGraph {
+    pub(crate) fn try_new(data: &ObjectData) -> Result<Self> {
+        let objects = data.objects();
+        let ids = objects
+            .column_by_name("id")
+            .map(|c| c.as_fixed_size_binary());
+        let parent_ids = objects
+            .column_by_name("parent_id")
+            .map(|c| c.as_fixed_size_binary());
+        let labels = objects.column_by_name("label").map(|c| c.as_string_view());
+        let names = objects.column_by_name("name").map(|c| c.as_list::<i32>());
+        let properties = objects
+            .column_by_name("properties")
+            .map(|c| c.as_string::<i64>());
+        let (Some(ids), Some(parent_ids), Some(labels), Some(names), Some(properties)) =
+            (ids, parent_ids, labels, names, properties)
+        else {
+            return Err(Error::invalid_data("missing columns in object data"));
+        };
+
+        let mut graph = ObjectGraph::default();
+        let mut ingredients = HashSet::new();
+        for idx in 0..objects.num_rows() {
+            let object_id = Uuid::from_slice(ids.value(idx))?;
+            let label = labels.value(idx);
+            let id = node_id(data, &object_id);
+            let name = names
+                .value(idx)
+                .as_string::<i32>()
+                .iter()
+                .flatten()
+                .last()
+                .unwrap_or_default()
+                .to_string();
+
+            if parent_ids.is_valid(idx) {
+                let parent_id = Uuid::from_slice(parent_ids.value(idx))?;
+                graph.edges.push(GraphEdge {
+                    source: node_id(data, &parent_id),
+                    target: id.clone(),
+                });
+            }
+
+            if label == ObjectLabel::MenuItem.as_ref() && properties.is_valid(idx) {
+                let item: MenuItem = serde_json::from_str(properties.value(idx))?;
+                for ingredient in item.ingredients {
+                    let ingredient_id = ingredient.ingredient_ref;
+                    if ingredients.insert(ingredient_id.clone()) {
+                        graph.nodes.push(GraphNode {
+                            id: ingredient_id.clone(),
+                            object_id: None,
+                            label: "ingredient".into(),
+                            name: ingredient_id
+                                .rsplit('/')
+                                .next()
+                                .unwrap_or_default()
+                                .to_string(),
+                        });
+                    }
+                    graph.edges.push(GraphEdge {
+                        source: id.clone(),
+                        target: ingredient_id,
+                    });
+                }
+            }
+
+            graph.nodes.push(GraphNode {
+                id,
+                object_id: Some(object_id),
+                label: label.to_string(),
+                name,
+            });
+        }
+
+        Ok(graph)
+    }
+
+    /// Render the graph in the given format.
+    pub fn render(&self, format: GraphFormat) -> Result<String> {
+        Ok(match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::D2 => self.to_d2(),
+            GraphFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    /// Render the graph as GraphViz DOT.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph caspers {\n  rankdir=LR;\n  node [shape=box];\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "  \"{}\" [label=\"{}\\n({})\", class=\"{}\"];",
+                escape(&node.id),
+                escape(&node.name),
+                node.label,
+                node.label
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "  \"{}\" -> \"{}\";",
+                escape(&edge.source),
+                escape(&edge.target)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Render the graph as D2 diagram.
+    pub fn to_d2(&self) -> String {
+        let mut out = String::from("direction: right\n");
+        for node in &self.nodes {
+            let _ = writeln!(
+                out,
+                "\"{}\": \"{} ({})\"",
+                escape(&node.id),
+                escape(&node.name),
+                node.label
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "\"{}\" -> \"{}\"",
+                escape(&edge.source),
+                escape(&edge.target)
+            );
+        }
+        out
+    }
+}
+
+/// Nodes are identified by their URI reference, falling back to the object id.
+fn node_id(data: &ObjectData, id: &Uuid) -> String {
+    data.object_uri(id)
+        .map(|uri| uri.to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Template;
+
+    #[test]
+    fn test_object_graph() -> Result<()> {
+        let setup = Template::default().load()?;
+        let objects = ObjectData::try_new(setup.object_data()?)?;
+        let graph = objects.graph()?;
+
+        let site = setup.sites[0].info.as_ref().unwrap();
+        let site_uri = crate::SiteId::uri_ref(&site.name);
+        assert!(
+            graph
+                .nodes
+                .iter()
+                .any(|n| n.id == site_uri && n.label == "site")
+        );
+        assert!(graph.edges.iter().any(|e| e.source == site_uri));
+        assert!(graph.nodes.iter().any(|n| n.label == "ingredient"));
+
+        // every edge connects known nodes
+        let ids: HashSet<_> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert!(
+            graph
+                .edges
+                .iter()
+                .all(|e| ids.contains(e.source.as_str()) && ids.contains(e.target.as_str()))
+        );
+
+        let dot = graph.render(GraphFormat::Dot)?;
+        assert!(dot.starts_with("digraph"));
+        assert!(dot.contains(&format!("\"{site_uri}\" ->")));
+        let json: serde_json::Value = serde_json::from_str(&graph.render(GraphFormat::Json)?)?;
+        assert_eq!(json["nodes"].as_array().unwrap().len(), graph.nodes.len());
+
+        Ok(())
+    }
+}
@@ -4,40 +4,58 @@
 //! Whenever feasible, state is tracked as Arrow RecordBatches for seamless introp with
 //! external data storages that might be used to store the state.
 
-use std::collections::HashMap;
-use std::sync::{Arc, LazyLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::time::Duration;
 
 use arrow::array::cast::AsArray as _;
 use chrono::{DateTime, Utc};
 use datafusion::prelude::{Expr, lit};
 use datafusion::scalar::ScalarValue;
-use geo_traits::PointTrait;
+use indexmap::IndexMap;
 use itertools::Itertools as _;
 use uuid::{ContextV7, Timestamp, Uuid};
 
 use crate::{
     Error, EventPayload, OrderLineUpdatedPayload, OrderUpdatedPayload, Result, SimulationConfig,
-    SimulationContext,
+    SimulationContext, SubstitutionStatus,
 };
 use crate::{OrderDataBuilder, idents::*};
 
 use self::movement::JourneyPlanner;
 
-pub(crate) use self::movement::{Journey, RoutingData, Transport};
-pub use self::objects::{ObjectData, ObjectLabel};
+pub use self::closures::{ClosureArea, RoadClosure};
+pub(crate) use self::demographics::demographic_item_weights;
+pub use self::demographics::{AgeBracket, Demographics, IncomeBand};
+pub use self::graph::{GraphEdge, GraphFormat, GraphNode, ObjectGraph};
+pub use self::movement::{DEFAULT_JOURNEY_TOLERANCE_M, Transport};
+pub(crate) use self::movement::{Journey, RoutingData};
+pub use self::objects::{IncompatibleMenuItem, ObjectData, ObjectLabel};
 pub use self::orders::OrderData;
 pub(crate) use self::orders::{OrderLineStatus, OrderStatus};
 pub(crate) use self::parse_json::parse_json;
 pub use self::population::{
     PersonRole, PersonState, PersonStatus, PersonStatusFlag, PopulationData,
 };
-
+pub use self::preferences::{FoodPreferences, MenuItemDiet};
+pub use self::properties::{PropertySchemas, PropertyViolation};
+pub use self::schedule::DailySchedule;
+pub(crate) use self::schedule::hours_between;
+pub use self::stats::{BatchStats, ColumnStats, SimulationStats, SiteLoad, StateStats};
+
+mod closures;
+mod demographics;
+mod graph;
 mod movement;
 mod objects;
 mod orders;
 mod parse_json;
 mod population;
+mod preferences;
+mod properties;
+mod schedule;
+mod stats;
+mod visits;
 
 #[derive(Debug, thiserror::Error)]
 enum StateError {
@@ -71,7 +89,8 @@ pub struct State {
     /// Order data
     orders: OrderData,
 
-    ts_context: ContextV7,
+    /// Shared by the sites stepping in parallel, see [`current_timestamp`](Self::current_timestamp)
+    ts_context: Mutex<ContextV7>,
 }
 
 impl State {
@@ -82,16 +101,24 @@ impl State {
         orders: OrderData,
         routing: HashMap<SiteId, RoutingData>,
     ) -> Self {
+        // streets closed for special events are closed like any other
+        let mut closures = config.road_closures.clone();
+        if let Some(calendar) = &config.calendar {
+            closures.extend(calendar.road_closures());
+        }
         Self {
             time_step: Duration::from_secs(config.time_increment.num_seconds() as u64),
             time: config.simulation_start,
             population,
             objects,
             orders,
-            ts_context: ContextV7::new(),
+            ts_context: Mutex::new(ContextV7::new()),
             routing: routing
                 .into_iter()
-                .map(|(id, data)| (id, data.into_trip_planner()))
+                .map(|(id, data)| {
+                    let planner = data.into_trip_planner();
+                    (id, planner.with_closures(&closures))
+                })
                 .collect(),
         }
     }
@@ -100,6 +127,13 @@ impl State {
         &self.objects
     }
 
+    /// Mutable access to the object data.
+    ///
+    /// Changes are reported as events at the start of the next simulation step.
+    pub fn objects_mut(&mut self) -> &mut ObjectData {
+        &mut self.objects
+    }
+
     pub fn population(&self) -> &PopulationData {
         &self.population
     }
@@ -108,6 +142,84 @@ impl State {
         &self.orders
     }
 
+    /// Size and cardinality of the in-memory state structures.
+    pub fn stats(&self) -> StateStats {
+        StateStats {
+            structures: vec![
+                BatchStats::from_batch("objects", self.objects.objects()),
+                BatchStats::from_batch("population", self.population.snapshot()),
+                BatchStats::from_batch("orders", self.orders.batch_orders()),
+                BatchStats::from_batch("order_lines", self.orders.batch_lines()),
+            ],
+            tables: vec![],
+        }
+    }
+
+    /// Counts of orders by status and people by status and role.
+    pub fn simulation_stats(&self) -> Result<SimulationStats> {
+        let mut site_names = HashMap::new();
+        for site in self.objects.sites()? {
+            site_names.insert(site.id(), site.properties()?.name);
+        }
+        let site_name = |site_id: &[u8]| -> Result<String> {
+            let site_id: SiteId = site_id.try_into()?;
+            Ok(site_names
+                .get(&site_id)
+                .cloned()
+                .unwrap_or_else(|| site_id.to_string()))
+        };
+
+        let mut sites: BTreeMap<_, SiteLoad> = site_names
+            .values()
+            .map(|name| (name.clone(), Default::default()))
+            .collect();
+        let mut orders_by_status = BTreeMap::new();
+        for order in self.orders.all_orders() {
+            *orders_by_status
+                .entry(order.status().to_string())
+                .or_default() += 1;
+            let load = sites.entry(site_name(order.site_id())?).or_default();
+            match order.status() {
+                status if status == OrderStatus::Submitted.as_ref() => load.submitted += 1,
+                status if status == OrderStatus::Processing.as_ref() => load.processing += 1,
+                status if status == OrderStatus::Ready.as_ref() => load.ready += 1,
+                _ => (),
+            }
+        }
+        let mut busy_couriers = 0;
+        for order_id in self.population.orders_in_delivery() {
+            busy_couriers += 1;
+            if let Some(order) = self.orders.order(order_id) {
+                sites
+                    .entry(site_name(order.site_id())?)
+                    .or_default()
+                    .delivering += 1;
+            }
+        }
+
+        let people_by_status = self
+            .population
+            .status_counts()
+            .into_iter()
+            .map(|(flag, count)| (flag.as_ref().to_string(), count))
+            .collect();
+        let active_journeys = self.population.count_with_status(&[
+            PersonStatusFlag::Moving,
+            PersonStatusFlag::Delivering,
+            PersonStatusFlag::WaitingForCustomer,
+        ]);
+        Ok(SimulationStats {
+            simulation_time: self.time,
+            orders_by_status,
+            people_by_status,
+            people_by_role: self.population.role_counts()?,
+            active_journeys,
+            busy_couriers,
+            sites,
+            ..Default::default()
+        })
+    }
+
     pub fn trip_planner(&self, site_id: &SiteId) -> Option<&JourneyPlanner> {
         self.routing.get(site_id)
     }
@@ -126,8 +238,12 @@ impl State {
 
     /// Timestamp used to generate v7 uuids
     pub fn current_timestamp(&self) -> Timestamp {
+        let ts_context = self
+            .ts_context
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
         Timestamp::from_unix(
-            &self.ts_context,
+            &*ts_context,
             self.time.timestamp() as u64,
             self.time.timestamp_subsec_nanos(),
         )
@@ -141,6 +257,11 @@ impl State {
         self.time + self.time_step
     }
 
+    /// Set the length of the next step, e.g. as chosen by the event scheduler.
+    pub(crate) fn set_time_step(&mut self, time_step: Duration) {
+        self.time_step = time_step;
+    }
+
     pub(crate) fn process_site_events(&mut self, events: &[EventPayload]) -> Result<()> {
         let order_line_updates = events.iter().filter_map(|event| match event {
             EventPayload::OrderLineUpdated(payload) => Some(payload),
@@ -152,6 +273,28 @@ impl State {
             _ => None,
         });
         self.update_orders(order_updates)?;
+        // accepted substitutions change the price, rejected ones remove the item
+        let total_updates = events.iter().filter_map(|event| match event {
+            EventPayload::SubstitutionUpdated(payload)
+                if payload.status != SubstitutionStatus::Suggested =>
+            {
+                Some((payload.order_id, payload.price_delta))
+            }
+            _ => None,
+        });
+        self.orders.adjust_totals(total_updates)?;
+        let footprints = events.iter().filter_map(|event| match event {
+            EventPayload::DeliveryFootprint(payload) => Some((payload.order_id, payload.co2_g)),
+            _ => None,
+        });
+        self.orders.set_footprints(footprints)?;
+        for event in events {
+            if let EventPayload::SiteCheckOut(payload) = event {
+                self.population
+                    .site_visits_mut()
+                    .checked_out(&payload.person_id);
+            }
+        }
 
         Ok(())
     }
@@ -167,16 +310,7 @@ impl State {
 
         let mut builder = OrderDataBuilder::new();
         for order in new_orders {
-            builder.add_order(
-                order.site_id,
-                order.person_id,
-                order
-                    .destination
-                    .coord()
-                    .ok_or_else(|| Error::invalid_data("no destination coordinates"))?
-                    .try_into()?,
-                &order.items,
-            )?;
+            builder.add_created_order(order)?;
         }
         let order_data = builder.finish()?;
 
@@ -194,6 +328,32 @@ impl State {
         Ok(order_ids)
     }
 
+    /// Apply recorded events in the order they were emitted.
+    ///
+    /// New orders as well as order, order line and person status changes are applied.
+    /// Positions of moving people and changes to objects are not part of the events,
+    /// so they stay as they were.
+    pub(crate) async fn apply_events(
+        &mut self,
+        ctx: &SimulationContext,
+        events: &[EventPayload],
+    ) -> Result<()> {
+        self.process_population_events(events)?;
+        self.process_site_events(events)?;
+
+        // only the last status of every person matters
+        let mut updates = IndexMap::new();
+        for event in events {
+            if let EventPayload::PersonUpdated(payload) = event {
+                updates.insert(&payload.person_id, &payload.status);
+            }
+        }
+        if !updates.is_empty() {
+            self.population.update_person_status(ctx, updates).await?;
+        }
+        Ok(())
+    }
+
     fn update_order_lines<'a>(
         &mut self,
         updates: impl IntoIterator<Item = &'a OrderLineUpdatedPayload>,
@@ -284,3 +444,87 @@ pub trait EntityView {
         Ok(serde_json::from_str(raw)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::RecordBatch;
+    use arrow::datatypes::UInt32Type;
+    use datafusion::prelude::SessionContext;
+    use h3o::{LatLng, Resolution};
+
+    use super::*;
+    use crate::Template;
+
+    #[tokio::test]
+    async fn test_simulation_stats() -> Result<()> {
+        let objects = ObjectData::try_new(Template::default().load()?.object_data()?)?;
+        let mut builder = PopulationData::builder();
+        builder.add_site(10, 51.518898098201326, -0.13381370382489707)?;
+        let population = SessionContext::new().read_batch(builder.finish()?)?;
+        let population = PopulationData::try_new(population).await?;
+        let num_people = population.snapshot().num_rows();
+
+        let state = State::new(
+            &Default::default(),
+            objects,
+            population,
+            OrderData::empty(),
+            Default::default(),
+        );
+        let stats = state.simulation_stats()?;
+        assert_eq!(stats.simulation_time, state.current_time());
+        assert!(stats.orders_by_status.is_empty());
+        assert_eq!(stats.people_by_status.get("idle"), Some(&num_people));
+        assert_eq!(stats.people_by_role.values().sum::<usize>(), num_people);
+        assert_eq!(stats.active_journeys, 0);
+        assert_eq!(stats.busy_couriers, 0);
+        assert_eq!(stats.courier_utilization(), 0.0);
+        // every site is listed, even without any orders
+        assert_eq!(stats.sites.len(), state.objects().sites()?.count());
+        assert!(stats.sites.values().all(|load| load.queued() == 0));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_idle_people_near() -> Result<()> {
+        let ctx = SimulationContext::builder()
+            .with_use_in_memory(true)
+            .build()
+            .await?;
+        let site = LatLng::new(51.518898098201326, -0.13381370382489707)?;
+        let mut builder = PopulationData::builder();
+        builder.add_site(200, site.lat(), site.lng())?;
+        let population = ctx.ctx().read_batch(builder.finish()?)?;
+        let population = PopulationData::try_new(population).await?;
+
+        let center = site.to_cell(Resolution::Nine);
+        let near = |rings| population.idle_people_near(&ctx, center, rings, &PersonRole::Customer);
+        let rings = |batches: Vec<RecordBatch>| {
+            batches
+                .iter()
+                .flat_map(|batch| {
+                    let column = batch.column_by_name("ring").unwrap();
+                    column.as_primitive::<UInt32Type>().values().to_vec()
+                })
+                .collect_vec()
+        };
+
+        let in_cell = rings(near(0).await?.collect().await?);
+        let expected = population
+            .idle_people_in_cell(&ctx, center, &PersonRole::Customer)
+            .await?
+            .count()
+            .await?;
+        assert_eq!(in_cell.len(), expected);
+        assert!(in_cell.iter().all(|ring| *ring == 0));
+
+        // people in the outer rings follow those closer to the center
+        let nearby = rings(near(2).await?.collect().await?);
+        assert!(nearby.len() > in_cell.len());
+        assert!(nearby.is_sorted());
+        assert!(nearby.iter().all(|ring| *ring <= 2));
+
+        Ok(())
+    }
+}
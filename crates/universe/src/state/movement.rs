@@ -1,13 +1,15 @@
-use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
 
+use arrow::array::Array as _;
 use arrow::array::cast::AsArray as _;
 use arrow::array::{RecordBatch, types::Float64Type};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow_schema::extension::Uuid as UuidExtension;
+use chrono::{DateTime, Utc};
 use datafusion::common::SchemaExt;
 use fast_paths::{FastGraph, InputGraph, PathCalculator};
-use geo::Point;
+use geo::{Coord, LineString, Point, SimplifyIdx as _};
 use geo_traits::PointTrait;
 use geo_traits::to_geo::{ToGeoCoord, ToGeoLineString};
 use geoarrow::array::{LineStringArray, PointArray};
@@ -22,15 +24,36 @@ use uuid::Uuid;
 
 use crate::Result;
 
+use super::closures::RoadClosure;
+
+/// Tolerance in meters within which journeys are simplified when they are stored.
+pub const DEFAULT_JOURNEY_TOLERANCE_M: f64 = 5.0;
+
+/// Meters per degree of latitude.
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+/// H3 resolution at which street nodes are indexed to find nodes near a point.
+const NODE_INDEX_RESOLUTION: Resolution = Resolution::Nine;
+
+/// Means of transport of a journey.
+///
+/// Configuration files may name transports in snake case, e.g. `"foot"`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum Transport {
+    #[serde(alias = "foot")]
     Foot,
     #[default]
+    #[serde(alias = "bicycle")]
     Bicycle,
+    #[serde(alias = "car")]
     Car,
+    #[serde(alias = "bus")]
     Bus,
+    #[serde(alias = "train")]
     Train,
+    #[serde(alias = "plane")]
     Plane,
+    #[serde(alias = "ship")]
     Ship,
 }
 
@@ -51,6 +74,41 @@ impl Transport {
     pub fn default_velocity_m_s(&self) -> f64 {
         self.default_velocity_km_h() / 3.6
     }
+
+    /// OSM `highway` values of streets the transport may not use.
+    fn forbidden_highways(&self) -> &'static [&'static str] {
+        match self {
+            Transport::Foot => &["motorway", "motorway_link"],
+            Transport::Bicycle => &["motorway", "motorway_link", "steps"],
+            Transport::Car | Transport::Bus => &[
+                "footway",
+                "pedestrian",
+                "path",
+                "cycleway",
+                "bridleway",
+                "steps",
+                "corridor",
+                "elevator",
+                "platform",
+            ],
+            Transport::Train | Transport::Plane | Transport::Ship => &[],
+        }
+    }
+
+    /// Whether the transport may use the street.
+    ///
+    /// Edges merged from several ways carry a list of `highway` values, e.g.
+    /// `['residential', 'footway']`; the street is forbidden if any part of it is.
+    /// Streets without a `highway` value are open to all transports.
+    pub(crate) fn may_use(&self, edge: &StreetEdge<'_>) -> bool {
+        let Some(highway) = edge.highway() else {
+            return true;
+        };
+        let forbidden = self.forbidden_highways();
+        !highway
+            .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .any(|value| forbidden.contains(&value))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -80,6 +138,10 @@ pub struct Journey {
 }
 
 impl Journey {
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
     pub fn distance_m(&self) -> usize {
         self.legs.iter().map(|leg| leg.distance_m).sum()
     }
@@ -154,6 +216,66 @@ impl Journey {
         (self.current_leg_index, self.current_leg_progress)
     }
 
+    /// The journey with its polyline simplified to within `tolerance_m` meters.
+    ///
+    /// Vertices are dropped with the Douglas-Peucker algorithm. Merged legs keep the
+    /// sum of their distances, so the total distance and the progress along the
+    /// journey are unchanged. The first leg starts at the unknown origin and is kept.
+    pub(crate) fn simplified(&self, tolerance_m: f64) -> Journey {
+        if self.legs.len() < 3 {
+            return self.clone();
+        }
+        // project onto a plane around the first vertex, so the tolerance is in meters
+        let reference = self.legs[0].destination;
+        let scale_x = METERS_PER_DEGREE * reference.y().to_radians().cos();
+        let line: LineString = self
+            .legs
+            .iter()
+            .map(|leg| {
+                Coord::from((
+                    (leg.destination.x() - reference.x()) * scale_x,
+                    (leg.destination.y() - reference.y()) * METERS_PER_DEGREE,
+                ))
+            })
+            .collect();
+        let kept = line.simplify_idx(&tolerance_m);
+
+        let mut legs = vec![self.legs[0].clone()];
+        // index and progress of the current leg among the simplified legs
+        let mut current = (0, self.current_leg_progress);
+        for (start, end) in kept.iter().copied().tuple_windows() {
+            let merged = &self.legs[start + 1..=end];
+            let distance_m: usize = merged.iter().map(|leg| leg.distance_m).sum();
+            if (start + 1..=end).contains(&self.current_leg_index) {
+                let offset = self.current_leg_index - start - 1;
+                let completed_m = merged[..offset]
+                    .iter()
+                    .map(|leg| leg.distance_m)
+                    .sum::<usize>() as f64
+                    + merged[offset].distance_m as f64 * self.current_leg_progress;
+                let progress = match distance_m {
+                    0 => 0.0,
+                    _ => completed_m / distance_m as f64,
+                };
+                current = (legs.len(), progress);
+            }
+            legs.push(JourneyLeg {
+                destination: self.legs[end].destination,
+                distance_m,
+            });
+        }
+        if self.is_done() {
+            current = (legs.len(), 0.0);
+        }
+
+        Journey {
+            transport: self.transport,
+            legs,
+            current_leg_index: current.0,
+            current_leg_progress: current.1,
+        }
+    }
+
     pub fn has_started(&self) -> bool {
         self.current_leg_progress > 1e-10 || self.current_leg_index > 0
     }
@@ -188,6 +310,13 @@ impl Journey {
         self.total_distance_m() as f64 - self.distance_completed_m()
     }
 
+    /// Returns the time needed to travel the remaining distance
+    pub(crate) fn remaining_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(
+            self.distance_remaining_m().max(0.0) / self.transport.default_velocity_m_s(),
+        )
+    }
+
     /// Returns the progress percentage of the entire journey (0.0 to 1.0)
     pub(crate) fn progress_percentage(&self) -> f64 {
         if self.total_distance_m() == 0 {
@@ -200,6 +329,32 @@ impl Journey {
     pub(crate) fn estimated_time_remaining_s(&self) -> f64 {
         self.distance_remaining_m() / self.transport.default_velocity_m_s()
     }
+
+    /// Returns the travel velocity of the journey in m/s
+    pub(crate) fn velocity_m_s(&self) -> f64 {
+        self.transport.default_velocity_m_s()
+    }
+
+    /// Returns the position after travelling `distance_m` along the journey from `origin`.
+    ///
+    /// Without an origin the journey starts at the destination of the first leg.
+    pub(crate) fn position_at(&self, origin: Option<Point>, distance_m: f64) -> Option<Point> {
+        let mut from = origin.or_else(|| self.legs.first().map(|leg| leg.destination))?;
+        let mut remaining = distance_m.max(0.0);
+        for leg in &self.legs {
+            let leg_distance = leg.distance_m as f64;
+            if leg_distance > remaining {
+                let ratio = remaining / leg_distance;
+                return Some(Point::new(
+                    from.x() + (leg.destination.x() - from.x()) * ratio,
+                    from.y() + (leg.destination.y() - from.y()) * ratio,
+                ));
+            }
+            remaining -= leg_distance;
+            from = leg.destination;
+        }
+        Some(from)
+    }
 }
 
 impl<T: Into<JourneyLeg>> FromIterator<T> for Journey {
@@ -213,23 +368,84 @@ impl<T: Into<JourneyLeg>> FromIterator<T> for Journey {
     }
 }
 
+/// Transport and indices of the active closures a routing graph is prepared for.
+type GraphKey = (Transport, Vec<usize>);
+
 /// Auxiliary structure to handle journey planning and routing.
 pub struct JourneyPlanner {
     routing: RoutingData,
-    graph: FastGraph,
+    /// Number of nodes in the graph of all streets
+    num_nodes: usize,
+    /// Road closures affecting the network, with the indices of the edges they close
+    closures: Vec<(RoadClosure, Vec<usize>)>,
+    /// Graphs of the streets open to each transport, for each combination of active closures
+    graphs: Mutex<HashMap<GraphKey, Arc<FastGraph>>>,
+    /// Indices of the street nodes in each cell, built on first use
+    node_cells: OnceLock<HashMap<CellIndex, Vec<usize>>>,
 }
 
 impl JourneyPlanner {
     fn new(routing: RoutingData) -> Self {
-        let graph = routing.build_router();
-        Self { routing, graph }
+        let num_nodes = routing.input_graph(|_, _| true).get_num_nodes();
+        Self {
+            routing,
+            num_nodes,
+            closures: Vec::new(),
+            graphs: Mutex::new(HashMap::new()),
+            node_cells: OnceLock::new(),
+        }
+    }
+
+    /// Avoid the streets closed by `closures` when planning within their windows.
+    ///
+    /// Closures which do not close any street of the network are ignored.
+    pub(crate) fn with_closures(mut self, closures: &[RoadClosure]) -> Self {
+        self.closures = closures
+            .iter()
+            .filter_map(|closure| {
+                let edges = self
+                    .routing
+                    .edges()
+                    .enumerate()
+                    .filter(|(_, edge)| closure.closes(edge))
+                    .map(|(index, _)| index)
+                    .collect_vec();
+                (!edges.is_empty()).then(|| (closure.clone(), edges))
+            })
+            .collect();
+        self.graphs = Mutex::new(HashMap::new());
+        self
+    }
+
+    /// The routing graph of the streets open to `transport` at `time`.
+    ///
+    /// Graphs are prepared once for every transport and combination of active closures.
+    fn graph(&self, transport: Transport, time: DateTime<Utc>) -> Arc<FastGraph> {
+        let active = self
+            .closures
+            .iter()
+            .positions(|(closure, _)| closure.is_active(time))
+            .collect_vec();
+        let mut graphs = self.graphs.lock().unwrap_or_else(|err| err.into_inner());
+        graphs
+            .entry((transport, active))
+            .or_insert_with_key(|(transport, active)| {
+                let closed: HashSet<_> = active
+                    .iter()
+                    .flat_map(|index| self.closures[*index].1.iter().copied())
+                    .collect();
+                Arc::new(self.routing.build_router(|index, edge| {
+                    !closed.contains(&index) && transport.may_use(edge)
+                }))
+            })
+            .clone()
     }
 
     /// Get a path calculator for the routing graph.
     ///
     /// This calculator should be reused for repeated calls to the plan method.
     pub fn get_router(&self) -> PathCalculator {
-        fast_paths::create_calculator(&self.graph)
+        PathCalculator::new(self.num_nodes)
     }
 
     /// For a given point, find a nearby node in the routing graph.
@@ -245,42 +461,86 @@ impl JourneyPlanner {
             .map(|node| *node.id())
     }
 
+    /// A street node close to `point` with its position.
+    ///
+    /// Nodes in the cell of the point are preferred over nodes in the neighboring cells,
+    /// `None` is returned if neither has any.
+    pub(crate) fn node_near(&self, point: &LatLng) -> Option<(Uuid, Point)> {
+        let node_cells = self.node_cells.get_or_init(|| {
+            let mut node_cells: HashMap<_, Vec<_>> = HashMap::new();
+            for node in self.routing.nodes() {
+                if let Some(cell) = node.cell(NODE_INDEX_RESOLUTION) {
+                    node_cells.entry(cell).or_default().push(node.valid_index);
+                }
+            }
+            node_cells
+        });
+        let mut disk: Vec<(CellIndex, u32)> =
+            point.to_cell(NODE_INDEX_RESOLUTION).grid_disk_distances(1);
+        disk.sort_by_key(|(_, distance)| *distance);
+        let index = disk
+            .iter()
+            .find_map(|(cell, _)| node_cells.get(cell)?.first().copied())?;
+        let node = StreetNode::new(&self.routing, index);
+        Some((*node.id(), node.point()?))
+    }
+
+    /// Plan the shortest journey between two nodes by `transport`.
+    ///
+    /// The journey only uses streets open to the transport and avoids streets closed at `time`.
     pub fn plan(
         &self,
         router: &mut PathCalculator,
         origin: impl AsRef<Uuid>,
         destination: impl AsRef<Uuid>,
+        transport: Transport,
+        time: DateTime<Utc>,
     ) -> Option<Journey> {
         let origin_id = self.routing.node_map.get_index_of(origin.as_ref())?;
         let destination_id = self.routing.node_map.get_index_of(destination.as_ref())?;
-        let path = router.calc_path(&self.graph, origin_id, destination_id)?;
-        Some(
-            path.get_nodes()
-                .iter()
-                .tuple_windows()
-                .flat_map(|(a, b)| {
-                    let edge = self.routing.edge_map.get(&(*a, *b)).unwrap();
-                    let edge = self.routing.edge(*edge);
-                    let legs = edge
-                        .geometry()
-                        .unwrap()
-                        .to_line_string()
-                        .points()
-                        .tuple_windows()
-                        .filter_map(|(p0, p1)| {
-                            let distance = LatLng::new(p0.y(), p0.x())
-                                .ok()?
-                                .distance_m(LatLng::new(p1.y(), p1.x()).ok()?);
-                            Some(JourneyLeg {
-                                destination: p1,
-                                distance_m: distance.round().abs() as usize,
-                            })
+        let graph = self.graph(transport, time);
+        if origin_id.max(destination_id) >= graph.get_num_nodes() {
+            return None;
+        }
+        // without some streets a graph may lose trailing nodes, which the router must match
+        let mut own_router;
+        let router = if graph.get_num_nodes() == self.num_nodes {
+            router
+        } else {
+            own_router = fast_paths::create_calculator(&graph);
+            &mut own_router
+        };
+        let path = router.calc_path(&graph, origin_id, destination_id)?;
+        let legs = path
+            .get_nodes()
+            .iter()
+            .tuple_windows()
+            .flat_map(|(a, b)| {
+                let edge = self.routing.edge_map.get(&(*a, *b)).unwrap();
+                let edge = self.routing.edge(*edge);
+                let legs = edge
+                    .geometry()
+                    .unwrap()
+                    .to_line_string()
+                    .points()
+                    .tuple_windows()
+                    .filter_map(|(p0, p1)| {
+                        let distance = LatLng::new(p0.y(), p0.x())
+                            .ok()?
+                            .distance_m(LatLng::new(p1.y(), p1.x()).ok()?);
+                        Some(JourneyLeg {
+                            destination: p1,
+                            distance_m: distance.round().abs() as usize,
                         })
-                        .collect::<Vec<_>>();
-                    legs.into_iter()
-                })
-                .collect(),
-        )
+                    })
+                    .collect::<Vec<_>>();
+                legs.into_iter()
+            })
+            .collect_vec();
+        Some(Journey {
+            transport,
+            ..Journey::from_iter(legs)
+        })
     }
 }
 
@@ -438,10 +698,19 @@ impl RoutingData {
         StreetEdge::new(self, index)
     }
 
-    fn build_router(&self) -> FastGraph {
+    /// Contracted graph of the edges for which `include` holds.
+    fn build_router(&self, include: impl Fn(usize, &StreetEdge<'_>) -> bool) -> FastGraph {
+        fast_paths::prepare(&self.input_graph(include))
+    }
+
+    fn input_graph(&self, include: impl Fn(usize, &StreetEdge<'_>) -> bool) -> InputGraph {
         let mut graph = InputGraph::new();
 
-        for edge in self.edges() {
+        for (_, edge) in self
+            .edges()
+            .enumerate()
+            .filter(|(index, edge)| include(*index, edge))
+        {
             let source_id = self
                 .node_map
                 .get_index_of(&Uuid::from_slice(edge.source()).unwrap())
@@ -454,7 +723,7 @@ impl RoutingData {
         }
 
         graph.freeze();
-        fast_paths::prepare(&graph)
+        graph
     }
 
     pub fn into_trip_planner(self) -> JourneyPlanner {
@@ -491,6 +760,10 @@ impl<'a> StreetNode<'a> {
     pub fn geometry(&self) -> Result<ArrowPoint<'_>> {
         Ok(self.data.node_positions.value(self.valid_index)?)
     }
+
+    pub(crate) fn point(&self) -> Option<Point> {
+        Some(self.geometry().ok()?.coord()?.to_coord().into())
+    }
 }
 
 pub struct StreetEdge<'a> {
@@ -529,9 +802,39 @@ impl<'a> StreetEdge<'a> {
             .value(self.valid_index)
     }
 
+    /// OSM `highway` tag of the street, e.g. `residential` or `footway`.
+    pub fn highway(&self) -> Option<&str> {
+        self.property_str(0)
+    }
+
+    /// Name of the street, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.property_str(3)
+    }
+
+    fn property_str(&self, index: usize) -> Option<&str> {
+        let values = self.data.edges.column(3).as_struct().column(index);
+        if values.is_null(self.valid_index) {
+            return None;
+        }
+        match values.data_type() {
+            DataType::Utf8View => Some(values.as_string_view().value(self.valid_index)),
+            DataType::LargeUtf8 => Some(values.as_string::<i64>().value(self.valid_index)),
+            _ => Some(values.as_string_opt::<i32>()?.value(self.valid_index)),
+        }
+    }
+
     pub fn geometry(&self) -> Result<ArrowLineString<'_>> {
         Ok(self.data.edge_positions.value(self.valid_index)?)
     }
+
+    /// Points along the street.
+    pub(crate) fn points(&self) -> impl Iterator<Item = Point> {
+        self.geometry()
+            .map(|geometry| geometry.to_line_string().points().collect_vec())
+            .unwrap_or_default()
+            .into_iter()
+    }
 }
 
 #[cfg(test)]
@@ -700,6 +1003,7 @@ mod tests {
         assert!(journey.is_done());
         assert_eq!(journey.distance_completed_m(), 500.0);
         assert_eq!(journey.distance_remaining_m(), 0.0);
+        assert_eq!(journey.remaining_duration(), std::time::Duration::ZERO);
         assert_eq!(journey.progress_percentage(), 1.0);
 
         // Test estimated time remaining
@@ -721,6 +1025,243 @@ mod tests {
         ); // 1km at 15km/h = 240s
     }
 
+    /// Network with a direct route along `Main Street` and a detour via `Side Street`.
+    ///
+    /// `highways` are the tags of both parts of `Main Street`, then of `Side Street`.
+    fn network(highways: [Option<&str>; 4]) -> (RoutingData, [Uuid; 4]) {
+        use arrow::array::{
+            ArrayRef, FixedSizeBinaryArray, Float64Array, ListArray, StringArray, StructArray,
+        };
+        use arrow::buffer::OffsetBuffer;
+
+        let ids = [0, 1, 2, 3].map(|i| Uuid::from_u128(i + 1));
+        let points = [
+            (-0.100, 51.500),
+            (-0.099, 51.500),
+            (-0.099, 51.501),
+            (-0.098, 51.500),
+        ];
+        let ids_array = |ids: &[Uuid]| -> ArrayRef {
+            Arc::new(
+                FixedSizeBinaryArray::try_from_iter(ids.iter().map(|id| id.as_bytes())).unwrap(),
+            )
+        };
+        let struct_fields = |schema: &SchemaRef, index: usize| match schema.field(index).data_type()
+        {
+            DataType::Struct(fields) => fields.clone(),
+            DataType::List(item) => match item.data_type() {
+                DataType::Struct(fields) => fields.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let coords = |fields, points: &[(f64, f64)]| {
+            StructArray::new(
+                fields,
+                vec![
+                    Arc::new(Float64Array::from_iter_values(points.iter().map(|p| p.0)))
+                        as ArrayRef,
+                    Arc::new(Float64Array::from_iter_values(points.iter().map(|p| p.1))),
+                ],
+                None,
+            )
+        };
+
+        let schema = RoutingData::nodes_schema();
+        let nodes = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["test"; 4])),
+                ids_array(&ids),
+                Arc::new(StructArray::new_null(struct_fields(&schema, 2), 4)),
+                Arc::new(coords(struct_fields(&schema, 3), &points)),
+            ],
+        )
+        .unwrap();
+
+        let streets = [
+            (0, 1, "Main Street"),
+            (1, 3, "Main Street"),
+            (0, 2, "Side Street"),
+            (2, 3, "Side Street"),
+        ];
+        let length = |a: usize, b: usize| {
+            let a = LatLng::new(points[a].1, points[a].0).unwrap();
+            a.distance_m(LatLng::new(points[b].1, points[b].0).unwrap())
+        };
+        let schema = RoutingData::edges_schema();
+        let properties = struct_fields(&schema, 3);
+        let properties = StructArray::new(
+            properties.clone(),
+            vec![
+                Arc::new(StringArray::from(highways.to_vec())),
+                Arc::new(Float64Array::from_iter_values(
+                    streets.iter().map(|(a, b, _)| length(*a, *b)),
+                )),
+                arrow::array::new_null_array(properties[2].data_type(), 4),
+                Arc::new(StringArray::from_iter_values(streets.iter().map(|s| s.2))),
+                arrow::array::new_null_array(properties[4].data_type(), 4),
+                arrow::array::new_null_array(properties[5].data_type(), 4),
+            ],
+            None,
+        );
+        let DataType::List(item) = schema.field(4).data_type() else {
+            unreachable!()
+        };
+        let geometry = ListArray::new(
+            item.clone(),
+            OffsetBuffer::from_lengths([2; 4]),
+            Arc::new(coords(
+                struct_fields(&schema, 4),
+                &streets
+                    .iter()
+                    .flat_map(|(a, b, _)| [points[*a], points[*b]])
+                    .collect_vec(),
+            )),
+            None,
+        );
+        let edges = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["test"; 4])),
+                ids_array(&streets.iter().map(|s| ids[s.0]).collect_vec()),
+                ids_array(&streets.iter().map(|s| ids[s.1]).collect_vec()),
+                Arc::new(properties),
+                Arc::new(geometry),
+            ],
+        )
+        .unwrap();
+        (RoutingData::try_new(nodes, edges).unwrap(), ids)
+    }
+
+    #[test]
+    fn test_road_closures() {
+        let start = "2025-01-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let roadworks = RoadClosure {
+            name: "roadworks".into(),
+            streets: vec!["Main Street".into()],
+            area: None,
+            start,
+            end: start + chrono::Duration::hours(2),
+        };
+        let festival = RoadClosure {
+            name: "festival".into(),
+            streets: vec![],
+            area: Some(crate::ClosureArea {
+                latitude: 51.500,
+                longitude: -0.099,
+                radius_m: 10.0,
+            }),
+            start: start + chrono::Duration::hours(4),
+            end: start + chrono::Duration::hours(5),
+        };
+        let unrelated = RoadClosure {
+            name: "elsewhere".into(),
+            streets: vec!["High Street".into()],
+            ..roadworks.clone()
+        };
+        let (routing, [origin, _, _, destination]) = network([None; 4]);
+        let planner = routing
+            .into_trip_planner()
+            .with_closures(&[roadworks, festival, unrelated]);
+        assert_eq!(planner.closures.len(), 2);
+        let mut router = planner.get_router();
+        let mut distance = |time| {
+            planner
+                .plan(&mut router, origin, destination, Transport::Bicycle, time)
+                .unwrap()
+                .distance_m()
+        };
+
+        let direct = distance(start - chrono::Duration::minutes(1));
+        let detour = distance(start);
+        assert!(detour > direct + 100, "{detour} vs {direct}");
+        assert_eq!(distance(start + chrono::Duration::hours(2)), direct);
+        assert_eq!(distance(start + chrono::Duration::minutes(270)), detour);
+        assert_eq!(distance(start + chrono::Duration::hours(5)), direct);
+    }
+
+    #[test]
+    fn test_node_near() {
+        let (routing, ids) = network([None; 4]);
+        let planner = routing.into_trip_planner();
+        let target = LatLng::new(51.5001, -0.0981).unwrap();
+        let (id, point) = planner.node_near(&target).unwrap();
+        assert!(ids.contains(&id));
+        let node = LatLng::new(point.y(), point.x()).unwrap();
+        assert!(node.distance_m(target) < 500.0);
+        assert!(
+            planner
+                .node_near(&LatLng::new(48.85, 2.35).unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_access_restrictions() {
+        let (routing, [origin, _, _, destination]) = network([
+            Some("['pedestrian', 'residential']"),
+            Some("residential"),
+            Some("motorway_link"),
+            Some("motorway"),
+        ]);
+        let planner = routing.into_trip_planner();
+        let mut router = planner.get_router();
+        let time = Utc::now();
+        let mut plan = |transport| planner.plan(&mut router, origin, destination, transport, time);
+
+        let direct = plan(Transport::Foot).unwrap();
+        assert_eq!(direct.transport, Transport::Foot);
+        let by_car = plan(Transport::Car).unwrap();
+        assert_eq!(by_car.transport, Transport::Car);
+        assert!(by_car.distance_m() > direct.distance_m() + 100);
+
+        // bicycles may pass the pedestrian zone, but not the motorway
+        assert_eq!(
+            plan(Transport::Bicycle).unwrap().distance_m(),
+            direct.distance_m()
+        );
+    }
+
+    #[test]
+    fn test_simplified_journey() {
+        // east along a street with vertices every ~7m and 1m of jitter, then north
+        let mut points = (1..=20)
+            .map(|i| (-0.1 + i as f64 * 0.0001, 51.5 + (i % 2) as f64 * 0.00001))
+            .collect_vec();
+        points.extend((1..=10).map(|i| (-0.098, 51.5 + i as f64 * 0.0001)));
+        let legs = points
+            .iter()
+            .map(|p| (Point::new(p.0, p.1), 7))
+            .collect_vec();
+        let mut journey = Journey::from_iter(legs);
+        journey.advance(std::time::Duration::from_secs(10));
+        let completed = journey.distance_completed_m();
+
+        let simplified = journey.simplified(5.0);
+        assert!(simplified.legs.len() <= 4, "{:?}", simplified.legs);
+        assert_eq!(simplified.distance_m(), journey.distance_m());
+        assert_abs_diff_eq!(simplified.distance_completed_m(), completed, epsilon = 1e-9);
+        assert_eq!(
+            simplified.legs.last().unwrap().destination,
+            journey.legs.last().unwrap().destination
+        );
+        assert!(
+            simplified
+                .legs
+                .iter()
+                .any(|leg| leg.destination == Point::new(-0.098, 51.5))
+        );
+
+        // without tolerance the jitter along the street is kept
+        assert!(journey.simplified(0.0).legs.len() > 20);
+
+        while !journey.is_done() {
+            journey.advance(std::time::Duration::from_secs(60));
+        }
+        assert!(journey.simplified(5.0).is_done());
+    }
+
     #[test_log::test]
     fn test_empty_journey() {
         let journey = Journey::default();
@@ -728,6 +1269,7 @@ mod tests {
         assert_eq!(journey.total_distance_m(), 0);
         assert_eq!(journey.distance_completed_m(), 0.0);
         assert_eq!(journey.distance_remaining_m(), 0.0);
+        assert_eq!(journey.remaining_duration(), std::time::Duration::ZERO);
         assert_eq!(journey.progress_percentage(), 1.0);
         assert!(journey.is_done());
     }
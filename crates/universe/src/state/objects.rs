@@ -1,47 +1,65 @@
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
-use arrow::array::RecordBatch;
 use arrow::array::cast::AsArray as _;
+use arrow::array::{Array as _, LargeStringArray, RecordBatch, new_null_array};
+use arrow::compute::concat_batches;
+use arrow::datatypes::DataType;
 use dashmap::DashMap;
 use dashmap::mapref::one::Ref;
 use indexmap::IndexMap;
 use itertools::Itertools as _;
 use rand::Rng as _;
-use strum::AsRefStr;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+use uuid::Uuid;
 
-use crate::Error;
 use crate::error::Result;
-use crate::idents::{BrandId, KitchenId, MenuItemId, SiteId, StationId};
-use crate::models::{MenuItem, Site, Station};
+use crate::idents::{BrandId, KitchenId, MenuItemId, SiteId, StationId, TypedId};
+use crate::models::{KitchenStation, MenuItem, Site, Station};
+use crate::{Error, EventPayload, ObjectChange};
 
-use super::EntityView;
+use super::{EntityView, ObjectGraph, PropertySchemas};
 
-use crate::builders::ObjectDataBuilder;
+use crate::builders::{OBJECTS_SCHEMA, ObjectDataBuilder};
 
 #[derive(Debug, thiserror::Error)]
 enum VendorDataError {
-    #[error("Not found")]
-    NotFound,
+    #[error("Object not found: {0}")]
+    NotFound(Uuid),
 
     #[error("Inconsistent data")]
     InconsistentData,
 
-    #[error("Column not found")]
+    #[error("Column not found: {0}")]
     ColumnNotFound(&'static str),
+
+    #[error("Object already exists: {0}")]
+    AlreadyExists(Uuid),
+
+    #[error("Expected {expected} object, found {found}")]
+    UnexpectedLabel { expected: String, found: String },
+
+    #[error("Schema mismatch: {0}")]
+    SchemaMismatch(String),
 }
 
 impl From<VendorDataError> for Error {
     fn from(err: VendorDataError) -> Self {
         match err {
-            VendorDataError::NotFound => Error::NotFound,
+            VendorDataError::NotFound(id) => Error::not_found("object", id),
             VendorDataError::InconsistentData => Error::InvalidData(err.to_string()),
             VendorDataError::ColumnNotFound(_) => Error::InvalidData(err.to_string()),
+            VendorDataError::AlreadyExists(_)
+            | VendorDataError::UnexpectedLabel { .. }
+            | VendorDataError::SchemaMismatch(_) => Error::InvalidData(err.to_string()),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, AsRefStr, EnumString, Serialize, Deserialize)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum ObjectLabel {
     Site,
     Kitchen,
@@ -57,6 +75,22 @@ pub struct ObjectData {
     menu_items: Arc<DashMap<MenuItemId, MenuItem>>,
 
     menu_item_idx: IndexMap<MenuItemId, usize>,
+
+    /// URI references of all objects keyed by their id.
+    uris: HashMap<Uuid, String>,
+
+    /// Object ids keyed by their URI reference.
+    uri_ids: HashMap<String, Uuid>,
+
+    /// Stored ids of objects keyed by the id derived from their URI reference.
+    ///
+    /// Only contains objects whose stored id differs from the derived id, e.g. menu
+    /// items in snapshots written before their ids were derived from
+    /// `brands/<brand>/menu_items/<item>`.
+    id_aliases: HashMap<Uuid, Uuid>,
+
+    /// Changes applied since the last call to [`ObjectData::take_changes`].
+    changes: Vec<EventPayload>,
 }
 
 impl ObjectData {
@@ -66,25 +100,325 @@ impl ObjectData {
 
     /// Record batch MUST be sorted by parent_id.
     pub fn try_new(objects: RecordBatch) -> Result<Self> {
+        // snapshots written before the `uri` column was introduced
+        let objects = if objects.column_by_name("uri").is_none() {
+            let mut columns = objects.columns().to_vec();
+            columns.push(new_null_array(&DataType::Utf8, objects.num_rows()));
+            RecordBatch::try_new(OBJECTS_SCHEMA.clone(), columns)?
+        } else {
+            objects
+        };
         let data = Self {
             objects,
             menu_items: Arc::new(DashMap::new()),
             menu_item_idx: Default::default(),
+            uris: Default::default(),
+            uri_ids: Default::default(),
+            id_aliases: Default::default(),
+            changes: Vec::new(),
         };
-        data.update_indices()
+        data.update_indices()?.validate_properties()
+    }
+
+    /// Validate the properties of all objects against the registered schemas.
+    fn validate_properties(self) -> Result<Self> {
+        let schemas = PropertySchemas::default_schemas();
+        let properties = self
+            .objects
+            .column_by_name("properties")
+            .ok_or(VendorDataError::ColumnNotFound("properties"))?
+            .as_string::<i64>();
+        for (idx, ((id, _, label), props)) in self.iter_ids()?.zip(properties.iter()).enumerate() {
+            let (Some(label), Some(props)) = (label, props) else {
+                continue;
+            };
+            let location = id
+                .and_then(|id| self.uris.get(&Uuid::from_slice(id).ok()?))
+                .cloned()
+                .unwrap_or_else(|| format!("objects row {idx}"));
+            let label = label.parse().map_err(Error::generic)?;
+            schemas.validate(label, &serde_json::from_str(props)?, &location, "")?;
+        }
+        Ok(self)
     }
 
     fn update_indices(mut self) -> Result<Self> {
-        let menu_item_idx = self
+        self.menu_item_idx = self.menu_item_indices()?;
+        self.update_uri_indices()?;
+        Ok(self)
+    }
+
+    fn menu_item_indices(&self) -> Result<IndexMap<MenuItemId, usize>> {
+        Ok(self
             .iter_ids()?
             .enumerate()
             .filter(|&(_, (_, _, label))| label == Some(ObjectLabel::MenuItem.as_ref()))
             .filter_map(|(idx, (id, _parent_id, _))| {
                 id.and_then(|id| Some((id.try_into().ok()?, idx)))
             })
+            .collect())
+    }
+
+    fn update_uri_indices(&mut self) -> Result<()> {
+        let uris: HashMap<_, _> = self
+            .iter_ids()?
+            .zip(self.iter_uris()?)
+            .filter_map(|((id, _, _), uri)| Some((Uuid::from_slice(id?).ok()?, uri?)))
             .collect();
-        self.menu_item_idx = menu_item_idx;
-        Ok(self)
+        self.uri_ids = uris.iter().map(|(id, uri)| (uri.clone(), *id)).collect();
+        self.id_aliases = uris
+            .iter()
+            .filter_map(|(id, uri)| {
+                let derived = Uuid::new_v5(&Uuid::NAMESPACE_URL, uri.as_bytes());
+                (derived != *id).then_some((derived, *id))
+            })
+            .collect();
+        self.uris = uris;
+        Ok(())
+    }
+
+    /// Id under which an object is stored, resolving ids derived from its URI reference.
+    fn stored_id(&self, id: &Uuid) -> Uuid {
+        self.id_aliases.get(id).copied().unwrap_or(*id)
+    }
+
+    fn stored_menu_item_id(&self, item_id: &MenuItemId) -> MenuItemId {
+        if self.menu_item_idx.contains_key(item_id) {
+            return *item_id;
+        }
+        self.stored_id(item_id.as_ref()).into()
+    }
+
+    /// URI references for all objects.
+    ///
+    /// Falls back to joining the name segments for data written before the `uri`
+    /// column was introduced. Menu items of such data used `brands/<brand>/items/<item>`
+    /// as their name, which is mapped to the current URI scheme.
+    fn iter_uris(&self) -> Result<impl Iterator<Item = Option<String>> + '_> {
+        let uris = self
+            .objects
+            .column_by_name("uri")
+            .map(|col| col.as_string::<i32>());
+        let names = self
+            .objects
+            .column_by_name("name")
+            .ok_or(VendorDataError::ColumnNotFound("name"))?
+            .as_list::<i32>();
+        Ok((0..self.objects.num_rows()).map(move |idx| {
+            if let Some(uri) = uris.filter(|uris| uris.is_valid(idx)) {
+                return Some(uri.value(idx).to_string());
+            }
+            names.is_valid(idx).then(|| {
+                let names = names.value(idx);
+                let segments = names.as_string::<i32>().iter().flatten().collect_vec();
+                match segments.as_slice() {
+                    ["brands", brand, "items", item] => MenuItemId::uri_ref(brand, item),
+                    _ => segments.join("/"),
+                }
+            })
+        }))
+    }
+
+    /// Get the URI reference for an object.
+    pub fn uri_ref<T: TypedId>(&self, id: &T) -> Option<&str> {
+        self.object_uri(AsRef::<Uuid>::as_ref(id))
+    }
+
+    pub(super) fn object_uri(&self, id: &Uuid) -> Option<&str> {
+        self.uris.get(id).map(|uri| uri.as_str())
+    }
+
+    /// Object hierarchy as graph, e.g. to render it via GraphViz or D2.
+    pub fn graph(&self) -> Result<ObjectGraph> {
+        ObjectGraph::try_new(self)
+    }
+
+    /// Resolve the id of an object from its URI reference.
+    pub fn resolve_uri<T: TypedId>(&self, uri_ref: &str) -> Option<T> {
+        self.uri_ids.get(uri_ref).map(|id| T::from(*id))
+    }
+
+    /// Append new objects to the data.
+    ///
+    /// The batch is expected to be created via an [`ObjectDataBuilder`]; objects
+    /// that already exist or appear more than once in the batch are rejected.
+    /// A [`ObjectChange::Created`] event is recorded for every appended object.
+    pub fn append(&mut self, batch: RecordBatch) -> Result<()> {
+        let expected = OBJECTS_SCHEMA.fields();
+        let fields = batch.schema_ref().fields().clone();
+        if fields.len() != expected.len()
+            || fields
+                .iter()
+                .zip(expected.iter())
+                .any(|(f, e)| f.name() != e.name() || f.data_type() != e.data_type())
+        {
+            return Err(VendorDataError::SchemaMismatch(format!(
+                "expected columns {:?}",
+                expected.iter().map(|f| f.name()).collect_vec()
+            ))
+            .into());
+        }
+
+        let appended = ObjectData::try_new(batch.with_schema(OBJECTS_SCHEMA.clone())?)?;
+        let mut created = Vec::with_capacity(appended.objects.num_rows());
+        for (id, _, label) in appended.iter_ids()? {
+            let (Some(id), Some(label)) = (id, label) else {
+                return Err(VendorDataError::InconsistentData.into());
+            };
+            let id = Uuid::from_slice(id)?;
+            if self.row_index(&id)?.is_some() || created.iter().any(|(other, _)| other == &id) {
+                return Err(VendorDataError::AlreadyExists(id).into());
+            }
+            created.push((id, label.parse().map_err(Error::generic)?));
+        }
+
+        self.objects = concat_batches(&OBJECTS_SCHEMA, [&self.objects, &appended.objects])?;
+        self.update_uri_indices()?;
+        self.menu_item_idx = self.menu_item_indices()?;
+
+        for (id, label) in created {
+            self.record_change(id, label, ObjectChange::Created);
+        }
+        Ok(())
+    }
+
+    /// Add a new menu item to an existing brand.
+    pub fn add_menu_item(&mut self, brand_id: &BrandId, item: &MenuItem) -> Result<MenuItemId> {
+        self.expect_label(brand_id, ObjectLabel::Brand)?;
+        let brand_name = self
+            .uri_ref(brand_id)
+            .and_then(|uri| uri.strip_prefix("brands/"))
+            .ok_or(VendorDataError::InconsistentData)?
+            .to_string();
+
+        let mut item = item.clone();
+        let item_id = MenuItemId::from_names(&brand_name, &item.name);
+        item.id = item_id.to_string();
+
+        let mut builder = ObjectDataBuilder::new();
+        builder.append_menu_item(brand_id, &brand_name, &item);
+        self.append(builder.finish()?)?;
+        Ok(item_id)
+    }
+
+    /// Replace the properties of a site.
+    pub fn update_site(&mut self, site_id: &SiteId, site: &Site) -> Result<()> {
+        self.expect_label(site_id, ObjectLabel::Site)?;
+        self.update_properties(site_id, site)
+    }
+
+    /// Replace the properties of a station.
+    pub fn update_station(&mut self, station_id: &StationId, station: &Station) -> Result<()> {
+        self.expect_label(station_id, ObjectLabel::Station)?;
+        self.update_properties(station_id, station)
+    }
+
+    /// Replace the properties of a menu item, e.g. to change its price.
+    ///
+    /// The id and uri of a menu item are derived from its name, so renaming an
+    /// item is rejected; add it as a new item via [`Self::add_menu_item`] instead.
+    pub fn update_menu_item(&mut self, item_id: &MenuItemId, item: &MenuItem) -> Result<()> {
+        self.expect_label(item_id, ObjectLabel::MenuItem)?;
+        let current_name = self.menu_item(item_id)?.name.clone();
+        if current_name != item.name {
+            return Err(Error::invalid_data(format!(
+                "cannot rename menu item '{}' to '{}'; add it as a new menu item instead",
+                current_name, item.name
+            )));
+        }
+        self.update_properties(item_id, item)?;
+        self.menu_items.remove(&self.stored_menu_item_id(item_id));
+        Ok(())
+    }
+
+    /// Replace the properties of an object.
+    ///
+    /// Records a [`ObjectChange::Updated`] event for the object.
+    pub fn update_properties<T: TypedId>(
+        &mut self,
+        id: &T,
+        properties: &impl Serialize,
+    ) -> Result<()> {
+        let id = self.stored_id(AsRef::<Uuid>::as_ref(id));
+        let idx = self.row_index(&id)?.ok_or(VendorDataError::NotFound(id))?;
+        let label = self.label(idx)?;
+        let value = serde_json::to_value(properties)?;
+        let location = self
+            .uris
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string());
+        PropertySchemas::default_schemas().validate(label, &value, &location, "")?;
+        let value = value.to_string();
+
+        let current = self
+            .objects
+            .column_by_name("properties")
+            .ok_or(VendorDataError::ColumnNotFound("properties"))?
+            .as_string::<i64>();
+        let updated: LargeStringArray = current
+            .iter()
+            .enumerate()
+            .map(|(row, props)| {
+                if row == idx {
+                    Some(value.as_str())
+                } else {
+                    props
+                }
+            })
+            .collect();
+
+        let mut columns = self.objects.columns().to_vec();
+        let (col_idx, _) = OBJECTS_SCHEMA
+            .column_with_name("properties")
+            .ok_or(VendorDataError::ColumnNotFound("properties"))?;
+        columns[col_idx] = Arc::new(updated);
+        self.objects = RecordBatch::try_new(self.objects.schema(), columns)?;
+
+        self.record_change(id, label, ObjectChange::Updated);
+        Ok(())
+    }
+
+    /// Take all changes applied since the last call as events.
+    pub(crate) fn take_changes(&mut self) -> Vec<EventPayload> {
+        std::mem::take(&mut self.changes)
+    }
+
+    fn record_change(&mut self, id: Uuid, label: ObjectLabel, change: ObjectChange) {
+        let uri_ref = self.uris.get(&id).cloned();
+        self.changes
+            .push(EventPayload::object_changed(id, label, uri_ref, change));
+    }
+
+    fn row_index(&self, id: &Uuid) -> Result<Option<usize>> {
+        let id = &self.stored_id(id);
+        Ok(self
+            .iter_ids()?
+            .position(|(row_id, _, _)| row_id == Some(id.as_bytes().as_slice())))
+    }
+
+    fn label(&self, idx: usize) -> Result<ObjectLabel> {
+        let label = self
+            .iter_ids()?
+            .nth(idx)
+            .and_then(|(_, _, label)| label)
+            .ok_or(VendorDataError::InconsistentData)?;
+        label.parse().map_err(Error::generic)
+    }
+
+    fn expect_label<T: TypedId>(&self, id: &T, expected: ObjectLabel) -> Result<()> {
+        let idx = self
+            .row_index(AsRef::<Uuid>::as_ref(id))?
+            .ok_or(VendorDataError::NotFound(*AsRef::<Uuid>::as_ref(id)))?;
+        let found = self.label(idx)?;
+        if found != expected {
+            return Err(VendorDataError::UnexpectedLabel {
+                expected: expected.as_ref().to_string(),
+                found: found.as_ref().to_string(),
+            }
+            .into());
+        }
+        Ok(())
     }
 
     pub(crate) fn objects(&self) -> &RecordBatch {
@@ -122,19 +456,27 @@ impl ObjectData {
 
     /// Get the parsed properties for a menu item
     pub(crate) fn menu_item(&self, item_id: &MenuItemId) -> Result<Ref<'_, MenuItemId, MenuItem>> {
+        let item_id = &self.stored_menu_item_id(item_id);
         if let Some(item) = self.menu_items.get(item_id) {
             return Ok(item);
         }
         let view = self
             .menu_item_data(item_id)
-            .ok_or(VendorDataError::NotFound)?;
+            .ok_or_else(|| Error::not_found("menu item", item_id))?;
         let properties = view.properties()?;
         self.menu_items.insert(*item_id, properties.clone());
         Ok(self.menu_items.get(item_id).unwrap())
     }
 
+    /// Ids of all menu items.
+    pub(crate) fn menu_item_ids(&self) -> impl Iterator<Item = &MenuItemId> {
+        self.menu_item_idx.keys()
+    }
+
     pub(crate) fn menu_item_data(&self, item_id: &MenuItemId) -> Option<MenuItemView<'_>> {
-        let (id, idx) = self.menu_item_idx.get_key_value(item_id)?;
+        let (id, idx) = self
+            .menu_item_idx
+            .get_key_value(&self.stored_menu_item_id(item_id))?;
         Some(MenuItemView::new(id, self, *idx))
     }
 
@@ -177,7 +519,7 @@ impl ObjectData {
                 data: self,
                 valid_index: index,
             })
-            .ok_or(VendorDataError::NotFound.into())
+            .ok_or_else(|| Error::not_found("site", site_id))
     }
 
     pub(crate) fn kitchens(
@@ -224,6 +566,86 @@ impl ObjectData {
             },
         ))
     }
+
+    /// Find menu items that cannot be prepared by a kitchen they may be routed to.
+    ///
+    /// Order lines are distributed across all kitchens at a site accepting the item's
+    /// brand, so every such kitchen must have a station for each instruction of the item.
+    pub fn incompatible_menu_items(&self) -> Result<Vec<IncompatibleMenuItem>> {
+        let mut incompatible = Vec::new();
+        for site in self.sites()? {
+            for kitchen in self.kitchens(&site.id())? {
+                let (kitchen_id, brands) = kitchen?;
+                let stations: BTreeSet<i32> = self
+                    .kitchen_stations(&kitchen_id)?
+                    .map_ok(|(_, station)| station.station_type)
+                    .try_collect()?;
+                for (item_id, idx) in &self.menu_item_idx {
+                    let brand_id = BrandId::from(Uuid::from_slice(
+                        MenuItemView::new(item_id, self, *idx).brand_id(),
+                    )?);
+                    if !brands.contains(&brand_id) {
+                        continue;
+                    }
+                    let item = self.menu_item(item_id)?;
+                    let missing_stations: BTreeSet<_> = item
+                        .instructions
+                        .iter()
+                        .map(|instruction| instruction.required_station)
+                        .filter(|station| !stations.contains(station))
+                        .collect();
+                    if !missing_stations.is_empty() {
+                        let missing_stations = missing_stations
+                            .into_iter()
+                            .map(|station| {
+                                KitchenStation::try_from(station).map_err(|_| {
+                                    Error::invalid_data(format!(
+                                        "menu item '{}' ({item_id}) requires unknown station type {station}",
+                                        item.name
+                                    ))
+                                })
+                            })
+                            .try_collect()?;
+                        incompatible.push(IncompatibleMenuItem {
+                            site_id: site.id(),
+                            kitchen_id,
+                            menu_item_id: *item_id,
+                            name: item.name.clone(),
+                            missing_stations,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(incompatible)
+    }
+}
+
+/// A menu item requiring stations that are missing in a kitchen it may be routed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncompatibleMenuItem {
+    pub site_id: SiteId,
+    pub kitchen_id: KitchenId,
+    pub menu_item_id: MenuItemId,
+    pub name: String,
+    pub missing_stations: Vec<KitchenStation>,
+}
+
+impl std::fmt::Display for IncompatibleMenuItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "menu item '{}' ({}) needs {} missing in kitchen {} at site {}",
+            self.name,
+            self.menu_item_id,
+            self.missing_stations
+                .iter()
+                .map(|station| station.as_str_name())
+                .join(", "),
+            self.kitchen_id,
+            self.site_id,
+        )
+    }
 }
 
 pub struct MenuItemView<'a> {
@@ -292,3 +714,197 @@ impl EntityView for SiteView<'_> {
         self.valid_index
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{FixedSizeBinaryBuilder, ListBuilder, StringBuilder};
+
+    use super::*;
+    use crate::Template;
+
+    #[test]
+    fn test_uri_mapping() -> Result<()> {
+        let setup = Template::default().load()?;
+        let objects = ObjectData::try_new(setup.object_data()?)?;
+
+        let site = &setup.sites[0].info.as_ref().unwrap().name;
+        let site_id = SiteId::from_name(site);
+        assert_eq!(
+            objects.uri_ref(&site_id),
+            Some(SiteId::uri_ref(site).as_str())
+        );
+        assert_eq!(
+            objects.resolve_uri::<SiteId>(&SiteId::uri_ref(site)),
+            Some(site_id)
+        );
+
+        let brand = &setup.brands[0];
+        let item = &brand.items[0];
+        let item_id = MenuItemId::from_names(&brand.name, &item.name);
+        assert_eq!(item.id, item_id.to_string());
+        assert_eq!(
+            objects.resolve_uri::<MenuItemId>(&MenuItemId::uri_ref(&brand.name, &item.name)),
+            Some(item_id)
+        );
+        assert!(objects.menu_item_data(&item_id).is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mutations() -> Result<()> {
+        let setup = Template::default().load()?;
+        let mut objects = ObjectData::try_new(setup.object_data()?)?;
+        let num_objects = objects.objects().num_rows();
+
+        let brand = &setup.brands[0];
+        let brand_id = BrandId::from_name(&brand.name);
+        let mut item = brand.items[0].clone();
+        item.name = "new-item".into();
+        let item_id = objects.add_menu_item(&brand_id, &item)?;
+        assert_eq!(objects.objects().num_rows(), num_objects + 1);
+        assert_eq!(objects.menu_item(&item_id)?.name, "new-item");
+        assert!(objects.add_menu_item(&brand_id, &item).is_err());
+
+        item.price = 42.0;
+        objects.update_menu_item(&item_id, &item)?;
+        assert_eq!(objects.menu_item(&item_id)?.price, 42.0);
+
+        // renaming would invalidate the id derived from the name
+        let mut renamed = item.clone();
+        renamed.name = "renamed-item".into();
+        assert!(objects.update_menu_item(&item_id, &renamed).is_err());
+
+        // duplicates within a batch are rejected
+        let mut builder = ObjectDataBuilder::new();
+        let mut duplicate = item.clone();
+        duplicate.name = "duplicate-item".into();
+        builder.append_menu_item(&brand_id, &brand.name, &duplicate);
+        builder.append_menu_item(&brand_id, &brand.name, &duplicate);
+        assert!(objects.append(builder.finish()?).is_err());
+        assert_eq!(objects.objects().num_rows(), num_objects + 1);
+
+        // labels are checked before updating
+        let site_id = SiteId::from_name(&setup.sites[0].info.as_ref().unwrap().name);
+        assert!(
+            objects
+                .update_menu_item(&MenuItemId::from(*AsRef::<Uuid>::as_ref(&site_id)), &item)
+                .is_err()
+        );
+
+        let changes = objects.take_changes();
+        assert_eq!(changes.len(), 2);
+        let EventPayload::ObjectChanged(created) = &changes[0] else {
+            panic!("expected object changed event");
+        };
+        assert_eq!(created.change, ObjectChange::Created);
+        assert_eq!(created.label, ObjectLabel::MenuItem);
+        assert_eq!(
+            created.uri_ref.as_deref(),
+            Some(MenuItemId::uri_ref(&brand.name, "new-item").as_str())
+        );
+        assert!(objects.take_changes().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incompatible_menu_items() -> Result<()> {
+        let setup = Template::default().load()?;
+        let mut objects = ObjectData::try_new(setup.object_data()?)?;
+        assert!(objects.incompatible_menu_items()?.is_empty());
+
+        // replace all stoves in a kitchen with ovens
+        let site_id = SiteId::from_name(&setup.sites[0].info.as_ref().unwrap().name);
+        let (kitchen_id, _) = objects.kitchens(&site_id)?.next().unwrap()?;
+        let stations: Vec<_> = objects.kitchen_stations(&kitchen_id)?.try_collect()?;
+        for (station_id, mut station) in stations {
+            if station.station_type() == KitchenStation::Stove {
+                station.set_station_type(KitchenStation::Oven);
+                objects.update_station(&station_id, &station)?;
+            }
+        }
+
+        let incompatible = objects.incompatible_menu_items()?;
+        assert!(!incompatible.is_empty());
+        assert!(incompatible.iter().all(|item| item.kitchen_id == kitchen_id
+            && item.site_id == site_id
+            && item.missing_stations == vec![KitchenStation::Stove]));
+
+        Ok(())
+    }
+
+    /// Objects as written before menu item ids were derived from `brands/<brand>/menu_items/<item>`
+    /// and before the `uri` column was introduced.
+    fn legacy_objects(objects: &RecordBatch) -> Result<RecordBatch> {
+        let ids = objects.column_by_name("id").unwrap().as_fixed_size_binary();
+        let labels = objects.column_by_name("label").unwrap().as_string_view();
+        let names = objects.column_by_name("name").unwrap().as_list::<i32>();
+
+        let mut id_builder = FixedSizeBinaryBuilder::new(16);
+        let mut name_builder = ListBuilder::new(StringBuilder::new());
+        for idx in 0..objects.num_rows() {
+            let names = names.value(idx);
+            let mut segments = names.as_string::<i32>().iter().collect_vec();
+            if labels.value(idx) == ObjectLabel::MenuItem.as_ref() {
+                segments[2] = Some("items");
+                let legacy_uri = segments.iter().flatten().join("/");
+                id_builder.append_value(MenuItemId::from_uri_ref(legacy_uri))?;
+            } else {
+                id_builder.append_value(ids.value(idx))?;
+            }
+            name_builder.append_value(segments);
+        }
+
+        let schema = Arc::new(OBJECTS_SCHEMA.project(&[0, 1, 2, 3, 4])?);
+        Ok(RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(id_builder.finish()),
+                objects.column_by_name("parent_id").unwrap().clone(),
+                objects.column_by_name("label").unwrap().clone(),
+                Arc::new(name_builder.finish()),
+                objects.column_by_name("properties").unwrap().clone(),
+            ],
+        )?)
+    }
+
+    #[test]
+    fn test_legacy_snapshot() -> Result<()> {
+        let setup = Template::default().load()?;
+        let mut objects = ObjectData::try_new(legacy_objects(&setup.object_data()?)?)?;
+
+        let brand = &setup.brands[0];
+        let item = &brand.items[0];
+        let legacy_id =
+            MenuItemId::from_uri_ref(format!("brands/{}/items/{}", brand.name, item.name));
+        let item_id = MenuItemId::from_names(&brand.name, &item.name);
+        assert_ne!(legacy_id, item_id);
+
+        // order lines in legacy snapshots keep resolving to their menu items
+        assert_eq!(objects.menu_item(&legacy_id)?.name, item.name);
+
+        // as do ids derived from the current URI scheme
+        assert_eq!(objects.menu_item(&item_id)?.name, item.name);
+        assert_eq!(
+            objects.resolve_uri::<MenuItemId>(&MenuItemId::uri_ref(&brand.name, &item.name)),
+            Some(legacy_id)
+        );
+        assert_eq!(
+            objects.uri_ref(&legacy_id),
+            Some(MenuItemId::uri_ref(&brand.name, &item.name).as_str())
+        );
+
+        // legacy data can be updated and extended
+        let mut updated = item.clone();
+        updated.price = 42.0;
+        objects.update_menu_item(&item_id, &updated)?;
+        assert_eq!(objects.menu_item(&legacy_id)?.price, 42.0);
+
+        let mut new_item = item.clone();
+        new_item.name = "new-item".into();
+        objects.add_menu_item(&BrandId::from_name(&brand.name), &new_item)?;
+
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use arrow::array::types::Float64Type;
-use arrow::array::{RecordBatch, StringArray, cast::AsArray as _};
+use arrow::array::{Array as _, Float64Array, RecordBatch, StringArray, cast::AsArray as _};
 use arrow::compute::{concat_batches, partition};
 use h3o::LatLng;
 use indexmap::{IndexMap, IndexSet};
@@ -10,15 +10,21 @@ use itertools::Itertools as _;
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumString};
 
+use crate::PriorityTier;
 use crate::builders::{ORDER_LINE_SCHEMA, ORDER_SCHEMA};
 use crate::context::SimulationContext;
 use crate::error::{Error, Result};
-use crate::idents::{OrderId, OrderLineId, SiteId};
+use crate::idents::{OrderId, OrderLineId, PersonId, SiteId};
 
 pub static ORDER_SITE_ID_IDX: usize = 1;
 pub static ORDER_CUSTOMER_ID_IDX: usize = 2;
 pub static ORDER_DESTINATION_IDX: usize = 3;
-pub static ORDER_STATUS_IDX: usize = 4;
+pub static ORDER_TOTAL_IDX: usize = 4;
+pub static ORDER_TIP_IDX: usize = 5;
+pub static ORDER_CURRENCY_IDX: usize = 6;
+pub static ORDER_PRIORITY_IDX: usize = 7;
+pub static ORDER_CO2_IDX: usize = 8;
+pub static ORDER_STATUS_IDX: usize = 9;
 
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
@@ -46,6 +52,16 @@ pub enum OrderStatus {
     Unknown(String),
 }
 
+impl OrderStatus {
+    /// Whether the order still requires handling, i.e. it is not delivered or aborted.
+    pub fn is_open(&self) -> bool {
+        !matches!(
+            self,
+            OrderStatus::Delivered | OrderStatus::Cancelled | OrderStatus::Failed
+        )
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display, AsRefStr, Serialize, Deserialize,
 )]
@@ -64,6 +80,8 @@ pub enum OrderLineStatus {
     Delivered,
     /// Order line is waiting
     Waiting,
+    /// Order line was removed from the order, e.g. after a rejected substitution
+    Removed,
 }
 
 pub struct OrderData {
@@ -74,6 +92,51 @@ pub struct OrderData {
     /// The slice is expressed as tuple (offset, length)
     index: IndexMap<OrderId, (usize, (usize, usize))>,
     lines_index: IndexSet<OrderLineId>,
+    lookup: OrderLookup,
+}
+
+/// Secondary indices into the orders data, holding row positions of orders.
+#[derive(Debug, Clone, Default)]
+struct OrderLookup {
+    by_customer: HashMap<PersonId, Vec<usize>>,
+    open_by_site: HashMap<SiteId, IndexSet<usize>>,
+    /// Number of leading order rows covered by the indices
+    num_rows: usize,
+}
+
+impl OrderLookup {
+    /// Add all order rows not yet covered by the indices.
+    fn extend(&mut self, orders: &RecordBatch) -> Result<()> {
+        let rows = self.num_rows..orders.num_rows();
+        let site_ids = orders.column(ORDER_SITE_ID_IDX).as_fixed_size_binary();
+        let customer_ids = orders.column(ORDER_CUSTOMER_ID_IDX).as_fixed_size_binary();
+        let statuses = orders.column(ORDER_STATUS_IDX).as_string::<i32>();
+        for row in rows.clone() {
+            let customer_id = customer_ids.value(row).try_into()?;
+            self.by_customer.entry(customer_id).or_default().push(row);
+            if parse_status(statuses.value(row)).is_open() {
+                let site_id = site_ids.value(row).try_into()?;
+                self.open_by_site.entry(site_id).or_default().insert(row);
+            }
+        }
+        self.num_rows = rows.end;
+        Ok(())
+    }
+
+    /// Track the new status of the order in `row`.
+    fn set_status(&mut self, site_id: SiteId, row: usize, status: &OrderStatus) {
+        if status.is_open() {
+            self.open_by_site.entry(site_id).or_default().insert(row);
+        } else if let Some(open) = self.open_by_site.get_mut(&site_id) {
+            open.swap_remove(&row);
+        }
+    }
+}
+
+fn parse_status(status: &str) -> OrderStatus {
+    status
+        .parse()
+        .unwrap_or_else(|_| OrderStatus::Unknown(status.to_string()))
 }
 
 impl OrderData {
@@ -83,6 +146,7 @@ impl OrderData {
             lines: RecordBatch::new_empty(ORDER_LINE_SCHEMA.clone()),
             index: IndexMap::new(),
             lines_index: IndexSet::new(),
+            lookup: OrderLookup::default(),
         }
     }
 
@@ -98,6 +162,15 @@ impl OrderData {
     }
 
     pub(crate) fn try_new_from_data(orders: RecordBatch, lines: RecordBatch) -> Result<Self> {
+        Self::try_new_with_lookup(orders, lines, OrderLookup::default())
+    }
+
+    /// Create order data, extending `lookup` which already covers leading order rows.
+    fn try_new_with_lookup(
+        orders: RecordBatch,
+        lines: RecordBatch,
+        mut lookup: OrderLookup,
+    ) -> Result<Self> {
         if orders.schema().as_ref() != ORDER_SCHEMA.as_ref() {
             return Err(Error::invalid_data("expected orders to have schema"));
         }
@@ -149,11 +222,14 @@ impl OrderData {
             ));
         }
 
+        lookup.extend(&orders)?;
+
         Ok(Self {
             orders,
             lines,
             index,
             lines_index,
+            lookup,
         })
     }
 
@@ -190,19 +266,61 @@ impl OrderData {
         })
     }
 
-    pub(crate) fn orders_with_status(
-        &self,
-        site_id: &SiteId,
-        status: &OrderStatus,
-    ) -> impl Iterator<Item = OrderView<'_>> {
-        self.orders(site_id)
-            .filter(|order| order.status() == status.as_ref())
+    /// Orders of a site which are not yet delivered, cancelled or failed.
+    pub(crate) fn open_orders(&self, site_id: &SiteId) -> impl Iterator<Item = OrderView<'_>> {
+        self.rows_view(self.lookup.open_by_site.get(site_id).into_iter().flatten())
+    }
+
+    /// Whether any site but the `excluded` ones has orders which are not yet
+    /// delivered, cancelled or failed.
+    pub(crate) fn has_open_orders(&self, excluded: &HashSet<SiteId>) -> bool {
+        self.lookup
+            .open_by_site
+            .iter()
+            .any(|(site_id, open)| !open.is_empty() && !excluded.contains(site_id))
+    }
+
+    /// All orders placed by a customer, in the order they were submitted.
+    ///
+    /// Lets agents reading the state look up a customer's history without scanning all orders.
+    pub fn customer_orders(&self, customer_id: &PersonId) -> impl Iterator<Item = OrderView<'_>> {
+        self.rows_view(
+            self.lookup
+                .by_customer
+                .get(customer_id)
+                .into_iter()
+                .flatten(),
+        )
+    }
+
+    pub(crate) fn orders_with_status<'a>(
+        &'a self,
+        site_id: &'a SiteId,
+        status: &'a OrderStatus,
+    ) -> impl Iterator<Item = OrderView<'a>> {
+        let candidates: Box<dyn Iterator<Item = OrderView<'a>> + 'a> = if status.is_open() {
+            Box::new(self.open_orders(site_id))
+        } else {
+            Box::new(self.orders(site_id))
+        };
+        candidates.filter(move |order| order.status() == status.as_ref())
+    }
+
+    fn rows_view<'a>(
+        &'a self,
+        rows: impl IntoIterator<Item = &'a usize>,
+    ) -> impl Iterator<Item = OrderView<'a>> {
+        rows.into_iter().map(|row| {
+            let (id, _) = self.index.get_index(*row).unwrap();
+            OrderView::new(id, self, *row)
+        })
     }
 
+    /// Append orders, updating the lookup indices only for the new orders.
     pub(crate) fn merge(&self, other: Self) -> Result<Self> {
         let orders = concat_batches(&ORDER_SCHEMA, &[self.orders.clone(), other.orders])?;
         let lines = concat_batches(&ORDER_LINE_SCHEMA, &[self.lines.clone(), other.lines])?;
-        Self::try_new_from_data(orders, lines)
+        Self::try_new_with_lookup(orders, lines, self.lookup.clone())
     }
 
     /// Update the status of order lines.
@@ -244,19 +362,9 @@ impl OrderData {
 
         let statuses = self
             .all_orders()
-            .map(|order| order.compute_status().to_string());
-        let status_arr = Arc::new(StringArray::from(statuses.collect_vec()));
-        let mut arrays = self
-            .orders
-            .columns()
-            .iter()
-            .take(self.orders.num_columns() - 1)
-            .cloned()
+            .map(|order| order.compute_status())
             .collect_vec();
-        arrays.push(status_arr);
-        self.orders = RecordBatch::try_new(ORDER_SCHEMA.clone(), arrays)?;
-
-        Ok(())
+        self.set_order_statuses(statuses)
     }
 
     /// Update the status of orders.
@@ -265,15 +373,91 @@ impl OrderData {
         updates: impl IntoIterator<Item = (OrderId, &'a OrderStatus)>,
     ) -> Result<()> {
         let update_map: HashMap<OrderId, &OrderStatus> = updates.into_iter().collect();
-        let mut statuses = Vec::with_capacity(self.orders.num_rows());
-        for order in self.all_orders() {
-            if let Some(status) = update_map.get(order.id()) {
-                statuses.push(status.to_string());
-            } else {
-                statuses.push(order.status().to_string());
+        let statuses = self
+            .all_orders()
+            .map(|order| match update_map.get(order.id()) {
+                Some(status) => (*status).clone(),
+                None => parse_status(order.status()),
+            })
+            .collect_vec();
+        self.set_order_statuses(statuses)
+    }
+
+    /// Add amounts to the totals of orders, e.g. when items are substituted.
+    ///
+    /// Orders without a recorded total are left as they are.
+    pub(crate) fn adjust_totals(
+        &mut self,
+        updates: impl IntoIterator<Item = (OrderId, f64)>,
+    ) -> Result<()> {
+        let mut totals = self
+            .orders
+            .column(ORDER_TOTAL_IDX)
+            .as_primitive::<Float64Type>()
+            .iter()
+            .collect_vec();
+        let mut changed = false;
+        for (order_id, delta) in updates {
+            let Some((row, _)) = self.index.get(&order_id) else {
+                return Err(Error::invalid_data("order not found"));
+            };
+            if let Some(total) = totals[*row].as_mut() {
+                *total = ((*total + delta) * 100.0).round() / 100.0;
+                changed = true;
             }
         }
-        let status_arr = Arc::new(StringArray::from(statuses));
+        if !changed {
+            return Ok(());
+        }
+        let mut arrays = self.orders.columns().to_vec();
+        arrays[ORDER_TOTAL_IDX] = Arc::new(Float64Array::from(totals));
+        self.orders = RecordBatch::try_new(ORDER_SCHEMA.clone(), arrays)?;
+        Ok(())
+    }
+
+    /// Record the grams of CO2 emitted for delivering orders.
+    pub(crate) fn set_footprints(
+        &mut self,
+        updates: impl IntoIterator<Item = (OrderId, f64)>,
+    ) -> Result<()> {
+        let mut footprints = self
+            .orders
+            .column(ORDER_CO2_IDX)
+            .as_primitive::<Float64Type>()
+            .iter()
+            .collect_vec();
+        let mut changed = false;
+        for (order_id, co2_g) in updates {
+            let Some((row, _)) = self.index.get(&order_id) else {
+                return Err(Error::invalid_data("order not found"));
+            };
+            footprints[*row] = Some(co2_g);
+            changed = true;
+        }
+        if !changed {
+            return Ok(());
+        }
+        let mut arrays = self.orders.columns().to_vec();
+        arrays[ORDER_CO2_IDX] = Arc::new(Float64Array::from(footprints));
+        self.orders = RecordBatch::try_new(ORDER_SCHEMA.clone(), arrays)?;
+        Ok(())
+    }
+
+    /// Replace the status of all orders and keep the open orders index in sync.
+    fn set_order_statuses(&mut self, statuses: Vec<OrderStatus>) -> Result<()> {
+        let site_ids = self.orders.column(ORDER_SITE_ID_IDX).as_fixed_size_binary();
+        let current = self.orders.column(ORDER_STATUS_IDX).as_string::<i32>();
+        let mut values = Vec::with_capacity(statuses.len());
+        for (row, status) in statuses.iter().enumerate() {
+            let value = status.to_string();
+            if current.value(row) != value {
+                let site_id = site_ids.value(row).try_into()?;
+                self.lookup.set_status(site_id, row, status);
+            }
+            values.push(value);
+        }
+
+        let status_arr = Arc::new(StringArray::from(values));
         let mut arrays = self
             .orders
             .columns()
@@ -322,6 +506,57 @@ impl<'a> OrderView<'a> {
             .value(self.valid_index)
     }
 
+    /// Order total in the order currency, unknown for orders created before totals were recorded.
+    pub fn total(&self) -> Option<f64> {
+        self.amount(ORDER_TOTAL_IDX)
+    }
+
+    /// Currency of the total and tip, known whenever the total is.
+    pub fn currency(&self) -> Option<&str> {
+        let currencies = self
+            .data
+            .orders
+            .column(ORDER_CURRENCY_IDX)
+            .as_string::<i32>();
+        currencies
+            .is_valid(self.valid_index)
+            .then(|| currencies.value(self.valid_index))
+    }
+
+    /// Tip in the order currency the customer added to the order, if any.
+    pub fn tip(&self) -> Option<f64> {
+        self.amount(ORDER_TIP_IDX)
+    }
+
+    /// Grams of CO2 emitted for delivering the order, unknown until the delivery started.
+    pub fn co2_g(&self) -> Option<f64> {
+        self.amount(ORDER_CO2_IDX)
+    }
+
+    /// Priority tier of the customer, unknown for orders created before tiers were recorded.
+    pub fn priority(&self) -> Option<PriorityTier> {
+        let priorities = self
+            .data
+            .orders
+            .column(ORDER_PRIORITY_IDX)
+            .as_string::<i32>();
+        priorities
+            .is_valid(self.valid_index)
+            .then(|| priorities.value(self.valid_index).parse().ok())
+            .flatten()
+    }
+
+    fn amount(&self, column: usize) -> Option<f64> {
+        let amounts = self
+            .data
+            .orders
+            .column(column)
+            .as_primitive::<Float64Type>();
+        amounts
+            .is_valid(self.valid_index)
+            .then(|| amounts.value(self.valid_index))
+    }
+
     pub fn status(&self) -> &str {
         self.data
             .orders
@@ -369,9 +604,12 @@ impl<'a> OrderView<'a> {
             .any(|line| line.status() == OrderLineStatus::Processing.as_ref())
     }
 
+    /// Whether all lines still part of the order are ready.
     pub(crate) fn is_ready(&self) -> bool {
-        self.lines()
-            .all(|line| line.status() == OrderLineStatus::Ready.as_ref())
+        self.lines().all(|line| {
+            line.status() == OrderLineStatus::Ready.as_ref()
+                || line.status() == OrderLineStatus::Removed.as_ref()
+        })
     }
 
     pub(crate) fn destination(&self) -> Result<LatLng> {
@@ -434,3 +672,84 @@ fn get_id<'a>(batch: &'a RecordBatch, name: &str, idx: usize) -> &'a [u8] {
         .as_fixed_size_binary()
         .value(idx)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderDataBuilder;
+    use crate::idents::{BrandId, MenuItemId};
+
+    fn orders(site_id: SiteId, customers: &[PersonId]) -> Result<OrderData> {
+        let item = (
+            BrandId::from_name("brand"),
+            MenuItemId::from_names("brand", "item"),
+        );
+        let mut builder = OrderDataBuilder::new();
+        for customer in customers {
+            builder.add_order(site_id, *customer, LatLng::new(0.0, 0.0)?, &[item])?;
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_order_lookup() -> Result<()> {
+        let site = SiteId::from_name("site");
+        let other_site = SiteId::from_name("other");
+        let alice = PersonId::new();
+        let bob = PersonId::new();
+
+        let data = orders(site, &[alice, bob])?.merge(orders(other_site, &[alice])?)?;
+        assert_eq!(data.customer_orders(&alice).count(), 2);
+        assert_eq!(data.customer_orders(&bob).count(), 1);
+        assert_eq!(data.open_orders(&site).count(), 2);
+        assert_eq!(data.open_orders(&other_site).count(), 1);
+
+        let delivered = *data.customer_orders(&bob).next().unwrap().id();
+        let mut data = data;
+        data.update_orders([(delivered, &OrderStatus::Delivered)])?;
+        data.set_footprints([(delivered, 60.0)])?;
+        assert_eq!(data.order(&delivered).unwrap().co2_g(), Some(60.0));
+        let open = data
+            .open_orders(&site)
+            .map(|order| *order.id())
+            .collect_vec();
+        assert_eq!(open.len(), 1);
+        assert!(!open.contains(&delivered));
+        assert!(data.order(&open[0]).unwrap().co2_g().is_none());
+        assert_eq!(
+            data.orders_with_status(&site, &OrderStatus::Delivered)
+                .count(),
+            1
+        );
+
+        // order line updates recompute order statuses, delivered orders stay closed
+        let lines = data
+            .all_orders()
+            .flat_map(|order| order.lines().map(|line| *line.id()).collect_vec())
+            .collect_vec();
+        data.update_order_lines(lines.iter().map(|id| (*id, &OrderLineStatus::Processing)))?;
+        assert_eq!(data.open_orders(&site).count(), 1);
+        assert_eq!(
+            data.orders_with_status(&site, &OrderStatus::Processing)
+                .count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_line_ids() {
+        let order_id = OrderId::new();
+        let first = OrderLineId::for_order(&order_id, 0);
+        assert_eq!(first, OrderLineId::for_order(&order_id, 0));
+        assert_ne!(first, OrderLineId::for_order(&order_id, 1));
+        assert_ne!(first, OrderLineId::for_order(&OrderId::new(), 0));
+
+        // lines share the timestamp of their order
+        let order_uuid: &uuid::Uuid = order_id.as_ref();
+        let line_uuid: &uuid::Uuid = first.as_ref();
+        assert_eq!(line_uuid.get_version_num(), 7);
+        assert_eq!(line_uuid.get_timestamp(), order_uuid.get_timestamp());
+    }
+}
@@ -1,17 +1,20 @@
+use std::collections::{BTreeMap, HashMap};
 use std::convert::AsRef;
 use std::sync::Arc;
 
 use arrow::array::{DictionaryArray, FixedSizeBinaryBuilder, StringBuilder, StringViewBuilder};
-use arrow::array::{RecordBatch, cast::AsArray as _};
+use arrow::array::{RecordBatch, cast::AsArray as _, new_null_array};
 use arrow::compute::concat_batches;
 use arrow::datatypes::{Int8Type, Schema};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use datafusion::common::JoinType;
 use datafusion::functions::core::expr_ext::FieldAccessor;
-use datafusion::prelude::{DataFrame, coalesce, col, lit};
+use datafusion::logical_expr::case;
+use datafusion::prelude::{DataFrame, Expr, coalesce, col, lit};
 use geo::Point;
+use geo_traits::to_geo::ToGeoPoint as _;
 use geoarrow::array::{PointArray, PointBuilder};
-use geoarrow_array::IntoArrow;
+use geoarrow_array::{GeoArrowArrayAccessor as _, IntoArrow};
 use geoarrow_schema::{Dimension, PointType};
 use h3o::{CellIndex, Resolution};
 use indexmap::IndexMap;
@@ -24,11 +27,12 @@ use crate::context::SimulationContext;
 use crate::error::{Error, Result};
 use crate::functions as f;
 use crate::idents::{OrderId, PersonId};
-use crate::{EventPayload, OrderData, OrderStatus};
+use crate::{CourierActivity, EventPayload, OrderData, OrderStatus};
 
 use super::movement::Journey;
+use super::visits::SiteVisits;
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default, AsRefStr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, AsRefStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum PersonStatusFlag {
@@ -63,6 +67,20 @@ impl PersonStatus {
             PersonStatus::WaitingForCustomer(_, _) => PersonStatusFlag::WaitingForCustomer,
         }
     }
+
+    /// The status with its journey, if any, simplified to within `tolerance_m` meters.
+    pub(crate) fn with_simplified_journey(&self, tolerance_m: f64) -> Self {
+        match self {
+            PersonStatus::Moving(journey) => PersonStatus::Moving(journey.simplified(tolerance_m)),
+            PersonStatus::Delivering(order_id, journey) => {
+                PersonStatus::Delivering(*order_id, journey.simplified(tolerance_m))
+            }
+            PersonStatus::WaitingForCustomer(order_id, journey) => {
+                PersonStatus::WaitingForCustomer(*order_id, journey.simplified(tolerance_m))
+            }
+            status => status.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -92,6 +110,9 @@ pub struct PopulationData {
     /// efficiently lookup their [`Person`] data as it corresponds to
     /// the index value within the [`people`] array.
     lookup_index: IndexMap<PersonId, PersonState>,
+
+    /// Site check-ins of couriers, used to pair check-in and check-out events.
+    site_visits: SiteVisits,
 }
 
 impl PopulationData {
@@ -101,7 +122,7 @@ impl PopulationData {
 
     pub(crate) async fn try_new(population: DataFrame) -> Result<Self> {
         let batches = population.collect().await?;
-        let population = concat_batches(batches[0].schema_ref(), &batches)?;
+        let population = with_optional_columns(concat_batches(batches[0].schema_ref(), &batches)?)?;
 
         let positions = population
             .column_by_name("position")
@@ -134,14 +155,151 @@ impl PopulationData {
             population,
             positions,
             lookup_index,
+            site_visits: SiteVisits::default(),
         })
     }
 
     pub(crate) async fn try_new_from_ctx(ctx: &SimulationContext) -> Result<Self> {
-        let population = ctx.snapshots().population().await?.cache().await?;
+        let population = ctx.snapshots().cached("population", None).await?;
         Self::try_new(population).await
     }
 
+    /// Number of people whose current status matches any of the given flags.
+    pub(crate) fn count_with_status(&self, flags: &[PersonStatusFlag]) -> usize {
+        self.lookup_index
+            .values()
+            .filter(|state| flags.contains(&state.status.flag()))
+            .count()
+    }
+
+    /// Number of people per status.
+    pub(crate) fn status_counts(&self) -> HashMap<PersonStatusFlag, usize> {
+        let mut counts = HashMap::new();
+        for state in self.lookup_index.values() {
+            *counts.entry(state.status.flag()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Orders currently being delivered, one for each courier delivering an order.
+    pub(crate) fn orders_in_delivery(&self) -> impl Iterator<Item = &OrderId> {
+        self.lookup_index
+            .values()
+            .filter_map(|state| match &state.status {
+                PersonStatus::Delivering(order_id, _)
+                | PersonStatus::WaitingForCustomer(order_id, _) => Some(order_id),
+                _ => None,
+            })
+    }
+
+    /// Earliest time a person changes status on their own, e.g. arrives or is done eating.
+    ///
+    /// People waiting for customers to take their order are due at `now`.
+    pub(crate) fn next_transition(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.lookup_index
+            .values()
+            .filter_map(|state| match &state.status {
+                PersonStatus::Moving(journey) | PersonStatus::Delivering(_, journey) => {
+                    TimeDelta::from_std(journey.remaining_duration())
+                        .ok()
+                        .and_then(|remaining| now.checked_add_signed(remaining))
+                }
+                PersonStatus::WaitingForCustomer(_, _) => Some(now),
+                PersonStatus::Eating(until) => Some(*until),
+                PersonStatus::Idle | PersonStatus::AwaitingOrder(_) => None,
+            })
+            .min()
+    }
+
+    /// Number of people per role.
+    pub(crate) fn role_counts(&self) -> Result<BTreeMap<String, usize>> {
+        let roles = self
+            .population
+            .column_by_name("role")
+            .ok_or_else(|| Error::invalid_data("Missing 'role' column"))?
+            .as_dictionary::<Int8Type>();
+        let values = roles.values().as_string::<i32>();
+        let mut counts = BTreeMap::new();
+        for key in roles.keys().iter().flatten() {
+            *counts
+                .entry(values.value(key as usize).to_string())
+                .or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Ids of all people with the given role.
+    pub(crate) fn people_with_role(&self, role: &PersonRole) -> Result<Vec<PersonId>> {
+        let roles = self
+            .population
+            .column_by_name("role")
+            .ok_or_else(|| Error::invalid_data("Missing 'role' column"))?
+            .as_dictionary::<Int8Type>();
+        let values = roles.values().as_string::<i32>();
+        let ids = self
+            .population
+            .column_by_name("id")
+            .ok_or_else(|| Error::invalid_data("Missing 'id' column"))?
+            .as_fixed_size_binary();
+        roles
+            .keys()
+            .iter()
+            .zip(ids.iter())
+            .filter(|(key, _)| key.is_some_and(|key| values.value(key as usize) == role.as_ref()))
+            .filter_map(|(_, id)| id)
+            .map(|id| Ok(Uuid::from_slice(id)?.into()))
+            .collect()
+    }
+
+    /// Status and current position of all people with the given role.
+    pub(crate) fn people_with_role_status(
+        &self,
+        role: &PersonRole,
+    ) -> Result<Vec<(PersonId, &PersonStatus, Point)>> {
+        let roles = self
+            .population
+            .column_by_name("role")
+            .ok_or_else(|| Error::invalid_data("Missing 'role' column"))?
+            .as_dictionary::<Int8Type>();
+        let values = roles.values().as_string::<i32>();
+        let ids = self
+            .population
+            .column_by_name("id")
+            .ok_or_else(|| Error::invalid_data("Missing 'id' column"))?
+            .as_fixed_size_binary();
+        // positions are read from the batch, as its rows are reordered by updates
+        let positions: PointArray = (
+            self.population
+                .column_by_name("position")
+                .ok_or_else(|| Error::invalid_data("Missing 'position' column"))?
+                .as_struct(),
+            PointType::new(Dimension::XY, Default::default()),
+        )
+            .try_into()?;
+        let mut people = Vec::new();
+        for (row, key) in roles.keys().iter().enumerate() {
+            if key.is_none_or(|key| values.value(key as usize) != role.as_ref()) {
+                continue;
+            }
+            let person_id: PersonId = Uuid::from_slice(ids.value(row))?.into();
+            let Some(state) = self.lookup_index.get(&person_id) else {
+                continue;
+            };
+            if let Some(position) = positions.get(row)? {
+                people.push((person_id, &state.status, position.to_point()));
+            }
+        }
+        Ok(people)
+    }
+
+    pub(crate) fn site_visits(&self) -> &SiteVisits {
+        &self.site_visits
+    }
+
+    pub(crate) fn site_visits_mut(&mut self) -> &mut SiteVisits {
+        &mut self.site_visits
+    }
+
     pub(crate) fn snapshot(&self) -> &RecordBatch {
         &self.population
     }
@@ -160,6 +318,39 @@ impl PopulationData {
         filter_by_cell(df, cell_index)
     }
 
+    /// Idle people within `rings` rings of neighboring cells around `center`.
+    ///
+    /// The people are returned nearest first, with the ring of their cell in a `ring` column.
+    pub(crate) async fn idle_people_near(
+        &self,
+        ctx: &SimulationContext,
+        center: CellIndex,
+        rings: u32,
+        role: &PersonRole,
+    ) -> Result<DataFrame> {
+        let cell = f::h3_longlatash3().call(vec![
+            col("position").field("x"),
+            col("position").field("y"),
+            resolution_literal(center.resolution()),
+        ]);
+        let disk: Vec<(CellIndex, u32)> = center.grid_disk_distances(rings);
+        let mut ring = case(cell);
+        for (cell, distance) in disk {
+            ring.when(lit(u64::from(cell) as i64), lit(distance));
+        }
+        Ok(ctx
+            .ctx()
+            .read_batch(self.population.clone())?
+            .filter(
+                col("status")
+                    .eq(lit(PersonStatusFlag::Idle.as_ref()))
+                    .and(col("role").eq(lit(role.as_ref()))),
+            )?
+            .with_column("ring", ring.end()?)?
+            .filter(col("ring").is_not_null())?
+            .sort(vec![col("ring").sort(true, false)])?)
+    }
+
     pub(crate) async fn update_person_status(
         &mut self,
         ctx: &SimulationContext,
@@ -167,7 +358,10 @@ impl PopulationData {
     ) -> Result<()> {
         let mut update_data = StatusUpdateBuilder::new();
         for (id, status) in updates {
-            self.lookup_index.get_mut(id).ok_or(Error::NotFound)?.status = status.clone();
+            self.lookup_index
+                .get_mut(id)
+                .ok_or_else(|| Error::not_found("person", id))?
+                .status = status.clone();
             update_data.add_update(id.as_ref(), status)?;
         }
         let df_updates = ctx.ctx().read_batch(update_data.finish()?)?.select(vec![
@@ -186,6 +380,11 @@ impl PopulationData {
                 col("properties"),
                 col("position"),
                 coalesce(vec![col("state_new"), col("state")]).alias("state"),
+                col("schedule"),
+                col("age_bracket"),
+                col("household_size"),
+                col("income_band"),
+                col("preferences"),
             ])?
             .collect()
             .await?;
@@ -210,6 +409,11 @@ impl PopulationData {
                 PersonStatus::Moving(journey) => {
                     let progress = journey.advance(time_step);
                     let next_status = journey.is_done().then_some(PersonStatus::Idle);
+                    if next_status.is_some()
+                        && let Some(check_in) = self.site_visits.arrive(person_id)
+                    {
+                        events.push(check_in);
+                    }
                     (Some(progress), next_status)
                 }
                 PersonStatus::Delivering(order_id, journey) => {
@@ -220,6 +424,14 @@ impl PopulationData {
                         journey.reset_reverse();
                         PersonStatus::WaitingForCustomer(*order_id, journey)
                     });
+                    if next_status.is_some() {
+                        events.push(EventPayload::courier_updated(
+                            *person_id,
+                            *order_id,
+                            CourierActivity::ArrivedAtCustomer,
+                            None,
+                        ));
+                    }
                     (Some(progress), next_status)
                 }
                 PersonStatus::WaitingForCustomer(order_id, journey) => {
@@ -229,12 +441,21 @@ impl PopulationData {
                             OrderStatus::Delivered,
                             None,
                         ));
+                        events.push(EventPayload::courier_updated(
+                            *person_id,
+                            *order_id,
+                            CourierActivity::Delivered,
+                            None,
+                        ));
                         events.push(EventPayload::person_updated(
                             order.customer_person_id().try_into()?,
                             PersonStatus::Eating(
                                 *current_time + chrono::Duration::seconds(30 * 60),
                             ),
                         ));
+                        // couriers head back to the site they picked up the order from
+                        self.site_visits
+                            .head_to(*person_id, order.site_id().try_into()?);
                     };
                     (None, Some(PersonStatus::Moving(journey.clone())))
                 }
@@ -264,6 +485,11 @@ impl PopulationData {
                 col("properties"),
                 coalesce(vec![col("position_new"), col("position")]).alias("position"),
                 col("state"),
+                col("schedule"),
+                col("age_bracket"),
+                col("household_size"),
+                col("income_band"),
+                col("preferences"),
             ])?
             .collect()
             .await?;
@@ -283,8 +509,47 @@ impl PopulationData {
     }
 }
 
+/// The population with all columns added since the `state` column, empty if missing.
+///
+/// Populations generated before schedules, demographics and preferences were added
+/// order by the time of day alone, with the default basket sizes and menu item weights.
+fn with_optional_columns(population: RecordBatch) -> Result<RecordBatch> {
+    let mut fields = population.schema().fields().to_vec();
+    let mut columns = population.columns().to_vec();
+    for name in [
+        "schedule",
+        "age_bracket",
+        "household_size",
+        "income_band",
+        "preferences",
+    ] {
+        if population.column_by_name(name).is_some() {
+            continue;
+        }
+        let field = POPULATION_SCHEMA.field_with_name(name)?;
+        fields.push(Arc::new(field.clone()));
+        columns.push(new_null_array(field.data_type(), population.num_rows()));
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
 fn filter_by_cell(df: DataFrame, cell: CellIndex) -> Result<DataFrame> {
-    let resolution = match cell.resolution() {
+    Ok(df.filter(
+        f::h3_longlatash3()
+            .call(vec![
+                col("position").field("x"),
+                col("position").field("y"),
+                resolution_literal(cell.resolution()),
+            ])
+            .eq(lit(u64::from(cell) as i64)),
+    )?)
+}
+
+fn resolution_literal(resolution: Resolution) -> Expr {
+    match resolution {
         Resolution::Zero => lit(0_i8),
         Resolution::One => lit(1_i8),
         Resolution::Two => lit(2_i8),
@@ -301,16 +566,7 @@ fn filter_by_cell(df: DataFrame, cell: CellIndex) -> Result<DataFrame> {
         Resolution::Thirteen => lit(13_i8),
         Resolution::Fourteen => lit(14_i8),
         Resolution::Fifteen => lit(15_i8),
-    };
-    Ok(df.filter(
-        f::h3_longlatash3()
-            .call(vec![
-                col("position").field("x"),
-                col("position").field("y"),
-                resolution,
-            ])
-            .eq(lit(u64::from(cell) as i64)),
-    )?)
+    }
 }
 
 struct PositionUpdateBuilder {
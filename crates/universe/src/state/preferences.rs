@@ -0,0 +1,205 @@
+//! Cuisine and dietary preferences of customers.
+//!
+//! Every customer gets [`FoodPreferences`] when the population is generated: the
+//! cuisines they like, and whether they eat vegetarian, vegan or halal. Menu items a
+//! customer's diet rules out are never chosen, and items of liked cuisines are chosen
+//! more often, so demand is skewed towards the brands matching the population.
+//!
+//! Whether a menu item suits a diet is derived from its ingredients, see [`MenuItemDiet`].
+
+use arrow::array::{Array as _, AsArray as _, StructArray};
+use arrow::datatypes::DataType;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Cuisine, MenuItem};
+
+/// Relative weight of menu items of a cuisine the customer likes.
+const LIKED_CUISINE_WEIGHT: f64 = 3.0;
+
+/// Ingredients of meat and fish, which vegetarians do not eat.
+const MEAT_INGREDIENTS: &[&str] = &[
+    "bacon", "beef", "chicken", "fish", "ham", "lamb", "pork", "salmon", "shrimp", "tuna",
+];
+
+/// Ingredients of animal origin other than meat, which vegans do not eat either.
+const ANIMAL_INGREDIENTS: &[&str] = &["butter", "cheese", "cream", "egg", "honey", "milk"];
+
+/// Ingredients which are not halal.
+const HARAM_INGREDIENTS: &[&str] = &["bacon", "ham", "pork", "wine"];
+
+/// Cuisines a customer likes and the diet they follow.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoodPreferences {
+    /// Cuisines the customer orders more often, all cuisines are alike if empty
+    pub cuisines: Vec<Cuisine>,
+    pub vegetarian: bool,
+    pub vegan: bool,
+    pub halal: bool,
+}
+
+impl FoodPreferences {
+    /// Largest factor [`item_factor`](Self::item_factor) applies to a menu item.
+    pub const MAX_ITEM_FACTOR: f64 = LIKED_CUISINE_WEIGHT;
+
+    /// Draw the preferences of a customer.
+    pub(crate) fn sample(rng: &mut impl Rng) -> Self {
+        let cuisines = Cuisine::ALL
+            .into_iter()
+            .filter(|_| rng.random_bool(0.35))
+            .collect();
+        let vegan = rng.random_bool(0.03);
+        Self {
+            cuisines,
+            vegetarian: vegan || rng.random_bool(0.07),
+            vegan,
+            halal: rng.random_bool(0.05),
+        }
+    }
+
+    /// Read the preferences in `row` of a `preferences` column, if the row has them.
+    pub(crate) fn from_array(array: &StructArray, row: usize) -> Option<Self> {
+        if array.is_null(row) {
+            return None;
+        }
+        let flag = |name: &str| {
+            let column = array.column_by_name(name)?.as_boolean();
+            Some(column.is_valid(row) && column.value(row))
+        };
+        let cuisines = array
+            .column_by_name("cuisines")?
+            .as_list::<i32>()
+            .value(row);
+        let cuisines = match cuisines.data_type() {
+            DataType::Utf8View => cuisines
+                .as_string_view()
+                .iter()
+                .flatten()
+                .filter_map(|cuisine| cuisine.parse().ok())
+                .collect(),
+            _ => cuisines
+                .as_string::<i32>()
+                .iter()
+                .flatten()
+                .filter_map(|cuisine| cuisine.parse().ok())
+                .collect(),
+        };
+        Some(Self {
+            cuisines,
+            vegetarian: flag("vegetarian")?,
+            vegan: flag("vegan")?,
+            halal: flag("halal")?,
+        })
+    }
+
+    /// Factor applied to the weight of a menu item of `cuisine` with `diet`.
+    ///
+    /// Items ruled out by the customer's diet have a factor of zero.
+    pub fn item_factor(&self, cuisine: Option<Cuisine>, diet: &MenuItemDiet) -> f64 {
+        if !diet.suits(self) {
+            return 0.0;
+        }
+        match cuisine {
+            Some(cuisine) if self.cuisines.contains(&cuisine) => LIKED_CUISINE_WEIGHT,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Diets a menu item is suitable for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MenuItemDiet {
+    pub vegetarian: bool,
+    pub vegan: bool,
+    pub halal: bool,
+}
+
+impl Default for MenuItemDiet {
+    /// Items of unknown ingredients suit every diet.
+    fn default() -> Self {
+        Self {
+            vegetarian: true,
+            vegan: true,
+            halal: true,
+        }
+    }
+}
+
+impl MenuItemDiet {
+    /// Diets the ingredients of `item` are suitable for.
+    pub fn of(item: &MenuItem) -> Self {
+        let contains = |ingredients: &[&str]| {
+            item.ingredients.iter().any(|ingredient| {
+                let name = ingredient.ingredient_ref.trim_start_matches("ingredients/");
+                ingredients.contains(&name)
+            })
+        };
+        let meat = contains(MEAT_INGREDIENTS);
+        Self {
+            vegetarian: !meat,
+            vegan: !meat && !contains(ANIMAL_INGREDIENTS),
+            halal: !contains(HARAM_INGREDIENTS),
+        }
+    }
+
+    /// Whether a customer with `preferences` eats the item.
+    pub fn suits(&self, preferences: &FoodPreferences) -> bool {
+        (!preferences.vegetarian || self.vegetarian)
+            && (!preferences.vegan || self.vegan)
+            && (!preferences.halal || self.halal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+
+    use crate::IngredientQuantity;
+
+    use super::*;
+
+    fn item(ingredients: &[&str]) -> MenuItem {
+        MenuItem {
+            ingredients: ingredients
+                .iter()
+                .map(|name| IngredientQuantity {
+                    ingredient_ref: format!("ingredients/{name}"),
+                    quantity: "1".to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_menu_item_diet() {
+        let burger = MenuItemDiet::of(&item(&["bun", "beef", "lettuce"]));
+        let quesadilla = MenuItemDiet::of(&item(&["tortilla", "cheese", "beans"]));
+        let rice = MenuItemDiet::of(&item(&["rice", "broccoli", "soy_sauce"]));
+        assert!(!burger.vegetarian && burger.halal);
+        assert!(quesadilla.vegetarian && !quesadilla.vegan);
+        assert!(rice.vegan);
+
+        let vegan = FoodPreferences {
+            cuisines: vec![Cuisine::Asian],
+            vegetarian: true,
+            vegan: true,
+            halal: false,
+        };
+        assert!(!burger.suits(&vegan));
+        assert!(!quesadilla.suits(&vegan));
+        assert_eq!(vegan.item_factor(Some(Cuisine::FastFood), &burger), 0.0);
+        assert_eq!(vegan.item_factor(Some(Cuisine::Mexican), &rice), 1.0);
+        assert_eq!(
+            vegan.item_factor(Some(Cuisine::Asian), &rice),
+            LIKED_CUISINE_WEIGHT
+        );
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let preferences = FoodPreferences::sample(&mut rng);
+            assert!(!preferences.vegan || preferences.vegetarian);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+//! JSON Schemas for the `properties` of simulation objects.
+//!
+//! Object properties are stored as free-form JSON strings and parsed on demand.
+//! To surface broken setups early rather than deep inside a simulation run, the
+//! properties for every [`ObjectLabel`] are validated against a registered schema
+//! when setup files are loaded and when [`ObjectData`](super::ObjectData) is built.
+//!
+//! Properties are validated in their canonical form, i.e. after a round trip
+//! through the corresponding model type. This way camelCase field names and
+//! integer enum values are accepted, and fields holding their default value,
+//! which are omitted when serializing the models, are not reported as missing.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+use jsonschema::Validator;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::Error;
+use crate::error::Result;
+use crate::models::{Brand, Kitchen, MenuItem, Site, Station};
+
+use super::ObjectLabel;
+
+static DEFAULT_SCHEMAS: LazyLock<PropertySchemas> = LazyLock::new(|| {
+    let mut schemas = PropertySchemas::empty();
+    let defaults = [
+        (
+            ObjectLabel::Site,
+            include_str!("../../schemas/properties/site.json"),
+        ),
+        (
+            ObjectLabel::Kitchen,
+            include_str!("../../schemas/properties/kitchen.json"),
+        ),
+        (
+            ObjectLabel::Station,
+            include_str!("../../schemas/properties/station.json"),
+        ),
+        (
+            ObjectLabel::Brand,
+            include_str!("../../schemas/properties/brand.json"),
+        ),
+        (
+            ObjectLabel::MenuItem,
+            include_str!("../../schemas/properties/menu_item.json"),
+        ),
+    ];
+    for (label, schema) in defaults {
+        let schema = serde_json::from_str(schema).expect("default schemas are valid JSON");
+        schemas
+            .register(label, &schema)
+            .expect("default schemas are valid JSON Schemas");
+    }
+    schemas
+});
+
+/// A single violation of a property schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyViolation {
+    /// JSON pointer to the offending value, relative to the validated document.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for PropertyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {}", self.message)
+    }
+}
+
+/// Registry of JSON Schemas for object properties keyed by object label.
+pub struct PropertySchemas {
+    validators: HashMap<ObjectLabel, Validator>,
+}
+
+impl PropertySchemas {
+    /// Schemas shipped with the crate for all object labels.
+    pub fn default_schemas() -> &'static Self {
+        &DEFAULT_SCHEMAS
+    }
+
+    /// A registry without any schemas, all properties are considered valid.
+    pub fn empty() -> Self {
+        Self {
+            validators: HashMap::new(),
+        }
+    }
+
+    /// Register (or replace) the schema for an object label.
+    pub fn register(&mut self, label: ObjectLabel, schema: &Value) -> Result<()> {
+        let validator = jsonschema::validator_for(schema).map_err(|e| {
+            Error::invalid_data(format!(
+                "invalid property schema for '{}': {e}",
+                label.as_ref()
+            ))
+        })?;
+        self.validators.insert(label, validator);
+        Ok(())
+    }
+
+    /// Collect all violations of the schema registered for `label`.
+    pub fn violations(&self, label: ObjectLabel, properties: &Value) -> Vec<PropertyViolation> {
+        let Some(validator) = self.validators.get(&label) else {
+            return vec![];
+        };
+        validator
+            .iter_errors(properties)
+            .map(|err| PropertyViolation {
+                path: err.instance_path.to_string(),
+                message: err.to_string(),
+            })
+            .collect()
+    }
+
+    /// Validate properties for `label`.
+    ///
+    /// `location` identifies the validated document in the error message, e.g. the
+    /// path of a setup file or the URI of an object, and `pointer` the position of
+    /// the properties within that document.
+    pub fn validate(
+        &self,
+        label: ObjectLabel,
+        properties: &Value,
+        location: &str,
+        pointer: &str,
+    ) -> Result<()> {
+        let violations = match canonical(label, properties) {
+            Ok(canonical) => self.violations(label, &canonical),
+            Err(err) => {
+                // report schema violations of the raw document if possible, as
+                // they point to the offending value
+                let violations = self.violations(label, properties);
+                if violations.is_empty() {
+                    return Err(Error::invalid_data(format!(
+                        "invalid {} properties in {location}{pointer}: {err}",
+                        label.as_ref()
+                    )));
+                }
+                violations
+            }
+        };
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let details = violations
+            .iter()
+            .map(|v| format!("  - {pointer}{v}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(Error::invalid_data(format!(
+            "invalid {} properties in {location}:\n{details}",
+            label.as_ref()
+        )))
+    }
+
+    /// Validate the JSON document of a site setup file.
+    pub fn validate_site_setup(&self, setup: &Value, location: &str) -> Result<()> {
+        let info = setup.get("info").unwrap_or(&Value::Null);
+        self.validate(ObjectLabel::Site, info, location, "/info")?;
+        for (k_idx, kitchen) in array_items(setup, "kitchens") {
+            let pointer = format!("/kitchens/{k_idx}");
+            let info = kitchen.get("info").unwrap_or(&Value::Null);
+            self.validate(
+                ObjectLabel::Kitchen,
+                info,
+                location,
+                &format!("{pointer}/info"),
+            )?;
+            for (s_idx, station) in array_items(kitchen, "stations") {
+                let pointer = format!("{pointer}/stations/{s_idx}");
+                self.validate(ObjectLabel::Station, station, location, &pointer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the JSON document of a brand setup file.
+    pub fn validate_brand(&self, brand: &Value, location: &str) -> Result<()> {
+        self.validate(ObjectLabel::Brand, brand, location, "")?;
+        for (idx, item) in array_items(brand, "items") {
+            let pointer = format!("/items/{idx}");
+            self.validate(ObjectLabel::MenuItem, item, location, &pointer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Round trip properties through the model type of `label`.
+fn canonical(label: ObjectLabel, properties: &Value) -> serde_json::Result<Value> {
+    fn round_trip<T: Serialize + DeserializeOwned>(value: &Value) -> serde_json::Result<Value> {
+        serde_json::to_value(T::deserialize(value)?)
+    }
+    match label {
+        ObjectLabel::Site => round_trip::<Site>(properties),
+        ObjectLabel::Kitchen => round_trip::<Kitchen>(properties),
+        ObjectLabel::Station => round_trip::<Station>(properties),
+        ObjectLabel::Brand => round_trip::<Brand>(properties),
+        ObjectLabel::MenuItem => round_trip::<MenuItem>(properties),
+    }
+}
+
+fn array_items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = (usize, &'a Value)> {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .enumerate()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_site_setup_violations() {
+        let schemas = PropertySchemas::default_schemas();
+        let setup = json!({
+            "info": { "name": "test", "latitude": 52.3, "longitude": 4.8 },
+            "kitchens": [{
+                "info": { "name": "kitchen-1" },
+                "stations": [
+                    { "name": "oven-1", "station_type": "KITCHEN_STATION_OVEN" },
+                    { "name": "grill-1", "station_type": "KITCHEN_STATION_GRILL" }
+                ]
+            }]
+        });
+
+        let err = schemas
+            .validate_site_setup(&setup, "sites/test.json")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("sites/test.json"), "{err}");
+        assert!(err.contains("/kitchens/0/stations/1/station_type"), "{err}");
+    }
+
+    #[test]
+    fn test_canonical_properties() {
+        let schemas = PropertySchemas::default_schemas();
+
+        // default values are omitted when serializing the models
+        let site = json!({ "name": "equator", "latitude": 0.0, "longitude": 0.0 });
+        let site = serde_json::to_value(serde_json::from_value::<Site>(site).unwrap()).unwrap();
+        assert!(schemas.validate(ObjectLabel::Site, &site, "", "").is_ok());
+        let item = json!({
+            "name": "free-sample",
+            "price": 0.0,
+            "instructions": [{ "step": "serve", "required_station": "KITCHEN_STATION_WORKSTATION" }]
+        });
+        assert!(
+            schemas
+                .validate(ObjectLabel::MenuItem, &item, "", "")
+                .is_ok()
+        );
+
+        // camelCase field names and integer enum values
+        let setup = json!({
+            "info": { "name": "test", "latitude": 52.3, "longitude": 4.8 },
+            "kitchens": [{
+                "info": { "name": "kitchen-1" },
+                "stations": [
+                    { "name": "oven-1", "stationType": "KITCHEN_STATION_OVEN" },
+                    { "name": "stove-1", "station_type": 2 }
+                ]
+            }]
+        });
+        assert!(
+            schemas
+                .validate_site_setup(&setup, "sites/test.json")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_missing_schema_is_valid() {
+        let schemas = PropertySchemas::empty();
+        assert!(schemas.violations(ObjectLabel::Site, &json!(42)).is_empty());
+    }
+}
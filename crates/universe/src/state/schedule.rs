@@ -0,0 +1,148 @@
+//! Daily routines of customers.
+//!
+//! Every customer gets a [`DailySchedule`] when the population is generated: when they
+//! wake up, whether and when they work, when they have their meals and when they go to
+//! sleep. Customers only order while they are awake, and mostly around their own meal
+//! times, so demand over the day follows the mix of routines in the population rather
+//! than a single curve shared by everyone.
+
+use arrow::array::{Array as _, AsArray as _, StructArray};
+use arrow::datatypes::Float64Type;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Share of customers who work, and are away from home for a part of the day.
+const WORKING_SHARE: f64 = 0.65;
+
+/// Times of a customer's day, as hours since midnight UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailySchedule {
+    pub wake: f64,
+
+    /// Start of the working hours, if the customer works
+    pub work_start: Option<f64>,
+
+    /// End of the working hours, if the customer works
+    pub work_end: Option<f64>,
+
+    pub breakfast: f64,
+    pub lunch: f64,
+    pub dinner: f64,
+
+    /// Time the customer goes to sleep, past midnight for night owls
+    pub sleep: f64,
+}
+
+impl DailySchedule {
+    /// Draw the routine of a customer.
+    pub(crate) fn sample(rng: &mut impl Rng) -> Self {
+        let wake = around(rng, 7.0, 2.0);
+        let (work_start, work_end) = if rng.random_bool(WORKING_SHARE) {
+            let start = wake + rng.random_range(1.0..2.0);
+            (Some(start), Some(start + rng.random_range(7.5..9.5)))
+        } else {
+            (None, None)
+        };
+        Self {
+            wake,
+            work_start,
+            work_end,
+            breakfast: wake + rng.random_range(0.25..1.0),
+            lunch: around(rng, 12.5, 2.0),
+            dinner: around(rng, 19.0, 3.0),
+            sleep: (wake + rng.random_range(16.0..18.0)).rem_euclid(24.0),
+        }
+    }
+
+    /// Read the schedule in `row` of a `schedule` column, if the row has one.
+    pub(crate) fn from_array(array: &StructArray, row: usize) -> Option<Self> {
+        if array.is_null(row) {
+            return None;
+        }
+        let value = |name: &str| {
+            let column = array.column_by_name(name)?.as_primitive::<Float64Type>();
+            column.is_valid(row).then(|| column.value(row))
+        };
+        Some(Self {
+            wake: value("wake")?,
+            work_start: value("work_start"),
+            work_end: value("work_end"),
+            breakfast: value("breakfast")?,
+            lunch: value("lunch")?,
+            dinner: value("dinner")?,
+            sleep: value("sleep")?,
+        })
+    }
+
+    /// Whether the customer is awake at `hour` of the day.
+    pub fn is_awake(&self, hour: f64) -> bool {
+        within(hour, self.wake, self.sleep)
+    }
+
+    /// Whether the customer is at work at `hour` of the day.
+    pub fn is_working(&self, hour: f64) -> bool {
+        match (self.work_start, self.work_end) {
+            (Some(start), Some(end)) => within(hour, start, end),
+            _ => false,
+        }
+    }
+}
+
+/// Hours from `from` to `to`, the shorter way around the clock.
+pub(crate) fn hours_between(from: f64, to: f64) -> f64 {
+    (to - from + 12.0).rem_euclid(24.0) - 12.0
+}
+
+/// Whether `hour` lies between `start` and `end`, which may wrap around midnight.
+fn within(hour: f64, start: f64, end: f64) -> bool {
+    let hour = hour.rem_euclid(24.0);
+    let (start, end) = (start.rem_euclid(24.0), end.rem_euclid(24.0));
+    if start <= end {
+        start <= hour && hour < end
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+/// A value around `center`, at most `spread` away and more likely close to it.
+fn around(rng: &mut impl Rng, center: f64, spread: f64) -> f64 {
+    let half = spread / 2.0;
+    center + rng.random_range(-half..half) + rng.random_range(-half..half)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn test_daily_schedule() {
+        let schedule = DailySchedule {
+            wake: 7.0,
+            work_start: Some(8.5),
+            work_end: Some(17.0),
+            breakfast: 7.5,
+            lunch: 12.5,
+            dinner: 19.0,
+            sleep: 0.5,
+        };
+        assert!(schedule.is_awake(23.75));
+        assert!(schedule.is_awake(0.25));
+        assert!(!schedule.is_awake(3.0));
+        assert!(schedule.is_working(12.0));
+        assert!(!schedule.is_working(18.0));
+        assert_eq!(hours_between(23.0, 1.0), 2.0);
+        assert_eq!(hours_between(1.0, 23.0), -2.0);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let schedule = DailySchedule::sample(&mut rng);
+            assert!(schedule.is_awake(schedule.breakfast));
+            assert!(schedule.is_awake(schedule.lunch));
+            assert!(schedule.is_awake(schedule.dinner));
+            assert!(!schedule.is_awake(schedule.wake - 0.5));
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Size and cardinality diagnostics for the simulation state.
+//!
+//! The report is meant to help tune population sizes and to spot data that keeps
+//! growing over the course of a run (e.g. order batches that are never compacted).
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use arrow::array::{Array as _, RecordBatch};
+use arrow::compute::concat_batches;
+use chrono::{DateTime, Utc};
+use datafusion::common::Column;
+use datafusion::functions_aggregate::expr_fn::count;
+use datafusion::prelude::{DataFrame, Expr, col, lit};
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// Null statistics for a single column.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ColumnStats {
+    pub name: String,
+    pub null_count: usize,
+}
+
+/// Size and cardinality of a single state structure or registered table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchStats {
+    pub name: String,
+    pub num_rows: usize,
+    /// Memory held by the data in bytes.
+    ///
+    /// Only available for data held in memory by the simulation.
+    pub memory_bytes: Option<usize>,
+    pub columns: Vec<ColumnStats>,
+}
+
+impl BatchStats {
+    pub(crate) fn from_batch(name: impl Into<String>, batch: &RecordBatch) -> Self {
+        let columns = batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .map(|(field, array)| ColumnStats {
+                name: field.name().clone(),
+                null_count: array.null_count(),
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            num_rows: batch.num_rows(),
+            memory_bytes: Some(batch.get_array_memory_size()),
+            columns,
+        }
+    }
+
+    /// Compute row and null counts for a data frame without materializing it.
+    pub(crate) async fn from_frame(name: impl Into<String>, df: DataFrame) -> Result<Self> {
+        let names = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        let mut aggregates: Vec<Expr> = vec![count(lit(1)).alias("__num_rows")];
+        aggregates.extend(names.iter().enumerate().map(|(idx, name)| {
+            count(col(Column::new_unqualified(name))).alias(format!("__non_null_{idx}"))
+        }));
+
+        let batches = df.aggregate(vec![], aggregates)?.collect().await?;
+        let batch = concat_batches(batches[0].schema_ref(), &batches)?;
+        let value = |idx: usize| {
+            batch
+                .column(idx)
+                .as_any()
+                .downcast_ref::<arrow::array::Int64Array>()
+                .map(|arr| arr.value(0) as usize)
+                .unwrap_or_default()
+        };
+
+        let num_rows = value(0);
+        let columns = names
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| ColumnStats {
+                name,
+                null_count: num_rows - value(idx + 1),
+            })
+            .collect();
+
+        Ok(Self {
+            name: name.into(),
+            num_rows,
+            memory_bytes: None,
+            columns,
+        })
+    }
+
+    /// Fraction of null values for a column, `None` if the column does not exist.
+    pub fn null_ratio(&self, column: &str) -> Option<f64> {
+        self.columns
+            .iter()
+            .find(|c| c.name == column)
+            .map(|c| self.ratio(c.null_count))
+    }
+
+    fn ratio(&self, null_count: usize) -> f64 {
+        if self.num_rows == 0 {
+            0.0
+        } else {
+            null_count as f64 / self.num_rows as f64
+        }
+    }
+}
+
+/// Report on size and cardinality of the simulation state and registered tables.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StateStats {
+    /// In-memory state structures (objects, population, orders, order lines)
+    pub structures: Vec<BatchStats>,
+
+    /// Tables registered in the simulation context
+    pub tables: Vec<BatchStats>,
+}
+
+impl StateStats {
+    /// Total bytes held in memory by the state structures.
+    pub fn memory_bytes(&self) -> usize {
+        self.structures.iter().filter_map(|s| s.memory_bytes).sum()
+    }
+}
+
+impl fmt::Display for StateStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [("state", &self.structures), ("tables", &self.tables)];
+        for (title, entries) in sections {
+            if entries.is_empty() {
+                continue;
+            }
+            writeln!(f, "{title}:")?;
+            writeln!(f, "  {:<40} {:>12} {:>14}", "name", "rows", "memory bytes")?;
+            for entry in entries {
+                let memory = entry
+                    .memory_bytes
+                    .map(|b| b.to_string())
+                    .unwrap_or_else(|| "-".into());
+                writeln!(
+                    f,
+                    "  {:<40} {:>12} {:>14}",
+                    entry.name, entry.num_rows, memory
+                )?;
+                for column in entry.columns.iter().filter(|c| c.null_count > 0) {
+                    writeln!(
+                        f,
+                        "    {:<38} {:>11.2}% null",
+                        column.name,
+                        entry.ratio(column.null_count) * 100.0
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Counts of orders and people at a point in simulation time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationStats {
+    pub simulation_time: DateTime<Utc>,
+    pub orders_by_status: BTreeMap<String, usize>,
+    pub people_by_status: BTreeMap<String, usize>,
+    pub people_by_role: BTreeMap<String, usize>,
+    /// People currently moving along a route, e.g. couriers on a delivery
+    pub active_journeys: usize,
+    /// Couriers currently delivering an order
+    pub busy_couriers: usize,
+    /// Open orders and courier load of each site, by site name
+    pub sites: BTreeMap<String, SiteLoad>,
+    /// Steps completed since the simulation was built
+    pub steps: usize,
+    /// Events emitted since the simulation was built, by event kind
+    pub events_by_kind: BTreeMap<String, usize>,
+}
+
+impl SimulationStats {
+    /// Fraction of couriers currently delivering an order.
+    pub fn courier_utilization(&self) -> f64 {
+        match self.people_by_role.get("courier") {
+            Some(&couriers) if couriers > 0 => self.busy_couriers as f64 / couriers as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Orders waiting at a site and couriers delivering its orders.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteLoad {
+    /// Orders submitted, but not yet processed by a kitchen
+    pub submitted: usize,
+    /// Orders being prepared in the kitchens
+    pub processing: usize,
+    /// Orders ready and waiting for a courier
+    pub ready: usize,
+    /// Orders waiting for or occupying a packing station, counted as processing
+    pub packing: usize,
+    /// Couriers currently delivering orders of the site
+    pub delivering: usize,
+}
+
+impl SiteLoad {
+    /// Orders of the site not yet picked up by a courier.
+    pub fn queued(&self) -> usize {
+        self.submitted + self.processing + self.ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use datafusion::prelude::SessionContext;
+
+    use super::*;
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![Some("x"), None, None, Some("y")])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_and_frame_stats_agree() -> Result<()> {
+        let batch = batch();
+        let from_batch = BatchStats::from_batch("test", &batch);
+        assert_eq!(from_batch.num_rows, 4);
+        assert!(from_batch.memory_bytes.unwrap() > 0);
+        assert_eq!(from_batch.null_ratio("b"), Some(0.5));
+        assert_eq!(from_batch.null_ratio("missing"), None);
+
+        let df = SessionContext::new().read_batch(batch)?;
+        let from_frame = BatchStats::from_frame("test", df).await?;
+        assert_eq!(from_frame.num_rows, 4);
+        assert_eq!(from_frame.memory_bytes, None);
+        assert_eq!(from_frame.columns, from_batch.columns);
+
+        Ok(())
+    }
+}
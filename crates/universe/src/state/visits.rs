@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::EventPayload;
+use crate::idents::{OrderId, PersonId, SiteId};
+
+/// Pairs site check-in and check-out events of couriers.
+///
+/// Visits are not part of population snapshots. After resuming from a snapshot,
+/// couriers are not checked in at any site until they next return to one, and
+/// check-outs of couriers that are not checked in are suppressed rather than
+/// emitted without a matching check-in.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SiteVisits {
+    /// Sites people are currently travelling to.
+    heading_to: HashMap<PersonId, SiteId>,
+
+    /// Sites people are currently checked in at.
+    checked_in: HashMap<PersonId, SiteId>,
+}
+
+impl SiteVisits {
+    /// Record that a person started travelling to a site.
+    pub(crate) fn head_to(&mut self, person_id: PersonId, site_id: SiteId) {
+        self.heading_to.insert(person_id, site_id);
+    }
+
+    /// Complete the journey of a person, checking them in if they were heading to a site.
+    pub(crate) fn arrive(&mut self, person_id: &PersonId) -> Option<EventPayload> {
+        let site_id = self.heading_to.remove(person_id)?;
+        self.checked_in.insert(*person_id, site_id);
+        Some(EventPayload::site_check_in(site_id, *person_id))
+    }
+
+    /// Check-out event for a person leaving a site, if they are checked in there.
+    pub(crate) fn check_out(
+        &self,
+        site_id: &SiteId,
+        person_id: &PersonId,
+        order_ids: Vec<OrderId>,
+    ) -> Option<EventPayload> {
+        (self.checked_in.get(person_id) == Some(site_id))
+            .then(|| EventPayload::site_check_out(*site_id, *person_id, order_ids))
+    }
+
+    /// Apply a check-out event emitted by a site.
+    pub(crate) fn checked_out(&mut self, person_id: &PersonId) {
+        self.checked_in.remove(person_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_in_check_out_sequence() {
+        let mut visits = SiteVisits::default();
+        let site_id = SiteId::from_name("site");
+        let courier = PersonId::new();
+        let order_id = OrderId::new();
+
+        // couriers that never arrived at the site do not check out
+        assert!(
+            visits
+                .check_out(&site_id, &courier, vec![order_id])
+                .is_none()
+        );
+        assert!(visits.arrive(&courier).is_none());
+
+        visits.head_to(courier, site_id);
+        let Some(EventPayload::SiteCheckIn(check_in)) = visits.arrive(&courier) else {
+            panic!("expected check-in event");
+        };
+        assert_eq!(check_in.site_id, site_id);
+        assert_eq!(check_in.person_id, courier);
+
+        // check-outs are only paired with a check-in at the same site
+        let other_site = SiteId::from_name("other");
+        assert!(visits.check_out(&other_site, &courier, vec![]).is_none());
+        let Some(EventPayload::SiteCheckOut(check_out)) =
+            visits.check_out(&site_id, &courier, vec![order_id])
+        else {
+            panic!("expected check-out event");
+        };
+        assert_eq!(check_out.order_ids, vec![order_id]);
+
+        visits.checked_out(&courier);
+        assert!(visits.check_out(&site_id, &courier, vec![]).is_none());
+    }
+}
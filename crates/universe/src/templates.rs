@@ -1,20 +1,45 @@
 use crate::{
     Brand, BrandId, EntityView, Error, KitchenId, MenuItemId, ObjectData, PopulationData,
-    SimulationContext, SimulationSetup, SiteId, SiteSetup, StationId,
+    PropertySchemas, RedactionPolicy, SimulationContext, SimulationSetup, SiteId, SiteSetup,
+    StationId,
 };
 use itertools::Itertools as _;
-use rand::Rng as _;
+use rand::rngs::StdRng;
+use rand::{Rng as _, SeedableRng as _};
 
 use crate::error::Result;
 
-pub async fn initialize_template(caspers_directory: &url::Url, template: Template) -> Result<()> {
+pub use self::menus::*;
+
+mod menus;
+
+/// Initialize a working directory with the objects and population of `template`.
+///
+/// With a `seed`, the population is generated deterministically, so directories
+/// initialized from the same template and seed have the same people. Columns listed
+/// in `redaction` are redacted before the initial snapshot is stored.
+pub async fn initialize_template(
+    caspers_directory: &url::Url,
+    template: Template,
+    seed: Option<u64>,
+    redaction: RedactionPolicy,
+) -> Result<()> {
     let setup = template.load()?;
     let objects = setup.object_data()?;
     let object_data = ObjectData::try_new(objects)?;
 
-    let mut builder = PopulationData::builder();
+    let (mut builder, mut rng) = match seed {
+        Some(seed) => (
+            PopulationData::builder().with_seed(seed),
+            StdRng::seed_from_u64(seed),
+        ),
+        None => (
+            PopulationData::builder(),
+            StdRng::from_rng(&mut rand::rng()),
+        ),
+    };
     for site in object_data.sites()? {
-        let n_people = rand::rng().random_range(500..1500);
+        let n_people = rng.random_range(500..1500);
         let info = site.properties()?;
         builder.add_site(n_people, info.latitude, info.longitude)?;
     }
@@ -24,6 +49,7 @@ pub async fn initialize_template(caspers_directory: &url::Url, template: Templat
         .with_working_directory(caspers_directory.clone())
         .with_object_data(object_data)
         .with_population_data(population_data)
+        .with_redaction(redaction)
         .build()
         .await?;
 
@@ -33,6 +59,7 @@ pub async fn initialize_template(caspers_directory: &url::Url, template: Templat
 pub struct Template {
     sites: Vec<SiteTemplate>,
     brands: Vec<BrandTemplate>,
+    menus: Option<MenuGenerator>,
 }
 
 impl Default for Template {
@@ -44,13 +71,24 @@ impl Default for Template {
                 BrandTemplate::FastFood,
                 BrandTemplate::Mexican,
             ],
+            menus: None,
         }
     }
 }
 
 impl Template {
     pub fn new(sites: Vec<SiteTemplate>, brands: Vec<BrandTemplate>) -> Self {
-        Self { sites, brands }
+        Self {
+            sites,
+            brands,
+            menus: None,
+        }
+    }
+
+    /// Add the brands generated by `menus` to the brand templates.
+    pub fn with_menus(mut self, menus: MenuGenerator) -> Self {
+        self.menus = Some(menus);
+        self
     }
 
     pub fn load(&self) -> Result<SimulationSetup> {
@@ -118,46 +156,46 @@ impl BrandTemplate {
 
 fn load_template(template: &Template) -> Result<SimulationSetup> {
     let sites = template.sites.iter().map(load_site).try_collect()?;
-    let brands = template.brands.iter().map(load_brand).try_collect()?;
+    let mut brands: Vec<_> = template.brands.iter().map(load_brand).try_collect()?;
+    if let Some(menus) = &template.menus {
+        brands.extend(menus.generate_brands()?);
+    }
     Ok(SimulationSetup { sites, brands })
 }
 
 fn load_brand(brand: &BrandTemplate) -> Result<Brand> {
-    let mut brand: Brand = serde_json::from_slice(brand.data()).map_err(Error::from)?;
-    brand.id = BrandId::from_uri_ref(format!("brands/{}", brand.name)).to_string();
+    let value: serde_json::Value = serde_json::from_slice(brand.data())?;
+    PropertySchemas::default_schemas()
+        .validate_brand(&value, &format!("brand template '{brand}'"))?;
+    let mut brand: Brand = serde_json::from_value(value)?;
+    brand.id = BrandId::from_name(&brand.name).to_string();
 
     for menu_item in brand.items.iter_mut() {
-        menu_item.id =
-            MenuItemId::from_uri_ref(format!("brands/{}/menu_items/{}", brand.id, menu_item.name))
-                .to_string();
+        menu_item.id = MenuItemId::from_names(&brand.name, &menu_item.name).to_string();
     }
 
     Ok(brand)
 }
 
 fn load_site(site: &SiteTemplate) -> Result<SiteSetup> {
-    let mut site_setup: SiteSetup = serde_json::from_slice(site.data()).map_err(Error::from)?;
+    let value: serde_json::Value = serde_json::from_slice(site.data())?;
+    PropertySchemas::default_schemas()
+        .validate_site_setup(&value, &format!("site template '{site}'"))?;
+    let mut site_setup: SiteSetup = serde_json::from_value(value)?;
     let Some(ref mut site) = site_setup.info else {
         return Err(Error::invalid_data("missing site information"));
     };
-    site.id = SiteId::from_uri_ref(format!("sites/{}", site.name)).to_string();
+    site.id = SiteId::from_name(&site.name).to_string();
     site_setup.kitchens = site_setup
         .kitchens
         .into_iter()
         .map(|mut kitchen_setup| {
             if let Some(ref mut kitchen) = kitchen_setup.info {
-                kitchen.id = KitchenId::from_uri_ref(format!(
-                    "sites/{}/kitchens/{}",
-                    site.name, kitchen.name
-                ))
-                .to_string();
+                kitchen.id = KitchenId::from_names(&site.name, &kitchen.name).to_string();
 
                 for station in &mut kitchen_setup.stations {
-                    station.id = StationId::from_uri_ref(format!(
-                        "sites/{}/kitchens/{}/stations/{}",
-                        site.name, kitchen.name, station.name
-                    ))
-                    .to_string();
+                    station.id =
+                        StationId::from_names(&site.name, &kitchen.name, &station.name).to_string();
                 }
             }
 
@@ -0,0 +1,673 @@
+//! Generated brands and menus.
+//!
+//! The brand templates only cover a handful of menu items. To simulate kitchens with
+//! larger and more varied menus, [`MenuGenerator`] creates brands for a mix of
+//! cuisines. Menu items are sampled from per-cuisine dish templates, which list the
+//! ingredients of a dish by their reference in the ingredient catalog and the steps
+//! to prepare it on the different kitchen stations. Prices and step durations vary
+//! between brands, with durations kept within a realistic range for the station the
+//! step is performed on.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom as _;
+use rand::{Rng, SeedableRng as _};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::{
+    Brand, BrandId, Cuisine, Error, IngredientQuantity, Instruction, KitchenStation, MenuItem,
+    MenuItemId, PropertySchemas,
+};
+
+use KitchenStation::{Oven, Stove, Workstation};
+
+/// Generates brands with menus for a mix of cuisines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MenuGenerator {
+    /// Number of brands generated for each cuisine
+    pub cuisine_mix: BTreeMap<Cuisine, usize>,
+
+    /// Number of items on the menu of every brand, limited by the dishes of its cuisine
+    pub items_per_brand: usize,
+
+    /// Seed for sampling dishes, prices and durations
+    pub seed: Option<u64>,
+}
+
+impl Default for MenuGenerator {
+    fn default() -> Self {
+        Self {
+            cuisine_mix: [
+                (Cuisine::Asian, 1),
+                (Cuisine::FastFood, 1),
+                (Cuisine::Mexican, 1),
+            ]
+            .into(),
+            items_per_brand: 4,
+            seed: None,
+        }
+    }
+}
+
+impl MenuGenerator {
+    /// Generate `count` brands of `cuisine`.
+    pub fn with_cuisine(mut self, cuisine: Cuisine, count: usize) -> Self {
+        self.cuisine_mix.insert(cuisine, count);
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Generate the brands of the cuisine mix.
+    ///
+    /// Brands are named after their cuisine and numbered, e.g. `generated_asian_1`.
+    pub fn generate_brands(&self) -> Result<Vec<Brand>> {
+        if self.items_per_brand == 0 {
+            return Err(Error::invalid_data(
+                "generated brands need at least one menu item",
+            ));
+        }
+        let catalog = ingredient_catalog()?;
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+
+        let mut brands = Vec::new();
+        for (cuisine, count) in &self.cuisine_mix {
+            for idx in 1..=*count {
+                let name = format!("generated_{cuisine}_{idx}");
+                let dishes = dishes(*cuisine).choose_multiple(&mut rng, self.items_per_brand);
+                let items = dishes
+                    .map(|dish| dish.menu_item(&name, &catalog, &mut rng))
+                    .collect::<Result<Vec<_>>>()?;
+                let brand = Brand {
+                    id: BrandId::from_name(&name).to_string(),
+                    name,
+                    description: format!("Generated {} cuisine", cuisine_label(*cuisine)),
+                    category: cuisine.to_string(),
+                    items,
+                    currency: None,
+                };
+                let value = serde_json::to_value(&brand)?;
+                PropertySchemas::default_schemas()
+                    .validate_brand(&value, &format!("generated brand '{}'", brand.name))?;
+                brands.push(brand);
+            }
+        }
+        Ok(brands)
+    }
+}
+
+fn cuisine_label(cuisine: Cuisine) -> &'static str {
+    match cuisine {
+        Cuisine::Asian => "Asian",
+        Cuisine::FastFood => "fast food",
+        Cuisine::Mexican => "Mexican",
+    }
+}
+
+/// References of the ingredients in the ingredient catalog.
+fn ingredient_catalog() -> Result<HashSet<String>> {
+    #[derive(Deserialize)]
+    struct Ingredient {
+        name: String,
+    }
+    let ingredients: Vec<Ingredient> =
+        serde_json::from_slice(include_bytes!("../../templates/base/ingredients.json"))?;
+    Ok(ingredients.into_iter().map(|i| i.name).collect())
+}
+
+/// Range of realistic durations in seconds of a single step on a station.
+fn station_durations(station: KitchenStation) -> (u32, u32) {
+    match station {
+        KitchenStation::Workstation => (30, 600),
+        KitchenStation::Stove => (60, 1200),
+        KitchenStation::Oven => (300, 1800),
+        KitchenStation::Unspecified => (30, 1800),
+    }
+}
+
+struct StepTemplate {
+    step: &'static str,
+    station: KitchenStation,
+    /// Typical duration of the step in seconds
+    duration: u32,
+    description: &'static str,
+}
+
+impl StepTemplate {
+    fn instruction(&self, rng: &mut impl Rng) -> Instruction {
+        let (min, max) = station_durations(self.station);
+        let duration = (self.duration as f64 * rng.random_range(0.8..1.25)).round() as u32;
+        Instruction {
+            step: self.step.to_string(),
+            description: self.description.to_string(),
+            required_station: self.station as i32,
+            expected_duration: duration.clamp(min, max),
+        }
+    }
+}
+
+struct DishTemplate {
+    name: &'static str,
+    description: &'static str,
+    /// Typical price of the dish
+    price: f64,
+    /// Ingredient references and quantities
+    ingredients: &'static [(&'static str, &'static str)],
+    steps: &'static [StepTemplate],
+}
+
+impl DishTemplate {
+    fn menu_item(
+        &self,
+        brand: &str,
+        catalog: &HashSet<String>,
+        rng: &mut impl Rng,
+    ) -> Result<MenuItem> {
+        let ingredients = self
+            .ingredients
+            .iter()
+            .map(|(ingredient, quantity)| {
+                let ingredient_ref = format!("ingredients/{ingredient}");
+                if !catalog.contains(&ingredient_ref) {
+                    return Err(Error::invalid_data(format!(
+                        "dish '{}' uses ingredient '{ingredient}' missing from the catalog",
+                        self.name
+                    )));
+                }
+                Ok(IngredientQuantity {
+                    ingredient_ref,
+                    quantity: quantity.to_string(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        // prices end in .49 or .99 like on real menus
+        let price = (self.price * rng.random_range(0.9..1.15) * 2.0).round() / 2.0 - 0.01;
+        Ok(MenuItem {
+            id: MenuItemId::from_names(brand, self.name).to_string(),
+            name: self.name.to_string(),
+            description: self.description.to_string(),
+            price: (price * 100.0).round() / 100.0,
+            image_url: None,
+            ingredients,
+            instructions: self
+                .steps
+                .iter()
+                .map(|step| step.instruction(rng))
+                .collect(),
+            currency: None,
+        })
+    }
+}
+
+const fn step(
+    step: &'static str,
+    station: KitchenStation,
+    duration: u32,
+    description: &'static str,
+) -> StepTemplate {
+    StepTemplate {
+        step,
+        station,
+        duration,
+        description,
+    }
+}
+
+fn dishes(cuisine: Cuisine) -> &'static [DishTemplate] {
+    match cuisine {
+        Cuisine::Asian => ASIAN_DISHES,
+        Cuisine::FastFood => FAST_FOOD_DISHES,
+        Cuisine::Mexican => MEXICAN_DISHES,
+    }
+}
+
+static ASIAN_DISHES: &[DishTemplate] = &[
+    DishTemplate {
+        name: "Beef and Broccoli Stir Fry",
+        description: "Sliced beef and broccoli in a garlic soy glaze over rice.",
+        price: 15.99,
+        ingredients: &[
+            ("beef", "150g"),
+            ("broccoli", "100g"),
+            ("soy_sauce", "2 tbsp"),
+            ("garlic", "2 cloves"),
+            ("rice", "200g"),
+        ],
+        steps: &[
+            step(
+                "prep",
+                Workstation,
+                300,
+                "Slice beef and cut broccoli into florets.",
+            ),
+            step("cook-rice", Stove, 900, "Simmer rice until tender."),
+            step(
+                "stir-fry",
+                Stove,
+                420,
+                "Stir fry beef, broccoli and garlic with soy sauce.",
+            ),
+            step("plate", Workstation, 90, "Serve the stir fry over rice."),
+        ],
+    },
+    DishTemplate {
+        name: "Vegetable Fried Rice",
+        description: "Wok fried rice with egg, green onion and soy sauce.",
+        price: 12.99,
+        ingredients: &[
+            ("rice", "250g"),
+            ("egg", "2"),
+            ("green_onion", "2 stalks"),
+            ("soy_sauce", "1 tbsp"),
+            ("garlic", "1 clove"),
+        ],
+        steps: &[
+            step(
+                "prep",
+                Workstation,
+                120,
+                "Chop green onion and mince garlic.",
+            ),
+            step("scramble", Stove, 120, "Scramble the eggs in the wok."),
+            step(
+                "fry-rice",
+                Stove,
+                360,
+                "Fry rice with garlic, eggs and soy sauce.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Chicken Teriyaki Bowl",
+        description: "Glazed chicken thighs with green onion on steamed rice.",
+        price: 14.49,
+        ingredients: &[
+            ("chicken", "160g"),
+            ("rice", "200g"),
+            ("soy_sauce", "2 tbsp"),
+            ("garlic", "1 clove"),
+            ("green_onion", "1 stalk"),
+        ],
+        steps: &[
+            step(
+                "marinate",
+                Workstation,
+                180,
+                "Coat chicken in soy sauce and garlic.",
+            ),
+            step("cook-rice", Stove, 900, "Simmer rice until tender."),
+            step("glaze", Stove, 600, "Pan fry and glaze the chicken."),
+            step(
+                "assemble",
+                Workstation,
+                90,
+                "Slice chicken over rice and garnish.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Chicken Lettuce Wraps",
+        description: "Minced chicken with garlic and soy served in lettuce cups.",
+        price: 11.99,
+        ingredients: &[
+            ("chicken", "140g"),
+            ("lettuce", "4 leaves"),
+            ("garlic", "2 cloves"),
+            ("soy_sauce", "1 tbsp"),
+            ("green_onion", "1 stalk"),
+        ],
+        steps: &[
+            step(
+                "prep",
+                Workstation,
+                240,
+                "Mince chicken and wash lettuce cups.",
+            ),
+            step(
+                "saute",
+                Stove,
+                480,
+                "Saute chicken with garlic and soy sauce.",
+            ),
+            step(
+                "fill",
+                Workstation,
+                120,
+                "Fill lettuce cups and top with green onion.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Garlic Broccoli",
+        description: "Roasted broccoli tossed in garlic and soy.",
+        price: 7.99,
+        ingredients: &[
+            ("broccoli", "200g"),
+            ("garlic", "3 cloves"),
+            ("soy_sauce", "1 tbsp"),
+        ],
+        steps: &[
+            step("prep", Workstation, 120, "Cut broccoli and slice garlic."),
+            step(
+                "roast",
+                Oven,
+                900,
+                "Roast broccoli until charred at the edges.",
+            ),
+            step("toss", Workstation, 60, "Toss with garlic and soy sauce."),
+        ],
+    },
+    DishTemplate {
+        name: "Egg Drop Rice Bowl",
+        description: "Silky soy braised egg over rice with green onion.",
+        price: 9.99,
+        ingredients: &[
+            ("egg", "3"),
+            ("rice", "200g"),
+            ("soy_sauce", "1 tbsp"),
+            ("green_onion", "1 stalk"),
+        ],
+        steps: &[
+            step("cook-rice", Stove, 900, "Simmer rice until tender."),
+            step(
+                "braise-egg",
+                Stove,
+                240,
+                "Drop beaten eggs into simmering soy broth.",
+            ),
+            step(
+                "plate",
+                Workstation,
+                60,
+                "Spoon eggs over rice and garnish.",
+            ),
+        ],
+    },
+];
+
+static FAST_FOOD_DISHES: &[DishTemplate] = &[
+    DishTemplate {
+        name: "Classic Burger",
+        description: "Grilled beef patty with lettuce on a toasted bun.",
+        price: 10.99,
+        ingredients: &[("beef", "150g"), ("lettuce", "2 leaves"), ("bun", "1")],
+        steps: &[
+            step(
+                "form-patty",
+                Workstation,
+                120,
+                "Season and form the beef patty.",
+            ),
+            step("grill", Stove, 480, "Grill the patty to medium."),
+            step(
+                "assemble",
+                Workstation,
+                90,
+                "Toast bun and assemble with lettuce.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Cheeseburger",
+        description: "Beef patty with melted cheese, lettuce and a toasted bun.",
+        price: 11.99,
+        ingredients: &[
+            ("beef", "150g"),
+            ("cheese", "2 slices"),
+            ("lettuce", "2 leaves"),
+            ("bun", "1"),
+        ],
+        steps: &[
+            step(
+                "form-patty",
+                Workstation,
+                120,
+                "Season and form the beef patty.",
+            ),
+            step(
+                "grill",
+                Stove,
+                540,
+                "Grill the patty and melt cheese on top.",
+            ),
+            step(
+                "assemble",
+                Workstation,
+                90,
+                "Toast bun and assemble with lettuce.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Crispy Chicken Sandwich",
+        description: "Oven baked crispy chicken with lettuce on a bun.",
+        price: 10.49,
+        ingredients: &[("chicken", "150g"), ("lettuce", "2 leaves"), ("bun", "1")],
+        steps: &[
+            step(
+                "bread-chicken",
+                Workstation,
+                180,
+                "Bread the chicken breast.",
+            ),
+            step("bake", Oven, 1080, "Bake the chicken until crisp."),
+            step(
+                "assemble",
+                Workstation,
+                90,
+                "Assemble the sandwich with lettuce.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Egg Salad Sandwich",
+        description: "Creamy egg salad with lettuce on toasted bread.",
+        price: 8.99,
+        ingredients: &[("egg", "2"), ("bread", "2 slices"), ("lettuce", "2 leaves")],
+        steps: &[
+            step("boil-eggs", Stove, 600, "Hard boil the eggs."),
+            step("mix", Workstation, 180, "Chop eggs and mix the egg salad."),
+            step(
+                "assemble",
+                Workstation,
+                120,
+                "Toast bread and assemble the sandwich.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Grilled Cheese",
+        description: "Golden toasted bread with melted cheese.",
+        price: 7.49,
+        ingredients: &[("bread", "2 slices"), ("cheese", "3 slices")],
+        steps: &[
+            step(
+                "assemble",
+                Workstation,
+                60,
+                "Layer cheese between the bread slices.",
+            ),
+            step("grill", Stove, 300, "Grill until golden and melted."),
+        ],
+    },
+];
+
+static MEXICAN_DISHES: &[DishTemplate] = &[
+    DishTemplate {
+        name: "Chicken Tacos",
+        description: "Soft tortillas with seasoned chicken, lettuce and salsa.",
+        price: 12.99,
+        ingredients: &[
+            ("chicken", "120g"),
+            ("lettuce", "40g"),
+            ("tortilla", "2"),
+            ("garlic", "1 clove"),
+            ("salsa", "2 tbsp"),
+        ],
+        steps: &[
+            step(
+                "cook-chicken",
+                Stove,
+                600,
+                "Season and cook chicken with garlic, then slice.",
+            ),
+            step("warm-tortillas", Stove, 120, "Warm tortillas on a skillet."),
+            step(
+                "assemble",
+                Workstation,
+                180,
+                "Fill tortillas with chicken, lettuce and salsa.",
+            ),
+        ],
+    },
+    DishTemplate {
+        name: "Beef Burrito",
+        description: "Flour tortilla with beef, rice, beans, cheese and salsa.",
+        price: 13.99,
+        ingredients: &[
+            ("tortilla", "1"),
+            ("beef", "140g"),
+            ("rice", "100g"),
+            ("beans", "80g"),
+            ("cheese", "40g"),
+            ("salsa", "2 tbsp"),
+        ],
+        steps: &[
+            step("cook-rice", Stove, 900, "Simmer rice until tender."),
+            step("brown-beef", Stove, 480, "Brown the seasoned beef."),
+            step("roll", Workstation, 180, "Fill and roll the burrito."),
+        ],
+    },
+    DishTemplate {
+        name: "Vegetarian Quesadilla",
+        description: "Grilled tortilla filled with cheese, beans and green onion.",
+        price: 10.99,
+        ingredients: &[
+            ("tortilla", "1"),
+            ("cheese", "50g"),
+            ("beans", "60g"),
+            ("green_onion", "1 stalk"),
+        ],
+        steps: &[
+            step(
+                "fill",
+                Workstation,
+                120,
+                "Fill the tortilla with cheese, beans and onion.",
+            ),
+            step("grill", Stove, 300, "Grill until the cheese melts."),
+            step("cut", Workstation, 60, "Cut into wedges."),
+        ],
+    },
+    DishTemplate {
+        name: "Loaded Nachos",
+        description: "Baked tortilla chips with cheese, beans and salsa.",
+        price: 9.99,
+        ingredients: &[
+            ("tortilla", "3"),
+            ("cheese", "80g"),
+            ("beans", "80g"),
+            ("salsa", "3 tbsp"),
+        ],
+        steps: &[
+            step("cut-chips", Workstation, 120, "Cut tortillas into chips."),
+            step(
+                "bake",
+                Oven,
+                600,
+                "Bake chips topped with cheese and beans.",
+            ),
+            step("top", Workstation, 60, "Top with salsa."),
+        ],
+    },
+    DishTemplate {
+        name: "Chicken Enchiladas",
+        description: "Tortillas rolled around chicken, baked in salsa and cheese.",
+        price: 14.49,
+        ingredients: &[
+            ("tortilla", "3"),
+            ("chicken", "150g"),
+            ("cheese", "60g"),
+            ("salsa", "4 tbsp"),
+        ],
+        steps: &[
+            step("cook-chicken", Stove, 600, "Poach and shred the chicken."),
+            step(
+                "roll",
+                Workstation,
+                240,
+                "Roll chicken into tortillas and cover with salsa.",
+            ),
+            step("bake", Oven, 1200, "Bake with cheese until bubbling."),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dishes_use_catalog_ingredients() -> Result<()> {
+        let catalog = ingredient_catalog()?;
+        let mut rng = StdRng::seed_from_u64(0);
+        for cuisine in Cuisine::ALL {
+            for dish in dishes(cuisine) {
+                let item = dish.menu_item("brand", &catalog, &mut rng)?;
+                for instruction in &item.instructions {
+                    let (min, max) = station_durations(instruction.required_station());
+                    assert!((min..=max).contains(&instruction.expected_duration));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_brands() -> Result<()> {
+        let generator = MenuGenerator::default()
+            .with_cuisine(Cuisine::Mexican, 2)
+            .with_seed(42);
+        let brands = generator.generate_brands()?;
+        assert_eq!(brands.len(), 4);
+        assert_eq!(brands.iter().filter(|b| b.category == "mexican").count(), 2);
+        assert!(brands.iter().all(|b| b.items.len() == 4));
+        assert_eq!(
+            brands
+                .iter()
+                .map(|b| b.name.as_str())
+                .collect::<HashSet<_>>()
+                .len(),
+            4
+        );
+
+        // brands are generated deterministically from the seed
+        assert_eq!(generator.generate_brands()?, brands);
+        let price = brands[0].items[0].price;
+        assert!((price * 100.0).round() as i64 % 50 == 49);
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_with_menus() -> Result<()> {
+        let setup = crate::Template::default()
+            .with_menus(MenuGenerator::default().with_seed(7))
+            .load()?;
+        assert_eq!(setup.brands.len(), 6);
+        let objects = crate::ObjectData::try_new(setup.object_data()?)?;
+        let generated = &setup.brands[3];
+        let item = &generated.items[0];
+        let menu_item = objects.menu_item(&MenuItemId::from_names(&generated.name, &item.name))?;
+        assert_eq!(menu_item.instructions, item.instructions);
+        Ok(())
+    }
+}
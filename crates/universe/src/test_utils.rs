@@ -42,13 +42,25 @@ pub async fn print_frame(frame: &DataFrame) -> Result<()> {
 #[cfg(test)]
 #[fixture]
 pub async fn simulation_context() -> Result<SimulationContext> {
+    simulation_context_in(None, None).await
+}
+
+/// Context of a simulation of the default template, stored in `working_directory` if set.
+///
+/// With a `seed`, the population is generated deterministically.
+#[cfg(test)]
+pub(crate) async fn simulation_context_in(
+    working_directory: Option<url::Url>,
+    seed: Option<u64>,
+) -> Result<SimulationContext> {
     use crate::{
         EntityView, ObjectData, PopulationData, ROUTING_EDGES_REF, ROUTING_NODES_REF,
         context::storage::register_system,
     };
     use chrono::{Timelike as _, Utc};
     use datafusion::catalog::{MemorySchemaProvider, SchemaProvider};
-    use rand::Rng as _;
+    use rand::rngs::StdRng;
+    use rand::{Rng as _, SeedableRng as _};
 
     let caspers_root = find_git_root()?.join(".caspers/system/");
     let system_path = url::Url::from_directory_path(caspers_root)
@@ -58,9 +70,18 @@ pub async fn simulation_context() -> Result<SimulationContext> {
     let objects = setup.object_data()?;
     let object_data = ObjectData::try_new(objects)?;
 
-    let mut builder = PopulationData::builder();
+    let (mut builder, mut rng) = match seed {
+        Some(seed) => (
+            PopulationData::builder().with_seed(seed),
+            StdRng::seed_from_u64(seed),
+        ),
+        None => (
+            PopulationData::builder(),
+            StdRng::from_rng(&mut rand::rng()),
+        ),
+    };
     for site in object_data.sites()? {
-        let n_people = rand::rng().random_range(500..1500);
+        let n_people = rng.random_range(500..1500);
         let info = site.properties()?;
         builder.add_site(n_people, info.latitude, info.longitude)?;
     }
@@ -70,7 +91,8 @@ pub async fn simulation_context() -> Result<SimulationContext> {
     let start_time = start_time.with_hour(12).unwrap();
 
     let ctx = SimulationContext::builder()
-        .with_use_in_memory(true)
+        .with_use_in_memory(working_directory.is_none())
+        .with_working_directory(working_directory)
         .with_population_data(population_data)
         .with_object_data(object_data)
         .with_simulation_start_time(start_time)
@@ -128,9 +150,42 @@ pub(crate) async fn runner_fixed(
     builder.build().await
 }
 
+/// A new order of a customer at the London site, paid in USD through the app.
+///
+/// Tests override the fields they depend on, e.g.
+/// `OrderCreatedPayload { total: 20.0, ..order_created(order_id) }`.
+#[cfg(test)]
+pub(crate) fn order_created(order_id: crate::idents::OrderId) -> crate::OrderCreatedPayload {
+    use crate::idents::{PersonId, SiteId};
+
+    crate::OrderCreatedPayload {
+        order_id,
+        site_id: SiteId::from_name("london"),
+        person_id: PersonId::new(),
+        items: vec![],
+        cuisines: vec![],
+        destination: geo::Point::new(-0.1278, 51.5074),
+        total: 10.0,
+        currency: crate::Currency::USD,
+        channel: crate::OrderChannel::App,
+        priority: Default::default(),
+        promised_at: chrono::Utc::now(),
+        campaigns: vec![],
+        tip: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use arrow::compute::concat_batches;
+    use arrow::ipc::writer::StreamWriter;
+    use chrono::{DateTime, Utc};
+    use datafusion::prelude::col;
+
+    use std::sync::Arc;
+
     use super::*;
+    use crate::{BehaviorPlugin, EventScheduler, OrderStatus, SettingsUpdate, StopConditions};
 
     #[tokio::test]
     async fn test_simulation() {
@@ -144,4 +199,185 @@ mod tests {
         let event_stats = simulation.event_stats();
         assert!(event_stats.num_orders_created > 0);
     }
+
+    #[tokio::test]
+    async fn test_run_until() {
+        let mut simulation = setup_test_simulation(None).await.unwrap();
+
+        let stop = StopConditions::steps(1_000).with_max_orders(1);
+        simulation.run_until(stop).await.unwrap();
+
+        // the run ends with the step the first order was created in
+        let event_stats = simulation.event_stats();
+        assert!(event_stats.num_orders_created >= 1);
+        assert!(simulation.stats().steps < 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_resume_orders_in_flight() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let location = url::Url::from_directory_path(dir.path()).unwrap();
+        let ctx = simulation_context_in(Some(location.clone()), None).await?;
+        let start_time = *ctx.current_time();
+        let mut simulation = Simulation::builder()
+            .with_context(ctx)
+            .with_start_time(start_time)
+            .build()
+            .await?;
+
+        // the run ends with a snapshot as soon as orders are in flight
+        let stop = StopConditions::steps(1_000).with_predicate(|state| {
+            state
+                .orders()
+                .all_orders()
+                .any(|order| order.status() == OrderStatus::Processing.as_ref())
+        });
+        simulation.run_until(stop).await?;
+        simulation.pause().await?;
+        let in_flight = simulation
+            .state()
+            .orders()
+            .all_orders()
+            .filter(|order| order.status() != OrderStatus::Delivered.as_ref())
+            .map(|order| *order.id())
+            .collect::<Vec<_>>();
+        assert!(!in_flight.is_empty());
+
+        let ctx = SimulationContext::builder()
+            .with_working_directory(location)
+            .with_resume_snapshot(*simulation.ctx().snapshot_id())
+            .await?
+            .build()
+            .await?;
+        let start_time = *ctx.current_time();
+        let mut resumed = Simulation::builder()
+            .with_context(ctx)
+            .with_start_time(start_time)
+            .build()
+            .await?;
+
+        let pending = in_flight.clone();
+        let stop = StopConditions::steps(5_000).with_predicate(move |state| {
+            pending.iter().all(|order_id| {
+                state
+                    .orders()
+                    .order(order_id)
+                    .is_some_and(|order| order.status() == OrderStatus::Delivered.as_ref())
+            })
+        });
+        resumed.run_until(stop).await?;
+        for order_id in &in_flight {
+            let order = resumed.state().orders().order(order_id).unwrap();
+            assert_eq!(order.status(), OrderStatus::Delivered.as_ref());
+        }
+        Ok(())
+    }
+
+    /// Plugin whose couriers can never be asked for a delivery.
+    #[derive(Debug)]
+    struct FailingDispatch;
+
+    impl BehaviorPlugin for FailingDispatch {
+        fn accept_assignment(&self, _: f64, _: f64) -> Result<Option<bool>> {
+            Err(Error::internal("dispatch unavailable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_finishes_with_failed_sites() -> Result<()> {
+        let ctx = simulation_context().await?;
+        let start_time = *ctx.current_time();
+        let mut simulation = Simulation::builder()
+            .with_context(ctx)
+            .with_start_time(start_time)
+            .with_plugin(Arc::new(FailingDispatch))
+            .with_site_failure_threshold(1)
+            .with_event_scheduler(EventScheduler::default())
+            .build()
+            .await?;
+
+        // sites fail as soon as they offer their first delivery
+        let stop = StopConditions::steps(1_000).with_max_orders(1);
+        simulation.run_until(stop).await?;
+        simulation.control().update(SettingsUpdate {
+            demand_multiplier: Some(0.0),
+            ..Default::default()
+        })?;
+        simulation.run(200).await?;
+        assert!(simulation.quarantined_sites().next().is_some());
+
+        // without demand, the orders left at quarantined sites do not keep the
+        // scheduler from skipping ahead
+        let until = simulation.state().current_time() + chrono::Duration::days(2);
+        simulation
+            .run_until(StopConditions::steps(200).with_until(until))
+            .await?;
+        assert!(simulation.state().current_time() >= until);
+        Ok(())
+    }
+
+    /// Plugin whose couriers crash when asked for a delivery.
+    #[derive(Debug)]
+    struct PanickingDispatch;
+
+    impl BehaviorPlugin for PanickingDispatch {
+        fn accept_assignment(&self, _: f64, _: f64) -> Result<Option<bool>> {
+            panic!("dispatch crashed")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_survives_panicking_sites() -> Result<()> {
+        let ctx = simulation_context().await?;
+        let start_time = *ctx.current_time();
+        let mut simulation = Simulation::builder()
+            .with_context(ctx)
+            .with_start_time(start_time)
+            .with_plugin(Arc::new(PanickingDispatch))
+            .with_site_failure_threshold(2)
+            .build()
+            .await?;
+
+        // panicking steps fail like any other, their sites are rolled back once and
+        // then quarantined, while the runners are kept and the run ends as usual
+        simulation.run(500).await?;
+        assert!(simulation.quarantined_sites().next().is_some());
+        simulation.objects_mut()?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seeded_runs_are_reproducible() -> Result<()> {
+        let start = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let ctx = simulation_context_in(None, Some(7)).await?;
+            let mut simulation = Simulation::builder()
+                .with_context(ctx)
+                .with_start_time(start)
+                .with_seed(7)
+                .build()
+                .await?;
+            simulation.run(500).await?;
+
+            let results = simulation.ctx().results();
+            let mut tables = Vec::new();
+            for table in [results.invoices().await?, results.order_feedback().await?] {
+                let batches = table
+                    .sort(vec![col("id").sort(true, false)])?
+                    .collect()
+                    .await?;
+                let batch = concat_batches(batches[0].schema_ref(), &batches)?;
+                let mut writer = StreamWriter::try_new(Vec::new(), batch.schema_ref())?;
+                writer.write(&batch)?;
+                tables.push((batch.num_rows(), writer.into_inner()?));
+            }
+            runs.push(tables);
+        }
+        assert!(runs[0].iter().all(|(rows, _)| *rows > 0));
+        assert_eq!(runs[0], runs[1]);
+        Ok(())
+    }
 }
@@ -0,0 +1,66 @@
+//! Writing events must not allocate per event.
+//!
+//! Lives in its own test binary, since counting allocations requires replacing
+//! the global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use caspers_universe::{
+    EventDataBuilder, EventPayload, ObjectChange, ObjectChangedPayload, ObjectLabel,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|count| count.get())
+}
+
+#[test]
+fn test_add_payload_allocations() -> Result<(), Box<dyn std::error::Error>> {
+    const NUM_EVENTS: usize = 1000;
+
+    let payload = EventPayload::ObjectChanged(ObjectChangedPayload {
+        object_id: Uuid::nil(),
+        label: ObjectLabel::MenuItem,
+        uri_ref: None,
+        change: ObjectChange::Updated,
+    });
+    let timestamp = Utc::now();
+
+    let mut builder = EventDataBuilder::with_capacity(NUM_EVENTS);
+    let before = allocations();
+    for _ in 0..NUM_EVENTS {
+        builder.add_payload(timestamp, &payload)?;
+    }
+    let allocated = allocations() - before;
+
+    // only the reusable serialization buffer and a few builder re-allocations
+    assert!(
+        allocated < 16,
+        "{allocated} allocations for {NUM_EVENTS} events"
+    );
+    assert_eq!(builder.build()?.num_rows(), NUM_EVENTS);
+
+    Ok(())
+}
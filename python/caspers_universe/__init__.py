@@ -1,4 +1,12 @@
+from ._internal import BrandId as BrandId
+from ._internal import KitchenId as KitchenId
+from ._internal import MenuItemId as MenuItemId
+from ._internal import OrderId as OrderId
+from ._internal import OrderLineId as OrderLineId
+from ._internal import PersonId as PersonId
 from ._internal import Site as Site
+from ._internal import SiteId as SiteId
+from ._internal import StationId as StationId
 from ._internal import load_simulation_setup as load_simulation_setup
 from ._internal import run_simulation as run_simulation
 from .plots import plot_site as plot_site
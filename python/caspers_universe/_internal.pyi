@@ -155,6 +155,230 @@ class Brand:
     def items(self) -> list[MenuItem]:
         """The list of menu items for the brand."""
 
+class SiteId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> SiteId:
+        """Create an id from its 16 byte representation."""
+
+    @staticmethod
+    def from_uri_ref(uri_ref: str) -> SiteId:
+        """Create an id from a URI reference (e.g. `sites/<site>`)."""
+
+    @staticmethod
+    def parse(value: str) -> SiteId:
+        """Parse an id from either its UUID or URI reference form."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class KitchenId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> KitchenId:
+        """Create an id from its 16 byte representation."""
+
+    @staticmethod
+    def from_uri_ref(uri_ref: str) -> KitchenId:
+        """Create an id from a URI reference (e.g. `sites/<site>/kitchens/<kitchen>`)."""
+
+    @staticmethod
+    def parse(value: str) -> KitchenId:
+        """Parse an id from either its UUID or URI reference form."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class StationId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> StationId:
+        """Create an id from its 16 byte representation."""
+
+    @staticmethod
+    def from_uri_ref(uri_ref: str) -> StationId:
+        """Create an id from a URI reference (e.g. `sites/<site>/kitchens/<kitchen>/stations/<station>`)."""
+
+    @staticmethod
+    def parse(value: str) -> StationId:
+        """Parse an id from either its UUID or URI reference form."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class BrandId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> BrandId:
+        """Create an id from its 16 byte representation."""
+
+    @staticmethod
+    def from_uri_ref(uri_ref: str) -> BrandId:
+        """Create an id from a URI reference (e.g. `brands/<brand>`)."""
+
+    @staticmethod
+    def parse(value: str) -> BrandId:
+        """Parse an id from either its UUID or URI reference form."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class MenuItemId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> MenuItemId:
+        """Create an id from its 16 byte representation."""
+
+    @staticmethod
+    def from_uri_ref(uri_ref: str) -> MenuItemId:
+        """Create an id from a URI reference (e.g. `brands/<brand>/menu_items/<item>`)."""
+
+    @staticmethod
+    def parse(value: str) -> MenuItemId:
+        """Parse an id from either its UUID or URI reference form."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class OrderId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> OrderId:
+        """Create an id from its 16 byte representation."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class OrderLineId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> OrderLineId:
+        """Create an id from its 16 byte representation."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class PersonId:
+    def __init__(self, value: str) -> None:
+        """Create an id from its UUID string form."""
+
+    @staticmethod
+    def from_bytes(value: bytes) -> PersonId:
+        """Create an id from its 16 byte representation."""
+
+    @property
+    def bytes(self) -> bytes:
+        """The 16 byte representation as stored in Arrow data."""
+
+    @property
+    def hex(self) -> str:
+        """The id as a 32 character hex string."""
+
+    @property
+    def urn(self) -> str:
+        """The id as a `urn:uuid:` URN."""
+
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
 class SimulationSetup:
     @property
     def sites(self) -> list[SiteSetup]:
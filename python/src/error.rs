@@ -43,7 +43,8 @@ fn inner_to_py_err(err: InnerError) -> PyErr {
         InnerError::ObjectStore { source } => object_store_to_py(source),
         InnerError::Arrow { source } => arrow_to_py(&source),
         InnerError::Datafusion { source } => datafusion_to_py(source),
-        _ => CaspersError::new_err(err.to_string()),
+        InnerError::TableNotFound(_) => TableNotFoundError::new_err(err.to_string()),
+        _ => CaspersError::new_err(err.report()),
     }
 }
 
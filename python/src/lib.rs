@@ -1,8 +1,8 @@
 use std::{collections::HashMap, sync::OnceLock};
 
 use caspers_universe::{
-    SimulationSetup, Site, load_simulation_setup as load_simulation,
-    run_simulation as run_simulation_inner,
+    BrandId, KitchenId, MenuItemId, OrderId, OrderLineId, PersonId, SimulationSetup, Site, SiteId,
+    StationId, load_simulation_setup as load_simulation, run_simulation as run_simulation_inner,
 };
 use pyo3::{exceptions::PyValueError, prelude::*};
 use tokio::runtime::Runtime;
@@ -69,6 +69,15 @@ fn resolve_url(url: &str) -> PyResult<Url> {
 fn _internal(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Site>()?;
 
+    m.add_class::<SiteId>()?;
+    m.add_class::<KitchenId>()?;
+    m.add_class::<StationId>()?;
+    m.add_class::<BrandId>()?;
+    m.add_class::<MenuItemId>()?;
+    m.add_class::<OrderId>()?;
+    m.add_class::<OrderLineId>()?;
+    m.add_class::<PersonId>()?;
+
     m.add_function(wrap_pyfunction!(load_simulation_setup, m)?)?;
     m.add_function(wrap_pyfunction!(run_simulation, m)?)?;
 